@@ -1,32 +1,46 @@
 use std::path::PathBuf;
 
-const LETTERS: [char; 9] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I'];
+const LETTERS: [char; 12] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L'];
 
 // Enums will have 2^N fields, so we default to a small number and expose feature flags to generate
-// larger `DeltaN` types.
+// larger `DeltaN` types and tuple implementations.
 //
-// TODO: Increase to 4 whenever we're working on supporting 3-tuples and 4-tuples
-//
-// TODO: Expose those feature flags. Such as `delta-5` `delta-6` etc. Experiment to see how large
-//  we can go without impacting compile times too much.
-const MAX_DELTA_N: u8 = 2;
+// `delta-5` through `delta-12` each enable the feature below them, so the largest enabled feature
+// decides how many `DeltaN` types get generated.
+const DEFAULT_MAX_DELTA_N: u8 = 4;
+const LARGEST_DELTA_N_FEATURE: u8 = 12;
 
 fn main() {
-    let delta_n_types = generate_delta_n_types();
+    let max_delta_n = max_delta_n();
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
 
-    let out_dir = std::env::var("OUT_DIR").unwrap();
     std::fs::write(
-        PathBuf::from(out_dir).join("delta_n_types.rs"),
-        delta_n_types,
+        out_dir.join("delta_n_types.rs"),
+        generate_delta_n_types(max_delta_n),
+    )
+    .unwrap();
+    std::fs::write(
+        out_dir.join("tuple_impls.rs"),
+        generate_tuple_impls(max_delta_n),
     )
     .unwrap();
 }
 
+/// The largest `N` to generate `DeltaN` types and N-tuple implementations for, based on which
+/// `delta-N` features are enabled.
+fn max_delta_n() -> u8 {
+    (DEFAULT_MAX_DELTA_N + 1..=LARGEST_DELTA_N_FEATURE)
+        .rev()
+        .find(|n| std::env::var_os(format!("CARGO_FEATURE_DELTA_{}", n)).is_some())
+        .unwrap_or(DEFAULT_MAX_DELTA_N)
+}
+
 /// Generate `DeltaN` types.
 ///
+/// Their `serde` and `Debug`/`PartialEq` implementations are generated instead of derived, see
+/// [`generate_delta_n_serialize`] and [`generate_delta_owned_n_deserialize`].
+///
 /// ```no_run
-/// #[derive(serde::Serialize)]
-/// #[cfg_attr(feature = "impl-tester", derive(Debug, PartialEq))]
 /// #[allow(non_camel_case_types, missing_docs)]
 /// pub enum Delta2<A, B> {
 ///     NoChange,
@@ -35,19 +49,18 @@ fn main() {
 ///     Change_0_1(A, B),
 /// }
 ///
-/// #[derive(serde::Deserialize)]
 /// #[allow(non_camel_case_types, missing_docs)]
-/// pub enum DiffOwned2<A, B> {
+/// pub enum DeltaOwned2<A, B> {
 ///     NoChange,
 ///     Change_0(A),
 ///     Change_1(B),
 ///     Change_0_1(A, B),
 /// }
 /// ```
-fn generate_delta_n_types() -> String {
+fn generate_delta_n_types(max_delta_n: u8) -> String {
     let mut all_types = "".to_string();
 
-    for field_count in 2..=MAX_DELTA_N {
+    for field_count in 1..=max_delta_n {
         let bool_combinations = make_bool_combinations(field_count as _);
 
         let mut change_combinations = "".to_string();
@@ -83,8 +96,6 @@ fn generate_delta_n_types() -> String {
 
         let diff_n = format!(
             r#"
-#[derive(serde::Serialize)]
-#[cfg_attr(any(test, feature = "impl-tester"), derive(Debug, PartialEq))]
 #[allow(non_camel_case_types, missing_docs)]
 pub enum Delta{field_count}<{diff_n_generics}> {{
     NoChange,
@@ -97,7 +108,6 @@ pub enum Delta{field_count}<{diff_n_generics}> {{
 
         let diff_n_owned = format!(
             r#"
-#[derive(serde::Deserialize)]
 #[allow(non_camel_case_types, missing_docs)]
 pub enum DeltaOwned{field_count}<{diff_n_generics}> {{
     NoChange,
@@ -110,11 +120,1207 @@ pub enum DeltaOwned{field_count}<{diff_n_generics}> {{
 
         all_types += &diff_n;
         all_types += &diff_n_owned;
+        all_types += &generate_variant_tables(field_count);
+        all_types += &generate_delta_n_field_conversions(field_count);
+        all_types += &generate_delta_owned_n_field_conversions(field_count);
+        all_types += &generate_delta_n_serialize(field_count);
+        all_types += &generate_delta_owned_n_deserialize(field_count);
+        all_types += &generate_delta_n_to_owned_delta(field_count);
     }
 
     all_types
 }
 
+/// Generate methods for converting a `DeltaN` to and from the deltas for each of its fields.
+///
+/// ```no_run
+/// impl<A, B> Delta2<A, B> {
+///     pub fn fields(&self) -> (Option<&A>, Option<&B>) {
+///         match self {
+///             Delta2::NoChange => (None, None),
+///             Delta2::Change_0(a) => (Some(a), None),
+///             Delta2::Change_1(b) => (None, Some(b)),
+///             Delta2::Change_0_1(a, b) => (Some(a), Some(b)),
+///         }
+///     }
+///
+///     pub fn from_fields(fields: (Option<A>, Option<B>)) -> Self {
+///         let (a, b) = fields;
+///
+///         match a {
+///             None => match b {
+///                 None => Delta2::NoChange,
+///                 Some(b) => Delta2::Change_1(b),
+///             },
+///             Some(a) => match b {
+///                 None => Delta2::Change_0(a),
+///                 Some(b) => Delta2::Change_0_1(a, b),
+///             },
+///         }
+///     }
+/// }
+/// ```
+fn generate_delta_n_field_conversions(field_count: u8) -> String {
+    generate_field_conversions(
+        &format!("Delta{}", field_count),
+        field_count,
+        "/// The delta for each field, or `None` for fields that did not change.",
+        "fields(&self)",
+        "&",
+    )
+}
+
+/// Generate methods for converting a `DeltaOwnedN` to and from the patches for each of its fields.
+///
+/// Same as [`generate_delta_n_field_conversions`], except that the fields are moved out of the
+/// delta.
+///
+/// ```no_run
+/// impl<A, B> DeltaOwned2<A, B> {
+///     pub fn into_fields(self) -> (Option<A>, Option<B>) {
+///         match self {
+///             DeltaOwned2::NoChange => (None, None),
+///             DeltaOwned2::Change_0(a) => (Some(a), None),
+///             DeltaOwned2::Change_1(b) => (None, Some(b)),
+///             DeltaOwned2::Change_0_1(a, b) => (Some(a), Some(b)),
+///         }
+///     }
+///
+///     pub fn from_fields(fields: (Option<A>, Option<B>)) -> Self {
+///         // ...
+///     }
+/// }
+/// ```
+fn generate_delta_owned_n_field_conversions(field_count: u8) -> String {
+    generate_field_conversions(
+        &format!("DeltaOwned{}", field_count),
+        field_count,
+        "/// The patch for each field, or `None` for fields that did not change.",
+        "into_fields(self)",
+        "",
+    )
+}
+
+fn generate_field_conversions(
+    type_name: &str,
+    field_count: u8,
+    fields_doc: &str,
+    fields_signature: &str,
+    field_reference: &str,
+) -> String {
+    let letters = &LETTERS[0..field_count as usize];
+
+    let generics: String = letters.iter().map(|l| format!("{}, ", l)).collect();
+    let option_generics: String = letters.iter().map(|l| format!("Option<{}>, ", l)).collect();
+    let option_field_generics: String = letters
+        .iter()
+        .map(|l| format!("Option<{}{}>, ", field_reference, l))
+        .collect();
+    let bindings: String = letters
+        .iter()
+        .map(|l| format!("{}, ", l.to_ascii_lowercase()))
+        .collect();
+
+    let mut fields_arms = "".to_string();
+
+    for bools in make_bool_combinations(field_count as _) {
+        let options: String = bools
+            .iter()
+            .enumerate()
+            .map(|(idx, changed)| match changed {
+                true => format!("Some({}), ", letters[idx].to_ascii_lowercase()),
+                false => "None, ".to_string(),
+            })
+            .collect();
+
+        fields_arms += &format!(
+            r#"{type_name}::{variant} => ({options}),
+            "#,
+            type_name = type_name,
+            variant = variant_pattern(letters, &bools),
+            options = options
+        );
+    }
+
+    format!(
+        r#"
+impl<{generics}> {type_name}<{generics}> {{
+    {fields_doc}
+    #[allow(clippy::type_complexity)]
+    pub fn {fields_signature} -> ({option_field_generics}) {{
+        match self {{
+            {fields_arms}
+        }}
+    }}
+
+    /// Create a delta from the change to each field.
+    #[allow(clippy::type_complexity)]
+    pub fn from_fields(fields: ({option_generics})) -> Self {{
+        let ({bindings}) = fields;
+
+        {from_fields_body}
+    }}
+}}"#,
+        generics = generics,
+        type_name = type_name,
+        fields_doc = fields_doc,
+        fields_signature = fields_signature,
+        option_field_generics = option_field_generics,
+        option_generics = option_generics,
+        bindings = bindings,
+        fields_arms = fields_arms,
+        from_fields_body = generate_from_fields_match(type_name, letters, &mut vec![]),
+    )
+}
+
+/// Match on one field at a time, instead of on a tuple of every field, since the compiler takes a
+/// very long time to check a match with `2^N` tuple patterns for larger values of `N`.
+fn generate_from_fields_match(
+    type_name: &str,
+    letters: &[char],
+    changed: &mut Vec<bool>,
+) -> String {
+    if changed.len() == letters.len() {
+        return format!("{}::{}", type_name, variant_pattern(letters, changed));
+    }
+
+    let binding = letters[changed.len()].to_ascii_lowercase();
+
+    changed.push(false);
+    let unchanged = generate_from_fields_match(type_name, letters, changed);
+    changed.pop();
+
+    changed.push(true);
+    let changed_arm = generate_from_fields_match(type_name, letters, changed);
+    changed.pop();
+
+    format!(
+        r#"match {binding} {{
+            None => {unchanged},
+            Some({binding}) => {changed_arm},
+        }}"#,
+        binding = binding,
+        unchanged = unchanged,
+        changed_arm = changed_arm
+    )
+}
+
+/// `NoChange` or a variant such as `Change_0_2(a, c)`.
+fn variant_pattern(letters: &[char], changed: &[bool]) -> String {
+    let mut variant = "Change".to_string();
+    let mut bindings = vec![];
+
+    for (idx, changed) in changed.iter().enumerate() {
+        if *changed {
+            variant += &format!("_{}", idx);
+            bindings.push(letters[idx].to_ascii_lowercase().to_string());
+        }
+    }
+
+    if bindings.is_empty() {
+        "NoChange".to_string()
+    } else {
+        format!("{}({})", variant, bindings.join(", "))
+    }
+}
+
+/// Generate lookup tables between the index of each `DeltaN` variant, its name and the fields
+/// that it changes, where each changed field is a bit in a `u16`.
+///
+/// ```no_run
+/// static DELTA_2_VARIANTS: [&str; 4] = ["NoChange", "Change_0", "Change_1", "Change_0_1"];
+/// static DELTA_2_CHANGED_FIELDS: [u16; 4] = [0b0, 0b1, 0b10, 0b11];
+/// static DELTA_2_VARIANT_INDICES: [u32; 4] = [0, 1, 2, 3];
+/// ```
+fn generate_variant_tables(field_count: u8) -> String {
+    let letters = &LETTERS[0..field_count as usize];
+    let combinations = make_bool_combinations(field_count as _);
+
+    let masks: Vec<u16> = combinations
+        .iter()
+        .map(|bools| {
+            bools
+                .iter()
+                .enumerate()
+                .filter(|(_, changed)| **changed)
+                .map(|(idx, _)| 1 << idx)
+                .sum()
+        })
+        .collect();
+
+    let variant_names: String = combinations
+        .iter()
+        .map(|bools| {
+            let variant = variant_pattern(letters, bools);
+            format!(r#""{}", "#, variant.split('(').next().unwrap())
+        })
+        .collect();
+    let changed_fields: String = masks.iter().map(|mask| format!("{:#b}, ", mask)).collect();
+
+    let mut variant_indices = vec![0; masks.len()];
+    for (idx, mask) in masks.iter().enumerate() {
+        variant_indices[*mask as usize] = idx;
+    }
+    let variant_indices: String = variant_indices
+        .iter()
+        .map(|idx| format!("{}, ", idx))
+        .collect();
+
+    format!(
+        r#"
+static DELTA_{field_count}_VARIANTS: [&str; {variant_count}] = [{variant_names}];
+static DELTA_{field_count}_CHANGED_FIELDS: [u16; {variant_count}] = [{changed_fields}];
+static DELTA_{field_count}_VARIANT_INDICES: [u32; {variant_count}] = [{variant_indices}];
+"#,
+        field_count = field_count,
+        variant_count = masks.len(),
+        variant_names = variant_names,
+        changed_fields = changed_fields,
+        variant_indices = variant_indices,
+    )
+}
+
+/// Generate the `serde::Serialize` implementation for a `DeltaN`, along with the `Debug` and
+/// `PartialEq` implementations that are used when testing.
+///
+/// These write the same thing that their derived implementations would, but the derived
+/// implementations handle each of the `2^N` variants separately, which makes larger `DeltaN` types
+/// take minutes to compile.
+///
+/// ```no_run
+/// impl<A: serde::Serialize, B: serde::Serialize> serde::Serialize for Delta2<A, B> {
+///     fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+///         use serde::ser::SerializeTupleVariant;
+///
+///         let (a, b) = self.fields();
+///         let (variant_index, variant) = self.variant();
+///         let changed = DELTA_2_CHANGED_FIELDS[variant_index as usize];
+///
+///         match changed.count_ones() {
+///             0 => serializer.serialize_unit_variant("Delta2", variant_index, variant),
+///             1 => {
+///                 if let Some(a) = a {
+///                     return serializer.serialize_newtype_variant("Delta2", variant_index, variant, a);
+///                 }
+///                 // ...
+///             }
+///             len => {
+///                 let mut state = serializer.serialize_tuple_variant(
+///                     "Delta2",
+///                     variant_index,
+///                     variant,
+///                     len as usize,
+///                 )?;
+///                 if let Some(a) = a {
+///                     state.serialize_field(a)?;
+///                 }
+///                 // ...
+///                 state.end()
+///             }
+///         }
+///     }
+/// }
+/// ```
+fn generate_delta_n_serialize(field_count: u8) -> String {
+    let letters = &LETTERS[0..field_count as usize];
+
+    let generics: String = letters.iter().map(|l| format!("{}, ", l)).collect();
+    let bounded_generics = |bound: &str| -> String {
+        letters
+            .iter()
+            .map(|l| format!("{}: {}, ", l, bound))
+            .collect()
+    };
+    let bindings: String = letters
+        .iter()
+        .map(|l| format!("{}, ", l.to_ascii_lowercase()))
+        .collect();
+    let changed_fields = letters
+        .iter()
+        .enumerate()
+        .map(|(idx, l)| match idx {
+            0 => format!("{}.is_some() as u16", l.to_ascii_lowercase()),
+            _ => format!("({}.is_some() as u16) << {}", l.to_ascii_lowercase(), idx),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ");
+
+    let for_each_changed = |code: &str| -> String {
+        letters
+            .iter()
+            .map(|l| {
+                format!(
+                    r#"if let Some({binding}) = {binding} {{
+                    {code}
+                }}
+                "#,
+                    binding = l.to_ascii_lowercase(),
+                    code = code.replace("FIELD", &l.to_ascii_lowercase().to_string())
+                )
+            })
+            .collect()
+    };
+
+    format!(
+        r#"
+impl<{generics}> Delta{field_count}<{generics}> {{
+    /// The index and name of this delta's variant.
+    fn variant(&self) -> (u32, &'static str) {{
+        let ({bindings}) = self.fields();
+        let changed = {changed_fields};
+
+        let variant_index = DELTA_{field_count}_VARIANT_INDICES[changed as usize];
+        (variant_index, DELTA_{field_count}_VARIANTS[variant_index as usize])
+    }}
+}}
+
+impl<{serialize_generics}> serde::Serialize for Delta{field_count}<{generics}> {{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {{
+        use serde::ser::SerializeTupleVariant;
+
+        let ({bindings}) = self.fields();
+        let (variant_index, variant) = self.variant();
+        let changed = DELTA_{field_count}_CHANGED_FIELDS[variant_index as usize];
+
+        match changed.count_ones() {{
+            0 => serializer.serialize_unit_variant("Delta{field_count}", variant_index, variant),
+            1 => {{
+                {serialize_newtype}
+                unreachable!("One field changed")
+            }}
+            len => {{
+                let mut state = serializer.serialize_tuple_variant(
+                    "Delta{field_count}",
+                    variant_index,
+                    variant,
+                    len as usize,
+                )?;
+                {serialize_fields}
+                state.end()
+            }}
+        }}
+    }}
+}}
+
+#[cfg(any(test, feature = "impl-tester"))]
+impl<{debug_generics}> std::fmt::Debug for Delta{field_count}<{generics}> {{
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {{
+        let ({bindings}) = self.fields();
+        let mut tuple = formatter.debug_tuple(self.variant().1);
+
+        {debug_fields}
+        tuple.finish()
+    }}
+}}
+
+#[cfg(any(test, feature = "impl-tester"))]
+impl<{partial_eq_generics}> PartialEq for Delta{field_count}<{generics}> {{
+    fn eq(&self, other: &Self) -> bool {{
+        self.fields() == other.fields()
+    }}
+}}"#,
+        field_count = field_count,
+        generics = generics,
+        serialize_generics = bounded_generics("serde::Serialize"),
+        debug_generics = bounded_generics("std::fmt::Debug"),
+        partial_eq_generics = bounded_generics("PartialEq"),
+        bindings = bindings,
+        changed_fields = changed_fields,
+        serialize_newtype = for_each_changed(&format!(
+            r#"return serializer.serialize_newtype_variant(
+                        "Delta{}",
+                        variant_index,
+                        variant,
+                        FIELD,
+                    );"#,
+            field_count
+        )),
+        serialize_fields = for_each_changed("state.serialize_field(FIELD)?;"),
+        debug_fields = for_each_changed("tuple.field(FIELD);"),
+    )
+}
+
+/// Generate the `serde::Deserialize` implementation for a `DeltaOwnedN`.
+///
+/// This reads the same format that `#[derive(serde::Deserialize)]` would, but the derived
+/// implementation has a separate visitor for every one of the `2^N` variants, which makes larger
+/// `DeltaOwnedN` types take minutes to compile. Instead we look up which fields changed using the
+/// variant's index and then read those fields.
+///
+/// ```no_run
+/// impl<'de, A: serde::Deserialize<'de>, B: serde::Deserialize<'de>> serde::Deserialize<'de>
+///     for DeltaOwned2<A, B>
+/// {
+///     fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+///         struct EnumVisitor<A, B>(std::marker::PhantomData<(A, B)>);
+///         struct ChangedFieldsVisitor<A, B>(u16, std::marker::PhantomData<(A, B)>);
+///
+///         impl<'de, A: serde::Deserialize<'de>, B: serde::Deserialize<'de>>
+///             serde::de::Visitor<'de> for EnumVisitor<A, B>
+///         {
+///             // ...
+///
+///             fn visit_enum<Data: serde::de::EnumAccess<'de>>(
+///                 self,
+///                 data: Data,
+///             ) -> Result<Self::Value, Data::Error> {
+///                 use serde::de::VariantAccess;
+///
+///                 let (variant, access) =
+///                     data.variant_seed(crate::delta_n::VariantIndexSeed(&DELTA_2_VARIANTS))?;
+///                 let changed = DELTA_2_CHANGED_FIELDS[variant];
+///
+///                 match changed.count_ones() {
+///                     0 => access.unit_variant().map(|_| DeltaOwned2::NoChange),
+///                     1 => match changed.trailing_zeros() {
+///                         0 => Ok(DeltaOwned2::from_fields((Some(access.newtype_variant()?), None))),
+///                         _ => Ok(DeltaOwned2::from_fields((None, Some(access.newtype_variant()?)))),
+///                     },
+///                     len => access.tuple_variant(
+///                         len as usize,
+///                         ChangedFieldsVisitor(changed, std::marker::PhantomData),
+///                     ),
+///                 }
+///             }
+///         }
+///
+///         impl<'de, A: serde::Deserialize<'de>, B: serde::Deserialize<'de>>
+///             serde::de::Visitor<'de> for ChangedFieldsVisitor<A, B>
+///         {
+///             // ...
+///
+///             fn visit_seq<Seq: serde::de::SeqAccess<'de>>(
+///                 self,
+///                 mut seq: Seq,
+///             ) -> Result<Self::Value, Seq::Error> {
+///                 let mut read = 0;
+///
+///                 let a = crate::delta_n::next_changed_field(&mut seq, self.0, 0, &mut read)?;
+///                 let b = crate::delta_n::next_changed_field(&mut seq, self.0, 1, &mut read)?;
+///
+///                 Ok(DeltaOwned2::from_fields((a, b)))
+///             }
+///         }
+///
+///         deserializer.deserialize_enum(
+///             "DeltaOwned2",
+///             &DELTA_2_VARIANTS,
+///             EnumVisitor(std::marker::PhantomData),
+///         )
+///     }
+/// }
+/// ```
+fn generate_delta_owned_n_deserialize(field_count: u8) -> String {
+    let letters = &LETTERS[0..field_count as usize];
+
+    let generics: String = letters.iter().map(|l| format!("{}, ", l)).collect();
+    let deserialize_generics: String = letters
+        .iter()
+        .map(|l| format!("{}: serde::Deserialize<'de>, ", l))
+        .collect();
+
+    let newtype_arms: String = (0..field_count as usize)
+        .map(|changed_idx| {
+            let fields: String = (0..field_count as usize)
+                .map(|idx| match idx == changed_idx {
+                    true => "Some(access.newtype_variant()?), ",
+                    false => "None, ",
+                })
+                .collect();
+
+            format!(
+                r#"{changed_idx} => Ok(DeltaOwned{field_count}::from_fields(({fields}))),
+                        "#,
+                changed_idx = changed_idx,
+                field_count = field_count,
+                fields = fields
+            )
+        })
+        .collect();
+
+    let read_fields: String = letters
+        .iter()
+        .enumerate()
+        .map(|(idx, l)| {
+            format!(
+                r#"let {binding} = crate::delta_n::next_changed_field(&mut seq, self.0, {idx}, &mut read)?;
+                "#,
+                binding = l.to_ascii_lowercase(),
+                idx = idx
+            )
+        })
+        .collect();
+    let bindings: String = letters
+        .iter()
+        .map(|l| format!("{}, ", l.to_ascii_lowercase()))
+        .collect();
+
+    format!(
+        r#"
+impl<'de, {deserialize_generics}> serde::Deserialize<'de> for DeltaOwned{field_count}<{generics}> {{
+    #[allow(clippy::type_complexity)]
+    fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {{
+        struct EnumVisitor<{generics}>(std::marker::PhantomData<({generics})>);
+        struct ChangedFieldsVisitor<{generics}>(u16, std::marker::PhantomData<({generics})>);
+
+        impl<'de, {deserialize_generics}> serde::de::Visitor<'de> for EnumVisitor<{generics}> {{
+            type Value = DeltaOwned{field_count}<{generics}>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {{
+                formatter.write_str("enum DeltaOwned{field_count}")
+            }}
+
+            fn visit_enum<Data: serde::de::EnumAccess<'de>>(
+                self,
+                data: Data,
+            ) -> Result<Self::Value, Data::Error> {{
+                use serde::de::VariantAccess;
+
+                let (variant, access) = data.variant_seed(crate::delta_n::VariantIndexSeed(
+                    &DELTA_{field_count}_VARIANTS,
+                ))?;
+                let changed = DELTA_{field_count}_CHANGED_FIELDS[variant];
+
+                match changed.count_ones() {{
+                    0 => access.unit_variant().map(|_| DeltaOwned{field_count}::NoChange),
+                    1 => match changed.trailing_zeros() {{
+                        {newtype_arms}
+                        _ => unreachable!("Only {field_count} fields can change"),
+                    }},
+                    len => access.tuple_variant(
+                        len as usize,
+                        ChangedFieldsVisitor(changed, std::marker::PhantomData),
+                    ),
+                }}
+            }}
+        }}
+
+        impl<'de, {deserialize_generics}> serde::de::Visitor<'de>
+            for ChangedFieldsVisitor<{generics}>
+        {{
+            type Value = DeltaOwned{field_count}<{generics}>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {{
+                formatter.write_str("the changed fields of a DeltaOwned{field_count} variant")
+            }}
+
+            fn visit_seq<Seq: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: Seq,
+            ) -> Result<Self::Value, Seq::Error> {{
+                let mut read = 0;
+
+                {read_fields}
+                Ok(DeltaOwned{field_count}::from_fields(({bindings})))
+            }}
+        }}
+
+        deserializer.deserialize_enum(
+            "DeltaOwned{field_count}",
+            &DELTA_{field_count}_VARIANTS,
+            EnumVisitor(std::marker::PhantomData),
+        )
+    }}
+}}"#,
+        field_count = field_count,
+        generics = generics,
+        deserialize_generics = deserialize_generics,
+        newtype_arms = newtype_arms,
+        read_fields = read_fields,
+        bindings = bindings,
+    )
+}
+
+/// Generate the `ToOwnedDelta` implementation that converts a `DeltaN` into a `DeltaOwnedN`.
+///
+/// ```no_run
+/// impl<A, B, OwnedA, OwnedB> crate::ToOwnedDelta<DeltaOwned2<OwnedA, OwnedB>> for Delta2<A, B>
+/// where
+///     A: crate::ToOwnedDelta<OwnedA>,
+///     B: crate::ToOwnedDelta<OwnedB>,
+/// {
+///     fn to_owned_delta(self) -> DeltaOwned2<OwnedA, OwnedB> {
+///         match self {
+///             Delta2::NoChange => DeltaOwned2::NoChange,
+///             Delta2::Change_0(a) => DeltaOwned2::Change_0(a.to_owned_delta()),
+///             Delta2::Change_1(b) => DeltaOwned2::Change_1(b.to_owned_delta()),
+///             Delta2::Change_0_1(a, b) => {
+///                 DeltaOwned2::Change_0_1(a.to_owned_delta(), b.to_owned_delta())
+///             }
+///         }
+///     }
+/// }
+/// ```
+fn generate_delta_n_to_owned_delta(field_count: u8) -> String {
+    let letters = &LETTERS[0..field_count as usize];
+
+    let generics: String = letters.iter().map(|l| format!("{}, ", l)).collect();
+    let owned_generics: String = letters.iter().map(|l| format!("Owned{}, ", l)).collect();
+    let bounds: String = letters
+        .iter()
+        .map(|l| format!("{l}: crate::ToOwnedDelta<Owned{l}>, ", l = l))
+        .collect();
+
+    let mut arms = "".to_string();
+
+    for bools in make_bool_combinations(field_count as _) {
+        let mut variant = "Change".to_string();
+        let mut bindings = vec![];
+        let mut conversions = vec![];
+
+        for (idx, changed) in bools.iter().enumerate() {
+            if *changed {
+                let binding = letters[idx].to_ascii_lowercase();
+
+                variant += &format!("_{}", idx);
+                bindings.push(binding.to_string());
+                conversions.push(format!("{}.to_owned_delta()", binding));
+            }
+        }
+
+        let (pattern, converted) = if bindings.is_empty() {
+            ("NoChange".to_string(), "NoChange".to_string())
+        } else {
+            (
+                format!("{}({})", variant, bindings.join(", ")),
+                format!("{}({})", variant, conversions.join(", ")),
+            )
+        };
+
+        arms += &format!(
+            r#"Delta{field_count}::{pattern} => DeltaOwned{field_count}::{converted},
+            "#,
+            field_count = field_count,
+            pattern = pattern,
+            converted = converted
+        );
+    }
+
+    format!(
+        r#"
+impl<{generics}{owned_generics}> crate::ToOwnedDelta<DeltaOwned{field_count}<{owned_generics}>>
+    for Delta{field_count}<{generics}>
+where
+    {bounds}
+{{
+    fn to_owned_delta(self) -> DeltaOwned{field_count}<{owned_generics}> {{
+        match self {{
+            {arms}
+        }}
+    }}
+}}"#,
+        generics = generics,
+        owned_generics = owned_generics,
+        bounds = bounds,
+        field_count = field_count,
+        arms = arms,
+    )
+}
+
+/// Generate the dipa implementations for every tuple from 1-tuples up to `max_delta_n`-tuples.
+///
+/// Each field is diffed and patched on its own, and the field deltas are combined into a `DeltaN`.
+///
+/// ```no_run
+/// impl<'s, 'e, A: Diffable<'s, 'e, A>, B: Diffable<'s, 'e, B>> Diffable<'s, 'e, (A, B)> for (A, B) {
+///     type Delta = Delta2<<A as Diffable<'s, 'e, A>>::Delta, <B as Diffable<'s, 'e, B>>::Delta>;
+///     type DeltaOwned =
+///         DeltaOwned2<<A as Diffable<'s, 'e, A>>::DeltaOwned, <B as Diffable<'s, 'e, B>>::DeltaOwned>;
+///
+///     fn create_delta_towards(&'s self, end_state: &'e (A, B)) -> CreatedDelta<Self::Delta> {
+///         self.create_delta_towards_version(end_state, u32::MAX)
+///     }
+///
+///     fn create_delta_towards_version(
+///         &'s self,
+///         end_state: &'e (A, B),
+///         version: u32,
+///     ) -> CreatedDelta<Self::Delta> {
+///         let diff0 = self.0.create_delta_towards_version(&end_state.0, version);
+///         let diff1 = self.1.create_delta_towards_version(&end_state.1, version);
+///
+///         let did_change = diff0.did_change || diff1.did_change;
+///         let delta = Delta2::from_fields((
+///             if diff0.did_change { Some(diff0.delta) } else { None },
+///             if diff1.did_change { Some(diff1.delta) } else { None },
+///         ));
+///
+///         CreatedDelta { delta, did_change }
+///     }
+/// }
+///
+/// // ... Along with Patchable, ReversiblePatchable, SquashablePatches, MergeablePatches,
+/// // SchemaFingerprint, VisitDelta, ToJsonPatch and WithinEpsilon implementations.
+/// ```
+fn generate_tuple_impls(max_delta_n: u8) -> String {
+    let mut all_impls = "".to_string();
+
+    for field_count in 1..=max_delta_n {
+        let tuple = TupleTokens::new(field_count);
+
+        all_impls += &generate_tuple_diffable(&tuple);
+        all_impls += &generate_tuple_patchable(&tuple);
+        all_impls += &generate_tuple_borrowed_delta(&tuple);
+        all_impls += &generate_tuple_reversible_patchable(&tuple);
+        all_impls += &generate_tuple_squashable_patches(&tuple);
+        all_impls += &generate_tuple_mergeable_patches(&tuple);
+        all_impls += &generate_tuple_schema_fingerprint(&tuple);
+        all_impls += &generate_tuple_visit_delta(&tuple);
+        all_impls += &generate_tuple_to_json_patch(&tuple);
+        all_impls += &generate_tuple_within_epsilon(&tuple);
+    }
+
+    all_impls
+}
+
+/// Pieces of code that every N-tuple implementation uses.
+struct TupleTokens {
+    field_count: u8,
+    letters: &'static [char],
+    /// `(A, B, )`
+    tuple: String,
+    /// `crate::delta_n::Delta2<<A as Diffable<'s, 'e, A>>::Delta, ...>`
+    delta: String,
+    /// `crate::delta_n::DeltaOwned2<<A as Diffable<'s, 'e, A>>::DeltaOwned, ...>`
+    delta_owned: String,
+}
+
+impl TupleTokens {
+    fn new(field_count: u8) -> Self {
+        let letters = &LETTERS[0..field_count as usize];
+
+        let tuple: String = letters.iter().map(|l| format!("{}, ", l)).collect();
+        let deltas: String = letters
+            .iter()
+            .map(|l| format!("<{l} as Diffable<'s, 'e, {l}>>::Delta, ", l = l))
+            .collect();
+        let deltas_owned: String = letters
+            .iter()
+            .map(|l| format!("<{l} as Diffable<'s, 'e, {l}>>::DeltaOwned, ", l = l))
+            .collect();
+
+        TupleTokens {
+            field_count,
+            letters,
+            tuple: format!("({})", tuple),
+            delta: format!("crate::delta_n::Delta{}<{}>", field_count, deltas),
+            delta_owned: format!(
+                "crate::delta_n::DeltaOwned{}<{}>",
+                field_count, deltas_owned
+            ),
+        }
+    }
+
+    /// `A: Diffable<'s, 'e, A> + Patchable<<A as Diffable<'s, 'e, A>>::DeltaOwned>, ...`
+    fn bounds_with_owned_delta_trait(&self, patch_trait: &str) -> String {
+        self.letters
+            .iter()
+            .map(|l| {
+                format!(
+                    "{l}: Diffable<'s, 'e, {l}> + {patch_trait}<<{l} as Diffable<'s, 'e, {l}>>::DeltaOwned>, ",
+                    l = l,
+                    patch_trait = patch_trait
+                )
+            })
+            .collect()
+    }
+
+    /// `A: VisitDelta<'s, 'e>, ...`
+    fn bounds(&self, bound: &str) -> String {
+        self.letters
+            .iter()
+            .map(|l| format!("{}: {}, ", l, bound))
+            .collect()
+    }
+
+    /// `prefix0, prefix1, `
+    fn bindings(&self, prefix: &str) -> String {
+        (0..self.field_count)
+            .map(|idx| format!("{}{}, ", prefix, idx))
+            .collect()
+    }
+
+    /// Run some code for every field index.
+    fn per_field(&self, code: impl Fn(usize, char) -> String) -> String {
+        self.letters
+            .iter()
+            .enumerate()
+            .map(|(idx, l)| code(idx, *l))
+            .collect()
+    }
+}
+
+fn generate_tuple_diffable(tuple: &TupleTokens) -> String {
+    let diffs = tuple.per_field(|idx, _| {
+        format!(
+            r#"let diff{idx} = self.{idx}.create_delta_towards_version(&end_state.{idx}, version);
+        "#,
+            idx = idx
+        )
+    });
+    let did_change = (0..tuple.field_count)
+        .map(|idx| format!("diff{}.did_change", idx))
+        .collect::<Vec<_>>()
+        .join(" || ");
+    let changed_deltas = tuple.per_field(|idx, _| {
+        format!(
+            "if diff{idx}.did_change {{ Some(diff{idx}.delta) }} else {{ None }}, ",
+            idx = idx
+        )
+    });
+
+    format!(
+        r#"
+impl<'s, 'e, {bounds}> Diffable<'s, 'e, {tuple}> for {tuple} {{
+    type Delta = {delta};
+    type DeltaOwned = {delta_owned};
+
+    fn create_delta_towards(&'s self, end_state: &'e {tuple}) -> CreatedDelta<Self::Delta> {{
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }}
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e {tuple},
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {{
+        {diffs}
+        let did_change = {did_change};
+        let delta = crate::delta_n::Delta{field_count}::from_fields(({changed_deltas}));
+
+        CreatedDelta {{ delta, did_change }}
+    }}
+}}"#,
+        bounds = tuple.per_field(|_, l| format!("{l}: Diffable<'s, 'e, {l}>, ", l = l)),
+        tuple = tuple.tuple,
+        delta = tuple.delta,
+        delta_owned = tuple.delta_owned,
+        field_count = tuple.field_count,
+        diffs = diffs,
+        did_change = did_change,
+        changed_deltas = changed_deltas,
+    )
+}
+
+fn generate_tuple_patchable(tuple: &TupleTokens) -> String {
+    let apply_patches = tuple.per_field(|idx, _| {
+        format!(
+            r#"if let Some(patch{idx}) = patch{idx} {{
+            self.{idx}
+                .apply_patch(patch{idx})
+                .map_err(|err| err.with_parent(PathSegment::TupleField({idx})))?;
+        }}
+        "#,
+            idx = idx
+        )
+    });
+
+    format!(
+        r#"
+impl<'s, 'e, {bounds}> Patchable<{delta_owned}> for {tuple} {{
+    fn apply_patch(&mut self, patch: {delta_owned}) -> Result<(), PatchError> {{
+        let ({patches}) = patch.into_fields();
+
+        {apply_patches}
+        Ok(())
+    }}
+}}"#,
+        bounds = tuple.bounds_with_owned_delta_trait("Patchable"),
+        tuple = tuple.tuple,
+        delta_owned = tuple.delta_owned,
+        patches = tuple.bindings("patch"),
+        apply_patches = apply_patches,
+    )
+}
+
+/// The borrowed delta is a `DeltaOwnedN` of the fields' borrowed deltas. There is no `Patchable`
+/// implementation for it since it would conflict with the one for the owned delta.
+fn generate_tuple_borrowed_delta(tuple: &TupleTokens) -> String {
+    let apply_patches = tuple.per_field(|idx, _| {
+        format!(
+            r#"if let Some(patch{idx}) = patch{idx} {{
+            self.{idx}
+                .apply_borrowed_patch(patch{idx})
+                .map_err(|err| err.with_parent(PathSegment::TupleField({idx})))?;
+        }}
+        "#,
+            idx = idx
+        )
+    });
+    let deltas_borrowed: String = tuple
+        .letters
+        .iter()
+        .map(|l| format!("<{l} as BorrowedDelta<'de>>::DeltaBorrowed, ", l = l))
+        .collect();
+
+    format!(
+        r#"
+impl<'de, {bounds}> BorrowedDelta<'de> for {tuple} {{
+    type DeltaBorrowed = crate::delta_n::DeltaOwned{field_count}<{deltas_borrowed}>;
+
+    fn apply_borrowed_patch(&mut self, patch: Self::DeltaBorrowed) -> Result<(), PatchError> {{
+        let ({patches}) = patch.into_fields();
+
+        {apply_patches}
+        Ok(())
+    }}
+}}"#,
+        bounds = tuple.bounds("BorrowedDelta<'de>"),
+        tuple = tuple.tuple,
+        field_count = tuple.field_count,
+        deltas_borrowed = deltas_borrowed,
+        patches = tuple.bindings("patch"),
+        apply_patches = apply_patches,
+    )
+}
+
+fn generate_tuple_reversible_patchable(tuple: &TupleTokens) -> String {
+    let apply_patches = tuple.per_field(|idx, _| {
+        format!(
+            r#"let inverse{idx} = patch{idx}
+            .map(|patch| self.{idx}.apply_patch_returning_inverse(patch))
+            .transpose()
+            .map_err(|err| err.with_parent(PathSegment::TupleField({idx})))?;
+        "#,
+            idx = idx
+        )
+    });
+
+    format!(
+        r#"
+impl<'s, 'e, {bounds}> ReversiblePatchable<{delta_owned}> for {tuple} {{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: {delta_owned},
+    ) -> Result<{delta_owned}, PatchError> {{
+        let ({patches}) = patch.into_fields();
+
+        {apply_patches}
+        Ok(crate::delta_n::DeltaOwned{field_count}::from_fields(({inverses})))
+    }}
+}}"#,
+        bounds = tuple.bounds_with_owned_delta_trait("ReversiblePatchable"),
+        tuple = tuple.tuple,
+        delta_owned = tuple.delta_owned,
+        field_count = tuple.field_count,
+        patches = tuple.bindings("patch"),
+        inverses = tuple.bindings("inverse"),
+        apply_patches = apply_patches,
+    )
+}
+
+fn generate_tuple_squashable_patches(tuple: &TupleTokens) -> String {
+    let squashes = tuple.per_field(|idx, l| {
+        format!(
+            r#"let field{idx} = match (first{idx}, second{idx}) {{
+            (Some(first), Some(second)) => Some(
+                {l}::squash_patches(first, second)
+                    .map_err(|err| err.with_parent(PathSegment::TupleField({idx})))?,
+            ),
+            (first, second) => second.or(first),
+        }};
+        "#,
+            idx = idx,
+            l = l
+        )
+    });
+
+    format!(
+        r#"
+impl<'s, 'e, {bounds}> SquashablePatches<{delta_owned}> for {tuple} {{
+    fn squash_patches(
+        first: {delta_owned},
+        second: {delta_owned},
+    ) -> Result<{delta_owned}, PatchError> {{
+        let ({firsts}) = first.into_fields();
+        let ({seconds}) = second.into_fields();
+
+        {squashes}
+        Ok(crate::delta_n::DeltaOwned{field_count}::from_fields(({fields})))
+    }}
+}}"#,
+        bounds = tuple.bounds_with_owned_delta_trait("SquashablePatches"),
+        tuple = tuple.tuple,
+        delta_owned = tuple.delta_owned,
+        field_count = tuple.field_count,
+        firsts = tuple.bindings("first"),
+        seconds = tuple.bindings("second"),
+        fields = tuple.bindings("field"),
+        squashes = squashes,
+    )
+}
+
+fn generate_tuple_mergeable_patches(tuple: &TupleTokens) -> String {
+    let merges = tuple.per_field(|idx, l| {
+        format!(
+            r#"let field{idx} = match (a{idx}, b{idx}) {{
+            (Some(a), Some(b)) => {l}::merge_patches(&base.{idx}, a, b)
+                .map_err(|err| conflicts.extend(err.with_parent(PathSegment::TupleField({idx}))))
+                .ok(),
+            (a, b) => a.or(b),
+        }};
+        "#,
+            idx = idx,
+            l = l
+        )
+    });
+
+    format!(
+        r#"
+impl<'s, 'e, {bounds}> MergeablePatches<{delta_owned}> for {tuple} {{
+    fn merge_patches(
+        base: &Self,
+        a: {delta_owned},
+        b: {delta_owned},
+    ) -> Result<{delta_owned}, MergeConflicts> {{
+        let ({a_fields}) = a.into_fields();
+        let ({b_fields}) = b.into_fields();
+
+        let mut conflicts = MergeConflicts::default();
+
+        {merges}
+        if !conflicts.is_empty() {{
+            return Err(conflicts);
+        }}
+
+        Ok(crate::delta_n::DeltaOwned{field_count}::from_fields(({fields})))
+    }}
+}}"#,
+        bounds = tuple.bounds_with_owned_delta_trait("MergeablePatches"),
+        tuple = tuple.tuple,
+        delta_owned = tuple.delta_owned,
+        field_count = tuple.field_count,
+        a_fields = tuple.bindings("a"),
+        b_fields = tuple.bindings("b"),
+        fields = tuple.bindings("field"),
+        merges = merges,
+    )
+}
+
+/// The 2-tuple is hashed as `"(A, B)"`, the 1-tuple as `"(A,)"` and so on.
+fn generate_tuple_schema_fingerprint(tuple: &TupleTokens) -> String {
+    let name = if tuple.field_count == 1 {
+        "(A,)".to_string()
+    } else {
+        let letters: Vec<String> = tuple.letters.iter().map(|l| l.to_string()).collect();
+        format!("({})", letters.join(", "))
+    };
+    let field_fingerprints = tuple.per_field(|_, l| {
+        format!(
+            r#"
+        .write_u64({}::FINGERPRINT)"#,
+            l
+        )
+    });
+
+    format!(
+        r#"
+impl<'s, 'e, {generics}> crate::SchemaFingerprint<'s, 'e> for {tuple}
+where
+    {bounds}
+{{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("{name}"){field_fingerprints}
+        .finish();
+}}"#,
+        generics = tuple.per_field(|_, l| format!("{}, ", l)),
+        tuple = tuple.tuple,
+        bounds = tuple.bounds("crate::SchemaFingerprint<'s, 'e>"),
+        name = name,
+        field_fingerprints = field_fingerprints,
+    )
+}
+
+fn generate_tuple_visit_delta(tuple: &TupleTokens) -> String {
+    let visits = tuple.per_field(|idx, _| {
+        format!(
+            r#"if let Some(delta{idx}) = delta{idx} {{
+            visitor.enter(PathSegment::TupleField({idx}));
+            self.{idx}.visit_delta(delta{idx}, visitor);
+            visitor.exit();
+        }}
+        "#,
+            idx = idx
+        )
+    });
+
+    format!(
+        r#"
+impl<'s, 'e, {bounds}> VisitDelta<'s, 'e> for {tuple} {{
+    fn visit_delta(&self, delta: &{delta}, visitor: &mut dyn DeltaVisitor) {{
+        let ({deltas}) = delta.fields();
+
+        {visits}
+    }}
+}}"#,
+        bounds = tuple.bounds("VisitDelta<'s, 'e>"),
+        tuple = tuple.tuple,
+        delta = tuple.delta,
+        deltas = tuple.bindings("delta"),
+        visits = visits,
+    )
+}
+
+/// `serde_json` writes tuples as arrays, so each field is addressed by its index.
+fn generate_tuple_to_json_patch(tuple: &TupleTokens) -> String {
+    let pushes = tuple.per_field(|idx, _| {
+        format!(
+            r#"if let Some(delta{idx}) = delta{idx} {{
+            self.{idx}.push_json_patch(delta{idx}, &path.child({idx}), patch)?;
+        }}
+        "#,
+            idx = idx
+        )
+    });
+
+    format!(
+        r#"
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, {bounds}> crate::ToJsonPatch<'s, 'e> for {tuple} {{
+    fn push_json_patch(
+        &self,
+        delta: &{delta},
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {{
+        let ({deltas}) = delta.fields();
+
+        {pushes}
+        Ok(())
+    }}
+}}"#,
+        bounds = tuple.bounds("crate::ToJsonPatch<'s, 'e>"),
+        tuple = tuple.tuple,
+        delta = tuple.delta,
+        deltas = tuple.bindings("delta"),
+        pushes = pushes,
+    )
+}
+
+/// A tuple is within epsilon when every one of its fields is.
+fn generate_tuple_within_epsilon(tuple: &TupleTokens) -> String {
+    let comparisons: Vec<String> = (0..tuple.field_count)
+        .map(|idx| {
+            format!(
+                "self.{idx}.within_epsilon(&other.{idx}, epsilon)",
+                idx = idx
+            )
+        })
+        .collect();
+
+    format!(
+        r#"
+impl<{generics}> crate::WithinEpsilon for {tuple} {{
+    fn within_epsilon(&self, other: &Self, epsilon: f64) -> bool {{
+        {comparisons}
+    }}
+}}"#,
+        generics = tuple.bounds("crate::WithinEpsilon"),
+        tuple = tuple.tuple,
+        comparisons = comparisons.join(" && "),
+    )
+}
+
 /// Every possible combination of `n` booleans being true or false
 /// There are `2 ^ field_count` combinations.
 ///
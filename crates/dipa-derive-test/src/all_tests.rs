@@ -3,12 +3,33 @@
 // slowed down by warning compilation errors.
 #![deny(warnings)]
 
+mod borrowed_delta;
+mod change_flagged_field;
+mod delta_visitor;
 mod enum_with_fields;
+mod epsilon_field;
 mod field_batching_strategy;
+mod fixed_size_array;
+mod incremental_field;
+mod json_patch;
 mod max_fields_per_batch;
+mod merge_patches;
+mod packed_bools;
+mod patch_error;
+mod quantized_field;
+mod result_field;
+mod reversible_patch;
+mod schema_evolution;
+mod schema_fingerprint;
+mod shared_and_interior_mutability;
+mod squash_patches;
+mod std_misc_fields;
 mod struct_with_fields;
+mod text_field;
+mod to_owned_delta;
 mod zero_sized_type;
 
 mod public_type;
+mod recursive_type;
 
 mod ui;
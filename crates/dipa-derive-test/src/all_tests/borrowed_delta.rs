@@ -0,0 +1,266 @@
+//! Verify that the generated borrowed delta types can be deserialized from the same bytes as the
+//! owned delta types and applied without copying their byte and string payloads.
+
+use bincode::Options;
+use dipa::{BorrowedDelta, Diffable, Patchable};
+use std::collections::{HashMap, HashSet, LinkedList};
+use std::fmt::Debug;
+use std::ops::Range;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Empty;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct OneField {
+    name: String,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct TwoFields {
+    bytes: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(field_batching_strategy = "no_batching")]
+struct TwoFieldsNoBatching {
+    bytes: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Nested {
+    one: OneField,
+    two: TwoFields,
+    label: Option<String>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+enum OneVariantOneField {
+    One { name: String },
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+enum OneVariantTwoFields {
+    One(String, u32),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+enum NoData {
+    One,
+    Two,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+enum WithData {
+    One,
+    Two(String),
+    Three { bytes: Vec<u8>, maybe: Option<u16> },
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Containers {
+    numbers: Vec<u32>,
+    map: HashMap<u8, String>,
+    set: HashSet<u16>,
+    tuple: (u8, String),
+    range: Range<u16>,
+}
+
+/// Verify that types with fields that do not have borrowed deltas still compile.
+#[allow(dead_code)]
+#[derive(DiffPatch)]
+struct HasListField {
+    list: LinkedList<u8>,
+    other: u8,
+}
+
+#[allow(dead_code)]
+#[derive(DiffPatch)]
+enum HasListVariant {
+    One(LinkedList<u8>),
+    Two,
+}
+
+/// Verify that we can patch structs from borrowed deltas.
+#[test]
+fn structs() {
+    borrowed_patch(Empty, Empty);
+
+    borrowed_patch(
+        OneField {
+            name: "a".to_string(),
+        },
+        OneField {
+            name: "abc".to_string(),
+        },
+    );
+
+    borrowed_patch(
+        TwoFields {
+            bytes: vec![1],
+            maybe: None,
+        },
+        TwoFields {
+            bytes: vec![1, 2, 3],
+            maybe: Some(3),
+        },
+    );
+
+    borrowed_patch(
+        TwoFieldsNoBatching {
+            bytes: vec![1, 2],
+            maybe: Some(2),
+        },
+        TwoFieldsNoBatching {
+            bytes: vec![1],
+            maybe: None,
+        },
+    );
+
+    borrowed_patch(
+        Nested {
+            one: OneField {
+                name: "a".to_string(),
+            },
+            two: TwoFields {
+                bytes: vec![],
+                maybe: None,
+            },
+            label: None,
+        },
+        Nested {
+            one: OneField {
+                name: "ab".to_string(),
+            },
+            two: TwoFields {
+                bytes: vec![4, 5],
+                maybe: Some(2),
+            },
+            label: Some("label".to_string()),
+        },
+    );
+}
+
+/// Verify that structs with container fields, such as a `Vec` of items other than bytes or a
+/// map, can be patched from borrowed deltas.
+#[test]
+fn container_fields() {
+    let start = Containers {
+        numbers: vec![1, 2],
+        map: vec![(1, "a".to_string())].into_iter().collect(),
+        set: vec![1].into_iter().collect(),
+        tuple: (1, "a".to_string()),
+        range: 0..5,
+    };
+
+    borrowed_patch(start.clone(), start.clone());
+    borrowed_patch(
+        start,
+        Containers {
+            numbers: vec![1, 2, 3],
+            map: vec![(1, "ab".to_string())].into_iter().collect(),
+            set: vec![2, 3].into_iter().collect(),
+            tuple: (1, "abc".to_string()),
+            range: 2..5,
+        },
+    );
+}
+
+/// Verify that we can patch enums from borrowed deltas, including changes between variants.
+#[test]
+fn enums() {
+    borrowed_patch(
+        OneVariantOneField::One {
+            name: "a".to_string(),
+        },
+        OneVariantOneField::One {
+            name: "ab".to_string(),
+        },
+    );
+
+    borrowed_patch(
+        OneVariantTwoFields::One("a".to_string(), 2),
+        OneVariantTwoFields::One("ab".to_string(), 3),
+    );
+
+    borrowed_patch(NoData::One, NoData::Two);
+
+    borrowed_patch(WithData::One, WithData::Two("a".to_string()));
+    borrowed_patch(
+        WithData::Two("a".to_string()),
+        WithData::Two("abc".to_string()),
+    );
+    borrowed_patch(
+        WithData::Two("a".to_string()),
+        WithData::Three {
+            bytes: vec![3],
+            maybe: None,
+        },
+    );
+    borrowed_patch(
+        WithData::Three {
+            bytes: vec![3],
+            maybe: Some(4),
+        },
+        WithData::Three {
+            bytes: vec![3, 5],
+            maybe: None,
+        },
+    );
+    borrowed_patch(WithData::Two("a".to_string()), WithData::One);
+}
+
+/// Verify that types with more than one field can also be patched from their borrowed delta using
+/// `Patchable`.
+#[test]
+fn patchable_from_borrowed_delta() {
+    let start = TwoFields {
+        bytes: vec![1],
+        maybe: None,
+    };
+    let end = TwoFields {
+        bytes: vec![1, 2],
+        maybe: Some(3),
+    };
+
+    let bytes = serialize(&start.create_delta_towards(&end).delta);
+    let patch: TwoFieldsDeltaBorrowed = bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap();
+
+    let mut patched = start.clone();
+    patched.apply_patch(patch).unwrap();
+
+    assert_eq!(patched, end);
+}
+
+/// Deserialize the borrowed delta from the serialized delta between `start` and `end` and verify
+/// that applying it to `start` gives us `end`.
+fn borrowed_patch<T>(start: T, end: T)
+where
+    T: Debug + Clone + PartialEq,
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'de> BorrowedDelta<'de>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+{
+    let bytes = serialize(&start.create_delta_towards(&end).delta);
+
+    let patch: <T as BorrowedDelta>::DeltaBorrowed = bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap();
+
+    let mut patched = start.clone();
+    patched.apply_borrowed_patch(patch).unwrap();
+
+    assert_eq!(patched, end);
+}
+
+fn serialize<D: serde::Serialize>(delta: &D) -> Vec<u8> {
+    bincode::options()
+        .with_varint_encoding()
+        .serialize(delta)
+        .unwrap()
+}
@@ -0,0 +1,50 @@
+//! Verify that a `ChangeFlagged` field is only diffed when it was mutated since the start state was
+//! cloned from it.
+
+use bincode::Options;
+use dipa::{ChangeFlagged, Diffable, Patchable};
+
+#[derive(Debug, Clone, PartialEq, DiffPatch)]
+struct River {
+    tick: u32,
+    water_droplets: ChangeFlagged<Vec<WaterDroplet>>,
+}
+
+#[derive(Debug, Clone, PartialEq, DiffPatch, Serialize, Deserialize)]
+struct WaterDroplet([u8; 3]);
+
+/// Verify that the droplets are sent after they are mutated and skipped when the last sent state
+/// was cloned after they were touched.
+#[test]
+fn struct_with_change_flagged_field() {
+    let mut state = River {
+        tick: 0,
+        water_droplets: ChangeFlagged::new(vec![WaterDroplet([1, 2, 3])]),
+    };
+    let mut client = state.clone();
+    let mut last_sent = state.clone();
+
+    state.water_droplets.push(WaterDroplet([4, 5, 6]));
+    patch(&mut client, &last_sent, &state);
+    assert_eq!(client, state);
+    last_sent = state.clone();
+
+    // The droplets were not touched, so only the tick is diffed.
+    state.tick += 1;
+    let diff = last_sent.create_delta_towards(&state);
+    assert!(matches!(diff.delta, RiverDelta::Change_0(_),));
+    patch(&mut client, &last_sent, &state);
+    assert_eq!(client, state);
+}
+
+/// Patch the client with the serialized delta from the last sent state towards the state.
+fn patch(client: &mut River, last_sent: &River, state: &River) {
+    let options = bincode::options().with_varint_encoding();
+
+    let bytes = options
+        .serialize(&last_sent.create_delta_towards(state).delta)
+        .unwrap();
+
+    let patch: <River as Diffable<River>>::DeltaOwned = options.deserialize(&bytes).unwrap();
+    client.apply_patch(patch).unwrap();
+}
@@ -0,0 +1,135 @@
+//! Verify that the generated types can describe the changes in their deltas.
+
+use crate::all_tests::to_owned_delta::NotClone;
+use dipa::{DeltaPrinter, VisitDelta};
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Player {
+    position: Position,
+    notifications: Vec<u8>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(field_batching_strategy = "no_batching")]
+struct PlayerNoBatching {
+    position: Position,
+    notifications: Vec<u8>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct OneField {
+    health: u16,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Tuple(u8, Option<u16>);
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+enum NoData {
+    Idle,
+    Running,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+enum WithData {
+    Idle,
+    Running { progress: u8, label: String },
+    Done(u32),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Machine {
+    state: WithData,
+}
+
+/// Verify that types with fields that can't be visited still compile.
+#[allow(dead_code)]
+#[derive(DiffPatch)]
+struct HasNotCloneField {
+    list: Vec<NotClone>,
+    other: u8,
+}
+
+/// Verify that we print the path to every changed field of a struct.
+#[test]
+fn structs() {
+    let start = Player {
+        position: Position { x: 1., y: 2. },
+        notifications: vec![1, 2],
+    };
+    let end = Player {
+        position: Position { x: 4., y: 2. },
+        notifications: vec![1, 2, 3],
+    };
+
+    print(
+        start.clone(),
+        end.clone(),
+        &["position.x: 1.0 -> 4.0", "notifications[2]: inserted"],
+    );
+
+    print(
+        PlayerNoBatching {
+            position: start.position,
+            notifications: start.notifications,
+        },
+        PlayerNoBatching {
+            position: end.position,
+            notifications: end.notifications,
+        },
+        &["position.x: 1.0 -> 4.0", "notifications[2]: inserted"],
+    );
+
+    print(
+        OneField { health: 5 },
+        OneField { health: 2 },
+        &["health: 5 -> 2"],
+    );
+
+    print(
+        Tuple(1, None),
+        Tuple(2, Some(3)),
+        &["0: 1 -> 2", "1: inserted"],
+    );
+}
+
+/// Verify that we print changes between variants and changes to the fields of a variant.
+#[test]
+fn enums() {
+    print(NoData::Idle, NoData::Running, &["Idle -> Running"]);
+    print(NoData::Idle, NoData::Idle, &[]);
+
+    print(WithData::Idle, WithData::Done(5), &["Idle -> Done"]);
+    print(WithData::Done(5), WithData::Done(6), &["Done.0: 5 -> 6"]);
+
+    print(
+        Machine {
+            state: WithData::Running {
+                progress: 1,
+                label: "a".to_string(),
+            },
+        },
+        Machine {
+            state: WithData::Running {
+                progress: 2,
+                label: "a".to_string(),
+            },
+        },
+        &["state.Running.progress: 1 -> 2"],
+    );
+}
+
+fn print<T>(start: T, end: T, expected: &[&str])
+where
+    T: for<'s, 'e> VisitDelta<'s, 'e>,
+{
+    let delta = start.create_delta_towards(&end).delta;
+
+    assert_eq!(DeltaPrinter::print(&start, &delta).lines(), expected);
+}
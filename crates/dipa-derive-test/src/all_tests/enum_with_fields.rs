@@ -221,3 +221,75 @@ fn two_variants_one_tuple() {
     }
     .test();
 }
+
+/// Verify that we can diff an enum with two variants, one of which is a struct like variant.
+#[test]
+fn two_variants_one_struct() {
+    DipaImplTester {
+        label: None,
+        start: &mut TwoVariantsOneStruct::One { foo: 5 },
+        end: &TwoVariantsOneStruct::Two,
+        expected_delta: TwoVariantsOneStructDelta::ChangedToVariantTwo,
+        expected_serialized_patch_size: 1,
+        expected_did_change: true,
+    }
+    .test();
+
+    DipaImplTester {
+        label: None,
+        start: &mut TwoVariantsOneStruct::One { foo: 5 },
+        end: &TwoVariantsOneStruct::One { foo: 10 },
+        expected_delta: TwoVariantsOneStructDelta::OneChange_0(10),
+        expected_serialized_patch_size: 2,
+        expected_did_change: true,
+    }
+    .test();
+}
+
+/// Verify that we can diff an enum with two variants, one of which has two tuple fields.
+#[test]
+fn two_variants_tuple_two_fields() {
+    DipaImplTester {
+        label: None,
+        start: &mut TwoVariantsTupleTwoFields::One,
+        end: &TwoVariantsTupleTwoFields::Two(1, 2),
+        expected_delta: TwoVariantsTupleTwoFieldsDelta::ChangedToVariantTwo(&1, &2),
+        expected_serialized_patch_size: 3,
+        expected_did_change: true,
+    }
+    .test();
+
+    DipaImplTester {
+        label: None,
+        start: &mut TwoVariantsTupleTwoFields::Two(1, 2),
+        end: &TwoVariantsTupleTwoFields::Two(1, 3),
+        expected_delta: TwoVariantsTupleTwoFieldsDelta::TwoChange_1(Some(3)),
+        expected_serialized_patch_size: 3,
+        expected_did_change: true,
+    }
+    .test();
+}
+
+/// Verify that we can diff an enum with two variants, one of which has two struct fields.
+#[test]
+fn two_variants_struct_two_fields() {
+    DipaImplTester {
+        label: None,
+        start: &mut TwoVariantsStructTwoFields::Two { buzz: 1, bazz: 2 },
+        end: &TwoVariantsStructTwoFields::One,
+        expected_delta: TwoVariantsStructTwoFieldsDelta::ChangedToVariantOne,
+        expected_serialized_patch_size: 1,
+        expected_did_change: true,
+    }
+    .test();
+
+    DipaImplTester {
+        label: None,
+        start: &mut TwoVariantsStructTwoFields::Two { buzz: 1, bazz: 2 },
+        end: &TwoVariantsStructTwoFields::Two { buzz: 3, bazz: 4 },
+        expected_delta: TwoVariantsStructTwoFieldsDelta::TwoChange_0_1(3, Some(4)),
+        expected_serialized_patch_size: 4,
+        expected_did_change: true,
+    }
+    .test();
+}
@@ -0,0 +1,115 @@
+//! Verify that fields with an epsilon attribute are left out of the delta until they move by more
+//! than their epsilon.
+
+use dipa::{Diffable, EpsilonTracked, Patchable, ToOwnedDelta};
+
+#[derive(Debug, PartialEq, DiffPatch)]
+struct Body {
+    #[dipa(epsilon = 0.01)]
+    position: [f32; 3],
+    #[dipa(epsilon = 0.1)]
+    angle: (f32, f64),
+    sleeping: bool,
+}
+
+/// Verify that jitter within epsilon does not count as a change.
+#[test]
+fn changes_within_epsilon_are_ignored() {
+    let start = body([0.; 3], (0., 0.), false);
+
+    let jittered = body([0.005, -0.01, 0.], (0.05, -0.05), false);
+    let delta = start.create_delta_towards(&jittered);
+    assert!(!delta.did_change);
+
+    let moved = body([0.005, 0.02, 0.], (0.05, -0.05), false);
+    assert!(start.create_delta_towards(&moved).did_change);
+
+    let turned = body([0.; 3], (0., 0.2), false);
+    assert!(start.create_delta_towards(&turned).did_change);
+}
+
+/// Verify that a field within epsilon is left out of the delta when other fields changed.
+#[test]
+fn field_within_epsilon_is_not_patched() {
+    let mut receiver = body([0.; 3], (0., 0.), false);
+    let sender = body([0.005, 0., 0.], (0., 0.), true);
+
+    let delta = receiver.create_delta_towards(&sender);
+    assert!(delta.did_change);
+
+    let patch: <Body as Diffable<Body>>::DeltaOwned = delta.delta.to_owned_delta();
+    receiver.apply_patch(patch).unwrap();
+
+    assert_eq!(receiver, body([0.; 3], (0., 0.), true));
+}
+
+/// Verify that small changes add up until they get sent when deltas are created from the
+/// receiver's state.
+#[test]
+fn small_changes_accumulate() {
+    let mut receiver = body([0.; 3], (0., 0.), false);
+
+    let mut sent = vec![];
+    for tick in 1..=6 {
+        let sender = body([0.004 * tick as f32, 0., 0.], (0., 0.), false);
+
+        let delta = receiver.create_delta_towards(&sender);
+        sent.push(delta.did_change);
+
+        let patch: <Body as Diffable<Body>>::DeltaOwned = delta.delta.to_owned_delta();
+        receiver.apply_patch(patch).unwrap();
+    }
+
+    assert_eq!(sent, vec![false, false, true, false, false, true]);
+    assert_eq!(receiver.position[0], 0.004 * 6.);
+}
+
+#[derive(Debug, PartialEq, DiffPatch)]
+struct TrackedBody {
+    #[dipa(epsilon = 0.01)]
+    position: EpsilonTracked<[f32; 3]>,
+    sleeping: bool,
+}
+
+/// Verify that many steps that are each within epsilon eventually get sent when every delta is
+/// created from the previous tick's state, and that the receiver stays within epsilon.
+#[test]
+fn tracked_field_drifts_past_epsilon() {
+    let mut receiver = tracked_body(0.);
+    let mut previous = tracked_body(0.);
+
+    let mut sent = vec![];
+    for tick in 1..=40 {
+        let current = tracked_body(0.003 * tick as f32);
+
+        let delta = previous.create_delta_towards(&current);
+        sent.push(delta.did_change);
+
+        let patch: <TrackedBody as Diffable<TrackedBody>>::DeltaOwned =
+            delta.delta.to_owned_delta();
+        receiver.apply_patch(patch).unwrap();
+
+        assert!((receiver.position[0] - current.position[0]).abs() <= 0.0101);
+
+        previous = current;
+    }
+
+    // Every step is 0.003, so the position is sent every fourth tick once it moved by 0.012.
+    let expected: Vec<bool> = (1..=40).map(|tick| tick % 4 == 0).collect();
+    assert_eq!(sent, expected);
+}
+
+fn tracked_body(x: f32) -> TrackedBody {
+    TrackedBody {
+        position: EpsilonTracked::new([x, 0., 0.]),
+        sleeping: false,
+    }
+}
+
+fn body(position: [f32; 3], angle: (f32, f64), sleeping: bool) -> Body {
+    Body {
+        position,
+        angle,
+        sleeping,
+    }
+}
@@ -15,6 +15,7 @@ struct NoBatching {
 #[dipa(field_batching_strategy = "no_batching")]
 #[derive(Debug, PartialEq)]
 #[rustfmt::skip]
+#[allow(dead_code)]
 struct NoBatchingManyFields {
     f1: (), f2: (), f3: (), f4: (), f5: (),
     f6: (), f7: (), f8: (), f9: (), f10: (),
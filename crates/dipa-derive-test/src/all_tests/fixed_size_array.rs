@@ -0,0 +1,65 @@
+//! Verify that structs with fixed size array fields can be diffed and patched.
+
+use bincode::Options;
+use dipa::{Diffable, Patchable};
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Transform {
+    position: [f32; 3],
+    scale: [f32; 3],
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Grid([[u8; 4]; 4]);
+
+/// Verify that only the changed array elements are encoded.
+#[test]
+fn struct_with_array_fields() {
+    let mut start = Transform {
+        position: [1., 2., 3.],
+        scale: [1., 1., 1.],
+    };
+    let end = Transform {
+        position: [1., 5., 3.],
+        scale: [1., 1., 1.],
+    };
+
+    // 1 for the struct's delta variant, 1 for the bitmask, 5 for the changed f32
+    assert_eq!(patch(&mut start, &end), 7);
+    assert_eq!(start, end);
+
+    // 1 for the struct's delta variant
+    assert_eq!(patch(&mut start, &end), 1);
+}
+
+/// Verify that nested arrays are diffed element by element.
+#[test]
+fn nested_arrays() {
+    let mut start = Grid([[0; 4]; 4]);
+    let mut end = start.clone();
+    end.0[3][2] = 9;
+
+    // 1 for the outer bitmask, 1 for the inner bitmask, 1 for the changed u8
+    assert_eq!(patch(&mut start, &end), 3);
+    assert_eq!(start, end);
+}
+
+/// Patch `start` into `end` and return the size of the serialized delta.
+fn patch<T>(start: &mut T, end: &T) -> usize
+where
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: serde::de::DeserializeOwned,
+{
+    let options = bincode::options().with_varint_encoding();
+
+    let bytes = options
+        .serialize(&start.create_delta_towards(end).delta)
+        .unwrap();
+    start
+        .apply_patch(options.deserialize(&bytes).unwrap())
+        .unwrap();
+
+    bytes.len()
+}
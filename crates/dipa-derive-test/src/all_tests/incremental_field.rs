@@ -0,0 +1,34 @@
+//! Verify that structs with incremental integer fields send the difference for those fields.
+
+use bincode::Options;
+use dipa::{Diffable, Incremental, Patchable};
+
+#[derive(Debug, PartialEq, DiffPatch)]
+struct Stats {
+    total_requests: Incremental<u64>,
+    last_request_id: u64,
+}
+
+/// Verify that a small change to a large incremental field is encoded as a small difference.
+#[test]
+fn struct_with_incremental_field() {
+    let mut start = Stats {
+        total_requests: Incremental(1_000_000_000),
+        last_request_id: 1_000_000_000,
+    };
+    let end = Stats {
+        total_requests: Incremental(1_000_000_001),
+        last_request_id: 1_000_000_000,
+    };
+
+    let options = bincode::options().with_varint_encoding();
+
+    let delta = start.create_delta_towards(&end);
+    let bytes = options.serialize(&delta.delta).unwrap();
+    // 1 for the changed fields, 1 for the Option and 1 for the difference
+    assert_eq!(bytes.len(), 3);
+
+    let patch: <Stats as Diffable<Stats>>::DeltaOwned = options.deserialize(&bytes).unwrap();
+    start.apply_patch(patch).unwrap();
+    assert_eq!(start, end);
+}
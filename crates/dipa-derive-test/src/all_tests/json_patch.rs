@@ -0,0 +1,218 @@
+//! Verify that the generated types can convert their deltas into JSON Patch documents.
+
+use dipa::{Diffable, JsonPointer, ToJsonPatch};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+struct Player {
+    position: Position,
+    notifications: Vec<u8>,
+    nickname: Option<String>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(field_batching_strategy = "no_batching")]
+struct PlayerNoBatching {
+    position: Position,
+    notifications: Vec<u8>,
+    nickname: Option<String>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+struct OneField {
+    health: u16,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+struct Newtype(Vec<u8>);
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+struct Tuple(u8, Option<u16>);
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+enum NoData {
+    Idle,
+    Running,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+enum WithData {
+    Idle,
+    Running { progress: u8, label: String },
+    Done(u32),
+    Scores(u8, Vec<u8>),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+enum OneVariantTwoFields {
+    Only { a: u8, b: Vec<u8> },
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+enum OneVariantNewtype {
+    Only(Vec<u8>),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+struct Machine {
+    state: WithData,
+    mode: NoData,
+    players: HashMap<String, Player>,
+}
+
+/// Verify that applying the JSON Patch to a struct gives us the end struct.
+#[test]
+fn structs() {
+    let start = Player {
+        position: Position { x: 1., y: 2. },
+        notifications: vec![1, 2],
+        nickname: None,
+    };
+    let end = Player {
+        position: Position { x: 4., y: 2. },
+        notifications: vec![1, 2, 3],
+        nickname: Some("a".to_string()),
+    };
+
+    json_patch(start.clone(), end.clone());
+    json_patch(
+        PlayerNoBatching {
+            position: start.position,
+            notifications: start.notifications,
+            nickname: start.nickname,
+        },
+        PlayerNoBatching {
+            position: end.position,
+            notifications: end.notifications,
+            nickname: end.nickname,
+        },
+    );
+
+    json_patch(OneField { health: 5 }, OneField { health: 2 });
+    json_patch(Newtype(vec![1, 2]), Newtype(vec![1, 2, 3]));
+    json_patch(Tuple(1, None), Tuple(2, Some(3)));
+}
+
+/// Verify that applying the JSON Patch to an enum gives us the end enum, whether or not the
+/// variant changed.
+#[test]
+fn enums() {
+    json_patch(NoData::Idle, NoData::Running);
+    json_patch(NoData::Idle, NoData::Idle);
+
+    json_patch(WithData::Idle, WithData::Done(5));
+    json_patch(WithData::Done(5), WithData::Idle);
+    json_patch(WithData::Done(5), WithData::Done(6));
+    json_patch(
+        WithData::Idle,
+        WithData::Running {
+            progress: 1,
+            label: "a".to_string(),
+        },
+    );
+    json_patch(
+        WithData::Running {
+            progress: 1,
+            label: "a".to_string(),
+        },
+        WithData::Running {
+            progress: 2,
+            label: "ab".to_string(),
+        },
+    );
+    json_patch(WithData::Idle, WithData::Scores(1, vec![2]));
+    json_patch(
+        WithData::Scores(1, vec![2]),
+        WithData::Scores(3, vec![2, 4]),
+    );
+
+    json_patch(
+        OneVariantTwoFields::Only { a: 1, b: vec![] },
+        OneVariantTwoFields::Only { a: 2, b: vec![3] },
+    );
+    json_patch(
+        OneVariantNewtype::Only(vec![1]),
+        OneVariantNewtype::Only(vec![1, 2]),
+    );
+}
+
+/// Verify that only the values that changed are written into the JSON Patch.
+#[test]
+fn nested_paths() {
+    let player = Player {
+        position: Position { x: 1., y: 2. },
+        notifications: vec![],
+        nickname: None,
+    };
+
+    let mut start = Machine {
+        state: WithData::Done(1),
+        mode: NoData::Idle,
+        players: HashMap::new(),
+    };
+    start.players.insert("alice".to_string(), player.clone());
+
+    let mut end = start.clone();
+    end.mode = NoData::Running;
+    end.players.get_mut("alice").unwrap().position.y = 3.;
+
+    let delta = start.create_delta_towards(&end).delta;
+    let patch = start.to_json_patch(&delta).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&patch).unwrap(),
+        serde_json::json!([
+            {"op": "replace", "path": "/mode", "value": "Running"},
+            {"op": "replace", "path": "/players/alice/position/y", "value": 3.0},
+        ])
+    );
+
+    json_patch(start.clone(), end);
+
+    let mut end = start.clone();
+    end.players.insert("bob".to_string(), player);
+    json_patch(start, end);
+}
+
+/// Verify that a delta's JSON Patch can be pushed onto a patch for a larger document.
+#[test]
+fn push_under_path() {
+    let start = OneField { health: 5 };
+    let delta = start.create_delta_towards(&OneField { health: 2 }).delta;
+
+    let mut patch = dipa::JsonPatch::default();
+    start
+        .push_json_patch(&delta, &JsonPointer::root().child("boss"), &mut patch)
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&patch).unwrap(),
+        r#"[{"op":"replace","path":"/boss/health","value":2}]"#
+    );
+}
+
+/// Apply the JSON Patch for the delta from `start` to `end` to the serde_json form of `start` and
+/// verify that we get `end`.
+fn json_patch<T>(start: T, end: T)
+where
+    T: for<'s, 'e> ToJsonPatch<'s, 'e> + Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let delta = start.create_delta_towards(&end).delta;
+    let patch = start.to_json_patch(&delta).unwrap();
+
+    let mut json = serde_json::to_value(&start).unwrap();
+    let rfc_patch: ::json_patch::Patch =
+        serde_json::from_value(serde_json::to_value(&patch).unwrap()).unwrap();
+    ::json_patch::patch(&mut json, &rfc_patch).unwrap();
+
+    assert_eq!(serde_json::from_value::<T>(json).unwrap(), end);
+}
@@ -1,6 +1,7 @@
 // Verify that max_fields_per_batch allows us to use more than 5 fields with the
 // one_batch strategy.
 #[derive(DiffPatch)]
+#[allow(dead_code)]
 #[dipa(max_fields_per_batch = 6, field_batching_strategy = "one_batch")]
 struct MyStruct {
     field1: (),
@@ -0,0 +1,337 @@
+//! Verify that the generated `MergeablePatches` implementations combine two patches that were
+//! created against the same base value, and report the paths of any conflicting changes.
+
+use bincode::Options;
+use dipa::{Diffable, MergeConflictReason, MergeConflicts, MergeablePatches, PathSegment};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge)]
+struct Empty;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge)]
+struct OneField {
+    field: Vec<u8>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge)]
+struct TwoFields {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge, field_batching_strategy = "no_batching")]
+struct TwoFieldsNoBatching {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge)]
+struct Nested {
+    inner: TwoFields,
+    scores: HashMap<u8, u32>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge)]
+enum OneVariantOneField {
+    One { field: Vec<u8> },
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge)]
+enum OneVariantTwoFields {
+    One(Vec<u8>, u32),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(merge)]
+enum NoData {
+    One,
+    Two,
+    Three,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(merge)]
+enum WithData {
+    One,
+    Two(Vec<u8>),
+    Three { list: Vec<u8>, maybe: Option<u16> },
+}
+
+/// Verify that we can merge patches to structs that change different fields, or different parts
+/// of the same field.
+#[test]
+fn structs() {
+    merge(Empty, Empty, Empty, Empty);
+
+    merge(
+        OneField {
+            field: vec![1, 2, 3, 4, 5],
+        },
+        OneField {
+            field: vec![9, 2, 3, 4, 5],
+        },
+        OneField {
+            field: vec![1, 2, 3, 4, 5, 6],
+        },
+        OneField {
+            field: vec![9, 2, 3, 4, 5, 6],
+        },
+    );
+
+    merge(
+        TwoFields {
+            list: vec![1, 2],
+            maybe: None,
+        },
+        TwoFields {
+            list: vec![1, 2, 3],
+            maybe: None,
+        },
+        TwoFields {
+            list: vec![1, 2],
+            maybe: Some(4),
+        },
+        TwoFields {
+            list: vec![1, 2, 3],
+            maybe: Some(4),
+        },
+    );
+
+    merge(
+        TwoFieldsNoBatching {
+            list: vec![1, 2],
+            maybe: Some(1),
+        },
+        TwoFieldsNoBatching {
+            list: vec![1],
+            maybe: Some(1),
+        },
+        TwoFieldsNoBatching {
+            list: vec![1, 2],
+            maybe: None,
+        },
+        TwoFieldsNoBatching {
+            list: vec![1],
+            maybe: None,
+        },
+    );
+
+    let base = Nested {
+        inner: TwoFields {
+            list: vec![1, 2, 3],
+            maybe: None,
+        },
+        scores: vec![(1, 10), (2, 20)].into_iter().collect(),
+    };
+
+    let mut a = base.clone();
+    a.inner.list.push(4);
+    a.scores.insert(1, 15);
+
+    let mut b = base.clone();
+    b.inner.maybe = Some(5);
+    b.scores.insert(3, 30);
+
+    merge(
+        base,
+        a,
+        b,
+        Nested {
+            inner: TwoFields {
+                list: vec![1, 2, 3, 4],
+                maybe: Some(5),
+            },
+            scores: vec![(1, 15), (2, 20), (3, 30)].into_iter().collect(),
+        },
+    );
+}
+
+/// Verify that we can merge patches to enums.
+#[test]
+fn enums() {
+    merge(
+        OneVariantOneField::One { field: vec![1, 2] },
+        OneVariantOneField::One {
+            field: vec![0, 1, 2],
+        },
+        OneVariantOneField::One {
+            field: vec![1, 2, 3],
+        },
+        OneVariantOneField::One {
+            field: vec![0, 1, 2, 3],
+        },
+    );
+
+    merge(
+        OneVariantTwoFields::One(vec![1], 2),
+        OneVariantTwoFields::One(vec![1, 2], 2),
+        OneVariantTwoFields::One(vec![1], 5),
+        OneVariantTwoFields::One(vec![1, 2], 5),
+    );
+
+    merge(NoData::One, NoData::Two, NoData::One, NoData::Two);
+    merge(NoData::One, NoData::One, NoData::Three, NoData::Three);
+    merge(NoData::One, NoData::Two, NoData::Two, NoData::Two);
+
+    merge(
+        WithData::One,
+        WithData::Two(vec![1]),
+        WithData::One,
+        WithData::Two(vec![1]),
+    );
+    merge(
+        WithData::Three {
+            list: vec![1, 2],
+            maybe: None,
+        },
+        WithData::Three {
+            list: vec![1, 2, 3],
+            maybe: None,
+        },
+        WithData::Three {
+            list: vec![1, 2],
+            maybe: Some(1),
+        },
+        WithData::Three {
+            list: vec![1, 2, 3],
+            maybe: Some(1),
+        },
+    );
+}
+
+/// Verify that every conflicting field is reported along with its path.
+#[test]
+fn conflicting_fields() {
+    let base = Nested {
+        inner: TwoFields {
+            list: vec![1, 2, 3],
+            maybe: Some(1),
+        },
+        scores: vec![(1, 10)].into_iter().collect(),
+    };
+
+    let mut a = base.clone();
+    a.inner.list = vec![1, 2, 3, 4];
+    a.inner.maybe = Some(2);
+    a.scores.insert(1, 11);
+
+    let mut b = base.clone();
+    b.inner.list = vec![1, 2, 3, 5];
+    b.inner.maybe = Some(3);
+    b.scores.insert(1, 12);
+
+    let conflicts = merge_conflicts(&base, &a, &b);
+    let paths: Vec<Vec<&PathSegment>> = conflicts
+        .conflicts()
+        .iter()
+        .map(|conflict| conflict.path().collect())
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            vec![
+                &PathSegment::Field("inner"),
+                &PathSegment::Field("list"),
+                &PathSegment::Index(3),
+            ],
+            vec![&PathSegment::Field("inner"), &PathSegment::Field("maybe"),],
+            vec![&PathSegment::Field("scores"), &PathSegment::MapValue],
+        ]
+    );
+}
+
+/// Verify that changing an enum to different variants, or changing to another variant while the
+/// other patch changes the current variant's fields, conflicts.
+#[test]
+fn conflicting_variants() {
+    let conflicts = merge_conflicts(&NoData::One, &NoData::Two, &NoData::Three);
+    assert_eq!(
+        conflicts.conflicts()[0].reason(),
+        &MergeConflictReason::ConcurrentChange
+    );
+
+    let conflicts = merge_conflicts(
+        &WithData::Two(vec![1]),
+        &WithData::Two(vec![1, 2]),
+        &WithData::One,
+    );
+    assert_eq!(conflicts.conflicts()[0].path().count(), 0);
+
+    let conflicts = merge_conflicts(
+        &WithData::Three {
+            list: vec![],
+            maybe: Some(1),
+        },
+        &WithData::Three {
+            list: vec![],
+            maybe: Some(2),
+        },
+        &WithData::Three {
+            list: vec![],
+            maybe: Some(3),
+        },
+    );
+    assert_eq!(
+        conflicts.conflicts()[0].path().collect::<Vec<_>>(),
+        vec![&PathSegment::Variant("Three"), &PathSegment::Field("maybe")]
+    );
+}
+
+/// Merge the patch from `base` to `a` with the patch from `base` to `b` and verify that applying
+/// the merged patch to `base` gives us `expected`.
+fn merge<T>(base: T, a: T, b: T, expected: T)
+where
+    T: Debug + Clone + PartialEq,
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: serde::de::DeserializeOwned,
+{
+    let patch_a = round_trip(&base.create_delta_towards(&a).delta);
+    let patch_b = round_trip(&base.create_delta_towards(&b).delta);
+
+    let merged = T::merge_patches(&base, patch_a, patch_b).unwrap();
+
+    let mut patched = base.clone();
+    patched.apply_patch(merged).unwrap();
+
+    assert_eq!(patched, expected);
+}
+
+/// Merge the patch from `base` to `a` with the patch from `base` to `b`, expecting conflicts.
+fn merge_conflicts<T>(base: &T, a: &T, b: &T) -> MergeConflicts
+where
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: serde::de::DeserializeOwned,
+{
+    let patch_a = round_trip(&base.create_delta_towards(a).delta);
+    let patch_b = round_trip(&base.create_delta_towards(b).delta);
+
+    match T::merge_patches(base, patch_a, patch_b) {
+        Ok(_) => panic!("Expected the patches to conflict"),
+        Err(conflicts) => conflicts,
+    }
+}
+
+fn round_trip<D: serde::Serialize, P: serde::de::DeserializeOwned>(delta: &D) -> P {
+    let bytes = bincode::options()
+        .with_varint_encoding()
+        .serialize(delta)
+        .unwrap();
+
+    bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap()
+}
@@ -0,0 +1,343 @@
+//! Verify that the bool fields of structs and enum variants are packed into one bitflag delta.
+
+use bincode::Options;
+use dipa::{
+    BorrowedDelta, DeltaPrinter, Diffable, MergeablePatches, Patchable, ReversiblePatchable,
+    SquashablePatches, ToJsonPatch,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible, squash, merge)]
+struct Status {
+    visible: bool,
+    health: u8,
+    poisoned: bool,
+    stunned: bool,
+    invisible: bool,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible)]
+struct OnlyBools {
+    a: bool,
+    b: bool,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible)]
+struct Tuple(bool, Vec<u8>, bool);
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible, pack_bools, field_batching_strategy = "no_batching")]
+struct NoBatching {
+    visible: bool,
+    health: u8,
+    poisoned: bool,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible)]
+struct SinceBool {
+    visible: bool,
+    poisoned: bool,
+    #[dipa(since = 2)]
+    stunned: bool,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible)]
+struct PrimitivePaths {
+    a: core::primitive::bool,
+    b: std::primitive::bool,
+    c: bool,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible, pack_bools = false)]
+struct NotPacked {
+    a: bool,
+    b: bool,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible)]
+enum OneVariant {
+    Only(bool, u16, bool),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible)]
+enum Movement {
+    Idle,
+    Walking {
+        running: bool,
+        speed: u8,
+        crouching: bool,
+    },
+    Falling(bool, bool),
+}
+
+/// Verify that any number of flipped bools cost one byte.
+#[test]
+fn flipped_bools_cost_one_byte() {
+    let start = status(false, false, false, false);
+
+    // The delta's variant plus the bitflags.
+    assert_eq!(patch(start.clone(), status(true, false, false, false)), 2);
+    assert_eq!(patch(start.clone(), status(true, true, true, true)), 2);
+
+    let mut hurt = status(true, true, true, true);
+    hurt.health = 50;
+    assert_eq!(patch(start, hurt), 3);
+
+    assert_eq!(
+        patch(
+            OnlyBools { a: true, b: false },
+            OnlyBools { a: false, b: true }
+        ),
+        2
+    );
+    assert_eq!(
+        patch(
+            NoBatching {
+                visible: false,
+                health: 1,
+                poisoned: false,
+            },
+            NoBatching {
+                visible: true,
+                health: 1,
+                poisoned: true,
+            },
+        ),
+        2
+    );
+}
+
+/// Verify that bools are packed by default unless packing is turned off, and that they are
+/// recognized through the paths to the primitive type.
+#[test]
+fn packing_can_be_turned_off() {
+    assert_eq!(
+        patch(
+            NotPacked { a: false, b: false },
+            NotPacked { a: true, b: true }
+        ),
+        3
+    );
+
+    assert_eq!(
+        patch(
+            PrimitivePaths {
+                a: false,
+                b: false,
+                c: false,
+            },
+            PrimitivePaths {
+                a: true,
+                b: true,
+                c: true,
+            },
+        ),
+        2
+    );
+}
+
+/// Verify that the bools of tuple structs and enum variants are packed without mixing up the
+/// fields around them.
+#[test]
+fn tuples_and_enums() {
+    patch(Tuple(false, vec![1], false), Tuple(true, vec![1, 2], true));
+    patch(Tuple(true, vec![], true), Tuple(true, vec![], false));
+
+    assert_eq!(
+        patch(
+            OneVariant::Only(false, 5, false),
+            OneVariant::Only(true, 5, true)
+        ),
+        2
+    );
+
+    let walking = Movement::Walking {
+        running: false,
+        speed: 3,
+        crouching: false,
+    };
+    let sprinting = Movement::Walking {
+        running: true,
+        speed: 3,
+        crouching: true,
+    };
+    assert_eq!(patch(walking.clone(), sprinting.clone()), 2);
+
+    patch(Movement::Idle, sprinting.clone());
+    patch(sprinting, Movement::Falling(true, false));
+    patch(
+        Movement::Falling(true, false),
+        Movement::Falling(false, true),
+    );
+    patch(Movement::Falling(true, false), walking);
+}
+
+/// Verify that a bool with a since attribute keeps its own delta so that it can be left out of
+/// deltas for earlier schema versions.
+#[test]
+fn since_bool_is_not_packed() {
+    let start = SinceBool {
+        visible: false,
+        poisoned: false,
+        stunned: false,
+    };
+    let end = SinceBool {
+        visible: true,
+        poisoned: true,
+        stunned: true,
+    };
+
+    assert_eq!(patch(start.clone(), end.clone()), 3);
+
+    let delta = start.create_delta_towards_version(&end, 1);
+    let patch: <SinceBool as Diffable<SinceBool>>::DeltaOwned = round_trip(&delta.delta);
+    let mut patched = start.clone();
+    patched.apply_patch(patch).unwrap();
+    assert_eq!(
+        patched,
+        SinceBool {
+            stunned: false,
+            ..end
+        }
+    );
+}
+
+/// Verify that patches to packed bools can be squashed and merged.
+#[test]
+fn squash_and_merge() {
+    let start = status(false, false, false, false);
+    let middle = status(true, true, false, false);
+    let end = status(false, true, true, false);
+
+    let squashed = Status::squash_patches(
+        round_trip(&start.create_delta_towards(&middle).delta),
+        round_trip(&middle.create_delta_towards(&end).delta),
+    )
+    .unwrap();
+    let mut patched = start.clone();
+    patched.apply_patch(squashed).unwrap();
+    assert_eq!(patched, end);
+
+    let merged = Status::merge_patches(
+        &start,
+        round_trip(&start.create_delta_towards(&middle).delta),
+        round_trip(&start.create_delta_towards(&end).delta),
+    )
+    .unwrap();
+    let mut patched = start.clone();
+    patched.apply_patch(merged).unwrap();
+    assert_eq!(patched, status(true, true, true, false));
+}
+
+/// Verify that every changed bool is described at its own path.
+#[test]
+fn describe_changed_bools() {
+    let start = status(false, false, false, false);
+    let end = status(true, false, true, false);
+
+    let delta = start.create_delta_towards(&end).delta;
+    assert_eq!(
+        DeltaPrinter::print(&start, &delta).lines(),
+        &["visible: false -> true", "stunned: false -> true"]
+    );
+
+    let start = Movement::Falling(true, false);
+    let delta = start
+        .create_delta_towards(&Movement::Falling(false, false))
+        .delta;
+    assert_eq!(
+        DeltaPrinter::print(&start, &delta).lines(),
+        &["Falling.0: true -> false"]
+    );
+
+    json_patch(start, Movement::Falling(false, true));
+    json_patch(Tuple(false, vec![], true), Tuple(true, vec![2], false));
+    json_patch(
+        status(false, false, false, false),
+        status(false, true, false, true),
+    );
+}
+
+fn status(visible: bool, poisoned: bool, stunned: bool, invisible: bool) -> Status {
+    Status {
+        visible,
+        health: 100,
+        poisoned,
+        stunned,
+        invisible,
+    }
+}
+
+/// Apply the delta from `start` to `end` along with its inverse, and as a borrowed delta.
+///
+/// Returns the size of the serialized delta.
+fn patch<T>(start: T, end: T) -> usize
+where
+    T: Debug + Clone + PartialEq,
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    T: for<'b> BorrowedDelta<'b>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: serde::de::DeserializeOwned,
+{
+    let delta = start.create_delta_towards(&end).delta;
+    let bytes = bincode::options()
+        .with_varint_encoding()
+        .serialize(&delta)
+        .unwrap();
+
+    let mut patched = start.clone();
+    let inverse = patched
+        .apply_patch_returning_inverse(round_trip(&delta))
+        .unwrap();
+    assert_eq!(patched, end);
+
+    patched.apply_patch(inverse).unwrap();
+    assert_eq!(patched, start);
+
+    let borrowed = bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap();
+    patched.apply_borrowed_patch(borrowed).unwrap();
+    assert_eq!(patched, end);
+
+    bytes.len()
+}
+
+fn json_patch<T>(start: T, end: T)
+where
+    T: for<'s, 'e> ToJsonPatch<'s, 'e> + Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let delta = start.create_delta_towards(&end).delta;
+    let patch = start.to_json_patch(&delta).unwrap();
+
+    let mut json = serde_json::to_value(&start).unwrap();
+    let rfc_patch: ::json_patch::Patch =
+        serde_json::from_value(serde_json::to_value(&patch).unwrap()).unwrap();
+    ::json_patch::patch(&mut json, &rfc_patch).unwrap();
+
+    assert_eq!(serde_json::from_value::<T>(json).unwrap(), end);
+}
+
+fn round_trip<D: serde::Serialize, P: serde::de::DeserializeOwned>(delta: &D) -> P {
+    let bytes = bincode::options()
+        .with_varint_encoding()
+        .serialize(delta)
+        .unwrap();
+
+    bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap()
+}
@@ -0,0 +1,99 @@
+//! Verify that the generated `Patchable` implementations return errors instead of panicking when
+//! given a patch that does not match the current state.
+
+use bincode::Options;
+use dipa::{Diffable, PatchErrorReason, Patchable, PathSegment};
+
+#[derive(Debug, DiffPatch, PartialEq)]
+struct Outer {
+    inner: Inner,
+    other: u8,
+}
+
+#[derive(Debug, DiffPatch, PartialEq)]
+struct Inner {
+    maybe: Option<u8>,
+    other: u8,
+}
+
+#[derive(Debug, DiffPatch, PartialEq, Serialize, Deserialize)]
+enum TwoVariants {
+    One(u8),
+    Two { field: u16 },
+}
+
+/// Verify that the error for a failed patch points to the field that could not be patched.
+#[test]
+fn error_contains_path_to_field() {
+    let start = Outer {
+        inner: Inner {
+            maybe: Some(1),
+            other: 2,
+        },
+        other: 3,
+    };
+    let end = Outer {
+        inner: Inner {
+            maybe: Some(4),
+            other: 2,
+        },
+        other: 3,
+    };
+
+    let mut stale = Outer {
+        inner: Inner {
+            maybe: None,
+            other: 2,
+        },
+        other: 3,
+    };
+
+    let delta = start.create_delta_towards(&end).delta;
+    let err = stale
+        .apply_patch(round_trip::<_, OuterDeltaOwned>(&delta))
+        .unwrap_err();
+
+    assert_eq!(err.reason(), &PatchErrorReason::OptionIsNone);
+    assert_eq!(
+        err.path().collect::<Vec<_>>(),
+        vec![&PathSegment::Field("inner"), &PathSegment::Field("maybe")]
+    );
+    assert_eq!(
+        err.to_string(),
+        "failed to apply patch at inner.maybe: \
+        cannot modify the inner value of an Option that is None"
+    );
+}
+
+/// Verify that patching the fields of one enum variant while the enum holds a different variant
+/// returns an error.
+#[test]
+fn variant_mismatch() {
+    let start = TwoVariants::Two { field: 1 };
+    let end = TwoVariants::Two { field: 2 };
+
+    let mut stale = TwoVariants::One(1);
+
+    let delta = start.create_delta_towards(&end).delta;
+    let err = stale
+        .apply_patch(round_trip::<_, TwoVariantsDeltaOwned>(&delta))
+        .unwrap_err();
+
+    assert_eq!(
+        err.reason(),
+        &PatchErrorReason::VariantMismatch { expected: "Two" }
+    );
+    assert_eq!(stale, TwoVariants::One(1));
+}
+
+fn round_trip<D: serde::Serialize, P: serde::de::DeserializeOwned>(delta: &D) -> P {
+    let bytes = bincode::options()
+        .with_varint_encoding()
+        .serialize(delta)
+        .unwrap();
+
+    bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap()
+}
@@ -1,6 +1,8 @@
 //! Verify that we can publicly expose types that use the derive macro.
 //! This essentially ensures that we mark the generated associated types as `pub`.
 
+#![allow(dead_code)]
+
 #[derive(DiffPatch)]
 pub struct EmptyStruct;
 
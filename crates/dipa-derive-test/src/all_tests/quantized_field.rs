@@ -0,0 +1,36 @@
+//! Verify that structs with quantized float fields send the movement for those fields.
+
+use bincode::Options;
+use dipa::{Diffable, Patchable, Quantized};
+
+#[derive(Debug, PartialEq, DiffPatch)]
+struct Player {
+    x: Quantized<f32, -1000, 1000, 21>,
+    y: Quantized<f32, -1000, 1000, 21>,
+}
+
+/// Verify that a small movement is encoded as a small difference and patches to the exact same
+/// value.
+#[test]
+fn struct_with_quantized_fields() {
+    let mut start = Player {
+        x: Quantized::new(123.456),
+        y: Quantized::new(-654.321),
+    };
+    let end = Player {
+        x: Quantized::new(123.466),
+        y: Quantized::new(-654.321),
+    };
+
+    let options = bincode::options().with_varint_encoding();
+
+    let delta = start.create_delta_towards(&end);
+    let bytes = options.serialize(&delta.delta).unwrap();
+    // 1 for the changed fields, 1 for the Option and 1 for the difference
+    assert_eq!(bytes.len(), 3);
+
+    let patch: <Player as Diffable<Player>>::DeltaOwned = options.deserialize(&bytes).unwrap();
+    start.apply_patch(patch).unwrap();
+    assert_eq!(start.x.get().to_bits(), end.x.get().to_bits());
+    assert_eq!(start, end);
+}
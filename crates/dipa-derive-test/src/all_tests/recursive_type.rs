@@ -0,0 +1,238 @@
+//! Verify that types which contain a `Box` of themselves, directly or through other types, can be
+//! diffed and patched.
+//!
+//! The generated delta types are public, so these types are public as well in order to be used
+//! within the deltas of the variants that hold them.
+
+use bincode::Options;
+use dipa::{
+    BorrowedDelta, Diffable, MergeablePatches, Patchable, ReversiblePatchable, SchemaFingerprint,
+    SquashablePatches, ToOwnedDelta,
+};
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible, squash, merge)]
+pub enum Node {
+    Leaf(u32),
+    Branch(Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkedList {
+    value: u8,
+    next: Option<Box<LinkedList>>,
+}
+
+/// A path to the type is recognized as the type.
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathList {
+    value: u8,
+    next: Option<Box<crate::all_tests::recursive_type::PathList>>,
+}
+
+pub type Child = Option<Box<AliasTree>>;
+
+/// A type alias can't be seen through, so the field needs the attribute.
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AliasTree {
+    value: u8,
+    #[dipa(recursive)]
+    child: Child,
+}
+
+/// A directory and a file that hold each other.
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Directory {
+    name: String,
+    #[dipa(recursive)]
+    file: Option<Box<File>>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+pub enum File {
+    Text(String),
+    Nested(#[dipa(recursive)] Box<Directory>),
+}
+
+/// Verify that a change deep within a tree only encodes the path to the changed leaf.
+#[test]
+fn recursive_enum() {
+    let leaf = |value| Box::new(Node::Leaf(value));
+
+    let mut start = Node::Branch(leaf(1), Box::new(Node::Branch(leaf(2), leaf(3))));
+    let end = Node::Branch(leaf(1), Box::new(Node::Branch(leaf(2), leaf(4))));
+
+    // 1 byte for the delta variant of each of the three nodes on the path to the changed leaf and
+    // 2 bytes for the leaf's new value.
+    assert_eq!(patch(&mut start, &end), 5);
+    assert_eq!(start, end);
+
+    // 1 byte for the new variant and 1 for its value.
+    let end = Node::Leaf(5);
+    assert_eq!(patch(&mut start, &end), 2);
+    assert_eq!(start, end);
+}
+
+/// Verify that we can diff and patch a recursive struct.
+#[test]
+fn recursive_struct() {
+    let mut start = LinkedList {
+        value: 1,
+        next: Some(Box::new(LinkedList {
+            value: 2,
+            next: None,
+        })),
+    };
+    let mut end = start.clone();
+    end.next.as_mut().unwrap().next = Some(Box::new(LinkedList {
+        value: 3,
+        next: None,
+    }));
+
+    // 3 bytes to reach the last node and 4 bytes for the node that was appended to it.
+    assert_eq!(patch(&mut start, &end), 7);
+    assert_eq!(start, end);
+}
+
+/// Verify that a type whose recursive field names the type through a path can be patched.
+#[test]
+fn recursive_path_field() {
+    let mut start = PathList {
+        value: 1,
+        next: None,
+    };
+    let end = PathList {
+        value: 1,
+        next: Some(Box::new(PathList {
+            value: 2,
+            next: None,
+        })),
+    };
+
+    patch(&mut start, &end);
+    assert_eq!(start, end);
+}
+
+/// Verify that a type whose recursive field is behind a type alias can be patched.
+#[test]
+fn recursive_alias_field() {
+    let leaf = |value| AliasTree { value, child: None };
+
+    let mut start = AliasTree {
+        value: 1,
+        child: Some(Box::new(leaf(2))),
+    };
+    let end = AliasTree {
+        value: 1,
+        child: Some(Box::new(AliasTree {
+            value: 2,
+            child: Some(Box::new(leaf(3))),
+        })),
+    };
+
+    patch(&mut start, &end);
+    assert_eq!(start, end);
+}
+
+/// Verify that mutually recursive types can be patched, including from a borrowed patch.
+#[test]
+fn mutually_recursive_types() {
+    let directory = |name: &str, file| Directory {
+        name: name.to_string(),
+        file: Some(Box::new(file)),
+    };
+
+    let start = directory(
+        "src",
+        File::Nested(Box::new(directory("bin", File::Text("main".to_string())))),
+    );
+    let end = directory(
+        "src",
+        File::Nested(Box::new(directory("bin", File::Text("server".to_string())))),
+    );
+
+    let mut patched = start.clone();
+    patch(&mut patched, &end);
+    assert_eq!(patched, end);
+
+    let bytes = bincode::serialize(&start.create_delta_towards(&end).delta).unwrap();
+    let mut patched = start.clone();
+    let borrowed: <Directory as BorrowedDelta>::DeltaBorrowed =
+        bincode::deserialize(&bytes).unwrap();
+    patched.apply_borrowed_patch(borrowed).unwrap();
+    assert_eq!(patched, end);
+
+    assert_ne!(
+        <Directory as SchemaFingerprint>::FINGERPRINT,
+        <File as SchemaFingerprint>::FINGERPRINT
+    );
+}
+
+/// Verify that patches for a recursive type can be reversed, squashed, merged and applied
+/// while borrowing from the serialized bytes.
+#[test]
+fn recursive_patch_operations() {
+    let leaf = |value| Box::new(Node::Leaf(value));
+
+    let base = Node::Branch(leaf(1), leaf(2));
+    let left = Node::Branch(leaf(3), leaf(2));
+    let both = Node::Branch(leaf(3), leaf(4));
+
+    let right = Node::Branch(leaf(1), leaf(4));
+
+    let mut patched = base.clone();
+    let to_left = base.create_delta_towards(&left).delta.to_owned_delta();
+    let inverse = patched.apply_patch_returning_inverse(to_left).unwrap();
+    assert_eq!(patched, left);
+    patched.apply_patch(inverse).unwrap();
+    assert_eq!(patched, base);
+
+    let to_left = base.create_delta_towards(&left).delta.to_owned_delta();
+    let to_right = base.create_delta_towards(&right).delta.to_owned_delta();
+    let merged = Node::merge_patches(&base, to_left, to_right).unwrap();
+    let mut patched = base.clone();
+    patched.apply_patch(merged).unwrap();
+    assert_eq!(patched, both);
+
+    let to_left = base.create_delta_towards(&left).delta.to_owned_delta();
+    let left_to_both = left.create_delta_towards(&both).delta.to_owned_delta();
+    let squashed = Node::squash_patches(to_left, left_to_both).unwrap();
+    let mut patched = base.clone();
+    patched.apply_patch(squashed).unwrap();
+    assert_eq!(patched, both);
+
+    let bytes = bincode::serialize(&base.create_delta_towards(&both).delta).unwrap();
+    let mut patched = base.clone();
+    let borrowed: <Node as BorrowedDelta>::DeltaBorrowed = bincode::deserialize(&bytes).unwrap();
+    patched.apply_borrowed_patch(borrowed).unwrap();
+    assert_eq!(patched, both);
+}
+
+/// Verify that recursive types have a fingerprint.
+#[test]
+fn recursive_fingerprint() {
+    assert_ne!(
+        <Node as SchemaFingerprint>::FINGERPRINT,
+        <LinkedList as SchemaFingerprint>::FINGERPRINT
+    );
+}
+
+/// Patch `start` into `end` and return the size of the serialized delta.
+fn patch<T>(start: &mut T, end: &T) -> usize
+where
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: serde::de::DeserializeOwned,
+{
+    let options = bincode::options().with_varint_encoding();
+
+    let bytes = options
+        .serialize(&start.create_delta_towards(end).delta)
+        .unwrap();
+    start
+        .apply_patch(options.deserialize(&bytes).unwrap())
+        .unwrap();
+
+    bytes.len()
+}
@@ -0,0 +1,50 @@
+//! Verify that structs that store the outcome of a job in a `Result` can be delta encoded.
+
+use bincode::Options;
+use dipa::{Diffable, Patchable};
+
+#[derive(Debug, Clone, PartialEq, DiffPatch)]
+struct Job {
+    id: u32,
+    outcome: Result<Vec<u8>, String>,
+}
+
+/// Verify that an unchanged job costs one byte and that changes to the outcome can be applied.
+#[test]
+fn struct_with_result_field() {
+    let start = Job {
+        id: 1,
+        outcome: Ok(vec![1, 2]),
+    };
+    assert_eq!(patch(&start, &start.clone()), 1);
+
+    for end in [
+        Job {
+            id: 1,
+            outcome: Ok(vec![1, 2, 3]),
+        },
+        Job {
+            id: 2,
+            outcome: Err("timed out".to_string()),
+        },
+    ] {
+        patch(&start, &end);
+    }
+}
+
+/// Patch a copy of `start` with the serialized delta towards `end`, returning the size of the
+/// delta.
+fn patch(start: &Job, end: &Job) -> usize {
+    let options = bincode::options().with_varint_encoding();
+
+    let bytes = options
+        .serialize(&start.create_delta_towards(end).delta)
+        .unwrap();
+
+    let patch: <Job as Diffable<Job>>::DeltaOwned = options.deserialize(&bytes).unwrap();
+    let mut patched = start.clone();
+    patched.apply_patch(patch).unwrap();
+    assert_eq!(&patched, end);
+
+    bytes.len()
+}
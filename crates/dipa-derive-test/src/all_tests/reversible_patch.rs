@@ -0,0 +1,150 @@
+//! Verify that the generated `ReversiblePatchable` implementations return patches that undo and
+//! then redo the original change.
+
+use bincode::Options;
+use dipa::{Diffable, ReversiblePatchable};
+use std::fmt::Debug;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(reversible)]
+struct Empty;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(reversible)]
+struct OneField {
+    field: Vec<u8>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(reversible)]
+struct TwoFields {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(reversible, field_batching_strategy = "no_batching")]
+struct TwoFieldsNoBatching {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(reversible)]
+enum OneVariantTwoFields {
+    One(Vec<u8>, u32),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(reversible)]
+enum NoData {
+    One,
+    Two,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(reversible)]
+enum WithData {
+    One,
+    Two(Vec<u8>),
+    Three { list: Vec<u8>, maybe: Option<u16> },
+}
+
+/// Verify that we can undo and redo patches to structs.
+#[test]
+fn structs() {
+    undo_redo(Empty, Empty);
+
+    undo_redo(
+        OneField { field: vec![1, 2] },
+        OneField {
+            field: vec![1, 2, 3],
+        },
+    );
+
+    undo_redo(
+        TwoFields {
+            list: vec![1, 2],
+            maybe: None,
+        },
+        TwoFields {
+            list: vec![2, 3, 4],
+            maybe: Some(5),
+        },
+    );
+
+    undo_redo(
+        TwoFieldsNoBatching {
+            list: vec![1, 2],
+            maybe: Some(5),
+        },
+        TwoFieldsNoBatching {
+            list: vec![1, 2],
+            maybe: None,
+        },
+    );
+}
+
+/// Verify that we can undo and redo patches to enums, including changes between variants.
+#[test]
+fn enums() {
+    undo_redo(
+        OneVariantTwoFields::One(vec![1], 2),
+        OneVariantTwoFields::One(vec![], 3),
+    );
+
+    undo_redo(NoData::One, NoData::Two);
+
+    undo_redo(WithData::One, WithData::Two(vec![1, 2]));
+    undo_redo(
+        WithData::Two(vec![1, 2]),
+        WithData::Three {
+            list: vec![3],
+            maybe: Some(4),
+        },
+    );
+    undo_redo(
+        WithData::Three {
+            list: vec![3],
+            maybe: Some(4),
+        },
+        WithData::Three {
+            list: vec![3, 5],
+            maybe: None,
+        },
+    );
+    undo_redo(WithData::Two(vec![1, 2]), WithData::One);
+}
+
+/// Patch `start` into `end`, then use the returned inverse to get back to `start` and the inverse
+/// of the inverse to get back to `end`.
+fn undo_redo<T>(start: T, end: T)
+where
+    T: Debug + Clone + PartialEq,
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: serde::de::DeserializeOwned,
+{
+    let delta = start.create_delta_towards(&end).delta;
+
+    let bytes = bincode::options()
+        .with_varint_encoding()
+        .serialize(&delta)
+        .unwrap();
+    let patch = bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap();
+
+    let mut patched = start.clone();
+
+    let undo = patched.apply_patch_returning_inverse(patch).unwrap();
+    assert_eq!(patched, end);
+
+    let redo = patched.apply_patch_returning_inverse(undo).unwrap();
+    assert_eq!(patched, start);
+
+    patched.apply_patch(redo).unwrap();
+    assert_eq!(patched, end);
+}
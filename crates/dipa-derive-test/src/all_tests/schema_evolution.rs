@@ -0,0 +1,282 @@
+//! Verify that fields added with `#[dipa(since = ...)]` keep deltas compatible between schema
+//! versions.
+
+use dipa::{ChangeFlagged, Diffable, Patchable, SchemaFingerprint};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+mod v1 {
+    #[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Player {
+        pub health: u8,
+        pub name: String,
+    }
+}
+
+mod v2 {
+    #[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Player {
+        pub health: u8,
+        pub name: String,
+        #[dipa(since = 2)]
+        pub mana: u8,
+    }
+}
+
+mod v3 {
+    #[derive(Debug, DiffPatch, Clone, PartialEq)]
+    pub struct Player {
+        pub health: u8,
+        pub name: String,
+        #[dipa(since = 2)]
+        pub mana: u8,
+        #[dipa(since = 3)]
+        pub level: u16,
+    }
+}
+
+/// Verify that a receiver on an earlier version can decode and apply deltas that were created for
+/// its version.
+#[test]
+fn older_receiver_decodes_delta_for_its_version() {
+    let start = v2::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana: 1,
+    };
+    let end = v2::Player {
+        health: 8,
+        name: "ab".to_string(),
+        mana: 2,
+    };
+
+    let delta = start.create_delta_towards_version(&end, 1);
+    assert!(delta.did_change);
+    let patch: <v1::Player as Diffable<v1::Player>>::DeltaOwned = reserialize(&delta.delta);
+
+    let mut receiver = v1::Player {
+        health: 10,
+        name: "a".to_string(),
+    };
+    receiver.apply_patch(patch).unwrap();
+
+    assert_eq!(
+        receiver,
+        v1::Player {
+            health: 8,
+            name: "ab".to_string()
+        }
+    );
+}
+
+/// Verify that a change to only newer fields is not a change at all for an earlier version.
+#[test]
+fn newer_fields_are_left_out_of_older_versions() {
+    let start = v3::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana: 1,
+        level: 1,
+    };
+    let end = v3::Player {
+        mana: 2,
+        level: 2,
+        ..start.clone()
+    };
+
+    let delta = start.create_delta_towards_version(&end, 1);
+    assert!(!delta.did_change);
+
+    let delta = start.create_delta_towards_version(&end, 2);
+    assert!(delta.did_change);
+
+    let patch: <v2::Player as Diffable<v2::Player>>::DeltaOwned = reserialize(&delta.delta);
+    let mut receiver = v2::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana: 1,
+    };
+    receiver.apply_patch(patch).unwrap();
+    assert_eq!(receiver.mana, 2);
+}
+
+/// Verify that a receiver on a later version can decode and apply deltas from an earlier version,
+/// leaving its newer fields untouched.
+#[test]
+fn newer_receiver_decodes_older_delta() {
+    let start = v1::Player {
+        health: 10,
+        name: "a".to_string(),
+    };
+    let end = v1::Player {
+        health: 8,
+        name: "ab".to_string(),
+    };
+
+    let delta = start.create_delta_towards(&end).delta;
+    let patch: <v3::Player as Diffable<v3::Player>>::DeltaOwned = reserialize(&delta);
+
+    let mut receiver = v3::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana: 5,
+        level: 7,
+    };
+    receiver.apply_patch(patch).unwrap();
+
+    assert_eq!(
+        receiver,
+        v3::Player {
+            health: 8,
+            name: "ab".to_string(),
+            mana: 5,
+            level: 7,
+        }
+    );
+}
+
+/// Verify that every field is diffed when no schema version was targeted.
+#[test]
+fn latest_version_by_default() {
+    let mut start = v3::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana: 1,
+        level: 1,
+    };
+    let end = v3::Player {
+        health: 8,
+        name: "ab".to_string(),
+        mana: 2,
+        level: 3,
+    };
+
+    let delta = start.create_delta_towards(&end).delta;
+    let patch: <v3::Player as Diffable<v3::Player>>::DeltaOwned = reserialize(&delta);
+    start.apply_patch(patch).unwrap();
+
+    assert_eq!(start, end);
+}
+
+/// Verify that every version of a struct has its own fingerprint, and that moving a field to a
+/// different schema version changes it.
+#[test]
+fn versions_have_their_own_fingerprint() {
+    let fingerprints = [
+        <v1::Player as SchemaFingerprint>::FINGERPRINT,
+        <v2::Player as SchemaFingerprint>::FINGERPRINT,
+        <v3::Player as SchemaFingerprint>::FINGERPRINT,
+        <v3_mana_since_3::Player as SchemaFingerprint>::FINGERPRINT,
+    ];
+
+    for (idx, fingerprint) in fingerprints.iter().enumerate() {
+        assert!(!fingerprints[idx + 1..].contains(fingerprint));
+    }
+}
+
+mod v3_mana_since_3 {
+    #[derive(Debug, DiffPatch, Clone, PartialEq)]
+    pub struct Player {
+        pub health: u8,
+        pub name: String,
+        #[dipa(since = 3)]
+        pub mana: u8,
+        #[dipa(since = 3)]
+        pub level: u16,
+    }
+}
+
+/// Verify that a derived type inside of an `Option` or a `Box` is diffed for the targeted version.
+#[test]
+fn version_reaches_option_and_box() {
+    let start = Some(Box::new(v2::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana: 1,
+    }));
+    let end = Some(Box::new(v2::Player {
+        mana: 2,
+        ..(**start.as_ref().unwrap()).clone()
+    }));
+
+    assert!(!start.create_delta_towards_version(&end, 1).did_change);
+    assert!(start.create_delta_towards_version(&end, 2).did_change);
+}
+
+/// Verify that a derived type inside of a container such as a map, tuple, array or `Result` is
+/// diffed for the targeted version.
+#[test]
+fn version_reaches_containers() {
+    let start = v2::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana: 1,
+    };
+    let end = v2::Player {
+        mana: 2,
+        ..start.clone()
+    };
+
+    fn assert_versioned<T: for<'s, 'e> Diffable<'s, 'e, T>>(start: T, end: T) {
+        assert!(!start.create_delta_towards_version(&end, 1).did_change);
+        assert!(start.create_delta_towards_version(&end, 2).did_change);
+        assert!(start.create_delta_towards(&end).did_change);
+    }
+
+    let map = |player: &v2::Player| vec![(1u8, player.clone())];
+    assert_versioned::<HashMap<_, _>>(
+        map(&start).into_iter().collect(),
+        map(&end).into_iter().collect(),
+    );
+    assert_versioned::<BTreeMap<_, _>>(
+        map(&start).into_iter().collect(),
+        map(&end).into_iter().collect(),
+    );
+    assert_versioned((1u8, start.clone()), (1u8, end.clone()));
+    assert_versioned([start.clone(), start.clone()], [start.clone(), end.clone()]);
+    assert_versioned::<Result<_, ()>>(Ok(start.clone()), Ok(end.clone()));
+    assert_versioned(RefCell::new(start.clone()), RefCell::new(end.clone()));
+
+    let mut flagged = ChangeFlagged::new(start.clone());
+    let baseline = flagged.clone();
+    *flagged = end.clone();
+    assert_versioned(baseline, flagged);
+}
+
+/// Verify that a receiver on an earlier version can apply the delta of a map of derived types that
+/// was created for its version.
+#[test]
+fn older_receiver_decodes_map_delta_for_its_version() {
+    let player = |mana| v2::Player {
+        health: 10,
+        name: "a".to_string(),
+        mana,
+    };
+    let start: HashMap<u8, v2::Player> = vec![(1, player(1)), (2, player(1))].into_iter().collect();
+    let mut end = start.clone();
+    end.get_mut(&1).unwrap().health = 8;
+    end.get_mut(&2).unwrap().mana = 2;
+
+    let delta = start.create_delta_towards_version(&end, 1);
+    let patch: <HashMap<u8, v1::Player> as Diffable<HashMap<u8, v1::Player>>>::DeltaOwned =
+        reserialize(&delta.delta);
+
+    let v1_player = |health| v1::Player {
+        health,
+        name: "a".to_string(),
+    };
+    let mut receiver: HashMap<u8, v1::Player> = vec![(1, v1_player(10)), (2, v1_player(10))]
+        .into_iter()
+        .collect();
+    receiver.apply_patch(patch).unwrap();
+
+    assert_eq!(receiver[&1], v1_player(8));
+    assert_eq!(receiver[&2], v1_player(10));
+}
+
+/// Serialize a delta and deserialize it as a different version's owned delta.
+fn reserialize<D: Serialize, P: DeserializeOwned>(delta: &D) -> P {
+    bincode::deserialize(&bincode::serialize(delta).unwrap()).unwrap()
+}
@@ -0,0 +1,157 @@
+//! Verify that the generated schema fingerprints change whenever the delta encoding changes.
+
+use dipa::{Diffable, Patchable, SchemaFingerprint};
+
+mod v1 {
+    #[derive(Debug, DiffPatch, Clone, PartialEq)]
+    pub struct Position {
+        pub x: u16,
+        pub y: u16,
+    }
+
+    #[derive(Debug, DiffPatch, Clone, PartialEq)]
+    pub struct Player {
+        pub position: Position,
+        pub name: String,
+    }
+
+    #[derive(Debug, DiffPatch, Clone, PartialEq)]
+    pub enum State {
+        Idle,
+        Running(u8),
+    }
+}
+
+mod renamed_field {
+    #[derive(DiffPatch)]
+    pub struct Position {
+        pub x: u16,
+        pub z: u16,
+    }
+}
+
+mod changed_field_type {
+    #[derive(DiffPatch)]
+    pub struct Position {
+        pub x: u16,
+        pub y: u32,
+    }
+}
+
+mod reordered_fields {
+    #[derive(DiffPatch)]
+    pub struct Position {
+        pub y: u16,
+        pub x: u16,
+    }
+}
+
+mod no_batching {
+    #[derive(DiffPatch)]
+    #[dipa(field_batching_strategy = "no_batching")]
+    pub struct Position {
+        pub x: u16,
+        pub y: u16,
+    }
+}
+
+mod nested_change {
+    use super::changed_field_type::Position;
+
+    #[derive(DiffPatch)]
+    pub struct Player {
+        pub position: Position,
+        pub name: String,
+    }
+}
+
+mod reordered_variants {
+    #[derive(DiffPatch)]
+    pub enum State {
+        Running(u8),
+        Idle,
+    }
+}
+
+mod changed_variant_field {
+    #[derive(DiffPatch)]
+    pub enum State {
+        Idle,
+        Running(u16),
+    }
+}
+
+/// Verify that every change to a type's delta encoding changes its fingerprint.
+#[test]
+fn encoding_changes_change_fingerprint() {
+    let position = <v1::Position as SchemaFingerprint>::FINGERPRINT;
+
+    assert_ne!(
+        position,
+        <renamed_field::Position as SchemaFingerprint>::FINGERPRINT
+    );
+    assert_ne!(
+        position,
+        <changed_field_type::Position as SchemaFingerprint>::FINGERPRINT
+    );
+    assert_ne!(
+        position,
+        <reordered_fields::Position as SchemaFingerprint>::FINGERPRINT
+    );
+    assert_ne!(
+        position,
+        <no_batching::Position as SchemaFingerprint>::FINGERPRINT
+    );
+
+    let state = <v1::State as SchemaFingerprint>::FINGERPRINT;
+    assert_ne!(
+        state,
+        <reordered_variants::State as SchemaFingerprint>::FINGERPRINT
+    );
+    assert_ne!(
+        state,
+        <changed_variant_field::State as SchemaFingerprint>::FINGERPRINT
+    );
+}
+
+/// Verify that changing a type also changes the fingerprint of the types that contain it.
+#[test]
+fn fingerprints_are_recursive() {
+    assert_ne!(
+        <v1::Player as SchemaFingerprint>::FINGERPRINT,
+        <nested_change::Player as SchemaFingerprint>::FINGERPRINT
+    );
+
+    assert_ne!(
+        <Option<v1::Position> as SchemaFingerprint>::FINGERPRINT,
+        <Option<changed_field_type::Position> as SchemaFingerprint>::FINGERPRINT
+    );
+}
+
+/// Verify that a delta prefixed with its fingerprint can be verified and applied by a receiver
+/// using the same type, and is rejected by a receiver using a different version of the type.
+#[test]
+fn prefix_and_verify_serialized_delta() {
+    let mut start = v1::Player {
+        position: v1::Position { x: 1, y: 2 },
+        name: "a".to_string(),
+    };
+    let end = v1::Player {
+        position: v1::Position { x: 1, y: 3 },
+        name: "ab".to_string(),
+    };
+
+    let delta = start.create_delta_towards(&end).delta;
+    let serialized = v1::Player::prefix_fingerprint(&bincode::serialize(&delta).unwrap());
+
+    let mismatch = nested_change::Player::strip_fingerprint(&serialized).unwrap_err();
+    assert_eq!(mismatch.expected(), nested_change::Player::FINGERPRINT);
+    assert_eq!(mismatch.received(), Some(v1::Player::FINGERPRINT));
+
+    let received = v1::Player::strip_fingerprint(&serialized).unwrap();
+    let patch: <v1::Player as Diffable<v1::Player>>::DeltaOwned =
+        bincode::deserialize(received).unwrap();
+    start.apply_patch(patch).unwrap();
+
+    assert_eq!(start, end);
+}
@@ -0,0 +1,51 @@
+//! Verify that structs with shared pointer and interior mutability fields can be diffed and
+//! patched.
+
+use bincode::Options;
+use dipa::{Diffable, Patchable};
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, DiffPatch)]
+struct World {
+    config: Arc<Vec<u8>>,
+    tick: RefCell<u32>,
+    players: Mutex<Vec<u16>>,
+}
+
+impl World {
+    fn new() -> Self {
+        World {
+            config: Arc::new(vec![1, 2, 3]),
+            tick: RefCell::new(0),
+            players: Mutex::new(vec![]),
+        }
+    }
+}
+
+/// Verify that a shared value that was not replaced is not encoded, and that the cells are
+/// patched through their inner values.
+#[test]
+fn struct_with_shared_and_cell_fields() {
+    let mut start = World::new();
+    let end = World {
+        config: start.config.clone(),
+        tick: RefCell::new(1),
+        players: Mutex::new(vec![7]),
+    };
+
+    let options = bincode::options().with_varint_encoding();
+
+    let delta = start.create_delta_towards(&end);
+    assert!(delta.did_change);
+    let bytes = options.serialize(&delta.delta).unwrap();
+
+    let patch: <World as Diffable<World>>::DeltaOwned = options.deserialize(&bytes).unwrap();
+    start.apply_patch(patch).unwrap();
+
+    assert!(Arc::ptr_eq(&start.config, &end.config));
+    assert_eq!(*start.tick.borrow(), 1);
+    assert_eq!(*start.players.lock().unwrap(), vec![7]);
+
+    assert!(!start.create_delta_towards(&end).did_change);
+}
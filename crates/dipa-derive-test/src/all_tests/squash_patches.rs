@@ -0,0 +1,251 @@
+//! Verify that the generated `SquashablePatches` implementations combine two patches into one
+//! patch that has the same effect as applying both of them.
+
+use bincode::Options;
+use dipa::{Diffable, PatchErrorReason, PathSegment, SquashablePatches};
+use std::fmt::Debug;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash)]
+struct Empty;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash)]
+struct OneField {
+    field: Vec<u8>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash)]
+struct TwoFields {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash, field_batching_strategy = "no_batching")]
+struct TwoFieldsNoBatching {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash)]
+struct Nested {
+    inner: TwoFields,
+    other: u32,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash)]
+enum OneVariantOneField {
+    One { field: Vec<u8> },
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash)]
+enum OneVariantTwoFields {
+    One(Vec<u8>, u32),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+#[dipa(squash)]
+enum NoData {
+    One,
+    Two,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(squash)]
+enum WithData {
+    One,
+    Two(Vec<u8>),
+    Three { list: Vec<u8>, maybe: Option<u16> },
+}
+
+/// Verify that we can squash patches to structs.
+#[test]
+fn structs() {
+    squash(Empty, Empty, Empty);
+
+    squash(
+        OneField { field: vec![1] },
+        OneField { field: vec![1, 2] },
+        OneField {
+            field: vec![1, 2, 3],
+        },
+    );
+
+    squash(
+        TwoFields {
+            list: vec![1],
+            maybe: None,
+        },
+        TwoFields {
+            list: vec![1, 2],
+            maybe: None,
+        },
+        TwoFields {
+            list: vec![1, 2],
+            maybe: Some(3),
+        },
+    );
+
+    squash(
+        TwoFieldsNoBatching {
+            list: vec![1],
+            maybe: Some(2),
+        },
+        TwoFieldsNoBatching {
+            list: vec![1, 5],
+            maybe: Some(3),
+        },
+        TwoFieldsNoBatching {
+            list: vec![1, 5, 6],
+            maybe: None,
+        },
+    );
+
+    squash(
+        Nested {
+            inner: TwoFields {
+                list: vec![],
+                maybe: None,
+            },
+            other: 1,
+        },
+        Nested {
+            inner: TwoFields {
+                list: vec![],
+                maybe: Some(2),
+            },
+            other: 1,
+        },
+        Nested {
+            inner: TwoFields {
+                list: vec![3],
+                maybe: Some(2),
+            },
+            other: 4,
+        },
+    );
+}
+
+/// Verify that we can squash patches to enums, including changes between variants.
+#[test]
+fn enums() {
+    squash(
+        OneVariantOneField::One { field: vec![1] },
+        OneVariantOneField::One { field: vec![] },
+        OneVariantOneField::One { field: vec![2, 3] },
+    );
+
+    squash(
+        OneVariantTwoFields::One(vec![1], 2),
+        OneVariantTwoFields::One(vec![1], 3),
+        OneVariantTwoFields::One(vec![1, 4], 3),
+    );
+
+    squash(NoData::One, NoData::Two, NoData::One);
+
+    squash(
+        WithData::One,
+        WithData::Two(vec![1]),
+        WithData::Two(vec![1, 2]),
+    );
+    squash(
+        WithData::Two(vec![1]),
+        WithData::Two(vec![1, 2]),
+        WithData::Three {
+            list: vec![3],
+            maybe: None,
+        },
+    );
+    squash(
+        WithData::Three {
+            list: vec![3],
+            maybe: Some(4),
+        },
+        WithData::Three {
+            list: vec![3, 5],
+            maybe: Some(4),
+        },
+        WithData::Three {
+            list: vec![3, 5],
+            maybe: None,
+        },
+    );
+    squash(
+        WithData::Three {
+            list: vec![],
+            maybe: None,
+        },
+        WithData::One,
+        WithData::One,
+    );
+}
+
+/// Verify that squashing a patch for one variant with a patch for another variant returns an
+/// error.
+#[test]
+fn different_variants_error() {
+    let first = round_trip(
+        &WithData::Two(vec![1])
+            .create_delta_towards(&WithData::Two(vec![1, 2]))
+            .delta,
+    );
+    let second = round_trip(
+        &WithData::Three {
+            list: vec![],
+            maybe: None,
+        }
+        .create_delta_towards(&WithData::Three {
+            list: vec![1],
+            maybe: None,
+        })
+        .delta,
+    );
+
+    let err = WithData::squash_patches(first, second)
+        .map(|_| ())
+        .unwrap_err();
+
+    assert_eq!(
+        err.reason(),
+        &PatchErrorReason::VariantMismatch { expected: "Two" }
+    );
+    assert_eq!(err.path().collect::<Vec<_>>(), Vec::<&PathSegment>::new());
+}
+
+/// Squash the patch from `start` to `middle` with the patch from `middle` to `end` and verify that
+/// applying the squashed patch to `start` gives us `end`.
+fn squash<T>(start: T, middle: T, end: T)
+where
+    T: Debug + Clone + PartialEq,
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> SquashablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: serde::Serialize,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: serde::de::DeserializeOwned,
+{
+    let first = round_trip(&start.create_delta_towards(&middle).delta);
+    let second = round_trip(&middle.create_delta_towards(&end).delta);
+
+    let squashed = T::squash_patches(first, second).unwrap();
+
+    let mut patched = start.clone();
+    patched.apply_patch(squashed).unwrap();
+
+    assert_eq!(patched, end);
+}
+
+fn round_trip<D: serde::Serialize, P: serde::de::DeserializeOwned>(delta: &D) -> P {
+    let bytes = bincode::options()
+        .with_varint_encoding()
+        .serialize(delta)
+        .unwrap();
+
+    bincode::options()
+        .with_varint_encoding()
+        .deserialize(&bytes)
+        .unwrap()
+}
@@ -0,0 +1,66 @@
+//! Verify that structs that hold times, ranges, paths, addresses and non-zero integers from the
+//! standard library can be delta encoded.
+
+use bincode::Options;
+use dipa::{Diffable, Patchable};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroU32;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, DiffPatch)]
+struct Download {
+    id: NonZeroU32,
+    from: SocketAddr,
+    to: PathBuf,
+    bytes: Range<u64>,
+    elapsed: Duration,
+}
+
+/// Verify that an unchanged download costs one byte and that changes to any of its fields can be
+/// applied.
+#[test]
+fn struct_with_std_misc_fields() {
+    let start = Download {
+        id: NonZeroU32::new(1).unwrap(),
+        from: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080),
+        to: PathBuf::from("/tmp/file.part"),
+        bytes: 0..1024,
+        elapsed: Duration::from_millis(250),
+    };
+    assert_eq!(patch(&start, &start.clone()), 1);
+
+    let progressed = Download {
+        bytes: 0..2048,
+        elapsed: Duration::from_millis(260),
+        ..start.clone()
+    };
+    patch(&start, &progressed);
+
+    let restarted = Download {
+        id: NonZeroU32::new(2).unwrap(),
+        from: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 443),
+        to: PathBuf::from("/home/user/file"),
+        bytes: 1024..4096,
+        elapsed: Duration::from_secs(0),
+    };
+    patch(&start, &restarted);
+}
+
+/// Patch a copy of `start` with the serialized delta towards `end`, returning the size of the
+/// delta.
+fn patch(start: &Download, end: &Download) -> usize {
+    let options = bincode::options().with_varint_encoding();
+
+    let bytes = options
+        .serialize(&start.create_delta_towards(end).delta)
+        .unwrap();
+
+    let patch: <Download as Diffable<Download>>::DeltaOwned = options.deserialize(&bytes).unwrap();
+    let mut patched = start.clone();
+    patched.apply_patch(patch).unwrap();
+    assert_eq!(&patched, end);
+
+    bytes.len()
+}
@@ -40,12 +40,14 @@ struct ThreeFields {
 
 #[derive(Debug, DiffPatch, Eq, PartialEq, Serialize)]
 #[dipa(diff_derives = "Debug, PartialEq")]
+#[allow(dead_code)]
 struct ThreeFieldsTuple(u8, u16, u32);
 
 //
 
 #[derive(Debug, DiffPatch, Eq, PartialEq, Serialize)]
 #[dipa(diff_derives = "Debug, PartialEq")]
+#[allow(dead_code)]
 struct FourFields {
     field1: u8,
     field2: u16,
@@ -55,12 +57,14 @@ struct FourFields {
 
 #[derive(Debug, DiffPatch, Eq, PartialEq, Serialize)]
 #[dipa(diff_derives = "Debug, PartialEq")]
+#[allow(dead_code)]
 struct FourFieldsTuple(u8, u16, u32, u64);
 
 //
 
 #[derive(Debug, DiffPatch, Eq, PartialEq, Serialize)]
 #[dipa(diff_derives = "Debug, PartialEq")]
+#[allow(dead_code)]
 struct FiveFields {
     field1: u8,
     field2: u16,
@@ -71,6 +75,7 @@ struct FiveFields {
 
 #[derive(Debug, DiffPatch, Eq, PartialEq, Serialize)]
 #[dipa(diff_derives = "Debug, PartialEq")]
+#[allow(dead_code)]
 struct FiveFieldsTuple(u8, u16, u32, u64, u128);
 
 /// Verify that we can generate a diff/patch for structs with one field.
@@ -0,0 +1,39 @@
+//! Verify that structs with text fields send edits that are measured in the text's unit.
+
+use bincode::Options;
+use dipa::{BorrowedDelta, Diffable, Patchable, Text, Utf16CodeUnits};
+
+#[derive(Debug, Clone, PartialEq, DiffPatch)]
+struct ChatMessage {
+    author: String,
+    body: Text<Utf16CodeUnits>,
+}
+
+/// Verify that a text field can be patched from both the owned and the borrowed delta.
+#[test]
+fn struct_with_text_field() {
+    let start = ChatMessage {
+        author: "crab 🦀".to_string(),
+        body: Text::from("🦀 hello"),
+    };
+    let end = ChatMessage {
+        author: "crab 🦞".to_string(),
+        body: Text::from("🦀 hello!"),
+    };
+
+    let options = bincode::options().with_varint_encoding();
+
+    let delta = start.create_delta_towards(&end);
+    let bytes = options.serialize(&delta.delta).unwrap();
+
+    let patch: <ChatMessage as Diffable<ChatMessage>>::DeltaOwned =
+        options.deserialize(&bytes).unwrap();
+    let mut patched = start.clone();
+    patched.apply_patch(patch).unwrap();
+    assert_eq!(patched, end);
+
+    let patch: <ChatMessage as BorrowedDelta>::DeltaBorrowed = options.deserialize(&bytes).unwrap();
+    let mut patched = start.clone();
+    patched.apply_borrowed_patch(patch).unwrap();
+    assert_eq!(patched, end);
+}
@@ -0,0 +1,187 @@
+//! Verify that the generated delta types can be converted into their owned delta types without
+//! serializing them.
+
+use dipa::{Diffable, Patchable, ToOwnedDelta};
+use std::fmt::Debug;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Empty;
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct OneField {
+    field: Vec<u8>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct TwoFields {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+#[dipa(field_batching_strategy = "no_batching")]
+struct TwoFieldsNoBatching {
+    list: Vec<u8>,
+    maybe: Option<u16>,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+struct Nested {
+    inner: TwoFields,
+    other: (u32, String),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+enum OneVariantTwoFields {
+    One(Vec<u8>, u32),
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq, Serialize, Deserialize)]
+enum NoData {
+    One,
+    Two,
+}
+
+#[derive(Debug, DiffPatch, Clone, PartialEq)]
+enum WithData {
+    One,
+    Two(Vec<u8>),
+    Three { list: Vec<u8>, maybe: Option<u16> },
+}
+
+/// A field type that does not implement `Clone`, so its delta can't be converted into an owned
+/// delta. It only implements `Diffable` and `Patchable`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(super) struct NotClone(u8);
+
+/// Verify that types with fields that can't be converted into owned deltas still compile.
+#[allow(dead_code)]
+#[derive(DiffPatch)]
+struct HasNotCloneField {
+    list: Vec<NotClone>,
+    other: u8,
+}
+
+#[allow(dead_code)]
+#[derive(DiffPatch)]
+enum HasNotCloneVariant {
+    One(NotClone),
+    Two,
+}
+
+impl<'s, 'e> Diffable<'s, 'e, NotClone> for NotClone {
+    type Delta = &'e NotClone;
+    type DeltaOwned = NotClone;
+
+    fn create_delta_towards(&self, end_state: &'e NotClone) -> dipa::CreatedDelta<&'e NotClone> {
+        dipa::CreatedDelta {
+            delta: end_state,
+            did_change: self != end_state,
+        }
+    }
+}
+
+impl Patchable<NotClone> for NotClone {
+    fn apply_patch(&mut self, patch: NotClone) -> Result<(), dipa::PatchError> {
+        *self = patch;
+        Ok(())
+    }
+}
+
+/// Verify that we can convert the deltas of structs into owned deltas.
+#[test]
+fn structs() {
+    to_owned(Empty, Empty);
+
+    to_owned(OneField { field: vec![1] }, OneField { field: vec![1, 2] });
+
+    to_owned(
+        TwoFields {
+            list: vec![1],
+            maybe: None,
+        },
+        TwoFields {
+            list: vec![1, 2],
+            maybe: Some(3),
+        },
+    );
+
+    to_owned(
+        TwoFieldsNoBatching {
+            list: vec![1],
+            maybe: Some(2),
+        },
+        TwoFieldsNoBatching {
+            list: vec![1],
+            maybe: None,
+        },
+    );
+
+    to_owned(
+        Nested {
+            inner: TwoFields {
+                list: vec![],
+                maybe: None,
+            },
+            other: (1, "a".to_string()),
+        },
+        Nested {
+            inner: TwoFields {
+                list: vec![3],
+                maybe: Some(2),
+            },
+            other: (1, "ab".to_string()),
+        },
+    );
+}
+
+/// Verify that we can convert the deltas of enums into owned deltas, including changes between
+/// variants.
+#[test]
+fn enums() {
+    to_owned(
+        OneVariantTwoFields::One(vec![1], 2),
+        OneVariantTwoFields::One(vec![1, 4], 3),
+    );
+
+    to_owned(NoData::One, NoData::Two);
+
+    to_owned(WithData::One, WithData::Two(vec![1]));
+    to_owned(WithData::Two(vec![1]), WithData::Two(vec![1, 2]));
+    to_owned(
+        WithData::Two(vec![1]),
+        WithData::Three {
+            list: vec![3],
+            maybe: None,
+        },
+    );
+    to_owned(
+        WithData::Three {
+            list: vec![3],
+            maybe: Some(4),
+        },
+        WithData::Three {
+            list: vec![3, 5],
+            maybe: None,
+        },
+    );
+    to_owned(WithData::Two(vec![]), WithData::One);
+}
+
+/// Convert the delta from `start` to `end` into an owned delta and verify that applying it to
+/// `start` gives us `end`.
+fn to_owned<T>(start: T, end: T)
+where
+    T: Debug + Clone + PartialEq,
+    T: for<'s, 'e> Diffable<'s, 'e, T>,
+    T: for<'s, 'e> Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta:
+        ToOwnedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    let patch = start.create_delta_towards(&end).delta.to_owned_delta();
+
+    let mut patched = start.clone();
+    patched.apply_patch(patch).unwrap();
+
+    assert_eq!(patched, end);
+}
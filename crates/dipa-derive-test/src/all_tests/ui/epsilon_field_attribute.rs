@@ -0,0 +1,33 @@
+use dipa_derive::DiffPatch;
+
+#[derive(DiffPatch)]
+#[dipa(field_batching_strategy = "no_batching")]
+struct NoBatching {
+    sleeping: bool,
+    #[dipa(epsilon = 0.01)]
+    position: f32,
+}
+
+#[derive(DiffPatch)]
+struct OneField {
+    #[dipa(epsilon = 0.01)]
+    position: f32,
+}
+
+#[derive(DiffPatch)]
+enum EnumField {
+    Idle,
+    Running {
+        #[dipa(epsilon = 0.01)]
+        speed: f32,
+    },
+}
+
+#[derive(DiffPatch)]
+struct ZeroEpsilon {
+    sleeping: bool,
+    #[dipa(epsilon = 0.0)]
+    position: f32,
+}
+
+fn main() {}
@@ -0,0 +1,48 @@
+use dipa_derive::DiffPatch;
+
+#[derive(DiffPatch)]
+struct OutOfOrder {
+    #[dipa(since = 2)]
+    mana: u8,
+    health: u8,
+}
+
+#[derive(DiffPatch)]
+#[dipa(field_batching_strategy = "no_batching")]
+struct NoBatching {
+    health: u8,
+    #[dipa(since = 2)]
+    mana: u8,
+}
+
+#[derive(DiffPatch)]
+struct OneField {
+    #[dipa(since = 2)]
+    mana: u8,
+}
+
+#[derive(DiffPatch)]
+enum EnumField {
+    Idle,
+    Running {
+        #[dipa(since = 2)]
+        speed: u8,
+    },
+}
+
+#[derive(DiffPatch)]
+struct ZeroVersion {
+    health: u8,
+    #[dipa(since = 0)]
+    mana: u8,
+}
+
+#[derive(DiffPatch)]
+struct NoFirstVersionFields {
+    #[dipa(since = 2)]
+    health: u8,
+    #[dipa(since = 2)]
+    mana: u8,
+}
+
+fn main() {}
@@ -1,9 +1,11 @@
 use syn::parse::{Parse, ParseStream, Result as SynResult};
-use syn::{Attribute, Ident, Lit};
+use syn::{Attribute, Ident, Lit, LitBool};
 
+pub use self::field_attributes::*;
 pub use self::field_batching_strategy::*;
 use crate::dipa_attribute::generated_delta_type_derives::parse_derives;
 
+mod field_attributes;
 mod field_batching_strategy;
 mod generated_delta_type_derives;
 mod max_fields_per_batch;
@@ -22,6 +24,21 @@ pub struct DipaAttrs {
     pub patch_derives: Vec<Ident>,
     pub max_fields_per_batch: Option<u8>,
     pub field_batching_strategy: Option<FieldBatchingStrategy>,
+    /// `None` unless the attribute was used, in which case bools are packed by default.
+    pub pack_bools: Option<bool>,
+    /// Whether to implement `dipa::ReversiblePatchable` for the type.
+    pub reversible: bool,
+    /// Whether to implement `dipa::SquashablePatches` for the type.
+    pub squash: bool,
+    /// Whether to implement `dipa::MergeablePatches` for the type.
+    pub merge: bool,
+}
+
+impl DipaAttrs {
+    /// Whether the bool fields of the struct or of each enum variant get packed into one field.
+    pub fn pack_bools(&self) -> bool {
+        self.pack_bools.unwrap_or(true)
+    }
 }
 
 impl Parse for DipaAttrs {
@@ -54,6 +71,18 @@ impl Parse for DipaAttrs {
                 DipaContainerAttr::FieldBatchingStrategy(f) => {
                     dipa_attrs.field_batching_strategy = Some(f);
                 }
+                DipaContainerAttr::PackBools(pack_bools) => {
+                    dipa_attrs.pack_bools = Some(pack_bools);
+                }
+                DipaContainerAttr::Reversible(reversible) => {
+                    dipa_attrs.reversible = reversible;
+                }
+                DipaContainerAttr::Squash(squash) => {
+                    dipa_attrs.squash = squash;
+                }
+                DipaContainerAttr::Merge(merge) => {
+                    dipa_attrs.merge = merge;
+                }
             };
         }
 
@@ -81,6 +110,24 @@ pub enum DipaContainerAttr {
     MaxDeltaBatch(u8),
     /// Controls how fields with a struct are batched when generating the delta type.
     FieldBatchingStrategy(FieldBatchingStrategy),
+    /// Whether to pack the bool fields of the struct or of each enum variant into one field, so
+    /// that all of their changes fit in a single `dipa::BoolFlags` delta. Bools are packed by
+    /// default.
+    ///
+    /// example: `dipa(pack_bools = false)`
+    PackBools(bool),
+    /// Implement `dipa::ReversiblePatchable` for the type. Every field type must implement it.
+    ///
+    /// example: `dipa(reversible)`
+    Reversible(bool),
+    /// Implement `dipa::SquashablePatches` for the type. Every field type must implement it.
+    ///
+    /// example: `dipa(squash)`
+    Squash(bool),
+    /// Implement `dipa::MergeablePatches` for the type. Every field type must implement it.
+    ///
+    /// example: `dipa(merge)`
+    Merge(bool),
 }
 
 impl Parse for DipaContainerAttr {
@@ -88,6 +135,27 @@ impl Parse for DipaContainerAttr {
         let original = input.fork();
 
         let key = input.parse::<Ident>()?;
+
+        // pack_bools = false
+        if key == "pack_bools" {
+            return Ok(DipaContainerAttr::PackBools(parse_flag(input)?));
+        }
+
+        // reversible
+        if key == "reversible" {
+            return Ok(DipaContainerAttr::Reversible(parse_flag(input)?));
+        }
+
+        // squash
+        if key == "squash" {
+            return Ok(DipaContainerAttr::Squash(parse_flag(input)?));
+        }
+
+        // merge
+        if key == "merge" {
+            return Ok(DipaContainerAttr::Merge(parse_flag(input)?));
+        }
+
         let _equals = input.parse::<Token![=]>()?;
 
         // diff_derives = "Debug, Copy"
@@ -106,14 +174,26 @@ impl Parse for DipaContainerAttr {
 
         // max_fields_per_batch = 6
         if key == "max_fields_per_batch" {
-            return Self::parse_max_fields_per_batch(&input);
+            return Self::parse_max_fields_per_batch(input);
         }
 
         // field_batching_strategy = "no_batching"
         if key == "field_batching_strategy" {
-            return Self::parse_field_batching_strategy(&input);
+            return Self::parse_field_batching_strategy(input);
         }
 
         Err(original.error("unknown attribute"))
     }
 }
+
+/// Parses a flag that is either bare, `flag`, or set explicitly, `flag = false`.
+fn parse_flag(input: ParseStream) -> SynResult<bool> {
+    if input.is_empty() || input.peek(Token![,]) {
+        return Ok(true);
+    }
+
+    let _equals = input.parse::<Token![=]>()?;
+    let value = input.parse::<LitBool>()?;
+
+    Ok(value.value)
+}
@@ -0,0 +1,222 @@
+use crate::dipa_attribute::maybe_parse_raw_dipa_attribute;
+use syn::__private::{Span, TokenStream2};
+use syn::parse::{Parse, ParseStream, Result as SynResult};
+use syn::{Attribute, Error as SynError, Ident, Lit, LitInt};
+
+/// A parsed representation of the #[dipa(...)] field attribute.
+#[derive(Debug, Default, Clone)]
+pub struct DipaFieldAttrs {
+    /// The schema version that the field was added in, or `None` if the field has been around
+    /// since the first version of the type.
+    pub since: Option<u32>,
+    /// How far a float field can move before it counts as changed, or `None` if any change to
+    /// the field counts.
+    pub epsilon: Option<f64>,
+    /// Whether the field's type holds the type that is being derived in a way that can't be seen
+    /// from the type's path, such as through a type alias or another type that holds it.
+    pub recursive: bool,
+}
+
+impl DipaFieldAttrs {
+    /// Parse the #[dipa(...)] attribute on a field, if there is one.
+    pub fn from_field_attrs(attrs: &[Attribute]) -> SynResult<Self> {
+        match maybe_parse_raw_dipa_attribute(attrs.to_vec()) {
+            Some(attrib) => syn::parse2(attrib.tokens),
+            None => Ok(DipaFieldAttrs::default()),
+        }
+    }
+
+    /// Fields without a `since` attribute are a part of every schema version.
+    pub fn schema_version(&self) -> u32 {
+        self.since.unwrap_or(0)
+    }
+}
+
+impl Parse for DipaFieldAttrs {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let mut field_attrs = DipaFieldAttrs::default();
+
+        if input.is_empty() {
+            return Ok(field_attrs);
+        }
+
+        let content;
+        parenthesized!(content in input);
+
+        let opts =
+            syn::punctuated::Punctuated::<DipaFieldAttr, syn::token::Comma>::parse_terminated(
+                &content,
+            )?;
+
+        for field_attr in opts.into_iter() {
+            match field_attr {
+                DipaFieldAttr::Since(since) => {
+                    field_attrs.since = Some(since);
+                }
+                DipaFieldAttr::Epsilon(epsilon) => {
+                    field_attrs.epsilon = Some(epsilon);
+                }
+                DipaFieldAttr::Recursive => {
+                    field_attrs.recursive = true;
+                }
+            };
+        }
+
+        Ok(field_attrs)
+    }
+}
+
+/// All of the supported attributes within the #[dipa(...)] field attribute.
+#[derive(Debug)]
+pub enum DipaFieldAttr {
+    /// The schema version that the field was added in.
+    ///
+    /// Deltas created with `dipa::Diffable::create_delta_towards_version` for an earlier version
+    /// never include the field, and deltas from earlier versions can still be decoded.
+    ///
+    /// example: `dipa(since = 2)`
+    Since(u32),
+    /// How far a float field can move away from the start state before the field counts as
+    /// changed. Uses `dipa::WithinEpsilon`, so floats and arrays or tuples of floats are
+    /// supported.
+    ///
+    /// example: `dipa(epsilon = 0.001)`
+    Epsilon(f64),
+    /// The field's type holds the type that is being derived, so the field's type is left out of
+    /// the bounds of the generated impls. Fields whose type mentions the derived type's name,
+    /// such as `Box<Node>`, are treated as recursive without the attribute.
+    ///
+    /// example: `dipa(recursive)`
+    Recursive,
+}
+
+impl Parse for DipaFieldAttr {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let original = input.fork();
+
+        let key = input.parse::<Ident>()?;
+
+        // recursive
+        if key == "recursive" {
+            return Ok(DipaFieldAttr::Recursive);
+        }
+
+        let _equals = input.parse::<Token![=]>()?;
+
+        // since = 2
+        if key == "since" {
+            let version = input.parse::<LitInt>()?;
+            let since: u32 = version.base10_parse()?;
+
+            // dipa-derive-test/src/all_tests/ui/since_field_attribute.rs
+            if since == 0 {
+                return Err(SynError::new(
+                    version.span(),
+                    r#"The since attribute must be greater than 0, since fields without it are
+already a part of every schema version."#,
+                ));
+            }
+
+            return Ok(DipaFieldAttr::Since(since));
+        }
+
+        // epsilon = 0.001
+        if key == "epsilon" {
+            let lit = input.parse::<Lit>()?;
+            let epsilon: f64 = match &lit {
+                Lit::Float(float) => float.base10_parse()?,
+                Lit::Int(int) => int.base10_parse()?,
+                _ => return Err(SynError::new(lit.span(), "expected a number")),
+            };
+
+            // dipa-derive-test/src/all_tests/ui/epsilon_field_attribute.rs
+            if epsilon <= 0. {
+                return Err(SynError::new(
+                    lit.span(),
+                    r#"The epsilon attribute must be greater than 0, since fields without it
+already count any change."#,
+                ));
+            }
+
+            return Ok(DipaFieldAttr::Epsilon(epsilon));
+        }
+
+        Err(original.error("unknown field attribute"))
+    }
+}
+
+/// A compile time error for a misused `#[dipa(since = ...)]` or `#[dipa(epsilon = ...)]` field
+/// attribute.
+pub fn field_attribute_error(span: Span, error: &str) -> TokenStream2 {
+    quote_spanned! {span=>
+        compile_error!(#error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that we parse the since attribute on a field.
+    #[test]
+    fn parse_since() {
+        let fields: syn::FieldsNamed = syn::parse_quote! {{
+            #[dipa(since = 3)]
+            some_field: u8
+        }};
+
+        let attrs = DipaFieldAttrs::from_field_attrs(&fields.named[0].attrs).unwrap();
+        assert_eq!(attrs.since, Some(3));
+        assert_eq!(attrs.schema_version(), 3);
+    }
+
+    /// Verify that we parse the epsilon attribute on a field, along with the since attribute.
+    #[test]
+    fn parse_epsilon() {
+        let fields: syn::FieldsNamed = syn::parse_quote! {{
+            #[dipa(epsilon = 0.25, since = 2)]
+            some_field: f32,
+            #[dipa(epsilon = 1)]
+            another_field: f32
+        }};
+
+        let attrs = DipaFieldAttrs::from_field_attrs(&fields.named[0].attrs).unwrap();
+        assert_eq!(attrs.epsilon, Some(0.25));
+        assert_eq!(attrs.since, Some(2));
+
+        let attrs = DipaFieldAttrs::from_field_attrs(&fields.named[1].attrs).unwrap();
+        assert_eq!(attrs.epsilon, Some(1.));
+    }
+
+    /// Verify that we parse the recursive attribute, which does not take a value.
+    #[test]
+    fn parse_recursive() {
+        let fields: syn::FieldsNamed = syn::parse_quote! {{
+            #[dipa(recursive)]
+            some_field: Children,
+            #[dipa(since = 2, recursive)]
+            another_field: Children
+        }};
+
+        let attrs = DipaFieldAttrs::from_field_attrs(&fields.named[0].attrs).unwrap();
+        assert!(attrs.recursive);
+
+        let attrs = DipaFieldAttrs::from_field_attrs(&fields.named[1].attrs).unwrap();
+        assert!(attrs.recursive);
+        assert_eq!(attrs.since, Some(2));
+    }
+
+    /// Verify that fields without a dipa attribute are a part of every schema version.
+    #[test]
+    fn no_field_attribute() {
+        let fields: syn::FieldsNamed = syn::parse_quote! {{
+            some_field: u8
+        }};
+
+        let attrs = DipaFieldAttrs::from_field_attrs(&fields.named[0].attrs).unwrap();
+        assert_eq!(attrs.since, None);
+        assert_eq!(attrs.epsilon, None);
+        assert!(!attrs.recursive);
+        assert_eq!(attrs.schema_version(), 0);
+    }
+}
@@ -2,10 +2,10 @@ use crate::dipa_attribute::DipaContainerAttr;
 use crate::{SynError, SynResult};
 use quote::__private::Span;
 use std::str::FromStr;
-use syn::parse::ParseBuffer;
-use syn::LitStr;
 use syn::__private::TokenStream2;
+use syn::parse::ParseBuffer;
 use syn::spanned::Spanned;
+use syn::LitStr;
 
 /// The strategy used to encode the delta of a struct or enum variant that has 2 or more fields.
 ///
@@ -21,9 +21,12 @@ use syn::spanned::Spanned;
 ///  batch. We may also want per group batching strategies. Perhaps the FieldBatchTrait impl
 ///  specifies the field batching strategy. Then the container level attribute controls how all
 ///  of those field level batches get batched into the final delta type.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
 pub enum FieldBatchingStrategy {
     /// Use a single enum to encode the delta.
+    // We use OneBatch as the default since it allows for the smallest possible diffs.
+    // But this does not mean that it is always the best. For example,
+    #[default]
     OneBatch,
     /// Use a struct that has one enum for every `max_fields_per_batch` fields in the original
     /// struct.
@@ -40,12 +43,11 @@ impl DipaContainerAttr {
         let strategy = FieldBatchingStrategy::from_str(&strategy.value()).map_err(|_provided| {
             SynError::new(
                 strategy.span(),
-                format!(
-                    r#"delta_strategy must equal "one_batch", "many_batches" or "no_batching".
+                r#"delta_strategy must equal "one_batch", "many_batches" or "no_batching".
 
 See: https://chinedufn/github.io/dipa/using-derive/attributes/index.html#container-attributes
 "#
-                ),
+                .to_string(),
             )
         })?;
 
@@ -59,10 +61,10 @@ impl FieldBatchingStrategy {
     pub fn validate_field_count(field_count: usize, fields_span: Span) -> Result<(), TokenStream2> {
         if field_count < 2 {
             let fields_span = fields_span.span();
-            let error = format!(
+            let error =
                 r#"A struct or enum variant must have at least two fields for field delta batching
 to be useful. Try removing the field_batching_strategy attribute."#
-            );
+                    .to_string();
             let error = quote_spanned! {fields_span=>
                 compile_error!(#error);
             };
@@ -73,6 +75,17 @@ to be useful. Try removing the field_batching_strategy attribute."#
     }
 }
 
+impl FieldBatchingStrategy {
+    /// The name that is used for this strategy in the `field_batching_strategy` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OneBatch => "one_batch",
+            Self::ManyBatches => "many_batches",
+            Self::NoBatching => "no_batching",
+        }
+    }
+}
+
 impl FromStr for FieldBatchingStrategy {
     type Err = String;
 
@@ -85,11 +98,3 @@ impl FromStr for FieldBatchingStrategy {
         }
     }
 }
-
-impl Default for FieldBatchingStrategy {
-    // We use OneBatch as the default since it allows for the smallest possible diffs.
-    // But this does not mean that it is always the best. For example,
-    fn default() -> Self {
-        FieldBatchingStrategy::OneBatch
-    }
-}
@@ -57,28 +57,38 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
 
     let enum_or_struct_name = input.ident;
 
-    let zero_sized_diff = create_zst_impl(&enum_or_struct_name);
+    let zero_sized_diff = create_zst_impl(&dipa_attrs, &enum_or_struct_name);
 
     // Generate:
     // impl<'p, Other> Diffable<'s, 'e, Other> for MyType { ... }
     // impl Patchable<Patch> for MyType { ... }
+    // impl ReversiblePatchable<Patch> for MyType { ... } // #[dipa(reversible)]
+    // impl SquashablePatches<Patch> for MyType { ... } // #[dipa(squash)]
+    // impl MergeablePatches<Patch> for MyType { ... } // #[dipa(merge)]
     let dipa_impl = match input.data {
         Data::Struct(struct_data) => {
             let fields = match &struct_data.fields {
-                Fields::Named(named_fields) => ParsedFields {
-                    fields: fields_named_to_vec_fields(named_fields),
-                    span: named_fields.span(),
-                },
-                Fields::Unnamed(unnamed_fields) => ParsedFields {
-                    fields: fields_unnamed_to_vec_fields(unnamed_fields),
-                    span: unnamed_fields.span(),
-                },
-                Fields::Unit => ParsedFields {
+                Fields::Named(named_fields) => {
+                    fields_named_to_vec_fields(named_fields).map(|fields| ParsedFields {
+                        fields,
+                        span: named_fields.span(),
+                    })
+                }
+                Fields::Unnamed(unnamed_fields) => fields_unnamed_to_vec_fields(unnamed_fields)
+                    .map(|fields| ParsedFields {
+                        fields,
+                        span: unnamed_fields.span(),
+                    }),
+                Fields::Unit => Ok(ParsedFields {
                     fields: vec![],
                     span: enum_or_struct_name.span(),
-                },
+                }),
             };
-            let parsed_struct = ParsedStruct {
+            let fields = match fields {
+                Ok(fields) => fields,
+                Err(err) => return err.to_compile_error().into(),
+            };
+            let mut parsed_struct = ParsedStruct {
                 // FIXME: Remove clone once we move the logic below into generate_dipa_impl()
                 name: enum_or_struct_name.clone(),
                 fields,
@@ -87,18 +97,28 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
             if let Err(err) = parsed_struct.validate_struct_container_attributes(&dipa_attrs) {
                 return err.into();
             }
+            if let Err(err) = parsed_struct.validate_struct_field_attributes(&dipa_attrs) {
+                return err.into();
+            }
+
+            if dipa_attrs.pack_bools() {
+                if let Err(err) = parsed_struct.fields.pack_bool_fields() {
+                    return err.into();
+                }
+            }
 
             // TODO: Move this logic into ParsedStruct.generate_dipa_impl()
             let struct_dipa_impl = match struct_data.fields {
                 // struct Foo { field_a: type1, field_b: type2, ... }
                 Fields::Named(fields) => {
-                    if fields.named.len() == 0 {
+                    if fields.named.is_empty() {
                         zero_sized_diff
                     } else if fields.named.len() == 1 {
                         let field = &fields.named[0];
                         let field_name = field.ident.as_ref().unwrap();
 
                         generate_single_field_struct_impl(
+                            &dipa_attrs,
                             &enum_or_struct_name,
                             quote_spanned! {field.span() => #field_name},
                             &field.ty,
@@ -109,10 +129,11 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
                 }
                 // struct Foo(type1, type2);
                 Fields::Unnamed(fields) => {
-                    if fields.unnamed.len() == 0 {
+                    if fields.unnamed.is_empty() {
                         zero_sized_diff
                     } else if fields.unnamed.len() == 1 {
                         generate_single_field_struct_impl(
+                            &dipa_attrs,
                             &enum_or_struct_name,
                             quote_spanned! {fields.unnamed[0].span() => 0},
                             &fields.unnamed[0].ty,
@@ -134,35 +155,51 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
                 .map(|v| {
                     let fields = match &v.fields {
                         Fields::Named(named_fields) => EnumVariantFields::Struct(ParsedFields {
-                            fields: fields_named_to_vec_fields(named_fields),
+                            fields: fields_named_to_vec_fields(named_fields)?,
                             span: named_fields.span(),
                         }),
                         Fields::Unnamed(unnamed_fields) => EnumVariantFields::Tuple(ParsedFields {
-                            fields: fields_unnamed_to_vec_fields(unnamed_fields),
+                            fields: fields_unnamed_to_vec_fields(unnamed_fields)?,
                             span: unnamed_fields.span(),
                         }),
                         Fields::Unit => EnumVariantFields::Unit,
                     };
 
-                    EnumVariant {
+                    Ok(EnumVariant {
                         name: v.ident.clone(),
                         fields,
-                    }
+                    })
                 })
-                .collect();
-            let parsed_enum = ParsedEnum {
+                .collect::<SynResult<Vec<EnumVariant>>>();
+            let variants = match variants {
+                Ok(variants) => variants,
+                Err(err) => return err.to_compile_error().into(),
+            };
+            let mut parsed_enum = ParsedEnum {
                 name: enum_or_struct_name.clone(),
                 variants,
             };
 
-            if enum_data.variants.len() == 0 {
+            if let Err(err) = parsed_enum.validate_enum_field_attributes() {
+                return err.into();
+            }
+
+            if dipa_attrs.pack_bools() {
+                for variant in parsed_enum.variants.iter_mut() {
+                    if let Err(err) = variant.fields.pack_bool_fields() {
+                        return err.into();
+                    }
+                }
+            }
+
+            if enum_data.variants.is_empty() {
                 zero_sized_diff
             } else if enum_data.variants.len() == 1 {
                 let variant = &enum_data.variants[0];
 
                 let fields = &variant.fields;
 
-                if fields.len() == 0 {
+                if fields.is_empty() {
                     zero_sized_diff
                 } else {
                     match &fields {
@@ -172,6 +209,7 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
                                 let field_name = field.ident.as_ref().unwrap();
 
                                 generate_single_variant_enum_single_struct_field_impl(
+                                    &dipa_attrs,
                                     enum_or_struct_name,
                                     &variant.ident,
                                     quote_spanned! {field.span() => #field_name},
@@ -187,6 +225,7 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
                                 let field = &fields.unnamed[0];
 
                                 generate_single_variant_enum_single_tuple_field_impl(
+                                    &dipa_attrs,
                                     enum_or_struct_name,
                                     &variant.ident,
                                     &field.ty,
@@ -204,7 +243,7 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
             } else {
                 generate_multi_variant_enum_impl(
                     enum_or_struct_name,
-                    enum_data.variants,
+                    parsed_enum.variants,
                     dipa_attrs,
                 )
             }
@@ -219,13 +258,66 @@ pub fn derive_diff_patch(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn impl_dipa(
+    dipa_attrs: &DipaAttrs,
     enum_or_struct_name: &syn::Ident,
     delta_type: TokenStream2,
     delta_owned_type: TokenStream2,
     create_delta_inner: TokenStream2,
     apply_patch_inner: TokenStream2,
+    reverse_patch_inner: TokenStream2,
+    squash_patches_inner: TokenStream2,
+    merge_patches_inner: TokenStream2,
 ) -> TokenStream2 {
+    // The reversible, squash and merge impls call the same trait on every field, so they are
+    // only generated when asked for. Otherwise field types that only implement Diffable and
+    // Patchable could not be used.
+    let reversible_impl = if dipa_attrs.reversible {
+        quote! {
+         impl<'s, 'e> dipa::ReversiblePatchable<#delta_owned_type> for #enum_or_struct_name {
+            fn apply_patch_returning_inverse (&mut self, patch: #delta_owned_type)
+              -> Result<#delta_owned_type, dipa::PatchError> {
+                let inverse = { #reverse_patch_inner };
+
+                Ok(inverse)
+            }
+         }
+        }
+    } else {
+        quote! {}
+    };
+
+    let squash_impl = if dipa_attrs.squash {
+        quote! {
+         impl<'s, 'e> dipa::SquashablePatches<#delta_owned_type> for #enum_or_struct_name {
+            fn squash_patches (first: #delta_owned_type, second: #delta_owned_type)
+              -> Result<#delta_owned_type, dipa::PatchError> {
+                let squashed = { #squash_patches_inner };
+
+                Ok(squashed)
+            }
+         }
+        }
+    } else {
+        quote! {}
+    };
+
+    let merge_impl = if dipa_attrs.merge {
+        quote! {
+         impl<'s, 'e> dipa::MergeablePatches<#delta_owned_type> for #enum_or_struct_name {
+            fn merge_patches (base: &Self, a: #delta_owned_type, b: #delta_owned_type)
+              -> Result<#delta_owned_type, dipa::MergeConflicts> {
+                let merged = { #merge_patches_inner };
+
+                Ok(merged)
+            }
+         }
+        }
+    } else {
+        quote! {}
+    };
+
     let tokens = quote! {
      impl<'s, 'e> dipa::Diffable<'s, 'e, #enum_or_struct_name> for #enum_or_struct_name {
         type Delta = #delta_type;
@@ -234,15 +326,32 @@ fn impl_dipa(
 
         fn create_delta_towards (&'s self, end_state: &'e #enum_or_struct_name)
           -> dipa::CreatedDelta<Self::Delta> {
+            dipa::Diffable::create_delta_towards_version(self, end_state, u32::MAX)
+        }
+
+        #[allow(unused_variables)]
+        fn create_delta_towards_version (
+            &'s self,
+            end_state: &'e #enum_or_struct_name,
+            version: u32,
+        ) -> dipa::CreatedDelta<Self::Delta> {
             #create_delta_inner
         }
      }
 
      impl<'s, 'e> dipa::Patchable<#delta_owned_type> for #enum_or_struct_name {
-        fn apply_patch (&mut self, patch: #delta_owned_type) {
+        fn apply_patch (&mut self, patch: #delta_owned_type) -> Result<(), dipa::PatchError> {
             #apply_patch_inner
+
+            Ok(())
         }
      }
+
+     #reversible_impl
+
+     #squash_impl
+
+     #merge_impl
     };
 
     tokens
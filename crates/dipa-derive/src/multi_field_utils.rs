@@ -4,33 +4,53 @@
 use quote::__private::TokenStream;
 use syn::__private::{Span, TokenStream2};
 use syn::spanned::Spanned;
-use syn::{FieldsNamed, FieldsUnnamed, Ident, Type};
+use syn::{FieldsNamed, FieldsUnnamed, Ident, Result as SynResult, Type};
 
+pub use self::borrowed_field_deltas::*;
 pub use self::field_changes::*;
+pub use self::json_patch_field_deltas::*;
+pub use self::merge_field_patches::*;
+pub use self::packed_bool_fields::*;
+pub use self::recursive_fields::*;
+pub use self::schema_fingerprint::*;
+pub use self::squash_field_patches::*;
 pub use self::struct_or_tuple_field::*;
-use crate::dipa_attribute::DipaAttrs;
+pub use self::to_owned_field_deltas::*;
+pub use self::visit_field_deltas::*;
+use crate::dipa_attribute::{DipaAttrs, DipaFieldAttrs};
 use crate::multi_field_utils::make_bool_combinations;
 
+mod borrowed_field_deltas;
 mod field_changes;
+mod json_patch_field_deltas;
+mod merge_field_patches;
+mod packed_bool_fields;
+mod recursive_fields;
+mod schema_fingerprint;
+mod squash_field_patches;
 mod struct_or_tuple_field;
+mod to_owned_field_deltas;
+mod visit_field_deltas;
 
-pub fn fields_named_to_vec_fields(fields: &FieldsNamed) -> Vec<StructOrTupleField> {
+pub fn fields_named_to_vec_fields(fields: &FieldsNamed) -> SynResult<Vec<StructOrTupleField>> {
     fields
         .named
         .iter()
         .map(|f| {
             let field_name = f.ident.as_ref().unwrap();
 
-            StructOrTupleField {
+            Ok(StructOrTupleField {
                 name: quote! {#field_name},
                 ty: f.ty.clone(),
                 span: f.span(),
-            }
+                attrs: DipaFieldAttrs::from_field_attrs(&f.attrs)?,
+                packed_bools: vec![],
+            })
         })
         .collect()
 }
 
-pub fn fields_unnamed_to_vec_fields(fields: &FieldsUnnamed) -> Vec<StructOrTupleField> {
+pub fn fields_unnamed_to_vec_fields(fields: &FieldsUnnamed) -> SynResult<Vec<StructOrTupleField>> {
     #[rustfmt::skip]
     let tuple_field_names = [
         quote! {0}, quote! {1}, quote! {2}, quote! {3},
@@ -46,11 +66,13 @@ pub fn fields_unnamed_to_vec_fields(fields: &FieldsUnnamed) -> Vec<StructOrTuple
         .map(|(idx, f)| {
             let field_name = &tuple_field_names[idx];
 
-            StructOrTupleField {
+            Ok(StructOrTupleField {
                 name: quote_spanned! {f.span() => #field_name},
                 ty: f.ty.clone(),
                 span: f.span(),
-            }
+                attrs: DipaFieldAttrs::from_field_attrs(&f.attrs)?,
+                packed_bools: vec![],
+            })
         })
         .collect()
 }
@@ -63,8 +85,8 @@ pub fn fields_unnamed_to_vec_fields(fields: &FieldsUnnamed) -> Vec<StructOrTuple
 // # use quote::quote;
 //
 // // Not included. Just here to illustrate.
-// // let diff0 = self.some_field_name.create_delta_towards(&end_state.some_field_name);
-// // let diff1 = self.another_field_name.create_delta_towards(&end_state.another_field_name);
+// // let diff0 = self.some_field_name.create_delta_towards_version(&end_state.some_field_name, version);
+// // let diff1 = self.another_field_name.create_delta_towards_version(&end_state.another_field_name, version);
 // // End not included.
 //
 // quote! {
@@ -90,7 +112,7 @@ pub fn make_match_diff_tokens(
     let bool_combinations = make_bool_combinations(fields.len(), dipa_attrs.max_fields_per_batch);
 
     let match_diff_inner_tokens =
-        make_match_diff_inner_tokens(diff_ty, change_prefix, span, &bool_combinations, &fields);
+        make_match_diff_inner_tokens(diff_ty, change_prefix, span, &bool_combinations, fields);
 
     // (diff0.1.did_change, diff1.1.did_change)
     let mut did_change_tokens = vec![];
@@ -119,14 +141,14 @@ pub fn make_match_diff_tokens(
 // match patch {
 //     #diff_n::NoChange => {}
 //     #diff_n::Change_0(field0_patch) => {
-//         field0_mut_ref.apply_patch(field0_patch);
+//         field0_mut_ref.apply_patch(field0_patch).map_err(...)?;
 //     }
 //     #diff_n::Change_1(field1_patch) => {
-//         field1_mut_ref.apply_patch(field1_patch);
+//         field1_mut_ref.apply_patch(field1_patch).map_err(...)?;
 //     }
 //     #diff_n::Change_0_1(field0_patch, field1_patch) => {
-//         field0_mut_ref.apply_patch(field0_patch);
-//         field1_mut_ref.apply_patch(field1_patch);
+//         field0_mut_ref.apply_patch(field0_patch).map_err(...)?;
+//         field1_mut_ref.apply_patch(field1_patch).map_err(...)?;
 //     }
 // };
 // ```
@@ -135,11 +157,13 @@ pub fn make_match_patch_tokens(
     diff_ty: &Type,
     fields: &[StructOrTupleField],
     field_mut_refs: Vec<TokenStream2>,
+    patch_kind: PatchKind,
     dipa_attrs: &DipaAttrs,
 ) -> TokenStream2 {
     let bool_combinations = make_bool_combinations(fields.len(), dipa_attrs.max_fields_per_batch);
 
-    let match_patch_inner_tokens = make_match_patch_inner_tokens(diff_ty, span, &bool_combinations);
+    let match_patch_inner_tokens =
+        make_match_patch_inner_tokens(diff_ty, span, &bool_combinations, fields, patch_kind);
 
     quote! {
       #(#field_mut_refs)*
@@ -151,6 +175,60 @@ pub fn make_match_patch_tokens(
     }
 }
 
+// Generate the tokens to match on the different possible Diff's, apply the appropriate patches to
+// the struct's sub fields and return the patch that undoes them.
+//
+// ```
+// let field0_mut_ref = &mut self.some_field_name;
+// let field1_mut_ref = &mut self.another_field_name;
+//
+// match patch {
+//     #diff_n::NoChange => #diff_n::NoChange,
+//     #diff_n::Change_0(field0_patch) => #diff_n::Change_0(
+//         field0_mut_ref.apply_patch_returning_inverse(field0_patch).map_err(...)?,
+//     ),
+//     #diff_n::Change_0_1(field0_patch, field1_patch) => #diff_n::Change_0_1(
+//         field0_mut_ref.apply_patch_returning_inverse(field0_patch).map_err(...)?,
+//         field1_mut_ref.apply_patch_returning_inverse(field1_patch).map_err(...)?,
+//     ),
+// }
+// ```
+pub fn make_match_reverse_patch_tokens(
+    span: Span,
+    diff_ty: &Type,
+    fields: &[StructOrTupleField],
+    field_mut_refs: Vec<TokenStream2>,
+    dipa_attrs: &DipaAttrs,
+) -> TokenStream2 {
+    let bool_combinations = make_bool_combinations(fields.len(), dipa_attrs.max_fields_per_batch);
+
+    let mut match_reverse_patch_inner_tokens = vec![];
+
+    // The first combination is the one where no fields changed.
+    for bools in bool_combinations.iter().skip(1) {
+        let changed_keys = get_change_variant_name(bools, span);
+
+        let incoming_fields = patched_field_patch_names(span, bools);
+
+        let inverse_expressions = get_reverse_patch_expressions(span, bools, fields);
+
+        match_reverse_patch_inner_tokens.push(quote! {
+          #diff_ty::#changed_keys(#(#incoming_fields),*) => {
+              #diff_ty::#changed_keys(#(#inverse_expressions),*)
+          }
+        });
+    }
+
+    quote! {
+      #(#field_mut_refs)*
+
+      match patch {
+         #diff_ty::NoChange => #diff_ty::NoChange,
+         #(#match_reverse_patch_inner_tokens)*
+      }
+    }
+}
+
 /// Generate the inside of a match statement that returns a diff based on which fields have changed.
 ///
 /// (false, false, false) => Diff3::NoChange,
@@ -219,28 +297,30 @@ fn make_match_diff_inner_tokens(
 /// Generate the inside of a match statement that applies a patch based on the diff.
 ///
 /// Diff2::Change_0_1(field0_patch, field1_patch) => {
-///     field0_mut_ref.apply_patch(field0_patch);
-///     field1_mut_ref.apply_patch(field1_patch);
+///     field0_mut_ref.apply_patch(field0_patch).map_err(...)?;
+///     field1_mut_ref.apply_patch(field1_patch).map_err(...)?;
 /// }
 fn make_match_patch_inner_tokens(
     diff_ty: &Type,
     span: Span,
     bool_combinations: &[Vec<bool>],
+    fields: &[StructOrTupleField],
+    patch_kind: PatchKind,
 ) -> Vec<TokenStream> {
     let mut match_patch_inner_tokens = vec![];
     let mut all_tuple_entries_are_false = true;
 
     for bools in bool_combinations {
-        let changed_keys = get_change_variant_name(&bools, span);
+        let changed_keys = get_change_variant_name(bools, span);
 
-        let incoming_fields = patched_field_patch_names(span, &bools);
+        let incoming_fields = patched_field_patch_names(span, bools);
 
-        let patch_expressions = get_patch_expressions(span, &bools);
+        let patch_expressions = get_patch_expressions(span, bools, fields, patch_kind);
 
         if !all_tuple_entries_are_false {
             //     Diff2::Change_0_1(field0_patch, field1_patch) => {
-            //         field0_mut_ref.apply_patch(field0_patch);
-            //         field1_mut_ref.apply_patch(field1_patch);
+            //         field0_mut_ref.apply_patch(field0_patch).map_err(...)?;
+            //         field1_mut_ref.apply_patch(field1_patch).map_err(...)?;
             //     }
             let match_patch_branch = quote! {
               #diff_ty::#changed_keys(#(#incoming_fields),*) => {
@@ -269,7 +349,7 @@ fn get_change_variant_name(bools: &[bool], span: Span) -> Ident {
         changed_keys += &format!("_{}", idx);
     }
 
-    Ident::new(&format!("{}", changed_keys), span)
+    Ident::new(&changed_keys.to_string(), span)
 }
 
 /// The names of each of the new patches that we need to apply.
@@ -291,10 +371,18 @@ fn patched_field_patch_names(span: Span, bools: &[bool]) -> Vec<Ident> {
     incoming_fields
 }
 
-/// field0_mut_ref.apply_patch(field0_patch);
-/// field2_mut_ref.apply_patch(field2_patch);
-/// field5_mut_ref.apply_patch(field5_patch);
-fn get_patch_expressions(span: Span, bools: &[bool]) -> Vec<TokenStream2> {
+/// field0_mut_ref
+///     .apply_patch(field0_patch)
+///     .map_err(|err| err.with_parent(dipa::PathSegment::Field("some_field_name")))?;
+/// field2_mut_ref
+///     .apply_patch(field2_patch)
+///     .map_err(|err| err.with_parent(dipa::PathSegment::Field("another_field_name")))?;
+fn get_patch_expressions(
+    span: Span,
+    bools: &[bool],
+    fields: &[StructOrTupleField],
+    patch_kind: PatchKind,
+) -> Vec<TokenStream2> {
     let mut patch_expressions = vec![];
 
     for (idx, _bool) in bools
@@ -305,8 +393,43 @@ fn get_patch_expressions(span: Span, bools: &[bool]) -> Vec<TokenStream2> {
         let patch_ident = Ident::new(&format!("field{}_patch", idx), span);
 
         let field_mut_ref_ident = Ident::new(&format!("field{}_mut_ref", idx), span);
-        patch_expressions.push(quote! {#field_mut_ref_ident.apply_patch(#patch_ident);});
+        let path_segment = fields[idx].path_segment();
+        let apply_patch = patch_kind.apply_field_patch(&field_mut_ref_ident, &patch_ident);
+
+        patch_expressions.push(quote! {
+            #apply_patch.map_err(|err| err.with_parent(#path_segment))?;
+        });
     }
 
     patch_expressions
 }
+
+/// field0_mut_ref
+///     .apply_patch_returning_inverse(field0_patch)
+///     .map_err(|err| err.with_parent(dipa::PathSegment::Field("some_field_name")))?
+/// field2_mut_ref
+///     .apply_patch_returning_inverse(field2_patch)
+///     .map_err(|err| err.with_parent(dipa::PathSegment::Field("another_field_name")))?
+fn get_reverse_patch_expressions(
+    span: Span,
+    bools: &[bool],
+    fields: &[StructOrTupleField],
+) -> Vec<TokenStream2> {
+    bools
+        .iter()
+        .enumerate()
+        .filter(|(_, did_change)| **did_change)
+        .map(|(idx, _)| {
+            let patch_ident = Ident::new(&format!("field{}_patch", idx), span);
+
+            let field_mut_ref_ident = Ident::new(&format!("field{}_mut_ref", idx), span);
+            let path_segment = fields[idx].path_segment();
+
+            quote! {
+                #field_mut_ref_ident
+                    .apply_patch_returning_inverse(#patch_ident)
+                    .map_err(|err| err.with_parent(#path_segment))?
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,307 @@
+use quote::ToTokens;
+use syn::__private::{Span, TokenStream2};
+use syn::{Ident, LitStr, Type};
+
+/// Whether generated `apply_patch` tokens apply an owned or a borrowed delta to each field.
+#[derive(Copy, Clone)]
+pub enum PatchKind {
+    Owned,
+    Borrowed,
+}
+
+impl PatchKind {
+    /// Apply a field's patch, where `field` is a mutable reference to the field.
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// // PatchKind::Owned
+    /// quote! { field0_mut_ref.apply_patch(field0_patch) };
+    /// // PatchKind::Borrowed
+    /// quote! { dipa::BorrowedDelta::apply_borrowed_patch(field0_mut_ref, field0_patch) };
+    /// ```
+    pub fn apply_field_patch(&self, field: &impl ToTokens, patch: &impl ToTokens) -> TokenStream2 {
+        match self {
+            PatchKind::Owned => quote! {
+                #field.apply_patch(#patch)
+            },
+            PatchKind::Borrowed => quote! {
+                dipa::BorrowedDelta::apply_borrowed_patch(#field, #patch)
+            },
+        }
+    }
+}
+
+/// Generate the `dipa::BorrowedDelta` implementation that applies a type's borrowed delta along
+/// with a `dipa::Patchable` implementation that forwards to it.
+///
+/// `delta_tys` are the types whose borrowed deltas the borrowed delta type holds and `value_tys`
+/// are the types that it holds by value, such as the fields of the variant that an enum changed
+/// to.
+///
+/// `delta_tys` and `value_tys` are the field types to bound, see
+/// [crate::multi_field_utils::bounded_field_tys].
+///
+/// The where clause bounds mention the `'b` or `'de` lifetime, so a field type that does not
+/// implement `dipa::BorrowedDelta` only means that the implementations can not be used instead of
+/// causing a compile time error.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     impl<'de> dipa::BorrowedDelta<'de> for MyStruct
+///     where
+///         u8: dipa::BorrowedDelta<'de>,
+///     {
+///         type DeltaBorrowed = MyStructDeltaBorrowed<'de>;
+///
+///         fn apply_borrowed_patch(&mut self, patch: MyStructDeltaBorrowed<'de>)
+///           -> Result<(), dipa::PatchError> {
+///             // ... Apply patch tokens ...
+///
+///             Ok(())
+///         }
+///     }
+///
+///     impl<'b> dipa::Patchable<MyStructDeltaBorrowed<'b>> for MyStruct
+///     where
+///         u8: dipa::BorrowedDelta<'b>,
+///     {
+///         fn apply_patch(&mut self, patch: MyStructDeltaBorrowed<'b>) -> Result<(), dipa::PatchError> {
+///             dipa::BorrowedDelta::apply_borrowed_patch(self, patch)
+///         }
+///     }
+/// };
+/// ```
+pub fn make_borrowed_delta_impl(
+    type_name: &Ident,
+    delta_borrowed_ty: &Ident,
+    delta_tys: &[Type],
+    value_tys: &[Type],
+    apply_patch_inner: TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        impl<'de> dipa::BorrowedDelta<'de> for #type_name
+        where
+            #(#delta_tys: dipa::BorrowedDelta<'de>,)*
+            #(#value_tys: serde::Deserialize<'de>,)*
+        {
+            type DeltaBorrowed = #delta_borrowed_ty<'de>;
+
+            fn apply_borrowed_patch(&mut self, patch: #delta_borrowed_ty<'de>)
+              -> Result<(), dipa::PatchError> {
+                #apply_patch_inner
+
+                Ok(())
+            }
+        }
+
+        impl<'b> dipa::Patchable<#delta_borrowed_ty<'b>> for #type_name
+        where
+            #(#delta_tys: dipa::BorrowedDelta<'b>,)*
+            #(#value_tys: serde::Deserialize<'b>,)*
+        {
+            fn apply_patch(&mut self, patch: #delta_borrowed_ty<'b>) -> Result<(), dipa::PatchError> {
+                dipa::BorrowedDelta::apply_borrowed_patch(self, patch)
+            }
+        }
+    }
+}
+
+/// Generate the `dipa::BorrowedDelta` implementation for a type that has a single field.
+///
+/// The owned delta of a single field type is the field's owned delta, so the borrowed delta is
+/// the field's borrowed delta. There is no `dipa::Patchable` implementation for it since it would
+/// conflict with the one for the owned delta.
+///
+/// `apply_patch_inner` applies a `patch` of the field's borrowed delta type.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     impl<'de> dipa::BorrowedDelta<'de> for MyStruct
+///     where
+///         u8: dipa::BorrowedDelta<'de>,
+///     {
+///         type DeltaBorrowed = <u8 as dipa::BorrowedDelta<'de>>::DeltaBorrowed;
+///
+///         fn apply_borrowed_patch(&mut self, patch: Self::DeltaBorrowed)
+///           -> Result<(), dipa::PatchError> {
+///             // ... Apply patch tokens ...
+///
+///             Ok(())
+///         }
+///     }
+/// };
+/// ```
+pub fn make_single_field_borrowed_delta_impl(
+    type_name: &Ident,
+    field_ty: &Type,
+    apply_patch_inner: TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        impl<'de> dipa::BorrowedDelta<'de> for #type_name
+        where
+            #field_ty: dipa::BorrowedDelta<'de>,
+        {
+            type DeltaBorrowed = <#field_ty as dipa::BorrowedDelta<'de>>::DeltaBorrowed;
+
+            fn apply_borrowed_patch(&mut self, patch: Self::DeltaBorrowed)
+              -> Result<(), dipa::PatchError> {
+                #apply_patch_inner
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The where clause for a borrowed delta type, since its fields are only well formed when every
+/// field type implements `dipa::BorrowedDelta`.
+///
+/// `delta_tys` are the field types to bound, see [crate::multi_field_utils::bounded_field_tys].
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     where u8: dipa::BorrowedDelta<'b>, Vec<u8>: dipa::BorrowedDelta<'b>,
+/// };
+/// ```
+///
+/// A type with recursive fields gets no bounds, see [borrowed_delta_serde_bound].
+pub fn borrowed_delta_where_clause(delta_tys: &[Type], has_recursive_fields: bool) -> TokenStream2 {
+    if has_recursive_fields {
+        return quote! {};
+    }
+
+    quote! {
+        where #(#delta_tys: dipa::BorrowedDelta<'b>,)*
+    }
+}
+
+/// The serde attribute that replaces the bounds that serde would infer for a borrowed delta type's
+/// `Deserialize` implementation.
+///
+/// Deserializing each field's borrowed delta with the `'de` lifetime is what lets byte and string
+/// payloads borrow from the input.
+///
+/// `delta_tys` and `value_tys` are the field types to bound, see
+/// [crate::multi_field_utils::bounded_field_tys].
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     #[serde(bound(
+///         deserialize = "<u8 as dipa::BorrowedDelta<'b>>::DeltaBorrowed: serde::Deserialize<'de>,"
+///     ))]
+/// };
+/// ```
+///
+/// A type with recursive fields only gets `'de: 'b`, the same bound that `#[serde(borrow)]`
+/// adds. Deserializing a recursive field's borrowed delta goes back through the type's own
+/// `dipa::BorrowedDelta<'b>` implementation, and the compiler picks bounds such as
+/// `u8: dipa::BorrowedDelta<'b>` over the field types' own implementations when proving it,
+/// which would only work if `'de` and `'b` were the same lifetime. The field types of a derived
+/// type are all known, so their implementations get checked without any bounds.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     #[serde(bound(deserialize = "'de: 'b"))]
+/// };
+/// ```
+pub fn borrowed_delta_serde_bound(
+    delta_tys: &[Type],
+    value_tys: &[Type],
+    has_recursive_fields: bool,
+) -> TokenStream2 {
+    let bounds = if has_recursive_fields {
+        quote! { 'de: 'b }
+    } else {
+        quote! {
+            #(<#delta_tys as dipa::BorrowedDelta<'b>>::DeltaBorrowed: serde::Deserialize<'de>,)*
+            #(#value_tys: serde::Deserialize<'de>,)*
+        }
+    };
+    let bounds = LitStr::new(&bounds.to_string(), Span::call_site());
+
+    quote! {
+        #[serde(bound(deserialize = #bounds))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we generate the BorrowedDelta and Patchable implementations for a borrowed
+    /// delta type.
+    #[test]
+    fn borrowed_delta_impl() {
+        let tokens = make_borrowed_delta_impl(
+            &Ident::new("MyEnum", Span::call_site()),
+            &Ident::new("MyEnumDeltaBorrowed", Span::call_site()),
+            &[Type::Verbatim(quote! {Vec<u8>})],
+            &[Type::Verbatim(quote! {Vec<u8>})],
+            quote! {},
+        );
+
+        let expected = quote! {
+            impl<'de> dipa::BorrowedDelta<'de> for MyEnum
+            where
+                Vec<u8>: dipa::BorrowedDelta<'de>,
+                Vec<u8>: serde::Deserialize<'de>,
+            {
+                type DeltaBorrowed = MyEnumDeltaBorrowed<'de>;
+
+                fn apply_borrowed_patch(&mut self, patch: MyEnumDeltaBorrowed<'de>)
+                  -> Result<(), dipa::PatchError> {
+                    Ok(())
+                }
+            }
+
+            impl<'b> dipa::Patchable<MyEnumDeltaBorrowed<'b>> for MyEnum
+            where
+                Vec<u8>: dipa::BorrowedDelta<'b>,
+                Vec<u8>: serde::Deserialize<'b>,
+            {
+                fn apply_patch(&mut self, patch: MyEnumDeltaBorrowed<'b>)
+                  -> Result<(), dipa::PatchError> {
+                    dipa::BorrowedDelta::apply_borrowed_patch(self, patch)
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that the serde bounds cover both the borrowed deltas and the values.
+    #[test]
+    fn serde_bound() {
+        let tokens = borrowed_delta_serde_bound(
+            &[Type::Verbatim(quote! {u16})],
+            &[Type::Verbatim(quote! {String})],
+            false,
+        );
+
+        let expected = quote! {
+            #[serde(bound(
+                deserialize = "< u16 as dipa :: BorrowedDelta < 'b >> :: DeltaBorrowed : serde :: Deserialize < 'de > , String : serde :: Deserialize < 'de > ,"
+            ))]
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that a type with recursive fields only bounds the lifetimes.
+    #[test]
+    fn recursive_serde_bound() {
+        let tokens = borrowed_delta_serde_bound(&[Type::Verbatim(quote! {u16})], &[], true);
+
+        let expected = quote! {
+            #[serde(bound(deserialize = "'de : 'b"))]
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -34,7 +34,7 @@ impl ChangedFieldIndices {
     ///     "FooChange_0_2"
     ///     ... etc
     pub fn variant_name(&self, prefix: &str) -> String {
-        if self.len() == 0 {
+        if self.is_empty() {
             format!("{}NoChange", prefix)
         } else {
             let mut changed = format!("{}Change", prefix);
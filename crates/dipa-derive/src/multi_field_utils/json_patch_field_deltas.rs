@@ -0,0 +1,303 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{ChangedFieldIndices, StructOrTupleField};
+use syn::__private::{Span, TokenStream2};
+use syn::{Ident, Type};
+
+/// Generate the `dipa::ToJsonPatch` implementation that converts a delta into JSON Patch
+/// operations.
+///
+/// Nothing is generated unless the `json-patch` feature is enabled, since the trait only exists in
+/// `dipa` when its `json-patch` feature is enabled.
+///
+/// `field_tys` are the field types to bound, see [crate::multi_field_utils::bounded_field_tys].
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     impl<'s, 'e> dipa::ToJsonPatch<'s, 'e> for MyStruct
+///     where
+///         u8: dipa::ToJsonPatch<'s, 'e>,
+///     {
+///         fn push_json_patch(
+///             &self,
+///             delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+///             path: &dipa::JsonPointer,
+///             patch: &mut dipa::JsonPatch,
+///         ) -> Result<(), dipa::JsonPatchError> {
+///             // ... Json patch tokens ...
+///
+///             Ok(())
+///         }
+///     }
+/// };
+/// ```
+pub fn make_json_patch_impl(
+    type_name: &Ident,
+    field_tys: &[Type],
+    json_patch_inner: TokenStream2,
+) -> TokenStream2 {
+    if !cfg!(feature = "json-patch") {
+        return quote! {};
+    }
+
+    quote! {
+        impl<'s, 'e> dipa::ToJsonPatch<'s, 'e> for #type_name
+        where
+            #(#field_tys: dipa::ToJsonPatch<'s, 'e>,)*
+        {
+            fn push_json_patch(
+                &self,
+                delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+                path: &dipa::JsonPointer,
+                patch: &mut dipa::JsonPatch,
+            ) -> Result<(), dipa::JsonPatchError> {
+                #json_patch_inner
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Where a field lives in the `serde_json` form of the type that holds it, relative to `path`.
+///
+/// Named fields are object members and unnamed fields are array elements, except for a single
+/// unnamed field which serde writes as the field's value on its own.
+///
+/// some_name -> &path.child("some_name")
+/// 0 (with other fields) -> &path.child(0usize)
+/// 0 (only field) -> path
+pub fn field_json_pointer(field_name: &TokenStream2, field_count: usize) -> TokenStream2 {
+    let field_name = field_name.to_string();
+
+    match field_name.parse::<usize>() {
+        Ok(_) if field_count == 1 => quote! { path },
+        Ok(idx) => quote! { &path.child(#idx) },
+        Err(_) => {
+            let field_name = field_name.trim_start_matches("r#");
+            quote! { &path.child(#field_name) }
+        }
+    }
+}
+
+/// Push the operations for one field, where `start_field` is a reference to the field's start
+/// value.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     <u8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+///         &self.some_field_name,
+///         delta0,
+///         &path.child("some_field_name"),
+///         patch,
+///     )?;
+/// };
+/// ```
+///
+/// Packed bools are given the pointer to the type that holds them, and push an operation for each
+/// bool that changed at that bool's own pointer.
+pub fn json_patch_field_delta_tokens(
+    field: &StructOrTupleField,
+    field_count: usize,
+    start_field: &TokenStream2,
+    delta: &TokenStream2,
+) -> TokenStream2 {
+    let ty = &field.ty;
+    let pointer = if field.is_packed_bools() {
+        quote! { path }
+    } else {
+        field_json_pointer(&field.name, field_count)
+    };
+
+    quote! {
+        <#ty as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(#start_field, #delta, #pointer, patch)?;
+    }
+}
+
+/// Generate the match arms that push the operations for the changed fields of a delta type that
+/// uses the `one_batch` field batching strategy.
+///
+/// A non empty `change_prefix` means that the delta type also holds the deltas for other enum
+/// variants, so a delta for a different variant is ignored.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     MyStructDelta::NoChange => {}
+///     MyStructDelta::Change_0(delta0) => {
+///         <u8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+///             &self.some_field_name,
+///             delta0,
+///             &path.child("some_field_name"),
+///             patch,
+///         )?;
+///     }
+///     // ...
+/// };
+/// ```
+pub fn make_json_patch_field_delta_arms(
+    delta_ty: &TokenStream2,
+    change_prefix: &str,
+    span: Span,
+    fields: &[StructOrTupleField],
+    start_fields: &[TokenStream2],
+    dipa_attrs: &DipaAttrs,
+) -> Vec<TokenStream2> {
+    let no_change = Ident::new(&format!("{}NoChange", change_prefix), span);
+
+    let mut arms = vec![quote! {
+        #delta_ty::#no_change => {}
+    }];
+
+    for changed_indices in
+        ChangedFieldIndices::all_changed_index_combinations(fields.len(), dipa_attrs)
+    {
+        let change_name = changed_indices.variant_name_ident(change_prefix, span);
+        let deltas: Vec<Ident> = changed_indices
+            .iter()
+            .map(|field_idx| Ident::new(&format!("delta{}", field_idx), span))
+            .collect();
+
+        let field_operations =
+            changed_indices
+                .iter()
+                .zip(deltas.iter())
+                .map(|(field_idx, delta)| {
+                    let field_idx = *field_idx as usize;
+
+                    json_patch_field_delta_tokens(
+                        &fields[field_idx],
+                        fields.len(),
+                        &start_fields[field_idx],
+                        &quote! {#delta},
+                    )
+                });
+
+        arms.push(quote! {
+            #delta_ty::#change_name(#(#deltas),*) => {
+                #(#field_operations)*
+            }
+        });
+    }
+
+    if !change_prefix.is_empty() {
+        arms.push(quote! {
+            _ => {}
+        });
+    }
+
+    arms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that the implementation only applies when every field type can be converted.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn json_patch_impl_bounds() {
+        let tokens = make_json_patch_impl(
+            &format_ident!("MyStruct"),
+            &[
+                Type::Verbatim(quote! {u8}),
+                Type::Verbatim(quote! {Vec<i8>}),
+            ],
+            quote! {},
+        );
+
+        let expected = quote! {
+            impl<'s, 'e> dipa::ToJsonPatch<'s, 'e> for MyStruct
+            where
+                u8: dipa::ToJsonPatch<'s, 'e>,
+                Vec<i8>: dipa::ToJsonPatch<'s, 'e>,
+            {
+                fn push_json_patch(
+                    &self,
+                    delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+                    path: &dipa::JsonPointer,
+                    patch: &mut dipa::JsonPatch,
+                ) -> Result<(), dipa::JsonPatchError> {
+                    Ok(())
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that fields point to object members, array elements or the value itself depending on
+    /// how serde writes them.
+    #[test]
+    fn field_json_pointers() {
+        assert_tokens_eq(
+            &field_json_pointer(&quote! {r#type}, 2),
+            &quote! { &path.child("type") },
+        );
+        assert_tokens_eq(
+            &field_json_pointer(&quote! {1}, 2),
+            &quote! { &path.child(1usize) },
+        );
+        assert_tokens_eq(&field_json_pointer(&quote! {0}, 1), &quote! { path });
+    }
+
+    /// Verify that we generate the arms that push the operations for each changed field.
+    #[test]
+    fn json_patch_two_fields() {
+        let tokens = make_json_patch_field_delta_arms(
+            &quote! {MyStructDelta},
+            "",
+            Span::call_site(),
+            &two_fields(),
+            &[quote! {&self.fielda}, quote! {&self.fieldb}],
+            &DipaAttrs::default(),
+        );
+        let tokens = quote! {#(#tokens)*};
+
+        let expected = quote! {
+            MyStructDelta::NoChange => {}
+            MyStructDelta::Change_0(delta0) => {
+                <u8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                    &self.fielda, delta0, &path.child("fielda"), patch
+                )?;
+            }
+            MyStructDelta::Change_1(delta1) => {
+                <i8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                    &self.fieldb, delta1, &path.child("fieldb"), patch
+                )?;
+            }
+            MyStructDelta::Change_0_1(delta0, delta1) => {
+                <u8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                    &self.fielda, delta0, &path.child("fielda"), patch
+                )?;
+                <i8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                    &self.fieldb, delta1, &path.child("fieldb"), patch
+                )?;
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn two_fields() -> Vec<StructOrTupleField> {
+        vec![
+            StructOrTupleField {
+                name: quote! {fielda},
+                ty: Type::Verbatim(quote! {u8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {fieldb},
+                ty: Type::Verbatim(quote! {i8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+        ]
+    }
+}
@@ -0,0 +1,339 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{ChangedFieldIndices, StructOrTupleField};
+use quote::ToTokens;
+use syn::__private::{Span, TokenStream2};
+use syn::Ident;
+
+/// Generate the tokens that merge the `a` and `b` patches for a type that uses the `one_batch`
+/// field batching strategy.
+///
+/// Each patch gets split into one `Option` per field. Fields that only one patch changed keep that
+/// patch, and fields that both patches changed get merged against the matching field in
+/// `base_fields`. Every field is merged before returning so that all of the conflicts get
+/// reported.
+///
+/// A non empty `change_prefix` means that the patch type also holds the patches for other enum
+/// variants, so a patch for a different variant returns an error.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     let (a0, a1) = match a {
+///         MyStructDeltaOwned::NoChange => (None, None),
+///         MyStructDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+///         MyStructDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+///         MyStructDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+///     };
+///     let (b0, b1) = match b {
+///         // ... Same as above ...
+///     };
+///
+///     let mut conflicts = dipa::MergeConflicts::default();
+///
+///     let merged0 = match (a0, b0) {
+///         (Some(a_patch), Some(b_patch)) => {
+///             <u8 as dipa::MergeablePatches<_>>::merge_patches(&base.some_field_name, a_patch, b_patch)
+///                 .map_err(|err| conflicts.extend(
+///                     err.with_parent(dipa::PathSegment::Field("some_field_name"))
+///                 ))
+///                 .ok()
+///         }
+///         (a_patch, b_patch) => a_patch.or(b_patch),
+///     };
+///     let merged1 = match (a1, b1) {
+///         // ... Same as above ...
+///     };
+///
+///     if !conflicts.is_empty() {
+///         return Err(conflicts);
+///     }
+///
+///     match (merged0, merged1) {
+///         (None, None) => MyStructDeltaOwned::NoChange,
+///         (Some(patch0), None) => MyStructDeltaOwned::Change_0(patch0),
+///         (None, Some(patch1)) => MyStructDeltaOwned::Change_1(patch1),
+///         (Some(patch0), Some(patch1)) => MyStructDeltaOwned::Change_0_1(patch0, patch1),
+///     }
+/// };
+/// ```
+pub fn make_merge_field_patches_tokens(
+    delta_owned_ty: &TokenStream2,
+    change_prefix: &str,
+    variant_name: Option<&str>,
+    span: Span,
+    fields: &[StructOrTupleField],
+    base_fields: &[TokenStream2],
+    dipa_attrs: &DipaAttrs,
+) -> TokenStream2 {
+    let a_fields = field_option_idents("a", span, fields.len());
+    let b_fields = field_option_idents("b", span, fields.len());
+    let merged_fields = field_option_idents("merged", span, fields.len());
+
+    let no_change = Ident::new(&format!("{}NoChange", change_prefix), span);
+
+    let mut split_arms = vec![];
+    let mut combine_arms = vec![];
+
+    let no_fields_changed = tuple(&vec![quote! {None}; fields.len()]);
+    split_arms.push(quote! {
+        #delta_owned_ty::#no_change => #no_fields_changed,
+    });
+    combine_arms.push(quote! {
+        #no_fields_changed => #delta_owned_ty::#no_change,
+    });
+
+    for changed_indices in
+        ChangedFieldIndices::all_changed_index_combinations(fields.len(), dipa_attrs)
+    {
+        let change_name = changed_indices.variant_name_ident(change_prefix, span);
+        let patches = changed_indices.patch_field_idents(span);
+
+        let field_options: Vec<TokenStream2> = (0..fields.len())
+            .map(|field_idx| {
+                match changed_indices
+                    .iter()
+                    .position(|changed| *changed as usize == field_idx)
+                {
+                    Some(patch_idx) => {
+                        let patch = &patches[patch_idx];
+                        quote! {Some(#patch)}
+                    }
+                    None => quote! {None},
+                }
+            })
+            .collect();
+        let field_options = tuple(&field_options);
+
+        split_arms.push(quote! {
+            #delta_owned_ty::#change_name(#(#patches),*) => #field_options,
+        });
+        combine_arms.push(quote! {
+            #field_options => #delta_owned_ty::#change_name(#(#patches),*),
+        });
+    }
+
+    if !change_prefix.is_empty() {
+        split_arms.push(quote! {
+            _ => return Err(dipa::PatchError::new(
+                dipa::PatchErrorReason::VariantMismatch { expected: #change_prefix }
+            ).into()),
+        });
+    }
+
+    let merge_statements = fields.iter().enumerate().map(|(idx, field)| {
+        let ty = &field.ty;
+        let a = &a_fields[idx];
+        let b = &b_fields[idx];
+        let merged = &merged_fields[idx];
+        let base_field = &base_fields[idx];
+
+        let path_segment = field.path_segment();
+        let variant_segment = variant_name.map(|variant_name| {
+            quote! { .with_parent(dipa::PathSegment::Variant(#variant_name)) }
+        });
+
+        quote! {
+            let #merged = match (#a, #b) {
+                (Some(a_patch), Some(b_patch)) => {
+                    <#ty as dipa::MergeablePatches<_>>::merge_patches(#base_field, a_patch, b_patch)
+                        .map_err(|err| conflicts.extend(
+                            err.with_parent(#path_segment)#variant_segment
+                        ))
+                        .ok()
+                }
+                (a_patch, b_patch) => a_patch.or(b_patch),
+            };
+        }
+    });
+
+    let a_fields = tuple(&a_fields);
+    let b_fields = tuple(&b_fields);
+    let merged_fields = tuple(&merged_fields);
+
+    quote! {
+        let #a_fields = match a {
+            #(#split_arms)*
+        };
+        let #b_fields = match b {
+            #(#split_arms)*
+        };
+
+        let mut conflicts = dipa::MergeConflicts::default();
+
+        #(#merge_statements)*
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        match #merged_fields {
+            #(#combine_arms)*
+        }
+    }
+}
+
+/// (a, b, c), or just `a` when there is only one item so that we don't generate tokens that
+/// trigger the `unused_parens` lint.
+fn tuple<T: ToTokens>(items: &[T]) -> TokenStream2 {
+    match items {
+        [item] => quote! {#item},
+        items => quote! {(#(#items),*)},
+    }
+}
+
+/// a0, a1, a2, ...
+fn field_option_idents(prefix: &str, span: Span, field_count: usize) -> Vec<Ident> {
+    (0..field_count)
+        .map(|idx| Ident::new(&format!("{}{}", prefix, idx), span))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::Type;
+
+    /// Verify that we generate the tokens that merge the patches of a struct with two fields.
+    #[test]
+    fn merge_two_fields() {
+        let tokens = make_merge_field_patches_tokens(
+            &quote! {MyStructDeltaOwned},
+            "",
+            None,
+            Span::call_site(),
+            &two_fields(),
+            &[quote! {&base.fielda}, quote! {&base.fieldb}],
+            &DipaAttrs::default(),
+        );
+
+        let expected = quote! {
+            let (a0, a1) = match a {
+                MyStructDeltaOwned::NoChange => (None, None),
+                MyStructDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyStructDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyStructDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+            let (b0, b1) = match b {
+                MyStructDeltaOwned::NoChange => (None, None),
+                MyStructDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyStructDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyStructDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+
+            let mut conflicts = dipa::MergeConflicts::default();
+
+            let merged0 = match (a0, b0) {
+                (Some(a_patch), Some(b_patch)) => {
+                    <u8 as dipa::MergeablePatches<_>>::merge_patches(&base.fielda, a_patch, b_patch)
+                        .map_err(|err| conflicts.extend(
+                            err.with_parent(dipa::PathSegment::Field("fielda"))
+                        ))
+                        .ok()
+                }
+                (a_patch, b_patch) => a_patch.or(b_patch),
+            };
+            let merged1 = match (a1, b1) {
+                (Some(a_patch), Some(b_patch)) => {
+                    <i8 as dipa::MergeablePatches<_>>::merge_patches(&base.fieldb, a_patch, b_patch)
+                        .map_err(|err| conflicts.extend(
+                            err.with_parent(dipa::PathSegment::Field("fieldb"))
+                        ))
+                        .ok()
+                }
+                (a_patch, b_patch) => a_patch.or(b_patch),
+            };
+
+            if !conflicts.is_empty() {
+                return Err(conflicts);
+            }
+
+            match (merged0, merged1) {
+                (None, None) => MyStructDeltaOwned::NoChange,
+                (Some(patch0), None) => MyStructDeltaOwned::Change_0(patch0),
+                (None, Some(patch1)) => MyStructDeltaOwned::Change_1(patch1),
+                (Some(patch0), Some(patch1)) => MyStructDeltaOwned::Change_0_1(patch0, patch1),
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that merging the patches for one variant of a multi variant enum returns an error
+    /// when given a patch for another variant, and that conflicts include the variant.
+    #[test]
+    fn merge_prefixed_variant_fields() {
+        let tokens = make_merge_field_patches_tokens(
+            &quote! {MyEnumDeltaOwned},
+            "Two",
+            Some("Two"),
+            Span::call_site(),
+            &two_fields()[0..1],
+            &[quote! {base0}],
+            &DipaAttrs::default(),
+        );
+
+        let expected = quote! {
+            let a0 = match a {
+                MyEnumDeltaOwned::TwoNoChange => None,
+                MyEnumDeltaOwned::TwoChange_0(patch0) => Some(patch0),
+                _ => return Err(dipa::PatchError::new(
+                    dipa::PatchErrorReason::VariantMismatch { expected: "Two" }
+                ).into()),
+            };
+            let b0 = match b {
+                MyEnumDeltaOwned::TwoNoChange => None,
+                MyEnumDeltaOwned::TwoChange_0(patch0) => Some(patch0),
+                _ => return Err(dipa::PatchError::new(
+                    dipa::PatchErrorReason::VariantMismatch { expected: "Two" }
+                ).into()),
+            };
+
+            let mut conflicts = dipa::MergeConflicts::default();
+
+            let merged0 = match (a0, b0) {
+                (Some(a_patch), Some(b_patch)) => {
+                    <u8 as dipa::MergeablePatches<_>>::merge_patches(base0, a_patch, b_patch)
+                        .map_err(|err| conflicts.extend(
+                            err
+                                .with_parent(dipa::PathSegment::Field("fielda"))
+                                .with_parent(dipa::PathSegment::Variant("Two"))
+                        ))
+                        .ok()
+                }
+                (a_patch, b_patch) => a_patch.or(b_patch),
+            };
+
+            if !conflicts.is_empty() {
+                return Err(conflicts);
+            }
+
+            match merged0 {
+                None => MyEnumDeltaOwned::TwoNoChange,
+                Some(patch0) => MyEnumDeltaOwned::TwoChange_0(patch0),
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn two_fields() -> Vec<StructOrTupleField> {
+        vec![
+            StructOrTupleField {
+                name: quote! {fielda},
+                ty: Type::Verbatim(quote! {u8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {fieldb},
+                ty: Type::Verbatim(quote! {i8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+        ]
+    }
+}
@@ -0,0 +1,358 @@
+//! Packing the bool fields of a struct or enum variant into one field so that all of their changes
+//! fit in a single `dipa::BoolFlags` delta.
+//!
+//! The packed field gets diffed and patched like any other field, using `dipa::PackedBools` and
+//! `dipa::PackedBoolsMut` to refer to the bools that it holds.
+
+use crate::dipa_attribute::{field_attribute_error, DipaFieldAttrs};
+use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
+use syn::__private::TokenStream2;
+use syn::{Ident, PathArguments, Type};
+
+/// Every bit of a `u64` holds one bool.
+const MAX_PACKED_BOOLS: usize = 64;
+
+impl ParsedFields {
+    /// Replace the bool fields with one field that holds all of them, as long as there are at least
+    /// two of them. The packed field takes the place of the first bool field.
+    ///
+    /// Skipped for types with the `#[dipa(pack_bools = false)]` container attribute. Bool fields
+    /// with a since or epsilon attribute are left alone since they need their own delta.
+    ///
+    /// If there are more bools than fit in a `u64`, tokens containing a compile time error are
+    /// returned.
+    pub fn pack_bool_fields(&mut self) -> Result<(), TokenStream2> {
+        let packable: Vec<&StructOrTupleField> =
+            self.fields.iter().filter(|f| is_packable(f)).collect();
+        if packable.len() < 2 {
+            return Ok(());
+        }
+
+        // dipa-derive-test/src/all_tests/ui/pack_bools_attribute.rs
+        if packable.len() > MAX_PACKED_BOOLS {
+            let error = format!(
+                r#"At most {max} bool fields can be packed, but there are {count}.
+Consider moving some of them into a nested struct, or using `dipa(pack_bools = false)`."#,
+                max = MAX_PACKED_BOOLS,
+                count = packable.len()
+            );
+
+            return Err(field_attribute_error(
+                packable[MAX_PACKED_BOOLS].span,
+                &error,
+            ));
+        }
+
+        let first_bool_idx = self.fields.iter().position(is_packable).unwrap();
+
+        let (packed_bools, mut fields): (Vec<_>, Vec<_>) =
+            self.fields.drain(..).partition(is_packable);
+
+        let bool_count = packed_bools.len();
+        let span = packed_bools[0].span;
+
+        fields.insert(
+            first_bool_idx,
+            StructOrTupleField {
+                name: quote_spanned! {span => __packed_bools},
+                ty: Type::Verbatim(quote_spanned! {span => dipa::PackedBools<#bool_count>}),
+                span,
+                attrs: DipaFieldAttrs::default(),
+                packed_bools,
+            },
+        );
+
+        self.fields = fields;
+
+        Ok(())
+    }
+}
+
+impl StructOrTupleField {
+    /// Whether or not this field holds the packed bool fields of its struct or enum variant.
+    pub fn is_packed_bools(&self) -> bool {
+        !self.packed_bools.is_empty()
+    }
+
+    /// A reference to the field, given the value that holds it.
+    ///
+    /// &self.some_name
+    /// &dipa::PackedBools::new([&self.bool_a, &self.bool_b], &[...])
+    pub fn reference(&self, owner: &TokenStream2) -> TokenStream2 {
+        let name = &self.name;
+
+        if !self.is_packed_bools() {
+            return quote! {&#owner.#name};
+        }
+
+        let bools = self.packed_bools.iter().map(|b| {
+            let name = &b.name;
+            quote! {&#owner.#name}
+        });
+        let paths = self.packed_bool_paths();
+
+        quote! {&dipa::PackedBools::new([#(#bools),*], #paths)}
+    }
+
+    /// A mutable reference to the field, given the value that holds it.
+    ///
+    /// &mut self.some_name
+    /// &mut dipa::PackedBoolsMut::new([&mut self.bool_a, &mut self.bool_b])
+    pub fn mut_reference(&self, owner: &TokenStream2) -> TokenStream2 {
+        let name = &self.name;
+
+        if !self.is_packed_bools() {
+            return quote! {&mut #owner.#name};
+        }
+
+        let bools = self.packed_bools.iter().map(|b| {
+            let name = &b.name;
+            quote! {&mut #owner.#name}
+        });
+
+        quote! {&mut dipa::PackedBoolsMut::new([#(#bools),*])}
+    }
+
+    /// The reference to the field that an enum variant pattern binds, using
+    /// [`EnumVariantFields::to_pattern_match_tokens`].
+    ///
+    /// prefix_some_name
+    /// (&dipa::PackedBools::new([prefix_bool_a, prefix_bool_b], &[...]))
+    ///
+    /// [`EnumVariantFields::to_pattern_match_tokens`]: crate::parsed_enum::EnumVariantFields::to_pattern_match_tokens
+    pub fn binding(&self, prefix: &'static str) -> TokenStream2 {
+        if !self.is_packed_bools() {
+            let binding = self.prefixed_name(prefix);
+            return quote! {#binding};
+        }
+
+        let bools = self.packed_bool_bindings(prefix);
+        let paths = self.packed_bool_paths();
+
+        quote! {(&dipa::PackedBools::new([#(#bools),*], #paths))}
+    }
+
+    /// The mutable reference to the field that an enum variant pattern binds.
+    ///
+    /// prefix_some_name
+    /// (&mut dipa::PackedBoolsMut::new([prefix_bool_a, prefix_bool_b]))
+    pub fn binding_mut(&self, prefix: &'static str) -> TokenStream2 {
+        if !self.is_packed_bools() {
+            let binding = self.prefixed_name(prefix);
+            return quote! {#binding};
+        }
+
+        let bools = self.packed_bool_bindings(prefix);
+
+        quote! {(&mut dipa::PackedBoolsMut::new([#(#bools),*]))}
+    }
+
+    fn packed_bool_bindings(&self, prefix: &'static str) -> Vec<Ident> {
+        self.packed_bools
+            .iter()
+            .map(|b| b.prefixed_name(prefix))
+            .collect()
+    }
+
+    /// &[dipa::PathSegment::Field("bool_a"), dipa::PathSegment::Field("bool_b")]
+    fn packed_bool_paths(&self) -> TokenStream2 {
+        let paths = self.packed_bools.iter().map(|b| b.path_segment());
+
+        quote! {&[#(#paths),*]}
+    }
+}
+
+/// The fields as they were declared, with packed bools replaced by the bool fields that they hold.
+pub fn unpack_bool_fields(fields: &[StructOrTupleField]) -> Vec<StructOrTupleField> {
+    let mut unpacked: Vec<StructOrTupleField> = fields
+        .iter()
+        .flat_map(|field| {
+            if field.is_packed_bools() {
+                field.packed_bools.clone()
+            } else {
+                vec![field.clone()]
+            }
+        })
+        .collect();
+
+    // Tuple fields have to stay in order since they are matched on by position.
+    if let Some(Ok(_)) = unpacked
+        .first()
+        .map(|field| field.name.to_string().parse::<usize>())
+    {
+        unpacked.sort_by_key(|field| field.name.to_string().parse::<usize>().unwrap());
+    }
+
+    unpacked
+}
+
+fn is_packable(field: &StructOrTupleField) -> bool {
+    is_bool(&field.ty) && field.attrs.since.is_none() && field.attrs.epsilon.is_none()
+}
+
+/// `bool`, or the path to it through `core::primitive` or `std::primitive`.
+fn is_bool(ty: &Type) -> bool {
+    let ty = match ty {
+        Type::Path(ty) if ty.qself.is_none() => ty,
+        _ => return false,
+    };
+
+    let segments: Vec<String> = ty
+        .path
+        .segments
+        .iter()
+        .map(|segment| match segment.arguments {
+            PathArguments::None => segment.ident.to_string(),
+            _ => String::new(),
+        })
+        .collect();
+
+    match segments.as_slice() {
+        [name] => name == "bool" && ty.path.leading_colon.is_none(),
+        [krate, primitive, name] => {
+            (krate == "core" || krate == "std") && primitive == "primitive" && name == "bool"
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+
+    fn field(name: TokenStream2, ty: TokenStream2) -> StructOrTupleField {
+        StructOrTupleField {
+            name,
+            ty: syn::parse2(ty).unwrap(),
+            span: Span::call_site(),
+            attrs: DipaFieldAttrs::default(),
+            packed_bools: vec![],
+        }
+    }
+
+    fn names(fields: &[StructOrTupleField]) -> Vec<String> {
+        fields.iter().map(|f| f.name.to_string()).collect()
+    }
+
+    /// Verify that bool fields get packed into one field in the place of the first bool.
+    #[test]
+    fn packs_bools_in_place_of_first_bool() {
+        let mut fields = ParsedFields {
+            fields: vec![
+                field(quote! {0}, quote! {u8}),
+                field(quote! {1}, quote! {bool}),
+                field(quote! {2}, quote! {Vec<bool>}),
+                field(quote! {3}, quote! {core::primitive::bool}),
+            ],
+            span: Span::call_site(),
+        };
+
+        fields.pack_bool_fields().unwrap();
+
+        assert_eq!(names(&fields), vec!["0", "__packed_bools", "2"]);
+        assert_eq!(names(&fields[1].packed_bools), vec!["1", "3"]);
+        let packed_ty = &fields[1].ty;
+        assert_tokens_eq(&quote! {#packed_ty}, &quote! {dipa::PackedBools<2usize>});
+
+        assert_eq!(
+            names(&unpack_bool_fields(&fields)),
+            vec!["0", "1", "2", "3"]
+        );
+    }
+
+    /// Verify that a single bool field is not packed, and neither are bools that need their own
+    /// delta.
+    #[test]
+    fn leaves_bools_that_can_not_be_packed() {
+        let mut since = field(quote! {b}, quote! {bool});
+        since.attrs.since = Some(2);
+
+        let mut fields = ParsedFields {
+            fields: vec![field(quote! {a}, quote! {bool}), since],
+            span: Span::call_site(),
+        };
+
+        fields.pack_bool_fields().unwrap();
+
+        assert_eq!(names(&fields), vec!["a", "b"]);
+    }
+
+    /// Verify that packed bools are referred to through the bools that they hold.
+    #[test]
+    fn packed_bools_references() {
+        let mut fields = ParsedFields {
+            fields: vec![
+                field(quote! {a}, quote! {bool}),
+                field(quote! {b}, quote! {bool}),
+            ],
+            span: Span::call_site(),
+        };
+        fields.pack_bool_fields().unwrap();
+
+        let paths = quote! {&[dipa::PathSegment::Field("a"), dipa::PathSegment::Field("b")]};
+
+        assert_tokens_eq(
+            &fields[0].reference(&quote! {self}),
+            &quote! {&dipa::PackedBools::new([&self.a, &self.b], #paths)},
+        );
+        assert_tokens_eq(
+            &fields[0].mut_reference(&quote! {self}),
+            &quote! {&mut dipa::PackedBoolsMut::new([&mut self.a, &mut self.b])},
+        );
+        assert_tokens_eq(
+            &fields[0].binding("start_"),
+            &quote! {(&dipa::PackedBools::new([start_a, start_b], #paths))},
+        );
+        assert_tokens_eq(
+            &fields[0].binding_mut("field_"),
+            &quote! {(&mut dipa::PackedBoolsMut::new([field_a, field_b]))},
+        );
+    }
+
+    /// Verify that bools are recognized through the paths to the primitive type, but not through
+    /// other paths that end in `bool`.
+    #[test]
+    fn primitive_bool_paths() {
+        for ty in [
+            quote! {bool},
+            quote! {core::primitive::bool},
+            quote! {std::primitive::bool},
+            quote! {::core::primitive::bool},
+        ] {
+            assert!(is_bool(&syn::parse2(ty.clone()).unwrap()), "{}", ty);
+        }
+
+        for ty in [
+            quote! {::bool},
+            quote! {my_crate::bool},
+            quote! {std::primitive::u8},
+            quote! {Option<bool>},
+        ] {
+            assert!(!is_bool(&syn::parse2(ty.clone()).unwrap()), "{}", ty);
+        }
+    }
+
+    /// Verify that more bools than fit in the bitflags are an error rather than being left
+    /// unpacked.
+    #[test]
+    fn too_many_bools() {
+        let mut fields = ParsedFields {
+            fields: (0..65)
+                .map(|idx| {
+                    let idx = syn::Index::from(idx);
+                    field(quote! {#idx}, quote! {bool})
+                })
+                .collect(),
+            span: Span::call_site(),
+        };
+
+        assert!(fields.pack_bool_fields().is_err());
+        assert_eq!(fields.len(), 65);
+
+        fields.fields.pop();
+        fields.pack_bool_fields().unwrap();
+        assert_eq!(fields.len(), 1);
+    }
+}
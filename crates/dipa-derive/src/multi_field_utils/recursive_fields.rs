@@ -0,0 +1,153 @@
+use crate::multi_field_utils::StructOrTupleField;
+use syn::{GenericArgument, Ident, PathArguments, Type};
+
+/// Whether or not a field holds the type that is being derived, such as the `Box<Node>` fields
+/// of a `Node` tree.
+///
+/// Bounding a recursive field's type in a where clause, such as with
+/// `Box<Node>: dipa::VisitDelta<'s, 'e>`, would require the impl to already hold in order to hold,
+/// which overflows when the compiler evaluates it. So we leave those fields out of the where
+/// clause. The impl that is being generated is what satisfies them.
+///
+/// Type aliases and types that hold the derived type, such as the other type in a pair of
+/// mutually recursive types, can't be seen from the field's type, so those fields need the
+/// `#[dipa(recursive)]` attribute.
+pub fn is_recursive_field(type_name: &Ident, field: &StructOrTupleField) -> bool {
+    field.attrs.recursive || is_recursive_field_ty(type_name, &field.ty)
+}
+
+/// Whether or not any path within the type ends in the type's name or in `Self`, such as
+/// `Option<Box<Node>>` or `Vec<(u8, crate::tree::Node)>`.
+pub fn is_recursive_field_ty(type_name: &Ident, field_ty: &Type) -> bool {
+    match field_ty {
+        Type::Path(ty) => {
+            let qself_is_recursive = ty
+                .qself
+                .as_ref()
+                .map(|qself| is_recursive_field_ty(type_name, &qself.ty))
+                .unwrap_or(false);
+
+            let names_type = ty
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident == *type_name || segment.ident == "Self")
+                .unwrap_or(false);
+
+            let argument_is_recursive = ty.path.segments.iter().any(|segment| {
+                let args = match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args,
+                    _ => return false,
+                };
+
+                args.args.iter().any(|arg| match arg {
+                    GenericArgument::Type(ty) => is_recursive_field_ty(type_name, ty),
+                    _ => false,
+                })
+            });
+
+            qself_is_recursive || names_type || argument_is_recursive
+        }
+        Type::Array(ty) => is_recursive_field_ty(type_name, &ty.elem),
+        Type::Slice(ty) => is_recursive_field_ty(type_name, &ty.elem),
+        Type::Reference(ty) => is_recursive_field_ty(type_name, &ty.elem),
+        Type::Ptr(ty) => is_recursive_field_ty(type_name, &ty.elem),
+        Type::Paren(ty) => is_recursive_field_ty(type_name, &ty.elem),
+        Type::Group(ty) => is_recursive_field_ty(type_name, &ty.elem),
+        Type::Tuple(ty) => ty
+            .elems
+            .iter()
+            .any(|elem| is_recursive_field_ty(type_name, elem)),
+        _ => false,
+    }
+}
+
+/// Whether or not any of the fields are recursive, see [is_recursive_field].
+pub fn has_recursive_fields<'a>(
+    type_name: &Ident,
+    fields: impl IntoIterator<Item = &'a StructOrTupleField>,
+) -> bool {
+    fields
+        .into_iter()
+        .any(|field| is_recursive_field(type_name, field))
+}
+
+/// The field types that the where clause of a generated impl needs to bound.
+///
+/// Recursive fields are left out, see [is_recursive_field].
+///
+/// Packed bools are left out as well. They implement every dipa trait, and bounding them would
+/// keep the compiler from seeing that their borrowed delta is a `dipa::BoolFlags`.
+pub fn bounded_field_tys<'a>(
+    type_name: &Ident,
+    fields: impl IntoIterator<Item = &'a StructOrTupleField>,
+) -> Vec<Type> {
+    fields
+        .into_iter()
+        .filter(|field| !is_recursive_field(type_name, field))
+        .filter(|field| !field.is_packed_bools())
+        .map(|field| field.ty.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use syn::__private::Span;
+
+    /// Verify that we find the type's name at the end of any path within a field's type.
+    #[test]
+    fn finds_recursive_field_tys() {
+        let node = format_ident!("Node");
+
+        let recursive: Vec<Type> = vec![
+            parse_quote!(Box<Node>),
+            parse_quote!(Option<Box<Node>>),
+            parse_quote!(Vec<(u8, Node)>),
+            parse_quote!(Box<Self>),
+            parse_quote!(crate::tree::Node),
+            parse_quote!(std::collections::HashMap<u8, Box<[crate::Node]>>),
+            parse_quote!(<Node as Trait>::Assoc),
+        ];
+        for ty in recursive.iter() {
+            assert!(is_recursive_field_ty(&node, ty), "{}", quote!(#ty));
+        }
+
+        let not_recursive: Vec<Type> = vec![
+            parse_quote!(u32),
+            parse_quote!(Box<NodeId>),
+            parse_quote!(Vec<Leaf>),
+            parse_quote!(Node::Id),
+            parse_quote!(node::Leaf),
+        ];
+        for ty in not_recursive.iter() {
+            assert!(!is_recursive_field_ty(&node, ty), "{}", quote!(#ty));
+        }
+    }
+
+    /// Verify that fields with the recursive attribute are left out of the bounds along with the
+    /// fields whose type names the derived type.
+    #[test]
+    fn bounded_field_tys_skip_recursive_fields() {
+        let field = |ty: Type, recursive: bool| StructOrTupleField {
+            name: quote! {field},
+            ty,
+            span: Span::call_site(),
+            attrs: DipaFieldAttrs {
+                recursive,
+                ..DipaFieldAttrs::default()
+            },
+            packed_bools: vec![],
+        };
+
+        let fields = vec![
+            field(parse_quote!(u8), false),
+            field(parse_quote!(Option<Box<Tree>>), false),
+            field(parse_quote!(Forest), true),
+        ];
+
+        let bounded = bounded_field_tys(&format_ident!("Tree"), &fields);
+        assert_eq!(bounded, vec![parse_quote!(u8)]);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{is_recursive_field, StructOrTupleField};
+use syn::__private::TokenStream2;
+use syn::{Ident, Type};
+
+/// Generate the `dipa::SchemaFingerprint` implementation, starting the hash with the type's name.
+///
+/// Like `dipa::VisitDelta`, the where clause bounds mention the `'s` and `'e` lifetimes so that a
+/// field type without a fingerprint only means that the type does not get one either.
+///
+/// `field_tys` are the field types to bound, see [crate::multi_field_utils::bounded_field_tys].
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     impl<'s, 'e> dipa::SchemaFingerprint<'s, 'e> for MyStruct
+///     where
+///         u8: dipa::SchemaFingerprint<'s, 'e>,
+///     {
+///         const FINGERPRINT: u64 = dipa::FingerprintHasher::new()
+///             .write_str("MyStruct")
+///             // ... Fingerprint writes ...
+///             .finish();
+///     }
+/// };
+/// ```
+pub fn make_schema_fingerprint_impl(
+    type_name: &Ident,
+    field_tys: &[Type],
+    fingerprint_writes: TokenStream2,
+) -> TokenStream2 {
+    let type_name_str = type_name.to_string();
+
+    quote! {
+        impl<'s, 'e> dipa::SchemaFingerprint<'s, 'e> for #type_name
+        where
+            #(#field_tys: dipa::SchemaFingerprint<'s, 'e>,)*
+        {
+            const FINGERPRINT: u64 = dipa::FingerprintHasher::new()
+                .write_str(#type_name_str)
+                #fingerprint_writes
+                .finish();
+        }
+    }
+}
+
+/// Hash a field's name along with the fingerprint of its type.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     .write_str("some_field_name")
+///     .write_u64(<u8 as dipa::SchemaFingerprint<'s, 'e>>::FINGERPRINT)
+/// };
+/// ```
+pub fn fingerprint_field_write(field_name: &TokenStream2, field_ty: &Type) -> TokenStream2 {
+    let field_name = field_name.to_string();
+    let field_name = field_name.trim_start_matches("r#");
+
+    quote! {
+        .write_str(#field_name)
+        .write_u64(<#field_ty as dipa::SchemaFingerprint<'s, 'e>>::FINGERPRINT)
+    }
+}
+
+/// Hash the number of fields followed by every field's name and type.
+///
+/// The count keeps a variant's fields from being mistaken for the names of the variants after it.
+///
+/// Packed bools are followed by the names of the bools that they hold, since the order of the
+/// names decides which bit of the delta belongs to each bool.
+///
+/// Fields with a `#[dipa(since = ...)]` attribute are followed by their schema version, since it
+/// decides which variants of the delta the field shows up in.
+///
+/// A field that holds the type itself, such as `Box<MyEnum>`, would need the fingerprint that is
+/// being computed, so the field's type is hashed by its name instead.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     .write_str("some_field_name")
+///     .write_str("Box < MyEnum >")
+/// };
+/// ```
+pub fn fingerprint_fields_writes(type_name: &Ident, fields: &[StructOrTupleField]) -> TokenStream2 {
+    let field_count = fields.len() as u64;
+    let field_writes = fields.iter().map(|field| {
+        let field_write = if is_recursive_field(type_name, field) {
+            let field_name = field.name.to_string();
+            let field_name = field_name.trim_start_matches("r#");
+            let ty = &field.ty;
+            let field_ty_str = quote!(#ty).to_string();
+
+            quote! {
+                .write_str(#field_name)
+                .write_str(#field_ty_str)
+            }
+        } else {
+            fingerprint_field_write(&field.name, &field.ty)
+        };
+        let since_write = field.attrs.since.map(|since| {
+            let since = since as u64;
+            quote! { .write_u64(#since) }
+        });
+        let packed_bool_names = field.packed_bools.iter().map(|packed_bool| {
+            let name = packed_bool.name.to_string();
+            name.trim_start_matches("r#").to_string()
+        });
+
+        quote! {
+            #field_write
+            #since_write
+            #(.write_str(#packed_bool_names))*
+        }
+    });
+
+    quote! {
+        .write_u64(#field_count)
+        #(#field_writes)*
+    }
+}
+
+/// Hash the field batching strategy, since it decides the shape of a multi field delta type.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     .write_str("one_batch")
+/// };
+/// ```
+pub fn fingerprint_batching_write(dipa_attrs: &DipaAttrs) -> TokenStream2 {
+    let strategy = dipa_attrs.field_batching_strategy.unwrap_or_default();
+    let strategy = strategy.as_str();
+
+    quote! {
+        .write_str(#strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::dipa_attribute::FieldBatchingStrategy;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+
+    /// Verify that the implementation only applies when every field type has a fingerprint.
+    #[test]
+    fn schema_fingerprint_impl_bounds() {
+        let tokens = make_schema_fingerprint_impl(
+            &format_ident!("MyStruct"),
+            &[
+                Type::Verbatim(quote! {u8}),
+                Type::Verbatim(quote! {Vec<i8>}),
+            ],
+            quote! {},
+        );
+
+        let expected = quote! {
+            impl<'s, 'e> dipa::SchemaFingerprint<'s, 'e> for MyStruct
+            where
+                u8: dipa::SchemaFingerprint<'s, 'e>,
+                Vec<i8>: dipa::SchemaFingerprint<'s, 'e>,
+            {
+                const FINGERPRINT: u64 = dipa::FingerprintHasher::new()
+                    .write_str("MyStruct")
+                    .finish();
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we hash the field count and then each field's name and type.
+    #[test]
+    fn fingerprint_two_fields() {
+        let fields = vec![
+            StructOrTupleField {
+                name: quote! {r#type},
+                ty: Type::Verbatim(quote! {u8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {fieldb},
+                ty: Type::Verbatim(quote! {Vec<i8>}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+        ];
+
+        let tokens = fingerprint_fields_writes(&format_ident!("MyStruct"), &fields);
+
+        let expected = quote! {
+            .write_u64(2u64)
+            .write_str("type")
+            .write_u64(<u8 as dipa::SchemaFingerprint<'s, 'e>>::FINGERPRINT)
+            .write_str("fieldb")
+            .write_u64(<Vec<i8> as dipa::SchemaFingerprint<'s, 'e>>::FINGERPRINT)
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that a field's since attribute is hashed after its type.
+    #[test]
+    fn fingerprint_since_field() {
+        let fields = vec![StructOrTupleField {
+            name: quote! {mana},
+            ty: Type::Verbatim(quote! {u8}),
+            span: Span::call_site(),
+            attrs: DipaFieldAttrs {
+                since: Some(2),
+                ..DipaFieldAttrs::default()
+            },
+            packed_bools: vec![],
+        }];
+
+        let tokens = fingerprint_fields_writes(&format_ident!("MyStruct"), &fields);
+
+        let expected = quote! {
+            .write_u64(1u64)
+            .write_str("mana")
+            .write_u64(<u8 as dipa::SchemaFingerprint<'s, 'e>>::FINGERPRINT)
+            .write_u64(2u64)
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that recursive fields are hashed by their type's name, whether or not the type
+    /// names the derived type.
+    #[test]
+    fn fingerprint_recursive_fields() {
+        let fields = vec![
+            StructOrTupleField {
+                name: quote! {next},
+                ty: parse_quote!(Option<Box<List>>),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {children},
+                ty: parse_quote!(Children),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs {
+                    recursive: true,
+                    ..DipaFieldAttrs::default()
+                },
+                packed_bools: vec![],
+            },
+        ];
+
+        let tokens = fingerprint_fields_writes(&format_ident!("List"), &fields);
+
+        let expected = quote! {
+            .write_u64(2u64)
+            .write_str("next")
+            .write_str("Option < Box < List > >")
+            .write_str("children")
+            .write_str("Children")
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that the default batching strategy is hashed when none is specified.
+    #[test]
+    fn fingerprint_batching_strategy() {
+        assert_tokens_eq(
+            &fingerprint_batching_write(&DipaAttrs::default()),
+            &quote! { .write_str("one_batch") },
+        );
+
+        let dipa_attrs = DipaAttrs {
+            field_batching_strategy: Some(FieldBatchingStrategy::NoBatching),
+            ..DipaAttrs::default()
+        };
+        assert_tokens_eq(
+            &fingerprint_batching_write(&dipa_attrs),
+            &quote! { .write_str("no_batching") },
+        );
+    }
+}
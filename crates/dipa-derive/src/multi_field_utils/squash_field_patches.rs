@@ -0,0 +1,294 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{ChangedFieldIndices, StructOrTupleField};
+use quote::ToTokens;
+use syn::__private::{Span, TokenStream2};
+use syn::Ident;
+
+/// Generate the tokens that squash the `first` and `second` patches for a type that uses the
+/// `one_batch` field batching strategy.
+///
+/// Each patch gets split into one `Option` per field, the fields that were changed by both
+/// patches get squashed, and then the squashed field patches get combined back into one patch.
+///
+/// A non empty `change_prefix` means that the patch type also holds the patches for other enum
+/// variants, so a patch for a different variant returns an error.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     let (first0, first1) = match first {
+///         MyStructDeltaOwned::NoChange => (None, None),
+///         MyStructDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+///         MyStructDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+///         MyStructDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+///     };
+///     let (second0, second1) = match second {
+///         // ... Same as above ...
+///     };
+///
+///     let squashed0 = match (first0, second0) {
+///         (Some(first_patch), Some(second_patch)) => Some(
+///             <u8 as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+///                 .map_err(|err| err.with_parent(dipa::PathSegment::Field("some_field_name")))?,
+///         ),
+///         (first_patch, second_patch) => second_patch.or(first_patch),
+///     };
+///     let squashed1 = match (first1, second1) {
+///         // ... Same as above ...
+///     };
+///
+///     match (squashed0, squashed1) {
+///         (None, None) => MyStructDeltaOwned::NoChange,
+///         (Some(patch0), None) => MyStructDeltaOwned::Change_0(patch0),
+///         (None, Some(patch1)) => MyStructDeltaOwned::Change_1(patch1),
+///         (Some(patch0), Some(patch1)) => MyStructDeltaOwned::Change_0_1(patch0, patch1),
+///     }
+/// };
+/// ```
+pub fn make_squash_field_patches_tokens(
+    delta_owned_ty: &TokenStream2,
+    change_prefix: &str,
+    variant_name: Option<&str>,
+    span: Span,
+    fields: &[StructOrTupleField],
+    dipa_attrs: &DipaAttrs,
+) -> TokenStream2 {
+    let first_fields = field_option_idents("first", span, fields.len());
+    let second_fields = field_option_idents("second", span, fields.len());
+    let squashed_fields = field_option_idents("squashed", span, fields.len());
+
+    let no_change = Ident::new(&format!("{}NoChange", change_prefix), span);
+
+    let mut split_arms = vec![];
+    let mut combine_arms = vec![];
+
+    let no_fields_changed = tuple(&vec![quote! {None}; fields.len()]);
+    split_arms.push(quote! {
+        #delta_owned_ty::#no_change => #no_fields_changed,
+    });
+    combine_arms.push(quote! {
+        #no_fields_changed => #delta_owned_ty::#no_change,
+    });
+
+    for changed_indices in
+        ChangedFieldIndices::all_changed_index_combinations(fields.len(), dipa_attrs)
+    {
+        let change_name = changed_indices.variant_name_ident(change_prefix, span);
+        let patches = changed_indices.patch_field_idents(span);
+
+        let field_options: Vec<TokenStream2> = (0..fields.len())
+            .map(|field_idx| {
+                match changed_indices
+                    .iter()
+                    .position(|changed| *changed as usize == field_idx)
+                {
+                    Some(patch_idx) => {
+                        let patch = &patches[patch_idx];
+                        quote! {Some(#patch)}
+                    }
+                    None => quote! {None},
+                }
+            })
+            .collect();
+        let field_options = tuple(&field_options);
+
+        split_arms.push(quote! {
+            #delta_owned_ty::#change_name(#(#patches),*) => #field_options,
+        });
+        combine_arms.push(quote! {
+            #field_options => #delta_owned_ty::#change_name(#(#patches),*),
+        });
+    }
+
+    if !change_prefix.is_empty() {
+        split_arms.push(quote! {
+            _ => return Err(dipa::PatchError::new(
+                dipa::PatchErrorReason::VariantMismatch { expected: #change_prefix }
+            )),
+        });
+    }
+
+    let squash_statements = fields.iter().enumerate().map(|(idx, field)| {
+        let ty = &field.ty;
+        let first = &first_fields[idx];
+        let second = &second_fields[idx];
+        let squashed = &squashed_fields[idx];
+
+        let path_segment = field.path_segment();
+        let variant_segment = variant_name.map(|variant_name| {
+            quote! { .with_parent(dipa::PathSegment::Variant(#variant_name)) }
+        });
+
+        quote! {
+            let #squashed = match (#first, #second) {
+                (Some(first_patch), Some(second_patch)) => Some(
+                    <#ty as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+                        .map_err(|err| err.with_parent(#path_segment)#variant_segment)?,
+                ),
+                (first_patch, second_patch) => second_patch.or(first_patch),
+            };
+        }
+    });
+
+    let first_fields = tuple(&first_fields);
+    let second_fields = tuple(&second_fields);
+    let squashed_fields = tuple(&squashed_fields);
+
+    quote! {
+        let #first_fields = match first {
+            #(#split_arms)*
+        };
+        let #second_fields = match second {
+            #(#split_arms)*
+        };
+
+        #(#squash_statements)*
+
+        match #squashed_fields {
+            #(#combine_arms)*
+        }
+    }
+}
+
+/// (a, b, c), or just `a` when there is only one item so that we don't generate tokens that
+/// trigger the `unused_parens` lint.
+fn tuple<T: ToTokens>(items: &[T]) -> TokenStream2 {
+    match items {
+        [item] => quote! {#item},
+        items => quote! {(#(#items),*)},
+    }
+}
+
+/// first0, first1, first2, ...
+fn field_option_idents(prefix: &str, span: Span, field_count: usize) -> Vec<Ident> {
+    (0..field_count)
+        .map(|idx| Ident::new(&format!("{}{}", prefix, idx), span))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::Type;
+
+    /// Verify that we generate the tokens that squash the patches of a struct with two fields.
+    #[test]
+    fn squash_two_fields() {
+        let tokens = make_squash_field_patches_tokens(
+            &quote! {MyStructDeltaOwned},
+            "",
+            None,
+            Span::call_site(),
+            &two_fields(),
+            &DipaAttrs::default(),
+        );
+
+        let expected = quote! {
+            let (first0, first1) = match first {
+                MyStructDeltaOwned::NoChange => (None, None),
+                MyStructDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyStructDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyStructDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+            let (second0, second1) = match second {
+                MyStructDeltaOwned::NoChange => (None, None),
+                MyStructDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyStructDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyStructDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+
+            let squashed0 = match (first0, second0) {
+                (Some(first_patch), Some(second_patch)) => Some(
+                    <u8 as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+                        .map_err(|err| err.with_parent(dipa::PathSegment::Field("fielda")))?,
+                ),
+                (first_patch, second_patch) => second_patch.or(first_patch),
+            };
+            let squashed1 = match (first1, second1) {
+                (Some(first_patch), Some(second_patch)) => Some(
+                    <i8 as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+                        .map_err(|err| err.with_parent(dipa::PathSegment::Field("fieldb")))?,
+                ),
+                (first_patch, second_patch) => second_patch.or(first_patch),
+            };
+
+            match (squashed0, squashed1) {
+                (None, None) => MyStructDeltaOwned::NoChange,
+                (Some(patch0), None) => MyStructDeltaOwned::Change_0(patch0),
+                (None, Some(patch1)) => MyStructDeltaOwned::Change_1(patch1),
+                (Some(patch0), Some(patch1)) => MyStructDeltaOwned::Change_0_1(patch0, patch1),
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that squashing the patches for one variant of a multi variant enum returns an error
+    /// when given a patch for another variant.
+    #[test]
+    fn squash_prefixed_variant_fields() {
+        let tokens = make_squash_field_patches_tokens(
+            &quote! {MyEnumDeltaOwned},
+            "Two",
+            Some("Two"),
+            Span::call_site(),
+            &two_fields()[0..1],
+            &DipaAttrs::default(),
+        );
+
+        let expected = quote! {
+            let first0 = match first {
+                MyEnumDeltaOwned::TwoNoChange => None,
+                MyEnumDeltaOwned::TwoChange_0(patch0) => Some(patch0),
+                _ => return Err(dipa::PatchError::new(
+                    dipa::PatchErrorReason::VariantMismatch { expected: "Two" }
+                )),
+            };
+            let second0 = match second {
+                MyEnumDeltaOwned::TwoNoChange => None,
+                MyEnumDeltaOwned::TwoChange_0(patch0) => Some(patch0),
+                _ => return Err(dipa::PatchError::new(
+                    dipa::PatchErrorReason::VariantMismatch { expected: "Two" }
+                )),
+            };
+
+            let squashed0 = match (first0, second0) {
+                (Some(first_patch), Some(second_patch)) => Some(
+                    <u8 as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+                        .map_err(|err| err
+                            .with_parent(dipa::PathSegment::Field("fielda"))
+                            .with_parent(dipa::PathSegment::Variant("Two")))?,
+                ),
+                (first_patch, second_patch) => second_patch.or(first_patch),
+            };
+
+            match squashed0 {
+                None => MyEnumDeltaOwned::TwoNoChange,
+                Some(patch0) => MyEnumDeltaOwned::TwoChange_0(patch0),
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn two_fields() -> Vec<StructOrTupleField> {
+        vec![
+            StructOrTupleField {
+                name: quote! {fielda},
+                ty: Type::Verbatim(quote! {u8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {fieldb},
+                ty: Type::Verbatim(quote! {i8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+        ]
+    }
+}
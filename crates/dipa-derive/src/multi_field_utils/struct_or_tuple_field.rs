@@ -1,3 +1,4 @@
+use crate::dipa_attribute::DipaFieldAttrs;
 use quote::__private::Span;
 use std::ops::{Deref, DerefMut};
 use syn::__private::TokenStream2;
@@ -19,16 +20,38 @@ pub struct StructOrTupleField {
     pub name: TokenStream2,
     pub ty: Type,
     pub span: Span,
+    pub attrs: DipaFieldAttrs,
+    /// The bool fields that this field holds if it is the packed bools of its struct or enum
+    /// variant, otherwise empty.
+    pub packed_bools: Vec<StructOrTupleField>,
 }
 
 impl StructOrTupleField {
     /// "a_prefix":
     ///   some_name -> a_prefix_some_name
     pub fn prefixed_name(&self, prefix: &'static str) -> Ident {
-        Ident::new(
-            &format!("{}{}", prefix, self.name.to_string()),
-            self.name.span(),
-        )
+        Ident::new(&format!("{}{}", prefix, self.name), self.name.span())
+    }
+
+    /// dipa::PathSegment::Field("some_name") or dipa::PathSegment::TupleField(0)
+    pub fn path_segment(&self) -> TokenStream2 {
+        field_path_segment(&self.name)
+    }
+}
+
+/// The `dipa::PathSegment` that a patch error for a field gets wrapped in.
+///
+/// some_name -> dipa::PathSegment::Field("some_name")
+/// 0 -> dipa::PathSegment::TupleField(0)
+pub fn field_path_segment(field_name: &TokenStream2) -> TokenStream2 {
+    let field_name = field_name.to_string();
+
+    match field_name.parse::<usize>() {
+        Ok(idx) => quote! { dipa::PathSegment::TupleField(#idx) },
+        Err(_) => {
+            let field_name = field_name.trim_start_matches("r#");
+            quote! { dipa::PathSegment::Field(#field_name) }
+        }
     }
 }
 
@@ -1,7 +1,7 @@
 use crate::dipa_attribute::{DipaAttrs, FieldBatchingStrategy};
-use crate::multi_field_utils::ParsedFields;
-use syn::Ident;
+use crate::multi_field_utils::{bounded_field_tys, ParsedFields};
 use syn::__private::TokenStream2;
+use syn::{Ident, Type};
 
 mod no_batching;
 mod one_batch;
@@ -9,26 +9,24 @@ mod one_batch;
 impl ParsedFields {
     /// Given named or unnamed fields return a Delta type that encompasses all of those fields.
     /// Depending on the field batching strategy this might be an enum or a struct.
-    pub fn generate_delta_types(&self, prefix: &str, dipa_attrs: &DipaAttrs) -> TokenStream2 {
-        if self.len() < 2 {
+    pub fn generate_delta_types(&self, type_name: &Ident, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        // A single field only makes it here when it holds the packed bools of multiple fields.
+        if self.len() < 2 && !self.iter().any(|field| field.is_packed_bools()) {
             unreachable!(
                 r#"Out logic is spread out a bit. Need to move the logic for 0 and 1 field
 into this function."#
             )
         }
 
-        match dipa_attrs
-            .field_batching_strategy
-            .unwrap_or(FieldBatchingStrategy::default())
-        {
+        match dipa_attrs.field_batching_strategy.unwrap_or_default() {
             FieldBatchingStrategy::OneBatch => {
-                self.generate_delta_type_one_batch(prefix, dipa_attrs)
+                self.generate_delta_type_one_batch(type_name, dipa_attrs)
             }
             FieldBatchingStrategy::ManyBatches => {
                 todo!(r#"Many batches strategy is not yet implemented."#)
             }
             FieldBatchingStrategy::NoBatching => {
-                self.generate_delta_type_no_batching(prefix, dipa_attrs)
+                self.generate_delta_type_no_batching(type_name, dipa_attrs)
             }
         }
     }
@@ -40,4 +38,14 @@ into this function."#
     pub fn delta_owned_name(&self, prefix: &str) -> Ident {
         Ident::new(&format!("{}DeltaOwned", prefix), self.span)
     }
+
+    pub fn delta_borrowed_name(&self, prefix: &str) -> Ident {
+        Ident::new(&format!("{}DeltaBorrowed", prefix), self.span)
+    }
+
+    /// The field types that the where clauses of the generated impls bound, see
+    /// [bounded_field_tys].
+    pub fn bounded_field_tys(&self, type_name: &Ident) -> Vec<Type> {
+        bounded_field_tys(type_name, &self.fields)
+    }
 }
@@ -1,18 +1,26 @@
 use crate::dipa_attribute::DipaAttrs;
-use crate::multi_field_utils::ParsedFields;
+use crate::multi_field_utils::{
+    borrowed_delta_serde_bound, borrowed_delta_where_clause, field_delta_to_owned_bounds,
+    has_recursive_fields, make_to_owned_delta_impl, ParsedFields,
+};
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 impl ParsedFields {
     pub(super) fn generate_delta_type_no_batching(
         &self,
-        prefix: &str,
+        type_name: &Ident,
         dipa_attrs: &DipaAttrs,
     ) -> TokenStream2 {
-        let delta_name = self.delta_name(prefix);
-        let delta_owned_name = self.delta_owned_name(prefix);
+        let prefix = type_name.to_string();
+        let delta_name = self.delta_name(&prefix);
+        let delta_owned_name = self.delta_owned_name(&prefix);
+        let delta_borrowed_name = self.delta_borrowed_name(&prefix);
 
         let mut delta_fields = vec![];
         let mut delta_owned_fields = vec![];
+        let mut delta_borrowed_fields = vec![];
+        let mut to_owned_fields = vec![];
 
         for field in self.fields.iter() {
             let field_name = &field.name;
@@ -24,8 +32,30 @@ impl ParsedFields {
             delta_owned_fields.push(quote! {
                 #field_name: <#ty as dipa::Diffable<'static, 'static, #ty>>::DeltaOwned
             });
+            delta_borrowed_fields.push(quote! {
+                #field_name: <#ty as dipa::BorrowedDelta<'b>>::DeltaBorrowed
+            });
+            to_owned_fields.push(quote! {
+                #field_name: dipa::ToOwnedDelta::to_owned_delta(self.#field_name)
+            });
         }
 
+        let to_owned_impl = make_to_owned_delta_impl(
+            &delta_name,
+            &delta_owned_name,
+            &field_delta_to_owned_bounds(type_name, &self.fields),
+            quote! {
+                #delta_owned_name {
+                    #(#to_owned_fields),*
+                }
+            },
+        );
+
+        let field_tys = self.bounded_field_tys(type_name);
+        let is_recursive = has_recursive_fields(type_name, &self.fields);
+        let borrowed_serde_bound = borrowed_delta_serde_bound(&field_tys, &[], is_recursive);
+        let borrowed_where_clause = borrowed_delta_where_clause(&field_tys, is_recursive);
+
         let (diff_derives, patch_derives) = (&dipa_attrs.diff_derives, &dipa_attrs.patch_derives);
 
         quote! {
@@ -40,6 +70,15 @@ impl ParsedFields {
             pub struct #delta_owned_name {
                 #(#delta_owned_fields),*
             }
+
+            #[derive(serde::Deserialize)]
+            #borrowed_serde_bound
+            #[allow(non_camel_case_types)]
+            pub struct #delta_borrowed_name<'b> #borrowed_where_clause {
+                #(#delta_borrowed_fields),*
+            }
+
+            #to_owned_impl
         }
     }
 }
@@ -47,18 +86,21 @@ impl ParsedFields {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
     use crate::dipa_attribute::FieldBatchingStrategy;
     use crate::multi_field_utils::StructOrTupleField;
     use crate::test_utils::assert_tokens_eq;
-    use syn::Type;
     use syn::__private::Span;
+    use syn::Type;
 
     /// Verify that if the batching strategy is one_batch that we generate a single enum as the
     /// delta type.
     #[test]
     fn generates_no_batching_delta() {
-        let mut attrs = DipaAttrs::default();
-        attrs.field_batching_strategy = Some(FieldBatchingStrategy::NoBatching);
+        let attrs = DipaAttrs {
+            field_batching_strategy: Some(FieldBatchingStrategy::NoBatching),
+            ..DipaAttrs::default()
+        };
 
         let parsed_fields = ParsedFields {
             fields: vec![
@@ -66,16 +108,20 @@ mod tests {
                     name: quote! {field_a},
                     ty: Type::Verbatim(quote! {u16}),
                     span: Span::call_site(),
+                    attrs: DipaFieldAttrs::default(),
+                    packed_bools: vec![],
                 },
                 StructOrTupleField {
                     name: quote! {field_b},
                     ty: Type::Verbatim(quote! {u32}),
                     span: Span::call_site(),
+                    attrs: DipaFieldAttrs::default(),
+                    packed_bools: vec![],
                 },
             ],
             span: Span::call_site(),
         };
-        let tokens = parsed_fields.generate_delta_types("MyStruct", &attrs);
+        let tokens = parsed_fields.generate_delta_types(&format_ident!("MyStruct"), &attrs);
 
         let expected = quote! {
             #[derive(serde::Serialize,)]
@@ -91,6 +137,35 @@ mod tests {
                 field_a: <u16 as dipa::Diffable<'static, 'static, u16>>::DeltaOwned,
                 field_b: <u32 as dipa::Diffable<'static, 'static, u32>>::DeltaOwned
             }
+
+            #[derive(serde::Deserialize)]
+            #[serde(bound(
+                deserialize = "< u16 as dipa :: BorrowedDelta < 'b >> :: DeltaBorrowed : serde :: Deserialize < 'de > , < u32 as dipa :: BorrowedDelta < 'b >> :: DeltaBorrowed : serde :: Deserialize < 'de > ,"
+            ))]
+            #[allow(non_camel_case_types)]
+            pub struct MyStructDeltaBorrowed<'b>
+            where
+                u16: dipa::BorrowedDelta<'b>,
+                u32: dipa::BorrowedDelta<'b>,
+            {
+                field_a: <u16 as dipa::BorrowedDelta<'b>>::DeltaBorrowed,
+                field_b: <u32 as dipa::BorrowedDelta<'b>>::DeltaBorrowed
+            }
+
+            impl<'s, 'e> dipa::ToOwnedDelta<MyStructDeltaOwned> for MyStructDelta<'s, 'e>
+            where
+                <u16 as dipa::Diffable<'s, 'e, u16>>::Delta:
+                    dipa::ToOwnedDelta<<u16 as dipa::Diffable<'static, 'static, u16>>::DeltaOwned>,
+                <u32 as dipa::Diffable<'s, 'e, u32>>::Delta:
+                    dipa::ToOwnedDelta<<u32 as dipa::Diffable<'static, 'static, u32>>::DeltaOwned>
+            {
+                fn to_owned_delta(self) -> MyStructDeltaOwned {
+                    MyStructDeltaOwned {
+                        field_a: dipa::ToOwnedDelta::to_owned_delta(self.field_a),
+                        field_b: dipa::ToOwnedDelta::to_owned_delta(self.field_b)
+                    }
+                }
+            }
         };
 
         assert_tokens_eq(&tokens, &expected);
@@ -1,27 +1,35 @@
 use crate::dipa_attribute::DipaAttrs;
-use crate::multi_field_utils::{ChangedFieldIndices, ParsedFields};
+use crate::multi_field_utils::{
+    borrowed_delta_serde_bound, borrowed_delta_where_clause, field_delta_to_owned_bounds,
+    has_recursive_fields, make_to_owned_delta_impl, make_to_owned_field_delta_arms,
+    ChangedFieldIndices, ParsedFields,
+};
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 impl ParsedFields {
     pub(super) fn generate_delta_type_one_batch(
         &self,
-        prefix: &str,
+        type_name: &Ident,
         dipa_attrs: &DipaAttrs,
     ) -> TokenStream2 {
-        let delta_name = self.delta_name(prefix);
-        let delta_owned_name = self.delta_owned_name(prefix);
+        let prefix = type_name.to_string();
+        let delta_name = self.delta_name(&prefix);
+        let delta_owned_name = self.delta_owned_name(&prefix);
+        let delta_borrowed_name = self.delta_borrowed_name(&prefix);
 
-        let changed_field_indices =
-            ChangedFieldIndices::all_changed_index_combinations(self.fields.len(), dipa_attrs);
+        let changed_field_indices = self.changed_field_indices_by_schema_version(dipa_attrs);
 
         let mut ref_variants = vec![];
         let mut owned_variants = vec![];
+        let mut borrowed_variants = vec![];
 
         for change_combinations in changed_field_indices {
             let variant_name = change_combinations.variant_name_ident("", self.span);
 
             let mut changed_delta_tys = vec![];
             let mut changed_owned_tys = vec![];
+            let mut changed_borrowed_tys = vec![];
 
             for idx in change_combinations.iter() {
                 let ty = &self.fields[*idx as usize].ty;
@@ -31,28 +39,64 @@ impl ParsedFields {
                 changed_owned_tys.push(quote! {
                     <#ty as dipa::Diffable<'static, 'static, #ty>>::DeltaOwned
                 });
+                changed_borrowed_tys.push(quote! {
+                    <#ty as dipa::BorrowedDelta<'b>>::DeltaBorrowed
+                });
             }
 
-            let changed_delta_tys = if changed_delta_tys.len() == 0 {
+            let changed_delta_tys = if changed_delta_tys.is_empty() {
                 quote! {}
             } else {
                 quote! {(#(#changed_delta_tys),*)}
             };
 
-            let changed_owned_tys = if changed_owned_tys.len() == 0 {
+            let changed_owned_tys = if changed_owned_tys.is_empty() {
                 quote! {}
             } else {
                 quote! {(#(#changed_owned_tys),*)}
             };
 
+            let changed_borrowed_tys = if changed_borrowed_tys.is_empty() {
+                quote! {}
+            } else {
+                quote! {(#(#changed_borrowed_tys),*)}
+            };
+
             ref_variants.push(quote! {
                 #variant_name#changed_delta_tys
             });
             owned_variants.push(quote! {
                 #variant_name#changed_owned_tys
             });
+            borrowed_variants.push(quote! {
+                #variant_name#changed_borrowed_tys
+            });
         }
 
+        let to_owned_arms = make_to_owned_field_delta_arms(
+            &delta_name,
+            &delta_owned_name,
+            "",
+            self.span,
+            self.fields.len(),
+            dipa_attrs,
+        );
+        let to_owned_impl = make_to_owned_delta_impl(
+            &delta_name,
+            &delta_owned_name,
+            &field_delta_to_owned_bounds(type_name, &self.fields),
+            quote! {
+                match self {
+                    #(#to_owned_arms)*
+                }
+            },
+        );
+
+        let field_tys = self.bounded_field_tys(type_name);
+        let is_recursive = has_recursive_fields(type_name, &self.fields);
+        let borrowed_serde_bound = borrowed_delta_serde_bound(&field_tys, &[], is_recursive);
+        let borrowed_where_clause = borrowed_delta_where_clause(&field_tys, is_recursive);
+
         let (diff_derives, patch_derives) = (&dipa_attrs.diff_derives, &dipa_attrs.patch_derives);
 
         quote! {
@@ -69,25 +113,66 @@ impl ParsedFields {
                 NoChange,
                 #(#owned_variants),*
             }
+
+            #[derive(serde::Deserialize)]
+            #borrowed_serde_bound
+            #[allow(non_camel_case_types, missing_docs)]
+            pub enum #delta_borrowed_name<'b> #borrowed_where_clause {
+                NoChange,
+                #(#borrowed_variants),*
+            }
+
+            #to_owned_impl
         }
     }
 }
 
+impl ParsedFields {
+    /// Every combination of changed fields, with the combinations that only hold fields from
+    /// earlier schema versions coming first.
+    ///
+    /// Adding a `#[dipa(since = ...)]` field then only appends variants to the delta type instead
+    /// of shifting the indices that the existing variants are serialized with.
+    ///
+    /// So for 3 fields where the last one has `#[dipa(since = 2)]` it would be
+    ///     [0], [1], [0, 1], [2], [0, 2], [1, 2], [0, 1, 2]
+    fn changed_field_indices_by_schema_version(
+        &self,
+        dipa_attrs: &DipaAttrs,
+    ) -> Vec<ChangedFieldIndices> {
+        let mut changed_field_indices =
+            ChangedFieldIndices::all_changed_index_combinations(self.fields.len(), dipa_attrs);
+
+        // A stable sort, so combinations from the same version keep their usual order.
+        changed_field_indices.sort_by_key(|change_combinations| {
+            change_combinations
+                .iter()
+                .map(|idx| self.fields[*idx as usize].attrs.schema_version())
+                .max()
+        });
+
+        changed_field_indices
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
     use crate::dipa_attribute::FieldBatchingStrategy;
     use crate::multi_field_utils::StructOrTupleField;
     use crate::test_utils::assert_tokens_eq;
-    use syn::Type;
     use syn::__private::Span;
+    use syn::Type;
 
     /// Verify that if the batching strategy is one_batch that we generate a single enum as the
     /// delta type.
     #[test]
     fn generates_one_batch() {
-        let mut attrs = DipaAttrs::default();
-        attrs.field_batching_strategy = Some(FieldBatchingStrategy::OneBatch);
+        let attrs = DipaAttrs {
+            field_batching_strategy: Some(FieldBatchingStrategy::OneBatch),
+            ..DipaAttrs::default()
+        };
 
         let parsed_fields = ParsedFields {
             fields: vec![
@@ -95,16 +180,20 @@ mod tests {
                     name: Default::default(),
                     ty: Type::Verbatim(quote! {u16}),
                     span: Span::call_site(),
+                    attrs: DipaFieldAttrs::default(),
+                    packed_bools: vec![],
                 },
                 StructOrTupleField {
                     name: Default::default(),
                     ty: Type::Verbatim(quote! {u32}),
                     span: Span::call_site(),
+                    attrs: DipaFieldAttrs::default(),
+                    packed_bools: vec![],
                 },
             ],
             span: Span::call_site(),
         };
-        let tokens = parsed_fields.generate_delta_types("MyStruct", &attrs);
+        let tokens = parsed_fields.generate_delta_types(&format_ident!("MyStruct"), &attrs);
 
         let expected = quote! {
             #[derive(serde::Serialize,)]
@@ -130,8 +219,94 @@ mod tests {
                     <u32 as dipa::Diffable<'static, 'static, u32>>::DeltaOwned
                 )
             }
+
+            #[derive(serde::Deserialize)]
+            #[serde(bound(
+                deserialize = "< u16 as dipa :: BorrowedDelta < 'b >> :: DeltaBorrowed : serde :: Deserialize < 'de > , < u32 as dipa :: BorrowedDelta < 'b >> :: DeltaBorrowed : serde :: Deserialize < 'de > ,"
+            ))]
+            #[allow(non_camel_case_types, missing_docs)]
+            pub enum MyStructDeltaBorrowed<'b>
+            where
+                u16: dipa::BorrowedDelta<'b>,
+                u32: dipa::BorrowedDelta<'b>,
+            {
+                NoChange,
+                Change_0(<u16 as dipa::BorrowedDelta<'b>>::DeltaBorrowed),
+                Change_1(<u32 as dipa::BorrowedDelta<'b>>::DeltaBorrowed),
+                Change_0_1(
+                    <u16 as dipa::BorrowedDelta<'b>>::DeltaBorrowed,
+                    <u32 as dipa::BorrowedDelta<'b>>::DeltaBorrowed
+                )
+            }
+
+            impl<'s, 'e> dipa::ToOwnedDelta<MyStructDeltaOwned> for MyStructDelta<'s, 'e>
+            where
+                <u16 as dipa::Diffable<'s, 'e, u16>>::Delta:
+                    dipa::ToOwnedDelta<<u16 as dipa::Diffable<'static, 'static, u16>>::DeltaOwned>,
+                <u32 as dipa::Diffable<'s, 'e, u32>>::Delta:
+                    dipa::ToOwnedDelta<<u32 as dipa::Diffable<'static, 'static, u32>>::DeltaOwned>
+            {
+                fn to_owned_delta(self) -> MyStructDeltaOwned {
+                    match self {
+                        MyStructDelta::NoChange => MyStructDeltaOwned::NoChange,
+                        MyStructDelta::Change_0(patch0) => MyStructDeltaOwned::Change_0(
+                            dipa::ToOwnedDelta::to_owned_delta(patch0)
+                        ),
+                        MyStructDelta::Change_1(patch1) => MyStructDeltaOwned::Change_1(
+                            dipa::ToOwnedDelta::to_owned_delta(patch1)
+                        ),
+                        MyStructDelta::Change_0_1(patch0, patch1) => MyStructDeltaOwned::Change_0_1(
+                            dipa::ToOwnedDelta::to_owned_delta(patch0),
+                            dipa::ToOwnedDelta::to_owned_delta(patch1)
+                        ),
+                    }
+                }
+            }
         };
 
         assert_tokens_eq(&tokens, &expected);
     }
+
+    /// Verify that the combinations that hold fields from later schema versions come last.
+    #[test]
+    fn orders_combinations_by_schema_version() {
+        let field = |since| StructOrTupleField {
+            name: Default::default(),
+            ty: Type::Verbatim(quote! {u16}),
+            span: Span::call_site(),
+            attrs: DipaFieldAttrs {
+                since,
+                ..DipaFieldAttrs::default()
+            },
+            packed_bools: vec![],
+        };
+
+        let parsed_fields = ParsedFields {
+            fields: vec![field(None), field(None), field(Some(2))],
+            span: Span::call_site(),
+        };
+
+        let variant_names: Vec<String> = parsed_fields
+            .changed_field_indices_by_schema_version(&DipaAttrs::default())
+            .iter()
+            .map(|indices| {
+                indices
+                    .variant_name_ident("", Span::call_site())
+                    .to_string()
+            })
+            .collect();
+
+        assert_eq!(
+            variant_names,
+            vec![
+                "Change_0",
+                "Change_1",
+                "Change_0_1",
+                "Change_2",
+                "Change_0_2",
+                "Change_1_2",
+                "Change_0_1_2"
+            ]
+        );
+    }
 }
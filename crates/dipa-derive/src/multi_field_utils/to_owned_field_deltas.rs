@@ -0,0 +1,177 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{is_recursive_field, ChangedFieldIndices, StructOrTupleField};
+use syn::__private::{Span, TokenStream2};
+use syn::Ident;
+
+/// Generate the `dipa::ToOwnedDelta` implementation that converts a delta type into its owned
+/// delta type.
+///
+/// The where clause bounds mention the `'s` and `'e` lifetimes, so a bound that does not hold
+/// (such as a field type that does not implement `Clone`) only means that the implementation can
+/// not be used instead of causing a compile time error.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     impl<'s, 'e> dipa::ToOwnedDelta<MyStructDeltaOwned> for MyStructDelta<'s, 'e>
+///     where
+///         <u8 as dipa::Diffable<'s, 'e, u8>>::Delta:
+///             dipa::ToOwnedDelta<<u8 as dipa::Diffable<'static, 'static, u8>>::DeltaOwned>,
+///     {
+///         fn to_owned_delta(self) -> MyStructDeltaOwned {
+///             // ... Conversion tokens ...
+///         }
+///     }
+/// };
+/// ```
+pub fn make_to_owned_delta_impl(
+    delta_ty: &Ident,
+    delta_owned_ty: &Ident,
+    bounds: &[TokenStream2],
+    conversion_tokens: TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        impl<'s, 'e> dipa::ToOwnedDelta<#delta_owned_ty> for #delta_ty<'s, 'e>
+        where
+            #(#bounds),*
+        {
+            fn to_owned_delta(self) -> #delta_owned_ty {
+                #conversion_tokens
+            }
+        }
+    }
+}
+
+/// The bounds that allow the delta for each field to be converted into the field's owned delta.
+///
+/// Recursive fields are not bounded, see [is_recursive_field].
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     <u8 as dipa::Diffable<'s, 'e, u8>>::Delta:
+///         dipa::ToOwnedDelta<<u8 as dipa::Diffable<'static, 'static, u8>>::DeltaOwned>
+/// };
+/// ```
+pub fn field_delta_to_owned_bounds(
+    type_name: &Ident,
+    fields: &[StructOrTupleField],
+) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .filter(|field| !is_recursive_field(type_name, field))
+        .map(|field| {
+            let ty = &field.ty;
+
+            quote! {
+                <#ty as dipa::Diffable<'s, 'e, #ty>>::Delta:
+                    dipa::ToOwnedDelta<<#ty as dipa::Diffable<'static, 'static, #ty>>::DeltaOwned>
+            }
+        })
+        .collect()
+}
+
+/// Generate the match arms that convert every variant of a delta type that uses the `one_batch`
+/// field batching strategy into the same variant of the owned delta type.
+///
+/// A non empty `change_prefix` is used for the variants of a multi variant enum's delta type.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     MyStructDelta::NoChange => MyStructDeltaOwned::NoChange,
+///     MyStructDelta::Change_0(patch0) => MyStructDeltaOwned::Change_0(
+///         dipa::ToOwnedDelta::to_owned_delta(patch0)
+///     ),
+///     // ...
+/// };
+/// ```
+pub fn make_to_owned_field_delta_arms(
+    delta_ty: &Ident,
+    delta_owned_ty: &Ident,
+    change_prefix: &str,
+    span: Span,
+    field_count: usize,
+    dipa_attrs: &DipaAttrs,
+) -> Vec<TokenStream2> {
+    let no_change = Ident::new(&format!("{}NoChange", change_prefix), span);
+
+    let mut arms = vec![quote! {
+        #delta_ty::#no_change => #delta_owned_ty::#no_change,
+    }];
+
+    for changed_indices in
+        ChangedFieldIndices::all_changed_index_combinations(field_count, dipa_attrs)
+    {
+        let change_name = changed_indices.variant_name_ident(change_prefix, span);
+        let patches = changed_indices.patch_field_idents(span);
+
+        arms.push(quote! {
+            #delta_ty::#change_name(#(#patches),*) => #delta_owned_ty::#change_name(
+                #(dipa::ToOwnedDelta::to_owned_delta(#patches)),*
+            ),
+        });
+    }
+
+    arms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::Type;
+
+    /// Verify that we generate the arms that convert each variant of a delta type into its owned
+    /// form.
+    #[test]
+    fn to_owned_two_fields() {
+        let arms = make_to_owned_field_delta_arms(
+            &Ident::new("MyEnumDelta", Span::call_site()),
+            &Ident::new("MyEnumDeltaOwned", Span::call_site()),
+            "Two",
+            Span::call_site(),
+            2,
+            &DipaAttrs::default(),
+        );
+
+        let expected = quote! {
+            MyEnumDelta::TwoNoChange => MyEnumDeltaOwned::TwoNoChange,
+            MyEnumDelta::TwoChange_0(patch0) => MyEnumDeltaOwned::TwoChange_0(
+                dipa::ToOwnedDelta::to_owned_delta(patch0)
+            ),
+            MyEnumDelta::TwoChange_1(patch1) => MyEnumDeltaOwned::TwoChange_1(
+                dipa::ToOwnedDelta::to_owned_delta(patch1)
+            ),
+            MyEnumDelta::TwoChange_0_1(patch0, patch1) => MyEnumDeltaOwned::TwoChange_0_1(
+                dipa::ToOwnedDelta::to_owned_delta(patch0),
+                dipa::ToOwnedDelta::to_owned_delta(patch1)
+            ),
+        };
+
+        assert_tokens_eq(&quote! {#(#arms)*}, &expected);
+    }
+
+    /// Verify that we bound the delta of every field.
+    #[test]
+    fn field_bounds() {
+        let fields = vec![StructOrTupleField {
+            name: quote! {fielda},
+            ty: Type::Verbatim(quote! {Vec<u8>}),
+            span: Span::call_site(),
+            attrs: DipaFieldAttrs::default(),
+            packed_bools: vec![],
+        }];
+
+        let bounds = field_delta_to_owned_bounds(&format_ident!("MyStruct"), &fields);
+
+        let expected = quote! {
+            <Vec<u8> as dipa::Diffable<'s, 'e, Vec<u8> >>::Delta: dipa::ToOwnedDelta<
+                <Vec<u8> as dipa::Diffable<'static, 'static, Vec<u8> >>::DeltaOwned
+            >
+        };
+
+        assert_tokens_eq(&quote! {#(#bounds),*}, &expected);
+    }
+}
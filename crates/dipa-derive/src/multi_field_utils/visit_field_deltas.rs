@@ -0,0 +1,274 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{ChangedFieldIndices, StructOrTupleField};
+use syn::__private::{Span, TokenStream2};
+use syn::{Ident, Type};
+
+/// Generate the `dipa::VisitDelta` implementation that describes the changes in a delta.
+///
+/// The where clause bounds mention the `'s` and `'e` lifetimes, so a field type that does not
+/// implement `dipa::VisitDelta` only means that the implementation can not be used instead of
+/// causing a compile time error.
+///
+/// `field_tys` are the field types to bound, see [crate::multi_field_utils::bounded_field_tys].
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     impl<'s, 'e> dipa::VisitDelta<'s, 'e> for MyStruct
+///     where
+///         u8: dipa::VisitDelta<'s, 'e>,
+///     {
+///         fn visit_delta(
+///             &self,
+///             delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+///             visitor: &mut dyn dipa::DeltaVisitor,
+///         ) {
+///             // ... Visit delta tokens ...
+///         }
+///     }
+/// };
+/// ```
+pub fn make_visit_delta_impl(
+    type_name: &Ident,
+    field_tys: &[Type],
+    visit_delta_inner: TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        impl<'s, 'e> dipa::VisitDelta<'s, 'e> for #type_name
+        where
+            #(#field_tys: dipa::VisitDelta<'s, 'e>,)*
+        {
+            fn visit_delta(
+                &self,
+                delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+                visitor: &mut dyn dipa::DeltaVisitor,
+            ) {
+                #visit_delta_inner
+            }
+        }
+    }
+}
+
+/// Visit the delta for one field, where `start_field` is a reference to the field's start value.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     visitor.enter(dipa::PathSegment::Field("some_field_name"));
+///     <u8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.some_field_name, delta0, visitor);
+///     visitor.exit();
+/// };
+/// ```
+///
+/// Packed bools enter the path of each bool that changed on their own.
+pub fn visit_field_delta_tokens(
+    field: &StructOrTupleField,
+    start_field: &TokenStream2,
+    delta: &TokenStream2,
+) -> TokenStream2 {
+    let ty = &field.ty;
+    let path_segment = field.path_segment();
+
+    if field.is_packed_bools() {
+        return quote! {
+            <#ty as dipa::VisitDelta<'s, 'e>>::visit_delta(#start_field, #delta, visitor);
+        };
+    }
+
+    quote! {
+        visitor.enter(#path_segment);
+        <#ty as dipa::VisitDelta<'s, 'e>>::visit_delta(#start_field, #delta, visitor);
+        visitor.exit();
+    }
+}
+
+/// Generate the match arms that visit the changed fields of a delta type that uses the
+/// `one_batch` field batching strategy.
+///
+/// A non empty `change_prefix` means that the delta type also holds the deltas for other enum
+/// variants, so a delta for a different variant is ignored.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     MyStructDelta::NoChange => {}
+///     MyStructDelta::Change_0(delta0) => {
+///         visitor.enter(dipa::PathSegment::Field("some_field_name"));
+///         <u8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.some_field_name, delta0, visitor);
+///         visitor.exit();
+///     }
+///     // ...
+/// };
+/// ```
+pub fn make_visit_field_delta_arms(
+    delta_ty: &TokenStream2,
+    change_prefix: &str,
+    span: Span,
+    fields: &[StructOrTupleField],
+    start_fields: &[TokenStream2],
+    dipa_attrs: &DipaAttrs,
+) -> Vec<TokenStream2> {
+    let no_change = Ident::new(&format!("{}NoChange", change_prefix), span);
+
+    let mut arms = vec![quote! {
+        #delta_ty::#no_change => {}
+    }];
+
+    for changed_indices in
+        ChangedFieldIndices::all_changed_index_combinations(fields.len(), dipa_attrs)
+    {
+        let change_name = changed_indices.variant_name_ident(change_prefix, span);
+        let deltas: Vec<Ident> = changed_indices
+            .iter()
+            .map(|field_idx| Ident::new(&format!("delta{}", field_idx), span))
+            .collect();
+
+        let visit_fields = changed_indices
+            .iter()
+            .zip(deltas.iter())
+            .map(|(field_idx, delta)| {
+                let field_idx = *field_idx as usize;
+
+                visit_field_delta_tokens(
+                    &fields[field_idx],
+                    &start_fields[field_idx],
+                    &quote! {#delta},
+                )
+            });
+
+        arms.push(quote! {
+            #delta_ty::#change_name(#(#deltas),*) => {
+                #(#visit_fields)*
+            }
+        });
+    }
+
+    if !change_prefix.is_empty() {
+        arms.push(quote! {
+            _ => {}
+        });
+    }
+
+    arms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that the implementation only applies when every field type can be visited.
+    #[test]
+    fn visit_delta_impl_bounds() {
+        let tokens = make_visit_delta_impl(
+            &format_ident!("MyStruct"),
+            &[
+                Type::Verbatim(quote! {u8}),
+                Type::Verbatim(quote! {Vec<i8>}),
+            ],
+            quote! {},
+        );
+
+        let expected = quote! {
+            impl<'s, 'e> dipa::VisitDelta<'s, 'e> for MyStruct
+            where
+                u8: dipa::VisitDelta<'s, 'e>,
+                Vec<i8>: dipa::VisitDelta<'s, 'e>,
+            {
+                fn visit_delta(
+                    &self,
+                    delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+                    visitor: &mut dyn dipa::DeltaVisitor,
+                ) {
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we generate the arms that visit each changed field of a struct.
+    #[test]
+    fn visit_two_fields() {
+        let tokens = make_visit_field_delta_arms(
+            &quote! {MyStructDelta},
+            "",
+            Span::call_site(),
+            &two_fields(),
+            &[quote! {&self.fielda}, quote! {&self.fieldb}],
+            &DipaAttrs::default(),
+        );
+        let tokens = quote! {#(#tokens)*};
+
+        let expected = quote! {
+            MyStructDelta::NoChange => {}
+            MyStructDelta::Change_0(delta0) => {
+                visitor.enter(dipa::PathSegment::Field("fielda"));
+                <u8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.fielda, delta0, visitor);
+                visitor.exit();
+            }
+            MyStructDelta::Change_1(delta1) => {
+                visitor.enter(dipa::PathSegment::Field("fieldb"));
+                <i8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.fieldb, delta1, visitor);
+                visitor.exit();
+            }
+            MyStructDelta::Change_0_1(delta0, delta1) => {
+                visitor.enter(dipa::PathSegment::Field("fielda"));
+                <u8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.fielda, delta0, visitor);
+                visitor.exit();
+                visitor.enter(dipa::PathSegment::Field("fieldb"));
+                <i8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.fieldb, delta1, visitor);
+                visitor.exit();
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that the arms for one variant of a multi variant enum ignore the deltas for other
+    /// variants.
+    #[test]
+    fn visit_prefixed_variant_fields() {
+        let tokens = make_visit_field_delta_arms(
+            &quote! {MyEnumDelta},
+            "Two",
+            Span::call_site(),
+            &two_fields()[0..1],
+            &[quote! {start_0}],
+            &DipaAttrs::default(),
+        );
+        let tokens = quote! {#(#tokens)*};
+
+        let expected = quote! {
+            MyEnumDelta::TwoNoChange => {}
+            MyEnumDelta::TwoChange_0(delta0) => {
+                visitor.enter(dipa::PathSegment::Field("fielda"));
+                <u8 as dipa::VisitDelta<'s, 'e>>::visit_delta(start_0, delta0, visitor);
+                visitor.exit();
+            }
+            _ => {}
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn two_fields() -> Vec<StructOrTupleField> {
+        vec![
+            StructOrTupleField {
+                name: quote! {fielda},
+                ty: Type::Verbatim(quote! {u8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {fieldb},
+                ty: Type::Verbatim(quote! {i8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+        ]
+    }
+}
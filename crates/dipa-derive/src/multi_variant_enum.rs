@@ -1,54 +1,27 @@
 use crate::dipa_attribute::DipaAttrs;
 use crate::impl_dipa;
 use crate::multi_field_utils::{
-    fields_named_to_vec_fields, fields_unnamed_to_vec_fields, ParsedFields,
+    borrowed_delta_serde_bound, make_borrowed_delta_impl, make_schema_fingerprint_impl, PatchKind,
 };
 use crate::parsed_enum::{
-    delta_owned_type_name, delta_type_name, make_two_enums_match_statement, DipaAssociatedType,
-    EnumVariant, EnumVariantFields, ParsedEnum,
+    delta_borrowed_type_name, delta_owned_type_name, delta_type_name,
+    make_two_enums_match_statement, variant_name_match, DipaAssociatedType, EnumVariant,
+    EnumVariantFields, ParsedEnum,
 };
 use syn::__private::TokenStream2;
-use syn::punctuated::Punctuated;
-use syn::spanned::Spanned;
-use syn::{Fields, Ident, Type, TypeReference, Variant};
+use syn::{Ident, Type, TypeReference};
 
 pub fn generate_multi_variant_enum_impl(
     enum_name: syn::Ident,
-    variants: Punctuated<Variant, Token![,]>,
+    enum_variants: Vec<EnumVariant>,
     dipa_attrs: DipaAttrs,
 ) -> TokenStream2 {
-    let mut enum_variants = vec![];
-
-    let mut all_variants_unit = true;
-
-    for idx in 0..variants.len() {
-        let variant = &variants[idx];
-        let fields = match &variant.fields {
-            Fields::Named(named) => {
-                all_variants_unit = false;
-                EnumVariantFields::Struct(ParsedFields {
-                    fields: fields_named_to_vec_fields(named),
-                    span: named.span(),
-                })
-            }
-            Fields::Unnamed(unnamed) => {
-                all_variants_unit = false;
-                EnumVariantFields::Tuple(ParsedFields {
-                    fields: fields_unnamed_to_vec_fields(unnamed),
-                    span: unnamed.span(),
-                })
-            }
-            Fields::Unit => EnumVariantFields::Unit,
-        };
-
-        enum_variants.push(EnumVariant {
-            name: variant.ident.clone(),
-            fields,
-        })
-    }
+    let all_variants_unit = enum_variants
+        .iter()
+        .all(|variant| matches!(variant.fields, EnumVariantFields::Unit));
 
     if all_variants_unit {
-        generate_multi_variant_enum_no_data_impl(enum_name, enum_variants)
+        generate_multi_variant_enum_no_data_impl(enum_name, enum_variants, &dipa_attrs)
     } else {
         generate_multi_variant_enum_with_data_impl(enum_name, enum_variants, &dipa_attrs)
     }
@@ -66,8 +39,40 @@ pub fn generate_multi_variant_enum_impl(
 fn generate_multi_variant_enum_no_data_impl(
     enum_name: syn::Ident,
     variants: Vec<EnumVariant>,
+    dipa_attrs: &DipaAttrs,
 ) -> TokenStream2 {
-    impl_dipa(
+    let variant_name = variant_name_match(&quote! {value}, &variants);
+
+    let variant_names = variants.iter().map(|variant| variant.name.to_string());
+    let schema_fingerprint_impl =
+        make_schema_fingerprint_impl(&enum_name, &[], quote! { #(.write_str(#variant_names))* });
+
+    // serde writes a variant without data as the variant's name.
+    let json_patch_impl = if cfg!(feature = "json-patch") {
+        quote! {
+            impl<'s, 'e> dipa::ToJsonPatch<'s, 'e> for #enum_name {
+                fn push_json_patch(
+                    &self,
+                    delta: &#enum_name,
+                    path: &dipa::JsonPointer,
+                    patch: &mut dipa::JsonPatch,
+                ) -> Result<(), dipa::JsonPatchError> {
+                    let variant_name = |value: &Self| #variant_name;
+
+                    if std::mem::discriminant(self) != std::mem::discriminant(delta) {
+                        patch.replace(path, variant_name(delta))?;
+                    }
+
+                    Ok(())
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let dipa_impl = impl_dipa(
+        dipa_attrs,
         &enum_name,
         quote! {
         #enum_name
@@ -77,7 +82,49 @@ fn generate_multi_variant_enum_no_data_impl(
         },
         no_data_diff_match(&enum_name, &variants),
         quote! {*self = patch;},
-    )
+        quote! {std::mem::replace(self, patch)},
+        quote! {second},
+        no_data_merge(),
+    );
+
+    quote! {
+        #dipa_impl
+
+        impl dipa::ToOwnedDelta<#enum_name> for #enum_name {
+            fn to_owned_delta(self) -> #enum_name {
+                self
+            }
+        }
+
+        impl<'de> dipa::BorrowedDelta<'de> for #enum_name
+        where
+            #enum_name: serde::Deserialize<'de>,
+        {
+            type DeltaBorrowed = #enum_name;
+
+            fn apply_borrowed_patch(&mut self, patch: #enum_name) -> Result<(), dipa::PatchError> {
+                *self = patch;
+
+                Ok(())
+            }
+        }
+
+        impl<'s, 'e> dipa::VisitDelta<'s, 'e> for #enum_name {
+            fn visit_delta(&self, delta: &#enum_name, visitor: &mut dyn dipa::DeltaVisitor) {
+                let variant_name = |value: &Self| #variant_name;
+
+                if std::mem::discriminant(self) != std::mem::discriminant(delta) {
+                    visitor.visit_change(dipa::DeltaChange::Variant {
+                        old: variant_name(self),
+                        new: variant_name(delta),
+                    });
+                }
+            }
+        }
+
+        #json_patch_impl
+        #schema_fingerprint_impl
+    }
 }
 
 /// #[derive(Dipa)] for an enum with two or more variants, where at least one variant contains data.
@@ -101,6 +148,7 @@ fn generate_multi_variant_enum_with_data_impl(
 
     let diff_ty = delta_type_name(&enum_name);
     let patch_ty = delta_owned_type_name(&enum_name);
+    let borrowed_patch_ty = delta_borrowed_type_name(&enum_name);
 
     let mut diff_variants = vec![];
 
@@ -122,7 +170,7 @@ fn generate_multi_variant_enum_with_data_impl(
         let mut diff_tys = quote! {};
         let mut ref_tys = quote! {};
 
-        if variant.fields.len() > 0 {
+        if !variant.fields.is_empty() {
             let tys: Vec<Type> = variant
                 .fields
                 .iter()
@@ -143,7 +191,7 @@ fn generate_multi_variant_enum_with_data_impl(
             ref_tys = quote! {( #(#tys),* )};
         };
 
-        if variant.fields.len() > 0 {
+        if !variant.fields.is_empty() {
             let tys: Vec<Type> = variant
                 .fields
                 .iter()
@@ -193,11 +241,33 @@ fn generate_multi_variant_enum_with_data_impl(
         #patch_type_definition
     };
 
+    // The fields of the variant that the enum changed to are deserialized by value.
+    let field_tys = parsed_enum.bounded_field_tys();
+    let value_tys = parsed_enum.bounded_value_tys();
+    let borrowed_serde_bound =
+        borrowed_delta_serde_bound(&field_tys, &value_tys, parsed_enum.has_recursive_fields());
+    let borrowed_patch_type_definition = parsed_enum
+        .create_associated_type_for_enum_with_fields(DipaAssociatedType::DeltaBorrowed, dipa_attrs);
+    let borrowed_patch_type_definition = quote! {
+        #[derive(serde::Deserialize)]
+        #borrowed_serde_bound
+        #borrowed_patch_type_definition
+    };
+
     let diff_tokens = diff_match_with_data(&enum_name, &parsed_enum.variants, dipa_attrs);
 
-    let patch_tokens = parsed_enum.create_patch_match_stmt(dipa_attrs);
+    let patch_tokens = parsed_enum.create_patch_match_stmt(&patch_ty, PatchKind::Owned, dipa_attrs);
+    let reverse_patch_tokens = parsed_enum.create_reverse_patch_match_stmt(dipa_attrs);
+    let squash_patches_tokens = parsed_enum.create_squash_patches_match_stmt(dipa_attrs);
+    let merge_patches_tokens = parsed_enum.create_merge_patches_match_stmt(dipa_attrs);
+
+    let to_owned_delta_impl = parsed_enum.create_to_owned_delta_impl(dipa_attrs);
+    let visit_delta_impl = parsed_enum.create_visit_delta_impl(dipa_attrs);
+    let json_patch_impl = parsed_enum.create_json_patch_impl(dipa_attrs);
+    let schema_fingerprint_impl = parsed_enum.create_schema_fingerprint_impl(dipa_attrs);
 
     let dipa_impl = impl_dipa(
+        dipa_attrs,
         &enum_name,
         quote! {
         #diff_ty#maybe_lifetime
@@ -207,13 +277,30 @@ fn generate_multi_variant_enum_with_data_impl(
         },
         quote! { #diff_tokens },
         quote! { #patch_tokens },
+        quote! { #reverse_patch_tokens },
+        quote! { #squash_patches_tokens },
+        quote! { #merge_patches_tokens },
+    );
+
+    let borrowed_delta_impl = make_borrowed_delta_impl(
+        &enum_name,
+        &borrowed_patch_ty,
+        &field_tys,
+        &value_tys,
+        parsed_enum.create_patch_match_stmt(&borrowed_patch_ty, PatchKind::Borrowed, dipa_attrs),
     );
 
     let tokens = quote! {
        #diff_type_definition
        #patch_type_definition
+       #borrowed_patch_type_definition
+       #to_owned_delta_impl
 
        #dipa_impl
+       #borrowed_delta_impl
+       #visit_delta_impl
+       #json_patch_impl
+       #schema_fingerprint_impl
     };
 
     // panic!("{}", tokens.to_string());
@@ -257,6 +344,24 @@ fn no_data_diff_match(enum_name: &syn::Ident, variants: &[EnumVariant]) -> Token
     }
 }
 
+/// The patch for an enum without data is the variant that it changed to, so two patches only
+/// conflict when they change the enum to different variants.
+fn no_data_merge() -> TokenStream2 {
+    quote! {
+        let changed = |patch: &Self| std::mem::discriminant(patch) != std::mem::discriminant(base);
+
+        if !changed(&b) || std::mem::discriminant(&a) == std::mem::discriminant(&b) {
+            a
+        } else if !changed(&a) {
+            b
+        } else {
+            return Err(dipa::MergeConflicts::new(
+                dipa::MergeConflictReason::ConcurrentChange
+            ));
+        }
+    }
+}
+
 /// Generate a match statement to diff an enum where at least one variant contains data.
 ///
 /// ```
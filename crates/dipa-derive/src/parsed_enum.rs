@@ -1,18 +1,27 @@
 //! Functions and types to help with code generation for implementation Diffable / Patchable for
 //! enums.
 
-use syn::Ident;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 pub use self::enum_variant::*;
 pub use self::generate_associated_types::*;
+pub use self::generate_visit_delta_enum_tokens::variant_name_match;
 
 mod enum_variant;
 
 mod generate_associated_types;
+mod generate_json_patch_enum_tokens;
+mod generate_merge_patches_enum_tokens;
 mod generate_patch_enum_tokens;
+mod generate_reverse_patch_enum_tokens;
+mod generate_schema_fingerprint_enum_tokens;
+mod generate_squash_patches_enum_tokens;
+mod generate_to_owned_delta_enum_tokens;
+mod generate_visit_delta_enum_tokens;
 
 mod generate_dipa_impl;
+mod validate_attributes;
 
 /// An enum
 pub struct ParsedEnum {
@@ -39,20 +48,24 @@ pub fn make_two_enums_match_statement(
 
 /// FIXME: Move this into a method on the ParsedEnum type
 pub fn delta_type_name(enum_name: &Ident) -> Ident {
-    Ident::new(&format!("{}Delta", enum_name.to_string()), enum_name.span())
+    Ident::new(&format!("{}Delta", enum_name), enum_name.span())
 }
 
 /// FIXME: Move this into a method on the ParsedEnum type
 pub fn delta_owned_type_name(enum_name: &Ident) -> Ident {
-    Ident::new(
-        &format!("{}DeltaOwned", enum_name.to_string()),
-        enum_name.span(),
-    )
+    Ident::new(&format!("{}DeltaOwned", enum_name), enum_name.span())
+}
+
+/// FIXME: Move this into a method on the ParsedEnum type
+pub fn delta_borrowed_type_name(enum_name: &Ident) -> Ident {
+    Ident::new(&format!("{}DeltaBorrowed", enum_name), enum_name.span())
 }
 
 #[cfg(test)]
 mod test_extras {
-    use quote::__private::{Ident, Span};
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use syn::__private::Span;
+    use syn::Ident;
     use syn::Type;
 
     use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
@@ -71,6 +84,8 @@ mod test_extras {
                 name: Default::default(),
                 ty: Type::Verbatim(quote! {u16}),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             }];
 
             ParsedEnum {
@@ -130,11 +145,15 @@ mod test_extras {
                 name: quote! {0},
                 ty: Type::Verbatim(quote! {u16}),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
             StructOrTupleField {
                 name: quote! {1},
                 ty: Type::Verbatim(quote! {u32}),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
         ];
 
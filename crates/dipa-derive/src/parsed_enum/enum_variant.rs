@@ -1,12 +1,21 @@
-use syn::Ident;
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::ChangedFieldIndices;
+use crate::parsed_enum::delta_owned_type_name;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 pub use self::enum_variant_fields::*;
 
 mod enum_variant_fields;
 
 mod diff_enum_variants;
+mod json_patch_enum_variants;
+mod merge_patch_enum_variants;
 mod patch_enum_variants;
+mod reverse_patch_enum_variants;
+mod squash_patch_enum_variants;
+mod to_owned_delta_enum_variants;
+mod visit_delta_enum_variants;
 
 mod diff_type_variants;
 
@@ -28,27 +37,47 @@ impl EnumVariant {
     }
 
     fn variant_no_change(&self) -> Ident {
-        Ident::new(
-            &format!("{}NoChange", self.name.to_string()),
-            self.name.span(),
-        )
+        Ident::new(&format!("{}NoChange", self.name), self.name.span())
     }
 
     fn changed_to_variant(&self) -> Ident {
         let variant = &self.name;
 
-        Ident::new(
-            &format!("ChangedToVariant{}", variant.to_string()),
-            self.name.span(),
-        )
+        Ident::new(&format!("ChangedToVariant{}", variant), self.name.span())
+    }
+
+    /// A pattern that matches every patch that changes this variant's fields.
+    ///
+    /// (MyEnumDeltaOwned::VariantCChange_0(..) | MyEnumDeltaOwned::VariantCChange_1(..))
+    fn field_changes_pattern(&self, enum_name: &Ident, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let variant_name_str = self.name.to_string();
+        let span = self.name.span();
+
+        let field_changes: Vec<TokenStream2> =
+            ChangedFieldIndices::all_changed_index_combinations(self.fields.len(), dipa_attrs)
+                .into_iter()
+                .map(|changed_indices| {
+                    let change_name = changed_indices.variant_name_ident(&variant_name_str, span);
+                    quote! { #patch_name::#change_name(..) }
+                })
+                .collect();
+
+        match field_changes.as_slice() {
+            [field_change] => quote! { #field_change },
+            field_changes => quote! { (#(#field_changes)|*) },
+        }
     }
 }
 
 #[cfg(test)]
 mod test_extras {
+    use crate::dipa_attribute::DipaFieldAttrs;
     use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
     use crate::parsed_enum::{EnumVariant, EnumVariantFields};
-    use quote::__private::{Ident, Span};
+    use syn::__private::Span;
+    use syn::Ident;
     use syn::Type;
 
     impl EnumVariant {
@@ -76,6 +105,8 @@ mod test_extras {
                 name: quote! {0},
                 ty: Type::Verbatim(quote! {u16}),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             }];
 
             EnumVariant {
@@ -99,11 +130,15 @@ mod test_extras {
                     name: quote! {0},
                     ty: Type::Verbatim(quote! {u16}),
                     span: Span::call_site(),
+                    attrs: DipaFieldAttrs::default(),
+                    packed_bools: vec![],
                 },
                 StructOrTupleField {
                     name: quote! {1},
                     ty: Type::Verbatim(quote! {u32}),
                     span: Span::call_site(),
+                    attrs: DipaFieldAttrs::default(),
+                    packed_bools: vec![],
                 },
             ];
 
@@ -33,7 +33,7 @@ impl EnumVariant {
         let left_variant = &self.name;
         let right_variant = &other.name;
 
-        let same_variant = left_variant.to_string() == right_variant.to_string();
+        let same_variant = right_variant == left_variant;
 
         if same_variant {
             return if self.fields.is_unit() {
@@ -43,7 +43,7 @@ impl EnumVariant {
             };
         }
 
-        if other.fields.len() == 0 {
+        if other.fields.is_empty() {
             self.block_different_variant_no_data(enum_name, other)
         } else {
             self.block_different_variant_with_data(enum_name, other)
@@ -92,8 +92,8 @@ impl EnumVariant {
     ///       MyEnum::AnotherVariant { some_field: start_some_field, another: start_another },
     ///       MyEnum::AnotherVariant { some_field: end_some_field, another: end_another },
     ///   ) => {
-    ///       let diff0 = start_some_field.create_delta_towards(&end_some_field);
-    ///       let diff1 = start_another.create_delta_towards(&end_another);
+    ///       let diff0 = start_some_field.create_delta_towards_version(&end_some_field, version);
+    ///       let diff1 = start_another.create_delta_towards_version(&end_another, version);
     ///
     ///       let delta = match (diff0.did_change, diff1.did_change) => {
     ///           (false, false) => MyEnumDelta::AnotherVariantNoChange,
@@ -131,7 +131,7 @@ impl EnumVariant {
             other.name.to_string().trim(),
             enum_name.span(),
             &other.fields,
-            &dipa_attrs,
+            dipa_attrs,
         );
 
         quote! {
@@ -238,8 +238,16 @@ impl EnumVariant {
 
     /// Generate code to diff every field in a struct or tuple variant.
     ///
-    /// let diff_0 = start0.create_delta_towards(&end0);
-    /// let diff_1 = start1.create_delta_towards(&end1);
+    /// let diff_0 = start0.create_delta_towards_version(&end0, version);
+    /// let diff_1 = start1.create_delta_towards_version(&end1, version);
+    ///
+    /// Packed bools are diffed through the bools that they hold.
+    ///
+    /// let diff_2 = dipa::Diffable::create_delta_towards_version(
+    ///     (&dipa::PackedBools::new([start_a, start_b], &[...])),
+    ///     (&dipa::PackedBools::new([end_a, end_b], &[...])),
+    ///     version,
+    /// );
     fn field_diff_statements(&self, other: &EnumVariant) -> Vec<TokenStream2> {
         self.fields
             .iter()
@@ -249,11 +257,20 @@ impl EnumVariant {
 
                 let diff_idx_ident = Ident::new(&format!("diff{}", field_idx), field_name.span());
 
+                if field.is_packed_bools() {
+                    let start = field.binding("start_");
+                    let end = other.fields[field_idx].binding("end_");
+
+                    return quote! {
+                    let #diff_idx_ident = dipa::Diffable::create_delta_towards_version(#start, #end, version);
+                    };
+                }
+
                 let start_ident = &field.prefixed_name("start_");
                 let end_ident = &other.fields[field_idx].prefixed_name("end_");
 
                 quote! {
-                let #diff_idx_ident = #start_ident.create_delta_towards(&#end_ident);
+                let #diff_idx_ident = #start_ident.create_delta_towards_version(&#end_ident, version);
                 }
             })
             .collect()
@@ -263,11 +280,12 @@ impl EnumVariant {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
     use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
     use crate::parsed_enum::EnumVariantFields;
     use crate::test_utils::assert_tokens_eq;
-    use syn::Type;
     use syn::__private::Span;
+    use syn::Type;
 
     /// Verify that we properly generate tokens for two of the same variant with no fields.
     #[test]
@@ -346,6 +364,8 @@ mod tests {
             name: quote! {field},
             ty,
             span: Span::call_site(),
+            attrs: DipaFieldAttrs::default(),
+            packed_bools: vec![],
         }];
         let new_variant = EnumVariant {
             name: variant_name_2(),
@@ -390,6 +410,8 @@ mod tests {
             name: quote! {field},
             ty,
             span: Span::call_site(),
+            attrs: DipaFieldAttrs::default(),
+            packed_bools: vec![],
         }];
         let new_variant = EnumVariant {
             name: variant_name_2(),
@@ -431,11 +453,15 @@ mod tests {
                 name: quote! {0},
                 ty: ty.clone(),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
             StructOrTupleField {
                 name: quote! {1},
                 ty: ty.clone(),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
         ];
         let old_variant = EnumVariant {
@@ -451,11 +477,15 @@ mod tests {
                 name: quote! {0},
                 ty: ty.clone(),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
             StructOrTupleField {
                 name: quote! {1},
                 ty,
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
         ];
         let new_variant = EnumVariant {
@@ -477,8 +507,8 @@ mod tests {
                 MyEnum::Variant2(start_0, start_1),
                 MyEnum::Variant2(end_0, end_1),
             ) => {
-                let diff0 = start_0.create_delta_towards(&end_0);
-                let diff1 = start_1.create_delta_towards(&end_1);
+                let diff0 = start_0.create_delta_towards_version(&end_0, version);
+                let diff1 = start_1.create_delta_towards_version(&end_1, version);
 
                 let delta = match (diff0.did_change, diff1.did_change) {
                     (false, false) => MyEnumDelta::Variant2NoChange,
@@ -510,11 +540,15 @@ mod tests {
                 name: quote! {field_a},
                 ty: ty.clone(),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
             StructOrTupleField {
                 name: quote! {field_b},
                 ty: ty.clone(),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
         ];
         let old_variant = EnumVariant {
@@ -530,11 +564,15 @@ mod tests {
                 name: quote! {field_a},
                 ty: ty.clone(),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
             StructOrTupleField {
                 name: quote! {field_b},
                 ty,
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
         ];
         let new_variant = EnumVariant {
@@ -556,8 +594,8 @@ mod tests {
                 MyEnum::Variant2 { field_a: start_field_a, field_b: start_field_b },
                 MyEnum::Variant2 { field_a: end_field_a, field_b: end_field_b },
             ) => {
-                let diff0 = start_field_a.create_delta_towards(&end_field_a);
-                let diff1 = start_field_b.create_delta_towards(&end_field_b);
+                let diff0 = start_field_a.create_delta_towards_version(&end_field_a, version);
+                let diff1 = start_field_b.create_delta_towards_version(&end_field_b, version);
 
                 let delta = match (diff0.did_change, diff1.did_change) {
                     (false, false) => MyEnumDelta::Variant2NoChange,
@@ -1,5 +1,5 @@
 use crate::dipa_attribute::DipaAttrs;
-use crate::multi_field_utils::ChangedFieldIndices;
+use crate::multi_field_utils::{unpack_bool_fields, ChangedFieldIndices};
 use crate::parsed_enum::{DipaAssociatedType, EnumVariant};
 use quote::ToTokens;
 use syn::__private::TokenStream2;
@@ -34,7 +34,7 @@ impl EnumVariant {
         let no_change = self.variant_no_change();
         diff_ty_variants.push(quote! { #no_change });
 
-        if self.fields.len() > 0 {
+        if !self.fields.is_empty() {
             diff_ty_variants.push(self.changed_to_with_ref_fields(associated_type));
             diff_ty_variants
                 .extend_from_slice(&self.change_same_variant(associated_type, dipa_attrs));
@@ -48,11 +48,10 @@ impl EnumVariant {
 
     /// quote![ChangedToVariantOne(&'e u16)
     fn changed_to_with_ref_fields(&self, associated_type: DipaAssociatedType) -> TokenStream2 {
-        let tys: Vec<Type> = self
-            .fields
+        let tys: Vec<Type> = unpack_bool_fields(&self.fields)
             .iter()
             .map(|f| {
-                let ty = if associated_type.has_lifetime() {
+                if associated_type.borrows_end_state() {
                     let ref_ty = Type::Reference(TypeReference {
                         and_token: syn::token::And::default(),
                         lifetime: Some(syn::Lifetime::new("'e", f.span)),
@@ -63,9 +62,7 @@ impl EnumVariant {
                 } else {
                     let owned_ty = &f.ty;
                     Type::Verbatim(quote! {#owned_ty})
-                };
-
-                ty
+                }
             })
             .collect();
 
@@ -102,18 +99,21 @@ impl EnumVariant {
                 let field = &self.fields[*field_idx as usize];
                 let ty = &field.ty;
 
-                let lifetime = match associated_type {
+                let field_delta_ty = match associated_type {
                     DipaAssociatedType::Delta => {
-                        quote! {'s, 'e}
+                        quote! { <#ty as dipa::Diffable<'s, 'e, #ty>>::#associated_type_tokens }
                     }
                     DipaAssociatedType::DeltaOwned => {
-                        quote! {'static, 'static}
+                        quote! {
+                            <#ty as dipa::Diffable<'static, 'static, #ty>>::#associated_type_tokens
+                        }
+                    }
+                    DipaAssociatedType::DeltaBorrowed => {
+                        quote! { <#ty as dipa::BorrowedDelta<'b>>::#associated_type_tokens }
                     }
                 };
 
-                tys.push(Type::Verbatim(
-                    quote! { <#ty as dipa::Diffable<#lifetime, #ty>>::#associated_type_tokens },
-                ));
+                tys.push(Type::Verbatim(field_delta_ty));
             }
 
             // example: (Option<u16>, Option<u32>)
@@ -141,7 +141,7 @@ mod tests {
 
         let diff_variants =
             variant.diff_type_variants(DipaAssociatedType::Delta, &DipaAttrs::default());
-        let expected = vec![quote! {TwoNoChange}, quote! {ChangedToVariantTwo}];
+        let expected = [quote! {TwoNoChange}, quote! {ChangedToVariantTwo}];
 
         assert_eq!(diff_variants.len(), expected.len());
 
@@ -157,7 +157,7 @@ mod tests {
 
         let diff_variants =
             variant.diff_type_variants(DipaAssociatedType::Delta, &DipaAttrs::default());
-        let expected = vec![
+        let expected = [
             quote! {OneNoChange},
             quote! {
             ChangedToVariantOne(&'e u16)},
@@ -178,7 +178,7 @@ mod tests {
 
         let diff_variants =
             variant.diff_type_variants(DipaAssociatedType::Delta, &DipaAttrs::default());
-        let expected = vec![
+        let expected = [
             quote! {TwoNoChange},
             quote! {
             ChangedToVariantTwo(&'e u16, &'e u32)},
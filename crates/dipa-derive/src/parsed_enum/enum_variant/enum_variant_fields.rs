@@ -1,7 +1,7 @@
-use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
+use crate::multi_field_utils::{unpack_bool_fields, ParsedFields, StructOrTupleField};
 use std::ops::Deref;
-use syn::Ident;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 /// Tuple -> MyEnum::Variant(...)
 /// Struct -> MyEnum::Variant { ... }
@@ -15,8 +15,8 @@ pub enum EnumVariantFields {
 impl EnumVariantFields {
     pub fn fields(&self) -> &[StructOrTupleField] {
         match self {
-            EnumVariantFields::Tuple(v) => &v,
-            EnumVariantFields::Struct(v) => &v,
+            EnumVariantFields::Tuple(v) => v,
+            EnumVariantFields::Struct(v) => v,
             EnumVariantFields::Unit => &[],
         }
     }
@@ -33,11 +33,13 @@ impl EnumVariantFields {
     ///   (prefix_field0, prefix_field1)
     /// Struct:
     ///   { field0: prefix_field0, field1: prefix_field1 }
+    ///
+    /// Packed bools bind each of the bools that they hold.
     pub fn to_pattern_match_tokens(&self, prefix: &'static str) -> TokenStream2 {
         match self {
             EnumVariantFields::Tuple(fields) => {
                 let mut fields_expanded = vec![];
-                for field in fields.iter() {
+                for field in unpack_bool_fields(fields).iter() {
                     let field_name = &field.name.to_string();
                     let field_name = field_name.trim();
                     let field_name_prefixed =
@@ -50,7 +52,7 @@ impl EnumVariantFields {
             }
             EnumVariantFields::Struct(fields) => {
                 let mut fields_expanded = vec![];
-                for field in fields.iter() {
+                for field in unpack_bool_fields(fields).iter() {
                     let field_name = &field.name;
 
                     let field_name_str = &field.name.to_string();
@@ -72,11 +74,13 @@ impl EnumVariantFields {
     /// If there are fields:
     ///  (prefix_field0, prefix_field1)
     ///
+    /// Packed bools are replaced by each of the bools that they hold.
+    ///
     /// Otherwise don't return any tokens.
     pub fn to_field_value_tokens_parenthesized(&self, prefix: &'static str) -> TokenStream2 {
         let mut fields_expanded = vec![];
 
-        for field in self.iter() {
+        for field in unpack_bool_fields(self).iter() {
             let field_name = &field.name.to_string();
             let field_name = field_name.trim();
             let field_name_prefixed = Ident::new(&format!("{}{}", prefix, field_name), field.span);
@@ -84,13 +88,23 @@ impl EnumVariantFields {
             fields_expanded.push(quote! {#field_name_prefixed});
         }
 
-        if fields_expanded.len() > 0 {
+        if !fields_expanded.is_empty() {
             quote! { ( #(#fields_expanded),* ) }
         } else {
             quote! {}
         }
     }
 
+    /// Pack the variant's bool fields into one field, see [`ParsedFields::pack_bool_fields`].
+    pub fn pack_bool_fields(&mut self) -> Result<(), TokenStream2> {
+        match self {
+            EnumVariantFields::Tuple(fields) | EnumVariantFields::Struct(fields) => {
+                fields.pack_bool_fields()
+            }
+            EnumVariantFields::Unit => Ok(()),
+        }
+    }
+
     /// Returns `true` if the enum_variant_fields is [`Unit`].
     pub fn is_unit(&self) -> bool {
         matches!(self, Self::Unit)
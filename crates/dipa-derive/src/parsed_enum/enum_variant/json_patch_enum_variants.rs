@@ -0,0 +1,262 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{make_json_patch_field_delta_arms, unpack_bool_fields};
+use crate::parsed_enum::{delta_type_name, EnumVariant, EnumVariantFields};
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl EnumVariant {
+    /// Generate the match arm that replaces the enum when a delta changes it to this variant.
+    ///
+    /// serde writes a unit variant as its name and any other variant as an object with the variant
+    /// name as its only member, so the new value is serialized through a local enum that borrows
+    /// the fields from the delta.
+    ///
+    /// All of the example doc comments below are based on the following enum.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// // VariantA
+    /// quote! {
+    ///     (_, MyEnumDelta::ChangedToVariantVariantA) => {
+    ///         patch.replace(path, "VariantA")?;
+    ///     }
+    /// };
+    ///
+    /// // VariantC
+    /// quote! {
+    ///     (_, MyEnumDelta::ChangedToVariantVariantC(field_0)) => {
+    ///         #[derive(serde::Serialize)]
+    ///         #[allow(non_camel_case_types, non_snake_case)]
+    ///         enum ChangedToVariant<'a> {
+    ///             VariantC(&'a i16),
+    ///         }
+    ///
+    ///         patch.replace(path, &ChangedToVariant::VariantC(*field_0))?;
+    ///     }
+    /// };
+    /// ```
+    ///
+    /// See [crate::parsed_enum::ParsedEnum.create_json_patch_impl].
+    pub fn generate_json_patch_changed_to_variant_block(&self, enum_name: &Ident) -> TokenStream2 {
+        let delta_name = delta_type_name(enum_name);
+        let changed_to_variant = self.changed_to_variant();
+        let variant_name = &self.name;
+        let variant_name_str = variant_name.to_string();
+
+        if self.fields.is_empty() {
+            return quote! {
+                (_, #delta_name::#changed_to_variant) => {
+                    patch.replace(path, #variant_name_str)?;
+                }
+            };
+        }
+
+        let values = self.fields.to_field_value_tokens_parenthesized("field_");
+        let fields = unpack_bool_fields(&self.fields);
+        let copied_values: Vec<TokenStream2> = fields
+            .iter()
+            .map(|field| {
+                let value = field.prefixed_name("field_");
+                quote! {*#value}
+            })
+            .collect();
+
+        let (borrowed_variant, new_value) = match &self.fields {
+            EnumVariantFields::Struct(_) => {
+                let names: Vec<&TokenStream2> = fields.iter().map(|field| &field.name).collect();
+                let tys = fields.iter().map(|field| &field.ty);
+
+                (
+                    quote! { #variant_name { #(#names: &'a #tys),* } },
+                    quote! { ChangedToVariant::#variant_name { #(#names: #copied_values),* } },
+                )
+            }
+            _ => {
+                let tys = fields.iter().map(|field| &field.ty);
+
+                (
+                    quote! { #variant_name(#(&'a #tys),*) },
+                    quote! { ChangedToVariant::#variant_name(#(#copied_values),*) },
+                )
+            }
+        };
+
+        quote! {
+            (_, #delta_name::#changed_to_variant#values) => {
+                #[derive(serde::Serialize)]
+                #[allow(non_camel_case_types, non_snake_case)]
+                enum ChangedToVariant<'a> {
+                    #borrowed_variant,
+                }
+
+                patch.replace(path, &#new_value)?;
+            }
+        }
+    }
+
+    /// Generate the match arm that pushes the operations for the changes to this variant's fields.
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// // VariantA
+    /// quote! {
+    ///     (MyEnum::VariantA { .. }, _) => {}
+    /// };
+    ///
+    /// // VariantC
+    /// quote! {
+    ///     (MyEnum::VariantC(start_0), delta) => {
+    ///         let path = &path.child("VariantC");
+    ///         match delta {
+    ///             // See [crate::multi_field_utils::make_json_patch_field_delta_arms]
+    ///         }
+    ///     }
+    /// };
+    /// ```
+    pub fn generate_json_patch_field_changes(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let variant_name = &self.name;
+
+        if self.fields.is_empty() {
+            return quote! {
+                (#enum_name::#variant_name { .. }, _) => {}
+            };
+        }
+
+        let delta_name = delta_type_name(enum_name);
+        let variant_name_str = variant_name.to_string();
+
+        let start_pattern = self.fields.to_pattern_match_tokens("start_");
+        let start_fields: Vec<TokenStream2> = self
+            .fields
+            .iter()
+            .map(|field| field.binding("start_"))
+            .collect();
+
+        let json_patch_arms = make_json_patch_field_delta_arms(
+            &quote! {#delta_name},
+            &variant_name_str,
+            variant_name.span(),
+            self.fields.fields(),
+            &start_fields,
+            dipa_attrs,
+        );
+
+        quote! {
+            (#enum_name::#variant_name#start_pattern, delta) => {
+                let path = &path.child(#variant_name_str);
+                match delta {
+                    #(#json_patch_arms)*
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that changing to a unit variant replaces the enum with the variant's name.
+    #[test]
+    fn changed_to_unit_variant() {
+        let tokens = EnumVariant::no_field_variant()
+            .generate_json_patch_changed_to_variant_block(&enum_name());
+
+        let expected = quote! {
+            (_, MyEnumDelta::ChangedToVariantTwo) => {
+                patch.replace(path, "Two")?;
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that changing to a variant with fields replaces the enum with the serde form of the
+    /// new variant.
+    #[test]
+    fn changed_to_variant_with_fields() {
+        let tokens = EnumVariant::two_fields_variant()
+            .generate_json_patch_changed_to_variant_block(&enum_name());
+
+        let expected = quote! {
+            (_, MyEnumDelta::ChangedToVariantTwo(field_0, field_1)) => {
+                #[derive(serde::Serialize)]
+                #[allow(non_camel_case_types, non_snake_case)]
+                enum ChangedToVariant<'a> {
+                    Two(&'a u16, &'a u32),
+                }
+
+                patch.replace(path, &ChangedToVariant::Two(*field_0, *field_1))?;
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that a variant without fields has no field changes to convert.
+    #[test]
+    fn no_field_changes_without_fields() {
+        let tokens = EnumVariant::no_field_variant()
+            .generate_json_patch_field_changes(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            (MyEnum::Two { .. }, _) => {}
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that the operations for a variant's fields are pushed under the variant's name.
+    #[test]
+    fn same_variant_fields() {
+        let tokens = EnumVariant::two_fields_variant()
+            .generate_json_patch_field_changes(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            (MyEnum::Two(start_0, start_1), delta) => {
+                let path = &path.child("Two");
+                match delta {
+                    MyEnumDelta::TwoNoChange => {}
+                    MyEnumDelta::TwoChange_0(delta0) => {
+                        <u16 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                            start_0, delta0, &path.child(0usize), patch
+                        )?;
+                    }
+                    MyEnumDelta::TwoChange_1(delta1) => {
+                        <u32 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                            start_1, delta1, &path.child(1usize), patch
+                        )?;
+                    }
+                    MyEnumDelta::TwoChange_0_1(delta0, delta1) => {
+                        <u16 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                            start_0, delta0, &path.child(0usize), patch
+                        )?;
+                        <u32 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                            start_1, delta1, &path.child(1usize), patch
+                        )?;
+                    }
+                    _ => {}
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn enum_name() -> Ident {
+        Ident::new("MyEnum", syn::__private::Span::call_site())
+    }
+}
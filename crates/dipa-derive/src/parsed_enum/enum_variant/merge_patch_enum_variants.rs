@@ -0,0 +1,206 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{make_merge_field_patches_tokens, unpack_bool_fields};
+use crate::parsed_enum::{delta_owned_type_name, EnumVariant};
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl EnumVariant {
+    /// Generate the match arms that merge two patches when one of them leaves this variant
+    /// unchanged.
+    ///
+    /// All of the example doc comments below are based on the following enum.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     (a, MyEnumDeltaOwned::VariantCNoChange) => a,
+    ///     (MyEnumDeltaOwned::VariantCNoChange, b) => b,
+    /// };
+    /// ```
+    ///
+    /// See [crate::parsed_enum::ParsedEnum.create_merge_patches_match_stmt].
+    pub fn generate_merge_no_change_blocks(&self, enum_name: &Ident) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+        let no_change = self.variant_no_change();
+
+        quote! {
+            (a, #patch_name::#no_change) => a,
+            (#patch_name::#no_change, b) => b,
+        }
+    }
+
+    /// Generate the match arm that merges two patches that both change this variant's fields.
+    ///
+    /// Variants without fields don't have any field changes, so no tokens are generated for them.
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     (a @ MyEnumDeltaOwned::VariantCChange_0(..), b @ MyEnumDeltaOwned::VariantCChange_0(..)) => {
+    ///         let base_0 = match base {
+    ///             MyEnum::VariantC(base_0) => base_0,
+    ///             _ => return Err(dipa::PatchError::new(
+    ///                 dipa::PatchErrorReason::VariantMismatch { expected: "VariantC" }
+    ///             ).into()),
+    ///         };
+    ///
+    ///         // See [crate::multi_field_utils::make_merge_field_patches_tokens]
+    ///     }
+    /// };
+    /// ```
+    pub fn generate_merge_field_changes(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        if self.fields.is_empty() {
+            return quote! {};
+        }
+
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let variant_name = &self.name;
+        let variant_name_str = variant_name.to_string();
+        let span = variant_name.span();
+
+        let field_changes = self.field_changes_pattern(enum_name, dipa_attrs);
+
+        let base_pattern = self.fields.to_pattern_match_tokens("base_");
+        let base_bindings: Vec<Ident> = unpack_bool_fields(&self.fields)
+            .iter()
+            .map(|field| field.prefixed_name("base_"))
+            .collect();
+        let base_fields_tuple = match base_bindings.as_slice() {
+            [base_binding] => quote! {#base_binding},
+            base_bindings => quote! {(#(#base_bindings),*)},
+        };
+        let base_fields: Vec<TokenStream2> = self
+            .fields
+            .iter()
+            .map(|field| field.binding("base_"))
+            .collect();
+
+        let merge_fields = make_merge_field_patches_tokens(
+            &quote! {#patch_name},
+            &variant_name_str,
+            Some(&variant_name_str),
+            span,
+            self.fields.fields(),
+            &base_fields,
+            dipa_attrs,
+        );
+
+        quote! {
+            (a @ #field_changes, b @ #field_changes) => {
+                let #base_fields_tuple = match base {
+                    #enum_name::#variant_name#base_pattern => #base_fields_tuple,
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: #variant_name_str }
+                    ).into()),
+                };
+
+                #merge_fields
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that a patch that leaves the variant unchanged merges into the other patch.
+    #[test]
+    fn no_change_blocks() {
+        let tokens = EnumVariant::one_field_variant().generate_merge_no_change_blocks(&enum_name());
+
+        let expected = quote! {
+            (a, MyEnumDeltaOwned::OneNoChange) => a,
+            (MyEnumDeltaOwned::OneNoChange, b) => b,
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we don't generate field change arms for a variant without fields.
+    #[test]
+    fn no_field_changes_without_fields() {
+        let tokens = EnumVariant::no_field_variant()
+            .generate_merge_field_changes(&enum_name(), &DipaAttrs::default());
+
+        assert_tokens_eq(&tokens, &quote! {});
+    }
+
+    /// Verify that we merge the patches to the fields of the same variant against the base
+    /// variant's fields.
+    #[test]
+    fn same_variant_fields() {
+        let tokens = EnumVariant::one_field_variant()
+            .generate_merge_field_changes(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            (a @ MyEnumDeltaOwned::OneChange_0(..), b @ MyEnumDeltaOwned::OneChange_0(..)) => {
+                let base_0 = match base {
+                    MyEnum::One(base_0) => base_0,
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                    ).into()),
+                };
+
+                let a0 = match a {
+                    MyEnumDeltaOwned::OneNoChange => None,
+                    MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                    ).into()),
+                };
+                let b0 = match b {
+                    MyEnumDeltaOwned::OneNoChange => None,
+                    MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                    ).into()),
+                };
+
+                let mut conflicts = dipa::MergeConflicts::default();
+
+                let merged0 = match (a0, b0) {
+                    (Some(a_patch), Some(b_patch)) => {
+                        <u16 as dipa::MergeablePatches<_>>::merge_patches(base_0, a_patch, b_patch)
+                            .map_err(|err| conflicts.extend(
+                                err
+                                    .with_parent(dipa::PathSegment::TupleField(0usize))
+                                    .with_parent(dipa::PathSegment::Variant("One"))
+                            ))
+                            .ok()
+                    }
+                    (a_patch, b_patch) => a_patch.or(b_patch),
+                };
+
+                if !conflicts.is_empty() {
+                    return Err(conflicts);
+                }
+
+                match merged0 {
+                    None => MyEnumDeltaOwned::OneNoChange,
+                    Some(patch0) => MyEnumDeltaOwned::OneChange_0(patch0),
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn enum_name() -> Ident {
+        Ident::new("MyEnum", syn::__private::Span::call_site())
+    }
+}
@@ -1,8 +1,8 @@
 use crate::dipa_attribute::DipaAttrs;
-use crate::multi_field_utils::ChangedFieldIndices;
-use crate::parsed_enum::{delta_owned_type_name, EnumVariant};
-use syn::Ident;
+use crate::multi_field_utils::{ChangedFieldIndices, PatchKind};
+use crate::parsed_enum::EnumVariant;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 impl EnumVariant {
     /// Generate the various patch blocks for an enum variant.
@@ -18,21 +18,29 @@ impl EnumVariant {
     /// }
     /// ```
     ///
+    /// `patch_name` is either the owned or the borrowed delta type, since both have the same
+    /// variants.
+    ///
     /// See [crate::enum_utils::EnumVariant.create_patch_match_stmt].
     pub fn generate_patch_blocks(
         &self,
         enum_name: &Ident,
+        patch_name: &Ident,
+        patch_kind: PatchKind,
         dipa_attrs: &DipaAttrs,
     ) -> Vec<TokenStream2> {
         let mut patch_blocks = vec![];
 
-        patch_blocks.push(self.generate_no_change_block(enum_name));
+        patch_blocks.push(self.generate_no_change_block(patch_name));
 
-        if self.fields.len() == 0 {
-            patch_blocks.push(self.generate_changed_to_variant_block_no_fields(enum_name));
+        if self.fields.is_empty() {
+            patch_blocks
+                .push(self.generate_changed_to_variant_block_no_fields(enum_name, patch_name));
         } else {
-            patch_blocks.push(self.generate_changed_to_variant_block_with_fields(enum_name));
-            patch_blocks.push(self.generate_field_changes(enum_name, dipa_attrs));
+            patch_blocks
+                .push(self.generate_changed_to_variant_block_with_fields(enum_name, patch_name));
+            patch_blocks
+                .push(self.generate_field_changes(enum_name, patch_name, patch_kind, dipa_attrs));
         }
 
         patch_blocks
@@ -44,9 +52,7 @@ impl EnumVariant {
     ///     MyEnumDeltaOwned::VariantANoChange => { }
     /// };
     /// ```
-    fn generate_no_change_block(&self, enum_name: &Ident) -> TokenStream2 {
-        let patch_name = delta_owned_type_name(enum_name);
-
+    fn generate_no_change_block(&self, patch_name: &Ident) -> TokenStream2 {
         let no_change = self.variant_no_change();
 
         quote! {
@@ -62,9 +68,11 @@ impl EnumVariant {
     ///     }
     /// };
     /// ```
-    fn generate_changed_to_variant_block_no_fields(&self, enum_name: &Ident) -> TokenStream2 {
-        let patch_name = delta_owned_type_name(enum_name);
-
+    fn generate_changed_to_variant_block_no_fields(
+        &self,
+        enum_name: &Ident,
+        patch_name: &Ident,
+    ) -> TokenStream2 {
         let changed_to = self.changed_to_variant();
 
         let variant_name = &self.name;
@@ -102,9 +110,11 @@ impl EnumVariant {
     ///     }
     /// };
     /// ```      
-    fn generate_changed_to_variant_block_with_fields(&self, enum_name: &Ident) -> TokenStream2 {
-        let patch_name = delta_owned_type_name(enum_name);
-
+    fn generate_changed_to_variant_block_with_fields(
+        &self,
+        enum_name: &Ident,
+        patch_name: &Ident,
+    ) -> TokenStream2 {
         let change_to_variant = self.changed_to_variant();
 
         let patches = self.fields.to_field_value_tokens_parenthesized("patch_");
@@ -127,9 +137,14 @@ impl EnumVariant {
     ///             MyEnum::VariantB {
     ///                 some_field: field_some_field, another_field: field_another_field
     ///             } => {
-    ///                 field_some_field.apply_patch(patch0);
+    ///                 field_some_field.apply_patch(patch0).map_err(|err| {
+    ///                     err.with_parent(dipa::PathSegment::Field("some_field"))
+    ///                         .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                 })?;
     ///             }
-    ///             _ => { panic!("TODO: Return Result::Err") }
+    ///             _ => return Err(dipa::PatchError::new(
+    ///                 dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+    ///             )),
     ///         }
     ///     }
     ///     MyEnumDeltaOwned::VariantBChange_1(patch1) => {
@@ -137,9 +152,14 @@ impl EnumVariant {
     ///             MyEnum::VariantB {
     ///                 some_field: field_some_field, another_field: field_another_field
     ///             } => {
-    ///                 field_another_field.apply_patch(patch1);
+    ///                 field_another_field.apply_patch(patch1).map_err(|err| {
+    ///                     err.with_parent(dipa::PathSegment::Field("another_field"))
+    ///                         .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                 })?;
     ///             }
-    ///             _ => { panic!("TODO: Return Result::Err") }
+    ///             _ => return Err(dipa::PatchError::new(
+    ///                 dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+    ///             )),
     ///         }
     ///     }
     ///     MyEnumDeltaOwned::VariantBChange_0_1(patch0, patch1) => {
@@ -147,20 +167,33 @@ impl EnumVariant {
     ///             MyEnum::VariantB {
     ///                 some_field: field_some_field, another_field: field_another_field
     ///             } => {
-    ///                 field_some_field.apply_patch(patch0);
-    ///                 field_another_field.apply_patch(patch1);
+    ///                 field_some_field.apply_patch(patch0).map_err(|err| {
+    ///                     err.with_parent(dipa::PathSegment::Field("some_field"))
+    ///                         .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                 })?;
+    ///                 field_another_field.apply_patch(patch1).map_err(|err| {
+    ///                     err.with_parent(dipa::PathSegment::Field("another_field"))
+    ///                         .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                 })?;
     ///             }
-    ///             _ => { panic!("TODO: Return Result::Err") }
+    ///             _ => return Err(dipa::PatchError::new(
+    ///                 dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+    ///             )),
     ///         }
     ///     }
     /// };
     /// ```      
-    fn generate_field_changes(&self, enum_name: &Ident, dipa_attrs: &DipaAttrs) -> TokenStream2 {
-        let patch_name = delta_owned_type_name(enum_name);
-
+    fn generate_field_changes(
+        &self,
+        enum_name: &Ident,
+        patch_name: &Ident,
+        patch_kind: PatchKind,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
         let mut patch_blocks = vec![];
 
         let variant_pattern_fields = self.fields.to_pattern_match_tokens("field_");
+        let variant_name_str = self.name.to_string();
         let span = self.name.span();
 
         for changed_indices in
@@ -177,12 +210,16 @@ impl EnumVariant {
                 let field_idx = *field_idx as usize;
 
                 if let Some(field) = self.fields.field_at_idx(field_idx) {
-                    let field_to_patch =
-                        Ident::new(&format!("field_{}", field.name.to_string()), span);
+                    let field_to_patch = field.binding_mut("field_");
+                    let path_segment = field.path_segment();
                     let patch = &patch_fields[change_idx];
+                    let apply_patch = patch_kind.apply_field_patch(&field_to_patch, patch);
 
                     patch_statements.push(quote! {
-                        #field_to_patch.apply_patch(#patch);
+                        #apply_patch.map_err(|err| {
+                            err.with_parent(#path_segment)
+                                .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                        })?;
                     })
                 }
             }
@@ -193,7 +230,9 @@ impl EnumVariant {
                         #enum_name::#variant_name#variant_pattern_fields => {
                             #(#patch_statements)*
                         }
-                        _ => { panic!("TODO: Return Result::Err") }
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: #variant_name_str }
+                        )),
                     }
                 }
             };
@@ -219,16 +258,17 @@ mod tests {
     //! ```
     //!
     use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
     use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
     use crate::parsed_enum::EnumVariantFields;
     use crate::test_utils::assert_tokens_eq;
-    use quote::__private::Span;
+    use syn::__private::Span;
     use syn::Type;
 
     /// Verify that we generate the proper match block tokens for a variant that has not changed.
     #[test]
     fn variant_no_change() {
-        let tokens = variant_a().generate_no_change_block(&enum_name());
+        let tokens = variant_a().generate_no_change_block(&patch_name());
 
         let expected = quote! {MyEnumDeltaOwned::VariantANoChange => {}};
 
@@ -239,7 +279,8 @@ mod tests {
     /// fields.
     #[test]
     fn changed_to_no_fields() {
-        let tokens = variant_a().generate_changed_to_variant_block_no_fields(&enum_name());
+        let tokens =
+            variant_a().generate_changed_to_variant_block_no_fields(&enum_name(), &patch_name());
 
         let expected = quote! {MyEnumDeltaOwned::ChangedToVariantVariantA => {
            *self = MyEnum::VariantA;
@@ -251,7 +292,8 @@ mod tests {
     /// Verify that we generate a match block for changing to a struct like enum variant.
     #[test]
     fn changed_to_struct_fields() {
-        let tokens = variant_b().generate_changed_to_variant_block_with_fields(&enum_name());
+        let tokens =
+            variant_b().generate_changed_to_variant_block_with_fields(&enum_name(), &patch_name());
 
         let expected = quote! {
             MyEnumDeltaOwned::ChangedToVariantVariantB(patch_some_field, patch_another_field) => {
@@ -268,7 +310,12 @@ mod tests {
     /// Verify that we generate a match block for applying patches to the same struct like variant.
     #[test]
     fn same_variant_struct_fields() {
-        let tokens = variant_b().generate_field_changes(&enum_name(), &DipaAttrs::default());
+        let tokens = variant_b().generate_field_changes(
+            &enum_name(),
+            &patch_name(),
+            PatchKind::Owned,
+            &DipaAttrs::default(),
+        );
 
         let expected = quote! {
             MyEnumDeltaOwned::VariantBChange_0(patch0) => {
@@ -276,9 +323,14 @@ mod tests {
                     MyEnum::VariantB {
                         some_field: field_some_field, another_field: field_another_field
                     } => {
-                        field_some_field.apply_patch(patch0);
+                        field_some_field.apply_patch(patch0).map_err(|err| {
+                            err.with_parent(dipa::PathSegment::Field("some_field"))
+                                .with_parent(dipa::PathSegment::Variant("VariantB"))
+                        })?;
                     }
-                    _ => { panic!("TODO: Return Result::Err") }
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+                    )),
                 }
             }
             MyEnumDeltaOwned::VariantBChange_1(patch1) => {
@@ -286,9 +338,14 @@ mod tests {
                     MyEnum::VariantB {
                         some_field: field_some_field, another_field: field_another_field
                     } => {
-                        field_another_field.apply_patch(patch1);
+                        field_another_field.apply_patch(patch1).map_err(|err| {
+                            err.with_parent(dipa::PathSegment::Field("another_field"))
+                                .with_parent(dipa::PathSegment::Variant("VariantB"))
+                        })?;
                     }
-                    _ => { panic!("TODO: Return Result::Err") }
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+                    )),
                 }
             }
             MyEnumDeltaOwned::VariantBChange_0_1(patch0, patch1) => {
@@ -296,10 +353,18 @@ mod tests {
                     MyEnum::VariantB {
                         some_field: field_some_field, another_field: field_another_field
                     } => {
-                        field_some_field.apply_patch(patch0);
-                        field_another_field.apply_patch(patch1);
+                        field_some_field.apply_patch(patch0).map_err(|err| {
+                            err.with_parent(dipa::PathSegment::Field("some_field"))
+                                .with_parent(dipa::PathSegment::Variant("VariantB"))
+                        })?;
+                        field_another_field.apply_patch(patch1).map_err(|err| {
+                            err.with_parent(dipa::PathSegment::Field("another_field"))
+                                .with_parent(dipa::PathSegment::Variant("VariantB"))
+                        })?;
                     }
-                    _ => { panic!("TODO: Return Result::Err") }
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+                    )),
                 }
             }
         };
@@ -310,6 +375,10 @@ mod tests {
         Ident::new("MyEnum", Span::call_site())
     }
 
+    fn patch_name() -> Ident {
+        Ident::new("MyEnumDeltaOwned", Span::call_site())
+    }
+
     fn variant_a() -> EnumVariant {
         EnumVariant {
             name: Ident::new("VariantA", Span::call_site()),
@@ -323,11 +392,15 @@ mod tests {
                 name: quote! {some_field},
                 ty: Type::Verbatim(quote! {Vec<f32>}),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
             StructOrTupleField {
                 name: quote! {another_field},
                 ty: Type::Verbatim(quote! {Option<u64>}),
                 span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
             },
         ];
         EnumVariant {
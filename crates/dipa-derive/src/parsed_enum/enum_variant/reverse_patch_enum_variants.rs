@@ -0,0 +1,262 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::ChangedFieldIndices;
+use crate::parsed_enum::{delta_owned_type_name, EnumVariant};
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl EnumVariant {
+    /// Generate the various blocks that patch an enum variant and return the inverse patch.
+    ///
+    /// All of the example doc comments below are based on the following enum.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantB { some_field: Vec<f32>, another_field: Option<u64> },
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// See [crate::parsed_enum::ParsedEnum.create_reverse_patch_match_stmt].
+    pub fn generate_reverse_patch_blocks(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> Vec<TokenStream2> {
+        let mut reverse_patch_blocks = vec![];
+
+        reverse_patch_blocks.push(self.generate_reverse_no_change_block(enum_name));
+        reverse_patch_blocks.push(self.generate_reverse_changed_to_variant_block(enum_name));
+
+        if !self.fields.is_empty() {
+            reverse_patch_blocks.push(self.generate_reverse_field_changes(enum_name, dipa_attrs));
+        }
+
+        reverse_patch_blocks
+    }
+
+    /// The patch that changes from some other variant back to this one.
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     MyEnum::VariantB {
+    ///         some_field: field_some_field, another_field: field_another_field
+    ///     } => MyEnumDeltaOwned::ChangedToVariantVariantB(field_some_field, field_another_field),
+    /// };
+    /// ```
+    pub fn generate_changed_to_previous_variant_block(&self, enum_name: &Ident) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let changed_to = self.changed_to_variant();
+
+        let variant_name = &self.name;
+        let fields = self.fields.to_pattern_match_tokens("field_");
+        let field_values = self.fields.to_field_value_tokens_parenthesized("field_");
+
+        quote! {
+            #enum_name::#variant_name#fields => #patch_name::#changed_to#field_values,
+        }
+    }
+
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     MyEnumDeltaOwned::VariantANoChange => MyEnumDeltaOwned::VariantANoChange,
+    /// };
+    /// ```
+    fn generate_reverse_no_change_block(&self, enum_name: &Ident) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let no_change = self.variant_no_change();
+
+        quote! {
+            #patch_name::#no_change => #patch_name::#no_change,
+        }
+    }
+
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0) => {
+    ///         changed_to_previous_variant(std::mem::replace(self, MyEnum::VariantC(patch_0)))
+    ///     }
+    /// };
+    /// ```
+    fn generate_reverse_changed_to_variant_block(&self, enum_name: &Ident) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let change_to_variant = self.changed_to_variant();
+
+        let patches = self.fields.to_field_value_tokens_parenthesized("patch_");
+        let set_fields = self.fields.to_pattern_match_tokens("patch_");
+
+        let variant_name = &self.name;
+
+        quote! {
+            #patch_name::#change_to_variant#patches => {
+                changed_to_previous_variant(
+                    std::mem::replace(self, #enum_name::#variant_name#set_fields)
+                )
+            }
+        }
+    }
+
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     MyEnumDeltaOwned::VariantCChange_0(patch0) => {
+    ///         match self {
+    ///             MyEnum::VariantC(field_0) => {
+    ///                 MyEnumDeltaOwned::VariantCChange_0(
+    ///                     field_0.apply_patch_returning_inverse(patch0).map_err(|err| {
+    ///                         err.with_parent(dipa::PathSegment::TupleField(0))
+    ///                             .with_parent(dipa::PathSegment::Variant("VariantC"))
+    ///                     })?
+    ///                 )
+    ///             }
+    ///             _ => return Err(dipa::PatchError::new(
+    ///                 dipa::PatchErrorReason::VariantMismatch { expected: "VariantC" }
+    ///             )),
+    ///         }
+    ///     }
+    /// };
+    /// ```
+    fn generate_reverse_field_changes(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let mut reverse_patch_blocks = vec![];
+
+        let variant_pattern_fields = self.fields.to_pattern_match_tokens("field_");
+        let variant_name_str = self.name.to_string();
+        let span = self.name.span();
+
+        for changed_indices in
+            ChangedFieldIndices::all_changed_index_combinations(self.fields.len(), dipa_attrs)
+        {
+            let variant_name = &self.name;
+            let patch_variant_name =
+                changed_indices.variant_name_ident(&self.name.to_string(), span);
+
+            let patch_fields = changed_indices.patch_field_idents(span);
+
+            let mut inverses = vec![];
+            for (change_idx, field_idx) in changed_indices.iter().enumerate() {
+                let field_idx = *field_idx as usize;
+
+                if let Some(field) = self.fields.field_at_idx(field_idx) {
+                    let field_to_patch = field.binding_mut("field_");
+                    let path_segment = field.path_segment();
+                    let patch = &patch_fields[change_idx];
+
+                    inverses.push(quote! {
+                        #field_to_patch.apply_patch_returning_inverse(#patch).map_err(|err| {
+                            err.with_parent(#path_segment)
+                                .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                        })?
+                    })
+                }
+            }
+
+            reverse_patch_blocks.push(quote! {
+                #patch_name::#patch_variant_name(#(#patch_fields),*) => {
+                    match self {
+                        #enum_name::#variant_name#variant_pattern_fields => {
+                            #patch_name::#patch_variant_name(#(#inverses),*)
+                        }
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: #variant_name_str }
+                        )),
+                    }
+                }
+            });
+        }
+
+        quote! {
+            #(#reverse_patch_blocks)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that the inverse of changing to a variant changes back to the previous variant.
+    #[test]
+    fn changed_to_variant() {
+        let tokens = EnumVariant::one_field_variant()
+            .generate_reverse_changed_to_variant_block(&enum_name());
+
+        let expected = quote! {
+            MyEnumDeltaOwned::ChangedToVariantOne(patch_0) => {
+                changed_to_previous_variant(std::mem::replace(self, MyEnum::One(patch_0)))
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we generate the patch that changes back to a previous variant that had fields.
+    #[test]
+    fn changed_to_previous_variant_with_fields() {
+        let tokens = EnumVariant::two_fields_variant()
+            .generate_changed_to_previous_variant_block(&enum_name());
+
+        let expected = quote! {
+            MyEnum::Two(field_0, field_1) => MyEnumDeltaOwned::ChangedToVariantTwo(field_0, field_1),
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we generate the patch that changes back to a previous variant without fields.
+    #[test]
+    fn changed_to_previous_variant_no_fields() {
+        let tokens = EnumVariant::no_field_variant()
+            .generate_changed_to_previous_variant_block(&enum_name());
+
+        let expected = quote! {
+            MyEnum::Two => MyEnumDeltaOwned::ChangedToVariantTwo,
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we invert the patches to the fields of the same variant.
+    #[test]
+    fn same_variant_fields() {
+        let tokens = EnumVariant::one_field_variant()
+            .generate_reverse_field_changes(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            MyEnumDeltaOwned::OneChange_0(patch0) => {
+                match self {
+                    MyEnum::One(field_0) => {
+                        MyEnumDeltaOwned::OneChange_0(
+                            field_0.apply_patch_returning_inverse(patch0).map_err(|err| {
+                                err.with_parent(dipa::PathSegment::TupleField(0usize))
+                                    .with_parent(dipa::PathSegment::Variant("One"))
+                            })?
+                        )
+                    }
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                    )),
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn enum_name() -> Ident {
+        Ident::new("MyEnum", syn::__private::Span::call_site())
+    }
+}
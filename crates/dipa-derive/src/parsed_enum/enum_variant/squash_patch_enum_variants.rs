@@ -0,0 +1,220 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_squash_field_patches_tokens;
+use crate::parsed_enum::{delta_owned_type_name, EnumVariant};
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl EnumVariant {
+    /// Generate the match arms that squash two patches when the second patch does not depend on
+    /// the first one.
+    ///
+    /// All of the example doc comments below are based on the following enum.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     (first, MyEnumDeltaOwned::VariantCNoChange) => first,
+    ///     (_, MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0)) => {
+    ///         MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0)
+    ///     }
+    /// };
+    /// ```
+    ///
+    /// See [crate::parsed_enum::ParsedEnum.create_squash_patches_match_stmt].
+    pub fn generate_squash_overwriting_blocks(&self, enum_name: &Ident) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let no_change = self.variant_no_change();
+        let changed_to = self.changed_to_variant();
+        let patches = self.fields.to_field_value_tokens_parenthesized("patch_");
+
+        quote! {
+            (first, #patch_name::#no_change) => first,
+            (_, #patch_name::#changed_to#patches) => {
+                #patch_name::#changed_to#patches
+            }
+        }
+    }
+
+    /// Generate the match arms that squash a second patch into a first patch that leaves the enum
+    /// as this variant.
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     (MyEnumDeltaOwned::VariantCNoChange, second) => second,
+    ///     (MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0), second) => {
+    ///         let mut value = MyEnum::VariantC(patch_0);
+    ///         dipa::Patchable::apply_patch(&mut value, second)?;
+    ///
+    ///         changed_to_variant(value)
+    ///     }
+    ///     (first @ MyEnumDeltaOwned::VariantCChange_0(..), second) => {
+    ///         // See [crate::multi_field_utils::make_squash_field_patches_tokens]
+    ///     }
+    /// };
+    /// ```
+    pub fn generate_squash_dependent_blocks(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let variant_name = &self.name;
+        let no_change = self.variant_no_change();
+        let changed_to = self.changed_to_variant();
+        let patches = self.fields.to_field_value_tokens_parenthesized("patch_");
+        let set_fields = self.fields.to_pattern_match_tokens("patch_");
+
+        let field_changes = if self.fields.is_empty() {
+            quote! {}
+        } else {
+            self.generate_squash_field_changes(enum_name, dipa_attrs)
+        };
+
+        quote! {
+            (#patch_name::#no_change, second) => second,
+            (#patch_name::#changed_to#patches, second) => {
+                let mut value = #enum_name::#variant_name#set_fields;
+                dipa::Patchable::apply_patch(&mut value, second)?;
+
+                changed_to_variant(value)
+            }
+            #field_changes
+        }
+    }
+
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     (first @ MyEnumDeltaOwned::VariantCChange_0(..), second) => {
+    ///         // See [crate::multi_field_utils::make_squash_field_patches_tokens]
+    ///     }
+    /// };
+    /// ```
+    fn generate_squash_field_changes(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let patch_name = delta_owned_type_name(enum_name);
+
+        let variant_name_str = self.name.to_string();
+        let span = self.name.span();
+
+        let field_changes = self.field_changes_pattern(enum_name, dipa_attrs);
+
+        let squash_fields = make_squash_field_patches_tokens(
+            &quote! {#patch_name},
+            &variant_name_str,
+            Some(&variant_name_str),
+            span,
+            self.fields.fields(),
+            dipa_attrs,
+        );
+
+        quote! {
+            (first @ #field_changes, second) => {
+                #squash_fields
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that a second patch that does not depend on the first patch is used as is.
+    #[test]
+    fn overwriting_blocks() {
+        let tokens =
+            EnumVariant::one_field_variant().generate_squash_overwriting_blocks(&enum_name());
+
+        let expected = quote! {
+            (first, MyEnumDeltaOwned::OneNoChange) => first,
+            (_, MyEnumDeltaOwned::ChangedToVariantOne(patch_0)) => {
+                MyEnumDeltaOwned::ChangedToVariantOne(patch_0)
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that a second patch gets squashed into a first patch that changed to a variant
+    /// without fields.
+    #[test]
+    fn dependent_blocks_no_fields() {
+        let tokens = EnumVariant::no_field_variant()
+            .generate_squash_dependent_blocks(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            (MyEnumDeltaOwned::TwoNoChange, second) => second,
+            (MyEnumDeltaOwned::ChangedToVariantTwo, second) => {
+                let mut value = MyEnum::Two;
+                dipa::Patchable::apply_patch(&mut value, second)?;
+
+                changed_to_variant(value)
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we squash the patches to the fields of the same variant.
+    #[test]
+    fn same_variant_fields() {
+        let tokens = EnumVariant::one_field_variant()
+            .generate_squash_field_changes(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            (first @ MyEnumDeltaOwned::OneChange_0(..), second) => {
+                let first0 = match first {
+                    MyEnumDeltaOwned::OneNoChange => None,
+                    MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                    )),
+                };
+                let second0 = match second {
+                    MyEnumDeltaOwned::OneNoChange => None,
+                    MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                    _ => return Err(dipa::PatchError::new(
+                        dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                    )),
+                };
+
+                let squashed0 = match (first0, second0) {
+                    (Some(first_patch), Some(second_patch)) => Some(
+                        <u16 as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+                            .map_err(|err| err
+                                .with_parent(dipa::PathSegment::TupleField(0usize))
+                                .with_parent(dipa::PathSegment::Variant("One")))?,
+                    ),
+                    (first_patch, second_patch) => second_patch.or(first_patch),
+                };
+
+                match squashed0 {
+                    None => MyEnumDeltaOwned::OneNoChange,
+                    Some(patch0) => MyEnumDeltaOwned::OneChange_0(patch0),
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn enum_name() -> Ident {
+        Ident::new("MyEnum", syn::__private::Span::call_site())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{
+    field_delta_to_owned_bounds, make_to_owned_field_delta_arms, unpack_bool_fields,
+};
+use crate::parsed_enum::{delta_owned_type_name, delta_type_name, EnumVariant};
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl EnumVariant {
+    /// Generate the match arms that convert the delta variants for this enum variant into their
+    /// owned delta variants.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// For `VariantC` we would generate:
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     MyEnumDelta::ChangedToVariantVariantC(field_0) => {
+    ///         MyEnumDeltaOwned::ChangedToVariantVariantC(
+    ///             dipa::ToOwnedDelta::to_owned_delta(field_0)
+    ///         )
+    ///     }
+    ///     MyEnumDelta::VariantCNoChange => MyEnumDeltaOwned::VariantCNoChange,
+    ///     MyEnumDelta::VariantCChange_0(patch0) => MyEnumDeltaOwned::VariantCChange_0(
+    ///         dipa::ToOwnedDelta::to_owned_delta(patch0)
+    ///     ),
+    /// };
+    /// ```
+    pub fn generate_to_owned_delta_arms(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let delta_name = delta_type_name(enum_name);
+        let delta_owned_name = delta_owned_type_name(enum_name);
+
+        let changed_to = self.changed_to_variant();
+
+        if self.fields.is_empty() {
+            let no_change = self.variant_no_change();
+
+            return quote! {
+                #delta_name::#changed_to => #delta_owned_name::#changed_to,
+                #delta_name::#no_change => #delta_owned_name::#no_change,
+            };
+        }
+
+        let values = self.fields.to_field_value_tokens_parenthesized("field_");
+        let owned_values: Vec<TokenStream2> = unpack_bool_fields(&self.fields)
+            .iter()
+            .map(|field| {
+                let field_name = field.name.to_string();
+                let value = Ident::new(&format!("field_{}", field_name.trim()), field.span);
+                quote! { dipa::ToOwnedDelta::to_owned_delta(#value) }
+            })
+            .collect();
+
+        let field_delta_arms = make_to_owned_field_delta_arms(
+            &delta_name,
+            &delta_owned_name,
+            &self.name.to_string(),
+            self.name.span(),
+            self.fields.len(),
+            dipa_attrs,
+        );
+
+        quote! {
+            #delta_name::#changed_to#values => {
+                #delta_owned_name::#changed_to(#(#owned_values),*)
+            }
+            #(#field_delta_arms)*
+        }
+    }
+
+    /// The bounds that allow this variant's delta variants to be converted into owned delta
+    /// variants.
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     &'e i16: dipa::ToOwnedDelta<i16>,
+    ///     <i16 as dipa::Diffable<'s, 'e, i16>>::Delta:
+    ///         dipa::ToOwnedDelta<<i16 as dipa::Diffable<'static, 'static, i16>>::DeltaOwned>
+    /// };
+    /// ```
+    pub fn to_owned_delta_bounds(&self, enum_name: &Ident) -> Vec<TokenStream2> {
+        let mut bounds: Vec<TokenStream2> = unpack_bool_fields(&self.fields)
+            .iter()
+            .map(|field| {
+                let ty = &field.ty;
+                quote! { &'e #ty: dipa::ToOwnedDelta<#ty> }
+            })
+            .collect();
+
+        bounds.extend(field_delta_to_owned_bounds(enum_name, &self.fields));
+
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+
+    /// Verify that we convert the delta variants of a variant without fields.
+    #[test]
+    fn no_fields_variant() {
+        let tokens = EnumVariant::no_field_variant().generate_to_owned_delta_arms(
+            &Ident::new("MyEnum", Span::call_site()),
+            &DipaAttrs::default(),
+        );
+
+        let expected = quote! {
+            MyEnumDelta::ChangedToVariantTwo => MyEnumDeltaOwned::ChangedToVariantTwo,
+            MyEnumDelta::TwoNoChange => MyEnumDeltaOwned::TwoNoChange,
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we convert the delta variants of a variant with one field.
+    #[test]
+    fn one_field_variant() {
+        let variant = EnumVariant::one_field_variant();
+        let tokens = variant.generate_to_owned_delta_arms(
+            &Ident::new("MyEnum", Span::call_site()),
+            &DipaAttrs::default(),
+        );
+
+        let expected = quote! {
+            MyEnumDelta::ChangedToVariantOne(field_0) => {
+                MyEnumDeltaOwned::ChangedToVariantOne(dipa::ToOwnedDelta::to_owned_delta(field_0))
+            }
+            MyEnumDelta::OneNoChange => MyEnumDeltaOwned::OneNoChange,
+            MyEnumDelta::OneChange_0(patch0) => MyEnumDeltaOwned::OneChange_0(
+                dipa::ToOwnedDelta::to_owned_delta(patch0)
+            ),
+        };
+        assert_tokens_eq(&tokens, &expected);
+
+        let bounds = variant.to_owned_delta_bounds(&Ident::new("MyEnum", Span::call_site()));
+        let expected = quote! {
+            &'e u16: dipa::ToOwnedDelta<u16>,
+            <u16 as dipa::Diffable<'s, 'e, u16>>::Delta:
+                dipa::ToOwnedDelta<<u16 as dipa::Diffable<'static, 'static, u16>>::DeltaOwned>
+        };
+        assert_tokens_eq(&quote! {#(#bounds),*}, &expected);
+    }
+}
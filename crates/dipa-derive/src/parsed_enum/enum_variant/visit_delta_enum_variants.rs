@@ -0,0 +1,177 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_visit_field_delta_arms;
+use crate::parsed_enum::{delta_type_name, EnumVariant};
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl EnumVariant {
+    /// Generate the match arm that visits a delta that changes the enum to this variant.
+    ///
+    /// All of the example doc comments below are based on the following enum.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     (_, MyEnumDelta::ChangedToVariantVariantC { .. }) => {
+    ///         visitor.visit_change(dipa::DeltaChange::Variant {
+    ///             old: start_variant,
+    ///             new: "VariantC",
+    ///         });
+    ///     }
+    /// };
+    /// ```
+    ///
+    /// See [crate::parsed_enum::ParsedEnum.create_visit_delta_impl].
+    pub fn generate_visit_changed_to_variant_block(&self, enum_name: &Ident) -> TokenStream2 {
+        let delta_name = delta_type_name(enum_name);
+        let changed_to_variant = self.changed_to_variant();
+        let variant_name_str = self.name.to_string();
+
+        quote! {
+            (_, #delta_name::#changed_to_variant { .. }) => {
+                visitor.visit_change(dipa::DeltaChange::Variant {
+                    old: start_variant,
+                    new: #variant_name_str,
+                });
+            }
+        }
+    }
+
+    /// Generate the match arm that visits the changes to this variant's fields.
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// // VariantA
+    /// quote! {
+    ///     (MyEnum::VariantA { .. }, _) => {}
+    /// };
+    ///
+    /// // VariantC
+    /// quote! {
+    ///     (MyEnum::VariantC(start_0), delta) => {
+    ///         visitor.enter(dipa::PathSegment::Variant("VariantC"));
+    ///         match delta {
+    ///             // See [crate::multi_field_utils::make_visit_field_delta_arms]
+    ///         }
+    ///         visitor.exit();
+    ///     }
+    /// };
+    /// ```
+    pub fn generate_visit_field_changes(
+        &self,
+        enum_name: &Ident,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let variant_name = &self.name;
+
+        if self.fields.is_empty() {
+            return quote! {
+                (#enum_name::#variant_name { .. }, _) => {}
+            };
+        }
+
+        let delta_name = delta_type_name(enum_name);
+        let variant_name_str = variant_name.to_string();
+
+        let start_pattern = self.fields.to_pattern_match_tokens("start_");
+        let start_fields: Vec<TokenStream2> = self
+            .fields
+            .iter()
+            .map(|field| field.binding("start_"))
+            .collect();
+
+        let visit_arms = make_visit_field_delta_arms(
+            &quote! {#delta_name},
+            &variant_name_str,
+            variant_name.span(),
+            self.fields.fields(),
+            &start_fields,
+            dipa_attrs,
+        );
+
+        quote! {
+            (#enum_name::#variant_name#start_pattern, delta) => {
+                visitor.enter(dipa::PathSegment::Variant(#variant_name_str));
+                match delta {
+                    #(#visit_arms)*
+                }
+                visitor.exit();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that changing to another variant is visited as a variant change.
+    #[test]
+    fn changed_to_variant() {
+        let tokens = EnumVariant::one_field_variant().generate_visit_changed_to_variant_block(
+            &Ident::new("MyEnum", syn::__private::Span::call_site()),
+        );
+
+        let expected = quote! {
+            (_, MyEnumDelta::ChangedToVariantOne { .. }) => {
+                visitor.visit_change(dipa::DeltaChange::Variant {
+                    old: start_variant,
+                    new: "One",
+                });
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that a variant without fields has no field changes to visit.
+    #[test]
+    fn no_field_changes_without_fields() {
+        let tokens = EnumVariant::no_field_variant()
+            .generate_visit_field_changes(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            (MyEnum::Two { .. }, _) => {}
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    /// Verify that we visit the changes to a variant's fields inside of the variant.
+    #[test]
+    fn same_variant_fields() {
+        let tokens = EnumVariant::one_field_variant()
+            .generate_visit_field_changes(&enum_name(), &DipaAttrs::default());
+
+        let expected = quote! {
+            (MyEnum::One(start_0), delta) => {
+                visitor.enter(dipa::PathSegment::Variant("One"));
+                match delta {
+                    MyEnumDelta::OneNoChange => {}
+                    MyEnumDelta::OneChange_0(delta0) => {
+                        visitor.enter(dipa::PathSegment::TupleField(0usize));
+                        <u16 as dipa::VisitDelta<'s, 'e>>::visit_delta(start_0, delta0, visitor);
+                        visitor.exit();
+                    }
+                    _ => {}
+                }
+                visitor.exit();
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+
+    fn enum_name() -> Ident {
+        Ident::new("MyEnum", syn::__private::Span::call_site())
+    }
+}
@@ -1,9 +1,15 @@
 use crate::dipa_attribute::DipaAttrs;
-use crate::parsed_enum::{delta_owned_type_name, delta_type_name, ParsedEnum};
-use quote::ToTokens;
+use crate::multi_field_utils::{
+    borrowed_delta_where_clause, bounded_field_tys, has_recursive_fields, unpack_bool_fields,
+    StructOrTupleField,
+};
+use crate::parsed_enum::{
+    delta_borrowed_type_name, delta_owned_type_name, delta_type_name, ParsedEnum,
+};
 use quote::__private::TokenStream;
-use syn::Ident;
+use quote::ToTokens;
 use syn::__private::TokenStream2;
+use syn::{Ident, Type};
 
 impl ParsedEnum {
     /// Generate the tokens for the diff or patch type for an enum that has at least one variant
@@ -44,9 +50,16 @@ impl ParsedEnum {
 
         let maybe_lifetime = associated_type.maybe_lifetime();
 
+        let where_clause = match associated_type {
+            DipaAssociatedType::DeltaBorrowed => {
+                borrowed_delta_where_clause(&self.bounded_field_tys(), self.has_recursive_fields())
+            }
+            _ => quote! {},
+        };
+
         quote! {
             #[allow(non_camel_case_types, missing_docs)]
-            pub enum #enum_associated_ty#maybe_lifetime {
+            pub enum #enum_associated_ty#maybe_lifetime #where_clause {
                 #(#diff_ty_variants),*,
             }
         }
@@ -56,14 +69,51 @@ impl ParsedEnum {
         match associated_type {
             DipaAssociatedType::Delta => delta_type_name(&self.name),
             DipaAssociatedType::DeltaOwned => delta_owned_type_name(&self.name),
+            DipaAssociatedType::DeltaBorrowed => delta_borrowed_type_name(&self.name),
         }
     }
+
+    /// The field types of every variant that the where clauses of the generated impls bound, see
+    /// [bounded_field_tys].
+    pub fn bounded_field_tys(&self) -> Vec<Type> {
+        bounded_field_tys(
+            &self.name,
+            self.variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter()),
+        )
+    }
+
+    /// Whether or not any variant has a recursive field, see [has_recursive_fields].
+    pub fn has_recursive_fields(&self) -> bool {
+        has_recursive_fields(
+            &self.name,
+            self.variants
+                .iter()
+                .flat_map(|variant| variant.fields.iter()),
+        )
+    }
+
+    /// The field types of every variant, with packed bools replaced by the bools that they hold.
+    /// These are the types that a delta holds by value when the enum changes variants.
+    ///
+    /// Recursive fields are left out, see [bounded_field_tys].
+    pub fn bounded_value_tys(&self) -> Vec<Type> {
+        let fields: Vec<StructOrTupleField> = self
+            .variants
+            .iter()
+            .flat_map(|variant| unpack_bool_fields(&variant.fields))
+            .collect();
+
+        bounded_field_tys(&self.name, &fields)
+    }
 }
 
 #[derive(Copy, Clone)]
 pub enum DipaAssociatedType {
     Delta,
     DeltaOwned,
+    DeltaBorrowed,
 }
 
 impl ToTokens for DipaAssociatedType {
@@ -71,23 +121,25 @@ impl ToTokens for DipaAssociatedType {
         match self {
             DipaAssociatedType::Delta => tokens.extend(quote! { Delta }),
             DipaAssociatedType::DeltaOwned => tokens.extend(quote! { DeltaOwned }),
+            DipaAssociatedType::DeltaBorrowed => tokens.extend(quote! { DeltaBorrowed }),
         }
     }
 }
 
 impl DipaAssociatedType {
     fn maybe_lifetime(&self) -> TokenStream2 {
-        if self.has_lifetime() {
-            quote! { <'s, 'e> }
-        } else {
-            quote! {}
+        match self {
+            DipaAssociatedType::Delta => quote! { <'s, 'e> },
+            DipaAssociatedType::DeltaOwned => quote! {},
+            DipaAssociatedType::DeltaBorrowed => quote! { <'b> },
         }
     }
 
-    pub fn has_lifetime(&self) -> bool {
+    /// Whether the variant that an enum changed to holds references to the end state's fields.
+    pub fn borrows_end_state(&self) -> bool {
         match self {
             DipaAssociatedType::Delta => true,
-            DipaAssociatedType::DeltaOwned => false,
+            DipaAssociatedType::DeltaOwned | DipaAssociatedType::DeltaBorrowed => false,
         }
     }
 }
@@ -1,14 +1,23 @@
 use crate::dipa_attribute::DipaAttrs;
 use crate::impl_dipa;
+use crate::multi_field_utils::{
+    make_borrowed_delta_impl, make_json_patch_impl, make_visit_delta_impl, PatchKind,
+};
 use crate::parsed_enum::{delta_owned_type_name, delta_type_name, ParsedEnum};
 use syn::__private::TokenStream2;
 
 mod generate_one_batch_apply_patch_tokens;
 mod generate_one_batch_create_delta_tokens;
+mod generate_one_batch_json_patch_tokens;
+mod generate_one_batch_merge_patches_tokens;
+mod generate_one_batch_reverse_patch_tokens;
+mod generate_one_batch_squash_patches_tokens;
+mod generate_one_batch_visit_delta_tokens;
 
 impl ParsedEnum {
-    /// Generate an implementation of Diffable and Patchable for an enum that has one variant where that
-    /// variant has 2 or more fields.
+    /// Generate an implementation of Diffable, Patchable, ReversiblePatchable, SquashablePatches,
+    /// MergeablePatches, BorrowedDelta and VisitDelta for an enum that has one variant where that variant has
+    /// 2 or more fields.
     pub fn generate_single_variant_multi_field_dipa_impl(
         &self,
         dipa_attrs: &DipaAttrs,
@@ -16,22 +25,62 @@ impl ParsedEnum {
         let delta_ty = delta_type_name(&self.name);
         let delta_owned_ty = delta_owned_type_name(&self.name);
 
-        let delta_type_definitions = self.variants[0]
-            .fields
-            .unwrap_parsed_fields()
-            .generate_delta_types(&self.name.to_string(), dipa_attrs);
+        let fields = self.variants[0].fields.unwrap_parsed_fields();
+
+        let delta_type_definitions = fields.generate_delta_types(&self.name, dipa_attrs);
+        let delta_borrowed_ty = fields.delta_borrowed_name(&self.name.to_string());
 
         let dipa_impl = impl_dipa(
+            dipa_attrs,
             &self.name,
             quote! {#delta_ty<'s, 'e>},
             quote! {#delta_owned_ty},
             self.generate_single_variant_multi_field_one_batch_create_delta_tokens(dipa_attrs),
-            self.generate_single_variant_multi_field_one_batch_apply_patch_tokens(dipa_attrs),
+            self.generate_single_variant_multi_field_one_batch_apply_patch_tokens(
+                &delta_owned_ty,
+                PatchKind::Owned,
+                dipa_attrs,
+            ),
+            self.generate_single_variant_multi_field_one_batch_reverse_patch_tokens(dipa_attrs),
+            self.generate_single_variant_multi_field_one_batch_squash_patches_tokens(dipa_attrs),
+            self.generate_single_variant_multi_field_one_batch_merge_patches_tokens(dipa_attrs),
+        );
+
+        let field_tys = fields.bounded_field_tys(&self.name);
+
+        let borrowed_delta_impl = make_borrowed_delta_impl(
+            &self.name,
+            &delta_borrowed_ty,
+            &field_tys,
+            &[],
+            self.generate_single_variant_multi_field_one_batch_apply_patch_tokens(
+                &delta_borrowed_ty,
+                PatchKind::Borrowed,
+                dipa_attrs,
+            ),
+        );
+
+        let visit_delta_impl = make_visit_delta_impl(
+            &self.name,
+            &field_tys,
+            self.generate_single_variant_multi_field_one_batch_visit_delta_tokens(dipa_attrs),
+        );
+
+        let json_patch_impl = make_json_patch_impl(
+            &self.name,
+            &field_tys,
+            self.generate_single_variant_multi_field_one_batch_json_patch_tokens(dipa_attrs),
         );
 
+        let schema_fingerprint_impl = self.create_schema_fingerprint_impl(dipa_attrs);
+
         quote! {
             #delta_type_definitions
             #dipa_impl
+            #borrowed_delta_impl
+            #visit_delta_impl
+            #json_patch_impl
+            #schema_fingerprint_impl
         }
     }
 }
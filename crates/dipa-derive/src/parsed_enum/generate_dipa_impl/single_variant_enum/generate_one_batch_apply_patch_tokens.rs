@@ -1,20 +1,26 @@
 use crate::dipa_attribute::DipaAttrs;
-use crate::multi_field_utils::ChangedFieldIndices;
-use crate::parsed_enum::{delta_owned_type_name, ParsedEnum};
+use crate::multi_field_utils::{ChangedFieldIndices, PatchKind};
+use crate::parsed_enum::ParsedEnum;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 impl ParsedEnum {
     /// Generate apply_patch tokens for an enum that has a single variant with multiple
     /// fields that is using the `field_batching_strategy = "one_batch"`.
+    ///
+    /// `patch_ty` is either the owned or the borrowed delta type, since both have the same
+    /// variants.
     pub(super) fn generate_single_variant_multi_field_one_batch_apply_patch_tokens(
         &self,
+        patch_ty: &Ident,
+        patch_kind: PatchKind,
         dipa_attrs: &DipaAttrs,
     ) -> TokenStream2 {
         let enum_name = &self.name;
-        let delta_owned_name = delta_owned_type_name(enum_name);
 
         let variant = &self.variants[0];
         let variant_name = &variant.name;
+        let variant_name_str = variant_name.to_string();
 
         let fields = &variant.fields;
 
@@ -34,19 +40,23 @@ impl ParsedEnum {
                 let field_idx = *field_idx as usize;
 
                 let field = &fields[field_idx];
-                let field_name = &field.name;
 
-                let field_name = format_ident!("field_{}", field_name.to_string());
+                let field_name = field.binding_mut("field_");
+                let path_segment = field.path_segment();
 
                 let patch = &patches[idx];
+                let apply_patch = patch_kind.apply_field_patch(&field_name, patch);
 
                 field_applies.push(quote! {
-                    #field_name.apply_patch(#patch);
+                    #apply_patch.map_err(|err| {
+                        err.with_parent(#path_segment)
+                            .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                    })?;
                 })
             }
 
             patch_blocks.push(quote! {
-                #delta_owned_name::#change_name(#(#patches),*) => {
+                #patch_ty::#change_name(#(#patches),*) => {
                     #(#field_applies)*
                 }
             });
@@ -56,7 +66,7 @@ impl ParsedEnum {
             match self {
                 #enum_name::#variant_name#field_patterns => {
                     match patch {
-                        #delta_owned_name::NoChange => {}
+                        #patch_ty::NoChange => {}
                         #(#patch_blocks)*
                     }
                 }
@@ -69,6 +79,7 @@ impl ParsedEnum {
 mod tests {
     use super::*;
     use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
 
     /// Verify that we properly generate the tokens for determining the delta between two single
     /// variant multi field `field_batching_strategy = "one_batch"` enums.
@@ -76,10 +87,11 @@ mod tests {
     fn generates_tokens() {
         let parsed_enum = ParsedEnum::new_test_one_variant_two_unnamed_fields();
 
-        let tokens =
-            parsed_enum.generate_single_variant_multi_field_one_batch_apply_patch_tokens(
-                &DipaAttrs::default(),
-            );
+        let tokens = parsed_enum.generate_single_variant_multi_field_one_batch_apply_patch_tokens(
+            &Ident::new("MyEnumDeltaOwned", Span::call_site()),
+            PatchKind::Owned,
+            &DipaAttrs::default(),
+        );
 
         let expected = quote! {
             match self {
@@ -87,14 +99,26 @@ mod tests {
                     match patch {
                         MyEnumDeltaOwned::NoChange => {}
                         MyEnumDeltaOwned::Change_0(patch0) => {
-                            field_0.apply_patch(patch0);
+                            field_0.apply_patch(patch0).map_err(|err| {
+                                err.with_parent(dipa::PathSegment::TupleField(0usize))
+                                    .with_parent(dipa::PathSegment::Variant("MyVariant"))
+                            })?;
                         }
                         MyEnumDeltaOwned::Change_1(patch1) => {
-                            field_1.apply_patch(patch1);
+                            field_1.apply_patch(patch1).map_err(|err| {
+                                err.with_parent(dipa::PathSegment::TupleField(1usize))
+                                    .with_parent(dipa::PathSegment::Variant("MyVariant"))
+                            })?;
                         }
                         MyEnumDeltaOwned::Change_0_1(patch0, patch1) => {
-                            field_0.apply_patch(patch0);
-                            field_1.apply_patch(patch1);
+                            field_0.apply_patch(patch0).map_err(|err| {
+                                err.with_parent(dipa::PathSegment::TupleField(0usize))
+                                    .with_parent(dipa::PathSegment::Variant("MyVariant"))
+                            })?;
+                            field_1.apply_patch(patch1).map_err(|err| {
+                                err.with_parent(dipa::PathSegment::TupleField(1usize))
+                                    .with_parent(dipa::PathSegment::Variant("MyVariant"))
+                            })?;
                         }
                     }
                 }
@@ -80,8 +80,8 @@ impl ParsedEnum {
         }
     }
 
-    /// let diff0 = start_fielda.create_delta_towards(&end_fielda);
-    /// let diff1 = start_fieldb.create_delta_towards(&end_fieldb);
+    /// let diff0 = start_fielda.create_delta_towards_version(&end_fielda, version);
+    /// let diff1 = start_fieldb.create_delta_towards_version(&end_fieldb, version);
     fn field_diff_statements(&self, fields: &EnumVariantFields) -> Vec<TokenStream2> {
         fields
             .iter()
@@ -91,11 +91,20 @@ impl ParsedEnum {
 
                 let diff_idx_ident = Ident::new(&format!("diff{}", field_idx), field_name.span());
 
+                if field.is_packed_bools() {
+                    let start = field.binding("start_");
+                    let end = field.binding("end_");
+
+                    return quote! {
+                        let #diff_idx_ident = dipa::Diffable::create_delta_towards_version(#start, #end, version);
+                    };
+                }
+
                 let start_ident = format_ident!("start_{}", field.name.to_string());
                 let end_ident = format_ident!("end_{}", field.name.to_string());
 
                 quote! {
-                    let #diff_idx_ident = #start_ident.create_delta_towards(&#end_ident);
+                    let #diff_idx_ident = #start_ident.create_delta_towards_version(&#end_ident, version);
                 }
             })
             .collect()
@@ -139,8 +148,8 @@ mod tests {
                     MyEnum::MyVariant(start_0, start_1),
                     MyEnum::MyVariant(end_0, end_1)
                 ) => {
-                    let diff0 = start_0.create_delta_towards(&end_0);
-                    let diff1 = start_1.create_delta_towards(&end_1);
+                    let diff0 = start_0.create_delta_towards_version(&end_0, version);
+                    let diff1 = start_1.create_delta_towards_version(&end_1, version);
 
                     let did_change = diff0.did_change || diff1.did_change;
 
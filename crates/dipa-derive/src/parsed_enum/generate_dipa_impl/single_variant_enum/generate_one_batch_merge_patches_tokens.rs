@@ -0,0 +1,117 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_merge_field_patches_tokens;
+use crate::parsed_enum::{delta_owned_type_name, ParsedEnum};
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Generate merge_patches tokens for an enum that has a single variant with multiple fields
+    /// that is using the `field_batching_strategy = "one_batch"`.
+    pub(super) fn generate_single_variant_multi_field_one_batch_merge_patches_tokens(
+        &self,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let enum_name = &self.name;
+        let delta_owned_name = delta_owned_type_name(enum_name);
+
+        let variant = &self.variants[0];
+        let variant_name = &variant.name;
+
+        let base_pattern = variant.fields.to_pattern_match_tokens("base_");
+        let base_fields: Vec<TokenStream2> = variant
+            .fields
+            .iter()
+            .map(|field| field.binding("base_"))
+            .collect();
+
+        let merge_fields = make_merge_field_patches_tokens(
+            &quote! {#delta_owned_name},
+            "",
+            Some(&variant_name.to_string()),
+            variant_name.span(),
+            variant.fields.fields(),
+            &base_fields,
+            dipa_attrs,
+        );
+
+        quote! {
+            let #enum_name::#variant_name#base_pattern = base;
+
+            #merge_fields
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we properly generate the tokens for merging the patches of a single variant
+    /// multi field `field_batching_strategy = "one_batch"` enum.
+    #[test]
+    fn generates_tokens() {
+        let parsed_enum = ParsedEnum::new_test_one_variant_two_unnamed_fields();
+
+        let tokens = parsed_enum
+            .generate_single_variant_multi_field_one_batch_merge_patches_tokens(
+                &DipaAttrs::default(),
+            );
+
+        let expected = quote! {
+            let MyEnum::MyVariant(base_0, base_1) = base;
+
+            let (a0, a1) = match a {
+                MyEnumDeltaOwned::NoChange => (None, None),
+                MyEnumDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyEnumDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyEnumDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+            let (b0, b1) = match b {
+                MyEnumDeltaOwned::NoChange => (None, None),
+                MyEnumDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyEnumDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyEnumDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+
+            let mut conflicts = dipa::MergeConflicts::default();
+
+            let merged0 = match (a0, b0) {
+                (Some(a_patch), Some(b_patch)) => {
+                    <u16 as dipa::MergeablePatches<_>>::merge_patches(base_0, a_patch, b_patch)
+                        .map_err(|err| conflicts.extend(
+                            err
+                                .with_parent(dipa::PathSegment::TupleField(0usize))
+                                .with_parent(dipa::PathSegment::Variant("MyVariant"))
+                        ))
+                        .ok()
+                }
+                (a_patch, b_patch) => a_patch.or(b_patch),
+            };
+            let merged1 = match (a1, b1) {
+                (Some(a_patch), Some(b_patch)) => {
+                    <u32 as dipa::MergeablePatches<_>>::merge_patches(base_1, a_patch, b_patch)
+                        .map_err(|err| conflicts.extend(
+                            err
+                                .with_parent(dipa::PathSegment::TupleField(1usize))
+                                .with_parent(dipa::PathSegment::Variant("MyVariant"))
+                        ))
+                        .ok()
+                }
+                (a_patch, b_patch) => a_patch.or(b_patch),
+            };
+
+            if !conflicts.is_empty() {
+                return Err(conflicts);
+            }
+
+            match (merged0, merged1) {
+                (None, None) => MyEnumDeltaOwned::NoChange,
+                (Some(patch0), None) => MyEnumDeltaOwned::Change_0(patch0),
+                (None, Some(patch1)) => MyEnumDeltaOwned::Change_1(patch1),
+                (Some(patch0), Some(patch1)) => MyEnumDeltaOwned::Change_0_1(patch0, patch1),
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
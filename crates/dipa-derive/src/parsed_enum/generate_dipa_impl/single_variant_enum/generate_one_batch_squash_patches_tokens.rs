@@ -0,0 +1,87 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_squash_field_patches_tokens;
+use crate::parsed_enum::{delta_owned_type_name, ParsedEnum};
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Generate squash_patches tokens for an enum that has a single variant with multiple fields
+    /// that is using the `field_batching_strategy = "one_batch"`.
+    pub(super) fn generate_single_variant_multi_field_one_batch_squash_patches_tokens(
+        &self,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let delta_owned_name = delta_owned_type_name(&self.name);
+
+        let variant = &self.variants[0];
+
+        make_squash_field_patches_tokens(
+            &quote! {#delta_owned_name},
+            "",
+            Some(&variant.name.to_string()),
+            variant.name.span(),
+            variant.fields.fields(),
+            dipa_attrs,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we properly generate the tokens for squashing the patches of a single variant
+    /// multi field `field_batching_strategy = "one_batch"` enum.
+    #[test]
+    fn generates_tokens() {
+        let parsed_enum = ParsedEnum::new_test_one_variant_two_unnamed_fields();
+
+        let tokens = parsed_enum
+            .generate_single_variant_multi_field_one_batch_squash_patches_tokens(
+                &DipaAttrs::default(),
+            );
+
+        let expected = quote! {
+            let (first0, first1) = match first {
+                MyEnumDeltaOwned::NoChange => (None, None),
+                MyEnumDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyEnumDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyEnumDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+            let (second0, second1) = match second {
+                MyEnumDeltaOwned::NoChange => (None, None),
+                MyEnumDeltaOwned::Change_0(patch0) => (Some(patch0), None),
+                MyEnumDeltaOwned::Change_1(patch1) => (None, Some(patch1)),
+                MyEnumDeltaOwned::Change_0_1(patch0, patch1) => (Some(patch0), Some(patch1)),
+            };
+
+            let squashed0 = match (first0, second0) {
+                (Some(first_patch), Some(second_patch)) => Some(
+                    <u16 as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+                        .map_err(|err| err
+                            .with_parent(dipa::PathSegment::TupleField(0usize))
+                            .with_parent(dipa::PathSegment::Variant("MyVariant")))?,
+                ),
+                (first_patch, second_patch) => second_patch.or(first_patch),
+            };
+            let squashed1 = match (first1, second1) {
+                (Some(first_patch), Some(second_patch)) => Some(
+                    <u32 as dipa::SquashablePatches<_>>::squash_patches(first_patch, second_patch)
+                        .map_err(|err| err
+                            .with_parent(dipa::PathSegment::TupleField(1usize))
+                            .with_parent(dipa::PathSegment::Variant("MyVariant")))?,
+                ),
+                (first_patch, second_patch) => second_patch.or(first_patch),
+            };
+
+            match (squashed0, squashed1) {
+                (None, None) => MyEnumDeltaOwned::NoChange,
+                (Some(patch0), None) => MyEnumDeltaOwned::Change_0(patch0),
+                (None, Some(patch1)) => MyEnumDeltaOwned::Change_1(patch1),
+                (Some(patch0), Some(patch1)) => MyEnumDeltaOwned::Change_0_1(patch0, patch1),
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
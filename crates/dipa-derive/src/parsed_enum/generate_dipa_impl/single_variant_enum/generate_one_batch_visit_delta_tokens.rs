@@ -0,0 +1,94 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_visit_field_delta_arms;
+use crate::parsed_enum::{delta_type_name, ParsedEnum};
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Generate visit_delta tokens for an enum that has a single variant with multiple fields
+    /// that is using the `field_batching_strategy = "one_batch"`.
+    pub(super) fn generate_single_variant_multi_field_one_batch_visit_delta_tokens(
+        &self,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
+        let enum_name = &self.name;
+        let delta_name = delta_type_name(enum_name);
+
+        let variant = &self.variants[0];
+        let variant_name = &variant.name;
+        let variant_name_str = variant_name.to_string();
+
+        let start_pattern = variant.fields.to_pattern_match_tokens("start_");
+        let start_fields: Vec<TokenStream2> = variant
+            .fields
+            .iter()
+            .map(|field| field.binding("start_"))
+            .collect();
+
+        let visit_arms = make_visit_field_delta_arms(
+            &quote! {#delta_name},
+            "",
+            variant_name.span(),
+            variant.fields.fields(),
+            &start_fields,
+            dipa_attrs,
+        );
+
+        quote! {
+            let #enum_name::#variant_name#start_pattern = self;
+
+            visitor.enter(dipa::PathSegment::Variant(#variant_name_str));
+            match delta {
+                #(#visit_arms)*
+            }
+            visitor.exit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we properly generate the tokens for visiting the delta of a single variant
+    /// multi field `field_batching_strategy = "one_batch"` enum.
+    #[test]
+    fn generates_tokens() {
+        let parsed_enum = ParsedEnum::new_test_one_variant_two_unnamed_fields();
+
+        let tokens =
+            parsed_enum.generate_single_variant_multi_field_one_batch_visit_delta_tokens(
+                &DipaAttrs::default(),
+            );
+
+        let expected = quote! {
+            let MyEnum::MyVariant(start_0, start_1) = self;
+
+            visitor.enter(dipa::PathSegment::Variant("MyVariant"));
+            match delta {
+                MyEnumDelta::NoChange => {}
+                MyEnumDelta::Change_0(delta0) => {
+                    visitor.enter(dipa::PathSegment::TupleField(0usize));
+                    <u16 as dipa::VisitDelta<'s, 'e>>::visit_delta(start_0, delta0, visitor);
+                    visitor.exit();
+                }
+                MyEnumDelta::Change_1(delta1) => {
+                    visitor.enter(dipa::PathSegment::TupleField(1usize));
+                    <u32 as dipa::VisitDelta<'s, 'e>>::visit_delta(start_1, delta1, visitor);
+                    visitor.exit();
+                }
+                MyEnumDelta::Change_0_1(delta0, delta1) => {
+                    visitor.enter(dipa::PathSegment::TupleField(0usize));
+                    <u16 as dipa::VisitDelta<'s, 'e>>::visit_delta(start_0, delta0, visitor);
+                    visitor.exit();
+                    visitor.enter(dipa::PathSegment::TupleField(1usize));
+                    <u32 as dipa::VisitDelta<'s, 'e>>::visit_delta(start_1, delta1, visitor);
+                    visitor.exit();
+                }
+            }
+            visitor.exit();
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
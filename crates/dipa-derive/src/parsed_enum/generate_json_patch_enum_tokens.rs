@@ -0,0 +1,138 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_json_patch_impl;
+use crate::parsed_enum::ParsedEnum;
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Generate the `dipa::ToJsonPatch` implementation for an enum that has at least one variant
+    /// with fields.
+    ///
+    /// Changing to another variant replaces the whole enum, and changes to the fields of the
+    /// current variant are pushed under the variant's name.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// The final generate tokens will look something like:
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     impl<'s, 'e> dipa::ToJsonPatch<'s, 'e> for MyEnum
+    ///     where
+    ///         i16: dipa::ToJsonPatch<'s, 'e>,
+    ///     {
+    ///         fn push_json_patch(
+    ///             &self,
+    ///             delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+    ///             path: &dipa::JsonPointer,
+    ///             patch: &mut dipa::JsonPatch,
+    ///         ) -> Result<(), dipa::JsonPatchError> {
+    ///             match (self, delta) {
+    ///                 // See [crate::parsed_enum::EnumVariant.generate_json_patch_changed_to_variant_block]
+    ///                 // See [crate::parsed_enum::EnumVariant.generate_json_patch_field_changes]
+    ///             }
+    ///
+    ///             Ok(())
+    ///         }
+    ///     }
+    /// };
+    /// ```
+    pub fn create_json_patch_impl(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let enum_name = &self.name;
+
+        let mut changed_to_variant_tokens = vec![];
+        let mut field_change_tokens = vec![];
+
+        for variant in self.variants.iter() {
+            changed_to_variant_tokens
+                .push(variant.generate_json_patch_changed_to_variant_block(enum_name));
+            field_change_tokens
+                .push(variant.generate_json_patch_field_changes(enum_name, dipa_attrs));
+        }
+
+        make_json_patch_impl(
+            enum_name,
+            &self.bounded_field_tys(),
+            quote! {
+                match (self, delta) {
+                    #(#changed_to_variant_tokens)*
+                    #(#field_change_tokens)*
+                }
+            },
+        )
+    }
+}
+
+#[cfg(all(test, feature = "json-patch"))]
+mod tests {
+    use super::*;
+    use crate::parsed_enum::EnumVariant;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::Ident;
+
+    /// Verify that we generate the ToJsonPatch implementation for an enum with data.
+    #[test]
+    fn generates_json_patch_impl() {
+        let parsed_enum = ParsedEnum {
+            name: Ident::new("MyEnum", syn::__private::Span::call_site()),
+            variants: vec![
+                EnumVariant::one_field_variant(),
+                EnumVariant::no_field_variant(),
+            ],
+        };
+
+        let tokens = parsed_enum.create_json_patch_impl(&DipaAttrs::default());
+
+        let expected = quote! {
+            impl<'s, 'e> dipa::ToJsonPatch<'s, 'e> for MyEnum
+            where
+                u16: dipa::ToJsonPatch<'s, 'e>,
+            {
+                fn push_json_patch(
+                    &self,
+                    delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+                    path: &dipa::JsonPointer,
+                    patch: &mut dipa::JsonPatch,
+                ) -> Result<(), dipa::JsonPatchError> {
+                    match (self, delta) {
+                        (_, MyEnumDelta::ChangedToVariantOne(field_0)) => {
+                            #[derive(serde::Serialize)]
+                            #[allow(non_camel_case_types, non_snake_case)]
+                            enum ChangedToVariant<'a> {
+                                One(&'a u16),
+                            }
+
+                            patch.replace(path, &ChangedToVariant::One(*field_0))?;
+                        }
+                        (_, MyEnumDelta::ChangedToVariantTwo) => {
+                            patch.replace(path, "Two")?;
+                        }
+                        (MyEnum::One(start_0), delta) => {
+                            let path = &path.child("One");
+                            match delta {
+                                MyEnumDelta::OneNoChange => {}
+                                MyEnumDelta::OneChange_0(delta0) => {
+                                    <u16 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                                        start_0, delta0, path, patch
+                                    )?;
+                                }
+                                _ => {}
+                            }
+                        }
+                        (MyEnum::Two { .. }, _) => {}
+                    }
+
+                    Ok(())
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::parsed_enum::ParsedEnum;
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Create a match statement that merges two patches for this enum that were created against
+    /// the same base value.
+    ///
+    /// A patch that leaves the enum unchanged merges into the other patch, and patches that both
+    /// change the fields of the same variant get their fields merged. Any other pair of changes,
+    /// such as both patches changing the enum to a new variant, is a conflict since telling
+    /// whether they changed it to the same value would require the fields to implement
+    /// `PartialEq`.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// The final generate tokens will look something like:
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     let merged = match (a, b) {
+    ///         (a, MyEnumDeltaOwned::VariantANoChange) => a,
+    ///         (MyEnumDeltaOwned::VariantANoChange, b) => b,
+    ///         (a, MyEnumDeltaOwned::VariantCNoChange) => a,
+    ///         (MyEnumDeltaOwned::VariantCNoChange, b) => b,
+    ///
+    ///         (a @ MyEnumDeltaOwned::VariantCChange_0(..), b @ MyEnumDeltaOwned::VariantCChange_0(..)) => {
+    ///             // See [crate::parsed_enum::EnumVariant.generate_merge_field_changes]
+    ///         }
+    ///
+    ///         _ => return Err(dipa::MergeConflicts::new(
+    ///             dipa::MergeConflictReason::ConcurrentChange
+    ///         )),
+    ///     };
+    ///
+    ///     merged
+    /// };
+    /// ```
+    pub fn create_merge_patches_match_stmt(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let enum_name = &self.name;
+
+        let mut no_change_tokens = vec![];
+        let mut field_change_tokens = vec![];
+
+        for variant in self.variants.iter() {
+            no_change_tokens.push(variant.generate_merge_no_change_blocks(enum_name));
+            field_change_tokens.push(variant.generate_merge_field_changes(enum_name, dipa_attrs));
+        }
+
+        quote! {
+            let merged = match (a, b) {
+                #(#no_change_tokens)*
+                #(#field_change_tokens)*
+
+                _ => return Err(dipa::MergeConflicts::new(
+                    dipa::MergeConflictReason::ConcurrentChange
+                )),
+            };
+
+            merged
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we generate the merge patches match statement for an enum with data.
+    #[test]
+    fn generates_merge_patches_match_stmt() {
+        let parsed_enum = ParsedEnum::new_test_two_variants_one_field();
+
+        let tokens = parsed_enum.create_merge_patches_match_stmt(&DipaAttrs::default());
+
+        let expected = quote! {
+            let merged = match (a, b) {
+                (a, MyEnumDeltaOwned::OneNoChange) => a,
+                (MyEnumDeltaOwned::OneNoChange, b) => b,
+                (a, MyEnumDeltaOwned::TwoNoChange) => a,
+                (MyEnumDeltaOwned::TwoNoChange, b) => b,
+
+                (a @ MyEnumDeltaOwned::OneChange_0(..), b @ MyEnumDeltaOwned::OneChange_0(..)) => {
+                    let base_ = match base {
+                        MyEnum::One(base_) => base_,
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                        ).into()),
+                    };
+
+                    let a0 = match a {
+                        MyEnumDeltaOwned::OneNoChange => None,
+                        MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                        ).into()),
+                    };
+                    let b0 = match b {
+                        MyEnumDeltaOwned::OneNoChange => None,
+                        MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                        ).into()),
+                    };
+
+                    let mut conflicts = dipa::MergeConflicts::default();
+
+                    let merged0 = match (a0, b0) {
+                        (Some(a_patch), Some(b_patch)) => {
+                            <u16 as dipa::MergeablePatches<_>>::merge_patches(
+                                base_,
+                                a_patch,
+                                b_patch
+                            )
+                            .map_err(|err| conflicts.extend(
+                                err
+                                    .with_parent(dipa::PathSegment::Field(""))
+                                    .with_parent(dipa::PathSegment::Variant("One"))
+                            ))
+                            .ok()
+                        }
+                        (a_patch, b_patch) => a_patch.or(b_patch),
+                    };
+
+                    if !conflicts.is_empty() {
+                        return Err(conflicts);
+                    }
+
+                    match merged0 {
+                        None => MyEnumDeltaOwned::OneNoChange,
+                        Some(patch0) => MyEnumDeltaOwned::OneChange_0(patch0),
+                    }
+                }
+
+                _ => return Err(dipa::MergeConflicts::new(
+                    dipa::MergeConflictReason::ConcurrentChange
+                )),
+            };
+
+            merged
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::PatchKind;
 use crate::parsed_enum::ParsedEnum;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 impl ParsedEnum {
     /// Create a match statement patching this enum based on the patch this enum variant with another.
@@ -39,9 +41,14 @@ impl ParsedEnum {
     ///                 MyEnum::VariantB {
     ///                     some_field: patch_some_field, another_field: patch_another_field
     ///                 } => {
-    ///                     some_field.apply_patch(patch0);
+    ///                     some_field.apply_patch(patch0).map_err(|err| {
+    ///                         err.with_parent(dipa::PathSegment::Field("some_field"))
+    ///                             .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                     })?;
     ///                 }
-    ///                 _ => { panic!("TODO: Return Result::Err") }
+    ///                 _ => return Err(dipa::PatchError::new(
+    ///                     dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+    ///                 )),
     ///             }
     ///         }
     ///         MyEnumDeltaOwned::VariantBChange_1(patch1) => {
@@ -49,9 +56,14 @@ impl ParsedEnum {
     ///                 MyEnum::VariantB {
     ///                     some_field: patch_some_field, another_field: patch_another_field
     ///                 } => {
-    ///                     another_field.apply_patch(patch1);
+    ///                     another_field.apply_patch(patch1).map_err(|err| {
+    ///                         err.with_parent(dipa::PathSegment::Field("another_field"))
+    ///                             .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                     })?;
     ///                 }
-    ///                 _ => { panic!("TODO: Return Result::Err") }
+    ///                 _ => return Err(dipa::PatchError::new(
+    ///                     dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+    ///                 )),
     ///             }
     ///         }
     ///         MyEnumDeltaOwned::VariantBChange_0_1(patch0, patch1) => {
@@ -59,10 +71,18 @@ impl ParsedEnum {
     ///                 MyEnum::VariantB {
     ///                     some_field: patch_some_field, another_field: patch_another_field
     ///                 } => {
-    ///                     some_field.apply_patch(patch0);
-    ///                     another_field.apply_patch(patch1);
+    ///                     some_field.apply_patch(patch0).map_err(|err| {
+    ///                         err.with_parent(dipa::PathSegment::Field("some_field"))
+    ///                             .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                     })?;
+    ///                     another_field.apply_patch(patch1).map_err(|err| {
+    ///                         err.with_parent(dipa::PathSegment::Field("another_field"))
+    ///                             .with_parent(dipa::PathSegment::Variant("VariantB"))
+    ///                     })?;
     ///                 }
-    ///                 _ => { panic!("TODO: Return Result::Err") }
+    ///                 _ => return Err(dipa::PatchError::new(
+    ///                     dipa::PatchErrorReason::VariantMismatch { expected: "VariantB" }
+    ///                 )),
     ///             }
     ///         }      
     ///
@@ -73,19 +93,31 @@ impl ParsedEnum {
     ///         MyEnumDeltaOwned::VariantCChange_0(patch0) => {
     ///             match self {
     ///                 MyEnum::VariantC(field_0) => {
-    ///                     field_0.apply_patch(patch0);
+    ///                     field_0.apply_patch(patch0).map_err(|err| {
+    ///                         err.with_parent(dipa::PathSegment::TupleField(0))
+    ///                             .with_parent(dipa::PathSegment::Variant("VariantC"))
+    ///                     })?;
     ///                 }
-    ///                 _ => { panic!("TODO: Return Result::Err") }
+    ///                 _ => return Err(dipa::PatchError::new(
+    ///                     dipa::PatchErrorReason::VariantMismatch { expected: "VariantC" }
+    ///                 )),
     ///             }   
     ///         }
     ///     }
     /// };
     /// ```
-    pub fn create_patch_match_stmt(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+    pub fn create_patch_match_stmt(
+        &self,
+        patch_name: &Ident,
+        patch_kind: PatchKind,
+        dipa_attrs: &DipaAttrs,
+    ) -> TokenStream2 {
         let mut inner_tokens = vec![];
 
         for variant in self.variants.iter() {
-            inner_tokens.extend_from_slice(&variant.generate_patch_blocks(&self.name, dipa_attrs));
+            inner_tokens.extend_from_slice(
+                &variant.generate_patch_blocks(&self.name, patch_name, patch_kind, dipa_attrs),
+            );
         }
 
         quote! {
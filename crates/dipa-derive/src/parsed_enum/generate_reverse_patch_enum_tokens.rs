@@ -0,0 +1,138 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::parsed_enum::ParsedEnum;
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Create a match statement that patches this enum and evaluates to the inverse patch.
+    ///
+    /// Changing to a different variant is undone by changing back to the variant that was replaced,
+    /// so the previous variant's fields get moved into the inverse patch.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// The final generate tokens will look something like:
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     let changed_to_previous_variant = |previous: MyEnum| match previous {
+    ///         MyEnum::VariantA => MyEnumDeltaOwned::ChangedToVariantVariantA,
+    ///         MyEnum::VariantC(field_0) => MyEnumDeltaOwned::ChangedToVariantVariantC(field_0),
+    ///     };
+    ///
+    ///     #[allow(unused)]
+    ///     let inverse = match patch {
+    ///         MyEnumDeltaOwned::VariantANoChange => MyEnumDeltaOwned::VariantANoChange,
+    ///         MyEnumDeltaOwned::ChangedToVariantVariantA => {
+    ///             changed_to_previous_variant(std::mem::replace(self, MyEnum::VariantA))
+    ///         }
+    ///
+    ///         MyEnumDeltaOwned::VariantCNoChange => MyEnumDeltaOwned::VariantCNoChange,
+    ///         MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0) => {
+    ///             changed_to_previous_variant(std::mem::replace(self, MyEnum::VariantC(patch_0)))
+    ///         }
+    ///         MyEnumDeltaOwned::VariantCChange_0(patch0) => {
+    ///             match self {
+    ///                 MyEnum::VariantC(field_0) => {
+    ///                     MyEnumDeltaOwned::VariantCChange_0(
+    ///                         field_0.apply_patch_returning_inverse(patch0).map_err(|err| {
+    ///                             err.with_parent(dipa::PathSegment::TupleField(0))
+    ///                                 .with_parent(dipa::PathSegment::Variant("VariantC"))
+    ///                         })?
+    ///                     )
+    ///                 }
+    ///                 _ => return Err(dipa::PatchError::new(
+    ///                     dipa::PatchErrorReason::VariantMismatch { expected: "VariantC" }
+    ///                 )),
+    ///             }
+    ///         }
+    ///     };
+    ///
+    ///     inverse
+    /// };
+    /// ```
+    pub fn create_reverse_patch_match_stmt(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let enum_name = &self.name;
+
+        let mut changed_to_previous_tokens = vec![];
+        let mut inner_tokens = vec![];
+
+        for variant in self.variants.iter() {
+            changed_to_previous_tokens
+                .push(variant.generate_changed_to_previous_variant_block(enum_name));
+            inner_tokens
+                .extend_from_slice(&variant.generate_reverse_patch_blocks(enum_name, dipa_attrs));
+        }
+
+        quote! {
+            let changed_to_previous_variant = |previous: #enum_name| match previous {
+                #(#changed_to_previous_tokens)*
+            };
+
+            #[allow(unused)] // Easier for now than prefixing unused fields with underscores
+            let inverse = match patch {
+                #(#inner_tokens)*
+            };
+
+            inverse
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we generate the reverse patch match statement for an enum with data.
+    #[test]
+    fn generates_reverse_patch_match_stmt() {
+        let parsed_enum = ParsedEnum::new_test_two_variants_one_field();
+
+        let tokens = parsed_enum.create_reverse_patch_match_stmt(&DipaAttrs::default());
+
+        let expected = quote! {
+            let changed_to_previous_variant = |previous: MyEnum| match previous {
+                MyEnum::One(field_) => MyEnumDeltaOwned::ChangedToVariantOne(field_),
+                MyEnum::Two => MyEnumDeltaOwned::ChangedToVariantTwo,
+            };
+
+            #[allow(unused)]
+            let inverse = match patch {
+                MyEnumDeltaOwned::OneNoChange => MyEnumDeltaOwned::OneNoChange,
+                MyEnumDeltaOwned::ChangedToVariantOne(patch_) => {
+                    changed_to_previous_variant(std::mem::replace(self, MyEnum::One(patch_)))
+                }
+                MyEnumDeltaOwned::OneChange_0(patch0) => {
+                    match self {
+                        MyEnum::One(field_) => {
+                            MyEnumDeltaOwned::OneChange_0(
+                                field_.apply_patch_returning_inverse(patch0).map_err(|err| {
+                                    err.with_parent(dipa::PathSegment::Field(""))
+                                        .with_parent(dipa::PathSegment::Variant("One"))
+                                })?
+                            )
+                        }
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                        )),
+                    }
+                }
+                MyEnumDeltaOwned::TwoNoChange => MyEnumDeltaOwned::TwoNoChange,
+                MyEnumDeltaOwned::ChangedToVariantTwo => {
+                    changed_to_previous_variant(std::mem::replace(self, MyEnum::Two))
+                }
+            };
+
+            inverse
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
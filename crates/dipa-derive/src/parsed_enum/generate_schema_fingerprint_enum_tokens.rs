@@ -0,0 +1,109 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::{
+    fingerprint_batching_write, fingerprint_fields_writes, make_schema_fingerprint_impl,
+};
+use crate::parsed_enum::ParsedEnum;
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Generate the `dipa::SchemaFingerprint` implementation for an enum that has at least one
+    /// variant with fields.
+    ///
+    /// Every variant's name is hashed in order followed by its fields, since both the variant
+    /// order and the field order decide how the delta gets encoded.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// The final generate tokens will look something like:
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     impl<'s, 'e> dipa::SchemaFingerprint<'s, 'e> for MyEnum
+    ///     where
+    ///         i16: dipa::SchemaFingerprint<'s, 'e>,
+    ///     {
+    ///         const FINGERPRINT: u64 = dipa::FingerprintHasher::new()
+    ///             .write_str("MyEnum")
+    ///             .write_str("one_batch")
+    ///             .write_str("VariantA")
+    ///             .write_u64(0u64)
+    ///             .write_str("VariantC")
+    ///             .write_u64(1u64)
+    ///             .write_str("0")
+    ///             .write_u64(<i16 as dipa::SchemaFingerprint<'s, 'e>>::FINGERPRINT)
+    ///             .finish();
+    ///     }
+    /// };
+    /// ```
+    pub fn create_schema_fingerprint_impl(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let batching_write = fingerprint_batching_write(dipa_attrs);
+
+        let variant_writes = self.variants.iter().map(|variant| {
+            let variant_name_str = variant.name.to_string();
+            let fields_writes = fingerprint_fields_writes(&self.name, variant.fields.fields());
+
+            quote! {
+                .write_str(#variant_name_str)
+                #fields_writes
+            }
+        });
+
+        make_schema_fingerprint_impl(
+            &self.name,
+            &self.bounded_field_tys(),
+            quote! {
+                #batching_write
+                #(#variant_writes)*
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_enum::EnumVariant;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::Ident;
+
+    /// Verify that we hash every variant along with its fields.
+    #[test]
+    fn generates_schema_fingerprint_impl() {
+        let parsed_enum = ParsedEnum {
+            name: Ident::new("MyEnum", syn::__private::Span::call_site()),
+            variants: vec![
+                EnumVariant::one_field_variant(),
+                EnumVariant::no_field_variant(),
+            ],
+        };
+
+        let tokens = parsed_enum.create_schema_fingerprint_impl(&DipaAttrs::default());
+
+        let expected = quote! {
+            impl<'s, 'e> dipa::SchemaFingerprint<'s, 'e> for MyEnum
+            where
+                u16: dipa::SchemaFingerprint<'s, 'e>,
+            {
+                const FINGERPRINT: u64 = dipa::FingerprintHasher::new()
+                    .write_str("MyEnum")
+                    .write_str("one_batch")
+                    .write_str("One")
+                    .write_u64(1u64)
+                    .write_str("0")
+                    .write_u64(<u16 as dipa::SchemaFingerprint<'s, 'e>>::FINGERPRINT)
+                    .write_str("Two")
+                    .write_u64(0u64)
+                    .finish();
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
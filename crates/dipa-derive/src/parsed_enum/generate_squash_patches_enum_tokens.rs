@@ -0,0 +1,174 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::parsed_enum::ParsedEnum;
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Create a match statement that squashes two patches for this enum into one patch.
+    ///
+    /// A second patch that does not depend on the state that the first patch left the enum in
+    /// gets used as is. Otherwise the second patch is squashed into the first one.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// The final generate tokens will look something like:
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     let changed_to_variant = |value: MyEnum| match value {
+    ///         MyEnum::VariantA => MyEnumDeltaOwned::ChangedToVariantVariantA,
+    ///         MyEnum::VariantC(field_0) => MyEnumDeltaOwned::ChangedToVariantVariantC(field_0),
+    ///     };
+    ///
+    ///     let squashed = match (first, second) {
+    ///         (first, MyEnumDeltaOwned::VariantANoChange) => first,
+    ///         (_, MyEnumDeltaOwned::ChangedToVariantVariantA) => {
+    ///             MyEnumDeltaOwned::ChangedToVariantVariantA
+    ///         }
+    ///         (first, MyEnumDeltaOwned::VariantCNoChange) => first,
+    ///         (_, MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0)) => {
+    ///             MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0)
+    ///         }
+    ///
+    ///         (MyEnumDeltaOwned::VariantANoChange, second) => second,
+    ///         (MyEnumDeltaOwned::ChangedToVariantVariantA, second) => {
+    ///             let mut value = MyEnum::VariantA;
+    ///             dipa::Patchable::apply_patch(&mut value, second)?;
+    ///
+    ///             changed_to_variant(value)
+    ///         }
+    ///         (MyEnumDeltaOwned::VariantCNoChange, second) => second,
+    ///         (MyEnumDeltaOwned::ChangedToVariantVariantC(patch_0), second) => {
+    ///             let mut value = MyEnum::VariantC(patch_0);
+    ///             dipa::Patchable::apply_patch(&mut value, second)?;
+    ///
+    ///             changed_to_variant(value)
+    ///         }
+    ///         (first @ MyEnumDeltaOwned::VariantCChange_0(..), second) => {
+    ///             // See [crate::multi_field_utils::make_squash_field_patches_tokens]
+    ///         }
+    ///     };
+    ///
+    ///     squashed
+    /// };
+    /// ```
+    pub fn create_squash_patches_match_stmt(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let enum_name = &self.name;
+
+        let mut changed_to_variant_tokens = vec![];
+        let mut overwriting_tokens = vec![];
+        let mut dependent_tokens = vec![];
+
+        for variant in self.variants.iter() {
+            changed_to_variant_tokens
+                .push(variant.generate_changed_to_previous_variant_block(enum_name));
+            overwriting_tokens.push(variant.generate_squash_overwriting_blocks(enum_name));
+            dependent_tokens.push(variant.generate_squash_dependent_blocks(enum_name, dipa_attrs));
+        }
+
+        quote! {
+            let changed_to_variant = |value: #enum_name| match value {
+                #(#changed_to_variant_tokens)*
+            };
+
+            let squashed = match (first, second) {
+                #(#overwriting_tokens)*
+                #(#dependent_tokens)*
+            };
+
+            squashed
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we generate the squash patches match statement for an enum with data.
+    #[test]
+    fn generates_squash_patches_match_stmt() {
+        let parsed_enum = ParsedEnum::new_test_two_variants_one_field();
+
+        let tokens = parsed_enum.create_squash_patches_match_stmt(&DipaAttrs::default());
+
+        let expected = quote! {
+            let changed_to_variant = |value: MyEnum| match value {
+                MyEnum::One(field_) => MyEnumDeltaOwned::ChangedToVariantOne(field_),
+                MyEnum::Two => MyEnumDeltaOwned::ChangedToVariantTwo,
+            };
+
+            let squashed = match (first, second) {
+                (first, MyEnumDeltaOwned::OneNoChange) => first,
+                (_, MyEnumDeltaOwned::ChangedToVariantOne(patch_)) => {
+                    MyEnumDeltaOwned::ChangedToVariantOne(patch_)
+                }
+                (first, MyEnumDeltaOwned::TwoNoChange) => first,
+                (_, MyEnumDeltaOwned::ChangedToVariantTwo) => {
+                    MyEnumDeltaOwned::ChangedToVariantTwo
+                }
+
+                (MyEnumDeltaOwned::OneNoChange, second) => second,
+                (MyEnumDeltaOwned::ChangedToVariantOne(patch_), second) => {
+                    let mut value = MyEnum::One(patch_);
+                    dipa::Patchable::apply_patch(&mut value, second)?;
+
+                    changed_to_variant(value)
+                }
+                (first @ MyEnumDeltaOwned::OneChange_0(..), second) => {
+                    let first0 = match first {
+                        MyEnumDeltaOwned::OneNoChange => None,
+                        MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                        )),
+                    };
+                    let second0 = match second {
+                        MyEnumDeltaOwned::OneNoChange => None,
+                        MyEnumDeltaOwned::OneChange_0(patch0) => Some(patch0),
+                        _ => return Err(dipa::PatchError::new(
+                            dipa::PatchErrorReason::VariantMismatch { expected: "One" }
+                        )),
+                    };
+
+                    let squashed0 = match (first0, second0) {
+                        (Some(first_patch), Some(second_patch)) => Some(
+                            <u16 as dipa::SquashablePatches<_>>::squash_patches(
+                                first_patch,
+                                second_patch
+                            )
+                            .map_err(|err| err
+                                .with_parent(dipa::PathSegment::Field(""))
+                                .with_parent(dipa::PathSegment::Variant("One")))?,
+                        ),
+                        (first_patch, second_patch) => second_patch.or(first_patch),
+                    };
+
+                    match squashed0 {
+                        None => MyEnumDeltaOwned::OneNoChange,
+                        Some(patch0) => MyEnumDeltaOwned::OneChange_0(patch0),
+                    }
+                }
+
+                (MyEnumDeltaOwned::TwoNoChange, second) => second,
+                (MyEnumDeltaOwned::ChangedToVariantTwo, second) => {
+                    let mut value = MyEnum::Two;
+                    dipa::Patchable::apply_patch(&mut value, second)?;
+
+                    changed_to_variant(value)
+                }
+            };
+
+            squashed
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_to_owned_delta_impl;
+use crate::parsed_enum::{delta_owned_type_name, delta_type_name, ParsedEnum};
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Generate the `dipa::ToOwnedDelta` implementation for the delta type of an enum that has at
+    /// least one variant with fields.
+    ///
+    /// See [crate::parsed_enum::EnumVariant.generate_to_owned_delta_arms].
+    pub fn create_to_owned_delta_impl(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let mut arms = vec![];
+        let mut bounds = vec![];
+
+        for variant in self.variants.iter() {
+            arms.push(variant.generate_to_owned_delta_arms(&self.name, dipa_attrs));
+            bounds.extend(variant.to_owned_delta_bounds(&self.name));
+        }
+
+        make_to_owned_delta_impl(
+            &delta_type_name(&self.name),
+            &delta_owned_type_name(&self.name),
+            &bounds,
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we generate the ToOwnedDelta implementation for an enum's delta type.
+    #[test]
+    fn generates_to_owned_delta_impl() {
+        let parsed_enum = ParsedEnum::new_test_two_variants_one_field();
+
+        let tokens = parsed_enum.create_to_owned_delta_impl(&DipaAttrs::default());
+
+        let expected = quote! {
+            impl<'s, 'e> dipa::ToOwnedDelta<MyEnumDeltaOwned> for MyEnumDelta<'s, 'e>
+            where
+                &'e u16: dipa::ToOwnedDelta<u16>,
+                <u16 as dipa::Diffable<'s, 'e, u16>>::Delta:
+                    dipa::ToOwnedDelta<<u16 as dipa::Diffable<'static, 'static, u16>>::DeltaOwned>
+            {
+                fn to_owned_delta(self) -> MyEnumDeltaOwned {
+                    match self {
+                        MyEnumDelta::ChangedToVariantOne(field_) => {
+                            MyEnumDeltaOwned::ChangedToVariantOne(
+                                dipa::ToOwnedDelta::to_owned_delta(field_)
+                            )
+                        }
+                        MyEnumDelta::OneNoChange => MyEnumDeltaOwned::OneNoChange,
+                        MyEnumDelta::OneChange_0(patch0) => MyEnumDeltaOwned::OneChange_0(
+                            dipa::ToOwnedDelta::to_owned_delta(patch0)
+                        ),
+                        MyEnumDelta::ChangedToVariantTwo => MyEnumDeltaOwned::ChangedToVariantTwo,
+                        MyEnumDelta::TwoNoChange => MyEnumDeltaOwned::TwoNoChange,
+                    }
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::dipa_attribute::DipaAttrs;
+use crate::multi_field_utils::make_visit_delta_impl;
+use crate::parsed_enum::{EnumVariant, ParsedEnum};
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    /// Generate the `dipa::VisitDelta` implementation for an enum that has at least one variant
+    /// with fields.
+    ///
+    /// Changing to another variant is visited as a variant change, and changes to the fields of
+    /// the current variant are visited under the variant's name.
+    ///
+    /// ```
+    /// # #[allow(unused)]
+    /// enum MyEnum {
+    ///     VariantA,
+    ///     VariantC (i16)
+    /// }
+    /// ```
+    ///
+    /// The final generate tokens will look something like:
+    ///
+    /// ```
+    /// # use quote::quote;
+    /// quote! {
+    ///     impl<'s, 'e> dipa::VisitDelta<'s, 'e> for MyEnum
+    ///     where
+    ///         i16: dipa::VisitDelta<'s, 'e>,
+    ///     {
+    ///         fn visit_delta(
+    ///             &self,
+    ///             delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+    ///             visitor: &mut dyn dipa::DeltaVisitor,
+    ///         ) {
+    ///             let start_variant = match self {
+    ///                 Self::VariantA { .. } => "VariantA",
+    ///                 Self::VariantC { .. } => "VariantC",
+    ///             };
+    ///
+    ///             match (self, delta) {
+    ///                 // See [crate::parsed_enum::EnumVariant.generate_visit_changed_to_variant_block]
+    ///                 // See [crate::parsed_enum::EnumVariant.generate_visit_field_changes]
+    ///             }
+    ///         }
+    ///     }
+    /// };
+    /// ```
+    pub fn create_visit_delta_impl(&self, dipa_attrs: &DipaAttrs) -> TokenStream2 {
+        let enum_name = &self.name;
+
+        let mut changed_to_variant_tokens = vec![];
+        let mut field_change_tokens = vec![];
+
+        for variant in self.variants.iter() {
+            changed_to_variant_tokens
+                .push(variant.generate_visit_changed_to_variant_block(enum_name));
+            field_change_tokens.push(variant.generate_visit_field_changes(enum_name, dipa_attrs));
+        }
+
+        let start_variant = variant_name_match(&quote! {self}, &self.variants);
+
+        make_visit_delta_impl(
+            enum_name,
+            &self.bounded_field_tys(),
+            quote! {
+                let start_variant = #start_variant;
+
+                match (self, delta) {
+                    #(#changed_to_variant_tokens)*
+                    #(#field_change_tokens)*
+                }
+            },
+        )
+    }
+}
+
+/// The name of the variant that an enum value holds.
+///
+/// ```
+/// # use quote::quote;
+/// quote! {
+///     match self {
+///         Self::VariantA { .. } => "VariantA",
+///         Self::VariantC { .. } => "VariantC",
+///     }
+/// };
+/// ```
+pub fn variant_name_match(value: &TokenStream2, variants: &[EnumVariant]) -> TokenStream2 {
+    let arms = variants.iter().map(|variant| {
+        let variant_name = &variant.name;
+        let variant_name_str = variant_name.to_string();
+
+        quote! {
+            Self::#variant_name { .. } => #variant_name_str,
+        }
+    });
+
+    quote! {
+        match #value {
+            #(#arms)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::assert_tokens_eq;
+
+    /// Verify that we generate the VisitDelta implementation for an enum with data.
+    #[test]
+    fn generates_visit_delta_impl() {
+        let parsed_enum = ParsedEnum::new_test_two_variants_one_field();
+
+        let tokens = parsed_enum.create_visit_delta_impl(&DipaAttrs::default());
+
+        let expected = quote! {
+            impl<'s, 'e> dipa::VisitDelta<'s, 'e> for MyEnum
+            where
+                u16: dipa::VisitDelta<'s, 'e>,
+            {
+                fn visit_delta(
+                    &self,
+                    delta: &<Self as dipa::Diffable<'s, 'e, Self>>::Delta,
+                    visitor: &mut dyn dipa::DeltaVisitor,
+                ) {
+                    let start_variant = match self {
+                        Self::One { .. } => "One",
+                        Self::Two { .. } => "Two",
+                    };
+
+                    match (self, delta) {
+                        (_, MyEnumDelta::ChangedToVariantOne { .. }) => {
+                            visitor.visit_change(dipa::DeltaChange::Variant {
+                                old: start_variant,
+                                new: "One",
+                            });
+                        }
+                        (_, MyEnumDelta::ChangedToVariantTwo { .. }) => {
+                            visitor.visit_change(dipa::DeltaChange::Variant {
+                                old: start_variant,
+                                new: "Two",
+                            });
+                        }
+                        (MyEnum::One(start_), delta) => {
+                            visitor.enter(dipa::PathSegment::Variant("One"));
+                            match delta {
+                                MyEnumDelta::OneNoChange => {}
+                                MyEnumDelta::OneChange_0(delta0) => {
+                                    visitor.enter(dipa::PathSegment::Field(""));
+                                    <u16 as dipa::VisitDelta<'s, 'e>>::visit_delta(
+                                        start_,
+                                        delta0,
+                                        visitor
+                                    );
+                                    visitor.exit();
+                                }
+                                _ => {}
+                            }
+                            visitor.exit();
+                        }
+                        (MyEnum::Two { .. }, _) => {}
+                    }
+                }
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
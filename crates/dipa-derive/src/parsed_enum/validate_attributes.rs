@@ -0,0 +1,51 @@
+//! Validate the usage of different dipa attributes on an enum's variant fields.
+
+use crate::dipa_attribute::field_attribute_error;
+use crate::parsed_enum::ParsedEnum;
+use syn::__private::TokenStream2;
+
+impl ParsedEnum {
+    // Validate `#[dipa(...)]` attributes on the fields of an enum's variants.
+    //
+    // If any issues are found tokens containing a compile time error are returned.
+    //
+    // ```
+    // #[derive(DiffPatch)]
+    // enum Foo {
+    //     Bar {
+    //         #[dipa(...)] // <-- These are being validated.
+    //         field: u8,
+    //     },
+    // }
+    // ```
+    pub fn validate_enum_field_attributes(&self) -> Result<(), TokenStream2> {
+        let fields = self
+            .variants
+            .iter()
+            .flat_map(|variant| variant.fields.fields().iter());
+
+        let mut errs: Vec<TokenStream2> = vec![];
+        for field in fields {
+            if field.attrs.since.is_some() {
+                errs.push(field_attribute_error(
+                    field.span,
+                    "The since attribute is not yet supported on enum variant fields.",
+                ));
+            }
+            if field.attrs.epsilon.is_some() {
+                errs.push(field_attribute_error(
+                    field.span,
+                    "The epsilon attribute is not yet supported on enum variant fields.",
+                ));
+            }
+        }
+
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(quote! {
+                #(#errs)*
+            })
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use crate::dipa_attribute::{DipaAttrs, FieldBatchingStrategy};
 use crate::impl_dipa;
 use crate::multi_field_utils::{
-    make_match_diff_tokens, make_match_patch_tokens, StructOrTupleField,
+    fingerprint_batching_write, fingerprint_fields_writes, make_borrowed_delta_impl,
+    make_json_patch_field_delta_arms, make_json_patch_impl, make_match_diff_tokens,
+    make_match_patch_tokens, make_match_reverse_patch_tokens, make_merge_field_patches_tokens,
+    make_schema_fingerprint_impl, make_squash_field_patches_tokens, make_visit_delta_impl,
+    make_visit_field_delta_arms, PatchKind, StructOrTupleField,
 };
 use crate::parsed_struct::ParsedStruct;
 use syn::__private::TokenStream2;
@@ -10,6 +14,11 @@ use syn::{Ident, Type};
 
 mod generate_no_batching_apply_patch_tokens;
 mod generate_no_batching_create_delta_tokens;
+mod generate_no_batching_json_patch_tokens;
+mod generate_no_batching_merge_patches_tokens;
+mod generate_no_batching_reverse_patch_tokens;
+mod generate_no_batching_squash_patches_tokens;
+mod generate_no_batching_visit_delta_tokens;
 
 impl ParsedStruct {
     /// Generate an implementation of Diffable for a struct with 2 or more fields.
@@ -20,16 +29,24 @@ impl ParsedStruct {
         let prefix = &prefix;
         let delta_name = self.fields.delta_name(prefix);
         let delta_owned_name = self.fields.delta_owned_name(prefix);
+        let delta_borrowed_name = self.fields.delta_borrowed_name(prefix);
 
         let diff_ty = Type::Verbatim(quote! {#delta_name});
         let patch_ty = Type::Verbatim(quote! {#delta_owned_name});
+        let borrowed_patch_ty = Type::Verbatim(quote! {#delta_borrowed_name});
 
         let field_diffs_statements = field_diff_statements(&self.fields);
 
-        let (calculate_delta_tokens, apply_patch_tokens) = match dipa_attrs
-            .field_batching_strategy
-            .unwrap_or(FieldBatchingStrategy::default())
-        {
+        let (
+            calculate_delta_tokens,
+            apply_patch_tokens,
+            apply_borrowed_patch_tokens,
+            reverse_patch_tokens,
+            squash_tokens,
+            merge_tokens,
+            visit_delta_tokens,
+            json_patch_tokens,
+        ) = match dipa_attrs.field_batching_strategy.unwrap_or_default() {
             FieldBatchingStrategy::OneBatch => {
                 let field_mut_refs = field_mutable_references(&self.fields);
 
@@ -42,12 +59,77 @@ impl ParsedStruct {
                         dipa_attrs,
                     ),
                     make_match_patch_tokens(
+                        struct_name.span(),
+                        &patch_ty,
+                        &self.fields,
+                        field_mut_refs.clone(),
+                        PatchKind::Owned,
+                        dipa_attrs,
+                    ),
+                    make_match_patch_tokens(
+                        struct_name.span(),
+                        &borrowed_patch_ty,
+                        &self.fields,
+                        field_mut_refs.clone(),
+                        PatchKind::Borrowed,
+                        dipa_attrs,
+                    ),
+                    make_match_reverse_patch_tokens(
                         struct_name.span(),
                         &patch_ty,
                         &self.fields,
                         field_mut_refs,
                         dipa_attrs,
                     ),
+                    make_squash_field_patches_tokens(
+                        &quote! {#delta_owned_name},
+                        "",
+                        None,
+                        struct_name.span(),
+                        &self.fields,
+                        dipa_attrs,
+                    ),
+                    make_merge_field_patches_tokens(
+                        &quote! {#delta_owned_name},
+                        "",
+                        None,
+                        struct_name.span(),
+                        &self.fields,
+                        &field_base_references(&self.fields),
+                        dipa_attrs,
+                    ),
+                    {
+                        let visit_arms = make_visit_field_delta_arms(
+                            &quote! {#delta_name},
+                            "",
+                            struct_name.span(),
+                            &self.fields,
+                            &field_self_references(&self.fields),
+                            dipa_attrs,
+                        );
+
+                        quote! {
+                            match delta {
+                                #(#visit_arms)*
+                            }
+                        }
+                    },
+                    {
+                        let json_patch_arms = make_json_patch_field_delta_arms(
+                            &quote! {#delta_name},
+                            "",
+                            struct_name.span(),
+                            &self.fields,
+                            &field_self_references(&self.fields),
+                            dipa_attrs,
+                        );
+
+                        quote! {
+                            match delta {
+                                #(#json_patch_arms)*
+                            }
+                        }
+                    },
                 )
             }
             FieldBatchingStrategy::ManyBatches => {
@@ -55,13 +137,20 @@ impl ParsedStruct {
             }
             FieldBatchingStrategy::NoBatching => (
                 self.generate_no_batching_create_delta_tokens(),
-                self.generate_no_batching_apply_patch_tokens(),
+                self.generate_no_batching_apply_patch_tokens(PatchKind::Owned),
+                self.generate_no_batching_apply_patch_tokens(PatchKind::Borrowed),
+                self.generate_no_batching_reverse_patch_tokens(&delta_owned_name),
+                self.generate_no_batching_squash_patches_tokens(&delta_owned_name),
+                self.generate_no_batching_merge_patches_tokens(&delta_owned_name),
+                self.generate_no_batching_visit_delta_tokens(),
+                self.generate_no_batching_json_patch_tokens(),
             ),
         };
 
-        let delta_tys = self.fields.generate_delta_types(prefix, dipa_attrs);
+        let delta_tys = self.fields.generate_delta_types(struct_name, dipa_attrs);
 
         let dipa_impl = impl_dipa(
+            dipa_attrs,
             struct_name,
             quote! {#delta_name<'s, 'e>},
             quote! {#delta_owned_name},
@@ -77,12 +166,51 @@ impl ParsedStruct {
             quote! {
                #apply_patch_tokens
             },
+            quote! {
+               #reverse_patch_tokens
+            },
+            quote! {
+               #squash_tokens
+            },
+            quote! {
+               #merge_tokens
+            },
+        );
+
+        let field_tys = self.fields.bounded_field_tys(struct_name);
+
+        let borrowed_delta_impl = make_borrowed_delta_impl(
+            struct_name,
+            &delta_borrowed_name,
+            &field_tys,
+            &[],
+            apply_borrowed_patch_tokens,
+        );
+
+        let visit_delta_impl = make_visit_delta_impl(struct_name, &field_tys, visit_delta_tokens);
+
+        let json_patch_impl = make_json_patch_impl(struct_name, &field_tys, json_patch_tokens);
+
+        let batching_write = fingerprint_batching_write(dipa_attrs);
+        let fields_writes = fingerprint_fields_writes(struct_name, &self.fields);
+        let schema_fingerprint_impl = make_schema_fingerprint_impl(
+            struct_name,
+            &field_tys,
+            quote! { #batching_write #fields_writes },
         );
 
         let tokens = quote! {
             #delta_tys
 
             #dipa_impl
+
+            #borrowed_delta_impl
+
+            #visit_delta_impl
+
+            #json_patch_impl
+
+            #schema_fingerprint_impl
         };
 
         // panic!("{}", tokens.to_string());
@@ -98,20 +226,35 @@ fn field_mutable_references(fields: &[StructOrTupleField]) -> Vec<TokenStream2>
         .iter()
         .enumerate()
         .map(|(field_idx, field)| {
-            let field_name = &field.name;
-
             let mut_ref_ident =
-                Ident::new(&format!("field{}_mut_ref", field_idx), field_name.span());
+                Ident::new(&format!("field{}_mut_ref", field_idx), field.name.span());
+            let mut_ref = field.mut_reference(&quote! {self});
 
             quote! {
-            let #mut_ref_ident = &mut self.#field_name;
+            let #mut_ref_ident = #mut_ref;
             }
         })
         .collect()
 }
 
-/// let diff0 = self.some_field_name.create_delta_towards(&end_state.some_field_name);
-/// let diff1 = self.another_field_name.create_delta_towards(&end_state.another_field_name);
+/// &base.some_field_name, &base.another_field_name
+fn field_base_references(fields: &[StructOrTupleField]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .map(|field| field.reference(&quote! {base}))
+        .collect()
+}
+
+/// &self.some_field_name, &self.another_field_name
+fn field_self_references(fields: &[StructOrTupleField]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .map(|field| field.reference(&quote! {self}))
+        .collect()
+}
+
+/// let diff0 = self.some_field_name.create_delta_towards_version(&end_state.some_field_name, version);
+/// let diff1 = self.another_field_name.create_delta_towards_version(&end_state.another_field_name, version);
 /// let did_change = diff0.1.did_change || diff1.1.did_change;
 pub fn field_diff_statements(fields: &[StructOrTupleField]) -> TokenStream2 {
     let diffs = field_diff_calculations(fields);
@@ -136,8 +279,32 @@ pub fn field_diff_statements(fields: &[StructOrTupleField]) -> TokenStream2 {
     }
 }
 
-/// let diff0 = self.some_field_name.create_delta_towards(&end_state.some_field_name);
-/// let diff1 = self.another_field_name.create_delta_towards(&end_state.another_field_name);
+/// let diff0 = self.some_field_name.create_delta_towards_version(&end_state.some_field_name, version);
+/// let diff1 = self.another_field_name.create_delta_towards_version(&end_state.another_field_name, version);
+///
+/// Packed bools are diffed through the bools that they hold.
+///
+/// let diff2 = dipa::Diffable::create_delta_towards_version(
+///     &dipa::PackedBools::new([&self.bool_a, &self.bool_b], &[...]),
+///     &dipa::PackedBools::new([&end_state.bool_a, &end_state.bool_b], &[...]),
+///     version,
+/// );
+///
+/// A field with a `#[dipa(since = 2)]` attribute is left out of the delta when creating deltas for
+/// an earlier schema version.
+///
+/// let mut diff2 = self.new_field_name.create_delta_towards_version(&end_state.new_field_name, version);
+/// diff2.did_change &= version >= 2u32;
+///
+/// A field with a `#[dipa(epsilon = 0.01)]` attribute is left out of the delta when it is within
+/// epsilon of the start state, or of the last sent value for a `dipa::EpsilonTracked` field.
+///
+/// let mut diff3 = self.position.create_delta_towards_version(&end_state.position, version);
+/// diff3.did_change &= dipa::WithinEpsilon::moved_beyond_epsilon(
+///     &self.position,
+///     &end_state.position,
+///     0.01f64
+/// );
 fn field_diff_calculations(fields: &[StructOrTupleField]) -> Vec<TokenStream2> {
     fields
         .iter()
@@ -147,9 +314,122 @@ fn field_diff_calculations(fields: &[StructOrTupleField]) -> Vec<TokenStream2> {
 
             let diff_idx_ident = Ident::new(&format!("diff{}", field_idx), field_name.span());
 
-            quote! {
-            let #diff_idx_ident = self.#field_name.create_delta_towards(&end_state.#field_name);
+            if field.is_packed_bools() {
+                let start = field.reference(&quote! {self});
+                let end = field.reference(&quote! {end_state});
+
+                return quote! {
+                let #diff_idx_ident = dipa::Diffable::create_delta_towards_version(#start, #end, version);
+                };
+            }
+
+            let since = field.attrs.since.map(|since| {
+                quote! {
+                #diff_idx_ident.did_change &= version >= #since;
+                }
+            });
+            let epsilon = field.attrs.epsilon.map(|epsilon| {
+                quote! {
+                #diff_idx_ident.did_change &= dipa::WithinEpsilon::moved_beyond_epsilon(
+                    &self.#field_name,
+                    &end_state.#field_name,
+                    #epsilon
+                );
+                }
+            });
+
+            match (since, epsilon) {
+                (None, None) => quote! {
+                let #diff_idx_ident = self.#field_name.create_delta_towards_version(&end_state.#field_name, version);
+                },
+                (since, epsilon) => quote! {
+                let mut #diff_idx_ident = self.#field_name.create_delta_towards_version(&end_state.#field_name, version);
+                #since
+                #epsilon
+                },
             }
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+
+    /// Verify that a field added in a later schema version is only diffed when the schema version
+    /// that deltas are being created for includes it.
+    #[test]
+    fn since_fields_check_schema_version() {
+        let fields = vec![
+            StructOrTupleField {
+                name: quote! {health},
+                ty: Type::Verbatim(quote! {u8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {mana},
+                ty: Type::Verbatim(quote! {u8}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs {
+                    since: Some(2),
+                    ..DipaFieldAttrs::default()
+                },
+                packed_bools: vec![],
+            },
+        ];
+
+        let tokens = field_diff_calculations(&fields);
+
+        let expected = quote! {
+            let diff0 = self.health.create_delta_towards_version(&end_state.health, version);
+            let mut diff1 = self.mana.create_delta_towards_version(&end_state.mana, version);
+            diff1.did_change &= version >= 2u32;
+        };
+
+        assert_tokens_eq(&quote! {#(#tokens)*}, &expected);
+    }
+
+    /// Verify that a field with an epsilon is only counted as changed when it moved by more than
+    /// its epsilon.
+    #[test]
+    fn epsilon_fields_check_tolerance() {
+        let fields = vec![
+            StructOrTupleField {
+                name: quote! {sleeping},
+                ty: Type::Verbatim(quote! {bool}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs::default(),
+                packed_bools: vec![],
+            },
+            StructOrTupleField {
+                name: quote! {position},
+                ty: Type::Verbatim(quote! {[f32; 3]}),
+                span: Span::call_site(),
+                attrs: DipaFieldAttrs {
+                    epsilon: Some(0.5),
+                    ..DipaFieldAttrs::default()
+                },
+                packed_bools: vec![],
+            },
+        ];
+
+        let tokens = field_diff_calculations(&fields);
+
+        let expected = quote! {
+            let diff0 = self.sleeping.create_delta_towards_version(&end_state.sleeping, version);
+            let mut diff1 = self.position.create_delta_towards_version(&end_state.position, version);
+            diff1.did_change &= dipa::WithinEpsilon::moved_beyond_epsilon(
+                &self.position,
+                &end_state.position,
+                0.5f64
+            );
+        };
+
+        assert_tokens_eq(&quote! {#(#tokens)*}, &expected);
+    }
+}
@@ -1,26 +1,37 @@
+use crate::multi_field_utils::PatchKind;
 use crate::parsed_struct::generate_dipa_impl::multi_field_struct::field_mutable_references;
 use crate::parsed_struct::ParsedStruct;
-use syn::Ident;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
 impl ParsedStruct {
     // ```
     // let field0_mut_ref = &mut self.some_field_name;
     // let field1_mut_ref = &mut self.another_field_name;
     //
-    // field0_mut_ref.apply_patch(patch.some_field_name);
-    // field1_mut_ref.fieldb.apply_patch(patch.1);
+    // field0_mut_ref
+    //     .apply_patch(patch.some_field_name)
+    //     .map_err(|err| err.with_parent(dipa::PathSegment::Field("some_field_name")))?;
+    // field1_mut_ref
+    //     .apply_patch(patch.1)
+    //     .map_err(|err| err.with_parent(dipa::PathSegment::TupleField(1)))?;
     // ```
-    pub(super) fn generate_no_batching_apply_patch_tokens(&self) -> TokenStream2 {
+    pub(super) fn generate_no_batching_apply_patch_tokens(
+        &self,
+        patch_kind: PatchKind,
+    ) -> TokenStream2 {
         let field_mut_refs = field_mutable_references(&self.fields);
         let mut apply_patches = vec![];
 
         for (idx, field) in self.fields.iter().enumerate() {
             let field_name = &field.name;
             let field_mut_ref_ident = Ident::new(&format!("field{}_mut_ref", idx), field.span);
+            let path_segment = field.path_segment();
+            let apply_patch =
+                patch_kind.apply_field_patch(&field_mut_ref_ident, &quote! {patch.#field_name});
 
             apply_patches.push(quote! {
-                #field_mut_ref_ident.apply_patch(patch.#field_name);
+                #apply_patch.map_err(|err| err.with_parent(#path_segment))?;
             });
         }
 
@@ -34,10 +45,11 @@ impl ParsedStruct {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
     use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
     use crate::test_utils::assert_tokens_eq;
-    use syn::Type;
     use syn::__private::Span;
+    use syn::Type;
 
     /// Verify that we properly generate the diff for a type that uses th no_batching field batching
     /// strategy.
@@ -51,25 +63,33 @@ mod tests {
                         name: quote! {fielda},
                         ty: Type::Verbatim(quote! {u8}),
                         span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
                     },
                     StructOrTupleField {
                         name: quote! {fieldb},
                         ty: Type::Verbatim(quote! {i8}),
                         span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
                     },
                 ],
                 span: Span::call_site(),
             },
         };
 
-        let tokens = parsed_struct.generate_no_batching_apply_patch_tokens();
+        let tokens = parsed_struct.generate_no_batching_apply_patch_tokens(PatchKind::Owned);
 
         let expected = quote! {
             let field0_mut_ref = &mut self.fielda;
             let field1_mut_ref = &mut self.fieldb;
 
-            field0_mut_ref.apply_patch(patch.fielda);
-            field1_mut_ref.apply_patch(patch.fieldb);
+            field0_mut_ref
+                .apply_patch(patch.fielda)
+                .map_err(|err| err.with_parent(dipa::PathSegment::Field("fielda")))?;
+            field1_mut_ref
+                .apply_patch(patch.fieldb)
+                .map_err(|err| err.with_parent(dipa::PathSegment::Field("fieldb")))?;
         };
 
         assert_tokens_eq(&tokens, &expected);
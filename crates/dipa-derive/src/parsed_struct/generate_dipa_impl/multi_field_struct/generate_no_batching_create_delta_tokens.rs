@@ -4,8 +4,8 @@ use syn::__private::TokenStream2;
 impl ParsedStruct {
     // ```
     // // Not generated here. Just here to illustrate.
-    // let diff0 = self.some_field_name.create_delta_towards(&end_state.some_field_name);
-    // let diff1 = self.another_field_name.create_delta_towards(&end_state.another_field_name);
+    // let diff0 = self.some_field_name.create_delta_towards_version(&end_state.some_field_name, version);
+    // let diff1 = self.another_field_name.create_delta_towards_version(&end_state.another_field_name, version);
     // // End not generated here.
     //
     // let delta = MyStructDelta {
@@ -37,10 +37,11 @@ impl ParsedStruct {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
     use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
     use crate::test_utils::assert_tokens_eq;
-    use syn::Type;
     use syn::__private::Span;
+    use syn::Type;
 
     /// Verify that we properly generate the diff for a type that uses th no_batching field batching
     /// strategy.
@@ -54,11 +55,15 @@ mod tests {
                         name: quote! {fielda},
                         ty: Type::Verbatim(quote! {u8}),
                         span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
                     },
                     StructOrTupleField {
                         name: quote! {fieldb},
                         ty: Type::Verbatim(quote! {i8}),
                         span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
                     },
                 ],
                 span: Span::call_site(),
@@ -0,0 +1,82 @@
+use crate::multi_field_utils::json_patch_field_delta_tokens;
+use crate::parsed_struct::ParsedStruct;
+use syn::__private::TokenStream2;
+
+impl ParsedStruct {
+    // ```
+    // <u8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+    //     &self.some_field_name,
+    //     &delta.some_field_name,
+    //     &path.child("some_field_name"),
+    //     patch,
+    // )?;
+    // // ... Same for every other field ...
+    // ```
+    pub(super) fn generate_no_batching_json_patch_tokens(&self) -> TokenStream2 {
+        let field_operations = self.fields.iter().map(|field| {
+            let field_name = &field.name;
+
+            json_patch_field_delta_tokens(
+                field,
+                self.fields.len(),
+                &field.reference(&quote! {self}),
+                &quote! {&delta.#field_name},
+            )
+        });
+
+        quote! {
+            #(#field_operations)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+    use syn::Type;
+
+    /// Verify that we push the operations for every field's delta for a type that uses the
+    /// no_batching field batching strategy.
+    #[test]
+    fn generates_no_batching_json_patch() {
+        let parsed_struct = ParsedStruct {
+            name: format_ident!("MyStruct"),
+            fields: ParsedFields {
+                fields: vec![
+                    StructOrTupleField {
+                        name: quote! {0},
+                        ty: Type::Verbatim(quote! {u8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                    StructOrTupleField {
+                        name: quote! {1},
+                        ty: Type::Verbatim(quote! {i8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                ],
+                span: Span::call_site(),
+            },
+        };
+
+        let tokens = parsed_struct.generate_no_batching_json_patch_tokens();
+
+        let expected = quote! {
+            <u8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                &self.0, &delta.0, &path.child(0usize), patch
+            )?;
+            <i8 as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                &self.1, &delta.1, &path.child(1usize), patch
+            )?;
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
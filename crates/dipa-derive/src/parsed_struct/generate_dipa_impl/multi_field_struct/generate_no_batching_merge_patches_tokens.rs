@@ -0,0 +1,142 @@
+use crate::parsed_struct::ParsedStruct;
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl ParsedStruct {
+    // ```
+    // let mut conflicts = dipa::MergeConflicts::default();
+    //
+    // let merged0 = <u8 as dipa::MergeablePatches<_>>::merge_patches(
+    //     &base.some_field_name,
+    //     a.some_field_name,
+    //     b.some_field_name,
+    // )
+    // .map_err(|err| conflicts.extend(
+    //     err.with_parent(dipa::PathSegment::Field("some_field_name"))
+    // ))
+    // .ok();
+    // let merged1 = // ... Same as above ...
+    //
+    // match (merged0, merged1) {
+    //     (Some(merged0), Some(merged1)) => MyStructDeltaOwned {
+    //         some_field_name: merged0,
+    //         another_field_name: merged1,
+    //     },
+    //     _ => return Err(conflicts),
+    // }
+    // ```
+    pub(super) fn generate_no_batching_merge_patches_tokens(
+        &self,
+        delta_owned_name: &Ident,
+    ) -> TokenStream2 {
+        let merged_idents: Vec<Ident> = (0..self.fields.len())
+            .map(|idx| format_ident!("merged{}", idx))
+            .collect();
+
+        let merge_statements =
+            self.fields
+                .iter()
+                .zip(merged_idents.iter())
+                .map(|(field, merged)| {
+                    let field_name = &field.name;
+                    let ty = &field.ty;
+                    let base_field = field.reference(&quote! {base});
+                    let path_segment = field.path_segment();
+
+                    quote! {
+                        let #merged = <#ty as dipa::MergeablePatches<_>>::merge_patches(
+                            #base_field,
+                            a.#field_name,
+                            b.#field_name,
+                        )
+                        .map_err(|err| conflicts.extend(err.with_parent(#path_segment)))
+                        .ok();
+                    }
+                });
+
+        let field_names = self.fields.iter().map(|field| &field.name);
+
+        quote! {
+            let mut conflicts = dipa::MergeConflicts::default();
+
+            #(#merge_statements)*
+
+            match (#(#merged_idents),*) {
+                (#(Some(#merged_idents)),*) => #delta_owned_name {
+                    #(#field_names: #merged_idents),*
+                },
+                _ => return Err(conflicts),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+    use syn::Type;
+
+    /// Verify that we properly merge the patches for a type that uses the no_batching field
+    /// batching strategy.
+    #[test]
+    fn generates_no_batching_merge() {
+        let parsed_struct = ParsedStruct {
+            name: format_ident!("MyStruct"),
+            fields: ParsedFields {
+                fields: vec![
+                    StructOrTupleField {
+                        name: quote! {fielda},
+                        ty: Type::Verbatim(quote! {u8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                    StructOrTupleField {
+                        name: quote! {fieldb},
+                        ty: Type::Verbatim(quote! {i8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                ],
+                span: Span::call_site(),
+            },
+        };
+
+        let tokens = parsed_struct
+            .generate_no_batching_merge_patches_tokens(&format_ident!("MyStructDeltaOwned"));
+
+        let expected = quote! {
+            let mut conflicts = dipa::MergeConflicts::default();
+
+            let merged0 = <u8 as dipa::MergeablePatches<_>>::merge_patches(
+                &base.fielda,
+                a.fielda,
+                b.fielda,
+            )
+            .map_err(|err| conflicts.extend(err.with_parent(dipa::PathSegment::Field("fielda"))))
+            .ok();
+            let merged1 = <i8 as dipa::MergeablePatches<_>>::merge_patches(
+                &base.fieldb,
+                a.fieldb,
+                b.fieldb,
+            )
+            .map_err(|err| conflicts.extend(err.with_parent(dipa::PathSegment::Field("fieldb"))))
+            .ok();
+
+            match (merged0, merged1) {
+                (Some(merged0), Some(merged1)) => MyStructDeltaOwned {
+                    fielda: merged0,
+                    fieldb: merged1
+                },
+                _ => return Err(conflicts),
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::parsed_struct::generate_dipa_impl::multi_field_struct::field_mutable_references;
+use crate::parsed_struct::ParsedStruct;
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl ParsedStruct {
+    // ```
+    // let field0_mut_ref = &mut self.some_field_name;
+    // let field1_mut_ref = &mut self.another_field_name;
+    //
+    // MyStructDeltaOwned {
+    //     some_field_name: field0_mut_ref
+    //         .apply_patch_returning_inverse(patch.some_field_name)
+    //         .map_err(|err| err.with_parent(dipa::PathSegment::Field("some_field_name")))?,
+    //     another_field_name: field1_mut_ref
+    //         .apply_patch_returning_inverse(patch.another_field_name)
+    //         .map_err(|err| err.with_parent(dipa::PathSegment::Field("another_field_name")))?,
+    // }
+    // ```
+    pub(super) fn generate_no_batching_reverse_patch_tokens(
+        &self,
+        delta_owned_name: &Ident,
+    ) -> TokenStream2 {
+        let field_mut_refs = field_mutable_references(&self.fields);
+        let mut inverse_fields = vec![];
+
+        for (idx, field) in self.fields.iter().enumerate() {
+            let field_name = &field.name;
+            let field_mut_ref_ident = Ident::new(&format!("field{}_mut_ref", idx), field.span);
+            let path_segment = field.path_segment();
+
+            inverse_fields.push(quote! {
+                #field_name: #field_mut_ref_ident
+                    .apply_patch_returning_inverse(patch.#field_name)
+                    .map_err(|err| err.with_parent(#path_segment))?
+            });
+        }
+
+        quote! {
+            #(#field_mut_refs)*
+
+            #delta_owned_name {
+                #(#inverse_fields),*
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+    use syn::Type;
+
+    /// Verify that we properly generate the inverse patch for a type that uses the no_batching
+    /// field batching strategy.
+    #[test]
+    fn generates_no_batching_inverse() {
+        let parsed_struct = ParsedStruct {
+            name: format_ident!("MyStruct"),
+            fields: ParsedFields {
+                fields: vec![
+                    StructOrTupleField {
+                        name: quote! {fielda},
+                        ty: Type::Verbatim(quote! {u8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                    StructOrTupleField {
+                        name: quote! {fieldb},
+                        ty: Type::Verbatim(quote! {i8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                ],
+                span: Span::call_site(),
+            },
+        };
+
+        let tokens = parsed_struct
+            .generate_no_batching_reverse_patch_tokens(&format_ident!("MyStructDeltaOwned"));
+
+        let expected = quote! {
+            let field0_mut_ref = &mut self.fielda;
+            let field1_mut_ref = &mut self.fieldb;
+
+            MyStructDeltaOwned {
+                fielda: field0_mut_ref
+                    .apply_patch_returning_inverse(patch.fielda)
+                    .map_err(|err| err.with_parent(dipa::PathSegment::Field("fielda")))?,
+                fieldb: field1_mut_ref
+                    .apply_patch_returning_inverse(patch.fieldb)
+                    .map_err(|err| err.with_parent(dipa::PathSegment::Field("fieldb")))?
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::parsed_struct::ParsedStruct;
+use syn::__private::TokenStream2;
+use syn::Ident;
+
+impl ParsedStruct {
+    // ```
+    // MyStructDeltaOwned {
+    //     some_field_name: <u8 as dipa::SquashablePatches<_>>::squash_patches(
+    //         first.some_field_name,
+    //         second.some_field_name,
+    //     )
+    //     .map_err(|err| err.with_parent(dipa::PathSegment::Field("some_field_name")))?,
+    //     another_field_name: <i8 as dipa::SquashablePatches<_>>::squash_patches(
+    //         first.another_field_name,
+    //         second.another_field_name,
+    //     )
+    //     .map_err(|err| err.with_parent(dipa::PathSegment::Field("another_field_name")))?,
+    // }
+    // ```
+    pub(super) fn generate_no_batching_squash_patches_tokens(
+        &self,
+        delta_owned_name: &Ident,
+    ) -> TokenStream2 {
+        let squashed_fields = self.fields.iter().map(|field| {
+            let field_name = &field.name;
+            let ty = &field.ty;
+            let path_segment = field.path_segment();
+
+            quote! {
+                #field_name: <#ty as dipa::SquashablePatches<_>>::squash_patches(
+                    first.#field_name,
+                    second.#field_name,
+                )
+                .map_err(|err| err.with_parent(#path_segment))?
+            }
+        });
+
+        quote! {
+            #delta_owned_name {
+                #(#squashed_fields),*
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+    use syn::Type;
+
+    /// Verify that we properly squash the patches for a type that uses the no_batching field
+    /// batching strategy.
+    #[test]
+    fn generates_no_batching_squash() {
+        let parsed_struct = ParsedStruct {
+            name: format_ident!("MyStruct"),
+            fields: ParsedFields {
+                fields: vec![
+                    StructOrTupleField {
+                        name: quote! {fielda},
+                        ty: Type::Verbatim(quote! {u8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                    StructOrTupleField {
+                        name: quote! {fieldb},
+                        ty: Type::Verbatim(quote! {i8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                ],
+                span: Span::call_site(),
+            },
+        };
+
+        let tokens = parsed_struct
+            .generate_no_batching_squash_patches_tokens(&format_ident!("MyStructDeltaOwned"));
+
+        let expected = quote! {
+            MyStructDeltaOwned {
+                fielda: <u8 as dipa::SquashablePatches<_>>::squash_patches(
+                    first.fielda,
+                    second.fielda,
+                )
+                .map_err(|err| err.with_parent(dipa::PathSegment::Field("fielda")))?,
+                fieldb: <i8 as dipa::SquashablePatches<_>>::squash_patches(
+                    first.fieldb,
+                    second.fieldb,
+                )
+                .map_err(|err| err.with_parent(dipa::PathSegment::Field("fieldb")))?
+            }
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::multi_field_utils::visit_field_delta_tokens;
+use crate::parsed_struct::ParsedStruct;
+use syn::__private::TokenStream2;
+
+impl ParsedStruct {
+    // ```
+    // visitor.enter(dipa::PathSegment::Field("some_field_name"));
+    // <u8 as dipa::VisitDelta<'s, 'e>>::visit_delta(
+    //     &self.some_field_name,
+    //     &delta.some_field_name,
+    //     visitor
+    // );
+    // visitor.exit();
+    // // ... Same for every other field ...
+    // ```
+    pub(super) fn generate_no_batching_visit_delta_tokens(&self) -> TokenStream2 {
+        let visit_statements = self.fields.iter().map(|field| {
+            let field_name = &field.name;
+
+            visit_field_delta_tokens(
+                field,
+                &field.reference(&quote! {self}),
+                &quote! {&delta.#field_name},
+            )
+        });
+
+        quote! {
+            #(#visit_statements)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dipa_attribute::DipaFieldAttrs;
+    use crate::multi_field_utils::{ParsedFields, StructOrTupleField};
+    use crate::test_utils::assert_tokens_eq;
+    use syn::__private::Span;
+    use syn::Type;
+
+    /// Verify that we visit every field's delta for a type that uses the no_batching field
+    /// batching strategy.
+    #[test]
+    fn generates_no_batching_visit_delta() {
+        let parsed_struct = ParsedStruct {
+            name: format_ident!("MyStruct"),
+            fields: ParsedFields {
+                fields: vec![
+                    StructOrTupleField {
+                        name: quote! {fielda},
+                        ty: Type::Verbatim(quote! {u8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                    StructOrTupleField {
+                        name: quote! {fieldb},
+                        ty: Type::Verbatim(quote! {i8}),
+                        span: Span::call_site(),
+                        attrs: DipaFieldAttrs::default(),
+                        packed_bools: vec![],
+                    },
+                ],
+                span: Span::call_site(),
+            },
+        };
+
+        let tokens = parsed_struct.generate_no_batching_visit_delta_tokens();
+
+        let expected = quote! {
+            visitor.enter(dipa::PathSegment::Field("fielda"));
+            <u8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.fielda, &delta.fielda, visitor);
+            visitor.exit();
+            visitor.enter(dipa::PathSegment::Field("fieldb"));
+            <i8 as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.fieldb, &delta.fieldb, visitor);
+            visitor.exit();
+        };
+
+        assert_tokens_eq(&tokens, &expected);
+    }
+}
@@ -1,7 +1,8 @@
 //! Validate the usage of different dipa attributes on a type's container, variants and
 //! fields.
 
-use crate::dipa_attribute::{DipaAttrs, FieldBatchingStrategy};
+use crate::dipa_attribute::{field_attribute_error, DipaAttrs, FieldBatchingStrategy};
+use crate::multi_field_utils::StructOrTupleField;
 use crate::parsed_struct::ParsedStruct;
 use syn::__private::TokenStream2;
 
@@ -30,7 +31,7 @@ impl ParsedStruct {
             }
         }
 
-        if errs.len() == 0 {
+        if errs.is_empty() {
             Ok(())
         } else {
             let errs = quote! {
@@ -40,4 +41,109 @@ impl ParsedStruct {
             Err(errs)
         }
     }
+
+    // Validate `#[dipa(...)]` attributes on a struct's fields.
+    //
+    // ```
+    // #[derive(DiffPatch)]
+    // struct Foo {
+    //     field_a: u8,
+    //     #[dipa(...)] // <-- These are being validated.
+    //     field_b: u8,
+    // }
+    // ```
+    pub fn validate_struct_field_attributes(
+        &self,
+        attributes: &DipaAttrs,
+    ) -> Result<(), TokenStream2> {
+        let mut errs = vec![];
+
+        let since_fields: Vec<&StructOrTupleField> = self
+            .fields
+            .iter()
+            .filter(|field| field.attrs.since.is_some())
+            .collect();
+        if !since_fields.is_empty() {
+            errs.extend(self.field_left_out_of_delta_errors("since", &since_fields, attributes));
+
+            // A first version without any fields would be a unit struct, which has a different
+            // delta type.
+            if self.fields.len() >= 2 && since_fields.len() == self.fields.len() {
+                errs.push(field_attribute_error(
+                    since_fields[0].span,
+                    r#"At least one field must be a part of the first schema version, since a struct
+without fields uses a different delta type."#,
+                ));
+            }
+
+            for pair in self.fields.windows(2) {
+                if pair[1].attrs.schema_version() < pair[0].attrs.schema_version() {
+                    errs.push(field_attribute_error(
+                        pair[1].span,
+                        r#"Fields must be declared in the order of the schema version that they were
+added in. Try moving this field above the fields that have a larger since attribute."#,
+                    ));
+                }
+            }
+        }
+
+        let epsilon_fields: Vec<&StructOrTupleField> = self
+            .fields
+            .iter()
+            .filter(|field| field.attrs.epsilon.is_some())
+            .collect();
+        if !epsilon_fields.is_empty() {
+            errs.extend(self.field_left_out_of_delta_errors(
+                "epsilon",
+                &epsilon_fields,
+                attributes,
+            ));
+        }
+
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(quote! {
+                #(#errs)*
+            })
+        }
+    }
+
+    // The since and epsilon attributes work by leaving fields out of the delta even when they
+    // changed, which only the "one_batch" delta type of a struct with multiple fields can do.
+    fn field_left_out_of_delta_errors(
+        &self,
+        attribute: &str,
+        fields: &[&StructOrTupleField],
+        attributes: &DipaAttrs,
+    ) -> Vec<TokenStream2> {
+        let mut errs = vec![];
+
+        if self.fields.len() < 2 {
+            errs.push(field_attribute_error(
+                fields[0].span,
+                &format!(
+                    r#"The {} attribute requires a struct with at least two fields, since a struct
+with one field uses that field's delta type."#,
+                    attribute
+                ),
+            ));
+        }
+
+        if !matches!(
+            attributes.field_batching_strategy.unwrap_or_default(),
+            FieldBatchingStrategy::OneBatch
+        ) {
+            errs.push(field_attribute_error(
+                fields[0].span,
+                &format!(
+                    r#"The {} attribute is only supported by the "one_batch" field batching
+strategy."#,
+                    attribute
+                ),
+            ));
+        }
+
+        errs
+    }
 }
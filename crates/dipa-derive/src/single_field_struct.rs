@@ -1,14 +1,23 @@
+use crate::dipa_attribute::DipaAttrs;
 use crate::impl_dipa;
+use crate::multi_field_utils::{
+    field_json_pointer, field_path_segment, fingerprint_field_write, make_json_patch_impl,
+    make_schema_fingerprint_impl, make_single_field_borrowed_delta_impl, make_visit_delta_impl,
+};
 use syn::__private::TokenStream2;
 use syn::{Ident, Type};
 
 pub(super) fn generate_single_field_struct_impl(
+    dipa_attrs: &DipaAttrs,
     struct_name: &Ident,
     field_name: TokenStream2,
     field_type: &Type,
 ) -> TokenStream2 {
-    impl_dipa(
-        &struct_name,
+    let path_segment = field_path_segment(&field_name);
+
+    let dipa_impl = impl_dipa(
+        dipa_attrs,
+        struct_name,
         quote! {
         <#field_type as dipa::Diffable<'s, 'e, #field_type>>::Delta
         },
@@ -16,8 +25,72 @@ pub(super) fn generate_single_field_struct_impl(
         <#field_type as dipa::Diffable<'s, 'e, #field_type>>::DeltaOwned
         },
         quote! {
-        self.#field_name.create_delta_towards(&end_state.#field_name)
+        self.#field_name.create_delta_towards_version(&end_state.#field_name, version)
+        },
+        quote! {
+        self.#field_name
+            .apply_patch(patch)
+            .map_err(|err| err.with_parent(#path_segment))?;
+        },
+        quote! {
+        self.#field_name
+            .apply_patch_returning_inverse(patch)
+            .map_err(|err| err.with_parent(#path_segment))?
+        },
+        quote! {
+        <#field_type as dipa::SquashablePatches<_>>::squash_patches(first, second)
+            .map_err(|err| err.with_parent(#path_segment))?
+        },
+        quote! {
+        <#field_type as dipa::MergeablePatches<_>>::merge_patches(&base.#field_name, a, b)
+            .map_err(|err| err.with_parent(#path_segment))?
+        },
+    );
+
+    let borrowed_delta_impl = make_single_field_borrowed_delta_impl(
+        struct_name,
+        field_type,
+        quote! {
+        dipa::BorrowedDelta::apply_borrowed_patch(&mut self.#field_name, patch)
+            .map_err(|err| err.with_parent(#path_segment))?;
+        },
+    );
+
+    let visit_delta_impl = make_visit_delta_impl(
+        struct_name,
+        std::slice::from_ref(field_type),
+        quote! {
+        visitor.enter(#path_segment);
+        <#field_type as dipa::VisitDelta<'s, 'e>>::visit_delta(&self.#field_name, delta, visitor);
+        visitor.exit();
+        },
+    );
+
+    let field_pointer = field_json_pointer(&field_name, 1);
+    let json_patch_impl = make_json_patch_impl(
+        struct_name,
+        std::slice::from_ref(field_type),
+        quote! {
+        <#field_type as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+            &self.#field_name,
+            delta,
+            #field_pointer,
+            patch,
+        )?;
         },
-        quote! { self.#field_name.apply_patch(patch) },
-    )
+    );
+
+    let schema_fingerprint_impl = make_schema_fingerprint_impl(
+        struct_name,
+        std::slice::from_ref(field_type),
+        fingerprint_field_write(&field_name, field_type),
+    );
+
+    quote! {
+        #dipa_impl
+        #borrowed_delta_impl
+        #visit_delta_impl
+        #json_patch_impl
+        #schema_fingerprint_impl
+    }
 }
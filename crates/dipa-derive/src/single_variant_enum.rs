@@ -1,7 +1,13 @@
+use crate::dipa_attribute::DipaAttrs;
 use crate::impl_dipa;
+use crate::multi_field_utils::{
+    field_json_pointer, field_path_segment, fingerprint_field_write, make_json_patch_impl,
+    make_schema_fingerprint_impl, make_single_field_borrowed_delta_impl, make_visit_delta_impl,
+    PatchKind,
+};
 
-use syn::Type;
 use syn::__private::TokenStream2;
+use syn::Type;
 
 /// #[derive(Dipa)] for an enum with one struct variant that has one field.
 ///
@@ -12,12 +18,37 @@ use syn::__private::TokenStream2;
 /// }
 /// ```
 pub(super) fn generate_single_variant_enum_single_struct_field_impl(
+    dipa_attrs: &DipaAttrs,
     enum_name: syn::Ident,
     variant_name: &syn::Ident,
     field_name: TokenStream2,
     field_type: &Type,
 ) -> TokenStream2 {
-    impl_dipa(
+    let path_segment = field_path_segment(&field_name);
+    let variant_name_str = variant_name.to_string();
+
+    // match self {
+    //     Self::OnlyVariant { only_field } => {
+    //         only_field.apply_patch(patch).map_err(...)?;
+    //     }
+    // }
+    let apply_patch_tokens = |patch_kind: PatchKind| {
+        let apply_patch = patch_kind.apply_field_patch(&field_name, &quote! {patch});
+
+        quote! {
+          match self {
+             Self::#variant_name { #field_name } => {
+                 #apply_patch.map_err(|err| {
+                     err.with_parent(#path_segment)
+                         .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                 })?;
+             }
+          }
+        }
+    };
+
+    let dipa_impl = impl_dipa(
+        dipa_attrs,
         &enum_name,
         quote! {
         <#field_type as dipa::Diffable<'s, 'e, #field_type>>::Delta
@@ -27,29 +58,101 @@ pub(super) fn generate_single_variant_enum_single_struct_field_impl(
         },
         // match (self, end_state) {
         //     (Self::OnlyVariant { only_field: start }, Self::OnlyVariant { only_field: end }) => {
-        //         start.create_delta_towards(end)
+        //         start.create_delta_towards_version(end, version)
         //     }
         // }
         quote! {
           match (self, end_state) {
               (Self::#variant_name { #field_name: start }, Self::#variant_name { #field_name: end }) => {
-                  start.create_delta_towards(end)
+                  start.create_delta_towards_version(end, version)
               }
           }
         },
-        // match self {
-        //     Self::OnlyVariant { only_field } => {
-        //         only_field.patch(patch)
-        //     }
-        // }
+        apply_patch_tokens(PatchKind::Owned),
         quote! {
           match self {
              Self::#variant_name { #field_name } => {
-                 #field_name.apply_patch(patch)
+                 #field_name.apply_patch_returning_inverse(patch).map_err(|err| {
+                     err.with_parent(#path_segment)
+                         .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                 })?
+             }
+          }
+        },
+        quote! {
+          <#field_type as dipa::SquashablePatches<_>>::squash_patches(first, second).map_err(|err| {
+              err.with_parent(#path_segment)
+                  .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+          })?
+        },
+        quote! {
+          match base {
+             Self::#variant_name { #field_name } => {
+                 <#field_type as dipa::MergeablePatches<_>>::merge_patches(#field_name, a, b)
+                     .map_err(|err| {
+                         err.with_parent(#path_segment)
+                             .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                     })?
              }
           }
         },
-    )
+    );
+
+    let borrowed_delta_impl = make_single_field_borrowed_delta_impl(
+        &enum_name,
+        field_type,
+        apply_patch_tokens(PatchKind::Borrowed),
+    );
+
+    let visit_delta_impl = make_visit_delta_impl(
+        &enum_name,
+        std::slice::from_ref(field_type),
+        quote! {
+          match self {
+             Self::#variant_name { #field_name } => {
+                 visitor.enter(dipa::PathSegment::Variant(#variant_name_str));
+                 visitor.enter(#path_segment);
+                 <#field_type as dipa::VisitDelta<'s, 'e>>::visit_delta(#field_name, delta, visitor);
+                 visitor.exit();
+                 visitor.exit();
+             }
+          }
+        },
+    );
+
+    let field_pointer = field_json_pointer(&field_name, 1);
+    let json_patch_impl = make_json_patch_impl(
+        &enum_name,
+        std::slice::from_ref(field_type),
+        quote! {
+          match self {
+             Self::#variant_name { #field_name } => {
+                 let path = &path.child(#variant_name_str);
+                 <#field_type as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                     #field_name,
+                     delta,
+                     #field_pointer,
+                     patch,
+                 )?;
+             }
+          }
+        },
+    );
+
+    let field_write = fingerprint_field_write(&field_name, field_type);
+    let schema_fingerprint_impl = make_schema_fingerprint_impl(
+        &enum_name,
+        std::slice::from_ref(field_type),
+        quote! { .write_str(#variant_name_str) #field_write },
+    );
+
+    quote! {
+        #dipa_impl
+        #borrowed_delta_impl
+        #visit_delta_impl
+        #json_patch_impl
+        #schema_fingerprint_impl
+    }
 }
 
 /// #[derive(Dipa)] for an enum with one tuple variant that has one field.
@@ -61,11 +164,35 @@ pub(super) fn generate_single_variant_enum_single_struct_field_impl(
 /// }
 /// ```
 pub(super) fn generate_single_variant_enum_single_tuple_field_impl(
+    dipa_attrs: &DipaAttrs,
     enum_name: syn::Ident,
     variant_name: &syn::Ident,
     field_type: &Type,
 ) -> TokenStream2 {
-    impl_dipa(
+    let variant_name_str = variant_name.to_string();
+
+    // match self {
+    //     Self::OnlyVariant(only_field) => {
+    //         only_field.apply_patch(patch).map_err(...)?;
+    //     }
+    // }
+    let apply_patch_tokens = |patch_kind: PatchKind| {
+        let apply_patch = patch_kind.apply_field_patch(&quote! {current}, &quote! {patch});
+
+        quote! {
+          match self {
+             Self::#variant_name(current) => {
+                 #apply_patch.map_err(|err| {
+                     err.with_parent(dipa::PathSegment::TupleField(0))
+                         .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                 })?;
+             }
+          }
+        }
+    };
+
+    let dipa_impl = impl_dipa(
+        dipa_attrs,
         &enum_name,
         quote! {
         <#field_type as dipa::Diffable<'s, 'e, #field_type>>::Delta
@@ -75,27 +202,98 @@ pub(super) fn generate_single_variant_enum_single_tuple_field_impl(
         },
         // match (self, end_state) {
         //     (Self::OnlyVariant(only_field), Self::OnlyVariant(only_field)) => {
-        //         start.create_delta_towards(end)
+        //         start.create_delta_towards_version(end, version)
         //     }
         // }
         quote! {
           match (self, end_state) {
               (Self::#variant_name(start), Self::#variant_name(end)) => {
-                  start.create_delta_towards(end)
+                  start.create_delta_towards_version(end, version)
               }
           }
         },
-        // match self {
-        //     Self::OnlyVariant(only_field) => {
-        //         only_field.patch(patch)
-        //     }
-        // }
+        apply_patch_tokens(PatchKind::Owned),
         quote! {
           match self {
              Self::#variant_name(current) => {
-                 current.apply_patch(patch)
+                 current.apply_patch_returning_inverse(patch).map_err(|err| {
+                     err.with_parent(dipa::PathSegment::TupleField(0))
+                         .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                 })?
+             }
+          }
+        },
+        quote! {
+          <#field_type as dipa::SquashablePatches<_>>::squash_patches(first, second).map_err(|err| {
+              err.with_parent(dipa::PathSegment::TupleField(0))
+                  .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+          })?
+        },
+        quote! {
+          match base {
+             Self::#variant_name(current) => {
+                 <#field_type as dipa::MergeablePatches<_>>::merge_patches(current, a, b)
+                     .map_err(|err| {
+                         err.with_parent(dipa::PathSegment::TupleField(0))
+                             .with_parent(dipa::PathSegment::Variant(#variant_name_str))
+                     })?
              }
           }
         },
-    )
+    );
+
+    let borrowed_delta_impl = make_single_field_borrowed_delta_impl(
+        &enum_name,
+        field_type,
+        apply_patch_tokens(PatchKind::Borrowed),
+    );
+
+    let visit_delta_impl = make_visit_delta_impl(
+        &enum_name,
+        std::slice::from_ref(field_type),
+        quote! {
+          match self {
+             Self::#variant_name(current) => {
+                 visitor.enter(dipa::PathSegment::Variant(#variant_name_str));
+                 visitor.enter(dipa::PathSegment::TupleField(0));
+                 <#field_type as dipa::VisitDelta<'s, 'e>>::visit_delta(current, delta, visitor);
+                 visitor.exit();
+                 visitor.exit();
+             }
+          }
+        },
+    );
+
+    // serde writes a variant with a single unnamed field as `{"OnlyVariant": field}`.
+    let json_patch_impl = make_json_patch_impl(
+        &enum_name,
+        std::slice::from_ref(field_type),
+        quote! {
+          match self {
+             Self::#variant_name(current) => {
+                 <#field_type as dipa::ToJsonPatch<'s, 'e>>::push_json_patch(
+                     current,
+                     delta,
+                     &path.child(#variant_name_str),
+                     patch,
+                 )?;
+             }
+          }
+        },
+    );
+
+    let field_write = fingerprint_field_write(&quote! {0}, field_type);
+    let schema_fingerprint_impl = make_schema_fingerprint_impl(
+        &enum_name,
+        std::slice::from_ref(field_type),
+        quote! { .write_str(#variant_name_str) #field_write },
+    );
+
+    quote! {
+        #dipa_impl
+        #borrowed_delta_impl
+        #visit_delta_impl
+        #json_patch_impl
+        #schema_fingerprint_impl
+    }
 }
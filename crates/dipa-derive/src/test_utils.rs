@@ -2,8 +2,8 @@ use syn::__private::TokenStream2;
 
 pub fn assert_tokens_eq(left: &TokenStream2, right: &TokenStream2) {
     assert_eq!(
-        token_stream_to_vec(&left),
-        token_stream_to_vec(&right),
+        token_stream_to_vec(left),
+        token_stream_to_vec(right),
         r#"
 Left Tokens:
 {}
@@ -11,14 +11,14 @@ Left Tokens:
 Right Tokens:
 {}
 "#,
-        left.to_string(),
-        right.to_string()
+        left,
+        right
     )
 }
 
 pub fn _assert_starts_with_tokens(all_tokens: &TokenStream2, expected_start: &TokenStream2) {
-    let all_token_strings = token_stream_to_vec(&all_tokens);
-    let expected_start_strings = token_stream_to_vec(&expected_start);
+    let all_token_strings = token_stream_to_vec(all_tokens);
+    let expected_start_strings = token_stream_to_vec(expected_start);
 
     assert_eq!(
         &all_token_strings[0..expected_start_strings.len()],
@@ -30,8 +30,8 @@ All Tokens:
 Expected Start:
 {}
 "#,
-        all_tokens.to_string(),
-        expected_start.to_string()
+        all_tokens,
+        expected_start
     );
 }
 
@@ -1,9 +1,12 @@
+use crate::dipa_attribute::DipaAttrs;
 use crate::impl_dipa;
-use syn::Ident;
+use crate::multi_field_utils::make_schema_fingerprint_impl;
 use syn::__private::TokenStream2;
+use syn::Ident;
 
-pub(super) fn create_zst_impl(enum_or_struct_name: &Ident) -> TokenStream2 {
-    impl_dipa(
+pub(super) fn create_zst_impl(dipa_attrs: &DipaAttrs, enum_or_struct_name: &Ident) -> TokenStream2 {
+    let dipa_impl = impl_dipa(
+        dipa_attrs,
         enum_or_struct_name,
         quote! {()},
         quote! {()},
@@ -14,5 +17,48 @@ pub(super) fn create_zst_impl(enum_or_struct_name: &Ident) -> TokenStream2 {
             }
         },
         quote! {},
-    )
+        quote! {patch},
+        quote! {second},
+        quote! {b},
+    );
+
+    // The only value of a zero sized type serializes to the same JSON as itself, so there are
+    // never any operations.
+    let json_patch_impl = if cfg!(feature = "json-patch") {
+        quote! {
+            impl<'s, 'e> dipa::ToJsonPatch<'s, 'e> for #enum_or_struct_name {
+                fn push_json_patch(
+                    &self,
+                    _delta: &(),
+                    _path: &dipa::JsonPointer,
+                    _patch: &mut dipa::JsonPatch,
+                ) -> Result<(), dipa::JsonPatchError> {
+                    Ok(())
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let schema_fingerprint_impl = make_schema_fingerprint_impl(enum_or_struct_name, &[], quote! {});
+
+    quote! {
+        #dipa_impl
+
+        impl<'de> dipa::BorrowedDelta<'de> for #enum_or_struct_name {
+            type DeltaBorrowed = ();
+
+            fn apply_borrowed_patch(&mut self, _patch: ()) -> Result<(), dipa::PatchError> {
+                Ok(())
+            }
+        }
+
+        impl<'s, 'e> dipa::VisitDelta<'s, 'e> for #enum_or_struct_name {
+            fn visit_delta(&self, _delta: &(), _visitor: &mut dyn dipa::DeltaVisitor) {}
+        }
+
+        #json_patch_impl
+        #schema_fingerprint_impl
+    }
 }
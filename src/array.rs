@@ -0,0 +1,602 @@
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, PatchErrorReason, Patchable, PathSegment, ReversiblePatchable, SquashablePatches,
+    ToOwnedDelta, VisitDelta,
+};
+#[cfg(feature = "json-patch")]
+use crate::{JsonPatch, JsonPatchError, JsonPointer, ToJsonPatch};
+
+mod array_delta_serde;
+
+/// The delta for a fixed size array.
+///
+/// Arrays never change length, so instead of searching for a common subsequence like `Vec<T>`
+/// does, every element is diffed with the element at the same index.
+///
+/// Serialized as a bitmask of the changed indices followed by the delta of each changed element,
+/// so an unchanged array of any length is encoded as a single byte.
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ArrayDelta<D, const N: usize> {
+    /// The changed indices, in ascending order, along with their deltas.
+    changes: Vec<(usize, D)>,
+}
+
+impl<D, const N: usize> ArrayDelta<D, N> {
+    /// The changed indices, in ascending order, along with their deltas.
+    pub fn changes(&self) -> &[(usize, D)] {
+        &self.changes
+    }
+}
+
+impl<'s, 'e, T: Diffable<'s, 'e, T>, const N: usize> Diffable<'s, 'e, [T; N]> for [T; N] {
+    type Delta = ArrayDelta<<T as Diffable<'s, 'e, T>>::Delta, N>;
+
+    type DeltaOwned = ArrayDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned, N>;
+
+    fn create_delta_towards(&'s self, end_state: &'e [T; N]) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e [T; N],
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        let changes: Vec<_> = self
+            .iter()
+            .zip(end_state.iter())
+            .enumerate()
+            .filter_map(|(idx, (start, end))| {
+                let diff = start.create_delta_towards_version(end, version);
+
+                if diff.did_change {
+                    Some((idx, diff.delta))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let did_change = !changes.is_empty();
+
+        CreatedDelta {
+            delta: ArrayDelta { changes },
+            did_change,
+        }
+    }
+}
+
+impl<T, D, const N: usize> Patchable<ArrayDelta<D, N>> for [T; N]
+where
+    T: Patchable<D>,
+{
+    fn apply_patch(&mut self, patch: ArrayDelta<D, N>) -> Result<(), PatchError> {
+        for (idx, delta) in patch.changes {
+            element_mut(self, idx)?
+                .apply_patch(delta)
+                .map_err(|err| err.with_parent(PathSegment::Index(idx)))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, D, const N: usize> ReversiblePatchable<ArrayDelta<D, N>> for [T; N]
+where
+    T: ReversiblePatchable<D>,
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: ArrayDelta<D, N>,
+    ) -> Result<ArrayDelta<D, N>, PatchError> {
+        let mut inverse = Vec::with_capacity(patch.changes.len());
+
+        for (idx, delta) in patch.changes {
+            let inverse_delta = element_mut(self, idx)?
+                .apply_patch_returning_inverse(delta)
+                .map_err(|err| err.with_parent(PathSegment::Index(idx)))?;
+
+            inverse.push((idx, inverse_delta));
+        }
+
+        Ok(ArrayDelta { changes: inverse })
+    }
+}
+
+impl<T, D, const N: usize> SquashablePatches<ArrayDelta<D, N>> for [T; N]
+where
+    T: SquashablePatches<D>,
+{
+    fn squash_patches(
+        first: ArrayDelta<D, N>,
+        second: ArrayDelta<D, N>,
+    ) -> Result<ArrayDelta<D, N>, PatchError> {
+        let mut squashed = Vec::with_capacity(first.changes.len() + second.changes.len());
+
+        let mut first = first.changes.into_iter().peekable();
+        let mut second = second.changes.into_iter().peekable();
+
+        loop {
+            let first_idx = first.peek().map(|(idx, _)| *idx);
+            let second_idx = second.peek().map(|(idx, _)| *idx);
+
+            let change = match (first_idx, second_idx) {
+                (None, None) => break,
+                (Some(_), None) => first.next().unwrap(),
+                (None, Some(_)) => second.next().unwrap(),
+                (Some(first_idx), Some(second_idx)) if first_idx < second_idx => {
+                    first.next().unwrap()
+                }
+                (Some(first_idx), Some(second_idx)) if second_idx < first_idx => {
+                    second.next().unwrap()
+                }
+                (Some(idx), Some(_)) => {
+                    let (_, first_delta) = first.next().unwrap();
+                    let (_, second_delta) = second.next().unwrap();
+
+                    let delta = T::squash_patches(first_delta, second_delta)
+                        .map_err(|err| err.with_parent(PathSegment::Index(idx)))?;
+                    (idx, delta)
+                }
+            };
+
+            squashed.push(change);
+        }
+
+        Ok(ArrayDelta { changes: squashed })
+    }
+}
+
+impl<T, D, const N: usize> MergeablePatches<ArrayDelta<D, N>> for [T; N]
+where
+    T: MergeablePatches<D>,
+{
+    fn merge_patches(
+        base: &Self,
+        a: ArrayDelta<D, N>,
+        b: ArrayDelta<D, N>,
+    ) -> Result<ArrayDelta<D, N>, MergeConflicts> {
+        let mut merged = Vec::with_capacity(a.changes.len() + b.changes.len());
+        let mut conflicts = MergeConflicts::default();
+
+        let mut b = b.changes;
+
+        for (idx, a_delta) in a.changes {
+            let b_delta = match b.iter().position(|(b_idx, _)| *b_idx == idx) {
+                Some(position) => b.remove(position).1,
+                None => {
+                    merged.push((idx, a_delta));
+                    continue;
+                }
+            };
+
+            let delta = match base.get(idx) {
+                Some(base) => T::merge_patches(base, a_delta, b_delta),
+                None => {
+                    Err(
+                        PatchError::new(PatchErrorReason::IndexOutOfBounds { index: idx, len: N })
+                            .into(),
+                    )
+                }
+            };
+
+            match delta {
+                Ok(delta) => merged.push((idx, delta)),
+                Err(err) => conflicts.extend(err.with_parent(PathSegment::Index(idx))),
+            };
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        merged.extend(b);
+        merged.sort_by_key(|(idx, _)| *idx);
+
+        Ok(ArrayDelta { changes: merged })
+    }
+}
+
+impl<D, O, const N: usize> ToOwnedDelta<ArrayDelta<O, N>> for ArrayDelta<D, N>
+where
+    D: ToOwnedDelta<O>,
+{
+    fn to_owned_delta(self) -> ArrayDelta<O, N> {
+        ArrayDelta {
+            changes: self
+                .changes
+                .into_iter()
+                .map(|(idx, delta)| (idx, delta.to_owned_delta()))
+                .collect(),
+        }
+    }
+}
+
+impl<'de, T: BorrowedDelta<'de>, const N: usize> BorrowedDelta<'de> for [T; N] {
+    type DeltaBorrowed = ArrayDelta<<T as BorrowedDelta<'de>>::DeltaBorrowed, N>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: ArrayDelta<<T as BorrowedDelta<'de>>::DeltaBorrowed, N>,
+    ) -> Result<(), PatchError> {
+        for (idx, delta) in patch.changes {
+            element_mut(self, idx)?
+                .apply_borrowed_patch(delta)
+                .map_err(|err| err.with_parent(PathSegment::Index(idx)))?;
+        }
+
+        Ok(())
+    }
+}
+
+// The length is a part of the fingerprint even though the encoding does not depend on it, since a
+// delta for a longer array can only be applied to a shorter one by failing.
+impl<'s, 'e, T: crate::SchemaFingerprint<'s, 'e>, const N: usize> crate::SchemaFingerprint<'s, 'e>
+    for [T; N]
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("Array")
+        .write_u64(N as u64)
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+impl<'s, 'e, T: VisitDelta<'s, 'e>, const N: usize> VisitDelta<'s, 'e> for [T; N] {
+    fn visit_delta(
+        &self,
+        delta: &ArrayDelta<<T as Diffable<'s, 'e, T>>::Delta, N>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        for (idx, delta) in delta.changes.iter() {
+            if let Some(element) = self.get(*idx) {
+                visitor.enter(PathSegment::Index(*idx));
+                element.visit_delta(delta, visitor);
+                visitor.exit();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T: ToJsonPatch<'s, 'e>, const N: usize> ToJsonPatch<'s, 'e> for [T; N] {
+    fn push_json_patch(
+        &self,
+        delta: &ArrayDelta<<T as Diffable<'s, 'e, T>>::Delta, N>,
+        path: &JsonPointer,
+        patch: &mut JsonPatch,
+    ) -> Result<(), JsonPatchError> {
+        for (idx, delta) in delta.changes.iter() {
+            if let Some(element) = self.get(*idx) {
+                element.push_json_patch(delta, &path.child(idx), patch)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn element_mut<T, const N: usize>(array: &mut [T; N], idx: usize) -> Result<&mut T, PatchError> {
+    array
+        .get_mut(idx)
+        .ok_or_else(|| PatchError::new(PatchErrorReason::IndexOutOfBounds { index: idx, len: N }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::dipa_impl_tester::DipaImplTester;
+    use crate::to_owned_delta::test_to_owned_delta;
+    use crate::MergeConflictReason;
+
+    /// Verify that we can diff/patch a fixed size array.
+    #[test]
+    fn dipa_array_impl() {
+        DipaImplTester {
+            label: Some("Array no change"),
+            start: &mut [1u32, 2, 3],
+            end: &[1, 2, 3],
+            expected_delta: ArrayDelta { changes: vec![] },
+            // The empty bitmask
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Array one change"),
+            start: &mut [1u32, 2, 3],
+            end: &[1, 5, 3],
+            expected_delta: ArrayDelta {
+                changes: vec![(1, Some(5))],
+            },
+            // 1 for the bitmask, 2 for the changed u32
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Array many changes"),
+            start: &mut [1u32, 2, 3],
+            end: &[4, 2, 6],
+            expected_delta: ArrayDelta {
+                changes: vec![(0, Some(4)), (2, Some(6))],
+            },
+            expected_serialized_patch_size: 5,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Array change after the first 7 elements"),
+            start: &mut [0u32; 10],
+            end: &[0, 0, 0, 0, 0, 0, 0, 0, 0, 9],
+            expected_delta: ArrayDelta {
+                changes: vec![(9, Some(9))],
+            },
+            // 2 for the bitmask, 2 for the changed u32
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Empty array"),
+            start: &mut [0u32; 0],
+            end: &[],
+            expected_delta: ArrayDelta { changes: vec![] },
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that an unchanged array is a single byte no matter how long it is.
+    #[test]
+    fn large_array_unchanged() {
+        DipaImplTester {
+            label: None,
+            start: &mut [[1u8; 4]; 30],
+            end: &[[1u8; 4]; 30],
+            expected_delta: ArrayDelta { changes: vec![] },
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a patch for an index past the end of the array returns an error.
+    #[test]
+    fn out_of_bounds_errors() {
+        let mut array = [1u32, 2];
+
+        let err = array
+            .apply_patch(ArrayDelta {
+                changes: vec![(2, Some(5))],
+            })
+            .unwrap_err();
+
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::IndexOutOfBounds { index: 2, len: 2 }
+        );
+        assert_eq!(array, [1, 2]);
+    }
+
+    /// Verify that errors from an element are wrapped in the element's index.
+    #[test]
+    fn element_errors_have_index_path() {
+        let mut array = [None::<u32>, None];
+
+        let err = array
+            .apply_patch(ArrayDelta {
+                changes: vec![(1, crate::option::OptionDeltaOwned::InnerChange(Some(5)))],
+            })
+            .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::OptionIsNone);
+        assert_eq!(err.path().collect::<Vec<_>>(), vec![&PathSegment::Index(1)]);
+    }
+
+    /// Verify that squashing patches whose changes interleave keeps the indices in ascending
+    /// order, which the bitmask encoding relies on.
+    #[test]
+    fn squash_keeps_indices_in_order() {
+        let start = [0u16; 10];
+        let middle = [0, 0, 0, 0, 0, 0, 0, 0, 0, 9];
+        let end = [0, 0, 2, 0, 0, 0, 0, 0, 0, 8];
+
+        let squashed = <[u16; 10]>::squash_patches(
+            start.create_delta_towards(&middle).delta.to_owned_delta(),
+            middle.create_delta_towards(&end).delta.to_owned_delta(),
+        )
+        .unwrap();
+        assert_eq!(squashed.changes, vec![(2, Some(2)), (9, Some(8))]);
+
+        let bytes = bincode::serialize(&squashed).unwrap();
+        let mut patched = start;
+        patched
+            .apply_patch(bincode::deserialize(&bytes).unwrap())
+            .unwrap();
+        assert_eq!(patched, end);
+    }
+
+    /// Verify that an element's squash error is wrapped in the element's index.
+    #[test]
+    fn squash_element_errors_have_index_path() {
+        use crate::option::OptionDeltaOwned;
+
+        let err = <[Option<u32>; 2]>::squash_patches(
+            ArrayDelta {
+                changes: vec![(1, OptionDeltaOwned::OuterChange(None))],
+            },
+            ArrayDelta {
+                changes: vec![(1, OptionDeltaOwned::InnerChange(Some(5)))],
+            },
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::OptionIsNone);
+        assert_eq!(err.path().collect::<Vec<_>>(), vec![&PathSegment::Index(1)]);
+    }
+
+    /// Verify that merging patches that change different elements keeps the indices in
+    /// ascending order and that both sides changing one element merges that element.
+    #[test]
+    fn merge_keeps_indices_in_order() {
+        let base = [vec![1u8], vec![], vec![2]];
+        let a = [vec![1, 3], vec![], vec![2]];
+        let b = [vec![0, 1], vec![4], vec![2]];
+
+        let merged = <[Vec<u8>; 3]>::merge_patches(
+            &base,
+            base.create_delta_towards(&a).delta.to_owned_delta(),
+            base.create_delta_towards(&b).delta.to_owned_delta(),
+        )
+        .unwrap();
+        assert_eq!(
+            merged
+                .changes
+                .iter()
+                .map(|(idx, _)| *idx)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        let mut patched = base;
+        patched.apply_patch(merged).unwrap();
+        assert_eq!(patched, [vec![0, 1, 3], vec![4], vec![2]]);
+    }
+
+    /// Verify that both sides changing an index past the end of the base array conflicts
+    /// instead of panicking.
+    #[test]
+    fn merge_out_of_bounds_conflicts() {
+        let conflicts = <[u32; 2]>::merge_patches(
+            &[1, 2],
+            ArrayDelta {
+                changes: vec![(5, Some(3))],
+            },
+            ArrayDelta {
+                changes: vec![(5, Some(3))],
+            },
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::InvalidPatch(PatchErrorReason::IndexOutOfBounds {
+                index: 5,
+                len: 2
+            })
+        );
+    }
+
+    /// Verify that changing the same element to different values conflicts.
+    #[test]
+    fn merge_same_element_conflicts() {
+        let conflicts = <[u32; 2]>::merge_patches(
+            &[1, 2],
+            ArrayDelta {
+                changes: vec![(1, Some(3))],
+            },
+            ArrayDelta {
+                changes: vec![(1, Some(4))],
+            },
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        let conflict = &conflicts.conflicts()[0];
+        assert_eq!(conflict.reason(), &MergeConflictReason::ConcurrentChange);
+        assert_eq!(
+            conflict.path().collect::<Vec<_>>(),
+            vec![&PathSegment::Index(1)]
+        );
+    }
+
+    /// Verify that an owned delta keeps the indices of changes past the first bitmask byte.
+    #[test]
+    fn array_to_owned_delta() {
+        let start = [0u16; 9];
+        let end = [0, 1, 0, 0, 0, 0, 0, 0, 3];
+
+        let delta = start.create_delta_towards(&end).delta.to_owned_delta();
+        assert_eq!(delta.changes, vec![(1, Some(1)), (8, Some(3))]);
+
+        test_to_owned_delta(
+            [
+                vec![1u8],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![2],
+            ],
+            [
+                vec![1u8],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![2, 3],
+            ],
+        );
+    }
+
+    /// Verify that we describe the changes to nested arrays under each of their indices.
+    #[test]
+    fn print_array_delta() {
+        test_print([[1u32, 2], [3, 4]], [[1, 2], [5, 4]], &["[1][0]: 3 -> 5"]);
+        test_print([1u32, 2, 3], [1, 2, 3], &[]);
+    }
+
+    /// Verify that JSON Patch operations for nested arrays point at the changed element.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn array_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        let start = [[1u32, 2], [3, 4]];
+        let end = [[1, 2], [3, 6]];
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(
+            serde_json::to_string(&start.to_json_patch(&delta).unwrap()).unwrap(),
+            r#"[{"op":"replace","path":"/1/1","value":6}]"#
+        );
+
+        test_json_patch([vec![1u8], vec![]], [vec![1, 2], vec![3]]);
+    }
+
+    /// Verify that a borrowed patch reads the deltas of changes past the first bitmask byte and
+    /// that a borrowed patch for an index past the end of the array returns an error.
+    #[test]
+    fn array_borrowed_patch() {
+        let strings = |last: &str| {
+            let mut strings: [String; 9] = Default::default();
+            strings[8] = last.to_string();
+            strings
+        };
+        test_borrowed_patch(strings("a"), strings("abc"));
+
+        let mut array = [1u32, 2];
+        let err = array
+            .apply_borrowed_patch(ArrayDelta {
+                changes: vec![(3, Some(5))],
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::IndexOutOfBounds { index: 3, len: 2 }
+        );
+    }
+}
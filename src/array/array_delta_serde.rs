@@ -0,0 +1,184 @@
+//! An `ArrayDelta` is serialized as a bitmask of the indices that changed followed by the delta of
+//! each changed element.
+//!
+//! The bitmask is split into groups of 7 indices. Each group is written as one byte, with the
+//! high bit set when more groups follow, so a delta where nothing changed is a single `0` byte no
+//! matter how long the array is.
+//!
+//! An array of length `N` never needs more than `ceil(N / 7)` bytes of bitmask, so deserializing
+//! fails instead of reading further when a malformed delta keeps setting the high bit, or when it
+//! marks an index that is not in the array.
+
+use crate::array::ArrayDelta;
+use serde::de::{Error as DeError, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+const INDICES_PER_BYTE: usize = 7;
+const MORE_BYTES: u8 = 0b1000_0000;
+
+impl<D: Serialize, const N: usize> Serialize for ArrayDelta<D, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mask = changed_mask(self.changes.iter().map(|(idx, _)| *idx));
+
+        let mut tuple = serializer.serialize_tuple(mask.len() + self.changes.len())?;
+        for byte in mask.iter() {
+            tuple.serialize_element(byte)?;
+        }
+        for (_, delta) in self.changes.iter() {
+            tuple.serialize_element(delta)?;
+        }
+
+        tuple.end()
+    }
+}
+
+impl<'de, D: Deserialize<'de>, const N: usize> Deserialize<'de> for ArrayDelta<D, N> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        // The number of elements depends on the bitmask, so we ask for the most that there can be
+        // and stop once every changed element's delta has been read.
+        deserializer.deserialize_tuple(max_mask_bytes(N) + N, ArrayDeltaVisitor(PhantomData))
+    }
+}
+
+struct ArrayDeltaVisitor<D, const N: usize>(PhantomData<D>);
+
+impl<'de, D: Deserialize<'de>, const N: usize> Visitor<'de> for ArrayDeltaVisitor<D, N> {
+    type Value = ArrayDelta<D, N>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a bitmask of changed indices followed by their deltas")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut changed_indices = vec![];
+
+        let mut group = 0;
+        loop {
+            if group == max_mask_bytes(N) {
+                return Err(A::Error::custom(format_args!(
+                    "the changed index bitmask of an array of length {} is longer than {} bytes",
+                    N,
+                    max_mask_bytes(N)
+                )));
+            }
+
+            let byte: u8 = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(group, &self))?;
+
+            for bit in 0..INDICES_PER_BYTE {
+                if byte & (1 << bit) != 0 {
+                    let idx = group * INDICES_PER_BYTE + bit;
+                    if idx >= N {
+                        return Err(A::Error::custom(format_args!(
+                            "changed index {} is out of bounds for an array of length {}",
+                            idx, N
+                        )));
+                    }
+
+                    changed_indices.push(idx);
+                }
+            }
+
+            group += 1;
+
+            if byte & MORE_BYTES == 0 {
+                break;
+            }
+        }
+
+        let mut changes = Vec::with_capacity(changed_indices.len());
+        for (read, idx) in changed_indices.into_iter().enumerate() {
+            let delta = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(group + read, &self))?;
+
+            changes.push((idx, delta));
+        }
+
+        Ok(ArrayDelta { changes })
+    }
+}
+
+/// The most bitmask bytes that an array of length `len` needs. Even an empty array writes one.
+fn max_mask_bytes(len: usize) -> usize {
+    len.div_ceil(INDICES_PER_BYTE).max(1)
+}
+
+/// Pack the changed indices, which must be in ascending order, into bitmask bytes.
+fn changed_mask(changed_indices: impl Iterator<Item = usize>) -> Vec<u8> {
+    let mut mask = vec![0];
+
+    for idx in changed_indices {
+        let group = idx / INDICES_PER_BYTE;
+
+        while mask.len() <= group {
+            let last = mask.len() - 1;
+            mask[last] |= MORE_BYTES;
+            mask.push(0);
+        }
+
+        mask[group] |= 1 << (idx % INDICES_PER_BYTE);
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the bitmask only grows to hold the last changed index.
+    #[test]
+    fn mask_bytes() {
+        assert_eq!(changed_mask(vec![].into_iter()), vec![0]);
+        assert_eq!(changed_mask(vec![0, 2].into_iter()), vec![0b101]);
+        assert_eq!(changed_mask(vec![6].into_iter()), vec![0b100_0000]);
+        assert_eq!(changed_mask(vec![7].into_iter()), vec![MORE_BYTES, 0b1]);
+        assert_eq!(
+            changed_mask(vec![1, 15].into_iter()),
+            vec![MORE_BYTES | 0b10, MORE_BYTES, 0b10]
+        );
+    }
+
+    /// Verify that a delta can be round tripped through a self describing format.
+    #[test]
+    fn json_round_trip() {
+        let delta = ArrayDelta::<_, 10> {
+            changes: vec![(1, 5u8), (9, 6)],
+        };
+
+        let json = serde_json::to_string(&delta).unwrap();
+        assert_eq!(json, "[130,4,5,6]");
+
+        let round_tripped: ArrayDelta<u8, 10> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, delta);
+    }
+
+    /// Verify that a bitmask that keeps setting the more bytes bit fails once it is longer than
+    /// the array needs, instead of reading until the input runs out.
+    #[test]
+    fn mask_longer_than_array_errors() {
+        assert_eq!(max_mask_bytes(0), 1);
+        assert_eq!(max_mask_bytes(7), 1);
+        assert_eq!(max_mask_bytes(8), 2);
+
+        let endless = vec![MORE_BYTES; 1000];
+        assert!(bincode::deserialize::<ArrayDelta<u8, 8>>(&endless).is_err());
+        assert!(serde_json::from_str::<ArrayDelta<u8, 8>>("[128,128,0]").is_err());
+
+        let two_bytes: ArrayDelta<u8, 8> = bincode::deserialize(&[MORE_BYTES, 1, 5]).unwrap();
+        assert_eq!(two_bytes.changes, vec![(7, 5)]);
+    }
+
+    /// Verify that a bitmask that marks an index past the end of the array fails.
+    #[test]
+    fn mask_index_out_of_bounds_errors() {
+        assert!(serde_json::from_str::<ArrayDelta<u8, 2>>("[4,9]").is_err());
+        assert!(serde_json::from_str::<ArrayDelta<u8, 0>>("[1,9]").is_err());
+        assert!(serde_json::from_str::<ArrayDelta<u8, 3>>("[4,9]").is_ok());
+    }
+}
@@ -0,0 +1,392 @@
+use crate::{CreatedDelta, Diffable};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Keeps the recent snapshots of some state along with the most recent snapshot that each client
+/// has acknowledged, so that every client can be sent a delta from the last state that it is known
+/// to have.
+///
+/// dipa does not know anything about your transport. You push a snapshot whenever the state that
+/// you send changes, send each client whatever [`BaselineStore::create_update`] gives you and call
+/// [`BaselineStore::acknowledge`] when a client tells you which snapshot it received.
+///
+/// Snapshots are pruned once every client has acknowledged a newer one, or when there are more
+/// than `max_snapshots` of them. A client whose acknowledged snapshot was pruned gets sent the
+/// full state.
+///
+/// ```
+/// # use dipa::{BaselineStore, BaselineUpdate};
+/// let mut store: BaselineStore<Vec<u8>, &str> = BaselineStore::new(32);
+/// store.add_client("alice");
+///
+/// let first = store.push_snapshot(vec![1, 2, 3]);
+/// match store.create_update(&"alice").unwrap() {
+///     BaselineUpdate::Full { sequence, state } => {
+///         assert_eq!(sequence, first);
+///         assert_eq!(state, &vec![1, 2, 3]);
+///     }
+///     BaselineUpdate::Delta { .. } => unreachable!(),
+/// };
+///
+/// store.acknowledge(&"alice", first);
+/// let second = store.push_snapshot(vec![1, 2, 3, 4]);
+///
+/// match store.create_update(&"alice").unwrap() {
+///     BaselineUpdate::Delta { baseline, sequence, delta } => {
+///         assert_eq!((baseline, sequence), (first, second));
+///         assert!(delta.did_change);
+///     }
+///     BaselineUpdate::Full { .. } => unreachable!(),
+/// };
+/// ```
+///
+/// Acknowledgements can be lost or arrive after an update that the client has not acknowledged
+/// yet, so a client should keep the states that it has received keyed by their sequence number
+/// and apply a delta to its copy of the delta's `baseline`, not to whatever state it last
+/// received.
+pub struct BaselineStore<T, C> {
+    /// Ordered from the oldest snapshot to the latest snapshot.
+    snapshots: VecDeque<(u64, T)>,
+    max_snapshots: usize,
+    next_sequence: u64,
+    clients: HashMap<C, ClientBaseline>,
+}
+
+/// What a client should be sent in order to bring it up to date with the latest snapshot.
+pub enum BaselineUpdate<'a, T, D> {
+    /// The client has not acknowledged a snapshot that is still stored, so it needs the full
+    /// state.
+    Full {
+        /// The sequence number of the snapshot.
+        sequence: u64,
+        /// The latest snapshot.
+        state: &'a T,
+    },
+    /// The delta from the client's acknowledged snapshot to the latest snapshot.
+    Delta {
+        /// The sequence number of the snapshot that the delta should be applied to.
+        baseline: u64,
+        /// The sequence number of the snapshot that applying the delta results in.
+        sequence: u64,
+        /// The delta from the baseline to the latest snapshot.
+        delta: CreatedDelta<D>,
+    },
+}
+
+struct ClientBaseline {
+    acked: Option<u64>,
+    /// Snapshots older than this will never be used as this client's baseline.
+    ///
+    /// Before a client acknowledges anything this is the snapshot that was the latest when the
+    /// client was added, since that is the oldest snapshot that it could have been sent.
+    oldest_needed: u64,
+}
+
+impl<T, C: Eq + Hash> BaselineStore<T, C> {
+    /// Create a store that holds at most `max_snapshots` snapshots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_snapshots` is zero.
+    pub fn new(max_snapshots: usize) -> Self {
+        assert!(
+            max_snapshots > 0,
+            "A BaselineStore must hold at least one snapshot."
+        );
+
+        BaselineStore {
+            snapshots: VecDeque::new(),
+            max_snapshots,
+            next_sequence: 0,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Store the latest state and return its sequence number.
+    ///
+    /// Sequence numbers start at zero and increase by one with every snapshot.
+    pub fn push_snapshot(&mut self, state: T) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        self.snapshots.push_back((sequence, state));
+        self.prune();
+
+        sequence
+    }
+
+    /// The latest snapshot and its sequence number.
+    pub fn latest(&self) -> Option<(u64, &T)> {
+        self.snapshots
+            .back()
+            .map(|(sequence, state)| (*sequence, state))
+    }
+
+    /// The snapshot with the given sequence number, if it has not been pruned.
+    pub fn snapshot(&self, sequence: u64) -> Option<&T> {
+        let oldest = self.snapshots.front()?.0;
+        let idx = sequence.checked_sub(oldest)? as usize;
+
+        self.snapshots.get(idx).map(|(_, state)| state)
+    }
+
+    /// The number of snapshots that are currently stored.
+    pub fn snapshot_count(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Start tracking a client's acknowledgements.
+    ///
+    /// Does nothing if the client is already being tracked.
+    pub fn add_client(&mut self, client: C) {
+        let oldest_needed = self.latest().map(|(sequence, _)| sequence);
+        let oldest_needed = oldest_needed.unwrap_or(self.next_sequence);
+
+        self.clients.entry(client).or_insert(ClientBaseline {
+            acked: None,
+            oldest_needed,
+        });
+    }
+
+    /// Stop tracking a client, allowing the snapshots that only it needed to be pruned.
+    pub fn remove_client(&mut self, client: &C) {
+        self.clients.remove(client);
+        self.prune();
+    }
+
+    /// Record that a client has received the snapshot with the given sequence number.
+    ///
+    /// Acknowledgements for clients that are not being tracked, for snapshots that have not been
+    /// pushed yet or that are older than the client's current acknowledgement are ignored, since
+    /// acknowledgements can arrive out of order.
+    pub fn acknowledge(&mut self, client: &C, sequence: u64) {
+        if sequence >= self.next_sequence {
+            return;
+        }
+
+        let baseline = match self.clients.get_mut(client) {
+            Some(baseline) => baseline,
+            None => return,
+        };
+        if baseline
+            .acked
+            .map(|acked| sequence <= acked)
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        baseline.acked = Some(sequence);
+        baseline.oldest_needed = sequence;
+
+        self.prune();
+    }
+
+    /// The sequence number of the latest snapshot that the client has acknowledged.
+    pub fn acknowledged(&self, client: &C) -> Option<u64> {
+        self.clients.get(client)?.acked
+    }
+
+    /// Create what the client needs in order to get from the latest snapshot that it acknowledged
+    /// to the latest snapshot.
+    ///
+    /// Returns `None` if no snapshots have been pushed yet. Clients that are not being tracked get
+    /// the full state.
+    pub fn create_update<'a>(&'a self, client: &C) -> Option<BaselineUpdate<'a, T, T::Delta>>
+    where
+        T: Diffable<'a, 'a, T>,
+    {
+        let (sequence, latest) = self.latest()?;
+
+        let baseline = self
+            .clients
+            .get(client)
+            .and_then(|client| client.acked)
+            .and_then(|acked| Some((acked, self.snapshot(acked)?)));
+
+        let update = match baseline {
+            Some((baseline, baseline_state)) => BaselineUpdate::Delta {
+                baseline,
+                sequence,
+                delta: baseline_state.create_delta_towards(latest),
+            },
+            None => BaselineUpdate::Full {
+                sequence,
+                state: latest,
+            },
+        };
+
+        Some(update)
+    }
+
+    /// Remove the snapshots that no client needs, always keeping the latest snapshot.
+    fn prune(&mut self) {
+        let oldest_needed = self
+            .clients
+            .values()
+            .map(|client| client.oldest_needed)
+            .min()
+            .unwrap_or(u64::MAX);
+
+        while self.snapshots.len() > 1 {
+            let (oldest, _) = &self.snapshots[0];
+
+            if *oldest < oldest_needed || self.snapshots.len() > self.max_snapshots {
+                self.snapshots.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Patchable;
+    use bincode::Options;
+
+    type State = Vec<u8>;
+
+    /// A client on the other end of a lossy connection that keeps every state that it received
+    /// so that it can apply deltas to whichever baseline they were created from.
+    #[derive(Default)]
+    struct SimulatedClient {
+        received: HashMap<u64, State>,
+        latest: Option<u64>,
+    }
+
+    impl SimulatedClient {
+        /// Receive an update and return the sequence number to acknowledge.
+        fn receive(
+            &mut self,
+            update: BaselineUpdate<State, <State as Diffable<State>>::Delta>,
+        ) -> u64 {
+            let (sequence, state) = match update {
+                BaselineUpdate::Full { sequence, state } => (sequence, state.clone()),
+                BaselineUpdate::Delta {
+                    baseline,
+                    sequence,
+                    delta,
+                } => {
+                    let bytes = bincode::options()
+                        .with_varint_encoding()
+                        .serialize(&delta.delta)
+                        .unwrap();
+                    let patch: <State as Diffable<State>>::DeltaOwned = bincode::options()
+                        .with_varint_encoding()
+                        .deserialize(&bytes)
+                        .unwrap();
+
+                    let mut state = self.received[&baseline].clone();
+                    state.apply_patch(patch).unwrap();
+                    (sequence, state)
+                }
+            };
+
+            self.received.insert(sequence, state);
+            self.latest = Some(sequence.max(self.latest.unwrap_or(0)));
+
+            sequence
+        }
+
+        fn latest_state(&self) -> &State {
+            &self.received[&self.latest.unwrap()]
+        }
+    }
+
+    /// Verify that a client stays in sync when updates and acknowledgements get dropped.
+    #[test]
+    fn stays_in_sync_with_simulated_loss() {
+        let mut store = BaselineStore::new(64);
+        store.add_client(0);
+
+        let mut client = SimulatedClient::default();
+
+        let mut state: State = vec![];
+        for tick in 0..100u8 {
+            state.push(tick);
+            if tick % 7 == 0 {
+                state.remove(0);
+            }
+            store.push_snapshot(state.clone());
+
+            let update = store.create_update(&0).unwrap();
+
+            // Drop every third update and every other acknowledgement that makes it through.
+            if tick % 3 == 0 {
+                continue;
+            }
+            let ack = client.receive(update);
+            assert_eq!(client.latest_state(), &state);
+
+            if tick % 2 == 0 {
+                store.acknowledge(&0, ack);
+            }
+        }
+    }
+
+    /// Verify that snapshots are pruned once every client has acknowledged a newer snapshot.
+    #[test]
+    fn prunes_acknowledged_snapshots() {
+        let mut store: BaselineStore<u8, &str> = BaselineStore::new(10);
+        store.add_client("a");
+        store.add_client("b");
+
+        for state in 0..5 {
+            store.push_snapshot(state);
+        }
+        assert_eq!(store.snapshot_count(), 5);
+
+        store.acknowledge(&"a", 3);
+        assert_eq!(store.snapshot_count(), 5);
+
+        store.acknowledge(&"b", 2);
+        assert_eq!(store.snapshot_count(), 3);
+        assert_eq!(store.snapshot(1), None);
+        assert_eq!(store.snapshot(2), Some(&2));
+
+        store.remove_client(&"b");
+        assert_eq!(store.snapshot_count(), 2);
+    }
+
+    /// Verify that older acknowledgements do not move a client's baseline backwards.
+    #[test]
+    fn ignores_out_of_order_acknowledgements() {
+        let mut store: BaselineStore<u8, &str> = BaselineStore::new(10);
+        store.add_client("a");
+
+        for state in 0..5 {
+            store.push_snapshot(state);
+        }
+
+        store.acknowledge(&"a", 3);
+        store.acknowledge(&"a", 1);
+        store.acknowledge(&"a", 50);
+
+        assert_eq!(store.acknowledged(&"a"), Some(3));
+    }
+
+    /// Verify that a client whose baseline was pruned because the store is full gets sent the
+    /// full state.
+    #[test]
+    fn full_state_when_baseline_pruned() {
+        let mut store: BaselineStore<u8, &str> = BaselineStore::new(3);
+        store.add_client("a");
+
+        store.push_snapshot(0);
+        store.acknowledge(&"a", 0);
+
+        for state in 1..5 {
+            store.push_snapshot(state);
+        }
+        assert_eq!(store.snapshot_count(), 3);
+
+        match store.create_update(&"a").unwrap() {
+            BaselineUpdate::Full { sequence, state } => {
+                assert_eq!((sequence, *state), (4, 4));
+            }
+            BaselineUpdate::Delta { .. } => panic!("Expected the full state"),
+        }
+    }
+}
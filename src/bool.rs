@@ -1,4 +1,3 @@
-use crate::CreatedDelta;
 use crate::{number_diff_impl_u8_or_i8, number_patch_impl_u8_or_i8};
 
 number_diff_impl_u8_or_i8!(bool, bool);
@@ -0,0 +1,133 @@
+use crate::PatchError;
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::fmt::Formatter;
+
+/// A patch type that can be deserialized without copying the bytes and strings that it holds out
+/// of the buffer that it was deserialized from, so a client can patch its state straight from the
+/// bytes that it received over the network.
+///
+/// Most types also implement [`crate::Patchable`] for their borrowed delta. Types that derive
+/// `DiffPatch` and have a single field do not, since their owned delta is the field's owned delta
+/// and a second `Patchable` implementation would conflict with it, so prefer
+/// [`BorrowedDelta::apply_borrowed_patch`] in generic code.
+///
+/// ```
+/// # use bincode::Options;
+/// # use dipa::{BorrowedDelta, Diffable};
+/// let mut name = "dipa".to_string();
+/// let latest = "dipa derive".to_string();
+/// let delta = name.create_delta_towards(&latest).delta;
+///
+/// let bytes = bincode::options().with_varint_encoding().serialize(&delta).unwrap();
+///
+/// let patch: <String as BorrowedDelta>::DeltaBorrowed = bincode::options()
+///     .with_varint_encoding()
+///     .deserialize(&bytes)
+///     .unwrap();
+///
+/// name.apply_borrowed_patch(patch).unwrap();
+/// assert_eq!(name, latest);
+/// ```
+///
+/// Whether or not a payload can be borrowed depends on the serialization format. Byte slices can
+/// be borrowed from formats such as bincode that write them as a length followed by the raw bytes.
+pub trait BorrowedDelta<'de> {
+    /// Serialized the same way as the type's `Diffable::DeltaOwned`, but borrows from the
+    /// buffer that it gets deserialized from.
+    type DeltaBorrowed: Deserialize<'de>;
+
+    /// Apply a borrowed patch.
+    fn apply_borrowed_patch(&mut self, patch: Self::DeltaBorrowed) -> Result<(), PatchError>;
+
+    /// Deserialize the items that a patch inserts into a sequence of this type, such as the items
+    /// that get appended to a `Vec<Self>`.
+    ///
+    /// The items are deserialized into a new `Vec` by default. `u8` borrows them from the buffer
+    /// instead, which is what lets byte sequences be patched without copying the new bytes twice.
+    fn deserialize_sequence_items<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Cow<'de, [Self]>, D::Error>
+    where
+        Self: Sized + Clone + Deserialize<'de>,
+    {
+        Vec::deserialize(deserializer).map(Cow::Owned)
+    }
+}
+
+/// Borrow bytes from the buffer when the format supports it, such as bincode, and otherwise fall
+/// back to copying them, such as for JSON which writes them as a sequence of numbers.
+pub(crate) fn deserialize_byte_items<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Cow<'de, [u8]>, D::Error> {
+    struct ByteItemsVisitor;
+
+    impl<'de> Visitor<'de> for ByteItemsVisitor {
+        type Value = Cow<'de, [u8]>;
+
+        fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+            formatter.write_str("a sequence of bytes")
+        }
+
+        fn visit_borrowed_bytes<E>(self, bytes: &'de [u8]) -> Result<Self::Value, E> {
+            Ok(Cow::Borrowed(bytes))
+        }
+
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(bytes.to_vec()))
+        }
+
+        fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(bytes))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+
+            Ok(Cow::Owned(bytes))
+        }
+    }
+
+    deserializer.deserialize_bytes(ByteItemsVisitor)
+}
+
+#[cfg(test)]
+pub(crate) use self::test_utils::test_borrowed_patch;
+
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+    use crate::Diffable;
+    use bincode::Options;
+    use serde::Serialize;
+    use std::fmt::Debug;
+
+    /// Verify that patching `start` with the borrowed patch that was deserialized from the delta
+    /// between `start` and `end` gives us `end`.
+    pub(crate) fn test_borrowed_patch<T>(start: T, end: T)
+    where
+        T: Debug + Clone + PartialEq,
+        T: for<'s, 'e> Diffable<'s, 'e, T>,
+        T: for<'de> BorrowedDelta<'de>,
+        for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    {
+        let bytes = bincode::options()
+            .with_varint_encoding()
+            .serialize(&start.create_delta_towards(&end).delta)
+            .unwrap();
+
+        let patch: <T as BorrowedDelta>::DeltaBorrowed = bincode::options()
+            .with_varint_encoding()
+            .deserialize(&bytes)
+            .unwrap();
+
+        let mut patched = start.clone();
+        patched.apply_borrowed_patch(patch).unwrap();
+
+        assert_eq!(patched, end, "{:?} -> {:?}", start, end);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SquashablePatches, ToOwnedDelta, VisitDelta,
+};
+
+// The deltas are boxed as well so that a type that contains a `Box` of itself, such as a tree
+// node, has deltas that contain a `Box` of their own delta instead of being infinitely large.
+// Boxes serialize the same way as the value that they hold.
+//
+// `Box<[T]>` is diffed like a `Vec<T>`, see the sequence module.
+impl<'s, 'e, T> Diffable<'s, 'e, Box<T>> for Box<T>
+where
+    T: Diffable<'s, 'e, T>,
+{
+    type Delta = Box<<T as Diffable<'s, 'e, T>>::Delta>;
+    type DeltaOwned = Box<<T as Diffable<'s, 'e, T>>::DeltaOwned>;
+
+    fn create_delta_towards(&'s self, end_state: &'e Box<T>) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e Box<T>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        let CreatedDelta { delta, did_change } =
+            (**self).create_delta_towards_version(&**end_state, version);
+
+        CreatedDelta {
+            delta: Box::new(delta),
+            did_change,
+        }
+    }
+}
+
+impl<T, D> Patchable<Box<D>> for Box<T>
+where
+    T: Patchable<D>,
+{
+    fn apply_patch(&mut self, patch: Box<D>) -> Result<(), PatchError> {
+        (**self).apply_patch(*patch)
+    }
+}
+
+impl<T, D> ReversiblePatchable<Box<D>> for Box<T>
+where
+    T: ReversiblePatchable<D>,
+{
+    fn apply_patch_returning_inverse(&mut self, patch: Box<D>) -> Result<Box<D>, PatchError> {
+        (**self).apply_patch_returning_inverse(*patch).map(Box::new)
+    }
+}
+
+impl<T, D> SquashablePatches<Box<D>> for Box<T>
+where
+    T: SquashablePatches<D>,
+{
+    fn squash_patches(first: Box<D>, second: Box<D>) -> Result<Box<D>, PatchError> {
+        T::squash_patches(*first, *second).map(Box::new)
+    }
+}
+
+impl<T, D> MergeablePatches<Box<D>> for Box<T>
+where
+    T: MergeablePatches<D>,
+{
+    fn merge_patches(base: &Self, a: Box<D>, b: Box<D>) -> Result<Box<D>, MergeConflicts> {
+        T::merge_patches(base, *a, *b).map(Box::new)
+    }
+}
+
+impl<D, O> ToOwnedDelta<Box<O>> for Box<D>
+where
+    D: ToOwnedDelta<O>,
+{
+    fn to_owned_delta(self) -> Box<O> {
+        Box::new((*self).to_owned_delta())
+    }
+}
+
+impl<'de, T> BorrowedDelta<'de> for Box<T>
+where
+    T: BorrowedDelta<'de>,
+{
+    type DeltaBorrowed = Box<<T as BorrowedDelta<'de>>::DeltaBorrowed>;
+
+    fn apply_borrowed_patch(&mut self, patch: Self::DeltaBorrowed) -> Result<(), PatchError> {
+        (**self).apply_borrowed_patch(*patch)
+    }
+}
+
+// A box does not change how its value is encoded.
+impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for Box<T>
+where
+    T: crate::SchemaFingerprint<'s, 'e>,
+{
+    const FINGERPRINT: u64 = T::FINGERPRINT;
+}
+
+impl<'s, 'e, T> VisitDelta<'s, 'e> for Box<T>
+where
+    T: VisitDelta<'s, 'e>,
+{
+    fn visit_delta(
+        &self,
+        delta: &Box<<T as Diffable<'s, 'e, T>>::Delta>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        (**self).visit_delta(delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T> crate::ToJsonPatch<'s, 'e> for Box<T>
+where
+    T: crate::ToJsonPatch<'s, 'e>,
+{
+    fn push_json_patch(
+        &self,
+        delta: &Box<<T as Diffable<'s, 'e, T>>::Delta>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        (**self).push_json_patch(delta, path, patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta_visitor::test_print;
+    use crate::dipa_impl_tester::DipaImplTester;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
+    use crate::SchemaFingerprint;
+
+    /// Verify that we can diff/patch a Box.
+    #[test]
+    fn dipa_box_impl() {
+        DipaImplTester {
+            label: Some("Box no change"),
+            start: &mut Box::new(1u32),
+            end: &Box::new(1u32),
+            expected_delta: Box::new(None),
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Box changed"),
+            start: &mut Box::new(1u32),
+            end: &Box::new(2u32),
+            expected_delta: Box::new(Some(2)),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a boxed delta serializes the same way as the value's delta, so that a patch
+    /// created from the unboxed value can be applied to the box, including as a borrowed patch.
+    #[test]
+    fn box_serializes_as_value() {
+        let boxed = Box::new("a".to_string());
+        let delta = "a".create_delta_towards("ab").delta;
+        let bytes = bincode::serialize(&delta).unwrap();
+
+        assert_eq!(
+            bincode::serialize(
+                &boxed
+                    .create_delta_towards(&Box::new("ab".to_string()))
+                    .delta
+            )
+            .unwrap(),
+            bytes
+        );
+
+        let mut patched = boxed.clone();
+        patched
+            .apply_borrowed_patch(bincode::deserialize(&bytes).unwrap())
+            .unwrap();
+        assert_eq!(*patched, "ab");
+
+        assert_eq!(
+            <Box<String> as SchemaFingerprint>::FINGERPRINT,
+            <String as SchemaFingerprint>::FINGERPRINT
+        );
+    }
+
+    /// Verify that patches to a boxed value inside of an `Option`, the way that recursive types
+    /// hold themselves, squash and convert into owned patches.
+    #[test]
+    fn optional_box_patches() {
+        test_squash(Some(Box::new(1u32)), None, Some(Box::new(3)));
+        test_squash(Some(Box::new(1u32)), Some(Box::new(2)), Some(Box::new(5)));
+        test_squash(None, Some(Box::new(vec![1u8])), Some(Box::new(vec![1, 2])));
+
+        test_to_owned_delta(Some(Box::new(vec![1u8])), Some(Box::new(vec![2, 1])));
+    }
+
+    /// Verify that boxes do not add a segment to the path of a conflict or of a change.
+    #[test]
+    fn boxes_are_not_in_paths() {
+        let base = Box::new(Box::new((1u32, 2u32)));
+        let a = base.create_delta_towards(&Box::new(Box::new((3, 2))));
+        let b = base.create_delta_towards(&Box::new(Box::new((4, 2))));
+
+        let boxed_conflicts = Box::<Box<(u32, u32)>>::merge_patches(
+            &base,
+            a.delta.to_owned_delta(),
+            b.delta.to_owned_delta(),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        let unboxed = (1u32, 2u32);
+        let unboxed_conflicts = <(u32, u32)>::merge_patches(
+            &unboxed,
+            unboxed.create_delta_towards(&(3, 2)).delta.to_owned_delta(),
+            unboxed.create_delta_towards(&(4, 2)).delta.to_owned_delta(),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(boxed_conflicts, unboxed_conflicts);
+
+        test_print(
+            Box::new(Box::new((1u16, 2u16))),
+            Box::new(Box::new((1u16, 3u16))),
+            &["1: 2 -> 3"],
+        );
+        #[cfg(feature = "json-patch")]
+        crate::json_patch::test_json_patch(Box::new(vec![1u8]), Box::new(vec![1u8, 2]));
+    }
+}
@@ -0,0 +1,413 @@
+//! `RefCell<T>`, `Mutex<T>`, `RwLock<T>` and `Cell<T>` only hand out their value for as long as
+//! a guard is alive, so a delta can not borrow from the value that it was created from. Their
+//! delta is the inner type's owned delta instead, which serializes the same way as the inner
+//! type's delta.
+//!
+//! Patching uses `get_mut`, which does not need to check for other borrows or take a lock since
+//! the cell is already borrowed mutably.
+//!
+//! Diffing a `RefCell` or merging patches for one panics if the `RefCell` is mutably borrowed,
+//! since there is no value to read until that borrow ends. Diffing a `Mutex` or an `RwLock` that
+//! the current thread holds the write lock of deadlocks for the same reason.
+
+use crate::{
+    BorrowedDelta, CreatedDelta, Diffable, MergeConflicts, MergeablePatches, PatchError, Patchable,
+    ReversiblePatchable, SquashablePatches, ToOwnedDelta,
+};
+use std::cell::{Cell, Ref, RefCell};
+use std::sync::{Mutex, PoisonError, RwLock};
+
+macro_rules! guarded_cell_impl {
+    ($cell:ident, |$read_cell:ident| $read:expr, |$write_cell:ident| $write:expr) => {
+        impl<'s, 'e, T> Diffable<'s, 'e, $cell<T>> for $cell<T>
+        where
+            T: for<'a, 'b> Diffable<'a, 'b, T>,
+            for<'a, 'b> <T as Diffable<'a, 'b, T>>::Delta:
+                ToOwnedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+        {
+            type Delta = <T as Diffable<'s, 'e, T>>::DeltaOwned;
+            type DeltaOwned = <T as Diffable<'s, 'e, T>>::DeltaOwned;
+
+            fn create_delta_towards(
+                &'s self,
+                end_state: &'e $cell<T>,
+            ) -> CreatedDelta<Self::Delta> {
+                self.create_delta_towards_version(end_state, u32::MAX)
+            }
+
+            fn create_delta_towards_version(
+                &'s self,
+                end_state: &'e $cell<T>,
+                version: u32,
+            ) -> CreatedDelta<Self::Delta> {
+                let start = {
+                    let $read_cell = self;
+                    $read
+                };
+
+                // Guarding the same cell twice could deadlock.
+                if std::ptr::eq(self, end_state) {
+                    let diff = start.create_delta_towards_version(&*start, version);
+
+                    return CreatedDelta {
+                        delta: diff.delta.to_owned_delta(),
+                        did_change: diff.did_change,
+                    };
+                }
+
+                let end = {
+                    let $read_cell = end_state;
+                    $read
+                };
+                let diff = start.create_delta_towards_version(&*end, version);
+
+                CreatedDelta {
+                    delta: diff.delta.to_owned_delta(),
+                    did_change: diff.did_change,
+                }
+            }
+        }
+
+        impl<T, D> Patchable<D> for $cell<T>
+        where
+            T: Patchable<D>,
+        {
+            fn apply_patch(&mut self, patch: D) -> Result<(), PatchError> {
+                let $write_cell = self;
+                $write.apply_patch(patch)
+            }
+        }
+
+        impl<T, D> ReversiblePatchable<D> for $cell<T>
+        where
+            T: ReversiblePatchable<D>,
+        {
+            fn apply_patch_returning_inverse(&mut self, patch: D) -> Result<D, PatchError> {
+                let $write_cell = self;
+                $write.apply_patch_returning_inverse(patch)
+            }
+        }
+
+        impl<T, D> SquashablePatches<D> for $cell<T>
+        where
+            T: SquashablePatches<D>,
+        {
+            fn squash_patches(first: D, second: D) -> Result<D, PatchError> {
+                T::squash_patches(first, second)
+            }
+        }
+
+        impl<T, D> MergeablePatches<D> for $cell<T>
+        where
+            T: MergeablePatches<D>,
+        {
+            fn merge_patches(base: &Self, a: D, b: D) -> Result<D, MergeConflicts> {
+                let $read_cell = base;
+                T::merge_patches(&*$read, a, b)
+            }
+        }
+
+        impl<'de, T> BorrowedDelta<'de> for $cell<T>
+        where
+            T: BorrowedDelta<'de>,
+        {
+            type DeltaBorrowed = <T as BorrowedDelta<'de>>::DeltaBorrowed;
+
+            fn apply_borrowed_patch(
+                &mut self,
+                patch: Self::DeltaBorrowed,
+            ) -> Result<(), PatchError> {
+                let $write_cell = self;
+                $write.apply_borrowed_patch(patch)
+            }
+        }
+
+        impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for $cell<T>
+        where
+            T: for<'a, 'b> crate::SchemaFingerprint<'a, 'b>,
+            for<'a, 'b> <T as Diffable<'a, 'b, T>>::Delta:
+                ToOwnedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+        {
+            const FINGERPRINT: u64 = <T as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+        }
+    };
+}
+
+guarded_cell_impl!(RefCell, |cell| borrow_ref_cell(cell), |cell| cell.get_mut());
+
+/// Borrow a `RefCell` that is being diffed.
+///
+/// # Panics
+///
+/// Panics if the `RefCell` is mutably borrowed.
+fn borrow_ref_cell<T>(cell: &RefCell<T>) -> Ref<'_, T> {
+    cell.try_borrow()
+        .expect("Can not diff a RefCell while it is mutably borrowed")
+}
+
+// A poisoned lock still holds a value that can be diffed and patched, and a patch that panicked
+// part of the way through would have returned an error to the caller instead.
+guarded_cell_impl!(
+    Mutex,
+    |cell| cell.lock().unwrap_or_else(PoisonError::into_inner),
+    |cell| cell.get_mut().unwrap_or_else(PoisonError::into_inner)
+);
+guarded_cell_impl!(
+    RwLock,
+    |cell| cell.read().unwrap_or_else(PoisonError::into_inner),
+    |cell| cell.get_mut().unwrap_or_else(PoisonError::into_inner)
+);
+
+impl<'s, 'e, T> Diffable<'s, 'e, Cell<T>> for Cell<T>
+where
+    T: Copy,
+    T: for<'a, 'b> Diffable<'a, 'b, T>,
+    for<'a, 'b> <T as Diffable<'a, 'b, T>>::Delta:
+        ToOwnedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    type Delta = <T as Diffable<'s, 'e, T>>::DeltaOwned;
+    type DeltaOwned = <T as Diffable<'s, 'e, T>>::DeltaOwned;
+
+    fn create_delta_towards(&'s self, end_state: &'e Cell<T>) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e Cell<T>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        let start = self.get();
+        let end = end_state.get();
+
+        let diff = start.create_delta_towards_version(&end, version);
+
+        CreatedDelta {
+            delta: diff.delta.to_owned_delta(),
+            did_change: diff.did_change,
+        }
+    }
+}
+
+impl<T, D> Patchable<D> for Cell<T>
+where
+    T: Patchable<D>,
+{
+    fn apply_patch(&mut self, patch: D) -> Result<(), PatchError> {
+        self.get_mut().apply_patch(patch)
+    }
+}
+
+impl<T, D> ReversiblePatchable<D> for Cell<T>
+where
+    T: ReversiblePatchable<D>,
+{
+    fn apply_patch_returning_inverse(&mut self, patch: D) -> Result<D, PatchError> {
+        self.get_mut().apply_patch_returning_inverse(patch)
+    }
+}
+
+impl<T, D> SquashablePatches<D> for Cell<T>
+where
+    T: SquashablePatches<D>,
+{
+    fn squash_patches(first: D, second: D) -> Result<D, PatchError> {
+        T::squash_patches(first, second)
+    }
+}
+
+impl<T, D> MergeablePatches<D> for Cell<T>
+where
+    T: Copy + MergeablePatches<D>,
+{
+    fn merge_patches(base: &Self, a: D, b: D) -> Result<D, MergeConflicts> {
+        T::merge_patches(&base.get(), a, b)
+    }
+}
+
+impl<'de, T> BorrowedDelta<'de> for Cell<T>
+where
+    T: BorrowedDelta<'de>,
+{
+    type DeltaBorrowed = <T as BorrowedDelta<'de>>::DeltaBorrowed;
+
+    fn apply_borrowed_patch(&mut self, patch: Self::DeltaBorrowed) -> Result<(), PatchError> {
+        self.get_mut().apply_borrowed_patch(patch)
+    }
+}
+
+impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for Cell<T>
+where
+    T: Copy,
+    T: for<'a, 'b> crate::SchemaFingerprint<'a, 'b>,
+    for<'a, 'b> <T as Diffable<'a, 'b, T>>::Delta:
+        ToOwnedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    const FINGERPRINT: u64 = <T as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::dipa_impl_tester::DipaImplTester;
+    use crate::SchemaFingerprint;
+
+    /// Verify that we can diff/patch a RefCell.
+    #[test]
+    fn dipa_ref_cell_impl() {
+        DipaImplTester {
+            label: Some("RefCell no change"),
+            start: &mut RefCell::new(1u32),
+            end: &RefCell::new(1u32),
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test();
+
+        DipaImplTester {
+            label: Some("RefCell changed"),
+            start: &mut RefCell::new(vec![1u8]),
+            end: &RefCell::new(vec![1u8, 2]),
+            expected_delta: vec![1u8]
+                .create_delta_towards(&vec![1u8, 2])
+                .delta
+                .to_owned_delta(),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test();
+    }
+
+    /// Verify that diffing a RefCell that is mutably borrowed panics with a message that says why.
+    #[test]
+    #[should_panic(expected = "Can not diff a RefCell while it is mutably borrowed")]
+    fn diff_mutably_borrowed_ref_cell() {
+        let start = RefCell::new(1u8);
+        let end = RefCell::new(2u8);
+
+        let _borrow = end.borrow_mut();
+        start.create_delta_towards(&end);
+    }
+
+    /// Verify that we can diff/patch a Mutex and an RwLock.
+    ///
+    /// Neither implements `PartialEq`, so we compare their inner values.
+    #[test]
+    fn dipa_lock_impl() {
+        let mut mutex = Mutex::new("a".to_string());
+        let diff = mutex.create_delta_towards(&Mutex::new("abc".to_string()));
+        assert!(diff.did_change);
+        mutex.apply_patch(diff.delta).unwrap();
+        assert_eq!(*mutex.lock().unwrap(), "abc");
+
+        let mut rw_lock = RwLock::new(1u64);
+        let diff = rw_lock.create_delta_towards(&RwLock::new(5));
+        assert_eq!(diff.delta, Some(5));
+        rw_lock.apply_patch(diff.delta).unwrap();
+        assert_eq!(*rw_lock.read().unwrap(), 5);
+    }
+
+    /// Verify that diffing a lock with itself does not deadlock.
+    #[test]
+    fn diff_lock_with_itself() {
+        let mutex = Mutex::new(1u16);
+        assert!(!mutex.create_delta_towards(&mutex).did_change);
+
+        let rw_lock = RwLock::new(1u16);
+        assert!(!rw_lock.create_delta_towards(&rw_lock).did_change);
+    }
+
+    /// Verify that we can diff/patch a Cell.
+    #[test]
+    fn dipa_cell_impl() {
+        DipaImplTester {
+            label: Some("Cell changed"),
+            start: &mut Cell::new(2i32),
+            end: &Cell::new(-3i32),
+            expected_delta: Some(-3),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a poisoned lock can still be diffed, patched and used as a merge base.
+    #[test]
+    fn poisoned_lock() {
+        let mut mutex = Mutex::new(vec![1u8]);
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = mutex.lock().unwrap();
+            panic!("poison the lock");
+        }));
+        assert!(mutex.is_poisoned());
+
+        let end = Mutex::new(vec![1u8, 2]);
+        let diff = mutex.create_delta_towards(&end);
+        assert!(diff.did_change);
+
+        let merged = Mutex::merge_patches(&mutex, diff.delta.clone(), diff.delta).unwrap();
+        mutex.apply_patch(merged).unwrap();
+        assert_eq!(
+            *mutex.lock().unwrap_or_else(PoisonError::into_inner),
+            vec![1, 2]
+        );
+    }
+
+    /// Verify that merging patches for a RefCell that is mutably borrowed panics with a message
+    /// that says why.
+    #[test]
+    #[should_panic(expected = "Can not diff a RefCell while it is mutably borrowed")]
+    fn merge_mutably_borrowed_ref_cell() {
+        let base = RefCell::new(1u32);
+        let _borrow = base.borrow_mut();
+
+        let _ = RefCell::merge_patches(&base, Some(2), None);
+    }
+
+    /// Verify that the deltas of a cell are already owned, so they can be squashed and merged
+    /// without converting them first, and that a `Cell` merges against a copy of its value.
+    #[test]
+    fn cell_deltas_are_owned() {
+        let start = RefCell::new("a".to_string());
+        let middle = RefCell::new("ab".to_string());
+        let end = RefCell::new("b".to_string());
+
+        let squashed = RefCell::<String>::squash_patches(
+            start.create_delta_towards(&middle).delta,
+            middle.create_delta_towards(&end).delta,
+        )
+        .unwrap();
+        let mut patched = start.clone();
+        patched.apply_patch(squashed).unwrap();
+        assert_eq!(patched, end);
+
+        let base = Cell::new((1u8, 2u8));
+        let merged = Cell::merge_patches(
+            &base,
+            base.create_delta_towards(&Cell::new((3, 2))).delta,
+            base.create_delta_towards(&Cell::new((1, 4))).delta,
+        )
+        .unwrap();
+        let mut patched = base.clone();
+        patched.apply_patch(merged).unwrap();
+        assert_eq!(patched.get(), (3, 4));
+    }
+
+    /// Verify that a borrowed patch can be deserialized from a cell's delta, which is the inner
+    /// value's owned delta, and that a lock has the fingerprint of its value since they serialize
+    /// the same way.
+    #[test]
+    fn delta_serializes_as_inner_delta() {
+        test_borrowed_patch(
+            RefCell::new("a".to_string()),
+            RefCell::new("ab".to_string()),
+        );
+
+        assert_eq!(
+            <Mutex<Vec<u8>> as SchemaFingerprint>::FINGERPRINT,
+            <Vec<u8> as SchemaFingerprint>::FINGERPRINT
+        );
+    }
+}
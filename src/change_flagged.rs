@@ -0,0 +1,507 @@
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SquashablePatches, ToOwnedDelta, VisitDelta,
+};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A value that remembers when it was last mutably accessed, so that delta encoding it can be
+/// skipped when it was not touched since the start state was taken.
+///
+/// Delta encoding some types is expensive, such as a `Vec` with thousands of items which takes
+/// `O(M * N)` time to diff. Every `ChangeFlagged` has a generation that changes whenever the value
+/// is accessed through `DerefMut`, even if it is not actually modified, and when a patch that
+/// changes it is applied. Clones share their generation, so when the start state is a clone of the
+/// end state that was taken before it was last touched, the generations match and the delta is
+/// `NoChange` without diffing the inner values at all. Otherwise the inner values are diffed as
+/// usual.
+///
+/// Since the start state's generation is compared rather than a flag on the end state, every
+/// baseline is diffed correctly no matter how old it is, such as when each client has acknowledged
+/// a different snapshot.
+///
+/// There is no flag to reset after a delta is sent. Keeping a clone of the sent state as the next
+/// start state is what resets it, since the clone keeps the generation that the value had when it
+/// was sent. Creating a delta never changes either value.
+///
+/// ```
+/// # use dipa::{ChangeFlagged, ChangeFlaggedDelta, Diffable};
+/// let mut current = ChangeFlagged::new(vec![1u32, 2, 3]);
+/// let baseline = current.clone();
+///
+/// // The generations match, so the vectors are not diffed.
+/// let delta = baseline.create_delta_towards(&current).delta;
+/// assert!(matches!(delta, ChangeFlaggedDelta::NoChange));
+///
+/// current.push(4);
+/// assert_ne!(current.generation(), baseline.generation());
+/// assert!(baseline.create_delta_towards(&current).did_change);
+/// ```
+///
+/// Changes that are made without going through `DerefMut`, such as through a `Cell` inside of the
+/// value, are not noticed. Call [`ChangeFlagged::mark_changed`] after making them.
+///
+/// The delta has a variant for when the value was not diffed, so it is one byte larger than the
+/// inner value's delta. The inner value's delta for "no change" can only be created by diffing,
+/// which is what this type avoids.
+///
+/// Only the inner value is compared for equality and serialized, the generation is not. A new or
+/// deserialized value gets a generation that no other value has.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChangeFlagged<T> {
+    value: T,
+    #[serde(skip, default = "next_generation")]
+    generation: u64,
+}
+
+/// The delta of a [`ChangeFlagged`] value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChangeFlaggedDelta<D> {
+    /// The value was not changed, or was not touched since the start state was taken.
+    NoChange,
+    /// The delta of the inner value.
+    Changed(D),
+}
+
+// Generations are unique across every `ChangeFlagged`, so that two values that were cloned from
+// the same value and then mutated separately never share a generation.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn next_generation() -> u64 {
+    NEXT_GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+impl<T> ChangeFlagged<T> {
+    /// Wrap a value. It gets a new generation.
+    pub fn new(value: T) -> Self {
+        ChangeFlagged {
+            value,
+            generation: next_generation(),
+        }
+    }
+
+    /// The generation of the value, which changes whenever it is mutably accessed or patched.
+    ///
+    /// Two values with the same generation have equal inner values.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Give the value a new generation, such as after modifying it through interior mutability.
+    pub fn mark_changed(&mut self) {
+        self.generation = next_generation();
+    }
+
+    /// Unwrap the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Default> Default for ChangeFlagged<T> {
+    fn default() -> Self {
+        ChangeFlagged::new(T::default())
+    }
+}
+
+impl<T> From<T> for ChangeFlagged<T> {
+    fn from(value: T) -> Self {
+        ChangeFlagged::new(value)
+    }
+}
+
+impl<T> Deref for ChangeFlagged<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for ChangeFlagged<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.mark_changed();
+        &mut self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for ChangeFlagged<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for ChangeFlagged<T> {}
+
+// Matching generations mean that the end state was not touched since the start state was cloned
+// from it.
+impl<'s, 'e, T> Diffable<'s, 'e, ChangeFlagged<T>> for ChangeFlagged<T>
+where
+    T: Diffable<'s, 'e, T>,
+{
+    type Delta = ChangeFlaggedDelta<<T as Diffable<'s, 'e, T>>::Delta>;
+    type DeltaOwned = ChangeFlaggedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>;
+
+    fn create_delta_towards(
+        &'s self,
+        end_state: &'e ChangeFlagged<T>,
+    ) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e ChangeFlagged<T>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        if self.generation == end_state.generation {
+            return CreatedDelta {
+                delta: ChangeFlaggedDelta::NoChange,
+                did_change: false,
+            };
+        }
+
+        let diff = self
+            .value
+            .create_delta_towards_version(&end_state.value, version);
+
+        let delta = match diff.did_change {
+            true => ChangeFlaggedDelta::Changed(diff.delta),
+            false => ChangeFlaggedDelta::NoChange,
+        };
+
+        CreatedDelta {
+            delta,
+            did_change: diff.did_change,
+        }
+    }
+}
+
+impl<T, D> Patchable<ChangeFlaggedDelta<D>> for ChangeFlagged<T>
+where
+    T: Patchable<D>,
+{
+    fn apply_patch(&mut self, patch: ChangeFlaggedDelta<D>) -> Result<(), PatchError> {
+        if let ChangeFlaggedDelta::Changed(patch) = patch {
+            self.value.apply_patch(patch)?;
+            self.mark_changed();
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, D> ReversiblePatchable<ChangeFlaggedDelta<D>> for ChangeFlagged<T>
+where
+    T: ReversiblePatchable<D>,
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: ChangeFlaggedDelta<D>,
+    ) -> Result<ChangeFlaggedDelta<D>, PatchError> {
+        match patch {
+            ChangeFlaggedDelta::NoChange => Ok(ChangeFlaggedDelta::NoChange),
+            ChangeFlaggedDelta::Changed(patch) => {
+                let inverse = self.value.apply_patch_returning_inverse(patch)?;
+                self.mark_changed();
+
+                Ok(ChangeFlaggedDelta::Changed(inverse))
+            }
+        }
+    }
+}
+
+impl<T, D> SquashablePatches<ChangeFlaggedDelta<D>> for ChangeFlagged<T>
+where
+    T: SquashablePatches<D>,
+{
+    fn squash_patches(
+        first: ChangeFlaggedDelta<D>,
+        second: ChangeFlaggedDelta<D>,
+    ) -> Result<ChangeFlaggedDelta<D>, PatchError> {
+        match (first, second) {
+            (ChangeFlaggedDelta::Changed(first), ChangeFlaggedDelta::Changed(second)) => Ok(
+                ChangeFlaggedDelta::Changed(T::squash_patches(first, second)?),
+            ),
+            (first, ChangeFlaggedDelta::NoChange) => Ok(first),
+            (ChangeFlaggedDelta::NoChange, second) => Ok(second),
+        }
+    }
+}
+
+impl<T, D> MergeablePatches<ChangeFlaggedDelta<D>> for ChangeFlagged<T>
+where
+    T: MergeablePatches<D>,
+{
+    fn merge_patches(
+        base: &Self,
+        a: ChangeFlaggedDelta<D>,
+        b: ChangeFlaggedDelta<D>,
+    ) -> Result<ChangeFlaggedDelta<D>, MergeConflicts> {
+        match (a, b) {
+            (ChangeFlaggedDelta::Changed(a), ChangeFlaggedDelta::Changed(b)) => Ok(
+                ChangeFlaggedDelta::Changed(T::merge_patches(&base.value, a, b)?),
+            ),
+            (a, ChangeFlaggedDelta::NoChange) => Ok(a),
+            (ChangeFlaggedDelta::NoChange, b) => Ok(b),
+        }
+    }
+}
+
+impl<D, O> ToOwnedDelta<ChangeFlaggedDelta<O>> for ChangeFlaggedDelta<D>
+where
+    D: ToOwnedDelta<O>,
+{
+    fn to_owned_delta(self) -> ChangeFlaggedDelta<O> {
+        match self {
+            ChangeFlaggedDelta::NoChange => ChangeFlaggedDelta::NoChange,
+            ChangeFlaggedDelta::Changed(delta) => {
+                ChangeFlaggedDelta::Changed(delta.to_owned_delta())
+            }
+        }
+    }
+}
+
+impl<'de, T> BorrowedDelta<'de> for ChangeFlagged<T>
+where
+    T: BorrowedDelta<'de>,
+{
+    type DeltaBorrowed = ChangeFlaggedDelta<<T as BorrowedDelta<'de>>::DeltaBorrowed>;
+
+    fn apply_borrowed_patch(&mut self, patch: Self::DeltaBorrowed) -> Result<(), PatchError> {
+        if let ChangeFlaggedDelta::Changed(patch) = patch {
+            self.value.apply_borrowed_patch(patch)?;
+            self.mark_changed();
+        }
+
+        Ok(())
+    }
+}
+
+impl<'s, 'e, T: VisitDelta<'s, 'e>> VisitDelta<'s, 'e> for ChangeFlagged<T> {
+    fn visit_delta(
+        &self,
+        delta: &ChangeFlaggedDelta<<T as Diffable<'s, 'e, T>>::Delta>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        if let ChangeFlaggedDelta::Changed(delta) = delta {
+            self.value.visit_delta(delta, visitor);
+        }
+    }
+}
+
+impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for ChangeFlagged<T>
+where
+    T: crate::SchemaFingerprint<'s, 'e>,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("ChangeFlagged")
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+// A `ChangeFlagged` serializes as its inner value, so the inner value's JSON Patch applies to it
+// as is.
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T: crate::ToJsonPatch<'s, 'e>> crate::ToJsonPatch<'s, 'e> for ChangeFlagged<T> {
+    fn push_json_patch(
+        &self,
+        delta: &ChangeFlaggedDelta<<T as Diffable<'s, 'e, T>>::Delta>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        match delta {
+            ChangeFlaggedDelta::Changed(delta) => self.value.push_json_patch(delta, path, patch),
+            ChangeFlaggedDelta::NoChange => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequence::SequenceModificationDelta;
+    use crate::{DeltaPrinter, DipaImplTester, ToOwnedDelta};
+
+    /// Verify that values with different generations are diffed.
+    #[test]
+    fn change_flagged_dipa() {
+        DipaImplTester {
+            label: Some("Different generations and changed"),
+            start: &mut ChangeFlagged::new(vec![1u8, 2]),
+            end: &ChangeFlagged::new(vec![1u8, 2, 3]),
+            expected_delta: ChangeFlaggedDelta::Changed(vec![
+                SequenceModificationDelta::AppendOne { item: &3 },
+            ]),
+            // 1 for the variant, 1 for the vec length, 1 for the modification, 1 for the item
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Different generations but unchanged"),
+            start: &mut ChangeFlagged::new(vec![1u8, 2]),
+            end: &ChangeFlagged::new(vec![1u8, 2]),
+            expected_delta: ChangeFlaggedDelta::NoChange,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that the values are not diffed when they share a generation, which we check by
+    /// giving values that differ the same generation.
+    #[test]
+    fn same_generation_is_not_diffed() {
+        let start = ChangeFlagged::new(vec![1u8, 2]);
+        let mut end = ChangeFlagged::new(vec![1u8, 2, 3]);
+        end.generation = start.generation;
+
+        let diff = start.create_delta_towards(&end);
+
+        assert_eq!(diff.delta, ChangeFlaggedDelta::NoChange);
+        assert!(!diff.did_change);
+    }
+
+    /// Verify that baselines of different ages that were cloned from the current value are each
+    /// brought up to date, since no baseline resets anything that the others rely on.
+    #[test]
+    fn baselines_of_different_ages() {
+        let mut current = ChangeFlagged::new(vec![1u8]);
+        let old_baseline = current.clone();
+
+        current.push(2);
+        let recent_baseline = current.clone();
+
+        let recent_delta = recent_baseline.create_delta_towards(&current);
+        assert_eq!(recent_delta.delta, ChangeFlaggedDelta::NoChange);
+
+        let old_delta = old_baseline.create_delta_towards(&current);
+        assert_eq!(
+            old_delta.delta,
+            ChangeFlaggedDelta::Changed(vec![SequenceModificationDelta::AppendOne { item: &2 }])
+        );
+
+        current.push(3);
+        for baseline in [old_baseline, recent_baseline] {
+            let mut client = baseline.clone();
+            client
+                .apply_patch(
+                    baseline
+                        .create_delta_towards(&current)
+                        .delta
+                        .to_owned_delta(),
+                )
+                .unwrap();
+            assert_eq!(client, current);
+        }
+    }
+
+    /// Verify that a clone shares its generation until either value is mutated, and that values
+    /// that were mutated separately never share a generation.
+    #[test]
+    fn clones_share_a_generation_until_mutated() {
+        let mut original = ChangeFlagged::new(5u32);
+        let mut clone = original.clone();
+        assert_eq!(original.generation(), clone.generation());
+
+        *clone += 1;
+        assert_ne!(original.generation(), clone.generation());
+
+        *original += 1;
+        assert_ne!(original.generation(), clone.generation());
+
+        let before = original.generation();
+        assert_eq!(*original, 6);
+        assert_eq!(original.generation(), before);
+
+        original.mark_changed();
+        assert_ne!(original.generation(), before);
+    }
+
+    /// Verify that only patches that change the value give it a new generation.
+    #[test]
+    fn patches_change_the_generation() {
+        let mut value = ChangeFlagged::new(5u32);
+        let before = value.generation();
+
+        value.apply_patch(ChangeFlaggedDelta::NoChange).unwrap();
+        assert_eq!(value.generation(), before);
+
+        value
+            .apply_patch(ChangeFlaggedDelta::Changed(Some(7)))
+            .unwrap();
+        assert_ne!(value.generation(), before);
+        assert_eq!(value.into_inner(), 7);
+    }
+
+    /// Verify that the generation is not serialized and that deserialized values get a new one.
+    #[test]
+    fn generation_is_not_serialized() {
+        let value = ChangeFlagged::new(5u32);
+
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bytes, bincode::serialize(&5u32).unwrap());
+
+        let deserialized: ChangeFlagged<u32> = bincode::deserialize(&bytes).unwrap();
+        assert_ne!(deserialized.generation(), value.generation());
+    }
+
+    /// Verify that a patch that skipped the diff squashes and merges with one that did not.
+    #[test]
+    fn skipped_patches_squash_and_merge() {
+        let base = ChangeFlagged::new(vec![1u8, 2]);
+        let mut changed = base.clone();
+        changed.push(3);
+        let untouched = changed.clone();
+
+        type Patch = <ChangeFlagged<Vec<u8>> as Diffable<
+            'static,
+            'static,
+            ChangeFlagged<Vec<u8>>,
+        >>::DeltaOwned;
+
+        let first: Patch = base.create_delta_towards(&changed).delta.to_owned_delta();
+        let second: Patch = changed
+            .create_delta_towards(&untouched)
+            .delta
+            .to_owned_delta();
+        assert_eq!(second, ChangeFlaggedDelta::NoChange);
+
+        let squashed =
+            ChangeFlagged::<Vec<u8>>::squash_patches(first.clone(), second.clone()).unwrap();
+        assert_eq!(squashed, first);
+        assert_eq!(
+            ChangeFlagged::<Vec<u8>>::squash_patches(second, first.clone()).unwrap(),
+            first
+        );
+
+        let merged =
+            ChangeFlagged::merge_patches(&base, ChangeFlaggedDelta::NoChange, first.clone())
+                .unwrap();
+        assert_eq!(merged, first);
+    }
+
+    /// Verify that a value that was not diffed prints nothing.
+    #[test]
+    fn print_skipped_delta() {
+        let value = ChangeFlagged::new(1u16);
+        let delta = value.create_delta_towards(&value).delta;
+
+        assert_eq!(DeltaPrinter::print(&value, &delta).to_string(), "");
+    }
+
+    /// Verify that applying a flagged value's JSON Patch to the start value gives us the end value.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn change_flagged_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        test_json_patch(
+            ChangeFlagged::new(vec![1u16, 2]),
+            ChangeFlagged::new(vec![1u16, 3]),
+        );
+    }
+}
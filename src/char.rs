@@ -0,0 +1,57 @@
+use crate::{number_diff_impl_option_wrapped, number_patch_impl_option_wrapped};
+
+// serde encodes a char as its UTF-8 bytes, so most deltas for ASCII characters are two bytes.
+number_diff_impl_option_wrapped!(char, char);
+number_patch_impl_option_wrapped!(char, Option<char>);
+
+#[cfg(test)]
+mod tests {
+    use crate::dipa_impl_tester::DipaImplTester;
+
+    #[test]
+    fn char_unchanged() {
+        DipaImplTester {
+            label: Some("Diff patch same char"),
+            start: &mut 'a',
+            end: &'a',
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test();
+    }
+
+    #[test]
+    fn char_changed() {
+        DipaImplTester {
+            label: Some("Diff patch different char"),
+            start: &mut 'a',
+            end: &'b',
+            expected_delta: Some('b'),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test();
+    }
+
+    /// Verify that characters outside of ASCII take up as many bytes as their UTF-8 encoding.
+    #[test]
+    fn char_changed_multi_byte() {
+        DipaImplTester {
+            label: Some("Diff patch multi byte char"),
+            start: &mut 'a',
+            end: &'🦀',
+            expected_delta: Some('🦀'),
+            expected_serialized_patch_size: 5,
+            expected_did_change: true,
+        }
+        .test();
+    }
+
+    /// Verify that we can merge char patches unless they set different characters.
+    #[test]
+    fn merge_char_patches() {
+        crate::merge_patches::test_merge('a', 'b', 'a', 'b');
+        crate::merge_patches::test_merge('a', 'b', 'b', 'b');
+    }
+}
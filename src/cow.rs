@@ -1,4 +1,7 @@
-use crate::{CreatedDelta, Diffable, Patchable};
+use crate::{
+    CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches, PatchError, Patchable,
+    ReversiblePatchable, SquashablePatches, VisitDelta,
+};
 use std::borrow::{Borrow, Cow};
 
 impl<'s, 'e, 'a, T> Diffable<'s, 'e, Cow<'a, T>> for Cow<'a, T>
@@ -11,9 +14,17 @@ where
     type DeltaOwned = <T as Diffable<'s, 'e, T>>::DeltaOwned;
 
     fn create_delta_towards(&'s self, end_state: &'e Cow<'a, T>) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e Cow<'a, T>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
         let inner_self: &T = self.borrow();
 
-        inner_self.create_delta_towards(end_state.borrow())
+        inner_self.create_delta_towards_version(end_state.borrow(), version)
     }
 }
 
@@ -24,14 +35,113 @@ where
     T: Diffable<'s, 'e, T>,
     <T as ToOwned>::Owned: Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
 {
-    fn apply_patch(&mut self, patch: <T as Diffable<'s, 'e, T>>::DeltaOwned) {
+    fn apply_patch(
+        &mut self,
+        patch: <T as Diffable<'s, 'e, T>>::DeltaOwned,
+    ) -> Result<(), PatchError> {
         self.to_mut().apply_patch(patch)
     }
 }
 
+impl<'s, 'e, 'a, T> ReversiblePatchable<<Self as Diffable<'s, 'e, Self>>::DeltaOwned> for Cow<'a, T>
+where
+    T: ToOwned,
+    T: ?Sized,
+    T: Diffable<'s, 'e, T>,
+    <T as ToOwned>::Owned: ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: <T as Diffable<'s, 'e, T>>::DeltaOwned,
+    ) -> Result<<T as Diffable<'s, 'e, T>>::DeltaOwned, PatchError> {
+        self.to_mut().apply_patch_returning_inverse(patch)
+    }
+}
+
+impl<'s, 'e, 'a, T> SquashablePatches<<Self as Diffable<'s, 'e, Self>>::DeltaOwned> for Cow<'a, T>
+where
+    T: ToOwned,
+    T: ?Sized,
+    T: Diffable<'s, 'e, T>,
+    <T as ToOwned>::Owned: SquashablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn squash_patches(
+        first: <T as Diffable<'s, 'e, T>>::DeltaOwned,
+        second: <T as Diffable<'s, 'e, T>>::DeltaOwned,
+    ) -> Result<<T as Diffable<'s, 'e, T>>::DeltaOwned, PatchError> {
+        <T as ToOwned>::Owned::squash_patches(first, second)
+    }
+}
+
+impl<'s, 'e, 'a, T> MergeablePatches<<Self as Diffable<'s, 'e, Self>>::DeltaOwned> for Cow<'a, T>
+where
+    T: ToOwned,
+    T: ?Sized,
+    T: Diffable<'s, 'e, T>,
+    <T as ToOwned>::Owned: MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    // The owned type's implementation needs the base as an owned value, so a borrowed base gets
+    // cloned.
+    fn merge_patches(
+        base: &Self,
+        a: <T as Diffable<'s, 'e, T>>::DeltaOwned,
+        b: <T as Diffable<'s, 'e, T>>::DeltaOwned,
+    ) -> Result<<T as Diffable<'s, 'e, T>>::DeltaOwned, MergeConflicts> {
+        match base {
+            Cow::Borrowed(base) => <T as ToOwned>::Owned::merge_patches(&(*base).to_owned(), a, b),
+            Cow::Owned(base) => <T as ToOwned>::Owned::merge_patches(base, a, b),
+        }
+    }
+}
+
+impl<'s, 'e, 'a, T> crate::SchemaFingerprint<'s, 'e> for Cow<'a, T>
+where
+    T: ToOwned,
+    T: ?Sized,
+    T: crate::SchemaFingerprint<'s, 'e>,
+{
+    const FINGERPRINT: u64 = T::FINGERPRINT;
+}
+
+impl<'s, 'e, 'a, T> VisitDelta<'s, 'e> for Cow<'a, T>
+where
+    T: ToOwned,
+    T: ?Sized,
+    T: VisitDelta<'s, 'e>,
+{
+    fn visit_delta(
+        &self,
+        delta: &<T as Diffable<'s, 'e, T>>::Delta,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        let inner_self: &T = self.borrow();
+
+        inner_self.visit_delta(delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, 'a, T> crate::ToJsonPatch<'s, 'e> for Cow<'a, T>
+where
+    T: ToOwned,
+    T: ?Sized,
+    T: crate::ToJsonPatch<'s, 'e>,
+{
+    fn push_json_patch(
+        &self,
+        delta: &<T as Diffable<'s, 'e, T>>::Delta,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        let inner_self: &T = self.borrow();
+
+        inner_self.push_json_patch(delta, path, patch)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::sequence::SequenceModificationDelta;
+    use crate::text::TextEdit;
     use crate::DipaImplTester;
     use std::borrow::Cow;
 
@@ -85,8 +195,12 @@ mod tests {
             label: Some("Cow change Borrowed -> Owned"),
             start: &mut Cow::Borrowed(hello_static),
             end: &Cow::Owned(empty_static.to_string()),
-            expected_delta: vec![SequenceModificationDelta::DeleteAll],
-            expected_serialized_patch_size: 2,
+            expected_delta: vec![TextEdit {
+                index: 0,
+                remove: 5,
+                insert: "",
+            }],
+            expected_serialized_patch_size: 4,
             expected_did_change: true,
         }
         .test();
@@ -95,8 +209,12 @@ mod tests {
             label: Some("Cow change Borrowed -> Borrowed"),
             start: &mut Cow::Borrowed(hello_static),
             end: &Cow::Borrowed(empty_static),
-            expected_delta: vec![SequenceModificationDelta::DeleteAll],
-            expected_serialized_patch_size: 2,
+            expected_delta: vec![TextEdit {
+                index: 0,
+                remove: 5,
+                insert: "",
+            }],
+            expected_serialized_patch_size: 4,
             expected_did_change: true,
         }
         .test();
@@ -105,8 +223,12 @@ mod tests {
             label: Some("Cow change Owned -> Borrowed"),
             start: &mut Cow::Owned(hello_static.to_string()),
             end: &Cow::Borrowed(empty_static),
-            expected_delta: vec![SequenceModificationDelta::DeleteAll],
-            expected_serialized_patch_size: 2,
+            expected_delta: vec![TextEdit {
+                index: 0,
+                remove: 5,
+                insert: "",
+            }],
+            expected_serialized_patch_size: 4,
             expected_did_change: true,
         }
         .test();
@@ -115,8 +237,12 @@ mod tests {
             label: Some("Cow change Owned -> Owned"),
             start: &mut Cow::Owned::<'static, str>(hello_static.to_string()),
             end: &Cow::Owned(empty_static.to_string()),
-            expected_delta: vec![SequenceModificationDelta::DeleteAll],
-            expected_serialized_patch_size: 2,
+            expected_delta: vec![TextEdit {
+                index: 0,
+                remove: 5,
+                insert: "",
+            }],
+            expected_serialized_patch_size: 4,
             expected_did_change: true,
         }
         .test();
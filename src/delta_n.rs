@@ -20,3 +20,74 @@
 //
 // Useful for our generic tuple dipa implementations.
 include!(concat!(env!("OUT_DIR"), "/delta_n_types.rs"));
+
+/// Deserializes an enum variant's identifier, which is its index in binary formats and its name in
+/// self describing formats, into the variant's index.
+pub(crate) struct VariantIndexSeed(pub &'static [&'static str]);
+
+impl<'de> serde::de::DeserializeSeed<'de> for VariantIndexSeed {
+    type Value = usize;
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for VariantIndexSeed {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("variant identifier")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<usize, E> {
+        if value < self.0.len() as u64 {
+            Ok(value as usize)
+        } else {
+            Err(E::invalid_value(
+                serde::de::Unexpected::Unsigned(value),
+                &"a variant index",
+            ))
+        }
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<usize, E> {
+        self.0
+            .iter()
+            .position(|variant| *variant == value)
+            .ok_or_else(|| E::unknown_variant(value, self.0))
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<usize, E> {
+        match std::str::from_utf8(value) {
+            Ok(value) => self.visit_str(value),
+            Err(_) => Err(E::invalid_value(
+                serde::de::Unexpected::Bytes(value),
+                &"a variant name",
+            )),
+        }
+    }
+}
+
+/// Read the next field of a variant if the field at `field_idx` is one of the `changed` fields.
+pub(crate) fn next_changed_field<'de, S, T>(
+    seq: &mut S,
+    changed: u16,
+    field_idx: u8,
+    read: &mut usize,
+) -> Result<Option<T>, S::Error>
+where
+    S: serde::de::SeqAccess<'de>,
+    T: serde::Deserialize<'de>,
+{
+    if changed & (1 << field_idx) == 0 {
+        return Ok(None);
+    }
+
+    let field = seq
+        .next_element()?
+        .ok_or_else(|| serde::de::Error::invalid_length(*read, &"a changed field"))?;
+    *read += 1;
+
+    Ok(Some(field))
+}
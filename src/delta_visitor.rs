@@ -0,0 +1,243 @@
+use crate::{Diffable, PathSegment};
+use std::fmt::{Debug, Display, Formatter};
+
+/// Walks the changes in a delta against the value that it was created from.
+///
+/// Deltas use compact variants such as `Change_0_1` that are hard to read while debugging. Visiting
+/// a delta reports every change along with the path to the value that changed, which makes it
+/// easier to see why a delta is larger than expected.
+///
+/// This is implemented for the start value instead of the delta since types with a single field
+/// reuse their field's delta type, so the delta on its own does not know which type it came from.
+///
+/// ```
+/// # use dipa::{DeltaPrinter, Diffable};
+/// let start = vec![1u32, 2, 3];
+/// let end = vec![1, 2, 3, 4];
+///
+/// let delta = start.create_delta_towards(&end).delta;
+///
+/// assert_eq!(DeltaPrinter::print(&start, &delta).to_string(), "[3]: inserted");
+/// ```
+pub trait VisitDelta<'s, 'e>: Diffable<'s, 'e, Self> {
+    /// Report every change that `delta` makes to `self` to the visitor.
+    fn visit_delta(&self, delta: &Self::Delta, visitor: &mut dyn DeltaVisitor);
+}
+
+/// Receives the changes in a delta from [`VisitDelta::visit_delta`].
+///
+/// Changes are reported relative to the path that has been entered but not yet exited.
+pub trait DeltaVisitor {
+    /// Move into a field, variant or index of the current value.
+    fn enter(&mut self, segment: PathSegment);
+
+    /// Move into the value stored under a key of the current map or set.
+    fn enter_key(&mut self, key: &dyn Debug);
+
+    /// Move back out of the most recently entered segment or key.
+    fn exit(&mut self);
+
+    /// The value at the current path changed.
+    fn visit_change(&mut self, change: DeltaChange<'_>);
+}
+
+/// A change to the value at the visitor's current path.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum DeltaChange<'a> {
+    /// The value was set to a new value.
+    Value {
+        /// The value before the change.
+        old: &'a dyn Debug,
+        /// The value after the change.
+        new: &'a dyn Debug,
+    },
+    /// An enum changed from one variant to another.
+    Variant {
+        /// The variant before the change.
+        old: &'static str,
+        /// The variant after the change.
+        new: &'static str,
+    },
+    /// The value was replaced by a new value that gets sent in full.
+    Replaced,
+    /// The value was added, such as a new sequence item, map entry or `Some` value.
+    Inserted,
+    /// The value was removed.
+    Removed,
+}
+
+/// A [`DeltaVisitor`] that renders every change as a line of text.
+///
+/// Each line starts with the path to the change, such as `position.x: 1.0 -> 4.0` or
+/// `notifications[2]: inserted`.
+#[derive(Debug, Clone, Default)]
+pub struct DeltaPrinter {
+    path: Vec<PrintedSegment>,
+    lines: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum PrintedSegment {
+    Segment(PathSegment),
+    Key(String),
+}
+
+impl DeltaPrinter {
+    /// Create a printer with no lines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Print every change that `delta` makes to `start`.
+    pub fn print<'s, 'e, T: VisitDelta<'s, 'e>>(
+        start: &T,
+        delta: &<T as Diffable<'s, 'e, T>>::Delta,
+    ) -> Self {
+        let mut printer = Self::new();
+        start.visit_delta(delta, &mut printer);
+
+        printer
+    }
+
+    /// One line per change, in the order that they were visited.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Whether no changes were printed.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    fn push_line(&mut self, description: std::fmt::Arguments) {
+        let line = if self.path.is_empty() {
+            description.to_string()
+        } else {
+            format!("{}: {}", Path(&self.path), description)
+        };
+
+        self.lines.push(line);
+    }
+}
+
+impl DeltaVisitor for DeltaPrinter {
+    fn enter(&mut self, segment: PathSegment) {
+        self.path.push(PrintedSegment::Segment(segment));
+    }
+
+    fn enter_key(&mut self, key: &dyn Debug) {
+        self.path.push(PrintedSegment::Key(format!("{:?}", key)));
+    }
+
+    fn exit(&mut self) {
+        self.path.pop();
+    }
+
+    fn visit_change(&mut self, change: DeltaChange<'_>) {
+        match change {
+            DeltaChange::Value { old, new } => {
+                self.push_line(format_args!("{:?} -> {:?}", old, new))
+            }
+            DeltaChange::Variant { old, new } => self.push_line(format_args!("{} -> {}", old, new)),
+            DeltaChange::Replaced => self.push_line(format_args!("replaced")),
+            DeltaChange::Inserted => self.push_line(format_args!("inserted")),
+            DeltaChange::Removed => self.push_line(format_args!("removed")),
+        }
+    }
+}
+
+impl Display for DeltaPrinter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.lines.join("\n"))
+    }
+}
+
+/// Formats a path the same way as [`PatchError`](crate::PatchError) paths, with map and set keys
+/// shown in brackets.
+struct Path<'a>(&'a [PrintedSegment]);
+
+impl Display for Path<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (idx, segment) in self.0.iter().enumerate() {
+            match segment {
+                PrintedSegment::Segment(PathSegment::Field(name))
+                | PrintedSegment::Segment(PathSegment::Variant(name)) => {
+                    if idx > 0 {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(name)?;
+                }
+                PrintedSegment::Segment(PathSegment::TupleField(field)) => {
+                    if idx > 0 {
+                        f.write_str(".")?;
+                    }
+                    write!(f, "{}", field)?;
+                }
+                PrintedSegment::Segment(PathSegment::Index(index)) => write!(f, "[{}]", index)?,
+                PrintedSegment::Segment(PathSegment::MapValue) => f.write_str("[..]")?,
+                PrintedSegment::Key(key) => write!(f, "[{}]", key)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) use self::test_utils::test_print;
+
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+
+    /// Print the delta from `start` to `end` and verify that we get the `expected` lines.
+    pub(crate) fn test_print<T>(start: T, end: T, expected: &[&str])
+    where
+        T: for<'s, 'e> VisitDelta<'s, 'e>,
+    {
+        let delta = start.create_delta_towards(&end).delta;
+
+        assert_eq!(DeltaPrinter::print(&start, &delta).lines(), expected);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that paths are rendered with fields separated by dots and indices and keys in
+    /// brackets.
+    #[test]
+    fn print_nested_path() {
+        let mut printer = DeltaPrinter::new();
+
+        printer.enter(PathSegment::Field("players"));
+        printer.enter_key(&"alice");
+        printer.enter(PathSegment::Field("inventory"));
+        printer.enter(PathSegment::Index(2));
+        printer.visit_change(DeltaChange::Inserted);
+        printer.exit();
+        printer.exit();
+        printer.enter(PathSegment::TupleField(0));
+        printer.visit_change(DeltaChange::Value {
+            old: &1u8,
+            new: &2u8,
+        });
+        printer.exit();
+        printer.exit();
+        printer.exit();
+
+        printer.visit_change(DeltaChange::Variant {
+            old: "Idle",
+            new: "Running",
+        });
+
+        assert_eq!(
+            printer.to_string(),
+            r#"players["alice"].inventory[2]: inserted
+players["alice"].0: 1 -> 2
+Idle -> Running"#
+        );
+    }
+}
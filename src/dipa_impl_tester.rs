@@ -1,7 +1,7 @@
 use super::Diffable;
 use std::fmt::Debug;
 
-use crate::{CreatedDelta, Patchable};
+use crate::{CreatedDelta, Patchable, ReversiblePatchable};
 use bincode::Options;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -36,10 +36,37 @@ where
     /// Verify that we can diff/patch from our start to our end as well as
     /// from our end to our start
     pub fn test(self) {
+        let patch = self.create_patch();
+        self.start.apply_patch(patch).unwrap();
+
+        assert_eq!(self.start, self.end, "{:?}", self.label);
+    }
+
+    /// Run the same checks as [`DipaImplTester.test`], then verify that the inverse returned when
+    /// applying the patch brings us back to our start.
+    pub fn test_reversible(self)
+    where
+        T: ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned> + Clone,
+    {
+        let original_start = self.start.clone();
+
+        let patch = self.create_patch();
+        let inverse = self.start.apply_patch_returning_inverse(patch).unwrap();
+
+        assert_eq!(self.start, self.end, "{:?}", self.label);
+
+        self.start.apply_patch(inverse).unwrap();
+
+        assert_eq!(*self.start, original_start, "{:?}", self.label);
+    }
+
+    /// Diff our start and end, verify the delta and its serialized size, and then deserialize it
+    /// into a patch.
+    fn create_patch(&self) -> <T as Diffable<'s, 'e, T>>::DeltaOwned {
         // SAFETY: Using this to get around lifetime requirements. There may be a better approach
         // that does not require unsafe code. We aren't returning any borrowed data so this should
         // be safe.
-        let start = unsafe { &mut *(self.start as *mut T) };
+        let start = unsafe { &*(self.start as *const T) };
 
         let CreatedDelta { delta, did_change } = start.create_delta_towards(self.end);
 
@@ -58,14 +85,6 @@ Test Label {:?}
             .serialize(&delta)
             .unwrap();
 
-        let patch: <T as Diffable<'s, 'e, T>>::DeltaOwned = bincode::options()
-            .with_varint_encoding()
-            .deserialize(&delta_bytes[..])
-            .unwrap();
-        self.start.apply_patch(patch);
-
-        assert_eq!(self.start, self.end, "{:?}", self.label);
-
         assert_eq!(
             delta_bytes.len(),
             self.expected_serialized_patch_size,
@@ -78,5 +97,10 @@ Test Label: {:?}
             delta_bytes.len(),
             self.label
         );
+
+        bincode::options()
+            .with_varint_encoding()
+            .deserialize(&delta_bytes[..])
+            .unwrap()
     }
 }
@@ -0,0 +1,378 @@
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaChange, DeltaVisitor, Diffable, MergeConflictReason,
+    MergeConflicts, MergeablePatches, PatchError, Patchable, ReversiblePatchable,
+    SquashablePatches, ToOwnedDelta, VisitDelta, WithinEpsilon,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+use std::fmt::{Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// A value that remembers the last value of it that was sent, so that a `#[dipa(epsilon = ...)]`
+/// field notices slow drift even when deltas are created from the previous tick's state.
+///
+/// A plain epsilon field is compared against the start state. When the start state is the
+/// previous tick's state instead of the receiver's state, a value that moves by less than epsilon
+/// every tick is never sent. An `EpsilonTracked` field is compared against the last value that was
+/// sent instead, and the end state records which value that is for the next delta.
+///
+/// ```ignore
+/// #[derive(DiffPatch)]
+/// struct Body {
+///     #[dipa(epsilon = 0.01)]
+///     position: EpsilonTracked<f32>,
+///     sleeping: bool,
+/// }
+/// ```
+///
+/// This is what the generated code checks for the field:
+///
+/// ```
+/// # use dipa::{EpsilonTracked, WithinEpsilon};
+/// let mut previous = EpsilonTracked::new(0f32);
+///
+/// let mut moved = vec![];
+/// for tick in 1..=3 {
+///     let current = EpsilonTracked::new(0.004 * tick as f32);
+///     moved.push(previous.moved_beyond_epsilon(&current, 0.01));
+///     previous = current;
+/// }
+///
+/// assert_eq!(moved, vec![false, false, true]);
+/// ```
+///
+/// # Side effects
+///
+/// The last sent value is recorded on the end state while diffing, through a `Cell`, so creating a
+/// delta assumes that the delta gets sent. Creating a delta that is not sent, such as for a dry run,
+/// for printing with a [`DeltaPrinter`](crate::DeltaPrinter) or for a retry of a delta that was
+/// already created, moves the end state's baseline forwards anyway. The next delta is then
+/// compared against a value that the receiver never got, and the receiver drifts.
+///
+/// Every state should be diffed exactly once against the state before it, and that delta should be
+/// sent. To create a delta without recording it, diff towards a clone of the end state, since the
+/// clone gets its own copy of the last sent value. When sending to several receivers that each
+/// have their own baseline, use a plain field and diff from each receiver's state instead.
+///
+/// ```
+/// # use dipa::{Diffable, EpsilonTracked};
+/// let previous = EpsilonTracked::new(1u32);
+/// let mut current = previous.clone();
+/// *current = 2;
+///
+/// // Diffing a clone records the sent value on the clone and leaves `current` untouched.
+/// let preview = current.clone();
+/// assert!(previous.create_delta_towards(&preview).did_change);
+/// assert_eq!(preview.sent(), 2);
+/// assert_eq!(current.sent(), 1);
+///
+/// // Diffing `current` records that 2 was sent.
+/// previous.create_delta_towards(&current);
+/// assert_eq!(current.sent(), 2);
+/// ```
+///
+/// Only the value is compared for equality and serialized. A deserialized or patched value counts
+/// as sent.
+pub struct EpsilonTracked<T: Copy> {
+    value: T,
+    sent: Cell<T>,
+}
+
+impl<T: Copy> EpsilonTracked<T> {
+    /// Wrap a value that has not been sent yet.
+    pub fn new(value: T) -> Self {
+        EpsilonTracked {
+            value,
+            sent: Cell::new(value),
+        }
+    }
+
+    /// The last value that was sent, which is what the receiver has.
+    pub fn sent(&self) -> T {
+        self.sent.get()
+    }
+
+    /// Unwrap the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Copy + Default> Default for EpsilonTracked<T> {
+    fn default() -> Self {
+        EpsilonTracked::new(T::default())
+    }
+}
+
+impl<T: Copy> From<T> for EpsilonTracked<T> {
+    fn from(value: T) -> Self {
+        EpsilonTracked::new(value)
+    }
+}
+
+impl<T: Copy> Clone for EpsilonTracked<T> {
+    fn clone(&self) -> Self {
+        EpsilonTracked {
+            value: self.value,
+            sent: self.sent.clone(),
+        }
+    }
+}
+
+impl<T: Copy + Debug> Debug for EpsilonTracked<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: Copy> Deref for EpsilonTracked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Copy> DerefMut for EpsilonTracked<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: Copy + PartialEq> PartialEq for EpsilonTracked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Copy + Serialize> Serialize for EpsilonTracked<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Copy + Deserialize<'de>> Deserialize<'de> for EpsilonTracked<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(EpsilonTracked::new)
+    }
+}
+
+impl<T: Copy + WithinEpsilon> WithinEpsilon for EpsilonTracked<T> {
+    fn within_epsilon(&self, other: &Self, epsilon: f64) -> bool {
+        self.value.within_epsilon(&other.value, epsilon)
+    }
+
+    // Compared against the value that the receiver has, and the end state remembers that value
+    // for the delta that gets created from it. See the type's docs for why this side effect means
+    // that every delta that gets created should be sent.
+    fn moved_beyond_epsilon(&self, end_state: &Self, epsilon: f64) -> bool {
+        let sent = self.sent.get();
+        let moved = !sent.within_epsilon(&end_state.value, epsilon);
+
+        end_state
+            .sent
+            .set(if moved { end_state.value } else { sent });
+
+        moved
+    }
+}
+
+// The delta is the whole value since the receiver has the last sent value, which can differ from
+// the start state's value.
+//
+// Diffing records the value that will be sent on the end state, so a delta that is created but not
+// sent still moves the end state's baseline forwards.
+impl<'s, 'e, T: Copy + PartialEq + 'e> Diffable<'s, 'e, EpsilonTracked<T>> for EpsilonTracked<T> {
+    type Delta = Option<&'e T>;
+    type DeltaOwned = Option<T>;
+
+    fn create_delta_towards(
+        &'s self,
+        end_state: &'e EpsilonTracked<T>,
+    ) -> CreatedDelta<Self::Delta> {
+        let sent = self.sent.get();
+        let did_change = sent != end_state.value;
+
+        end_state
+            .sent
+            .set(if did_change { end_state.value } else { sent });
+
+        CreatedDelta {
+            delta: if did_change {
+                Some(&end_state.value)
+            } else {
+                None
+            },
+            did_change,
+        }
+    }
+}
+
+impl<T: Copy> Patchable<Option<T>> for EpsilonTracked<T> {
+    fn apply_patch(&mut self, patch: Option<T>) -> Result<(), PatchError> {
+        if let Some(value) = patch {
+            *self = EpsilonTracked::new(value);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Copy> ReversiblePatchable<Option<T>> for EpsilonTracked<T> {
+    fn apply_patch_returning_inverse(&mut self, patch: Option<T>) -> Result<Option<T>, PatchError> {
+        Ok(patch.map(|value| std::mem::replace(self, EpsilonTracked::new(value)).value))
+    }
+}
+
+impl<T: Copy> SquashablePatches<Option<T>> for EpsilonTracked<T> {
+    fn squash_patches(first: Option<T>, second: Option<T>) -> Result<Option<T>, PatchError> {
+        Ok(second.or(first))
+    }
+}
+
+impl<T: Copy + PartialEq> MergeablePatches<Option<T>> for EpsilonTracked<T> {
+    fn merge_patches(
+        _base: &Self,
+        a: Option<T>,
+        b: Option<T>,
+    ) -> Result<Option<T>, MergeConflicts> {
+        match (a, b) {
+            (Some(a), Some(b)) if a != b => {
+                Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange))
+            }
+            (a, b) => Ok(a.or(b)),
+        }
+    }
+}
+
+impl<T: Copy> ToOwnedDelta<Option<T>> for Option<&T> {
+    fn to_owned_delta(self) -> Option<T> {
+        self.copied()
+    }
+}
+
+impl<'de, T: Copy + Deserialize<'de>> BorrowedDelta<'de> for EpsilonTracked<T> {
+    type DeltaBorrowed = Option<T>;
+
+    fn apply_borrowed_patch(&mut self, patch: Option<T>) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+impl<'s, 'e, T: Copy + PartialEq + Debug + 'e> VisitDelta<'s, 'e> for EpsilonTracked<T> {
+    fn visit_delta(&self, delta: &Option<&'e T>, visitor: &mut dyn DeltaVisitor) {
+        if let Some(new) = delta {
+            visitor.visit_change(DeltaChange::Value {
+                old: &self.value,
+                new,
+            });
+        }
+    }
+}
+
+impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for EpsilonTracked<T>
+where
+    T: Copy + PartialEq + 'e + crate::SchemaFingerprint<'s, 'e>,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("EpsilonTracked")
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+// An `EpsilonTracked` serializes as its value, so the new value replaces it.
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T> crate::ToJsonPatch<'s, 'e> for EpsilonTracked<T>
+where
+    T: Copy + PartialEq + Serialize + 'e,
+{
+    fn push_json_patch(
+        &self,
+        delta: &Option<&'e T>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        match delta {
+            Some(new) => patch.replace(path, new),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DipaImplTester;
+
+    /// Verify that the delta is created against the last sent value rather than the start state's
+    /// value.
+    #[test]
+    fn epsilon_tracked_dipa() {
+        DipaImplTester {
+            label: Some("Changed"),
+            start: &mut EpsilonTracked::new(1.5f32),
+            end: &EpsilonTracked::new(2.),
+            expected_delta: Some(&2.),
+            expected_serialized_patch_size: 5,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        let start = EpsilonTracked::new(1.5f32);
+        let end = EpsilonTracked::new(2.);
+        start.moved_beyond_epsilon(&end, 1.);
+        let back = EpsilonTracked::new(1.5f32);
+
+        let diff = end.create_delta_towards(&back);
+
+        assert_eq!(diff.delta, None);
+        assert!(!diff.did_change);
+    }
+
+    /// Verify that steps that are each within epsilon are sent once they add up to more than
+    /// epsilon, and that the end state remembers what was sent.
+    #[test]
+    fn small_steps_drift_past_epsilon() {
+        let mut previous = EpsilonTracked::new([0f32, 0.]);
+
+        let mut moved = vec![];
+        for tick in 1..=7 {
+            let current = EpsilonTracked::new([0.003 * tick as f32, 0.]);
+            moved.push(previous.moved_beyond_epsilon(&current, 0.01));
+            previous = current;
+        }
+
+        assert_eq!(moved, vec![false, false, false, true, false, false, false]);
+        assert_eq!(previous.sent(), [0.003 * 4., 0.]);
+    }
+
+    /// Verify that a patched or deserialized value counts as sent and that only the value is
+    /// serialized.
+    #[test]
+    fn received_values_count_as_sent() {
+        let mut value = EpsilonTracked::new(1u16);
+        value.apply_patch(Some(5)).unwrap();
+        assert_eq!(value.sent(), 5);
+
+        *value = 6;
+        assert_eq!(value.sent(), 5);
+
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bytes, bincode::serialize(&6u16).unwrap());
+
+        let value: EpsilonTracked<u16> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(value.sent(), 6);
+    }
+
+    /// Verify that two patches that set different values conflict.
+    #[test]
+    fn concurrent_changes_conflict() {
+        let base = EpsilonTracked::new(1u16);
+
+        assert_eq!(
+            EpsilonTracked::merge_patches(&base, Some(2), None).unwrap(),
+            Some(2)
+        );
+        assert!(EpsilonTracked::merge_patches(&base, Some(2), Some(3)).is_err());
+    }
+}
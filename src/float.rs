@@ -1,4 +1,3 @@
-use crate::CreatedDelta;
 use crate::{number_diff_impl_option_wrapped, number_patch_impl_option_wrapped};
 
 number_diff_impl_option_wrapped!(f32, f32);
@@ -9,9 +8,8 @@ number_patch_impl_option_wrapped!(f64, Option<f64>);
 
 #[cfg(test)]
 mod tests {
-    use super::*;
     use crate::dipa_impl_tester::DipaImplTester;
-    use crate::{Diffable, Patchable};
+    use crate::{CreatedDelta, Diffable, PatchError, Patchable};
 
     /// We wrap f32 so that we can impl Eq and PartialEq
     #[derive(Debug, Deserialize, Serialize, Copy, Clone)]
@@ -30,7 +28,7 @@ mod tests {
     }
 
     impl Patchable<Option<f32>> for F32TestWrapper {
-        fn apply_patch(&mut self, patch: Option<f32>) {
+        fn apply_patch(&mut self, patch: Option<f32>) -> Result<(), PatchError> {
             self.0.apply_patch(patch)
         }
     }
@@ -45,7 +43,7 @@ mod tests {
     }
 
     impl Patchable<Option<f64>> for F64TestWrapper {
-        fn apply_patch(&mut self, patch: Option<f64>) {
+        fn apply_patch(&mut self, patch: Option<f64>) -> Result<(), PatchError> {
             self.0.apply_patch(patch)
         }
     }
@@ -0,0 +1,426 @@
+use std::ops::{Deref, DerefMut};
+
+/// An integer that is delta encoded as the difference between its start and end values instead of
+/// as its end value.
+///
+/// Integers are normally sent in full whenever they change, so a `u64` counter that goes from
+/// `1_000_000_000` to `1_000_000_001` costs 6 bytes under varint encoding. An `Incremental<u64>`
+/// sends the difference of `1` instead, which takes 1 byte.
+///
+/// The difference is computed with wrapping arithmetic and zigzag encoded, so small moves in either
+/// direction stay small no matter how large the number is. Values that jump around a lot are better
+/// off as plain integers, since a large difference can take a byte more than the new value would.
+///
+/// ```
+/// # use dipa::{Diffable, Incremental, Patchable};
+/// let mut start = Incremental(1_000_000_000u64);
+/// let end = Incremental(1_000_000_001u64);
+///
+/// let delta = start.create_delta_towards(&end).delta;
+/// assert_eq!(delta, Some(2));
+///
+/// start.apply_patch(delta).unwrap();
+/// assert_eq!(start, end);
+/// ```
+///
+/// Concurrent patches to an `Incremental` are merged by applying both differences, so two clients
+/// that each increment a counter by one end up incrementing it by two.
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Incremental<T>(pub T);
+
+impl<T> Deref for Incremental<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Incremental<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Incremental<T> {
+    fn from(value: T) -> Self {
+        Incremental(value)
+    }
+}
+
+/// Implement the dipa traits for an `Incremental` integer.
+///
+/// `$same_width` is the signed integer with the same width as the integer. `$signed` and `$unsigned`
+/// are the integers that the difference is encoded with, which are wider than the integer for
+/// `usize` and `isize` since serde encodes those as 64-bit integers.
+///
+/// The delta is the zigzag encoded difference as an `Option<$unsigned>`, where `None` means that
+/// the value did not change.
+macro_rules! incremental_impl {
+    ($num_ty:ty, $same_width:ty, $signed:ty, $unsigned:ty) => {
+        impl Incremental<$num_ty> {
+            /// The zigzag encoded difference from `self` to `end`.
+            fn difference_towards(&self, end: $num_ty) -> $unsigned {
+                let difference = end.wrapping_sub(self.0) as $same_width as $signed;
+
+                ((difference << 1) ^ (difference >> (<$signed>::BITS - 1))) as $unsigned
+            }
+
+            /// The value after adding a zigzag encoded difference.
+            fn with_difference(&self, difference: $unsigned) -> $num_ty {
+                self.0
+                    .wrapping_add(Self::decode_difference(difference) as $num_ty)
+            }
+
+            fn decode_difference(difference: $unsigned) -> $signed {
+                ((difference >> 1) as $signed) ^ -((difference & 1) as $signed)
+            }
+
+            /// The difference after applying both differences, or `None` if they cancel out.
+            fn sum_differences(a: Option<$unsigned>, b: Option<$unsigned>) -> Option<$unsigned> {
+                match (a, b) {
+                    (Some(a), Some(b)) => {
+                        let sum =
+                            Self::decode_difference(a).wrapping_add(Self::decode_difference(b));
+                        let sum = ((sum << 1) ^ (sum >> (<$signed>::BITS - 1))) as $unsigned;
+
+                        Some(sum).filter(|sum| *sum != 0)
+                    }
+                    (a, b) => a.or(b),
+                }
+            }
+        }
+
+        impl<'s, 'e> crate::Diffable<'s, 'e, Incremental<$num_ty>> for Incremental<$num_ty> {
+            type Delta = Option<$unsigned>;
+
+            type DeltaOwned = Option<$unsigned>;
+
+            fn create_delta_towards(
+                &self,
+                end_state: &Incremental<$num_ty>,
+            ) -> crate::CreatedDelta<Self::Delta> {
+                let did_change = self != end_state;
+
+                let delta = match did_change {
+                    true => Some(self.difference_towards(end_state.0)),
+                    false => None,
+                };
+
+                crate::CreatedDelta { delta, did_change }
+            }
+        }
+
+        impl crate::Patchable<Option<$unsigned>> for Incremental<$num_ty> {
+            fn apply_patch(&mut self, patch: Option<$unsigned>) -> Result<(), crate::PatchError> {
+                if let Some(difference) = patch {
+                    self.0 = self.with_difference(difference);
+                }
+
+                Ok(())
+            }
+        }
+
+        impl crate::ReversiblePatchable<Option<$unsigned>> for Incremental<$num_ty> {
+            fn apply_patch_returning_inverse(
+                &mut self,
+                patch: Option<$unsigned>,
+            ) -> Result<Option<$unsigned>, crate::PatchError> {
+                Ok(patch.map(|difference| {
+                    let end = self.with_difference(difference);
+                    let inverse = Incremental(end).difference_towards(self.0);
+                    self.0 = end;
+
+                    inverse
+                }))
+            }
+        }
+
+        // Both differences are applied one after the other, so the squashed difference is their
+        // sum.
+        impl crate::SquashablePatches<Option<$unsigned>> for Incremental<$num_ty> {
+            fn squash_patches(
+                first: Option<$unsigned>,
+                second: Option<$unsigned>,
+            ) -> Result<Option<$unsigned>, crate::PatchError> {
+                Ok(Self::sum_differences(first, second))
+            }
+        }
+
+        // Concurrent differences are both applied, such as two clients that each increment a
+        // counter.
+        impl crate::MergeablePatches<Option<$unsigned>> for Incremental<$num_ty> {
+            fn merge_patches(
+                _base: &Self,
+                a: Option<$unsigned>,
+                b: Option<$unsigned>,
+            ) -> Result<Option<$unsigned>, crate::MergeConflicts> {
+                Ok(Self::sum_differences(a, b))
+            }
+        }
+
+        impl<'de> crate::BorrowedDelta<'de> for Incremental<$num_ty> {
+            type DeltaBorrowed = Option<$unsigned>;
+
+            fn apply_borrowed_patch(
+                &mut self,
+                patch: Option<$unsigned>,
+            ) -> Result<(), crate::PatchError> {
+                crate::Patchable::apply_patch(self, patch)
+            }
+        }
+
+        impl<'s, 'e> crate::VisitDelta<'s, 'e> for Incremental<$num_ty> {
+            fn visit_delta(
+                &self,
+                delta: &Option<$unsigned>,
+                visitor: &mut dyn crate::DeltaVisitor,
+            ) {
+                if let Some(difference) = delta {
+                    let new = self.with_difference(*difference);
+                    visitor.visit_change(crate::DeltaChange::Value {
+                        old: &self.0,
+                        new: &new,
+                    });
+                }
+            }
+        }
+
+        impl<'s, 'e> crate::SchemaFingerprint<'s, 'e> for Incremental<$num_ty> {
+            const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+                .write_str("Incremental")
+                .write_u64(<$num_ty as crate::SchemaFingerprint>::FINGERPRINT)
+                .finish();
+        }
+
+        // An `Incremental` serializes as its integer, so the JSON Patch replaces the integer with
+        // its new value.
+        #[cfg(feature = "json-patch")]
+        impl<'s, 'e> crate::ToJsonPatch<'s, 'e> for Incremental<$num_ty> {
+            fn push_json_patch(
+                &self,
+                delta: &Option<$unsigned>,
+                path: &crate::JsonPointer,
+                patch: &mut crate::JsonPatch,
+            ) -> Result<(), crate::JsonPatchError> {
+                match delta {
+                    Some(difference) => patch.replace(path, &self.with_difference(*difference)),
+                    None => Ok(()),
+                }
+            }
+        }
+    };
+}
+
+incremental_impl!(u16, i16, i16, u16);
+incremental_impl!(i16, i16, i16, u16);
+incremental_impl!(u32, i32, i32, u32);
+incremental_impl!(i32, i32, i32, u32);
+incremental_impl!(u64, i64, i64, u64);
+incremental_impl!(i64, i64, i64, u64);
+incremental_impl!(u128, i128, i128, u128);
+incremental_impl!(i128, i128, i128, u128);
+
+// The difference is sign extended to 64 bits, so a delta created on a 64-bit target can be applied
+// on a 32-bit one (such as wasm32) and the other way around.
+incremental_impl!(usize, isize, i64, u64);
+incremental_impl!(isize, isize, i64, u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::{BorrowedDelta, Diffable, DipaImplTester, MergeablePatches, SquashablePatches};
+
+    /// Verify that small changes to large numbers are encoded as small differences.
+    #[test]
+    fn small_differences() {
+        DipaImplTester {
+            label: Some("No change"),
+            start: &mut Incremental(1_000_000_000u64),
+            end: &Incremental(1_000_000_000u64),
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Increment"),
+            start: &mut Incremental(1_000_000_000u64),
+            end: &Incremental(1_000_000_001u64),
+            expected_delta: Some(2),
+            // 1 for the Option and 1 for the difference
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Decrement"),
+            start: &mut Incremental(1_000_000_000u64),
+            end: &Incremental(999_999_990u64),
+            expected_delta: Some(19),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Signed"),
+            start: &mut Incremental(-5_000_000_000i64),
+            end: &Incremental(-5_000_000_003i64),
+            expected_delta: Some(5),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that differences wrap around the ends of the integer's range.
+    #[test]
+    fn wrapping_differences() {
+        DipaImplTester {
+            label: Some("Wrap past max"),
+            start: &mut Incremental(u16::MAX),
+            end: &Incremental(1u16),
+            expected_delta: Some(4),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Min to max"),
+            start: &mut Incremental(i32::MIN),
+            end: &Incremental(i32::MAX),
+            expected_delta: Some(1),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Largest difference"),
+            start: &mut Incremental(0u128),
+            end: &Incremental(u128::MAX / 2 + 1),
+            expected_delta: Some(u128::MAX),
+            // 1 for the Option, 1 for the varint marker and 16 for the difference
+            expected_serialized_patch_size: 18,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a usize difference is sign extended, so that decreasing it stays small.
+    #[test]
+    fn usize_differences() {
+        DipaImplTester {
+            label: Some("Decrease usize"),
+            start: &mut Incremental(5usize),
+            end: &Incremental(3usize),
+            expected_delta: Some(3),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that concurrent differences are both applied.
+    #[test]
+    fn merge_adds_differences() {
+        test_merge(
+            Incremental(10u32),
+            Incremental(11),
+            Incremental(12),
+            Incremental(13),
+        );
+        test_merge(
+            Incremental(10i64),
+            Incremental(15),
+            Incremental(5),
+            Incremental(10),
+        );
+        test_merge(
+            Incremental(10u16),
+            Incremental(10),
+            Incremental(12),
+            Incremental(12),
+        );
+
+        assert_eq!(
+            Incremental::<u64>::merge_patches(&Incremental(0), Some(2), Some(1)),
+            Ok(None)
+        );
+    }
+
+    /// Verify that squashed differences wrap around the ends of the integer's range and that
+    /// differences that cancel out squash into no change.
+    #[test]
+    fn squash_adds_differences() {
+        test_squash(
+            Incremental(u16::MAX - 1),
+            Incremental(1),
+            Incremental(u16::MAX),
+        );
+        test_squash(
+            Incremental(i8::MAX as i16),
+            Incremental(i16::MIN),
+            Incremental(0),
+        );
+
+        assert_eq!(
+            Incremental::<u64>::squash_patches(Some(4), Some(3)),
+            Ok(None)
+        );
+    }
+
+    /// Verify that a usize patch that was serialized as a 64-bit difference, such as by a 64-bit
+    /// server, can be applied from a borrowed patch.
+    #[test]
+    fn usize_borrowed_patch() {
+        let bytes = bincode::serialize(&Some(3u64)).unwrap();
+
+        let mut value = Incremental(5usize);
+        value
+            .apply_borrowed_patch(bincode::deserialize(&bytes).unwrap())
+            .unwrap();
+        assert_eq!(value, Incremental(3));
+    }
+
+    /// Verify that a change is described and exported as the new value rather than the
+    /// difference.
+    #[test]
+    fn changes_are_new_values() {
+        test_print(Incremental(u16::MAX), Incremental(1u16), &["65535 -> 1"]);
+
+        #[cfg(feature = "json-patch")]
+        {
+            use crate::ToJsonPatch;
+
+            let start = Incremental(1_000_000_000u64);
+            let delta = start.create_delta_towards(&Incremental(999_999_999)).delta;
+            assert_eq!(
+                serde_json::to_string(&start.to_json_patch(&delta).unwrap()).unwrap(),
+                r#"[{"op":"replace","path":"","value":999999999}]"#
+            );
+        }
+    }
+
+    /// Verify that an incremental integer has a different fingerprint than the integer, since its
+    /// delta is encoded differently.
+    #[test]
+    fn incremental_fingerprint() {
+        use crate::SchemaFingerprint;
+
+        assert_ne!(
+            <Incremental<u64> as SchemaFingerprint>::FINGERPRINT,
+            <u64 as SchemaFingerprint>::FINGERPRINT
+        );
+    }
+}
@@ -1,11 +1,14 @@
-use crate::CreatedDelta;
 use crate::{
     number_diff_impl_option_wrapped, number_diff_impl_u8_or_i8, number_patch_impl_mut_u8_or_i8,
     number_patch_impl_option_wrapped, number_patch_impl_u8_or_i8,
 };
 
 number_diff_impl_u8_or_i8!(u8, u8);
-number_patch_impl_u8_or_i8!(u8, u8);
+number_patch_impl_u8_or_i8!(
+    u8,
+    u8,
+    deserialize_sequence_items = crate::borrowed_delta::deserialize_byte_items
+);
 number_patch_impl_mut_u8_or_i8!(&mut u8, u8);
 
 number_diff_impl_u8_or_i8!(i8, i8);
@@ -32,6 +35,13 @@ number_patch_impl_option_wrapped!(u128, Option<u128>);
 number_diff_impl_option_wrapped!(i128, i128);
 number_patch_impl_option_wrapped!(i128, Option<i128>);
 
+// serde always encodes usize as a u64 and isize as an i64, so a delta created on a 64-bit target
+// can be applied on a 32-bit one (such as wasm32) as long as the new value fits.
+number_diff_impl_option_wrapped!(usize, usize);
+number_patch_impl_option_wrapped!(usize, Option<usize>);
+number_diff_impl_option_wrapped!(isize, isize);
+number_patch_impl_option_wrapped!(isize, Option<isize>);
+
 #[cfg(test)]
 mod tests_signed {
 
@@ -166,6 +176,46 @@ mod tests_signed {
         }
         .test();
     }
+
+    #[test]
+    fn diff_patch_usize_same() {
+        DipaImplTester {
+            label: Some("Diff patch same usize"),
+            start: &mut 0usize,
+            end: &0usize,
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test();
+    }
+
+    #[test]
+    fn diff_patch_usize_different() {
+        DipaImplTester {
+            label: Some("Diff patch different usizes"),
+            start: &mut 0usize,
+            end: &1usize,
+            expected_delta: Some(1),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test();
+    }
+
+    /// Verify that a usize delta is encoded the same way as a u64 delta, so that targets with
+    /// different pointer widths can exchange deltas.
+    #[test]
+    fn usize_encoded_as_u64() {
+        use bincode::Options;
+
+        for value in [0usize, 250, 251, 70_000, u32::MAX as usize] {
+            assert_eq!(
+                bincode::options().serialize(&Some(value)).unwrap(),
+                bincode::options().serialize(&Some(value as u64)).unwrap(),
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -302,4 +352,73 @@ mod tests_unsigned {
         }
         .test();
     }
+
+    #[test]
+    fn diff_patch_isize_same() {
+        DipaImplTester {
+            label: Some("Diff patch same isize"),
+            start: &mut 0isize,
+            end: &0isize,
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test();
+    }
+
+    #[test]
+    fn diff_patch_isize_different() {
+        DipaImplTester {
+            label: Some("Diff patch different isizes"),
+            start: &mut 0isize,
+            end: &-1isize,
+            expected_delta: Some(-1),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test();
+    }
+
+    /// Verify that an isize delta is encoded the same way as an i64 delta, so that targets with
+    /// different pointer widths can exchange deltas.
+    #[test]
+    fn isize_encoded_as_i64() {
+        use bincode::Options;
+
+        for value in [0isize, -1, 125, -70_000, i32::MIN as isize] {
+            assert_eq!(
+                bincode::options().serialize(&Some(value)).unwrap(),
+                bincode::options().serialize(&Some(value as i64)).unwrap(),
+            );
+        }
+    }
+
+    /// Verify that we can squash patches for numbers that use the Option wrapped delta.
+    #[test]
+    fn squash_option_wrapped_patches() {
+        crate::squash_patches::test_squash(1u32, 2, 3);
+        crate::squash_patches::test_squash(1u32, 2, 2);
+        crate::squash_patches::test_squash(1u32, 1, 2);
+        crate::squash_patches::test_squash(1i64, -5, 1);
+        crate::squash_patches::test_squash(1usize, 2, 3);
+        crate::squash_patches::test_squash(1isize, -5, 1);
+    }
+
+    /// Verify that we can merge number patches unless they set different values.
+    #[test]
+    fn merge_number_patches() {
+        use crate::{MergeConflictReason, MergeablePatches};
+
+        crate::merge_patches::test_merge(1u32, 2, 1, 2);
+        crate::merge_patches::test_merge(1u32, 2, 2, 2);
+        crate::merge_patches::test_merge(1u8, 1, 3, 3);
+        crate::merge_patches::test_merge(1u8, 3, 3, 3);
+
+        let conflicts = u8::merge_patches(&1, 2, 3).unwrap_err();
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::ConcurrentChange
+        );
+        assert!(u32::merge_patches(&1, Some(2), Some(3)).is_err());
+    }
 }
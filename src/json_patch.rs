@@ -0,0 +1,289 @@
+use crate::Diffable;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+
+/// Converts a delta into an [RFC 6902](https://tools.ietf.org/html/rfc6902) JSON Patch document.
+///
+/// Applying the JSON Patch to the `serde_json` form of the start value gives the `serde_json` form
+/// of the end value, so a delta can be forwarded to clients that speak JSON Patch without keeping
+/// a second diff around.
+///
+/// Paths use serde's default names. Fields renamed with `#[serde(rename = "...")]` and enums that
+/// use a non default serde representation are not reflected in the paths.
+///
+/// Like [`VisitDelta`](crate::VisitDelta) this is implemented for the start value, since a delta on
+/// its own does not know where in the JSON document its values live.
+///
+/// ```
+/// # use dipa::{Diffable, ToJsonPatch};
+/// let start = vec![1u32, 2, 3];
+/// let end = vec![1, 2, 3, 4];
+///
+/// let delta = start.create_delta_towards(&end).delta;
+/// let patch = start.to_json_patch(&delta).unwrap();
+///
+/// assert_eq!(
+///     serde_json::to_string(&patch).unwrap(),
+///     r#"[{"op":"add","path":"/3","value":4}]"#
+/// );
+/// ```
+pub trait ToJsonPatch<'s, 'e>: Diffable<'s, 'e, Self> {
+    /// Push the operations for the changes that `delta` makes to `self`, where `self` lives at
+    /// `path` within the JSON document.
+    fn push_json_patch(
+        &self,
+        delta: &Self::Delta,
+        path: &JsonPointer,
+        patch: &mut JsonPatch,
+    ) -> Result<(), JsonPatchError>;
+
+    /// Convert a delta that was created from `self` into a JSON Patch document.
+    fn to_json_patch(&self, delta: &Self::Delta) -> Result<JsonPatch, JsonPatchError> {
+        let mut patch = JsonPatch::default();
+        self.push_json_patch(delta, &JsonPointer::root(), &mut patch)?;
+
+        Ok(patch)
+    }
+}
+
+/// A JSON Patch document, serialized as an array of operations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonPatch {
+    operations: Vec<JsonPatchOperation>,
+}
+
+/// A single JSON Patch operation.
+///
+/// Deltas only ever add, remove or replace values, so the `move`, `copy` and `test` operations are
+/// never created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum JsonPatchOperation {
+    /// Insert a value into an array or add a member to an object.
+    Add {
+        /// Where to add the value.
+        path: JsonPointer,
+        /// The value to add.
+        value: Value,
+    },
+    /// Remove the value at the path.
+    Remove {
+        /// The value to remove.
+        path: JsonPointer,
+    },
+    /// Replace the value at the path.
+    Replace {
+        /// The value to replace.
+        path: JsonPointer,
+        /// The new value.
+        value: Value,
+    },
+}
+
+impl JsonPatch {
+    /// Add `value` at `path`.
+    pub fn add<T: Serialize + ?Sized>(
+        &mut self,
+        path: &JsonPointer,
+        value: &T,
+    ) -> Result<(), JsonPatchError> {
+        self.operations.push(JsonPatchOperation::Add {
+            path: path.clone(),
+            value: serde_json::to_value(value)?,
+        });
+
+        Ok(())
+    }
+
+    /// Remove the value at `path`.
+    pub fn remove(&mut self, path: &JsonPointer) {
+        self.operations
+            .push(JsonPatchOperation::Remove { path: path.clone() });
+    }
+
+    /// Replace the value at `path` with `value`.
+    pub fn replace<T: Serialize + ?Sized>(
+        &mut self,
+        path: &JsonPointer,
+        value: &T,
+    ) -> Result<(), JsonPatchError> {
+        self.operations.push(JsonPatchOperation::Replace {
+            path: path.clone(),
+            value: serde_json::to_value(value)?,
+        });
+
+        Ok(())
+    }
+
+    /// The operations, in the order that they need to be applied.
+    pub fn operations(&self) -> &[JsonPatchOperation] {
+        &self.operations
+    }
+
+    /// Whether the patch leaves the document unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+impl From<JsonPatch> for Vec<JsonPatchOperation> {
+    fn from(patch: JsonPatch) -> Self {
+        patch.operations
+    }
+}
+
+/// An [RFC 6901](https://tools.ietf.org/html/rfc6901) JSON Pointer, such as `/position/x`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonPointer(String);
+
+impl JsonPointer {
+    /// The pointer to the whole document.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// The pointer to a member or array index of the value that this points to.
+    pub fn child(&self, token: impl Display) -> Self {
+        let token = token.to_string().replace('~', "~0").replace('/', "~1");
+
+        JsonPointer(format!("{}/{}", self.0, token))
+    }
+
+    /// The pointer to the end of the array that this points to, used to append to it.
+    pub fn array_end(&self) -> Self {
+        self.child("-")
+    }
+
+    /// The pointer as text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for JsonPointer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A delta could not be converted into a JSON Patch, either because one of its values could not be
+/// converted into JSON or because it was not created from the value that it was converted against.
+#[derive(Debug)]
+pub struct JsonPatchError(serde_json::Error);
+
+impl JsonPatchError {
+    /// The delta refers to items that the start value does not have, such as when converting a
+    /// delta against a different value than the one that it was created from.
+    pub(crate) fn not_created_from(value: &str) -> Self {
+        JsonPatchError(serde::ser::Error::custom(format!(
+            "the delta was not created from this {}",
+            value
+        )))
+    }
+}
+
+impl From<serde_json::Error> for JsonPatchError {
+    fn from(err: serde_json::Error) -> Self {
+        JsonPatchError(err)
+    }
+}
+
+impl Display for JsonPatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Could not convert the delta into a JSON Patch: {}",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for JsonPatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// The pointer to the value stored under a map key.
+///
+/// `serde_json` writes map keys as object member names, so numeric and boolean keys become text.
+pub(crate) fn map_key_pointer<K: Serialize + ?Sized>(
+    map: &JsonPointer,
+    key: &K,
+) -> Result<JsonPointer, JsonPatchError> {
+    match serde_json::to_value(key)? {
+        Value::String(key) => Ok(map.child(key)),
+        Value::Number(key) => Ok(map.child(key)),
+        Value::Bool(key) => Ok(map.child(key)),
+        _ => Err(JsonPatchError(serde::ser::Error::custom(
+            "map key must be a string or a number",
+        ))),
+    }
+}
+
+#[cfg(test)]
+pub(crate) use self::test_utils::test_json_patch;
+
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use std::fmt::Debug;
+
+    /// Verify that applying the JSON Patch for the delta from `start` to `end` to the serde_json
+    /// form of `start` gives us `end`.
+    pub(crate) fn test_json_patch<T>(start: T, end: T)
+    where
+        T: for<'s, 'e> ToJsonPatch<'s, 'e> + Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        let delta = start.create_delta_towards(&end).delta;
+        let patch = start.to_json_patch(&delta).unwrap();
+
+        let mut json = serde_json::to_value(&start).unwrap();
+        let rfc_patch: json_patch::Patch =
+            serde_json::from_value(serde_json::to_value(&patch).unwrap()).unwrap();
+        json_patch::patch(&mut json, &rfc_patch).unwrap();
+
+        assert_eq!(serde_json::from_value::<T>(json).unwrap(), end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that operations are serialized the way that RFC 6902 describes them.
+    #[test]
+    fn serialize_operations() {
+        let mut patch = JsonPatch::default();
+        let list = JsonPointer::root().child("list");
+
+        patch.add(&list.array_end(), &1u8).unwrap();
+        patch.replace(&list.child(0), &"a").unwrap();
+        patch.remove(&list.child(1));
+
+        assert_eq!(
+            serde_json::to_string(&patch).unwrap(),
+            r#"[{"op":"add","path":"/list/-","value":1},{"op":"replace","path":"/list/0","value":"a"},{"op":"remove","path":"/list/1"}]"#
+        );
+    }
+
+    /// Verify that `~` and `/` are escaped within a pointer's reference tokens.
+    #[test]
+    fn escape_pointer_tokens() {
+        assert_eq!(JsonPointer::root().child("a/b~c").as_str(), "/a~1b~0c");
+    }
+
+    /// Verify that map keys that serde_json writes as text can be pointed to.
+    #[test]
+    fn map_key_pointers() {
+        let map = JsonPointer::root();
+
+        assert_eq!(map_key_pointer(&map, "a").unwrap().as_str(), "/a");
+        assert_eq!(map_key_pointer(&map, &5u32).unwrap().as_str(), "/5");
+        assert!(map_key_pointer(&map, &(1u8, 2u8)).is_err());
+    }
+}
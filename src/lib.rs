@@ -29,22 +29,92 @@ pub use dipa_derive::DiffPatch;
 
 mod sequence;
 
+mod array;
 mod bool;
+mod boxed;
+mod cell;
+mod char;
 mod cow;
 mod float;
 mod integer;
 mod map;
+mod net;
+mod non_zero;
 mod null;
 mod option;
+mod path;
+mod range;
+mod result;
 mod set;
+mod shared;
+pub use self::shared::SharedDelta;
 mod string;
+mod time;
 mod tuple;
+mod wrapping;
 
 #[macro_use]
 mod number_impl;
 
 mod delta_n;
 
+mod patch_error;
+pub use self::patch_error::{PatchError, PatchErrorReason, PathSegment};
+
+mod reversible_patch;
+pub use self::reversible_patch::ReversiblePatchable;
+
+mod squash_patches;
+pub use self::squash_patches::SquashablePatches;
+
+mod merge_patches;
+pub use self::merge_patches::{
+    MergeConflict, MergeConflictReason, MergeConflicts, MergeablePatches,
+};
+
+mod to_owned_delta;
+pub use self::to_owned_delta::ToOwnedDelta;
+
+mod borrowed_delta;
+pub use self::borrowed_delta::BorrowedDelta;
+
+mod baseline_store;
+pub use self::baseline_store::{BaselineStore, BaselineUpdate};
+
+mod delta_visitor;
+pub use self::delta_visitor::{DeltaChange, DeltaPrinter, DeltaVisitor, VisitDelta};
+
+mod schema_fingerprint;
+pub use self::schema_fingerprint::{FingerprintHasher, FingerprintMismatch, SchemaFingerprint};
+
+mod incremental;
+pub use self::incremental::Incremental;
+
+mod change_flagged;
+pub use self::change_flagged::{ChangeFlagged, ChangeFlaggedDelta};
+
+mod quantized;
+pub use self::quantized::{Quantized, QuantizedFloat};
+
+mod within_epsilon;
+pub use self::within_epsilon::WithinEpsilon;
+
+mod epsilon_tracked;
+pub use self::epsilon_tracked::EpsilonTracked;
+
+mod packed_bools;
+pub use self::packed_bools::{BoolFlags, PackedBools, PackedBoolsMut};
+
+mod text;
+pub use self::text::{Chars, Text, TextUnit, Utf16CodeUnits, Utf8Bytes};
+
+#[cfg(feature = "json-patch")]
+mod json_patch;
+#[cfg(feature = "json-patch")]
+pub use self::json_patch::{
+    JsonPatch, JsonPatchError, JsonPatchOperation, JsonPointer, ToJsonPatch,
+};
+
 #[cfg(any(test, feature = "impl-tester"))]
 mod dipa_impl_tester;
 #[cfg(any(test, feature = "impl-tester"))]
@@ -69,6 +139,39 @@ pub trait Diffable<'s, 'e, Other: ?Sized> {
     /// Diff self with some target end state, generating a patch that would convert
     ///  self -> end_state.
     fn create_delta_towards(&'s self, end_state: &'e Other) -> CreatedDelta<Self::Delta>;
+
+    /// Diff self with some target end state for a receiver that is running an earlier schema
+    /// version of your types.
+    ///
+    /// Struct fields that were added with a `#[dipa(since = N)]` attribute are left out of the
+    /// delta when `N` is greater than `version`, so an older receiver never sees a delta variant
+    /// that it does not know about. `u32::MAX` includes every field.
+    ///
+    /// Derived types pass the version on to their fields, and the wrappers and containers in this
+    /// crate pass it on to the values that they diff. Values that get sent whole, such as an item
+    /// that is added to a `Vec` or an entry that is added to a map, are serialized with all of
+    /// their fields.
+    ///
+    /// The default implementation ignores the version. Implementations that diff inner values
+    /// should override it and pass the version on.
+    ///
+    /// ```
+    /// # use dipa::Diffable;
+    /// let start = vec![1u8, 2];
+    /// let end = vec![1, 2, 3];
+    ///
+    /// // Any `#[dipa(since = 2)]` struct fields are left out of the delta.
+    /// let delta = start.create_delta_towards_version(&end, 1);
+    /// # assert!(delta.did_change);
+    /// ```
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e Other,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        let _ = version;
+        self.create_delta_towards(end_state)
+    }
 }
 
 /// Modifies a type using n a patch.
@@ -79,9 +182,19 @@ pub trait Diffable<'s, 'e, Other: ?Sized> {
 /// to [`Diffable::DeltaOwned`]. Then you would use that owned delta as the patch to apply via
 /// [`Patchable.apply_patch`].
 ///
-/// FIXME: This should return a result since it is possible to, for example, accidentally apply a
-///  a patch to the wrong data structure do to a logical bug.
+/// Applying a patch can fail if the patch was not created against the current state, such as when
+/// a patch is stale or was applied to the wrong data structure due to a logical bug. When this
+/// happens a [`PatchError`] describing where and why the patch failed is returned.
 pub trait Patchable<P> {
     /// Apply a patch.
-    fn apply_patch(&mut self, patch: P);
+    ///
+    /// Patches are not atomic. If an error is returned, the parts of the patch that came before
+    /// the failure may already have been applied. For example, a `Vec` keeps the modifications
+    /// that came before the one that failed, and a derived struct keeps the fields that were
+    /// patched before the one that failed. `String` and [`Text`] are the exception and are left
+    /// unchanged.
+    ///
+    /// After an error you should treat the value as out of sync and replace it with a full copy of
+    /// the sender's state, or patch a clone and only keep it if patching succeeded.
+    fn apply_patch(&mut self, patch: P) -> Result<(), PatchError>;
 }
@@ -1,14 +1,120 @@
-use crate::Diffable;
+use crate::{
+    BorrowedDelta, DeltaChange, DeltaVisitor, Diffable, PatchError, PatchErrorReason, Patchable,
+    PathSegment, ReversiblePatchable, ToOwnedDelta, VisitDelta,
+};
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use serde::Serialize;
 use std::fmt::{Debug, Formatter};
 
 #[macro_use]
 mod map_impl_macro;
+#[cfg(feature = "json-patch")]
+mod map_json_patch;
+mod map_merge_patches;
+mod map_squash_patches;
 
 map_impl!(std::collections::HashMap<K,V>, hash_map_impl, );
 map_impl!(std::collections::BTreeMap<K,V>, btree_map_impl, + Ord);
 
+/// Patch the value stored under a key, failing if the key is not in the map.
+fn patch_map_value<V: Patchable<P>, P>(value: Option<&mut V>, patch: P) -> Result<(), PatchError> {
+    let result = match value {
+        Some(value) => value.apply_patch(patch),
+        None => Err(PatchError::new(PatchErrorReason::MissingMapKey)),
+    };
+
+    result.map_err(|err| err.with_parent(PathSegment::MapValue))
+}
+
+/// Patch the value stored under a key with a borrowed patch, failing if the key is not in the map.
+fn borrowed_patch_map_value<'b, V: BorrowedDelta<'b>>(
+    value: Option<&mut V>,
+    patch: V::DeltaBorrowed,
+) -> Result<(), PatchError> {
+    let result = match value {
+        Some(value) => value.apply_borrowed_patch(patch),
+        None => Err(PatchError::new(PatchErrorReason::MissingMapKey)),
+    };
+
+    result.map_err(|err| err.with_parent(PathSegment::MapValue))
+}
+
+/// Patch the value stored under a key, returning the patch that undoes the change.
+fn reverse_patch_map_value<V: ReversiblePatchable<P>, P>(
+    value: Option<&mut V>,
+    patch: P,
+) -> Result<P, PatchError> {
+    let result = match value {
+        Some(value) => value.apply_patch_returning_inverse(patch),
+        None => Err(PatchError::new(PatchErrorReason::MissingMapKey)),
+    };
+
+    result.map_err(|err| err.with_parent(PathSegment::MapValue))
+}
+
+/// Describe the changes that a delta makes to a map, using each entry's key as its path.
+fn visit_map_delta<'s, 'e, 'b, K, V>(
+    start_value: impl Fn(&K) -> Option<&'b V>,
+    start_keys: impl Iterator<Item = &'b K>,
+    delta: &MapDelta<'s, 'e, K, V>,
+    visitor: &mut dyn DeltaVisitor,
+) where
+    K: 'b + Debug,
+    V: 'b + VisitDelta<'s, 'e>,
+    <V as Diffable<'s, 'e, V>>::Delta: Serialize,
+{
+    let visit_entry = |visitor: &mut dyn DeltaVisitor, key: &K, change: DeltaChange| {
+        visitor.enter_key(key);
+        visitor.visit_change(change);
+        visitor.exit();
+    };
+    let visit_value = |visitor: &mut dyn DeltaVisitor,
+                       key: &K,
+                       value_delta: &<V as Diffable<'s, 'e, V>>::Delta| {
+        if let Some(value) = start_value(key) {
+            visitor.enter_key(key);
+            value.visit_delta(value_delta, visitor);
+            visitor.exit();
+        }
+    };
+
+    match delta {
+        MapDelta::NoChange => {}
+        MapDelta::RemoveAll => {
+            for key in start_keys {
+                visit_entry(visitor, key, DeltaChange::Removed);
+            }
+        }
+        MapDelta::AddOneField(key, _) => visit_entry(visitor, key, DeltaChange::Inserted),
+        MapDelta::RemoveOneField(key) => visit_entry(visitor, key, DeltaChange::Removed),
+        MapDelta::ChangeOneField(key, value_delta) => visit_value(visitor, key, value_delta),
+        MapDelta::ModifyMany {
+            added,
+            removed,
+            changed,
+        } => {
+            for (key, value_delta) in changed {
+                visit_value(visitor, key, value_delta);
+            }
+            for key in removed {
+                visit_entry(visitor, key, DeltaChange::Removed);
+            }
+            for (key, _) in added {
+                visit_entry(visitor, key, DeltaChange::Inserted);
+            }
+        }
+        MapDelta::ReplaceAll(entries) => {
+            for key in start_keys {
+                visit_entry(visitor, key, DeltaChange::Removed);
+            }
+            for (key, _) in entries {
+                visit_entry(visitor, key, DeltaChange::Inserted);
+            }
+        }
+    }
+}
+
 #[derive(Serialize)]
 /// The delta between two maps.
 pub enum MapDelta<'s, 'e, K, V: Diffable<'s, 'e, V>>
@@ -31,6 +137,11 @@ where
         removed: Vec<&'s K>,
         changed: Vec<(&'s K, <V as Diffable<'s, 'e, V>>::Delta)>,
     },
+    /// Remove all entries and then add new ones.
+    ///
+    /// Not created when diffing. Used when squashing a patch that removes all entries with the
+    /// patches that come after it.
+    ReplaceAll(Vec<(&'e K, &'e V)>),
 }
 
 #[derive(Deserialize)]
@@ -56,6 +167,84 @@ where
         removed: Vec<K>,
         changed: Vec<(K, <V as Diffable<'s, 'e, V>>::DeltaOwned)>,
     },
+    /// Remove all entries and then add new ones.
+    ///
+    /// Not created when diffing. Used when squashing a patch that removes all entries with the
+    /// patches that come after it.
+    ReplaceAll(Vec<(K, V)>),
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "K: Deserialize<'de>, V: Deserialize<'de>, \
+    <V as BorrowedDelta<'b>>::DeltaBorrowed: Deserialize<'de>"))]
+#[allow(missing_docs)]
+/// The delta between two maps, borrowing the changed values' deltas from the buffer that it was
+/// deserialized from.
+///
+/// Serialized the same way as a `MapDeltaOwned`.
+pub enum MapDeltaBorrowed<'b, K, V: BorrowedDelta<'b>> {
+    /// Nothing has changed
+    NoChange,
+    /// Remove all elements from the HashMap
+    RemoveAll,
+    /// Add one field to the HashMap
+    AddOneField(K, V),
+    /// Remove one field to the HashMap
+    RemoveOneField(K),
+    /// Change one field to the HashMap
+    ChangeOneField(K, #[serde(borrow)] <V as BorrowedDelta<'b>>::DeltaBorrowed),
+    /// Modify multiple entries
+    ModifyMany {
+        added: Vec<(K, V)>,
+        removed: Vec<K>,
+        #[serde(borrow)]
+        changed: Vec<(K, <V as BorrowedDelta<'b>>::DeltaBorrowed)>,
+    },
+    /// Remove all entries and then add new ones.
+    ReplaceAll(Vec<(K, V)>),
+}
+
+// See the `OptionDelta` implementation for why `V` is bounded over every pair of lifetimes.
+impl<'s, 'e, 's2, 'e2, K, V> ToOwnedDelta<MapDeltaOwned<'s2, 'e2, K, V>> for MapDelta<'s, 'e, K, V>
+where
+    K: Clone,
+    V: Clone,
+    V: for<'a, 'b> Diffable<'a, 'b, V>,
+    <V as Diffable<'s, 'e, V>>::Delta: Serialize,
+    <V as Diffable<'s, 'e, V>>::Delta: ToOwnedDelta<<V as Diffable<'s2, 'e2, V>>::DeltaOwned>,
+    <V as Diffable<'s2, 'e2, V>>::DeltaOwned: DeserializeOwned,
+{
+    fn to_owned_delta(self) -> MapDeltaOwned<'s2, 'e2, K, V> {
+        match self {
+            MapDelta::NoChange => MapDeltaOwned::NoChange,
+            MapDelta::RemoveAll => MapDeltaOwned::RemoveAll,
+            MapDelta::AddOneField(k, v) => MapDeltaOwned::AddOneField(k.clone(), v.clone()),
+            MapDelta::RemoveOneField(k) => MapDeltaOwned::RemoveOneField(k.clone()),
+            MapDelta::ChangeOneField(k, delta) => {
+                MapDeltaOwned::ChangeOneField(k.clone(), delta.to_owned_delta())
+            }
+            MapDelta::ModifyMany {
+                added,
+                removed,
+                changed,
+            } => MapDeltaOwned::ModifyMany {
+                added: clone_entries(added),
+                removed: removed.into_iter().cloned().collect(),
+                changed: changed
+                    .into_iter()
+                    .map(|(k, delta)| (k.clone(), delta.to_owned_delta()))
+                    .collect(),
+            },
+            MapDelta::ReplaceAll(entries) => MapDeltaOwned::ReplaceAll(clone_entries(entries)),
+        }
+    }
+}
+
+fn clone_entries<K: Clone, V: Clone>(entries: Vec<(&K, &V)>) -> Vec<(K, V)> {
+    entries
+        .into_iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
 }
 
 // Used by DipaImplTester
@@ -98,6 +287,9 @@ where
                     .field("changed", changed)
                     .finish()?;
             }
+            MapDelta::ReplaceAll(entries) => {
+                f.debug_tuple("ReplaceAll").field(entries).finish()?;
+            }
         };
 
         Ok(())
@@ -153,6 +345,9 @@ where
 
             (Self::RemoveAll, Self::RemoveAll) => true,
             (Self::RemoveAll, _) => false,
+
+            (Self::ReplaceAll(left), Self::ReplaceAll(right)) => left == right,
+            (Self::ReplaceAll(_), _) => false,
         }
     }
 }
@@ -160,7 +355,13 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
     use crate::DipaImplTester;
+    use crate::{MergeConflictReason, MergeablePatches, SquashablePatches};
     use std::collections::{BTreeMap, HashMap};
 
     /// Verify that we properly handle an unchanged empty HashMap
@@ -174,7 +375,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly handle an unchanged HashMap that has fields.
@@ -188,7 +389,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can remove a field from the original HashMap
@@ -202,7 +403,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can add a field to the original HashMap
@@ -216,7 +417,7 @@ mod tests {
             expected_serialized_patch_size: 3,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can remove a field to the original HashMap
@@ -232,7 +433,7 @@ mod tests {
             expected_serialized_patch_size: 2,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can remove a field to the original HashMap
@@ -248,7 +449,7 @@ mod tests {
             expected_serialized_patch_size: 4,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can add multiple fields to the map.
@@ -266,7 +467,7 @@ mod tests {
             expected_serialized_patch_size: 8,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can remove multiple entries from the map.
@@ -286,7 +487,7 @@ mod tests {
             expected_serialized_patch_size: 6,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can change multiple entries within the map.
@@ -306,6 +507,259 @@ mod tests {
             expected_serialized_patch_size: 10,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
+    }
+
+    /// Verify that changing the value of a key that is not in the map returns an error.
+    #[test]
+    fn change_missing_key_errors() {
+        let mut map: HashMap<u32, u64> = HashMap::new();
+
+        let err = map
+            .apply_patch(MapDeltaOwned::ChangeOneField(1, Some(5)))
+            .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::MissingMapKey);
+        assert_eq!(err.path().collect::<Vec<_>>(), vec![&PathSegment::MapValue]);
+    }
+
+    /// Verify that undoing an added entry that overwrote an existing value restores the old value
+    /// instead of removing the key.
+    #[test]
+    fn undo_overwritten_entry() {
+        let mut map: HashMap<u32, u64> = vec![(1, 2)].into_iter().collect();
+
+        let inverse = map
+            .apply_patch_returning_inverse(MapDeltaOwned::AddOneField(1, 5))
+            .unwrap();
+        assert!(matches!(inverse, MapDeltaOwned::AddOneField(1, 2)));
+
+        map.apply_patch(inverse).unwrap();
+        assert_eq!(map, vec![(1, 2)].into_iter().collect());
+    }
+
+    /// Verify that we can squash consecutive map patches.
+    #[test]
+    fn squash_map_patches() {
+        let map = |entries: &[(u32, u64)]| entries.iter().copied().collect::<BTreeMap<_, _>>();
+
+        test_squash(map(&[(1, 1)]), map(&[(1, 5)]), map(&[(1, 7)]));
+        test_squash(
+            map(&[(1, 1)]),
+            map(&[(1, 1), (2, 2)]),
+            map(&[(1, 1), (2, 4)]),
+        );
+        test_squash(map(&[(1, 1)]), map(&[(1, 1), (2, 2)]), map(&[(2, 2)]));
+        test_squash(map(&[(1, 1), (2, 2)]), map(&[]), map(&[(3, 3)]));
+        test_squash(
+            map(&[(1, 1), (2, 2)]),
+            map(&[(1, 1)]),
+            map(&[(1, 1), (2, 5)]),
+        );
+    }
+
+    /// Verify that clearing a map and then adding entries squashes into a ReplaceAll, and that the
+    /// ReplaceAll can be undone.
+    #[test]
+    fn squash_remove_all_then_add() {
+        let mut map: HashMap<u32, u64> = vec![(1, 2)].into_iter().collect();
+
+        let squashed = HashMap::<u32, u64>::squash_patches(
+            MapDeltaOwned::RemoveAll,
+            MapDeltaOwned::AddOneField(3, 4),
+        )
+        .unwrap();
+        assert!(matches!(&squashed, MapDeltaOwned::ReplaceAll(entries) if entries == &[(3, 4)]));
+
+        let inverse = map.apply_patch_returning_inverse(squashed).unwrap();
+        assert_eq!(map, vec![(3, 4)].into_iter().collect());
+
+        map.apply_patch(inverse).unwrap();
+        assert_eq!(map, vec![(1, 2)].into_iter().collect());
+    }
+
+    /// Verify that squashing a change to a key that the first patch removed returns an error.
+    #[test]
+    fn squash_change_after_remove_errors() {
+        let err = HashMap::<u32, u64>::squash_patches(
+            MapDeltaOwned::RemoveOneField(1),
+            MapDeltaOwned::ChangeOneField(1, Some(5)),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::MissingMapKey);
+    }
+
+    /// Verify that we can merge map patches that modify different keys, or that make the same
+    /// mergeable change to a key.
+    #[test]
+    fn merge_map_patches() {
+        let map = |entries: &[(u32, u64)]| entries.iter().copied().collect::<BTreeMap<_, _>>();
+
+        test_merge(
+            map(&[(1, 1), (2, 2)]),
+            map(&[(1, 5), (2, 2)]),
+            map(&[(1, 1), (2, 2), (3, 3)]),
+            map(&[(1, 5), (2, 2), (3, 3)]),
+        );
+        test_merge(
+            map(&[(1, 1), (2, 2)]),
+            map(&[(2, 2)]),
+            map(&[(2, 2)]),
+            map(&[(2, 2)]),
+        );
+        test_merge(
+            map(&[(1, 1), (2, 2)]),
+            map(&[]),
+            map(&[(1, 1), (2, 2)]),
+            map(&[]),
+        );
+
+        let base: HashMap<u8, Vec<u8>> = vec![(1, vec![1, 2, 3, 4, 5])].into_iter().collect();
+        test_merge(
+            base,
+            vec![(1, vec![9, 2, 3, 4, 5])].into_iter().collect(),
+            vec![(1, vec![1, 2, 3, 4, 5, 6])].into_iter().collect(),
+            vec![(1, vec![9, 2, 3, 4, 5, 6])].into_iter().collect(),
+        );
+    }
+
+    /// Verify that changing the same key in different ways is reported as a conflict at the
+    /// key's value, and that clearing the map conflicts with any other change.
+    #[test]
+    fn merge_conflicting_map_patches() {
+        let base: HashMap<u32, u64> = vec![(1, 1), (2, 2)].into_iter().collect();
+
+        let conflicts = HashMap::<u32, u64>::merge_patches(
+            &base,
+            MapDeltaOwned::ChangeOneField(1, Some(5)),
+            MapDeltaOwned::ModifyMany {
+                added: vec![],
+                removed: vec![1],
+                changed: vec![(2, Some(3))],
+            },
+        )
+        .map(|_| ())
+        .unwrap_err();
+        assert_eq!(conflicts.conflicts().len(), 1);
+        assert_eq!(
+            conflicts.conflicts()[0].path().collect::<Vec<_>>(),
+            vec![&PathSegment::MapValue]
+        );
+
+        let conflicts = HashMap::<u32, u64>::merge_patches(
+            &base,
+            MapDeltaOwned::RemoveAll,
+            MapDeltaOwned::AddOneField(3, 3),
+        )
+        .map(|_| ())
+        .unwrap_err();
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::ConcurrentChange
+        );
+    }
+
+    /// Verify that map changes are described using the key of each entry that changed.
+    #[test]
+    fn print_map_delta() {
+        let map =
+            |entries: &[(&'static str, u32)]| entries.iter().copied().collect::<BTreeMap<_, _>>();
+
+        test_print(map(&[("a", 1)]), map(&[("a", 2)]), &[r#"["a"]: 1 -> 2"#]);
+        test_print(
+            map(&[("a", 1), ("b", 2)]),
+            map(&[("a", 3), ("c", 4), ("d", 5)]),
+            &[
+                r#"["a"]: 1 -> 3"#,
+                r#"["b"]: removed"#,
+                r#"["c"]: inserted"#,
+                r#"["d"]: inserted"#,
+            ],
+        );
+        test_print(
+            map(&[("a", 1), ("b", 2)]),
+            map(&[]),
+            &[r#"["a"]: removed"#, r#"["b"]: removed"#],
+        );
+    }
+
+    /// Verify that applying a map's JSON Patch to the start object gives us the end object.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn map_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        let map = |entries: &[(&str, Vec<u32>)]| {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        test_json_patch(map(&[("a", vec![1])]), map(&[("a", vec![1, 2])]));
+        test_json_patch(
+            map(&[("a", vec![1]), ("b", vec![2])]),
+            map(&[("a", vec![1, 3]), ("c", vec![4]), ("d/e", vec![])]),
+        );
+        test_json_patch(map(&[("a", vec![1]), ("b", vec![2])]), map(&[]));
+
+        let numeric_keys =
+            |entries: &[(u16, u8)]| entries.iter().copied().collect::<BTreeMap<_, _>>();
+        test_json_patch(
+            numeric_keys(&[(1, 1), (2, 2)]),
+            numeric_keys(&[(1, 5), (2, 6), (3, 3), (4, 4)]),
+        );
+    }
+
+    /// Verify that we can convert map deltas into owned deltas without serializing them.
+    #[test]
+    fn map_to_owned_delta() {
+        let start: HashMap<u8, Vec<u8>> = vec![(1, vec![1]), (2, vec![2])].into_iter().collect();
+
+        test_to_owned_delta(start.clone(), HashMap::new());
+        test_to_owned_delta(HashMap::new(), start.clone());
+
+        let mut end = start.clone();
+        end.insert(3, vec![3]);
+        test_to_owned_delta(start.clone(), end);
+
+        let mut end = start.clone();
+        end.get_mut(&1).unwrap().push(4);
+        test_to_owned_delta(start.clone(), end);
+
+        let mut end = start.clone();
+        end.get_mut(&2).unwrap().clear();
+        test_to_owned_delta(start, end);
+
+        test_to_owned_delta(
+            BTreeMap::<u8, u8>::new(),
+            vec![(1, 2)].into_iter().collect(),
+        );
+    }
+
+    /// Verify that we can patch a map from a borrowed patch, including the values that changed.
+    #[test]
+    fn map_borrowed_patch() {
+        let start: HashMap<u8, String> = vec![(1, "a".to_string()), (2, "b".to_string())]
+            .into_iter()
+            .collect();
+
+        test_borrowed_patch(start.clone(), HashMap::new());
+        test_borrowed_patch(HashMap::new(), start.clone());
+
+        let mut end = start.clone();
+        end.get_mut(&1).unwrap().push_str("bc");
+        test_borrowed_patch(start.clone(), end.clone());
+
+        end.get_mut(&2).unwrap().clear();
+        end.insert(3, "c".to_string());
+        test_borrowed_patch(start, end);
+
+        test_borrowed_patch(
+            BTreeMap::<u8, Vec<u32>>::new(),
+            vec![(1, vec![2, 3])].into_iter().collect(),
+        );
     }
 }
@@ -2,9 +2,19 @@
 macro_rules! map_impl {
     ($map_ty:ty, $module:ident, $($additional_key_bounds:tt)*) => {
         mod $module {
-            use super::{MapDelta, MapDeltaOwned};
-            use crate::{CreatedDelta, Diffable, Patchable};
-            use serde::{Serialize, de::DeserializeOwned};
+            use super::{
+                borrowed_patch_map_value, patch_map_value, reverse_patch_map_value, visit_map_delta,
+                MapDelta, MapDeltaBorrowed, MapDeltaOwned,
+            };
+            use super::map_merge_patches::merge_map_patches;
+            use super::map_squash_patches::squash_map_patches;
+            use $crate::{
+                BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts,
+                MergeablePatches, PatchError, Patchable, ReversiblePatchable, SquashablePatches,
+                VisitDelta,
+            };
+            use serde::{Deserialize, Serialize, de::DeserializeOwned};
+            use std::fmt::Debug;
             use std::hash::Hash;
 
             type MapAssociatedDeltaOwned<'s, 'e, K, V> =
@@ -23,6 +33,14 @@ macro_rules! map_impl {
                 fn create_delta_towards(
                     &'s self,
                     end_state: &'e $map_ty,
+                ) -> CreatedDelta<Self::Delta> {
+                    self.create_delta_towards_version(end_state, u32::MAX)
+                }
+
+                fn create_delta_towards_version(
+                    &'s self,
+                    end_state: &'e $map_ty,
+                    version: u32,
                 ) -> CreatedDelta<Self::Delta> {
                     let mut did_change = false;
 
@@ -47,7 +65,8 @@ macro_rules! map_impl {
                                 fields_to_remove.push(key);
                             }
                             Some(end) => {
-                                let CreatedDelta {delta, did_change: changed} = start.create_delta_towards(end);
+                                let CreatedDelta {delta, did_change: changed} =
+                                    start.create_delta_towards_version(end, version);
 
                                 if changed {
                                     did_change = true;
@@ -105,7 +124,10 @@ macro_rules! map_impl {
                 <V as Diffable<'s,'e,V>>::Delta: Serialize,
                 <V as Diffable<'s,'e,V>>::DeltaOwned: DeserializeOwned
             {
-                fn apply_patch(&mut self, patch: MapAssociatedDeltaOwned<'s, 'e, K, V>) {
+                fn apply_patch(
+                    &mut self,
+                    patch: MapAssociatedDeltaOwned<'s, 'e, K, V>,
+                ) -> Result<(), PatchError> {
                     match patch {
                         MapDeltaOwned::NoChange => {}
                         MapDeltaOwned::RemoveAll => self.clear(),
@@ -116,7 +138,7 @@ macro_rules! map_impl {
                             self.remove(&k);
                         }
                         MapDeltaOwned::ChangeOneField(k, delta) => {
-                            self.get_mut(&k).unwrap().apply_patch(delta);
+                            patch_map_value(self.get_mut(&k), delta)?;
                         }
                         MapDeltaOwned::ModifyMany {
                             added,
@@ -132,10 +154,239 @@ macro_rules! map_impl {
                             }
 
                             for change in changed {
-                                self.get_mut(&change.0).unwrap().apply_patch(change.1);
+                                patch_map_value(self.get_mut(&change.0), change.1)?;
+                            }
+                        }
+                        MapDeltaOwned::ReplaceAll(entries) => {
+                            self.clear();
+                            self.extend(entries);
+                        }
+                    }
+
+                    Ok(())
+                }
+            }
+
+            impl<'b, K, V> Patchable<MapDeltaBorrowed<'b, K, V>> for $map_ty
+            where
+                K: Eq + Hash $($additional_key_bounds)*,
+                V: BorrowedDelta<'b>,
+            {
+                fn apply_patch(&mut self, patch: MapDeltaBorrowed<'b, K, V>) -> Result<(), PatchError> {
+                    match patch {
+                        MapDeltaBorrowed::NoChange => {}
+                        MapDeltaBorrowed::RemoveAll => self.clear(),
+                        MapDeltaBorrowed::AddOneField(k, v) => {
+                            self.insert(k, v);
+                        }
+                        MapDeltaBorrowed::RemoveOneField(k) => {
+                            self.remove(&k);
+                        }
+                        MapDeltaBorrowed::ChangeOneField(k, delta) => {
+                            borrowed_patch_map_value(self.get_mut(&k), delta)?;
+                        }
+                        MapDeltaBorrowed::ModifyMany {
+                            added,
+                            removed,
+                            changed,
+                        } => {
+                            for add in added {
+                                self.insert(add.0, add.1);
+                            }
+
+                            for remove in removed {
+                                self.remove(&remove);
+                            }
+
+                            for change in changed {
+                                borrowed_patch_map_value(self.get_mut(&change.0), change.1)?;
                             }
                         }
+                        MapDeltaBorrowed::ReplaceAll(entries) => {
+                            self.clear();
+                            self.extend(entries);
+                        }
                     }
+
+                    Ok(())
+                }
+            }
+
+            impl<'de, K, V> BorrowedDelta<'de> for $map_ty
+            where
+                K: Eq + Hash + Deserialize<'de> $($additional_key_bounds)*,
+                V: BorrowedDelta<'de> + Deserialize<'de>,
+            {
+                type DeltaBorrowed = MapDeltaBorrowed<'de, K, V>;
+
+                fn apply_borrowed_patch(
+                    &mut self,
+                    patch: MapDeltaBorrowed<'de, K, V>,
+                ) -> Result<(), PatchError> {
+                    self.apply_patch(patch)
+                }
+            }
+
+            impl<'s, 'e, K, V> ReversiblePatchable<MapAssociatedDeltaOwned<'s, 'e, K, V>> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + Clone $($additional_key_bounds)*,
+                V: 'e + Diffable<'s, 'e, V>,
+                V: ReversiblePatchable<<V as Diffable<'s, 'e, V>>::DeltaOwned>,
+                <V as Diffable<'s,'e,V>>::Delta: Serialize,
+                <V as Diffable<'s,'e,V>>::DeltaOwned: DeserializeOwned
+            {
+                fn apply_patch_returning_inverse(
+                    &mut self,
+                    patch: MapAssociatedDeltaOwned<'s, 'e, K, V>,
+                ) -> Result<MapAssociatedDeltaOwned<'s, 'e, K, V>, PatchError> {
+                    let inverse = match patch {
+                        MapDeltaOwned::NoChange => MapDeltaOwned::NoChange,
+                        MapDeltaOwned::RemoveAll => MapDeltaOwned::ModifyMany {
+                            added: std::mem::take(self).into_iter().collect(),
+                            removed: vec![],
+                            changed: vec![],
+                        },
+                        MapDeltaOwned::AddOneField(k, v) => {
+                            match self.insert(k.clone(), v) {
+                                Some(previous) => MapDeltaOwned::AddOneField(k, previous),
+                                None => MapDeltaOwned::RemoveOneField(k),
+                            }
+                        }
+                        MapDeltaOwned::RemoveOneField(k) => match self.remove(&k) {
+                            Some(removed) => MapDeltaOwned::AddOneField(k, removed),
+                            None => MapDeltaOwned::NoChange,
+                        },
+                        MapDeltaOwned::ChangeOneField(k, delta) => {
+                            let delta = reverse_patch_map_value(self.get_mut(&k), delta)?;
+                            MapDeltaOwned::ChangeOneField(k, delta)
+                        }
+                        MapDeltaOwned::ModifyMany {
+                            added,
+                            removed,
+                            changed,
+                        } => {
+                            let mut inverse_added = vec![];
+                            let mut inverse_removed = vec![];
+                            let mut inverse_changed = Vec::with_capacity(changed.len());
+
+                            for (k, v) in added {
+                                match self.insert(k.clone(), v) {
+                                    Some(previous) => inverse_added.push((k, previous)),
+                                    None => inverse_removed.push(k),
+                                };
+                            }
+
+                            for k in removed {
+                                if let Some(removed) = self.remove(&k) {
+                                    inverse_added.push((k, removed));
+                                }
+                            }
+
+                            for (k, delta) in changed {
+                                let delta = reverse_patch_map_value(self.get_mut(&k), delta)?;
+                                inverse_changed.push((k, delta));
+                            }
+
+                            MapDeltaOwned::ModifyMany {
+                                added: inverse_added,
+                                removed: inverse_removed,
+                                changed: inverse_changed,
+                            }
+                        }
+                        MapDeltaOwned::ReplaceAll(entries) => {
+                            let previous = std::mem::replace(self, entries.into_iter().collect());
+                            MapDeltaOwned::ReplaceAll(previous.into_iter().collect())
+                        }
+                    };
+
+                    Ok(inverse)
+                }
+            }
+
+            impl<'s, 'e, K, V> SquashablePatches<MapAssociatedDeltaOwned<'s, 'e, K, V>> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash $($additional_key_bounds)*,
+                V: 'e + Diffable<'s, 'e, V>,
+                V: SquashablePatches<<V as Diffable<'s, 'e, V>>::DeltaOwned>,
+                <V as Diffable<'s,'e,V>>::Delta: Serialize,
+                <V as Diffable<'s,'e,V>>::DeltaOwned: DeserializeOwned
+            {
+                fn squash_patches(
+                    first: MapAssociatedDeltaOwned<'s, 'e, K, V>,
+                    second: MapAssociatedDeltaOwned<'s, 'e, K, V>,
+                ) -> Result<MapAssociatedDeltaOwned<'s, 'e, K, V>, PatchError> {
+                    squash_map_patches(first, second)
+                }
+            }
+
+            impl<'s, 'e, K, V> MergeablePatches<MapAssociatedDeltaOwned<'s, 'e, K, V>> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash $($additional_key_bounds)*,
+                V: 'e + Diffable<'s, 'e, V>,
+                V: MergeablePatches<<V as Diffable<'s, 'e, V>>::DeltaOwned>,
+                <V as Diffable<'s,'e,V>>::Delta: Serialize,
+                <V as Diffable<'s,'e,V>>::DeltaOwned: DeserializeOwned
+            {
+                fn merge_patches(
+                    base: &Self,
+                    a: MapAssociatedDeltaOwned<'s, 'e, K, V>,
+                    b: MapAssociatedDeltaOwned<'s, 'e, K, V>,
+                ) -> Result<MapAssociatedDeltaOwned<'s, 'e, K, V>, MergeConflicts> {
+                    merge_map_patches(|key| base.get(key), a, b)
+                }
+            }
+
+            impl<'s, 'e, K, V> $crate::SchemaFingerprint<'s, 'e> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + $crate::SchemaFingerprint<'s, 'e> $($additional_key_bounds)*,
+                V: 'e + $crate::SchemaFingerprint<'s, 'e>,
+                <V as Diffable<'s,'e,V>>::Delta: Serialize,
+                <V as Diffable<'s,'e,V>>::DeltaOwned: DeserializeOwned
+            {
+                const FINGERPRINT: u64 = $crate::FingerprintHasher::new()
+                    .write_str("Map")
+                    .write_u64(K::FINGERPRINT)
+                    .write_u64(V::FINGERPRINT)
+                    .finish();
+            }
+
+            impl<'s, 'e, K, V> VisitDelta<'s, 'e> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + Debug $($additional_key_bounds)*,
+                V: 'e + VisitDelta<'s, 'e>,
+                <V as Diffable<'s,'e,V>>::Delta: Serialize,
+                <V as Diffable<'s,'e,V>>::DeltaOwned: DeserializeOwned
+            {
+                fn visit_delta(
+                    &self,
+                    delta: &MapDelta<'s, 'e, K, V>,
+                    visitor: &mut dyn DeltaVisitor,
+                ) {
+                    visit_map_delta(|key| self.get(key), self.keys(), delta, visitor)
+                }
+            }
+
+            #[cfg(feature = "json-patch")]
+            impl<'s, 'e, K, V> $crate::ToJsonPatch<'s, 'e> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + Serialize $($additional_key_bounds)*,
+                V: 'e + $crate::ToJsonPatch<'s, 'e> + Serialize,
+                <V as Diffable<'s,'e,V>>::Delta: Serialize,
+                <V as Diffable<'s,'e,V>>::DeltaOwned: DeserializeOwned
+            {
+                fn push_json_patch(
+                    &self,
+                    delta: &MapDelta<'s, 'e, K, V>,
+                    path: &$crate::JsonPointer,
+                    patch: &mut $crate::JsonPatch,
+                ) -> Result<(), $crate::JsonPatchError> {
+                    super::map_json_patch::push_map_json_patch(
+                        |key| self.get(key),
+                        self.keys(),
+                        delta,
+                        path,
+                        patch,
+                    )
                 }
             }
         }
@@ -0,0 +1,67 @@
+use crate::json_patch::map_key_pointer;
+use crate::map::MapDelta;
+use crate::{Diffable, JsonPatch, JsonPatchError, JsonPointer, ToJsonPatch};
+use serde::Serialize;
+
+// Tested in parent module.
+//
+// `serde_json` writes maps as objects, so every entry is addressed by its key.
+pub(super) fn push_map_json_patch<'s, 'e, 'b, K, V>(
+    start_value: impl Fn(&K) -> Option<&'b V>,
+    start_keys: impl Iterator<Item = &'b K>,
+    delta: &MapDelta<'s, 'e, K, V>,
+    path: &JsonPointer,
+    patch: &mut JsonPatch,
+) -> Result<(), JsonPatchError>
+where
+    K: 'b + Serialize,
+    V: 'b + ToJsonPatch<'s, 'e> + Serialize,
+    <V as Diffable<'s, 'e, V>>::Delta: Serialize,
+{
+    let change_value = |patch: &mut JsonPatch,
+                        key: &K,
+                        value_delta: &<V as Diffable<'s, 'e, V>>::Delta|
+     -> Result<(), JsonPatchError> {
+        match start_value(key) {
+            Some(value) => value.push_json_patch(value_delta, &map_key_pointer(path, key)?, patch),
+            None => Err(JsonPatchError::not_created_from("map")),
+        }
+    };
+
+    match delta {
+        MapDelta::NoChange => {}
+        MapDelta::RemoveAll => {
+            for key in start_keys {
+                patch.remove(&map_key_pointer(path, key)?);
+            }
+        }
+        MapDelta::AddOneField(key, value) => patch.add(&map_key_pointer(path, *key)?, value)?,
+        MapDelta::RemoveOneField(key) => patch.remove(&map_key_pointer(path, *key)?),
+        MapDelta::ChangeOneField(key, value_delta) => change_value(patch, key, value_delta)?,
+        MapDelta::ModifyMany {
+            added,
+            removed,
+            changed,
+        } => {
+            for (key, value_delta) in changed {
+                change_value(patch, key, value_delta)?;
+            }
+            for key in removed {
+                patch.remove(&map_key_pointer(path, *key)?);
+            }
+            for (key, value) in added {
+                patch.add(&map_key_pointer(path, *key)?, value)?;
+            }
+        }
+        MapDelta::ReplaceAll(entries) => {
+            for key in start_keys {
+                patch.remove(&map_key_pointer(path, key)?);
+            }
+            for (key, value) in entries {
+                patch.add(&map_key_pointer(path, *key)?, value)?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use crate::map::map_squash_patches::{EntryPatch, SquashedMapPatch};
+use crate::map::MapDeltaOwned;
+use crate::{
+    Diffable, MergeConflictReason, MergeConflicts, MergeablePatches, PatchError, PatchErrorReason,
+    PathSegment,
+};
+use serde::de::DeserializeOwned;
+
+// Tested in parent module.
+//
+// Patches that modify different keys are combined. A key that both patches modify only gets
+// merged when both patches remove it or both patches change its value, and clearing the map only
+// merges with a patch that does nothing.
+pub(super) fn merge_map_patches<'s, 'e, 'b, K, V>(
+    base_value: impl Fn(&K) -> Option<&'b V>,
+    a: MapDeltaOwned<'s, 'e, K, V>,
+    b: MapDeltaOwned<'s, 'e, K, V>,
+) -> Result<MapDeltaOwned<'s, 'e, K, V>, MergeConflicts>
+where
+    K: Eq,
+    V: 'b,
+    V: Diffable<'s, 'e, V>,
+    V: MergeablePatches<<V as Diffable<'s, 'e, V>>::DeltaOwned>,
+    <V as Diffable<'s, 'e, V>>::DeltaOwned: DeserializeOwned,
+{
+    let a = split_map_patch(a);
+    let mut b = split_map_patch(b);
+
+    if a.cleared || b.cleared {
+        return match (is_no_change(&a), is_no_change(&b)) {
+            (_, true) => Ok(a.into_patch()),
+            (true, false) => Ok(b.into_patch()),
+            (false, false) => Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange)),
+        };
+    }
+
+    let mut merged = SquashedMapPatch {
+        cleared: false,
+        entries: Vec::with_capacity(a.entries.len() + b.entries.len()),
+    };
+    let mut conflicts = MergeConflicts::default();
+
+    for (key, a_entry) in a.entries {
+        let b_entry = match b.entries.iter().position(|(k, _)| *k == key) {
+            Some(idx) => b.entries.remove(idx).1,
+            None => {
+                merged.entries.push((key, a_entry));
+                continue;
+            }
+        };
+
+        let entry = match (a_entry, b_entry) {
+            (EntryPatch::Remove, EntryPatch::Remove) => Ok(EntryPatch::Remove),
+            (EntryPatch::Change(a_patch), EntryPatch::Change(b_patch)) => match base_value(&key) {
+                Some(base) => V::merge_patches(base, a_patch, b_patch).map(EntryPatch::Change),
+                None => Err(PatchError::new(PatchErrorReason::MissingMapKey).into()),
+            },
+            _ => Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange)),
+        };
+
+        match entry {
+            Ok(entry) => merged.entries.push((key, entry)),
+            Err(err) => conflicts.extend(err.with_parent(PathSegment::MapValue)),
+        };
+    }
+
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+
+    merged.entries.extend(b.entries);
+
+    Ok(merged.into_patch())
+}
+
+fn split_map_patch<'s, 'e, K, V>(
+    patch: MapDeltaOwned<'s, 'e, K, V>,
+) -> SquashedMapPatch<K, V, <V as Diffable<'s, 'e, V>>::DeltaOwned>
+where
+    V: Diffable<'s, 'e, V>,
+    <V as Diffable<'s, 'e, V>>::DeltaOwned: DeserializeOwned,
+{
+    let mut split = SquashedMapPatch {
+        cleared: false,
+        entries: vec![],
+    };
+
+    match patch {
+        MapDeltaOwned::NoChange => {}
+        MapDeltaOwned::RemoveAll => {
+            split.cleared = true;
+        }
+        MapDeltaOwned::AddOneField(k, v) => split.entries.push((k, EntryPatch::Add(v))),
+        MapDeltaOwned::RemoveOneField(k) => split.entries.push((k, EntryPatch::Remove)),
+        MapDeltaOwned::ChangeOneField(k, delta) => {
+            split.entries.push((k, EntryPatch::Change(delta)))
+        }
+        MapDeltaOwned::ModifyMany {
+            added,
+            removed,
+            changed,
+        } => {
+            split
+                .entries
+                .extend(added.into_iter().map(|(k, v)| (k, EntryPatch::Add(v))));
+            split
+                .entries
+                .extend(removed.into_iter().map(|k| (k, EntryPatch::Remove)));
+            split.entries.extend(
+                changed
+                    .into_iter()
+                    .map(|(k, delta)| (k, EntryPatch::Change(delta))),
+            );
+        }
+        MapDeltaOwned::ReplaceAll(entries) => {
+            split.cleared = true;
+            split
+                .entries
+                .extend(entries.into_iter().map(|(k, v)| (k, EntryPatch::Add(v))));
+        }
+    };
+
+    split
+}
+
+fn is_no_change<K, V, P>(patch: &SquashedMapPatch<K, V, P>) -> bool {
+    !patch.cleared && patch.entries.is_empty()
+}
@@ -0,0 +1,186 @@
+use crate::map::MapDeltaOwned;
+use crate::{Diffable, PatchError, PatchErrorReason, PathSegment, SquashablePatches};
+use serde::de::DeserializeOwned;
+
+/// What a squashed patch does to the entry for one key.
+pub(super) enum EntryPatch<V, P> {
+    Add(V),
+    Remove,
+    Change(P),
+}
+
+/// The combined effect of a series of map patches.
+///
+/// Entries are looked up with a linear search since squashed patches tend to touch a small number
+/// of keys, and it lets us keep the order that keys were first modified in.
+pub(super) struct SquashedMapPatch<K, V, P> {
+    /// Whether the map gets cleared before the entry patches are applied.
+    pub(super) cleared: bool,
+    pub(super) entries: Vec<(K, EntryPatch<V, P>)>,
+}
+
+// Tested in parent module.
+pub(super) fn squash_map_patches<'s, 'e, K, V>(
+    first: MapDeltaOwned<'s, 'e, K, V>,
+    second: MapDeltaOwned<'s, 'e, K, V>,
+) -> Result<MapDeltaOwned<'s, 'e, K, V>, PatchError>
+where
+    K: Eq,
+    V: Diffable<'s, 'e, V>,
+    V: SquashablePatches<<V as Diffable<'s, 'e, V>>::DeltaOwned>,
+    <V as Diffable<'s, 'e, V>>::DeltaOwned: DeserializeOwned,
+{
+    let mut squashed = SquashedMapPatch {
+        cleared: false,
+        entries: vec![],
+    };
+
+    squashed.push_patch(first)?;
+    squashed.push_patch(second)?;
+
+    Ok(squashed.into_patch())
+}
+
+impl<K: Eq, V, P> SquashedMapPatch<K, V, P> {
+    fn push_patch<'s, 'e>(&mut self, patch: MapDeltaOwned<'s, 'e, K, V>) -> Result<(), PatchError>
+    where
+        V: Diffable<'s, 'e, V, DeltaOwned = P>,
+        V: SquashablePatches<P>,
+        P: DeserializeOwned,
+    {
+        match patch {
+            MapDeltaOwned::NoChange => {}
+            MapDeltaOwned::RemoveAll => {
+                self.clear();
+            }
+            MapDeltaOwned::AddOneField(k, v) => {
+                self.push_entry(k, EntryPatch::Add(v))?;
+            }
+            MapDeltaOwned::RemoveOneField(k) => {
+                self.push_entry(k, EntryPatch::Remove)?;
+            }
+            MapDeltaOwned::ChangeOneField(k, delta) => {
+                self.push_entry(k, EntryPatch::Change(delta))?;
+            }
+            // Pushed in the same order that they get applied in.
+            MapDeltaOwned::ModifyMany {
+                added,
+                removed,
+                changed,
+            } => {
+                for (k, v) in added {
+                    self.push_entry(k, EntryPatch::Add(v))?;
+                }
+
+                for k in removed {
+                    self.push_entry(k, EntryPatch::Remove)?;
+                }
+
+                for (k, delta) in changed {
+                    self.push_entry(k, EntryPatch::Change(delta))?;
+                }
+            }
+            MapDeltaOwned::ReplaceAll(entries) => {
+                self.clear();
+
+                for (k, v) in entries {
+                    self.push_entry(k, EntryPatch::Add(v))?;
+                }
+            }
+        };
+
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.cleared = true;
+        self.entries.clear();
+    }
+
+    fn push_entry(&mut self, key: K, entry: EntryPatch<V, P>) -> Result<(), PatchError>
+    where
+        V: SquashablePatches<P>,
+    {
+        let existing = self.entries.iter().position(|(k, _)| *k == key);
+
+        let previous = match existing {
+            Some(idx) => Some(self.entries.remove(idx).1),
+            None => None,
+        };
+
+        let entry = match (previous, entry) {
+            (_, EntryPatch::Add(v)) => EntryPatch::Add(v),
+            (_, EntryPatch::Remove) => EntryPatch::Remove,
+            (None, EntryPatch::Change(delta)) => {
+                if self.cleared {
+                    return Err(missing_key());
+                }
+
+                EntryPatch::Change(delta)
+            }
+            (Some(EntryPatch::Add(mut v)), EntryPatch::Change(delta)) => {
+                v.apply_patch(delta)
+                    .map_err(|err| err.with_parent(PathSegment::MapValue))?;
+                EntryPatch::Add(v)
+            }
+            (Some(EntryPatch::Change(first)), EntryPatch::Change(second)) => EntryPatch::Change(
+                V::squash_patches(first, second)
+                    .map_err(|err| err.with_parent(PathSegment::MapValue))?,
+            ),
+            (Some(EntryPatch::Remove), EntryPatch::Change(_)) => return Err(missing_key()),
+        };
+
+        self.entries.push((key, entry));
+
+        Ok(())
+    }
+
+    pub(super) fn into_patch<'s, 'e>(self) -> MapDeltaOwned<'s, 'e, K, V>
+    where
+        V: Diffable<'s, 'e, V, DeltaOwned = P>,
+        P: DeserializeOwned,
+    {
+        let mut added = vec![];
+        let mut removed = vec![];
+        let mut changed = vec![];
+
+        for (k, entry) in self.entries {
+            match entry {
+                EntryPatch::Add(v) => added.push((k, v)),
+                EntryPatch::Remove => removed.push(k),
+                EntryPatch::Change(delta) => changed.push((k, delta)),
+            }
+        }
+
+        // Removing keys from a map that was just cleared does nothing, and changes to keys that
+        // were not added back after clearing were already rejected.
+        if self.cleared {
+            return match added.is_empty() {
+                true => MapDeltaOwned::RemoveAll,
+                false => MapDeltaOwned::ReplaceAll(added),
+            };
+        }
+
+        match (added.len(), removed.len(), changed.len()) {
+            (0, 0, 0) => MapDeltaOwned::NoChange,
+            (1, 0, 0) => {
+                let (k, v) = added.remove(0);
+                MapDeltaOwned::AddOneField(k, v)
+            }
+            (0, 1, 0) => MapDeltaOwned::RemoveOneField(removed.remove(0)),
+            (0, 0, 1) => {
+                let (k, delta) = changed.remove(0);
+                MapDeltaOwned::ChangeOneField(k, delta)
+            }
+            _ => MapDeltaOwned::ModifyMany {
+                added,
+                removed,
+                changed,
+            },
+        }
+    }
+}
+
+fn missing_key() -> PatchError {
+    PatchError::new(PatchErrorReason::MissingMapKey).with_parent(PathSegment::MapValue)
+}
@@ -0,0 +1,246 @@
+use crate::patch_error::fmt_path;
+use crate::{PatchError, PatchErrorReason, Patchable, PathSegment};
+use std::fmt::{Display, Formatter};
+
+/// Merges two patches that were created against the same base state into one patch.
+///
+/// Useful when multiple editors make changes to the same state concurrently. Changes to different
+/// fields of a struct, different keys of a map or set and non overlapping ranges of a sequence
+/// are combined. Changes to the same value are merged recursively, and any changes that can not be
+/// merged are returned as [`MergeConflicts`].
+///
+/// ```
+/// # use dipa::{Diffable, MergeablePatches, ToOwnedDelta, Patchable};
+/// let base = vec![1u8, 2, 3, 4, 5];
+///
+/// let a = base.create_delta_towards(&vec![9, 2, 3, 4, 5]).delta.to_owned_delta();
+/// let b = base.create_delta_towards(&vec![1, 2, 3, 4, 5, 6]).delta.to_owned_delta();
+///
+/// let merged = Vec::merge_patches(&base, a, b).unwrap();
+///
+/// let mut merged_state = base.clone();
+/// merged_state.apply_patch(merged).unwrap();
+/// assert_eq!(merged_state, vec![9, 2, 3, 4, 5, 6]);
+/// ```
+pub trait MergeablePatches<P>: Patchable<P> {
+    /// Merge two patches that were both created against `base`.
+    ///
+    /// Applying the merged patch to `base` has the same effect as applying both of the patches'
+    /// changes.
+    fn merge_patches(base: &Self, a: P, b: P) -> Result<P, MergeConflicts>;
+}
+
+/// Every change that could not be merged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MergeConflicts {
+    conflicts: Vec<MergeConflict>,
+}
+
+/// A value that both patches changed in ways that could not be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Stored from the innermost segment to the outermost segment since conflicts get their path
+    /// filled in as they bubble up.
+    reversed_path: Vec<PathSegment>,
+    reason: MergeConflictReason,
+}
+
+/// Why two changes could not be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MergeConflictReason {
+    /// Both patches change the same value and their changes can not be combined, such as when
+    /// they set a number to different values or modify overlapping ranges of a sequence.
+    ConcurrentChange,
+    /// One of the patches could not be applied to the base state.
+    InvalidPatch(PatchErrorReason),
+}
+
+impl MergeConflicts {
+    /// Create a conflict for the value at the current position.
+    pub fn new(reason: MergeConflictReason) -> Self {
+        MergeConflicts {
+            conflicts: vec![MergeConflict {
+                reversed_path: vec![],
+                reason,
+            }],
+        }
+    }
+
+    /// Prepend a segment to the path of every conflict.
+    ///
+    /// Called by a parent value when merging the patches for one of its children fails.
+    pub fn with_parent(mut self, segment: PathSegment) -> Self {
+        for conflict in self.conflicts.iter_mut() {
+            conflict.reversed_path.push(segment.clone());
+        }
+
+        self
+    }
+
+    /// Add the conflicts from merging another value's patches.
+    ///
+    /// Used by values such as structs that keep merging their other children after one child
+    /// conflicts so that every conflict gets reported.
+    pub fn extend(&mut self, other: MergeConflicts) {
+        self.conflicts.extend(other.conflicts);
+    }
+
+    /// Whether there are no conflicts.
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Every conflict.
+    pub fn conflicts(&self) -> &[MergeConflict] {
+        &self.conflicts
+    }
+}
+
+impl MergeConflict {
+    /// The path from the root of the merged value to the value that has conflicting changes.
+    pub fn path(&self) -> impl Iterator<Item = &PathSegment> {
+        self.reversed_path.iter().rev()
+    }
+
+    /// Why the changes could not be merged.
+    pub fn reason(&self) -> &MergeConflictReason {
+        &self.reason
+    }
+}
+
+/// A patch that can not be applied to the base state can not be merged either.
+impl From<PatchError> for MergeConflicts {
+    fn from(err: PatchError) -> Self {
+        let mut reversed_path: Vec<PathSegment> = err.path().cloned().collect();
+        reversed_path.reverse();
+
+        MergeConflicts {
+            conflicts: vec![MergeConflict {
+                reversed_path,
+                reason: MergeConflictReason::InvalidPatch(err.reason().clone()),
+            }],
+        }
+    }
+}
+
+impl Display for MergeConflicts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} merge conflict(s)", self.conflicts.len())?;
+
+        for conflict in self.conflicts.iter() {
+            write!(f, "\n{}", conflict)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("conflict")?;
+
+        if !self.reversed_path.is_empty() {
+            f.write_str(" at ")?;
+            fmt_path(f, self.path())?;
+        }
+
+        match &self.reason {
+            MergeConflictReason::ConcurrentChange => f.write_str(": both patches change the value"),
+            MergeConflictReason::InvalidPatch(reason) => write!(f, ": invalid patch, {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MergeConflicts {}
+
+#[cfg(test)]
+pub(crate) use self::test_utils::test_merge;
+
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+    use crate::Diffable;
+    use bincode::Options;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::fmt::Debug;
+
+    /// Merge the patch from `base` to `a` with the patch from `base` to `b` and verify that
+    /// applying it to `base` gives us `expected`.
+    pub(crate) fn test_merge<T>(base: T, a: T, b: T, expected: T)
+    where
+        T: Debug + Clone + PartialEq,
+        T: for<'s, 'e> Diffable<'s, 'e, T>,
+        T: for<'s, 'e> MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+        for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+        for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    {
+        let patch_a = round_trip(&base.create_delta_towards(&a).delta);
+        let patch_b = round_trip(&base.create_delta_towards(&b).delta);
+
+        let merged = T::merge_patches(&base, patch_a, patch_b).unwrap();
+
+        let mut patched = base.clone();
+        patched.apply_patch(merged).unwrap();
+
+        assert_eq!(patched, expected, "{:?} + {:?} + {:?}", base, a, b);
+    }
+
+    fn round_trip<D: Serialize, P: DeserializeOwned>(delta: &D) -> P {
+        let bytes = bincode::options()
+            .with_varint_encoding()
+            .serialize(delta)
+            .unwrap();
+
+        bincode::options()
+            .with_varint_encoding()
+            .deserialize(&bytes)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that every conflict is displayed along with its path.
+    #[test]
+    fn display_conflicts() {
+        let mut conflicts = MergeConflicts::new(MergeConflictReason::ConcurrentChange)
+            .with_parent(PathSegment::Field("x"));
+        conflicts.extend(
+            MergeConflicts::new(MergeConflictReason::InvalidPatch(
+                PatchErrorReason::MissingMapKey,
+            ))
+            .with_parent(PathSegment::MapValue)
+            .with_parent(PathSegment::Field("scores")),
+        );
+
+        assert_eq!(
+            conflicts.to_string(),
+            "2 merge conflict(s)\n\
+            conflict at x: both patches change the value\n\
+            conflict at scores[..]: invalid patch, the key is not in the map"
+        );
+    }
+
+    /// Verify that converting a patch error keeps its path.
+    #[test]
+    fn from_patch_error() {
+        let conflicts: MergeConflicts = PatchError::new(PatchErrorReason::OptionIsNone)
+            .with_parent(PathSegment::Index(2))
+            .with_parent(PathSegment::Field("list"))
+            .into();
+
+        let conflict = &conflicts.conflicts()[0];
+        assert_eq!(
+            conflict.path().collect::<Vec<_>>(),
+            vec![&PathSegment::Field("list"), &PathSegment::Index(2)]
+        );
+        assert_eq!(
+            conflict.reason(),
+            &MergeConflictReason::InvalidPatch(PatchErrorReason::OptionIsNone)
+        );
+    }
+}
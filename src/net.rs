@@ -0,0 +1,115 @@
+use crate::{number_diff_impl_option_wrapped, number_patch_impl_option_wrapped};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+// Addresses are at most a few dozen bytes and tend to change all at once, so a changed address is
+// sent in full.
+//
+// Serde only serializes the IP and port of a `SocketAddrV6`, so a change to its flow info or scope
+// id is detected but not sent.
+number_diff_impl_option_wrapped!(IpAddr, IpAddr);
+number_patch_impl_option_wrapped!(IpAddr, Option<IpAddr>);
+number_diff_impl_option_wrapped!(Ipv4Addr, Ipv4Addr);
+number_patch_impl_option_wrapped!(Ipv4Addr, Option<Ipv4Addr>);
+number_diff_impl_option_wrapped!(Ipv6Addr, Ipv6Addr);
+number_patch_impl_option_wrapped!(Ipv6Addr, Option<Ipv6Addr>);
+
+number_diff_impl_option_wrapped!(SocketAddr, SocketAddr);
+number_patch_impl_option_wrapped!(SocketAddr, Option<SocketAddr>);
+number_diff_impl_option_wrapped!(SocketAddrV4, SocketAddrV4);
+number_patch_impl_option_wrapped!(SocketAddrV4, Option<SocketAddrV4>);
+number_diff_impl_option_wrapped!(SocketAddrV6, SocketAddrV6);
+number_patch_impl_option_wrapped!(SocketAddrV6, Option<SocketAddrV6>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Diffable, DipaImplTester, MergeConflictReason, MergeablePatches, Patchable};
+
+    #[test]
+    fn diff_patch_ip_addr() {
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        DipaImplTester {
+            label: Some("Diff patch same IpAddr"),
+            start: &mut localhost.clone(),
+            end: &localhost,
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Diff patch different IpAddr"),
+            start: &mut localhost.clone(),
+            end: &IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            expected_delta: Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            // 1 for the option, 1 for the variant, 4 for the octets
+            expected_serialized_patch_size: 6,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    #[test]
+    fn diff_patch_socket_addr() {
+        let start = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8080);
+        let end = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8081);
+
+        DipaImplTester {
+            label: Some("Diff patch different SocketAddr"),
+            start: &mut start.clone(),
+            end: &end,
+            expected_delta: Some(end),
+            // 1 for the option, 1 for the variant, 16 for the octets, 3 for the port
+            expected_serialized_patch_size: 21,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        let conflicts = SocketAddr::merge_patches(
+            &start,
+            Some(end),
+            Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080)),
+        )
+        .map(|_| ())
+        .unwrap_err();
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::ConcurrentChange
+        );
+    }
+
+    /// Verify that changing an address between IPv4 and IPv6 sends the new address in full.
+    #[test]
+    fn ip_version_change() {
+        DipaImplTester {
+            label: Some("IPv4 to IPv6"),
+            start: &mut IpAddr::V4(Ipv4Addr::LOCALHOST),
+            end: &IpAddr::V6(Ipv6Addr::LOCALHOST),
+            expected_delta: Some(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+            // 1 for the option, 1 for the variant, 16 for the octets
+            expected_serialized_patch_size: 18,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a change to only the scope id of a `SocketAddrV6` is detected, but that the
+    /// patch only carries the IP and port.
+    #[test]
+    fn socket_addr_v6_scope_id_is_not_sent() {
+        let start = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0);
+        let end = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 7);
+
+        let diff = start.create_delta_towards(&end);
+        assert!(diff.did_change);
+
+        let bytes = bincode::serialize(&diff.delta).unwrap();
+        let mut patched = start;
+        patched
+            .apply_patch(bincode::deserialize::<Option<SocketAddrV6>>(&bytes).unwrap())
+            .unwrap();
+        assert_eq!(patched, start);
+    }
+}
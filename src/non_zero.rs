@@ -0,0 +1,147 @@
+use crate::{
+    number_diff_impl_option_wrapped, number_diff_impl_u8_or_i8, number_patch_impl_option_wrapped,
+    number_patch_impl_u8_or_i8,
+};
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+
+// The delta holds the new non-zero value, so a patch can never make the value zero. serde rejects
+// a zero while deserializing the patch.
+number_diff_impl_u8_or_i8!(NonZeroU8, NonZeroU8);
+number_patch_impl_u8_or_i8!(NonZeroU8, NonZeroU8);
+number_diff_impl_u8_or_i8!(NonZeroI8, NonZeroI8);
+number_patch_impl_u8_or_i8!(NonZeroI8, NonZeroI8);
+
+number_diff_impl_option_wrapped!(NonZeroU16, NonZeroU16);
+number_patch_impl_option_wrapped!(NonZeroU16, Option<NonZeroU16>);
+number_diff_impl_option_wrapped!(NonZeroI16, NonZeroI16);
+number_patch_impl_option_wrapped!(NonZeroI16, Option<NonZeroI16>);
+
+number_diff_impl_option_wrapped!(NonZeroU32, NonZeroU32);
+number_patch_impl_option_wrapped!(NonZeroU32, Option<NonZeroU32>);
+number_diff_impl_option_wrapped!(NonZeroI32, NonZeroI32);
+number_patch_impl_option_wrapped!(NonZeroI32, Option<NonZeroI32>);
+
+number_diff_impl_option_wrapped!(NonZeroU64, NonZeroU64);
+number_patch_impl_option_wrapped!(NonZeroU64, Option<NonZeroU64>);
+number_diff_impl_option_wrapped!(NonZeroI64, NonZeroI64);
+number_patch_impl_option_wrapped!(NonZeroI64, Option<NonZeroI64>);
+
+number_diff_impl_option_wrapped!(NonZeroU128, NonZeroU128);
+number_patch_impl_option_wrapped!(NonZeroU128, Option<NonZeroU128>);
+number_diff_impl_option_wrapped!(NonZeroI128, NonZeroI128);
+number_patch_impl_option_wrapped!(NonZeroI128, Option<NonZeroI128>);
+
+number_diff_impl_option_wrapped!(NonZeroUsize, NonZeroUsize);
+number_patch_impl_option_wrapped!(NonZeroUsize, Option<NonZeroUsize>);
+number_diff_impl_option_wrapped!(NonZeroIsize, NonZeroIsize);
+number_patch_impl_option_wrapped!(NonZeroIsize, Option<NonZeroIsize>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::squash_patches::test_squash;
+    use crate::{DipaImplTester, MergeablePatches};
+
+    #[test]
+    fn diff_patch_non_zero_u8() {
+        let one = NonZeroU8::new(1).unwrap();
+
+        DipaImplTester {
+            label: Some("Diff patch NonZeroU8 to max"),
+            start: &mut one.clone(),
+            end: &NonZeroU8::MAX,
+            expected_delta: NonZeroU8::MAX,
+            expected_serialized_patch_size: 1,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Diff patch NonZeroU8 from max"),
+            start: &mut NonZeroU8::MAX.clone(),
+            end: &one,
+            expected_delta: one,
+            expected_serialized_patch_size: 1,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that the smallest and largest values of the 16 bit and pointer sized integers are
+    /// sent as their new value.
+    #[test]
+    fn diff_patch_non_zero_bounds() {
+        DipaImplTester {
+            label: Some("Diff patch same NonZeroU16"),
+            start: &mut NonZeroU16::MAX.clone(),
+            end: &NonZeroU16::MAX,
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Diff patch NonZeroU16 to max"),
+            start: &mut NonZeroU16::MIN.clone(),
+            end: &NonZeroU16::MAX,
+            expected_delta: Some(NonZeroU16::MAX),
+            // 1 for the option, 3 for the varint
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Diff patch NonZeroI16 to min"),
+            start: &mut NonZeroI16::new(-1).unwrap(),
+            end: &NonZeroI16::MIN,
+            expected_delta: Some(NonZeroI16::MIN),
+            // 1 for the option, 3 for the zigzag encoded varint
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Diff patch NonZeroUsize to max"),
+            start: &mut NonZeroUsize::MIN.clone(),
+            end: &NonZeroUsize::MAX,
+            expected_delta: Some(NonZeroUsize::MAX),
+            // 1 for the option, 1 for the varint marker and 8 for the value
+            expected_serialized_patch_size: 10,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        test_squash(
+            NonZeroI16::MIN,
+            NonZeroI16::MAX,
+            NonZeroI16::new(-1).unwrap(),
+        );
+    }
+
+    /// Verify that concurrent changes to different values conflict, since neither one is zero.
+    #[test]
+    fn merge_different_values_conflicts() {
+        assert!(NonZeroU32::merge_patches(
+            &NonZeroU32::MIN,
+            Some(NonZeroU32::MAX),
+            Some(NonZeroU32::new(2).unwrap())
+        )
+        .is_err());
+    }
+
+    /// Verify that a patch that holds a zero can not be deserialized.
+    #[test]
+    fn zero_patch_is_rejected() {
+        let zero = bincode::serialize(&Some(0u64)).unwrap();
+        assert!(bincode::deserialize::<Option<NonZeroU64>>(&zero).is_err());
+
+        let zero = bincode::serialize(&0u8).unwrap();
+        assert!(bincode::deserialize::<NonZeroU8>(&zero).is_err());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{CreatedDelta, Diffable, Patchable};
+use crate::DeltaVisitor;
+use crate::{
+    BorrowedDelta, CreatedDelta, Diffable, FingerprintHasher, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SchemaFingerprint, SquashablePatches, ToOwnedDelta,
+    VisitDelta,
+};
 
 impl<'s, 'e> Diffable<'s, 'e, ()> for () {
     type Delta = ();
@@ -13,5 +18,57 @@ impl<'s, 'e> Diffable<'s, 'e, ()> for () {
 }
 
 impl Patchable<()> for () {
-    fn apply_patch(&mut self, _patch: ()) {}
+    fn apply_patch(&mut self, _patch: ()) -> Result<(), PatchError> {
+        Ok(())
+    }
+}
+
+impl ReversiblePatchable<()> for () {
+    fn apply_patch_returning_inverse(&mut self, _patch: ()) -> Result<(), PatchError> {
+        Ok(())
+    }
+}
+
+impl SquashablePatches<()> for () {
+    fn squash_patches(_first: (), _second: ()) -> Result<(), PatchError> {
+        Ok(())
+    }
+}
+
+impl MergeablePatches<()> for () {
+    fn merge_patches(_base: &(), _a: (), _b: ()) -> Result<(), MergeConflicts> {
+        Ok(())
+    }
+}
+
+impl ToOwnedDelta<()> for () {
+    fn to_owned_delta(self) {}
+}
+
+impl<'s, 'e> VisitDelta<'s, 'e> for () {
+    fn visit_delta(&self, _delta: &(), _visitor: &mut dyn DeltaVisitor) {}
+}
+
+impl<'s, 'e> SchemaFingerprint<'s, 'e> for () {
+    const FINGERPRINT: u64 = FingerprintHasher::new().write_str("()").finish();
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e> crate::ToJsonPatch<'s, 'e> for () {
+    fn push_json_patch(
+        &self,
+        _delta: &(),
+        _path: &crate::JsonPointer,
+        _patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        Ok(())
+    }
+}
+
+impl<'de> BorrowedDelta<'de> for () {
+    type DeltaBorrowed = ();
+
+    fn apply_borrowed_patch(&mut self, _patch: ()) -> Result<(), PatchError> {
+        Ok(())
+    }
 }
@@ -3,12 +3,15 @@
 #[macro_export]
 macro_rules! number_diff_impl_option_wrapped {
     ($num_ty:ty , $other:ty) => {
-        impl<'s, 'e> crate::Diffable<'s, 'e, $other> for $num_ty {
+        impl<'s, 'e> $crate::Diffable<'s, 'e, $other> for $num_ty {
             type Delta = Option<$num_ty>;
 
             type DeltaOwned = Option<$num_ty>;
 
-            fn create_delta_towards(&self, end_state: &$other) -> CreatedDelta<Self::Delta> {
+            fn create_delta_towards(
+                &self,
+                end_state: &$other,
+            ) -> $crate::CreatedDelta<Self::Delta> {
                 let did_change = self != end_state;
 
                 let delta = match *self == *end_state {
@@ -16,7 +19,42 @@ macro_rules! number_diff_impl_option_wrapped {
                     false => Some(*end_state),
                 };
 
-                CreatedDelta { delta, did_change }
+                $crate::CreatedDelta { delta, did_change }
+            }
+        }
+
+        impl $crate::ToOwnedDelta<Option<$num_ty>> for Option<$num_ty> {
+            fn to_owned_delta(self) -> Self {
+                self
+            }
+        }
+
+        impl<'s, 'e> $crate::VisitDelta<'s, 'e> for $num_ty {
+            fn visit_delta(&self, delta: &Option<$num_ty>, visitor: &mut dyn $crate::DeltaVisitor) {
+                if let Some(new) = delta {
+                    visitor.visit_change($crate::DeltaChange::Value { old: self, new });
+                }
+            }
+        }
+
+        impl<'s, 'e> $crate::SchemaFingerprint<'s, 'e> for $num_ty {
+            const FINGERPRINT: u64 = $crate::FingerprintHasher::new()
+                .write_str(stringify!($num_ty))
+                .finish();
+        }
+
+        #[cfg(feature = "json-patch")]
+        impl<'s, 'e> $crate::ToJsonPatch<'s, 'e> for $num_ty {
+            fn push_json_patch(
+                &self,
+                delta: &Option<$num_ty>,
+                path: &$crate::JsonPointer,
+                patch: &mut $crate::JsonPatch,
+            ) -> Result<(), $crate::JsonPatchError> {
+                match delta {
+                    Some(new) => patch.replace(path, new),
+                    None => Ok(()),
+                }
             }
         }
     };
@@ -25,11 +63,51 @@ macro_rules! number_diff_impl_option_wrapped {
 #[macro_export]
 macro_rules! number_patch_impl_option_wrapped {
     ($num_ty:ty, $patch: ty) => {
-        impl crate::Patchable<$patch> for $num_ty {
-            fn apply_patch(&mut self, patch: $patch) {
+        impl $crate::Patchable<$patch> for $num_ty {
+            fn apply_patch(&mut self, patch: $patch) -> Result<(), $crate::PatchError> {
                 if let Some(patch) = patch {
                     *self = patch;
                 }
+
+                Ok(())
+            }
+        }
+
+        impl $crate::ReversiblePatchable<$patch> for $num_ty {
+            fn apply_patch_returning_inverse(
+                &mut self,
+                patch: $patch,
+            ) -> Result<$patch, $crate::PatchError> {
+                Ok(patch.map(|patch| std::mem::replace(self, patch)))
+            }
+        }
+
+        impl $crate::SquashablePatches<$patch> for $num_ty {
+            fn squash_patches(first: $patch, second: $patch) -> Result<$patch, $crate::PatchError> {
+                Ok(second.or(first))
+            }
+        }
+
+        impl $crate::MergeablePatches<$patch> for $num_ty {
+            fn merge_patches(
+                _base: &Self,
+                a: $patch,
+                b: $patch,
+            ) -> Result<$patch, $crate::MergeConflicts> {
+                match (a, b) {
+                    (Some(a), Some(b)) if a != b => Err($crate::MergeConflicts::new(
+                        $crate::MergeConflictReason::ConcurrentChange,
+                    )),
+                    (a, b) => Ok(a.or(b)),
+                }
+            }
+        }
+
+        impl<'de> $crate::BorrowedDelta<'de> for $num_ty {
+            type DeltaBorrowed = $patch;
+
+            fn apply_borrowed_patch(&mut self, patch: $patch) -> Result<(), $crate::PatchError> {
+                $crate::Patchable::apply_patch(self, patch)
             }
         }
     };
@@ -40,40 +118,177 @@ macro_rules! number_patch_impl_option_wrapped {
 #[macro_export]
 macro_rules! number_diff_impl_u8_or_i8 {
     ($num_ty:ty, $other:ty) => {
-        impl<'s, 'e> crate::Diffable<'s, 'e, $other> for $num_ty {
+        impl<'s, 'e> $crate::Diffable<'s, 'e, $other> for $num_ty {
             type Delta = $num_ty;
 
             type DeltaOwned = $num_ty;
 
-            fn create_delta_towards(&self, end_state: &$other) -> CreatedDelta<Self::Delta> {
+            fn create_delta_towards(
+                &self,
+                end_state: &$other,
+            ) -> $crate::CreatedDelta<Self::Delta> {
                 let did_change = *self != *end_state;
 
-                crate::CreatedDelta {
+                $crate::CreatedDelta {
                     delta: *end_state,
                     did_change,
                 }
             }
         }
+
+        impl $crate::ToOwnedDelta<$num_ty> for $num_ty {
+            fn to_owned_delta(self) -> Self {
+                self
+            }
+        }
+
+        impl<'s, 'e> $crate::VisitDelta<'s, 'e> for $num_ty {
+            fn visit_delta(&self, delta: &$num_ty, visitor: &mut dyn $crate::DeltaVisitor) {
+                if delta != self {
+                    visitor.visit_change($crate::DeltaChange::Value {
+                        old: self,
+                        new: delta,
+                    });
+                }
+            }
+        }
+
+        impl<'s, 'e> $crate::SchemaFingerprint<'s, 'e> for $num_ty {
+            const FINGERPRINT: u64 = $crate::FingerprintHasher::new()
+                .write_str(stringify!($num_ty))
+                .finish();
+        }
+
+        #[cfg(feature = "json-patch")]
+        impl<'s, 'e> $crate::ToJsonPatch<'s, 'e> for $num_ty {
+            fn push_json_patch(
+                &self,
+                delta: &$num_ty,
+                path: &$crate::JsonPointer,
+                patch: &mut $crate::JsonPatch,
+            ) -> Result<(), $crate::JsonPatchError> {
+                if delta != self {
+                    patch.replace(path, delta)?;
+                }
+
+                Ok(())
+            }
+        }
     };
 }
 
+/// `deserialize_sequence_items` optionally overrides `BorrowedDelta::deserialize_sequence_items`.
 #[macro_export]
 macro_rules! number_patch_impl_u8_or_i8 {
-    ($num_ty:ty, $patch: ty) => {
-        impl crate::Patchable<$patch> for $num_ty {
-            fn apply_patch(&mut self, patch: $patch) {
+    ($num_ty:ty, $patch: ty $(, deserialize_sequence_items = $deserialize_sequence_items:path)?) => {
+        impl $crate::Patchable<$patch> for $num_ty {
+            fn apply_patch(&mut self, patch: $patch) -> Result<(), $crate::PatchError> {
                 *self = patch;
+
+                Ok(())
+            }
+        }
+
+        impl $crate::ReversiblePatchable<$patch> for $num_ty {
+            fn apply_patch_returning_inverse(
+                &mut self,
+                patch: $patch,
+            ) -> Result<$patch, $crate::PatchError> {
+                Ok(std::mem::replace(self, patch))
+            }
+        }
+
+        impl $crate::SquashablePatches<$patch> for $num_ty {
+            fn squash_patches(
+                _first: $patch,
+                second: $patch,
+            ) -> Result<$patch, $crate::PatchError> {
+                Ok(second)
+            }
+        }
+
+        // The patch is the new value, so a patch that holds the base value did not change it.
+        impl $crate::MergeablePatches<$patch> for $num_ty {
+            fn merge_patches(
+                base: &Self,
+                a: $patch,
+                b: $patch,
+            ) -> Result<$patch, $crate::MergeConflicts> {
+                if a == b || b == *base {
+                    Ok(a)
+                } else if a == *base {
+                    Ok(b)
+                } else {
+                    Err($crate::MergeConflicts::new(
+                        $crate::MergeConflictReason::ConcurrentChange,
+                    ))
+                }
             }
         }
+
+        impl<'de> $crate::BorrowedDelta<'de> for $num_ty {
+            type DeltaBorrowed = $patch;
+
+            fn apply_borrowed_patch(&mut self, patch: $patch) -> Result<(), $crate::PatchError> {
+                $crate::Patchable::apply_patch(self, patch)
+            }
+
+            $(
+                fn deserialize_sequence_items<D: serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<std::borrow::Cow<'de, [Self]>, D::Error> {
+                    $deserialize_sequence_items(deserializer)
+                }
+            )?
+        }
     };
 }
 
 #[macro_export]
 macro_rules! number_patch_impl_mut_u8_or_i8 {
     ($num_ty:ty, $patch: ty) => {
-        impl crate::Patchable<$patch> for $num_ty {
-            fn apply_patch(&mut self, patch: $patch) {
+        impl $crate::Patchable<$patch> for $num_ty {
+            fn apply_patch(&mut self, patch: $patch) -> Result<(), $crate::PatchError> {
                 **self = patch;
+
+                Ok(())
+            }
+        }
+
+        impl $crate::ReversiblePatchable<$patch> for $num_ty {
+            fn apply_patch_returning_inverse(
+                &mut self,
+                patch: $patch,
+            ) -> Result<$patch, $crate::PatchError> {
+                Ok(std::mem::replace(*self, patch))
+            }
+        }
+
+        impl $crate::SquashablePatches<$patch> for $num_ty {
+            fn squash_patches(
+                _first: $patch,
+                second: $patch,
+            ) -> Result<$patch, $crate::PatchError> {
+                Ok(second)
+            }
+        }
+
+        // The patch is the new value, so a patch that holds the base value did not change it.
+        impl $crate::MergeablePatches<$patch> for $num_ty {
+            fn merge_patches(
+                base: &Self,
+                a: $patch,
+                b: $patch,
+            ) -> Result<$patch, $crate::MergeConflicts> {
+                if a == b || b == **base {
+                    Ok(a)
+                } else if a == **base {
+                    Ok(b)
+                } else {
+                    Err($crate::MergeConflicts::new(
+                        $crate::MergeConflictReason::ConcurrentChange,
+                    ))
+                }
             }
         }
     };
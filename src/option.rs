@@ -1,6 +1,12 @@
-use crate::{CreatedDelta, Diffable, Patchable};
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaChange, DeltaVisitor, Diffable, MergeConflictReason,
+    MergeConflicts, MergeablePatches, PatchError, PatchErrorReason, Patchable, ReversiblePatchable,
+    SquashablePatches, ToOwnedDelta, VisitDelta,
+};
+#[cfg(feature = "json-patch")]
+use crate::{JsonPatch, JsonPatchError, JsonPointer, ToJsonPatch};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 
 impl<'s, 'e, T: Diffable<'s, 'e, T>> Diffable<'s, 'e, Option<T>> for Option<T>
@@ -13,13 +19,21 @@ where
     type DeltaOwned = OptionDeltaOwned<'s, 'e, T>;
 
     fn create_delta_towards(&'s self, end_state: &'e Option<T>) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e Option<T>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
         let diff = match (self, end_state) {
             (None, None) => OptionDelta::NoChange,
             (None, Some(new)) => OptionDelta::OuterChange(Some(new)),
             (Some(_), None) => OptionDelta::OuterChange(None),
 
             (Some(old), Some(new)) => {
-                let diff = old.create_delta_towards(new);
+                let diff = old.create_delta_towards_version(new, version);
 
                 if diff.did_change {
                     OptionDelta::InnerChange(diff.delta)
@@ -29,10 +43,7 @@ where
             }
         };
 
-        let did_change = match &diff {
-            OptionDelta::NoChange => false,
-            _ => true,
-        };
+        let did_change = !matches!(&diff, OptionDelta::NoChange);
 
         CreatedDelta {
             delta: diff,
@@ -49,22 +60,226 @@ where
     <T as Diffable<'s, 'e, T>>::Delta: Serialize,
     <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
 {
-    fn apply_patch(&mut self, patch: <Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned) {
+    fn apply_patch(
+        &mut self,
+        patch: <Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned,
+    ) -> Result<(), PatchError> {
         match patch {
             OptionDeltaOwned::NoChange => {}
             OptionDeltaOwned::InnerChange(delta) => match self {
-                Some(inner) => inner.apply_patch(delta),
-                _ => panic!("No inner field to change"),
+                Some(inner) => inner.apply_patch(delta)?,
+                None => return Err(PatchError::new(PatchErrorReason::OptionIsNone)),
             },
             OptionDeltaOwned::OuterChange(outer) => {
                 *self = outer;
             }
         }
+
+        Ok(())
+    }
+}
+
+impl<'s, 'e, T> ReversiblePatchable<<Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned>
+    for Option<T>
+where
+    T: 'e,
+    T: Diffable<'s, 'e, T>,
+    T: ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: <Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned,
+    ) -> Result<<Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned, PatchError> {
+        let inverse = match patch {
+            OptionDeltaOwned::NoChange => OptionDeltaOwned::NoChange,
+            OptionDeltaOwned::InnerChange(delta) => match self {
+                Some(inner) => {
+                    OptionDeltaOwned::InnerChange(inner.apply_patch_returning_inverse(delta)?)
+                }
+                None => return Err(PatchError::new(PatchErrorReason::OptionIsNone)),
+            },
+            OptionDeltaOwned::OuterChange(outer) => {
+                OptionDeltaOwned::OuterChange(std::mem::replace(self, outer))
+            }
+        };
+
+        Ok(inverse)
+    }
+}
+
+impl<'s, 'e, T> SquashablePatches<<Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned>
+    for Option<T>
+where
+    T: 'e,
+    T: Diffable<'s, 'e, T>,
+    T: SquashablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+{
+    fn squash_patches(
+        first: <Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned,
+        second: <Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned,
+    ) -> Result<<Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned, PatchError> {
+        let squashed = match (first, second) {
+            (first, OptionDeltaOwned::NoChange) => first,
+            (_, OptionDeltaOwned::OuterChange(outer)) => OptionDeltaOwned::OuterChange(outer),
+            (OptionDeltaOwned::NoChange, OptionDeltaOwned::InnerChange(second)) => {
+                OptionDeltaOwned::InnerChange(second)
+            }
+            (OptionDeltaOwned::InnerChange(first), OptionDeltaOwned::InnerChange(second)) => {
+                OptionDeltaOwned::InnerChange(T::squash_patches(first, second)?)
+            }
+            (OptionDeltaOwned::OuterChange(mut outer), OptionDeltaOwned::InnerChange(second)) => {
+                match &mut outer {
+                    Some(inner) => inner.apply_patch(second)?,
+                    None => return Err(PatchError::new(PatchErrorReason::OptionIsNone)),
+                };
+
+                OptionDeltaOwned::OuterChange(outer)
+            }
+        };
+
+        Ok(squashed)
+    }
+}
+
+impl<'s, 'e, T> MergeablePatches<<Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned>
+    for Option<T>
+where
+    T: 'e,
+    T: Diffable<'s, 'e, T>,
+    T: MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+{
+    // Two outer changes are a conflict even if they set the same value, since comparing them
+    // would require `T: PartialEq`.
+    fn merge_patches(
+        base: &Self,
+        a: <Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned,
+        b: <Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned,
+    ) -> Result<<Option<T> as Diffable<'s, 'e, Option<T>>>::DeltaOwned, MergeConflicts> {
+        let merged = match (a, b) {
+            (a, OptionDeltaOwned::NoChange) => a,
+            (OptionDeltaOwned::NoChange, b) => b,
+            (OptionDeltaOwned::InnerChange(a), OptionDeltaOwned::InnerChange(b)) => match base {
+                Some(base) => OptionDeltaOwned::InnerChange(T::merge_patches(base, a, b)?),
+                None => return Err(PatchError::new(PatchErrorReason::OptionIsNone).into()),
+            },
+            _ => return Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange)),
+        };
+
+        Ok(merged)
+    }
+}
+
+impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for Option<T>
+where
+    T: 'e,
+    T: crate::SchemaFingerprint<'s, 'e>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("Option")
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+impl<'s, 'e, T> VisitDelta<'s, 'e> for Option<T>
+where
+    T: 'e,
+    T: VisitDelta<'s, 'e>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+{
+    fn visit_delta(
+        &self,
+        delta: &<Option<T> as Diffable<'s, 'e, Option<T>>>::Delta,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        match delta {
+            OptionDelta::NoChange => {}
+            OptionDelta::InnerChange(delta) => {
+                if let Some(inner) = self {
+                    inner.visit_delta(delta, visitor);
+                }
+            }
+            OptionDelta::OuterChange(outer) => match (self, outer) {
+                (None, _) => visitor.visit_change(DeltaChange::Inserted),
+                (Some(_), None) => visitor.visit_change(DeltaChange::Removed),
+                (Some(_), Some(_)) => visitor.visit_change(DeltaChange::Replaced),
+            },
+        }
+    }
+}
+
+// `serde_json` writes `Some(value)` as the value itself, so changes to the inner value use the
+// same path as the option.
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T> ToJsonPatch<'s, 'e> for Option<T>
+where
+    T: 'e,
+    T: ToJsonPatch<'s, 'e> + Serialize,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+{
+    fn push_json_patch(
+        &self,
+        delta: &<Option<T> as Diffable<'s, 'e, Option<T>>>::Delta,
+        path: &JsonPointer,
+        patch: &mut JsonPatch,
+    ) -> Result<(), JsonPatchError> {
+        match delta {
+            OptionDelta::NoChange => Ok(()),
+            OptionDelta::InnerChange(delta) => match self {
+                Some(inner) => inner.push_json_patch(delta, path, patch),
+                None => Ok(()),
+            },
+            OptionDelta::OuterChange(outer) => patch.replace(path, outer),
+        }
+    }
+}
+
+impl<'b, T> Patchable<OptionDeltaBorrowed<'b, T>> for Option<T>
+where
+    T: BorrowedDelta<'b>,
+{
+    fn apply_patch(&mut self, patch: OptionDeltaBorrowed<'b, T>) -> Result<(), PatchError> {
+        match patch {
+            OptionDeltaBorrowed::NoChange => {}
+            OptionDeltaBorrowed::InnerChange(delta) => match self {
+                Some(inner) => inner.apply_borrowed_patch(delta)?,
+                None => return Err(PatchError::new(PatchErrorReason::OptionIsNone)),
+            },
+            OptionDeltaBorrowed::OuterChange(outer) => {
+                *self = outer;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de, T> BorrowedDelta<'de> for Option<T>
+where
+    T: BorrowedDelta<'de>,
+    T: Deserialize<'de>,
+{
+    type DeltaBorrowed = OptionDeltaBorrowed<'de, T>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: OptionDeltaBorrowed<'de, T>,
+    ) -> Result<(), PatchError> {
+        self.apply_patch(patch)
     }
 }
 
 #[derive(Serialize)]
-#[allow(missing_docs)]
+#[allow(missing_docs, clippy::enum_variant_names)]
 pub enum OptionDelta<'s, 'e, T: Diffable<'s, 'e, T>>
 where
     <T as Diffable<'s, 'e, T>>::Delta: Serialize,
@@ -75,7 +290,7 @@ where
 }
 
 #[derive(Deserialize)]
-#[allow(missing_docs)]
+#[allow(missing_docs, clippy::enum_variant_names)]
 pub enum OptionDeltaOwned<'s, 'e, T: Diffable<'s, 'e, T>>
 where
     <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
@@ -85,6 +300,39 @@ where
     OuterChange(Option<T>),
 }
 
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>, \
+    <T as BorrowedDelta<'b>>::DeltaBorrowed: Deserialize<'de>"))]
+#[allow(missing_docs, clippy::enum_variant_names)]
+pub enum OptionDeltaBorrowed<'b, T: BorrowedDelta<'b>> {
+    NoChange,
+    #[serde(borrow)]
+    InnerChange(<T as BorrowedDelta<'b>>::DeltaBorrowed),
+    OuterChange(Option<T>),
+}
+
+// The owned delta can have different lifetimes than the borrowed delta, such as when it is the
+// field of a derived `DeltaOwned` type that uses `'static`. Bounding `T` over every pair of
+// lifetimes avoids the ambiguity that two separate `Diffable` bounds would cause.
+impl<'s, 'e, 's2, 'e2, T> ToOwnedDelta<OptionDeltaOwned<'s2, 'e2, T>> for OptionDelta<'s, 'e, T>
+where
+    T: Clone,
+    T: for<'a, 'b> Diffable<'a, 'b, T>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::Delta: ToOwnedDelta<<T as Diffable<'s2, 'e2, T>>::DeltaOwned>,
+    <T as Diffable<'s2, 'e2, T>>::DeltaOwned: DeserializeOwned,
+{
+    fn to_owned_delta(self) -> OptionDeltaOwned<'s2, 'e2, T> {
+        match self {
+            OptionDelta::NoChange => OptionDeltaOwned::NoChange,
+            OptionDelta::InnerChange(delta) => {
+                OptionDeltaOwned::InnerChange(delta.to_owned_delta())
+            }
+            OptionDelta::OuterChange(outer) => OptionDeltaOwned::OuterChange(outer.cloned()),
+        }
+    }
+}
+
 // Used by DipaImplTester
 impl<'s, 'e, T: Diffable<'s, 'e, T>> Debug for OptionDelta<'s, 'e, T>
 where
@@ -129,6 +377,11 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
     use crate::DipaImplTester;
 
     /// Verify that we can diff/patch an Option<T>
@@ -142,7 +395,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("Option<T>::Some no change"),
@@ -152,7 +405,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("Option<T>::Some change"),
@@ -162,7 +415,7 @@ mod tests {
             expected_serialized_patch_size: 3,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("Option<T> Some -> None"),
@@ -172,7 +425,7 @@ mod tests {
             expected_serialized_patch_size: 2,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("Option<T> None -> Some"),
@@ -182,6 +435,115 @@ mod tests {
             expected_serialized_patch_size: 3,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
+    }
+
+    /// Verify that patching the inner value of a None returns an error instead of panicking.
+    #[test]
+    fn inner_change_to_none_errors() {
+        let mut option: Option<u32> = None;
+
+        let err = option
+            .apply_patch(OptionDeltaOwned::InnerChange(Some(5)))
+            .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::OptionIsNone);
+        assert_eq!(option, None);
+    }
+
+    /// Verify that we can squash consecutive Option patches.
+    #[test]
+    fn squash_option_patches() {
+        test_squash(Some(1u32), Some(2), Some(3));
+        test_squash(Some(1u32), None, Some(3));
+        test_squash(None, Some(2u32), Some(3));
+        test_squash(Some(1u32), Some(2), None);
+        test_squash(None::<u32>, None, None);
+    }
+
+    /// Verify that squashing a change to the inner value of an Option that was set to None returns
+    /// an error.
+    #[test]
+    fn squash_inner_change_after_none_errors() {
+        let err = Option::<u32>::squash_patches(
+            OptionDeltaOwned::OuterChange(None),
+            OptionDeltaOwned::InnerChange(Some(5)),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::OptionIsNone);
+    }
+
+    /// Verify that we can merge Option patches that were created against the same Option.
+    #[test]
+    fn merge_option_patches() {
+        test_merge(Some(1u32), Some(2), Some(1), Some(2));
+        test_merge(None, None, Some(2u32), Some(2));
+        test_merge(
+            Some(vec![1u8, 2]),
+            Some(vec![0, 1, 2]),
+            Some(vec![1, 2, 3]),
+            Some(vec![0, 1, 2, 3]),
+        );
+    }
+
+    /// Verify that changing the inner value and replacing the Option conflict.
+    #[test]
+    fn merge_outer_and_inner_change_conflicts() {
+        let conflicts = Option::<u32>::merge_patches(
+            &Some(1),
+            OptionDeltaOwned::OuterChange(None),
+            OptionDeltaOwned::InnerChange(Some(5)),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::ConcurrentChange
+        );
+    }
+
+    /// Verify that we can convert Option deltas into owned deltas without serializing them.
+    #[test]
+    fn option_to_owned_delta() {
+        test_to_owned_delta(None, Some(vec![1u8]));
+        test_to_owned_delta(Some(vec![1u8]), Some(vec![1, 2]));
+        test_to_owned_delta(Some(vec![1u8]), None);
+        test_to_owned_delta(Some(3u32), Some(3));
+    }
+
+    /// Verify that we describe values being added, removed or replaced and changes to the inner
+    /// value.
+    #[test]
+    fn print_option_delta() {
+        test_print(None, Some(1u32), &["inserted"]);
+        test_print(Some(1u32), None, &["removed"]);
+        test_print(Some(1u32), Some(2), &["1 -> 2"]);
+        test_print(Some(vec![1u8]), Some(vec![]), &["[0]: removed"]);
+        test_print(Some(1u32), Some(1), &[]);
+    }
+
+    /// Verify that we can convert Option deltas into JSON Patches.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn option_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        test_json_patch(None, Some(1u32));
+        test_json_patch(Some(1u32), None);
+        test_json_patch(Some(1u32), Some(2));
+        test_json_patch(Some(vec![1u8, 2]), Some(vec![1]));
+        test_json_patch(Some(1u32), Some(1));
+    }
+
+    /// Verify that we can patch an Option from a borrowed patch.
+    #[test]
+    fn option_borrowed_patch() {
+        test_borrowed_patch(None, Some("a".to_string()));
+        test_borrowed_patch(Some("a".to_string()), Some("ab".to_string()));
+        test_borrowed_patch(Some("a".to_string()), None);
+        test_borrowed_patch(Some(1u32), Some(2));
     }
 }
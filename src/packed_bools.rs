@@ -0,0 +1,366 @@
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaChange, DeltaVisitor, Diffable, MergeConflicts,
+    MergeablePatches, PatchError, Patchable, PathSegment, ReversiblePatchable, SquashablePatches,
+    VisitDelta,
+};
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+/// The bool fields of a derived struct or enum variant, diffed together so that all of their
+/// changes fit in one [`BoolFlags`] delta.
+///
+/// The derive macro packs the bool fields of every struct or enum variant that has at least two of
+/// them, unless it has the `#[dipa(pack_bools = false)]` container attribute. Every bool field
+/// would otherwise need its own delta, and its own place among the `Change_*` variants of the
+/// `one_batch` delta type.
+///
+/// This holds copies of the bools along with the path to each of them, which is only used to
+/// describe a delta. [`PackedBoolsMut`] is used to patch them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedBools<const N: usize> {
+    bools: [bool; N],
+    paths: &'static [PathSegment; N],
+}
+
+/// Mutable references to the bool fields of a derived struct or enum variant, used to patch them
+/// with a [`BoolFlags`] delta.
+#[derive(Debug)]
+pub struct PackedBoolsMut<'a, const N: usize> {
+    bools: [&'a mut bool; N],
+}
+
+/// The delta for [`PackedBools`], where every set bit is a bool that flipped.
+///
+/// Serialized as the smallest unsigned integer that fits `N` bits, so up to 8 bools take one
+/// byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoolFlags<const N: usize>(u64);
+
+impl<const N: usize> PackedBools<N> {
+    /// Copy the bools, along with the path from their struct or enum variant to each of them.
+    pub fn new(bools: [&bool; N], paths: &'static [PathSegment; N]) -> Self {
+        PackedBools {
+            bools: bools.map(|b| *b),
+            paths,
+        }
+    }
+
+    fn flip(&mut self, flags: BoolFlags<N>) {
+        for (idx, b) in self.bools.iter_mut().enumerate() {
+            *b ^= flags.is_flipped(idx);
+        }
+    }
+}
+
+impl<'a, const N: usize> PackedBoolsMut<'a, N> {
+    /// Wrap the bools that patches get applied to.
+    pub fn new(bools: [&'a mut bool; N]) -> Self {
+        PackedBoolsMut { bools }
+    }
+
+    fn flip(&mut self, flags: BoolFlags<N>) {
+        for (idx, b) in self.bools.iter_mut().enumerate() {
+            **b ^= flags.is_flipped(idx);
+        }
+    }
+}
+
+impl<const N: usize> BoolFlags<N> {
+    fn is_flipped(&self, idx: usize) -> bool {
+        self.0 & (1 << idx) != 0
+    }
+}
+
+impl<'s, 'e, const N: usize> Diffable<'s, 'e, PackedBools<N>> for PackedBools<N> {
+    type Delta = BoolFlags<N>;
+
+    type DeltaOwned = BoolFlags<N>;
+
+    fn create_delta_towards(&self, end_state: &PackedBools<N>) -> CreatedDelta<Self::Delta> {
+        let flipped = self
+            .bools
+            .iter()
+            .zip(end_state.bools.iter())
+            .enumerate()
+            .filter(|(_, (start, end))| start != end)
+            .fold(0, |flipped, (idx, _)| flipped | 1 << idx);
+
+        CreatedDelta {
+            delta: BoolFlags(flipped),
+            did_change: flipped != 0,
+        }
+    }
+}
+
+impl<const N: usize> Patchable<BoolFlags<N>> for PackedBools<N> {
+    fn apply_patch(&mut self, patch: BoolFlags<N>) -> Result<(), PatchError> {
+        self.flip(patch);
+
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize> Patchable<BoolFlags<N>> for PackedBoolsMut<'a, N> {
+    fn apply_patch(&mut self, patch: BoolFlags<N>) -> Result<(), PatchError> {
+        self.flip(patch);
+
+        Ok(())
+    }
+}
+
+// Flipping the same bools again undoes a patch.
+
+impl<const N: usize> ReversiblePatchable<BoolFlags<N>> for PackedBools<N> {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: BoolFlags<N>,
+    ) -> Result<BoolFlags<N>, PatchError> {
+        self.flip(patch);
+
+        Ok(patch)
+    }
+}
+
+impl<'a, const N: usize> ReversiblePatchable<BoolFlags<N>> for PackedBoolsMut<'a, N> {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: BoolFlags<N>,
+    ) -> Result<BoolFlags<N>, PatchError> {
+        self.flip(patch);
+
+        Ok(patch)
+    }
+}
+
+impl<const N: usize> SquashablePatches<BoolFlags<N>> for PackedBools<N> {
+    fn squash_patches(
+        first: BoolFlags<N>,
+        second: BoolFlags<N>,
+    ) -> Result<BoolFlags<N>, PatchError> {
+        Ok(BoolFlags(first.0 ^ second.0))
+    }
+}
+
+// Both patches were created against the base, so a bool that either of them flipped ends up
+// flipped. When both flipped it they agree on its new value.
+impl<const N: usize> MergeablePatches<BoolFlags<N>> for PackedBools<N> {
+    fn merge_patches(
+        _base: &Self,
+        a: BoolFlags<N>,
+        b: BoolFlags<N>,
+    ) -> Result<BoolFlags<N>, MergeConflicts> {
+        Ok(BoolFlags(a.0 | b.0))
+    }
+}
+
+impl<'de, const N: usize> BorrowedDelta<'de> for PackedBools<N> {
+    type DeltaBorrowed = BoolFlags<N>;
+
+    fn apply_borrowed_patch(&mut self, patch: BoolFlags<N>) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+impl<'a, 'de, const N: usize> BorrowedDelta<'de> for PackedBoolsMut<'a, N> {
+    type DeltaBorrowed = BoolFlags<N>;
+
+    fn apply_borrowed_patch(&mut self, patch: BoolFlags<N>) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+impl<const N: usize> crate::ToOwnedDelta<BoolFlags<N>> for BoolFlags<N> {
+    fn to_owned_delta(self) -> BoolFlags<N> {
+        self
+    }
+}
+
+// Every bool is reported at its own path, since the packed bools have no path of their own.
+impl<'s, 'e, const N: usize> VisitDelta<'s, 'e> for PackedBools<N> {
+    fn visit_delta(&self, delta: &BoolFlags<N>, visitor: &mut dyn DeltaVisitor) {
+        for (idx, old) in self.bools.iter().enumerate() {
+            if delta.is_flipped(idx) {
+                visitor.enter(self.paths[idx].clone());
+                visitor.visit_change(DeltaChange::Value { old, new: &!old });
+                visitor.exit();
+            }
+        }
+    }
+}
+
+// The bools are a part of their struct or enum variant's schema, so they do not hash anything
+// beyond how many of them there are.
+impl<'s, 'e, const N: usize> crate::SchemaFingerprint<'s, 'e> for PackedBools<N> {
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("PackedBools")
+        .write_u64(N as u64)
+        .finish();
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, const N: usize> crate::ToJsonPatch<'s, 'e> for PackedBools<N> {
+    fn push_json_patch(
+        &self,
+        delta: &BoolFlags<N>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        for (idx, old) in self.bools.iter().enumerate() {
+            if delta.is_flipped(idx) {
+                let pointer = match &self.paths[idx] {
+                    PathSegment::TupleField(field_idx) => path.child(field_idx),
+                    PathSegment::Field(field_name) => path.child(field_name),
+                    // Only derived types pack bools, and they pass the paths of their fields.
+                    _ => return Err(crate::JsonPatchError::not_created_from("PackedBools")),
+                };
+
+                patch.replace(&pointer, &!old)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<const N: usize> Serialize for BoolFlags<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match N {
+            0..=8 => (self.0 as u8).serialize(serializer),
+            9..=16 => (self.0 as u16).serialize(serializer),
+            17..=32 => (self.0 as u32).serialize(serializer),
+            _ => self.0.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for BoolFlags<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let flags = match N {
+            0..=8 => u8::deserialize(deserializer)? as u64,
+            9..=16 => u16::deserialize(deserializer)? as u64,
+            17..=32 => u32::deserialize(deserializer)? as u64,
+            _ => u64::deserialize(deserializer)?,
+        };
+
+        Ok(BoolFlags(flags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::DipaImplTester;
+
+    const PATHS: &[PathSegment; 3] = &[
+        PathSegment::Field("a"),
+        PathSegment::Field("b"),
+        PathSegment::TupleField(2),
+    ];
+
+    fn bools(a: bool, b: bool, c: bool) -> PackedBools<3> {
+        PackedBools::new([&a, &b, &c], PATHS)
+    }
+
+    /// Verify that every flipped bool is a bit in a one byte delta.
+    #[test]
+    fn flipped_bools() {
+        DipaImplTester {
+            label: Some("No bools flipped"),
+            start: &mut bools(true, false, true),
+            end: &bools(true, false, true),
+            expected_delta: BoolFlags(0),
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Every bool flipped"),
+            start: &mut bools(true, false, true),
+            end: &bools(false, true, false),
+            expected_delta: BoolFlags(0b111),
+            expected_serialized_patch_size: 1,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that the delta uses the smallest integer that fits every bool.
+    #[test]
+    fn flags_size() {
+        assert_eq!(bincode::serialize(&BoolFlags::<8>(0xff)).unwrap().len(), 1);
+        assert_eq!(bincode::serialize(&BoolFlags::<9>(0x1ff)).unwrap().len(), 2);
+        assert_eq!(bincode::serialize(&BoolFlags::<32>(1)).unwrap().len(), 4);
+        assert_eq!(bincode::serialize(&BoolFlags::<64>(1)).unwrap().len(), 8);
+
+        let flags: BoolFlags<16> =
+            bincode::deserialize(&bincode::serialize(&BoolFlags::<16>(0x8001)).unwrap()).unwrap();
+        assert_eq!(flags, BoolFlags(0x8001));
+    }
+
+    /// Verify that patching through mutable references flips the referenced bools.
+    #[test]
+    fn patch_mutable_references() {
+        let (mut a, mut b) = (true, false);
+
+        let mut packed = PackedBoolsMut::new([&mut a, &mut b]);
+        let inverse = packed
+            .apply_patch_returning_inverse(BoolFlags(0b10))
+            .unwrap();
+        assert_eq!(inverse, BoolFlags(0b10));
+
+        assert_eq!((a, b), (true, true));
+    }
+
+    /// Verify that the other dipa traits work with packed bools.
+    #[test]
+    fn packed_bools_traits() {
+        test_squash(
+            bools(false, false, false),
+            bools(true, false, true),
+            bools(false, true, true),
+        );
+        test_merge(
+            bools(false, false, false),
+            bools(true, false, false),
+            bools(true, true, false),
+            bools(true, true, false),
+        );
+        test_print(
+            bools(false, false, false),
+            bools(true, false, true),
+            &["a: false -> true", "2: false -> true"],
+        );
+
+        #[cfg(feature = "json-patch")]
+        {
+            let start = bools(false, false, false);
+            let delta = start.create_delta_towards(&bools(true, false, true)).delta;
+            let patch = crate::ToJsonPatch::to_json_patch(&start, &delta).unwrap();
+
+            assert_eq!(
+                serde_json::to_string(&patch).unwrap(),
+                r#"[{"op":"replace","path":"/a","value":true},{"op":"replace","path":"/2","value":true}]"#
+            );
+        }
+    }
+
+    /// Verify that a JSON Patch can not be created for bools that are not fields.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn json_patch_needs_field_paths() {
+        let start = PackedBools::new(
+            [&false, &false],
+            &[PathSegment::Field("a"), PathSegment::Index(0)],
+        );
+
+        let delta = start.create_delta_towards(&PackedBools::new([&true, &false], start.paths));
+        assert!(crate::ToJsonPatch::to_json_patch(&start, &delta.delta).is_ok());
+
+        let delta = start.create_delta_towards(&PackedBools::new([&false, &true], start.paths));
+        assert!(crate::ToJsonPatch::to_json_patch(&start, &delta.delta).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt::{Display, Formatter};
+
+/// An error that occurred while applying a patch.
+///
+/// This typically means that the patch was created against a different start state than the one
+/// that it is being applied to, such as when a patch is stale or was sent to the wrong client.
+///
+/// When a patch fails to apply the receiver should be treated as out of sync. A common approach
+/// is to request the full state from the sender and then resume applying patches from there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    /// Stored from the innermost segment to the outermost segment since errors get their path
+    /// filled in as they bubble up.
+    reversed_path: Vec<PathSegment>,
+    reason: PatchErrorReason,
+}
+
+/// One step on the way from the root of a data structure to the value that could not be patched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A named struct field or enum variant field.
+    Field(&'static str),
+    /// A tuple, tuple struct or tuple variant field.
+    TupleField(usize),
+    /// An enum variant.
+    Variant(&'static str),
+    /// An index into a sequence.
+    Index(usize),
+    /// The value stored under some key in a map.
+    MapValue,
+}
+
+/// Why a patch could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PatchErrorReason {
+    /// The patch referred to an index that does not exist.
+    IndexOutOfBounds {
+        /// The index that the patch referred to.
+        index: usize,
+        /// The length of the sequence at the time that the index was accessed.
+        len: usize,
+    },
+    /// The patch modifies the inner value of an `Option` that is `None`.
+    OptionIsNone,
+    /// The patch modifies the value of a key that is not in the map.
+    MissingMapKey,
+    /// The patch modifies the fields of an enum variant but the enum currently holds a different
+    /// variant.
+    VariantMismatch {
+        /// The variant that the patch expected.
+        expected: &'static str,
+    },
+    /// Applying the patch would have left a `String` holding invalid UTF-8.
+    InvalidUtf8,
+    /// Applying the patch would have moved a `Quantized` value off the end of its grid.
+    QuantizedOutOfRange,
+    /// Applying the patch would have moved a `Duration` or `SystemTime` outside of the range of
+    /// times that it can hold.
+    TimeOutOfRange,
+}
+
+impl PatchError {
+    /// Create a new error for the value at the current position.
+    ///
+    /// Parent values will fill in the path as the error is returned up through them.
+    pub fn new(reason: PatchErrorReason) -> Self {
+        PatchError {
+            reversed_path: vec![],
+            reason,
+        }
+    }
+
+    /// Prepend a segment to the path of this error.
+    ///
+    /// Called by a parent value when a patch to one of its children fails.
+    pub fn with_parent(mut self, segment: PathSegment) -> Self {
+        self.reversed_path.push(segment);
+        self
+    }
+
+    /// The path from the root of the patched value to the value that could not be patched.
+    pub fn path(&self) -> impl Iterator<Item = &PathSegment> {
+        self.reversed_path.iter().rev()
+    }
+
+    /// Why the patch could not be applied.
+    pub fn reason(&self) -> &PatchErrorReason {
+        &self.reason
+    }
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("failed to apply patch")?;
+
+        if !self.reversed_path.is_empty() {
+            f.write_str(" at ")?;
+
+            fmt_path(f, self.path())?;
+        }
+
+        write!(f, ": {}", self.reason)
+    }
+}
+
+/// Display a path from the outermost value to the innermost value, such as
+/// `0.notifications[2]`.
+pub(crate) fn fmt_path<'a>(
+    f: &mut Formatter<'_>,
+    path: impl Iterator<Item = &'a PathSegment>,
+) -> std::fmt::Result {
+    for (idx, segment) in path.enumerate() {
+        match segment {
+            PathSegment::Field(name) | PathSegment::Variant(name) => {
+                if idx > 0 {
+                    f.write_str(".")?;
+                }
+                f.write_str(name)?;
+            }
+            PathSegment::TupleField(field) => {
+                if idx > 0 {
+                    f.write_str(".")?;
+                }
+                write!(f, "{}", field)?;
+            }
+            PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            PathSegment::MapValue => f.write_str("[..]")?,
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for PatchErrorReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchErrorReason::IndexOutOfBounds { index, len } => {
+                write!(f, "index {} is out of bounds for length {}", index, len)
+            }
+            PatchErrorReason::OptionIsNone => {
+                f.write_str("cannot modify the inner value of an Option that is None")
+            }
+            PatchErrorReason::MissingMapKey => f.write_str("the key is not in the map"),
+            PatchErrorReason::VariantMismatch { expected } => {
+                write!(f, "expected the {} variant", expected)
+            }
+            PatchErrorReason::InvalidUtf8 => f.write_str("the patched string is not valid UTF-8"),
+            PatchErrorReason::QuantizedOutOfRange => {
+                f.write_str("the patched quantized value is outside of its range")
+            }
+            PatchErrorReason::TimeOutOfRange => {
+                f.write_str("the patched time is outside of the range that it can hold")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that the path is displayed from the outermost value to the innermost value.
+    #[test]
+    fn display_path() {
+        let err = PatchError::new(PatchErrorReason::OptionIsNone)
+            .with_parent(PathSegment::Index(2))
+            .with_parent(PathSegment::Field("notifications"))
+            .with_parent(PathSegment::TupleField(0));
+
+        assert_eq!(
+            err.to_string(),
+            "failed to apply patch at 0.notifications[2]: \
+            cannot modify the inner value of an Option that is None"
+        );
+    }
+
+    /// Verify that errors at the root of the patched value do not display a path.
+    #[test]
+    fn display_no_path() {
+        let err = PatchError::new(PatchErrorReason::OptionIsNone);
+
+        assert_eq!(
+            err.to_string(),
+            "failed to apply patch: cannot modify the inner value of an Option that is None"
+        );
+    }
+}
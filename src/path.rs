@@ -0,0 +1,449 @@
+use crate::sequence::{
+    apply_borrowed_patch, merge_sequence_patches, squash_sequence_patches, visit_sequence_delta,
+    SequenceModificationDelta, SequenceModificationDeltaBorrowed, SequenceModificationDeltaOwned,
+};
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SquashablePatches, VisitDelta,
+};
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+
+// An `OsString` is delta encoded as a sequence of the bytes that it is encoded with, the same way
+// as a `Vec<u8>`. Those bytes are UTF-8 for strings that are valid unicode on every platform, so
+// a delta between two unicode strings can be applied anywhere. Other strings can only be patched on
+// the platform that the delta was created on.
+impl<'s, 'e> Diffable<'s, 'e, OsString> for OsString {
+    type Delta = Vec<SequenceModificationDelta<'e, u8>>;
+    type DeltaOwned = Vec<SequenceModificationDeltaOwned<u8>>;
+
+    fn create_delta_towards(&self, end_state: &'e OsString) -> CreatedDelta<Self::Delta> {
+        bytes_delta_towards(self, end_state)
+    }
+}
+
+impl Patchable<Vec<SequenceModificationDeltaOwned<u8>>> for OsString {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<(), PatchError> {
+        with_bytes(self, |bytes| bytes.apply_patch(patch))
+    }
+}
+
+impl ReversiblePatchable<Vec<SequenceModificationDeltaOwned<u8>>> for OsString {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<u8>>, PatchError> {
+        with_bytes(self, |bytes| bytes.apply_patch_returning_inverse(patch))
+    }
+}
+
+impl SquashablePatches<Vec<SequenceModificationDeltaOwned<u8>>> for OsString {
+    fn squash_patches(
+        first: Vec<SequenceModificationDeltaOwned<u8>>,
+        second: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<u8>>, PatchError> {
+        squash_sequence_patches(first, second)
+    }
+}
+
+impl MergeablePatches<Vec<SequenceModificationDeltaOwned<u8>>> for OsString {
+    fn merge_patches(
+        base: &Self,
+        a: Vec<SequenceModificationDeltaOwned<u8>>,
+        b: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<u8>>, MergeConflicts> {
+        merge_sequence_patches(base.as_encoded_bytes().len(), a, b)
+    }
+}
+
+impl<'b> Patchable<Vec<SequenceModificationDeltaBorrowed<'b, u8>>> for OsString {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'b, u8>>,
+    ) -> Result<(), PatchError> {
+        with_bytes(self, |bytes| apply_borrowed_patch(bytes, patch))
+    }
+}
+
+impl<'de> BorrowedDelta<'de> for OsString {
+    type DeltaBorrowed = Vec<SequenceModificationDeltaBorrowed<'de, u8>>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'de, u8>>,
+    ) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+impl<'s, 'e> crate::SchemaFingerprint<'s, 'e> for OsString {
+    const FINGERPRINT: u64 = <Vec<u8> as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+}
+
+impl<'s, 'e> VisitDelta<'s, 'e> for OsString {
+    fn visit_delta(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, u8>>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        visit_sequence_delta(self.as_encoded_bytes().len(), delta, visitor)
+    }
+}
+
+// serde encodes an `OsString` as an enum that holds the platform's representation of it, so the
+// JSON Patch replaces the whole string.
+#[cfg(feature = "json-patch")]
+impl<'s, 'e> crate::ToJsonPatch<'s, 'e> for OsString {
+    fn push_json_patch(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, u8>>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        if let Some(new) = patched(self, delta) {
+            patch.replace(path, &new)?;
+        }
+
+        Ok(())
+    }
+}
+
+// A `PathBuf` is delta encoded the same way as the `OsString` that it wraps.
+impl<'s, 'e> Diffable<'s, 'e, PathBuf> for PathBuf {
+    type Delta = Vec<SequenceModificationDelta<'e, u8>>;
+    type DeltaOwned = Vec<SequenceModificationDeltaOwned<u8>>;
+
+    fn create_delta_towards(&self, end_state: &'e PathBuf) -> CreatedDelta<Self::Delta> {
+        bytes_delta_towards(self.as_os_str(), end_state.as_os_str())
+    }
+}
+
+impl Patchable<Vec<SequenceModificationDeltaOwned<u8>>> for PathBuf {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<(), PatchError> {
+        with_os_string(self, |os_string| os_string.apply_patch(patch))
+    }
+}
+
+impl ReversiblePatchable<Vec<SequenceModificationDeltaOwned<u8>>> for PathBuf {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<u8>>, PatchError> {
+        with_os_string(self, |os_string| {
+            os_string.apply_patch_returning_inverse(patch)
+        })
+    }
+}
+
+impl SquashablePatches<Vec<SequenceModificationDeltaOwned<u8>>> for PathBuf {
+    fn squash_patches(
+        first: Vec<SequenceModificationDeltaOwned<u8>>,
+        second: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<u8>>, PatchError> {
+        squash_sequence_patches(first, second)
+    }
+}
+
+impl MergeablePatches<Vec<SequenceModificationDeltaOwned<u8>>> for PathBuf {
+    fn merge_patches(
+        base: &Self,
+        a: Vec<SequenceModificationDeltaOwned<u8>>,
+        b: Vec<SequenceModificationDeltaOwned<u8>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<u8>>, MergeConflicts> {
+        merge_sequence_patches(base.as_os_str().as_encoded_bytes().len(), a, b)
+    }
+}
+
+impl<'b> Patchable<Vec<SequenceModificationDeltaBorrowed<'b, u8>>> for PathBuf {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'b, u8>>,
+    ) -> Result<(), PatchError> {
+        with_os_string(self, |os_string| os_string.apply_patch(patch))
+    }
+}
+
+impl<'de> BorrowedDelta<'de> for PathBuf {
+    type DeltaBorrowed = Vec<SequenceModificationDeltaBorrowed<'de, u8>>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'de, u8>>,
+    ) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+impl<'s, 'e> crate::SchemaFingerprint<'s, 'e> for PathBuf {
+    const FINGERPRINT: u64 = <OsString as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+}
+
+impl<'s, 'e> VisitDelta<'s, 'e> for PathBuf {
+    fn visit_delta(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, u8>>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        visit_sequence_delta(self.as_os_str().as_encoded_bytes().len(), delta, visitor)
+    }
+}
+
+// serde encodes a `PathBuf` as a string, so the JSON Patch replaces the whole string.
+#[cfg(feature = "json-patch")]
+impl<'s, 'e> crate::ToJsonPatch<'s, 'e> for PathBuf {
+    fn push_json_patch(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, u8>>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        if let Some(new) = patched(self.as_os_str(), delta) {
+            patch.replace(path, &PathBuf::from(new))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn bytes_delta_towards<'e>(
+    start: &OsStr,
+    end: &'e OsStr,
+) -> CreatedDelta<Vec<SequenceModificationDelta<'e, u8>>> {
+    let start = start.as_encoded_bytes();
+    start.create_delta_towards(end.as_encoded_bytes())
+}
+
+/// Patch the encoded bytes of an `OsString`.
+///
+/// On unix the bytes are moved out of the string and back without copying them. A patch that fails
+/// part of the way through keeps the changes that it already made, the same as for a `Vec<u8>`.
+#[cfg(unix)]
+fn with_bytes<R>(
+    os_string: &mut OsString,
+    f: impl FnOnce(&mut Vec<u8>) -> Result<R, PatchError>,
+) -> Result<R, PatchError> {
+    use std::os::unix::ffi::OsStringExt;
+
+    let mut bytes = std::mem::take(os_string).into_vec();
+    let result = f(&mut bytes);
+    *os_string = OsString::from_vec(bytes);
+
+    result
+}
+
+/// Patch the encoded bytes of an `OsString`.
+///
+/// Other platforms can not check that arbitrary bytes are a valid `OsString`, so only unicode
+/// strings can be patched there. A copy of the bytes is patched, so that the string is left
+/// unchanged if the patched bytes are not valid UTF-8.
+#[cfg(not(unix))]
+fn with_bytes<R>(
+    os_string: &mut OsString,
+    f: impl FnOnce(&mut Vec<u8>) -> Result<R, PatchError>,
+) -> Result<R, PatchError> {
+    let mut bytes = os_string.as_encoded_bytes().to_vec();
+    let result = f(&mut bytes)?;
+    *os_string = String::from_utf8(bytes)
+        .map(OsString::from)
+        .map_err(|_| PatchError::new(crate::PatchErrorReason::InvalidUtf8))?;
+
+    Ok(result)
+}
+
+fn with_os_string<R>(
+    path: &mut PathBuf,
+    f: impl FnOnce(&mut OsString) -> Result<R, PatchError>,
+) -> Result<R, PatchError> {
+    let mut os_string = std::mem::take(path).into_os_string();
+    let result = f(&mut os_string);
+    *path = PathBuf::from(os_string);
+
+    result
+}
+
+/// The string after applying a delta that was created from it.
+#[cfg(feature = "json-patch")]
+fn patched(start: &OsStr, delta: &[SequenceModificationDelta<'_, u8>]) -> Option<OsString> {
+    use crate::ToOwnedDelta;
+
+    let mut new = start.to_os_string();
+    new.apply_patch(delta.to_vec().to_owned_delta()).ok()?;
+
+    Some(new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::{DipaImplTester, PatchErrorReason};
+
+    /// Verify that a path is delta encoded as the bytes that changed.
+    #[test]
+    fn path_buf_dipa() {
+        DipaImplTester {
+            label: Some("PathBuf unchanged"),
+            start: &mut PathBuf::from("/tmp/a.txt"),
+            end: &PathBuf::from("/tmp/a.txt"),
+            expected_delta: vec![],
+            // 1 for vec length
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("PathBuf file name changed"),
+            start: &mut PathBuf::from("/tmp/a.txt"),
+            end: &PathBuf::from("/tmp/b.txt"),
+            expected_delta: vec![SequenceModificationDelta::ReplaceOne {
+                index: 5,
+                new: &b'b',
+            }],
+            // 1 for vec length, 1 for the variant, 1 for the index, 1 for the byte
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that we can diff and patch an `OsString`.
+    #[test]
+    fn os_string_dipa() {
+        DipaImplTester {
+            label: Some("OsString appended to"),
+            start: &mut OsString::from("abc"),
+            end: &OsString::from("abcd"),
+            expected_delta: vec![SequenceModificationDelta::AppendOne { item: &b'd' }],
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that patches that change different components of a path merge, and that
+    /// consecutive patches squash.
+    #[test]
+    fn squash_and_merge_path_patches() {
+        test_squash(
+            PathBuf::from("/a"),
+            PathBuf::from("/a/b"),
+            PathBuf::from("/c/b"),
+        );
+        test_merge(
+            PathBuf::from("/a/b"),
+            PathBuf::from("/x/b"),
+            PathBuf::from("/a/y"),
+            PathBuf::from("/x/y"),
+        );
+    }
+
+    /// Verify that a patch that does not fit the path fails and leaves the path unchanged.
+    #[test]
+    fn path_patch_out_of_bounds() {
+        let mut path = PathBuf::from("/a");
+
+        let err = path
+            .apply_patch(vec![SequenceModificationDeltaOwned::DeleteOne { index: 5 }])
+            .unwrap_err();
+
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::IndexOutOfBounds { index: 5, len: 2 }
+        );
+        assert_eq!(path, PathBuf::from("/a"));
+    }
+
+    /// Verify that strings that are not unicode can be diffed and patched on unix, including from
+    /// a borrowed patch.
+    #[cfg(unix)]
+    #[test]
+    fn non_unicode_os_string() {
+        use std::os::unix::ffi::OsStringExt;
+
+        DipaImplTester {
+            label: Some("OsString with invalid UTF-8"),
+            start: &mut OsString::from_vec(vec![0xff, b'a']),
+            end: &OsString::from_vec(vec![0xfe, b'a']),
+            expected_delta: vec![SequenceModificationDelta::ReplaceFirst { item: &0xfe }],
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        test_borrowed_patch(
+            OsString::from("abc"),
+            OsString::from_vec(vec![b'a', 0xff, 0xfe, b'c']),
+        );
+    }
+
+    /// Verify that patching an `OsString` on unix reuses its bytes instead of copying them.
+    #[cfg(unix)]
+    #[test]
+    fn os_string_patch_reuses_bytes() {
+        let mut os_string = OsString::from("abc");
+        let bytes = os_string.as_encoded_bytes().as_ptr();
+
+        os_string
+            .apply_patch(vec![SequenceModificationDeltaOwned::ReplaceOne {
+                index: 1,
+                new: b'x',
+            }])
+            .unwrap();
+
+        assert_eq!(os_string, "axc");
+        assert_eq!(os_string.as_encoded_bytes().as_ptr(), bytes);
+    }
+
+    /// Verify that a patch that makes an `OsString` invalid UTF-8 fails on platforms other than
+    /// unix and leaves the string unchanged.
+    #[cfg(not(unix))]
+    #[test]
+    fn invalid_utf8_patch_errors() {
+        let mut os_string = OsString::from("abc");
+
+        let err = os_string
+            .apply_patch(vec![SequenceModificationDeltaOwned::ReplaceOne {
+                index: 1,
+                new: 0xff,
+            }])
+            .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::InvalidUtf8);
+        assert_eq!(os_string, "abc");
+    }
+
+    /// Verify that changes to a path are described with the bytes that changed.
+    #[test]
+    fn print_path_delta() {
+        test_print(PathBuf::from("/a"), PathBuf::from("/b"), &["[1]: replaced"]);
+    }
+
+    /// Verify that a path's JSON Patch replaces the whole path, since serde encodes it as a
+    /// string.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn path_json_patch() {
+        use crate::json_patch::test_json_patch;
+        use crate::ToJsonPatch;
+
+        let start = PathBuf::from("/tmp/a");
+        let end = PathBuf::from("/tmp/b");
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(
+            serde_json::to_string(&start.to_json_patch(&delta).unwrap()).unwrap(),
+            r#"[{"op":"replace","path":"","value":"/tmp/b"}]"#
+        );
+
+        test_json_patch(OsString::from("abc"), OsString::from("abcd"));
+    }
+}
@@ -0,0 +1,569 @@
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaChange, DeltaVisitor, Diffable, MergeConflictReason,
+    MergeConflicts, MergeablePatches, PatchError, PatchErrorReason, Patchable, ReversiblePatchable,
+    SquashablePatches, VisitDelta,
+};
+use serde::de::{Deserialize, Deserializer, Error as _, Unexpected};
+use serde::ser::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// A float that is stored as a point on a fixed-point grid between `MIN` and `MAX` with
+/// `2^BITS` points.
+///
+/// Floats are normally sent in full whenever they change, so a changed `f32` always costs 4
+/// bytes. A `Quantized` is delta encoded as the number of grid points that it moved by, so small
+/// movements take 1-2 bytes.
+///
+/// Only the grid point is stored, so the value after applying a patch is exactly the same as the
+/// sender's value. Values outside of the range are clamped to it.
+///
+/// A position between -1000 and 1000 meters with millimeter precision needs `2_000_000` grid
+/// points, which fits in 21 bits.
+///
+/// ```
+/// # use dipa::{Diffable, Patchable, Quantized};
+/// type Position = Quantized<f32, -1000, 1000, 21>;
+///
+/// let mut start = Position::new(10.0);
+/// let end = Position::new(10.05);
+///
+/// let delta = start.create_delta_towards(&end).delta;
+/// start.apply_patch(delta).unwrap();
+///
+/// assert_eq!(start.get(), end.get());
+/// assert!((start.get() - 10.05).abs() < 0.001);
+/// ```
+///
+/// A `Quantized` serializes as the index of its grid point.
+pub struct Quantized<F, const MIN: i64, const MAX: i64, const BITS: u8> {
+    index: u64,
+    float: PhantomData<F>,
+}
+
+/// A float type that can be [`Quantized`].
+///
+/// This is implemented for `f32` and `f64` and can not be implemented outside of dipa.
+pub trait QuantizedFloat: Copy + sealed::Sealed {
+    /// The number of bits of precision that the float has, which is the most `BITS` that a
+    /// [`Quantized`] float can use.
+    const MANTISSA_DIGITS: u8;
+
+    #[doc(hidden)]
+    fn to_f64(self) -> f64;
+
+    #[doc(hidden)]
+    fn from_f64(value: f64) -> Self;
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+impl QuantizedFloat for f32 {
+    const MANTISSA_DIGITS: u8 = f32::MANTISSA_DIGITS as u8;
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl QuantizedFloat for f64 {
+    const MANTISSA_DIGITS: u8 = f64::MANTISSA_DIGITS as u8;
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+impl<F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8>
+    Quantized<F, MIN, MAX, BITS>
+{
+    /// The index of the grid point at `MAX`.
+    ///
+    /// Evaluating this fails to compile if the range is empty or if there are more grid points
+    /// than the float can tell apart.
+    const MAX_INDEX: u64 = {
+        assert!(MIN < MAX, "Quantized MIN must be less than MAX");
+        assert!(
+            BITS >= 1 && BITS <= F::MANTISSA_DIGITS,
+            "Quantized BITS must be between 1 and the float's number of mantissa digits"
+        );
+
+        (1 << BITS) - 1
+    };
+
+    /// Quantize a value to the nearest grid point, clamping it to the range.
+    ///
+    /// `NaN` is quantized to `MIN`.
+    pub fn new(value: F) -> Self {
+        let grid_points =
+            (value.to_f64() - MIN as f64) / (MAX as f64 - MIN as f64) * Self::MAX_INDEX as f64;
+
+        Quantized {
+            // Casting saturates, so this clamps negative values and NaN to 0.
+            index: (grid_points.round() as u64).min(Self::MAX_INDEX),
+            float: PhantomData,
+        }
+    }
+
+    /// The value of the grid point.
+    pub fn get(&self) -> F {
+        let fraction = self.index as f64 / Self::MAX_INDEX as f64;
+
+        F::from_f64(MIN as f64 + (MAX as f64 - MIN as f64) * fraction)
+    }
+
+    /// The index of the grid point, from `0` at `MIN` up to `2^BITS - 1` at `MAX`.
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// The grid point at some index, or `None` if the index is past `MAX`.
+    pub fn from_index(index: u64) -> Option<Self> {
+        match index <= Self::MAX_INDEX {
+            true => Some(Quantized {
+                index,
+                float: PhantomData,
+            }),
+            false => None,
+        }
+    }
+
+    /// The grid point after moving by a zigzag encoded number of grid points.
+    fn moved_by(&self, difference: u64) -> Result<Self, PatchError> {
+        (self.index as i64)
+            .checked_add(decode_difference(difference))
+            .filter(|index| *index >= 0)
+            .and_then(|index| Self::from_index(index as u64))
+            .ok_or_else(|| PatchError::new(PatchErrorReason::QuantizedOutOfRange))
+    }
+}
+
+// The difference is zigzag encoded so that small movements in either direction stay small. Grid
+// indices have at most 53 bits, so the differences between them never overflow. A patch that did
+// not come from `create_delta_towards` can hold any difference though, so differences that are
+// decoded from a patch are added with overflow checks.
+fn encode_difference(difference: i64) -> u64 {
+    ((difference << 1) ^ (difference >> 63)) as u64
+}
+
+fn decode_difference(difference: u64) -> i64 {
+    ((difference >> 1) as i64) ^ -((difference & 1) as i64)
+}
+
+impl<'s, 'e, F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8>
+    Diffable<'s, 'e, Quantized<F, MIN, MAX, BITS>> for Quantized<F, MIN, MAX, BITS>
+{
+    type Delta = Option<u64>;
+
+    type DeltaOwned = Option<u64>;
+
+    fn create_delta_towards(&self, end_state: &Self) -> CreatedDelta<Self::Delta> {
+        let did_change = self.index != end_state.index;
+
+        let delta = match did_change {
+            true => Some(encode_difference(
+                end_state.index as i64 - self.index as i64,
+            )),
+            false => None,
+        };
+
+        CreatedDelta { delta, did_change }
+    }
+}
+
+impl<F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8> Patchable<Option<u64>>
+    for Quantized<F, MIN, MAX, BITS>
+{
+    fn apply_patch(&mut self, patch: Option<u64>) -> Result<(), PatchError> {
+        if let Some(difference) = patch {
+            *self = self.moved_by(difference)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8>
+    ReversiblePatchable<Option<u64>> for Quantized<F, MIN, MAX, BITS>
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Option<u64>,
+    ) -> Result<Option<u64>, PatchError> {
+        let difference = match patch {
+            Some(difference) => difference,
+            None => return Ok(None),
+        };
+
+        *self = self.moved_by(difference)?;
+
+        Ok(Some(encode_difference(-decode_difference(difference))))
+    }
+}
+
+// Both movements are applied one after the other, so the squashed movement is their sum. A sum that
+// is larger than the grid could never be applied.
+impl<F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8>
+    SquashablePatches<Option<u64>> for Quantized<F, MIN, MAX, BITS>
+{
+    fn squash_patches(first: Option<u64>, second: Option<u64>) -> Result<Option<u64>, PatchError> {
+        match (first, second) {
+            (Some(first), Some(second)) => {
+                let sum = decode_difference(first)
+                    .checked_add(decode_difference(second))
+                    .filter(|sum| sum.unsigned_abs() <= Self::MAX_INDEX)
+                    .ok_or_else(|| PatchError::new(PatchErrorReason::QuantizedOutOfRange))?;
+
+                Ok(Some(encode_difference(sum)).filter(|_| sum != 0))
+            }
+            (first, second) => Ok(second.or(first)),
+        }
+    }
+}
+
+// Patches created against the same base moved to the same grid point if and only if they moved by
+// the same difference.
+impl<F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8>
+    MergeablePatches<Option<u64>> for Quantized<F, MIN, MAX, BITS>
+{
+    fn merge_patches(
+        _base: &Self,
+        a: Option<u64>,
+        b: Option<u64>,
+    ) -> Result<Option<u64>, MergeConflicts> {
+        match (a, b) {
+            (Some(a), Some(b)) if a != b => {
+                Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange))
+            }
+            (a, b) => Ok(a.or(b)),
+        }
+    }
+}
+
+impl<'de, F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8> BorrowedDelta<'de>
+    for Quantized<F, MIN, MAX, BITS>
+{
+    type DeltaBorrowed = Option<u64>;
+
+    fn apply_borrowed_patch(&mut self, patch: Option<u64>) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+impl<'s, 'e, F: QuantizedFloat + Debug, const MIN: i64, const MAX: i64, const BITS: u8>
+    VisitDelta<'s, 'e> for Quantized<F, MIN, MAX, BITS>
+{
+    fn visit_delta(&self, delta: &Option<u64>, visitor: &mut dyn DeltaVisitor) {
+        let end = match delta.map(|difference| self.moved_by(difference)) {
+            Some(Ok(end)) => end,
+            _ => return,
+        };
+
+        visitor.visit_change(DeltaChange::Value {
+            old: &self.get(),
+            new: &end.get(),
+        });
+    }
+}
+
+impl<'s, 'e, F, const MIN: i64, const MAX: i64, const BITS: u8> crate::SchemaFingerprint<'s, 'e>
+    for Quantized<F, MIN, MAX, BITS>
+where
+    F: QuantizedFloat + crate::SchemaFingerprint<'s, 'e>,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("Quantized")
+        .write_u64(F::FINGERPRINT)
+        .write_u64(MIN as u64)
+        .write_u64(MAX as u64)
+        .write_u64(BITS as u64)
+        .finish();
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8>
+    crate::ToJsonPatch<'s, 'e> for Quantized<F, MIN, MAX, BITS>
+{
+    fn push_json_patch(
+        &self,
+        delta: &Option<u64>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        match delta.map(|difference| self.moved_by(difference)) {
+            Some(Ok(end)) => patch.replace(path, &end),
+            Some(Err(_)) => Err(crate::JsonPatchError::not_created_from("Quantized")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8> Serialize
+    for Quantized<F, MIN, MAX, BITS>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.index.serialize(serializer)
+    }
+}
+
+impl<'de, F: QuantizedFloat, const MIN: i64, const MAX: i64, const BITS: u8> Deserialize<'de>
+    for Quantized<F, MIN, MAX, BITS>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u64::deserialize(deserializer)?;
+
+        Self::from_index(index).ok_or_else(|| {
+            D::Error::invalid_value(Unexpected::Unsigned(index), &"a grid index within BITS")
+        })
+    }
+}
+
+impl<F: QuantizedFloat + Debug, const MIN: i64, const MAX: i64, const BITS: u8> Debug
+    for Quantized<F, MIN, MAX, BITS>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Quantized").field(&self.get()).finish()
+    }
+}
+
+// The float type does not implement every trait that the grid index does, so these are
+// implemented by hand instead of derived.
+
+impl<F, const MIN: i64, const MAX: i64, const BITS: u8> Clone for Quantized<F, MIN, MAX, BITS> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F, const MIN: i64, const MAX: i64, const BITS: u8> Copy for Quantized<F, MIN, MAX, BITS> {}
+
+impl<F, const MIN: i64, const MAX: i64, const BITS: u8> PartialEq for Quantized<F, MIN, MAX, BITS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<F, const MIN: i64, const MAX: i64, const BITS: u8> Eq for Quantized<F, MIN, MAX, BITS> {}
+
+impl<F, const MIN: i64, const MAX: i64, const BITS: u8> PartialOrd
+    for Quantized<F, MIN, MAX, BITS>
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F, const MIN: i64, const MAX: i64, const BITS: u8> Ord for Quantized<F, MIN, MAX, BITS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<F, const MIN: i64, const MAX: i64, const BITS: u8> Hash for Quantized<F, MIN, MAX, BITS> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeltaPrinter, DipaImplTester};
+
+    /// Millimeter precision between -1000 and 1000 meters.
+    type Position = Quantized<f32, -1000, 1000, 21>;
+
+    /// Verify that small movements are encoded as a small number of grid points.
+    #[test]
+    fn small_movements() {
+        DipaImplTester {
+            label: Some("No movement"),
+            start: &mut Position::new(10.),
+            end: &Position::new(10.),
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Move forwards 5 centimeters"),
+            start: &mut Position::new(10.),
+            end: &Position::new(10.05),
+            expected_delta: Some(106),
+            // 1 for the Option and 1 for the difference
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Move backwards 1 meter"),
+            start: &mut Position::new(10.),
+            end: &Position::new(9.),
+            expected_delta: Some(2095),
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that values are quantized to the nearest grid point within the range.
+    #[test]
+    fn quantize_to_grid() {
+        type Percent = Quantized<f64, 0, 100, 8>;
+
+        assert_eq!(Percent::new(0.).index(), 0);
+        assert_eq!(Percent::new(100.).index(), 255);
+        assert_eq!(Percent::new(50.).index(), 128);
+
+        assert_eq!(Percent::new(-5.).get(), 0.);
+        assert_eq!(Percent::new(500.).get(), 100.);
+        assert_eq!(Percent::new(f64::NAN).get(), 0.);
+
+        assert!((Position::new(12.3456).get() - 12.3456).abs() < 0.0005);
+    }
+
+    /// Verify that a patched value is exactly the same as the value that the patch was created
+    /// from, even after many patches.
+    #[test]
+    fn patched_values_are_exact() {
+        let mut server = Position::new(0.);
+        let mut client = server;
+
+        for step in 1..1000 {
+            let next = Position::new((step as f32 * 0.731).sin() * 900.);
+
+            client
+                .apply_patch(server.create_delta_towards(&next).delta)
+                .unwrap();
+            server = next;
+
+            assert_eq!(client.get().to_bits(), server.get().to_bits());
+        }
+    }
+
+    /// Verify that a patch that moves past the end of the grid is an error.
+    #[test]
+    fn move_out_of_range() {
+        let mut max = Position::new(1000.);
+
+        let err = max.apply_patch(Some(encode_difference(1))).unwrap_err();
+        assert_eq!(err.reason(), &PatchErrorReason::QuantizedOutOfRange);
+
+        let mut min = Position::new(-1000.);
+        assert!(min.apply_patch(Some(encode_difference(-1))).is_err());
+    }
+
+    /// Verify that a quantized value serializes as its grid index and rejects indices past the
+    /// end of its grid.
+    #[test]
+    fn serialize_index() {
+        type Small = Quantized<f32, 0, 1, 2>;
+
+        assert_eq!(serde_json::to_string(&Small::new(1.)).unwrap(), "3");
+        assert_eq!(serde_json::from_str::<Small>("2").unwrap().index(), 2);
+        assert!(serde_json::from_str::<Small>("4").is_err());
+    }
+
+    /// Verify that patches with differences that are far larger than any grid, such as patches
+    /// that were corrupted, are errors instead of overflowing.
+    #[test]
+    fn extreme_differences() {
+        for difference in [u64::MAX - 1, u64::MAX, u64::MAX - 3] {
+            let mut max = Position::new(1000.);
+            let err = max.apply_patch(Some(difference)).unwrap_err();
+            assert_eq!(err.reason(), &PatchErrorReason::QuantizedOutOfRange);
+
+            let mut min = Position::new(-1000.);
+            assert!(min.apply_patch(Some(difference)).is_err());
+            assert!(min.apply_patch_returning_inverse(Some(difference)).is_err());
+
+            let err = Position::squash_patches(Some(difference), Some(difference)).unwrap_err();
+            assert_eq!(err.reason(), &PatchErrorReason::QuantizedOutOfRange);
+        }
+
+        assert!(DeltaPrinter::print(&Position::new(0.), &Some(u64::MAX - 1))
+            .to_string()
+            .is_empty());
+    }
+
+    /// Verify that squashed movements are summed, that movements that cancel out squash to no
+    /// movement and that a sum that is larger than the grid is an error.
+    #[test]
+    fn squash_movements() {
+        let start = Position::new(1.);
+        let middle = Position::new(2.);
+
+        let forwards = start.create_delta_towards(&middle).delta;
+        let halfway_back = middle.create_delta_towards(&Position::new(1.5)).delta;
+        let back = middle.create_delta_towards(&start).delta;
+
+        let squashed = Position::squash_patches(forwards, halfway_back).unwrap();
+        assert_eq!(
+            squashed,
+            start.create_delta_towards(&Position::new(1.5)).delta
+        );
+
+        assert_eq!(Position::squash_patches(forwards, back).unwrap(), None);
+
+        let across = encode_difference(Position::MAX_INDEX as i64);
+        assert!(Position::squash_patches(Some(across), Some(encode_difference(1))).is_err());
+    }
+
+    /// Verify that patches that move to the same grid point merge and that patches that move to
+    /// different grid points conflict.
+    #[test]
+    fn merge_movements() {
+        let base = Position::new(0.);
+        let moved = base.create_delta_towards(&Position::new(2.)).delta;
+
+        assert_eq!(Position::merge_patches(&base, moved, moved).unwrap(), moved);
+        assert_eq!(Position::merge_patches(&base, None, moved).unwrap(), moved);
+        assert!(
+            Position::merge_patches(&base, Some(2), Some(4)).is_err(),
+            "Moving to different grid points conflicts"
+        );
+    }
+
+    /// Verify that a delta prints the values of the grid points rather than the difference.
+    #[test]
+    fn print_grid_points() {
+        type Percent = Quantized<f64, 0, 100, 8>;
+
+        let start = Percent::new(0.);
+        let delta = start.create_delta_towards(&Percent::new(100.)).delta;
+
+        assert_eq!(
+            DeltaPrinter::print(&start, &delta).to_string(),
+            "0.0 -> 100.0"
+        );
+    }
+
+    /// Verify that applying a quantized value's JSON Patch replaces it with its new grid index.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn quantized_json_patch() {
+        crate::json_patch::test_json_patch(Position::new(1.), Position::new(2.));
+
+        use crate::ToJsonPatch;
+        assert!(Position::new(0.)
+            .to_json_patch(&Some(u64::MAX - 1))
+            .is_err());
+    }
+}
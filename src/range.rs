@@ -0,0 +1,610 @@
+use crate::delta_n::{Delta2, DeltaOwned2};
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, PathSegment, ReversiblePatchable, SquashablePatches, VisitDelta,
+};
+use std::ops::{Range, RangeInclusive};
+
+// A range is delta encoded the same way as a struct with `start` and `end` fields, so only the
+// bounds that changed are sent.
+impl<'s, 'e, T: Diffable<'s, 'e, T>> Diffable<'s, 'e, Range<T>> for Range<T> {
+    type Delta = Delta2<<T as Diffable<'s, 'e, T>>::Delta, <T as Diffable<'s, 'e, T>>::Delta>;
+    type DeltaOwned =
+        DeltaOwned2<<T as Diffable<'s, 'e, T>>::DeltaOwned, <T as Diffable<'s, 'e, T>>::DeltaOwned>;
+
+    fn create_delta_towards(&'s self, end_state: &'e Range<T>) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e Range<T>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        bounds_delta_towards(
+            (&self.start, &self.end),
+            (&end_state.start, &end_state.end),
+            version,
+        )
+    }
+}
+
+impl<'s, 'e, T> Patchable<<Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned> for Range<T>
+where
+    T: Diffable<'s, 'e, T> + Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn apply_patch(
+        &mut self,
+        patch: <Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned,
+    ) -> Result<(), PatchError> {
+        let (start, end) = patch.into_fields();
+
+        if let Some(start) = start {
+            self.start.apply_patch(start).map_err(start_parent)?;
+        }
+        if let Some(end) = end {
+            self.end.apply_patch(end).map_err(end_parent)?;
+        }
+
+        Ok(())
+    }
+}
+
+type BoundsDeltaBorrowed<'de, T> =
+    DeltaOwned2<<T as BorrowedDelta<'de>>::DeltaBorrowed, <T as BorrowedDelta<'de>>::DeltaBorrowed>;
+
+impl<'de, T: BorrowedDelta<'de>> BorrowedDelta<'de> for Range<T> {
+    type DeltaBorrowed = BoundsDeltaBorrowed<'de, T>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: BoundsDeltaBorrowed<'de, T>,
+    ) -> Result<(), PatchError> {
+        let (start, end) = patch.into_fields();
+
+        if let Some(start) = start {
+            self.start
+                .apply_borrowed_patch(start)
+                .map_err(start_parent)?;
+        }
+        if let Some(end) = end {
+            self.end.apply_borrowed_patch(end).map_err(end_parent)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'s, 'e, T> ReversiblePatchable<<Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned>
+    for Range<T>
+where
+    T: Diffable<'s, 'e, T> + ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: <Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned,
+    ) -> Result<<Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned, PatchError> {
+        let (start, end) = patch.into_fields();
+
+        let start = start
+            .map(|patch| self.start.apply_patch_returning_inverse(patch))
+            .transpose()
+            .map_err(start_parent)?;
+        let end = end
+            .map(|patch| self.end.apply_patch_returning_inverse(patch))
+            .transpose()
+            .map_err(end_parent)?;
+
+        Ok(DeltaOwned2::from_fields((start, end)))
+    }
+}
+
+impl<'s, 'e, T> SquashablePatches<<Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned> for Range<T>
+where
+    T: Diffable<'s, 'e, T> + SquashablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn squash_patches(
+        first: <Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned,
+        second: <Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned,
+    ) -> Result<<Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned, PatchError> {
+        let (first_start, first_end) = first.into_fields();
+        let (second_start, second_end) = second.into_fields();
+
+        let start = match (first_start, second_start) {
+            (Some(first), Some(second)) => {
+                Some(T::squash_patches(first, second).map_err(start_parent)?)
+            }
+            (first, second) => second.or(first),
+        };
+        let end = match (first_end, second_end) {
+            (Some(first), Some(second)) => {
+                Some(T::squash_patches(first, second).map_err(end_parent)?)
+            }
+            (first, second) => second.or(first),
+        };
+
+        Ok(DeltaOwned2::from_fields((start, end)))
+    }
+}
+
+impl<'s, 'e, T> MergeablePatches<<Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned> for Range<T>
+where
+    T: Diffable<'s, 'e, T> + MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn merge_patches(
+        base: &Self,
+        a: <Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned,
+        b: <Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned,
+    ) -> Result<<Range<T> as Diffable<'s, 'e, Range<T>>>::DeltaOwned, MergeConflicts> {
+        merge_bounds((&base.start, &base.end), a, b)
+    }
+}
+
+impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for Range<T>
+where
+    T: crate::SchemaFingerprint<'s, 'e>,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("Range")
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+impl<'s, 'e, T: VisitDelta<'s, 'e>> VisitDelta<'s, 'e> for Range<T> {
+    fn visit_delta(
+        &self,
+        delta: &Delta2<<T as Diffable<'s, 'e, T>>::Delta, <T as Diffable<'s, 'e, T>>::Delta>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        visit_bounds((&self.start, &self.end), delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T: crate::ToJsonPatch<'s, 'e>> crate::ToJsonPatch<'s, 'e> for Range<T> {
+    fn push_json_patch(
+        &self,
+        delta: &Delta2<<T as Diffable<'s, 'e, T>>::Delta, <T as Diffable<'s, 'e, T>>::Delta>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        push_bounds_json_patch((&self.start, &self.end), delta, path, patch)
+    }
+}
+
+// The bounds of a `RangeInclusive` can only be moved out of it by value, so patches are applied to
+// a clone of its bounds.
+impl<'s, 'e, T: Diffable<'s, 'e, T>> Diffable<'s, 'e, RangeInclusive<T>> for RangeInclusive<T> {
+    type Delta = Delta2<<T as Diffable<'s, 'e, T>>::Delta, <T as Diffable<'s, 'e, T>>::Delta>;
+    type DeltaOwned =
+        DeltaOwned2<<T as Diffable<'s, 'e, T>>::DeltaOwned, <T as Diffable<'s, 'e, T>>::DeltaOwned>;
+
+    fn create_delta_towards(
+        &'s self,
+        end_state: &'e RangeInclusive<T>,
+    ) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e RangeInclusive<T>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        bounds_delta_towards(
+            (self.start(), self.end()),
+            (end_state.start(), end_state.end()),
+            version,
+        )
+    }
+}
+
+impl<'s, 'e, T> Patchable<<RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned>
+    for RangeInclusive<T>
+where
+    T: Clone + Diffable<'s, 'e, T> + Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn apply_patch(
+        &mut self,
+        patch: <RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned,
+    ) -> Result<(), PatchError> {
+        with_range(self, |range| range.apply_patch(patch))
+    }
+}
+
+impl<'de, T: Clone + BorrowedDelta<'de>> BorrowedDelta<'de> for RangeInclusive<T> {
+    type DeltaBorrowed = BoundsDeltaBorrowed<'de, T>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: BoundsDeltaBorrowed<'de, T>,
+    ) -> Result<(), PatchError> {
+        with_range(self, |range| range.apply_borrowed_patch(patch))
+    }
+}
+
+impl<'s, 'e, T>
+    ReversiblePatchable<<RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned>
+    for RangeInclusive<T>
+where
+    T: Clone + Diffable<'s, 'e, T> + ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: <RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned,
+    ) -> Result<<RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned, PatchError>
+    {
+        with_range(self, |range| range.apply_patch_returning_inverse(patch))
+    }
+}
+
+impl<'s, 'e, T>
+    SquashablePatches<<RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned>
+    for RangeInclusive<T>
+where
+    T: Clone + Diffable<'s, 'e, T> + SquashablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn squash_patches(
+        first: <RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned,
+        second: <RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned,
+    ) -> Result<<RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned, PatchError>
+    {
+        Range::<T>::squash_patches(first, second)
+    }
+}
+
+impl<'s, 'e, T>
+    MergeablePatches<<RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned>
+    for RangeInclusive<T>
+where
+    T: Clone + Diffable<'s, 'e, T> + MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    fn merge_patches(
+        base: &Self,
+        a: <RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned,
+        b: <RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned,
+    ) -> Result<
+        <RangeInclusive<T> as Diffable<'s, 'e, RangeInclusive<T>>>::DeltaOwned,
+        MergeConflicts,
+    > {
+        merge_bounds((base.start(), base.end()), a, b)
+    }
+}
+
+impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for RangeInclusive<T>
+where
+    T: crate::SchemaFingerprint<'s, 'e>,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("RangeInclusive")
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+impl<'s, 'e, T: VisitDelta<'s, 'e>> VisitDelta<'s, 'e> for RangeInclusive<T> {
+    fn visit_delta(
+        &self,
+        delta: &Delta2<<T as Diffable<'s, 'e, T>>::Delta, <T as Diffable<'s, 'e, T>>::Delta>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        visit_bounds((self.start(), self.end()), delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T: crate::ToJsonPatch<'s, 'e>> crate::ToJsonPatch<'s, 'e> for RangeInclusive<T> {
+    fn push_json_patch(
+        &self,
+        delta: &Delta2<<T as Diffable<'s, 'e, T>>::Delta, <T as Diffable<'s, 'e, T>>::Delta>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        push_bounds_json_patch((self.start(), self.end()), delta, path, patch)
+    }
+}
+
+fn bounds_delta_towards<'s, 'e, T: Diffable<'s, 'e, T>>(
+    (start, end): (&'s T, &'s T),
+    (new_start, new_end): (&'e T, &'e T),
+    version: u32,
+) -> CreatedDelta<Delta2<T::Delta, T::Delta>> {
+    let start = start.create_delta_towards_version(new_start, version);
+    let end = end.create_delta_towards_version(new_end, version);
+
+    let did_change = start.did_change || end.did_change;
+    let delta = Delta2::from_fields((
+        if start.did_change {
+            Some(start.delta)
+        } else {
+            None
+        },
+        if end.did_change {
+            Some(end.delta)
+        } else {
+            None
+        },
+    ));
+
+    CreatedDelta { delta, did_change }
+}
+
+fn merge_bounds<'s, 'e, T>(
+    (start, end): (&T, &T),
+    a: DeltaOwned2<T::DeltaOwned, T::DeltaOwned>,
+    b: DeltaOwned2<T::DeltaOwned, T::DeltaOwned>,
+) -> Result<DeltaOwned2<T::DeltaOwned, T::DeltaOwned>, MergeConflicts>
+where
+    T: Diffable<'s, 'e, T> + MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+{
+    let (a_start, a_end) = a.into_fields();
+    let (b_start, b_end) = b.into_fields();
+
+    let mut conflicts = MergeConflicts::default();
+
+    let start = match (a_start, b_start) {
+        (Some(a), Some(b)) => T::merge_patches(start, a, b)
+            .map_err(|err| conflicts.extend(err.with_parent(PathSegment::Field("start"))))
+            .ok(),
+        (a, b) => a.or(b),
+    };
+    let end = match (a_end, b_end) {
+        (Some(a), Some(b)) => T::merge_patches(end, a, b)
+            .map_err(|err| conflicts.extend(err.with_parent(PathSegment::Field("end"))))
+            .ok(),
+        (a, b) => a.or(b),
+    };
+
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+
+    Ok(DeltaOwned2::from_fields((start, end)))
+}
+
+fn visit_bounds<'s, 'e, T: VisitDelta<'s, 'e>>(
+    (start, end): (&T, &T),
+    delta: &Delta2<T::Delta, T::Delta>,
+    visitor: &mut dyn DeltaVisitor,
+) {
+    let (start_delta, end_delta) = delta.fields();
+
+    if let Some(start_delta) = start_delta {
+        visitor.enter(PathSegment::Field("start"));
+        start.visit_delta(start_delta, visitor);
+        visitor.exit();
+    }
+    if let Some(end_delta) = end_delta {
+        visitor.enter(PathSegment::Field("end"));
+        end.visit_delta(end_delta, visitor);
+        visitor.exit();
+    }
+}
+
+#[cfg(feature = "json-patch")]
+fn push_bounds_json_patch<'s, 'e, T: crate::ToJsonPatch<'s, 'e>>(
+    (start, end): (&T, &T),
+    delta: &Delta2<T::Delta, T::Delta>,
+    path: &crate::JsonPointer,
+    patch: &mut crate::JsonPatch,
+) -> Result<(), crate::JsonPatchError> {
+    let (start_delta, end_delta) = delta.fields();
+
+    if let Some(start_delta) = start_delta {
+        start.push_json_patch(start_delta, &path.child("start"), patch)?;
+    }
+    if let Some(end_delta) = end_delta {
+        end.push_json_patch(end_delta, &path.child("end"), patch)?;
+    }
+
+    Ok(())
+}
+
+/// Patch the bounds of a `RangeInclusive` as a `Range`. The range is left unchanged if the patch
+/// fails.
+fn with_range<T: Clone, R>(
+    range: &mut RangeInclusive<T>,
+    f: impl FnOnce(&mut Range<T>) -> Result<R, PatchError>,
+) -> Result<R, PatchError> {
+    let mut bounds = range.start().clone()..range.end().clone();
+    let result = f(&mut bounds)?;
+    *range = bounds.start..=bounds.end;
+
+    Ok(result)
+}
+
+fn start_parent(err: PatchError) -> PatchError {
+    err.with_parent(PathSegment::Field("start"))
+}
+
+fn end_parent(err: PatchError) -> PatchError {
+    err.with_parent(PathSegment::Field("end"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
+    use crate::{DipaImplTester, PatchErrorReason};
+
+    /// Verify that only the bounds of a range that changed are delta encoded.
+    #[test]
+    fn range_dipa() {
+        DipaImplTester {
+            label: Some("Range unchanged"),
+            start: &mut (1u32..5),
+            end: &(1u32..5),
+            expected_delta: Delta2::NoChange,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Range end changed"),
+            start: &mut (1u32..5),
+            end: &(1u32..9),
+            expected_delta: Delta2::Change_1(Some(9)),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Range both bounds changed"),
+            start: &mut (1u32..5),
+            end: &(2u32..9),
+            expected_delta: Delta2::Change_0_1(Some(2), Some(9)),
+            expected_serialized_patch_size: 5,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that an inclusive range is delta encoded the same way as a range.
+    #[test]
+    fn range_inclusive_dipa() {
+        DipaImplTester {
+            label: Some("RangeInclusive start changed"),
+            start: &mut (1u32..=5),
+            end: &(3u32..=5),
+            expected_delta: Delta2::Change_0(Some(3)),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("RangeInclusive end changed"),
+            start: &mut (1u32..=5),
+            end: &(1u32..=1),
+            expected_delta: Delta2::Change_1(Some(1)),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a patch to the start bound squashed with a patch to the end bound changes both
+    /// bounds.
+    #[test]
+    fn squash_patches_to_different_bounds() {
+        test_squash(1u16..5, 3..5, 3..9);
+        test_squash(1u16..=5, 1..=9, 3..=9);
+
+        let squashed = Range::<u16>::squash_patches(
+            DeltaOwned2::Change_0(Some(3)),
+            DeltaOwned2::Change_1(Some(9)),
+        )
+        .unwrap();
+        assert!(matches!(
+            squashed,
+            DeltaOwned2::Change_0_1(Some(3), Some(9))
+        ));
+    }
+
+    /// Verify that a patch that moves a bound back to where it started squashes into a patch
+    /// that does not change that bound.
+    #[test]
+    fn squash_bound_back_to_start() {
+        test_squash(1u16..5, 1..9, 1..5);
+        test_squash(1u16..=5, 3..=9, 1..=9);
+    }
+
+    /// Verify that patches that each change only one bound of a range merge, and that
+    /// changing the same bound to different values conflicts.
+    #[test]
+    fn merge_patches_to_different_bounds() {
+        test_merge(1u16..5, 3..5, 1..9, 3..9);
+        test_merge(1u16..=5, 1..=9, 3..=5, 3..=9);
+        test_merge(1u16..5, 1..9, 1..9, 1..9);
+
+        assert!(Range::<u16>::merge_patches(
+            &(1..5),
+            DeltaOwned2::Change_0(Some(2)),
+            DeltaOwned2::Change_0(Some(3)),
+        )
+        .is_err());
+    }
+
+    /// Verify that owned deltas to a range that changed one bound still patch that bound.
+    #[test]
+    fn range_to_owned_delta() {
+        test_to_owned_delta(1u16..2, 1..4);
+        test_to_owned_delta(1u16..=2, 0..=2);
+    }
+
+    /// Verify that conflicts and errors are reported at the bound that they happened in.
+    #[test]
+    fn range_errors_name_the_bound() {
+        let conflicts = Range::<u16>::merge_patches(
+            &(1..2),
+            DeltaOwned2::Change_1(Some(3)),
+            DeltaOwned2::Change_1(Some(4)),
+        )
+        .map(|_| ())
+        .unwrap_err();
+        let paths: Vec<Vec<&PathSegment>> = conflicts
+            .conflicts()
+            .iter()
+            .map(|conflict| conflict.path().collect())
+            .collect();
+        assert_eq!(paths, vec![vec![&PathSegment::Field("end")]]);
+
+        let mut range = vec![1u8]..=vec![2u8];
+        let err = range
+            .apply_patch(DeltaOwned2::Change_0_1(
+                vec![],
+                vec![crate::sequence::SequenceModificationDeltaOwned::DeleteOne { index: 3 }],
+            ))
+            .unwrap_err();
+        assert_eq!(
+            err.path().collect::<Vec<_>>(),
+            vec![&PathSegment::Field("end")]
+        );
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::IndexOutOfBounds { index: 3, len: 1 }
+        );
+        assert_eq!(range, vec![1u8]..=vec![2u8]);
+    }
+
+    /// Verify that changes to a range are described with the bound that changed.
+    #[test]
+    fn print_range_delta() {
+        test_print(1u16..2, 1..5, &["end: 2 -> 5"]);
+        test_print(1u16..=2, 3..=5, &["start: 1 -> 3", "end: 2 -> 5"]);
+    }
+
+    /// Verify that a range's JSON Patch only replaces the bounds that changed.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn range_json_patch() {
+        use crate::json_patch::test_json_patch;
+        use crate::ToJsonPatch;
+
+        let start = 1u16..2;
+        let end = 1u16..5;
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(
+            serde_json::to_string(&start.to_json_patch(&delta).unwrap()).unwrap(),
+            r#"[{"op":"replace","path":"/end","value":5}]"#
+        );
+
+        test_json_patch(1u16..=2, 3..=5);
+    }
+
+    /// Verify that we can patch a range from a borrowed patch.
+    #[test]
+    fn range_borrowed_patch() {
+        test_borrowed_patch(1u16..2, 1..5);
+        test_borrowed_patch(1u16..=2, 3..=5);
+        test_borrowed_patch(
+            "a".to_string().."c".to_string(),
+            "b".to_string().."c".to_string(),
+        );
+    }
+}
@@ -0,0 +1,719 @@
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaChange, DeltaVisitor, Diffable, MergeConflictReason,
+    MergeConflicts, MergeablePatches, PatchError, PatchErrorReason, Patchable, PathSegment,
+    ReversiblePatchable, SquashablePatches, ToOwnedDelta, VisitDelta,
+};
+#[cfg(feature = "json-patch")]
+use crate::{JsonPatch, JsonPatchError, JsonPointer, ToJsonPatch};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+impl<'s, 'e, T, E> Diffable<'s, 'e, Result<T, E>> for Result<T, E>
+where
+    T: 'e + Diffable<'s, 'e, T>,
+    E: 'e + Diffable<'s, 'e, E>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    type Delta = ResultDelta<'s, 'e, T, E>;
+    type DeltaOwned = ResultDeltaOwned<'s, 'e, T, E>;
+
+    fn create_delta_towards(&'s self, end_state: &'e Result<T, E>) -> CreatedDelta<Self::Delta> {
+        self.create_delta_towards_version(end_state, u32::MAX)
+    }
+
+    fn create_delta_towards_version(
+        &'s self,
+        end_state: &'e Result<T, E>,
+        version: u32,
+    ) -> CreatedDelta<Self::Delta> {
+        let diff = match (self, end_state) {
+            (Ok(old), Ok(new)) => {
+                let diff = old.create_delta_towards_version(new, version);
+
+                if diff.did_change {
+                    ResultDelta::OkChange(diff.delta)
+                } else {
+                    ResultDelta::NoChange
+                }
+            }
+            (Err(old), Err(new)) => {
+                let diff = old.create_delta_towards_version(new, version);
+
+                if diff.did_change {
+                    ResultDelta::ErrChange(diff.delta)
+                } else {
+                    ResultDelta::NoChange
+                }
+            }
+            (Err(_), Ok(new)) => ResultDelta::OuterChange(Ok(new)),
+            (Ok(_), Err(new)) => ResultDelta::OuterChange(Err(new)),
+        };
+
+        let did_change = !matches!(&diff, ResultDelta::NoChange);
+
+        CreatedDelta {
+            delta: diff,
+            did_change,
+        }
+    }
+}
+
+impl<'s, 'e, T, E> Patchable<<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned>
+    for Result<T, E>
+where
+    T: 'e + Diffable<'s, 'e, T>,
+    E: 'e + Diffable<'s, 'e, E>,
+    T: Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    E: Patchable<<E as Diffable<'s, 'e, E>>::DeltaOwned>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    fn apply_patch(
+        &mut self,
+        patch: <Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned,
+    ) -> Result<(), PatchError> {
+        match patch {
+            ResultDeltaOwned::NoChange => {}
+            ResultDeltaOwned::OkChange(delta) => match self {
+                Ok(ok) => ok.apply_patch(delta).map_err(ok_parent)?,
+                Err(_) => return Err(variant_mismatch("Ok")),
+            },
+            ResultDeltaOwned::ErrChange(delta) => match self {
+                Err(err) => err.apply_patch(delta).map_err(err_parent)?,
+                Ok(_) => return Err(variant_mismatch("Err")),
+            },
+            ResultDeltaOwned::OuterChange(outer) => {
+                *self = outer;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'s, 'e, T, E> ReversiblePatchable<<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned>
+    for Result<T, E>
+where
+    T: 'e + Diffable<'s, 'e, T>,
+    E: 'e + Diffable<'s, 'e, E>,
+    T: ReversiblePatchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    E: ReversiblePatchable<<E as Diffable<'s, 'e, E>>::DeltaOwned>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: <Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned,
+    ) -> Result<<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned, PatchError> {
+        let inverse = match patch {
+            ResultDeltaOwned::NoChange => ResultDeltaOwned::NoChange,
+            ResultDeltaOwned::OkChange(delta) => match self {
+                Ok(ok) => ResultDeltaOwned::OkChange(
+                    ok.apply_patch_returning_inverse(delta).map_err(ok_parent)?,
+                ),
+                Err(_) => return Err(variant_mismatch("Ok")),
+            },
+            ResultDeltaOwned::ErrChange(delta) => match self {
+                Err(err) => ResultDeltaOwned::ErrChange(
+                    err.apply_patch_returning_inverse(delta)
+                        .map_err(err_parent)?,
+                ),
+                Ok(_) => return Err(variant_mismatch("Err")),
+            },
+            ResultDeltaOwned::OuterChange(outer) => {
+                ResultDeltaOwned::OuterChange(std::mem::replace(self, outer))
+            }
+        };
+
+        Ok(inverse)
+    }
+}
+
+impl<'s, 'e, T, E> SquashablePatches<<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned>
+    for Result<T, E>
+where
+    T: 'e + Diffable<'s, 'e, T>,
+    E: 'e + Diffable<'s, 'e, E>,
+    T: SquashablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    E: SquashablePatches<<E as Diffable<'s, 'e, E>>::DeltaOwned>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    fn squash_patches(
+        first: <Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned,
+        second: <Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned,
+    ) -> Result<<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned, PatchError> {
+        let squashed = match (first, second) {
+            (first, ResultDeltaOwned::NoChange) => first,
+            (_, ResultDeltaOwned::OuterChange(outer)) => ResultDeltaOwned::OuterChange(outer),
+            (ResultDeltaOwned::NoChange, second) => second,
+            (ResultDeltaOwned::OkChange(first), ResultDeltaOwned::OkChange(second)) => {
+                ResultDeltaOwned::OkChange(T::squash_patches(first, second).map_err(ok_parent)?)
+            }
+            (ResultDeltaOwned::ErrChange(first), ResultDeltaOwned::ErrChange(second)) => {
+                ResultDeltaOwned::ErrChange(E::squash_patches(first, second).map_err(err_parent)?)
+            }
+            (ResultDeltaOwned::OuterChange(mut outer), ResultDeltaOwned::OkChange(second)) => {
+                match &mut outer {
+                    Ok(ok) => ok.apply_patch(second).map_err(ok_parent)?,
+                    Err(_) => return Err(variant_mismatch("Ok")),
+                };
+
+                ResultDeltaOwned::OuterChange(outer)
+            }
+            (ResultDeltaOwned::OuterChange(mut outer), ResultDeltaOwned::ErrChange(second)) => {
+                match &mut outer {
+                    Err(err) => err.apply_patch(second).map_err(err_parent)?,
+                    Ok(_) => return Err(variant_mismatch("Err")),
+                };
+
+                ResultDeltaOwned::OuterChange(outer)
+            }
+            (ResultDeltaOwned::ErrChange(_), ResultDeltaOwned::OkChange(_)) => {
+                return Err(variant_mismatch("Ok"))
+            }
+            (ResultDeltaOwned::OkChange(_), ResultDeltaOwned::ErrChange(_)) => {
+                return Err(variant_mismatch("Err"))
+            }
+        };
+
+        Ok(squashed)
+    }
+}
+
+impl<'s, 'e, T, E> MergeablePatches<<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned>
+    for Result<T, E>
+where
+    T: 'e + Diffable<'s, 'e, T>,
+    E: 'e + Diffable<'s, 'e, E>,
+    T: MergeablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    E: MergeablePatches<<E as Diffable<'s, 'e, E>>::DeltaOwned>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    // Two outer changes are a conflict even if they set the same value, since comparing them
+    // would require `T: PartialEq` and `E: PartialEq`.
+    fn merge_patches(
+        base: &Self,
+        a: <Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned,
+        b: <Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned,
+    ) -> Result<<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::DeltaOwned, MergeConflicts> {
+        let merged = match (a, b) {
+            (a, ResultDeltaOwned::NoChange) => a,
+            (ResultDeltaOwned::NoChange, b) => b,
+            (ResultDeltaOwned::OkChange(a), ResultDeltaOwned::OkChange(b)) => match base {
+                Ok(base) => ResultDeltaOwned::OkChange(
+                    T::merge_patches(base, a, b)
+                        .map_err(|conflicts| conflicts.with_parent(PathSegment::Variant("Ok")))?,
+                ),
+                Err(_) => return Err(variant_mismatch("Ok").into()),
+            },
+            (ResultDeltaOwned::ErrChange(a), ResultDeltaOwned::ErrChange(b)) => match base {
+                Err(base) => ResultDeltaOwned::ErrChange(
+                    E::merge_patches(base, a, b)
+                        .map_err(|conflicts| conflicts.with_parent(PathSegment::Variant("Err")))?,
+                ),
+                Ok(_) => return Err(variant_mismatch("Err").into()),
+            },
+            _ => return Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange)),
+        };
+
+        Ok(merged)
+    }
+}
+
+impl<'s, 'e, T, E> crate::SchemaFingerprint<'s, 'e> for Result<T, E>
+where
+    T: 'e + crate::SchemaFingerprint<'s, 'e>,
+    E: 'e + crate::SchemaFingerprint<'s, 'e>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("Result")
+        .write_u64(T::FINGERPRINT)
+        .write_u64(E::FINGERPRINT)
+        .finish();
+}
+
+impl<'s, 'e, T, E> VisitDelta<'s, 'e> for Result<T, E>
+where
+    T: 'e + VisitDelta<'s, 'e>,
+    E: 'e + VisitDelta<'s, 'e>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    fn visit_delta(
+        &self,
+        delta: &<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::Delta,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        match (self, delta) {
+            (_, ResultDelta::NoChange) => {}
+            (Ok(ok), ResultDelta::OkChange(delta)) => {
+                visitor.enter(PathSegment::Variant("Ok"));
+                ok.visit_delta(delta, visitor);
+                visitor.exit();
+            }
+            (Err(err), ResultDelta::ErrChange(delta)) => {
+                visitor.enter(PathSegment::Variant("Err"));
+                err.visit_delta(delta, visitor);
+                visitor.exit();
+            }
+            (_, ResultDelta::OuterChange(_)) => visitor.visit_change(DeltaChange::Replaced),
+            _ => {}
+        }
+    }
+}
+
+// `serde_json` writes `Ok(value)` as `{"Ok": value}`, so changes to the inner value are nested
+// under the variant's name.
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T, E> ToJsonPatch<'s, 'e> for Result<T, E>
+where
+    T: 'e + ToJsonPatch<'s, 'e> + Serialize,
+    E: 'e + ToJsonPatch<'s, 'e> + Serialize,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    fn push_json_patch(
+        &self,
+        delta: &<Result<T, E> as Diffable<'s, 'e, Result<T, E>>>::Delta,
+        path: &JsonPointer,
+        patch: &mut JsonPatch,
+    ) -> Result<(), JsonPatchError> {
+        match (self, delta) {
+            (_, ResultDelta::NoChange) => Ok(()),
+            (Ok(ok), ResultDelta::OkChange(delta)) => {
+                ok.push_json_patch(delta, &path.child("Ok"), patch)
+            }
+            (Err(err), ResultDelta::ErrChange(delta)) => {
+                err.push_json_patch(delta, &path.child("Err"), patch)
+            }
+            (_, ResultDelta::OuterChange(outer)) => patch.replace(path, outer),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'b, T, E> Patchable<ResultDeltaBorrowed<'b, T, E>> for Result<T, E>
+where
+    T: BorrowedDelta<'b>,
+    E: BorrowedDelta<'b>,
+{
+    fn apply_patch(&mut self, patch: ResultDeltaBorrowed<'b, T, E>) -> Result<(), PatchError> {
+        match patch {
+            ResultDeltaBorrowed::NoChange => {}
+            ResultDeltaBorrowed::OkChange(delta) => match self {
+                Ok(ok) => ok.apply_borrowed_patch(delta).map_err(ok_parent)?,
+                Err(_) => return Err(variant_mismatch("Ok")),
+            },
+            ResultDeltaBorrowed::ErrChange(delta) => match self {
+                Err(err) => err.apply_borrowed_patch(delta).map_err(err_parent)?,
+                Ok(_) => return Err(variant_mismatch("Err")),
+            },
+            ResultDeltaBorrowed::OuterChange(outer) => {
+                *self = outer;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de, T, E> BorrowedDelta<'de> for Result<T, E>
+where
+    T: BorrowedDelta<'de> + Deserialize<'de>,
+    E: BorrowedDelta<'de> + Deserialize<'de>,
+{
+    type DeltaBorrowed = ResultDeltaBorrowed<'de, T, E>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: ResultDeltaBorrowed<'de, T, E>,
+    ) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+fn ok_parent(err: PatchError) -> PatchError {
+    err.with_parent(PathSegment::Variant("Ok"))
+}
+
+fn err_parent(err: PatchError) -> PatchError {
+    err.with_parent(PathSegment::Variant("Err"))
+}
+
+fn variant_mismatch(expected: &'static str) -> PatchError {
+    PatchError::new(PatchErrorReason::VariantMismatch { expected })
+}
+
+/// Used to diff a `Result<T, E>`.
+///
+/// The inner value is diffed when both results hold the same variant, and the new result is sent
+/// in full when the variant changes.
+#[derive(Serialize)]
+#[allow(missing_docs, clippy::enum_variant_names)]
+pub enum ResultDelta<'s, 'e, T: Diffable<'s, 'e, T>, E: Diffable<'s, 'e, E>>
+where
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+{
+    NoChange,
+    OkChange(<T as Diffable<'s, 'e, T>>::Delta),
+    ErrChange(<E as Diffable<'s, 'e, E>>::Delta),
+    OuterChange(Result<&'e T, &'e E>),
+}
+
+/// Used to patch a `Result<T, E>`.
+#[derive(Deserialize)]
+#[allow(missing_docs, clippy::enum_variant_names)]
+pub enum ResultDeltaOwned<'s, 'e, T: Diffable<'s, 'e, T>, E: Diffable<'s, 'e, E>>
+where
+    <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::DeltaOwned: DeserializeOwned,
+{
+    NoChange,
+    OkChange(<T as Diffable<'s, 'e, T>>::DeltaOwned),
+    ErrChange(<E as Diffable<'s, 'e, E>>::DeltaOwned),
+    OuterChange(Result<T, E>),
+}
+
+/// Used to patch a `Result<T, E>` from a borrowed patch.
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>, E: Deserialize<'de>, \
+    <T as BorrowedDelta<'b>>::DeltaBorrowed: Deserialize<'de>, \
+    <E as BorrowedDelta<'b>>::DeltaBorrowed: Deserialize<'de>"))]
+#[allow(missing_docs, clippy::enum_variant_names)]
+pub enum ResultDeltaBorrowed<'b, T: BorrowedDelta<'b>, E: BorrowedDelta<'b>> {
+    NoChange,
+    #[serde(borrow)]
+    OkChange(<T as BorrowedDelta<'b>>::DeltaBorrowed),
+    #[serde(borrow)]
+    ErrChange(<E as BorrowedDelta<'b>>::DeltaBorrowed),
+    OuterChange(Result<T, E>),
+}
+
+// Bounds `T` and `E` over every pair of lifetimes for the same reason as the `Option` delta.
+impl<'s, 'e, 's2, 'e2, T, E> ToOwnedDelta<ResultDeltaOwned<'s2, 'e2, T, E>>
+    for ResultDelta<'s, 'e, T, E>
+where
+    T: Clone,
+    E: Clone,
+    T: for<'a, 'b> Diffable<'a, 'b, T>,
+    E: for<'a, 'b> Diffable<'a, 'b, E>,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+    <T as Diffable<'s, 'e, T>>::Delta: ToOwnedDelta<<T as Diffable<'s2, 'e2, T>>::DeltaOwned>,
+    <T as Diffable<'s2, 'e2, T>>::DeltaOwned: DeserializeOwned,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize,
+    <E as Diffable<'s, 'e, E>>::Delta: ToOwnedDelta<<E as Diffable<'s2, 'e2, E>>::DeltaOwned>,
+    <E as Diffable<'s2, 'e2, E>>::DeltaOwned: DeserializeOwned,
+{
+    fn to_owned_delta(self) -> ResultDeltaOwned<'s2, 'e2, T, E> {
+        match self {
+            ResultDelta::NoChange => ResultDeltaOwned::NoChange,
+            ResultDelta::OkChange(delta) => ResultDeltaOwned::OkChange(delta.to_owned_delta()),
+            ResultDelta::ErrChange(delta) => ResultDeltaOwned::ErrChange(delta.to_owned_delta()),
+            ResultDelta::OuterChange(outer) => {
+                ResultDeltaOwned::OuterChange(outer.cloned().map_err(E::clone))
+            }
+        }
+    }
+}
+
+// Used by DipaImplTester
+impl<'s, 'e, T: Diffable<'s, 'e, T>, E: Diffable<'s, 'e, E>> Debug for ResultDelta<'s, 'e, T, E>
+where
+    T: Debug,
+    E: Debug,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize + Debug,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResultDelta::NoChange => f.write_str("NoChange"),
+            ResultDelta::OkChange(delta) => f.debug_tuple("OkChange").field(delta).finish(),
+            ResultDelta::ErrChange(delta) => f.debug_tuple("ErrChange").field(delta).finish(),
+            ResultDelta::OuterChange(outer) => f.debug_tuple("OuterChange").field(outer).finish(),
+        }
+    }
+}
+
+// Used by DipaImplTester
+impl<'s, 'e, T: Diffable<'s, 'e, T>, E: Diffable<'s, 'e, E>> PartialEq for ResultDelta<'s, 'e, T, E>
+where
+    T: PartialEq,
+    E: PartialEq,
+    <T as Diffable<'s, 'e, T>>::Delta: Serialize + PartialEq,
+    <E as Diffable<'s, 'e, E>>::Delta: Serialize + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ResultDelta::NoChange, ResultDelta::NoChange) => true,
+            (ResultDelta::OkChange(left), ResultDelta::OkChange(right)) => left.eq(right),
+            (ResultDelta::ErrChange(left), ResultDelta::ErrChange(right)) => left.eq(right),
+            (ResultDelta::OuterChange(left), ResultDelta::OuterChange(right)) => left.eq(right),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
+    use crate::DipaImplTester;
+
+    /// Verify that we can diff/patch a Result<T, E>
+    #[test]
+    fn dipa_result_impl() {
+        DipaImplTester {
+            label: Some("Result<T, E>::Ok no change"),
+            start: &mut Result::<u32, u8>::Ok(1),
+            end: &Ok(1),
+            expected_delta: ResultDelta::NoChange,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Result<T, E>::Err no change"),
+            start: &mut Result::<u32, u8>::Err(1),
+            end: &Err(1),
+            expected_delta: ResultDelta::NoChange,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Result<T, E>::Ok change"),
+            start: &mut Result::<u32, u8>::Ok(1),
+            end: &Ok(5),
+            expected_delta: ResultDelta::OkChange(Some(5)),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Result<T, E>::Err change"),
+            start: &mut Result::<u32, u8>::Err(1),
+            end: &Err(5),
+            expected_delta: ResultDelta::ErrChange(5),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Result<T, E> Ok -> Err"),
+            start: &mut Result::<u32, u8>::Ok(1),
+            end: &Err(2),
+            expected_delta: ResultDelta::OuterChange(Err(&2)),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Result<T, E> Err -> Ok"),
+            start: &mut Result::<u32, u8>::Err(1),
+            end: &Ok(2),
+            expected_delta: ResultDelta::OuterChange(Ok(&2)),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that patching the inner value of the wrong variant returns an error instead of
+    /// panicking.
+    #[test]
+    fn inner_change_to_other_variant_errors() {
+        let mut result: Result<u32, u8> = Err(1);
+
+        let err = result
+            .apply_patch(ResultDeltaOwned::OkChange(Some(5)))
+            .unwrap_err();
+
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::VariantMismatch { expected: "Ok" }
+        );
+        assert_eq!(result, Err(1));
+    }
+
+    /// Verify that errors from patching the inner value contain the variant in their path.
+    #[test]
+    fn inner_error_contains_variant() {
+        let mut result: Result<Option<u32>, u8> = Ok(None);
+
+        let err = result
+            .apply_patch(ResultDeltaOwned::OkChange(
+                crate::option::OptionDeltaOwned::InnerChange(Some(5)),
+            ))
+            .unwrap_err();
+
+        assert_eq!(
+            err.path().collect::<Vec<_>>(),
+            vec![&PathSegment::Variant("Ok")]
+        );
+    }
+
+    /// Verify that a change to the variant followed by a change to the new variant's value
+    /// squashes into the variant change, and that patches for different variants can not be
+    /// squashed.
+    #[test]
+    fn squash_result_patches() {
+        test_squash(Ok::<u32, Vec<u8>>(1), Err(vec![2]), Err(vec![2, 3]));
+        test_squash(Err::<u32, u8>(1), Ok(2), Err(3));
+
+        let err = Result::<u32, u8>::squash_patches(
+            ResultDeltaOwned::OuterChange(Ok(1)),
+            ResultDeltaOwned::ErrChange(2),
+        )
+        .map(|_| ())
+        .unwrap_err();
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::VariantMismatch { expected: "Err" }
+        );
+
+        let err = Result::<Option<u32>, u8>::squash_patches(
+            ResultDeltaOwned::OuterChange(Ok(None)),
+            ResultDeltaOwned::OkChange(crate::option::OptionDeltaOwned::InnerChange(Some(5))),
+        )
+        .map(|_| ())
+        .unwrap_err();
+        assert_eq!(
+            err.path().collect::<Vec<_>>(),
+            vec![&PathSegment::Variant("Ok")]
+        );
+    }
+
+    /// Verify that changes to the same variant's value are merged, and that changing the variant
+    /// conflicts with other changes.
+    #[test]
+    fn merge_result_patches() {
+        test_merge(
+            Err::<u8, Vec<u8>>(vec![1, 2]),
+            Err(vec![0, 1, 2]),
+            Err(vec![1, 2, 3]),
+            Err(vec![0, 1, 2, 3]),
+        );
+        test_merge(Ok::<u32, u8>(1), Err(2), Ok(1), Err(2));
+
+        let conflicts = Result::<u32, u8>::merge_patches(
+            &Ok(1),
+            ResultDeltaOwned::OuterChange(Err(2)),
+            ResultDeltaOwned::OkChange(Some(5)),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::ConcurrentChange
+        );
+    }
+
+    /// Verify that an owned variant change holds a clone of the new value that the borrowed
+    /// delta referenced.
+    #[test]
+    fn result_to_owned_delta() {
+        let start = Ok::<Vec<u8>, String>(vec![1]);
+        let end = Err("failed".to_string());
+
+        let delta: ResultDeltaOwned<Vec<u8>, String> =
+            start.create_delta_towards(&end).delta.to_owned_delta();
+        assert!(matches!(delta, ResultDeltaOwned::OuterChange(Err(err)) if err == "failed"));
+
+        test_to_owned_delta(Err::<Vec<u8>, Vec<u8>>(vec![1]), Err(vec![1, 2]));
+    }
+
+    /// Verify that we describe changes to the inner value under its variant and replaced
+    /// variants.
+    #[test]
+    fn print_result_delta() {
+        test_print(
+            Err::<u32, (u8, u8)>((1, 2)),
+            Err((1, 3)),
+            &["Err.1: 2 -> 3"],
+        );
+        test_print(Ok::<u32, u8>(1), Err(2), &["replaced"]);
+        test_print(Err::<u32, u8>(1), Err(1), &[]);
+    }
+
+    /// Verify that changes to the inner value are exported under the variant's key and that a
+    /// variant change replaces the whole result.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn result_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        let json_patch = |start: Result<u32, Vec<u8>>, end: Result<u32, Vec<u8>>| {
+            let delta = start.create_delta_towards(&end).delta;
+            serde_json::to_string(&start.to_json_patch(&delta).unwrap()).unwrap()
+        };
+
+        assert_eq!(
+            json_patch(Err(vec![1]), Err(vec![1, 2])),
+            r#"[{"op":"add","path":"/Err/1","value":2}]"#
+        );
+        assert_eq!(
+            json_patch(Ok(1), Err(vec![])),
+            r#"[{"op":"replace","path":"","value":{"Err":[]}}]"#
+        );
+
+        test_json_patch(Ok::<u32, u8>(1), Ok(2));
+    }
+
+    /// Verify that a borrowed patch can change the variant and that a borrowed patch for the
+    /// other variant's value returns an error.
+    #[test]
+    fn result_borrowed_patch() {
+        test_borrowed_patch(Ok::<String, String>("a".to_string()), Err("b".to_string()));
+        test_borrowed_patch(Err::<String, String>("a".to_string()), Err("".to_string()));
+
+        let bytes = bincode::serialize(
+            &Err::<String, String>("a".to_string())
+                .create_delta_towards(&Err("ab".to_string()))
+                .delta,
+        )
+        .unwrap();
+
+        let mut result = Ok::<String, String>("a".to_string());
+        let err = result
+            .apply_borrowed_patch(bincode::deserialize(&bytes).unwrap())
+            .unwrap_err();
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::VariantMismatch { expected: "Err" }
+        );
+        assert_eq!(result, Ok("a".to_string()));
+    }
+}
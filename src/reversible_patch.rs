@@ -0,0 +1,27 @@
+use crate::{PatchError, Patchable};
+
+/// Applies a patch while recording how to undo it.
+///
+/// The returned inverse patch has the same type as the patch that was applied, so applying the
+/// inverse restores the previous state and returns a patch that redoes the original change.
+///
+/// ```
+/// # use dipa::{Patchable, ReversiblePatchable};
+/// let mut score: u32 = 5;
+///
+/// let undo = score.apply_patch_returning_inverse(Some(10)).unwrap();
+/// assert_eq!(score, 10);
+///
+/// let redo = score.apply_patch_returning_inverse(undo).unwrap();
+/// assert_eq!(score, 5);
+///
+/// score.apply_patch(redo).unwrap();
+/// assert_eq!(score, 10);
+/// ```
+///
+/// If applying the patch fails the inverse is lost, so `self` should be treated the same way as
+/// after a failed [`Patchable.apply_patch`].
+pub trait ReversiblePatchable<P>: Patchable<P> {
+    /// Apply a patch, returning the patch that would undo it.
+    fn apply_patch_returning_inverse(&mut self, patch: P) -> Result<P, PatchError>;
+}
@@ -0,0 +1,244 @@
+use crate::Diffable;
+use std::fmt::{Display, Formatter};
+
+/// A hash of everything that decides how a type's delta gets encoded.
+///
+/// When a sender and a receiver are built from different versions of a type, a delta from one
+/// can still deserialize on the other and then quietly corrupt its state when applied. Prefixing
+/// serialized deltas with the fingerprint lets the receiver reject them instead.
+///
+/// [`Diffable::Delta`] and [`Diffable::DeltaOwned`] are two views of the same serialized form, so
+/// the one fingerprint describes both. It is implemented on the value type since types with a
+/// single field reuse their field's delta type.
+///
+/// The fingerprint of a type is built from the fingerprints of the types that it holds, so changing
+/// a field deep inside of a data structure also changes the fingerprint of the data structures that
+/// contain it.
+///
+/// ```
+/// # use dipa::{Diffable, SchemaFingerprint};
+/// let start = vec![1u32, 2, 3];
+/// let end = vec![1, 2, 3, 4];
+///
+/// let delta = start.create_delta_towards(&end).delta;
+/// let serialized = Vec::<u32>::prefix_fingerprint(&bincode::serialize(&delta).unwrap());
+///
+/// let received = Vec::<u32>::strip_fingerprint(&serialized).unwrap();
+/// let delta: <Vec<u32> as Diffable<Vec<u32>>>::DeltaOwned = bincode::deserialize(received).unwrap();
+///
+/// assert!(Vec::<u16>::strip_fingerprint(&serialized).is_err());
+/// ```
+pub trait SchemaFingerprint<'s, 'e>: Diffable<'s, 'e, Self> {
+    /// The fingerprint of the type's delta encoding.
+    const FINGERPRINT: u64;
+
+    /// Prefix a serialized delta with the fingerprint.
+    fn prefix_fingerprint(serialized_delta: &[u8]) -> Vec<u8> {
+        let mut prefixed = Vec::with_capacity(FINGERPRINT_LEN + serialized_delta.len());
+        prefixed.extend_from_slice(&Self::FINGERPRINT.to_le_bytes());
+        prefixed.extend_from_slice(serialized_delta);
+
+        prefixed
+    }
+
+    /// Verify the fingerprint that [`SchemaFingerprint::prefix_fingerprint`] wrote and return the
+    /// serialized delta that follows it.
+    fn strip_fingerprint(prefixed: &[u8]) -> Result<&[u8], FingerprintMismatch> {
+        let received = match prefixed.get(..FINGERPRINT_LEN) {
+            Some(fingerprint) => {
+                let mut bytes = [0; FINGERPRINT_LEN];
+                bytes.copy_from_slice(fingerprint);
+                Some(u64::from_le_bytes(bytes))
+            }
+            None => None,
+        };
+
+        if received != Some(Self::FINGERPRINT) {
+            return Err(FingerprintMismatch {
+                expected: Self::FINGERPRINT,
+                received,
+            });
+        }
+
+        Ok(&prefixed[FINGERPRINT_LEN..])
+    }
+}
+
+const FINGERPRINT_LEN: usize = 8;
+
+/// Builds a [`SchemaFingerprint::FINGERPRINT`] at compile time.
+///
+/// Uses 64 bit FNV-1a, which is stable across platforms and compiler versions.
+///
+/// ```
+/// # use dipa::FingerprintHasher;
+/// const FINGERPRINT: u64 = FingerprintHasher::new()
+///     .write_str("MyStruct")
+///     .write_str("some_field")
+///     .write_u64(<u32 as dipa::SchemaFingerprint>::FINGERPRINT)
+///     .finish();
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct FingerprintHasher(u64);
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl FingerprintHasher {
+    /// Create a hasher that has not been written to.
+    pub const fn new() -> Self {
+        FingerprintHasher(FNV_OFFSET_BASIS)
+    }
+
+    /// Hash a name, such as a type, field or variant name.
+    ///
+    /// Names are length prefixed so that `("ab", "c")` and `("a", "bc")` hash differently.
+    pub const fn write_str(self, name: &str) -> Self {
+        let bytes = name.as_bytes();
+        let mut hasher = self.write_u64(bytes.len() as u64);
+
+        let mut idx = 0;
+        while idx < bytes.len() {
+            hasher = hasher.write_byte(bytes[idx]);
+            idx += 1;
+        }
+
+        hasher
+    }
+
+    /// Hash a number, such as the fingerprint of a field's type.
+    pub const fn write_u64(self, value: u64) -> Self {
+        let bytes = value.to_le_bytes();
+        let mut hasher = self;
+
+        let mut idx = 0;
+        while idx < bytes.len() {
+            hasher = hasher.write_byte(bytes[idx]);
+            idx += 1;
+        }
+
+        hasher
+    }
+
+    /// The fingerprint of everything that has been written.
+    pub const fn finish(self) -> u64 {
+        self.0
+    }
+
+    const fn write_byte(self, byte: u8) -> Self {
+        FingerprintHasher((self.0 ^ byte as u64).wrapping_mul(FNV_PRIME))
+    }
+}
+
+impl Default for FingerprintHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The fingerprint in front of a serialized delta did not match the receiver's type, so the delta
+/// was most likely created by a different version of the type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FingerprintMismatch {
+    expected: u64,
+    received: Option<u64>,
+}
+
+impl FingerprintMismatch {
+    /// The fingerprint of the receiver's type.
+    pub fn expected(&self) -> u64 {
+        self.expected
+    }
+
+    /// The fingerprint in front of the delta, or `None` if there were not enough bytes to hold one.
+    pub fn received(&self) -> Option<u64> {
+        self.received
+    }
+}
+
+impl Display for FingerprintMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.received {
+            Some(received) => write!(
+                f,
+                "Delta schema fingerprint {:#018x} does not match the expected {:#018x}",
+                received, self.expected
+            ),
+            None => write!(
+                f,
+                "Delta is too short to hold a schema fingerprint, expected {:#018x}",
+                self.expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FingerprintMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that we hash the same way as the reference FNV-1a implementation.
+    #[test]
+    fn fnv_1a() {
+        let mut hasher = FingerprintHasher::new();
+        for byte in b"foobar" {
+            hasher = hasher.write_byte(*byte);
+        }
+
+        assert_eq!(hasher.finish(), 0x85944171f73967e8);
+    }
+
+    /// Verify that names are separated from each other.
+    #[test]
+    fn names_are_length_prefixed() {
+        let ab_c = FingerprintHasher::new().write_str("ab").write_str("c");
+        let a_bc = FingerprintHasher::new().write_str("a").write_str("bc");
+
+        assert_ne!(ab_c.finish(), a_bc.finish());
+    }
+
+    /// Verify that std types with different delta encodings have different fingerprints.
+    #[test]
+    fn std_fingerprints_differ() {
+        let fingerprints = [
+            <u8 as SchemaFingerprint>::FINGERPRINT,
+            <u16 as SchemaFingerprint>::FINGERPRINT,
+            <Vec<u8> as SchemaFingerprint>::FINGERPRINT,
+            <Vec<u16> as SchemaFingerprint>::FINGERPRINT,
+            <Option<Vec<u8>> as SchemaFingerprint>::FINGERPRINT,
+            <(u8, u16) as SchemaFingerprint>::FINGERPRINT,
+            <(u16, u8) as SchemaFingerprint>::FINGERPRINT,
+        ];
+
+        for (idx, fingerprint) in fingerprints.iter().enumerate() {
+            assert!(!fingerprints[idx + 1..].contains(fingerprint));
+        }
+    }
+
+    /// Verify that a prefixed delta can only be stripped by a type with the same fingerprint.
+    #[test]
+    fn prefix_and_strip() {
+        let prefixed = <Vec<u8> as SchemaFingerprint>::prefix_fingerprint(&[1, 2, 3]);
+
+        assert_eq!(
+            <Vec<u8> as SchemaFingerprint>::strip_fingerprint(&prefixed),
+            Ok(&[1, 2, 3][..])
+        );
+        assert_eq!(
+            <Vec<i8> as SchemaFingerprint>::strip_fingerprint(&prefixed),
+            Err(FingerprintMismatch {
+                expected: <Vec<i8> as SchemaFingerprint>::FINGERPRINT,
+                received: Some(<Vec<u8> as SchemaFingerprint>::FINGERPRINT),
+            })
+        );
+        assert_eq!(
+            <Vec<u8> as SchemaFingerprint>::strip_fingerprint(&[1, 2]),
+            Err(FingerprintMismatch {
+                expected: <Vec<u8> as SchemaFingerprint>::FINGERPRINT,
+                received: None,
+            })
+        );
+    }
+}
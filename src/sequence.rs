@@ -1,11 +1,31 @@
-use crate::sequence::sequence_apply_patch::apply_patch;
+pub(crate) use crate::sequence::longest_common_subsequence::get_longest_common_subsequence;
+pub(crate) use crate::sequence::sequence_apply_borrowed_patch::apply_borrowed_patch;
+use crate::sequence::sequence_apply_patch::{apply_patch, apply_patch_returning_inverse};
 use crate::sequence::sequence_delta_patch_towards::delta_towards;
-use crate::{CreatedDelta, Diffable, Patchable};
-use serde::Serialize;
+pub(crate) use crate::sequence::sequence_merge_patches::{
+    merge_hunks, merge_sequence_patches, Hunk,
+};
+pub(crate) use crate::sequence::sequence_squash_patches::squash_sequence_patches;
+pub(crate) use crate::sequence::sequence_visit_delta::visit_sequence_delta;
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SquashablePatches, VisitDelta,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 mod longest_common_subsequence;
+mod sequence_apply_borrowed_patch;
 mod sequence_apply_patch;
+mod sequence_binary_heap;
 mod sequence_delta_patch_towards;
+#[cfg(feature = "json-patch")]
+mod sequence_json_patch;
+mod sequence_merge_patches;
+mod sequence_queue;
+mod sequence_squash_patches;
+mod sequence_to_owned_delta;
+mod sequence_visit_delta;
 
 impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> Diffable<'s, 'e, Vec<T>> for Vec<T>
 where
@@ -22,11 +42,108 @@ where
 }
 
 impl<T> Patchable<Vec<SequenceModificationDeltaOwned<T>>> for Vec<T> {
-    fn apply_patch(&mut self, patch: Vec<SequenceModificationDeltaOwned<T>>) {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<(), PatchError> {
         apply_patch(self, patch)
     }
 }
 
+impl<T> ReversiblePatchable<Vec<SequenceModificationDeltaOwned<T>>> for Vec<T> {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+        apply_patch_returning_inverse(self, patch)
+    }
+}
+
+impl<T> SquashablePatches<Vec<SequenceModificationDeltaOwned<T>>> for Vec<T> {
+    fn squash_patches(
+        first: Vec<SequenceModificationDeltaOwned<T>>,
+        second: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+        squash_sequence_patches(first, second)
+    }
+}
+
+impl<T: PartialEq> MergeablePatches<Vec<SequenceModificationDeltaOwned<T>>> for Vec<T> {
+    fn merge_patches(
+        base: &Self,
+        a: Vec<SequenceModificationDeltaOwned<T>>,
+        b: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, MergeConflicts> {
+        merge_sequence_patches(base.len(), a, b)
+    }
+}
+
+// The delta holds the inserted items themselves, so the item's fingerprint stands in for the shape
+// of its serialized form.
+impl<'s, 'e, T: 'e + crate::SchemaFingerprint<'s, 'e>> crate::SchemaFingerprint<'s, 'e> for Vec<T>
+where
+    T: PartialEq,
+    &'e T: serde::Serialize,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("Vec")
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> VisitDelta<'s, 'e> for Vec<T>
+where
+    T: PartialEq,
+    &'e T: serde::Serialize,
+{
+    fn visit_delta(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, T>>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        visit_sequence_delta(self.len(), delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> crate::ToJsonPatch<'s, 'e> for Vec<T>
+where
+    T: PartialEq,
+    &'e T: serde::Serialize,
+{
+    fn push_json_patch(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, T>>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        sequence_json_patch::push_sequence_json_patch(self.len(), delta, path, patch)
+    }
+}
+
+impl<'b, T: Clone> Patchable<Vec<SequenceModificationDeltaBorrowed<'b, T>>> for Vec<T> {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'b, T>>,
+    ) -> Result<(), PatchError> {
+        apply_borrowed_patch(self, patch)
+    }
+}
+
+impl<'de, T> BorrowedDelta<'de> for Vec<T>
+where
+    T: 'de + BorrowedDelta<'de> + Clone + Deserialize<'de>,
+{
+    type DeltaBorrowed = Vec<SequenceModificationDeltaBorrowed<'de, T>>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'de, T>>,
+    ) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
 impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> Diffable<'s, 'e, [T]> for &[T]
 where
     T: PartialEq,
@@ -41,6 +158,128 @@ where
     }
 }
 
+// A boxed slice is encoded the same way as a `Vec<T>`. Patching converts it into a `Vec<T>` and
+// back, which does not reallocate unless the patch changes the length.
+impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> Diffable<'s, 'e, Box<[T]>> for Box<[T]>
+where
+    T: PartialEq,
+    &'e T: serde::Serialize,
+{
+    type Delta = Vec<SequenceModificationDelta<'e, T>>;
+
+    type DeltaOwned = Vec<SequenceModificationDeltaOwned<T>>;
+
+    fn create_delta_towards(&self, end_state: &'e Self) -> CreatedDelta<Self::Delta> {
+        delta_towards(self, end_state)
+    }
+}
+
+impl<T> Patchable<Vec<SequenceModificationDeltaOwned<T>>> for Box<[T]> {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<(), PatchError> {
+        with_vec(self, |vec| apply_patch(vec, patch))
+    }
+}
+
+impl<T> ReversiblePatchable<Vec<SequenceModificationDeltaOwned<T>>> for Box<[T]> {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+        with_vec(self, |vec| apply_patch_returning_inverse(vec, patch))
+    }
+}
+
+impl<T> SquashablePatches<Vec<SequenceModificationDeltaOwned<T>>> for Box<[T]> {
+    fn squash_patches(
+        first: Vec<SequenceModificationDeltaOwned<T>>,
+        second: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+        squash_sequence_patches(first, second)
+    }
+}
+
+impl<T: PartialEq> MergeablePatches<Vec<SequenceModificationDeltaOwned<T>>> for Box<[T]> {
+    fn merge_patches(
+        base: &Self,
+        a: Vec<SequenceModificationDeltaOwned<T>>,
+        b: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, MergeConflicts> {
+        merge_sequence_patches(base.len(), a, b)
+    }
+}
+
+impl<'s, 'e, T: 'e + crate::SchemaFingerprint<'s, 'e>> crate::SchemaFingerprint<'s, 'e> for Box<[T]>
+where
+    T: PartialEq,
+    &'e T: serde::Serialize,
+{
+    const FINGERPRINT: u64 = <Vec<T> as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+}
+
+impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> VisitDelta<'s, 'e> for Box<[T]>
+where
+    T: PartialEq,
+    &'e T: serde::Serialize,
+{
+    fn visit_delta(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, T>>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        visit_sequence_delta(self.len(), delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> crate::ToJsonPatch<'s, 'e> for Box<[T]>
+where
+    T: PartialEq,
+    &'e T: serde::Serialize,
+{
+    fn push_json_patch(
+        &self,
+        delta: &Vec<SequenceModificationDelta<'e, T>>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        sequence_json_patch::push_sequence_json_patch(self.len(), delta, path, patch)
+    }
+}
+
+impl<'b, T: Clone> Patchable<Vec<SequenceModificationDeltaBorrowed<'b, T>>> for Box<[T]> {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'b, T>>,
+    ) -> Result<(), PatchError> {
+        with_vec(self, |vec| apply_borrowed_patch(vec, patch))
+    }
+}
+
+impl<'de, T> BorrowedDelta<'de> for Box<[T]>
+where
+    T: 'de + BorrowedDelta<'de> + Clone + Deserialize<'de>,
+{
+    type DeltaBorrowed = Vec<SequenceModificationDeltaBorrowed<'de, T>>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'de, T>>,
+    ) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+fn with_vec<T, R>(boxed: &mut Box<[T]>, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+    let mut vec = std::mem::take(boxed).into_vec();
+    let result = f(&mut vec);
+    *boxed = vec.into_boxed_slice();
+
+    result
+}
+
 /// Used to diff/patch sequences such as vectors and slices.
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -106,6 +345,15 @@ where
     ReplaceAllBeforeIncluding { before: usize, new: &'a [T] },
     /// Replace all values after the provided index, inclusive.
     ReplaceAllAfterIncluding { after: usize, new: &'a [T] },
+
+    /// Remove the first item and append an item to the end, such as when a full ring buffer
+    /// receives a new item.
+    PopFrontPushBackOne { item: &'a T },
+    /// Remove items from the beginning of the sequence and then append items to its end.
+    PopFrontPushBackMany {
+        pop_front: usize,
+        push_back: &'a [T],
+    },
 }
 
 /// Used to patch sequences such as vectors and slices.
@@ -166,12 +414,133 @@ pub enum SequenceModificationDeltaOwned<T> {
     ReplaceAllBeforeIncluding { before: usize, new: Vec<T> },
     /// Replace all values after the provided index, inclusive.
     ReplaceAllAfterIncluding { after: usize, new: Vec<T> },
+
+    /// Remove the first item and append an item to the end, such as when a full ring buffer
+    /// receives a new item.
+    PopFrontPushBackOne { item: T },
+    /// Remove items from the beginning of the sequence and then append items to its end.
+    PopFrontPushBackMany { pop_front: usize, push_back: Vec<T> },
+}
+
+/// Used to patch sequences such as vectors and slices from a borrowed patch.
+///
+/// Serialized the same way as a `SequenceModificationDeltaOwned<T>`. Runs of items are
+/// deserialized with [`BorrowedDelta::deserialize_sequence_items`], so byte sequences such as
+/// `Vec<u8>` do not copy the new bytes out of the buffer that the patch was deserialized from.
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(bound(deserialize = "T: 'de + BorrowedDelta<'de> + Clone + Deserialize<'de>"))]
+pub enum SequenceModificationDeltaBorrowed<'b, T: Clone> {
+    /// Insert into the Vec<T>, starting at some start index.
+    InsertOne { index: usize, value: T },
+    /// Prepend an item to the beginning of the vector.
+    PrependOne { item: T },
+    /// Append item to the end of the new vector
+    AppendOne { item: T },
+    /// Delete one item from the sequence
+    DeleteOne { index: usize },
+    /// Replace one item from the sequence
+    ReplaceOne { index: usize, new: T },
+
+    /// Delete the first item in the sequence
+    DeleteFirst,
+    /// Delete the last item in the sequence
+    DeleteLast,
+    /// Replace the first item in the sequence
+    ReplaceFirst { item: T },
+    /// Replace the last item in the sequence
+    ReplaceLast { item: T },
+
+    /// Prepend many items to the beginning of the vector.
+    PrependMany {
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        items: Cow<'b, [T]>,
+    },
+    /// Insert multiple items into the Vec<T>, starting at some start index.
+    InsertMany {
+        start_idx: usize,
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        items: Cow<'b, [T]>,
+    },
+    /// Delete from the Vec<T> starting from some start index
+    DeleteMany {
+        start_index: usize,
+        items_to_delete: usize,
+    },
+    /// Append item to the end of the new vector
+    AppendMany {
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        items: Cow<'b, [T]>,
+    },
+    /// Replace many items in the sequence.
+    ReplaceMany {
+        start_idx: usize,
+        items_to_replace: usize,
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        new: Cow<'b, [T]>,
+    },
+    /// Replace many items in the sequence when we are adding and removing the same number of
+    /// values.
+    ReplaceManySameAmountAddedAndRemoved {
+        index: usize,
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        new: Cow<'b, [T]>,
+    },
+
+    /// Replace all of the values in the old sequence with the values in the new sequence.
+    ReplaceAll {
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        new: Cow<'b, [T]>,
+    },
+
+    /// Delete all items
+    DeleteAll,
+    /// Remove all items *at* AND *before* the specified index.
+    DeleteAllBeforeIncluding { end_index: usize },
+    /// Remove all items *at* AND *after* the specified index.
+    DeleteAllAfterIncluding { start_index: usize },
+    /// Replace all values before the provided index, inclusive.
+    ReplaceAllBeforeIncluding {
+        before: usize,
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        new: Cow<'b, [T]>,
+    },
+    /// Replace all values after the provided index, inclusive.
+    ReplaceAllAfterIncluding {
+        after: usize,
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        new: Cow<'b, [T]>,
+    },
+
+    /// Remove the first item and append an item to the end, such as when a full ring buffer
+    /// receives a new item.
+    PopFrontPushBackOne { item: T },
+    /// Remove items from the beginning of the sequence and then append items to its end.
+    PopFrontPushBackMany {
+        pop_front: usize,
+        #[serde(borrow, deserialize_with = "deserialize_items")]
+        push_back: Cow<'b, [T]>,
+    },
+}
+
+fn deserialize_items<'de: 'b, 'b, T, D>(deserializer: D) -> Result<Cow<'b, [T]>, D::Error>
+where
+    T: 'de + BorrowedDelta<'de> + Clone + Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize_sequence_items(deserializer)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
     use crate::dipa_impl_tester::DipaImplTester;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
+    use crate::{PatchErrorReason, PathSegment, ToOwnedDelta};
     use bincode::Options;
 
     /// 1 byte for the u8 length of the Vec that holds all of the patch operations
@@ -189,7 +558,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we delete one extra item at the end.
@@ -206,7 +575,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we delete many extra items at the end.
@@ -226,7 +595,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly delete the first item.
@@ -243,7 +612,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly delete the first `n` items.
@@ -263,7 +632,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we delete many extra items at the end.
@@ -285,7 +654,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we delete one item in the middle.
@@ -304,7 +673,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we delete many items in the middle.
@@ -328,7 +697,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly go from an empty vec to one item in the vec.
@@ -349,7 +718,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly go from an empty vec to 2 items in the vec.
@@ -370,7 +739,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly insert one item at the beginning of the start sequence.
@@ -389,7 +758,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly insert many items at the beginning of the start sequence.
@@ -410,7 +779,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly diff/patch inserting one item in the middle
@@ -434,7 +803,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Insert multiple items into the middle of the array.
@@ -459,7 +828,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we append one item to the end.
@@ -478,7 +847,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we create a patch to append many items to the end.
@@ -499,7 +868,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can replace one item at the beginning of the array.
@@ -519,7 +888,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can replace many items in the middle of the array.
@@ -544,7 +913,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can replace one item at the end of the array.
@@ -564,7 +933,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can replace many items at the end of the array.
@@ -589,7 +958,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can replace one item in the middle of the array.
@@ -610,7 +979,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can rename `n` items with `m` new items.
@@ -637,7 +1006,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can replace many items in the middle of the array when we are removing just
@@ -665,7 +1034,111 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
+    }
+
+    /// Verify that we can replace one item in the middle of the array with many items.
+    #[test]
+    fn replace_one_with_many_in_middle() {
+        let expected_patch = vec![SequenceModificationDelta::ReplaceMany {
+            start_idx: 1,
+            items_to_replace: 1,
+            new: &[5, 6],
+        }];
+
+        // 1 for the one variant in the modifications
+        // 1 for index
+        // 1 for items to replace count
+        // 1 for length of items
+        // 2 for the items
+        let expected_serialized_patch_size = BASE_PATCH_BYTES + 1 + 1 + 1 + 1 + 2;
+
+        DipaImplTester {
+            label: None,
+            start: &mut vec![1u8, 2, 3],
+            end: &vec![1u8, 5, 6, 3],
+            expected_delta: expected_patch,
+            expected_serialized_patch_size,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that the items that replace part of the middle of the array are taken from after
+    /// the previous common item of the end array, even when it is at a different index than in the
+    /// start array.
+    #[test]
+    fn replace_many_in_middle_after_prepend() {
+        let expected_patch = vec![
+            SequenceModificationDelta::ReplaceMany {
+                start_idx: 1,
+                items_to_replace: 3,
+                new: &[6, 7],
+            },
+            SequenceModificationDelta::PrependOne { item: &0 },
+        ];
+
+        // 6 for the replace many modification
+        // 2 for the prepend one modification
+        let expected_serialized_patch_size = BASE_PATCH_BYTES + 6 + 2;
+
+        DipaImplTester {
+            label: None,
+            start: &mut vec![1u8, 2, 3, 4, 5],
+            end: &vec![0u8, 1, 6, 7, 5],
+            expected_delta: expected_patch,
+            expected_serialized_patch_size,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that we can replace items at the beginning and end of the array with a different
+    /// number of items.
+    #[test]
+    fn replace_beginning_and_end_with_different_amount() {
+        DipaImplTester {
+            label: Some("Replace first item with many"),
+            start: &mut vec![1u8, 2, 3],
+            end: &vec![4u8, 5, 2, 3],
+            expected_delta: vec![SequenceModificationDelta::ReplaceAllBeforeIncluding {
+                before: 0,
+                new: &[4, 5],
+            }],
+            // 1 for the variant, 1 for before, 1 for the items length, 2 for the items
+            expected_serialized_patch_size: BASE_PATCH_BYTES + 1 + 1 + 1 + 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Replace last item with many"),
+            start: &mut vec![1u8, 2, 3],
+            end: &vec![1u8, 2, 4, 5],
+            expected_delta: vec![SequenceModificationDelta::ReplaceAllAfterIncluding {
+                after: 2,
+                new: &[4, 5],
+            }],
+            // 1 for the variant, 1 for after, 1 for the items length, 2 for the items
+            expected_serialized_patch_size: BASE_PATCH_BYTES + 1 + 1 + 1 + 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that we replace the whole array when it has no items in common with the end array.
+    #[test]
+    fn replace_array_with_nothing_in_common() {
+        DipaImplTester {
+            label: None,
+            start: &mut vec![1u8, 2],
+            end: &vec![3u8, 4, 5],
+            expected_delta: vec![SequenceModificationDelta::ReplaceAll { new: &[3, 4, 5] }],
+            // 1 for the variant, 1 for the items length, 3 for the items
+            expected_serialized_patch_size: BASE_PATCH_BYTES + 1 + 1 + 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
     }
 
     /// Verify that we create a patch to remove all items.
@@ -684,7 +1157,7 @@ mod tests {
             expected_serialized_patch_size,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that only one byte is used to serialize a sequence modification variant.
@@ -704,4 +1177,375 @@ mod tests {
             1
         );
     }
+
+    /// Verify that a patch that refers to indices that do not exist returns an error.
+    #[test]
+    fn out_of_bounds_patch_errors() {
+        let patches = vec![
+            SequenceModificationDeltaOwned::DeleteOne { index: 2 },
+            SequenceModificationDeltaOwned::DeleteMany {
+                start_index: 1,
+                items_to_delete: 5,
+            },
+            SequenceModificationDeltaOwned::InsertOne { index: 3, value: 0 },
+            SequenceModificationDeltaOwned::ReplaceOne { index: 4, new: 0 },
+        ];
+
+        for patch in patches {
+            let mut vec = vec![1u8, 2];
+
+            let err = vec.apply_patch(vec![patch]).unwrap_err();
+
+            assert!(matches!(
+                err.reason(),
+                PatchErrorReason::IndexOutOfBounds { len: 2, .. }
+            ));
+        }
+
+        let mut vec: Vec<u8> = vec![];
+        assert!(vec
+            .apply_patch(vec![SequenceModificationDeltaOwned::DeleteLast])
+            .is_err());
+    }
+
+    /// Verify that the inverse of a patch with multiple modifications undoes them in reverse order,
+    /// and that undoing the inverse redoes the original patch.
+    #[test]
+    fn undo_and_redo_many_modifications() {
+        let mut vec = vec![1u8, 2, 3];
+
+        let patch = vec![
+            SequenceModificationDeltaOwned::DeleteFirst,
+            SequenceModificationDeltaOwned::InsertOne { index: 1, value: 9 },
+            SequenceModificationDeltaOwned::AppendMany { items: vec![4, 5] },
+        ];
+
+        let undo = vec.apply_patch_returning_inverse(patch).unwrap();
+        assert_eq!(vec, vec![2, 9, 3, 4, 5]);
+
+        let redo = vec.apply_patch_returning_inverse(undo).unwrap();
+        assert_eq!(vec, vec![1, 2, 3]);
+
+        vec.apply_patch(redo).unwrap();
+        assert_eq!(vec, vec![2, 9, 3, 4, 5]);
+    }
+
+    /// Verify that we can squash consecutive sequence patches.
+    #[test]
+    fn squash_sequence_patches() {
+        test_squash(vec![1u8, 2, 3], vec![1, 2, 3, 4, 5], vec![2, 3, 4, 5]);
+        test_squash(vec![1u8, 2, 3], vec![1, 9, 3], vec![1, 9, 3, 7]);
+        test_squash(vec![1u8, 2, 3], vec![], vec![4, 5]);
+        test_squash(vec![1u8, 2, 3], vec![4, 5], vec![]);
+        test_squash(vec![1u8, 2, 3, 4], vec![2, 3, 4, 5], vec![3, 4, 5, 6]);
+        test_squash(vec![1u8, 2, 3, 4, 5], vec![1, 9, 3, 4], vec![1, 8, 7, 3]);
+    }
+
+    /// Verify that modifications to the same part of the sequence get combined into one
+    /// modification, so that the squashed patch is smaller than the patches that it came from.
+    #[test]
+    fn squashed_patch_is_smaller() {
+        let start = vec![1u8, 2, 3, 4, 5, 6, 7, 8];
+        let middle = vec![1u8, 2, 9, 4, 5, 6, 7, 8];
+        let end = vec![1u8, 2, 10, 11, 5, 6, 7, 8];
+
+        let first = start.create_delta_towards(&middle).delta;
+        let second = middle.create_delta_towards(&end).delta;
+        let unsquashed_size = serialized_size(&first) + serialized_size(&second);
+
+        let squashed =
+            Vec::<u8>::squash_patches(first.to_owned_delta(), second.to_owned_delta()).unwrap();
+        assert_eq!(
+            squashed,
+            vec![
+                SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved {
+                    index: 2,
+                    new: vec![10, 11]
+                }
+            ]
+        );
+        assert!(serialized_size(&squashed) < unsquashed_size);
+
+        let mut patched = start;
+        patched.apply_patch(squashed).unwrap();
+        assert_eq!(patched, end);
+    }
+
+    /// Verify that a squashed patch can be applied to sequences of any length, even when the
+    /// second patch's indices point to different items depending on the length.
+    #[test]
+    fn squash_without_knowing_the_length() {
+        let squashed = Vec::<u8>::squash_patches(
+            vec![SequenceModificationDeltaOwned::AppendOne { item: 4 }],
+            vec![
+                SequenceModificationDeltaOwned::DeleteOne { index: 0 },
+                SequenceModificationDeltaOwned::AppendOne { item: 5 },
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            squashed,
+            vec![
+                SequenceModificationDeltaOwned::AppendOne { item: 4 },
+                SequenceModificationDeltaOwned::PopFrontPushBackOne { item: 5 },
+            ]
+        );
+
+        for (start, end) in [(vec![], vec![5]), (vec![1, 2], vec![2, 4, 5])] {
+            let mut patched = start;
+            patched.apply_patch(squashed.clone()).unwrap();
+            assert_eq!(patched, end);
+        }
+    }
+
+    /// Verify that squashing fails if the second patch can't be applied on top of the first.
+    #[test]
+    fn squash_invalid_patches() {
+        let err = Vec::<u8>::squash_patches(
+            vec![SequenceModificationDeltaOwned::DeleteAll],
+            vec![SequenceModificationDeltaOwned::DeleteOne { index: 0 }],
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err.reason(),
+            PatchErrorReason::IndexOutOfBounds { .. }
+        ));
+    }
+
+    /// Verify that modifications that come before the vector gets replaced are dropped when
+    /// squashing.
+    #[test]
+    fn squash_drops_modifications_before_replace_all() {
+        let squashed = Vec::<u8>::squash_patches(
+            vec![SequenceModificationDeltaOwned::AppendOne { item: 4 }],
+            vec![
+                SequenceModificationDeltaOwned::ReplaceAll { new: vec![5] },
+                SequenceModificationDeltaOwned::AppendOne { item: 6 },
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            squashed,
+            vec![SequenceModificationDeltaOwned::ReplaceAll { new: vec![5, 6] }]
+        );
+    }
+
+    /// Verify that we can merge sequence patches that modify different ranges of the sequence.
+    #[test]
+    fn merge_sequence_patches() {
+        test_merge(
+            vec![1u8, 2, 3, 4, 5],
+            vec![0, 1, 2, 3, 4, 5],
+            vec![1, 2, 3, 4, 5, 6],
+            vec![0, 1, 2, 3, 4, 5, 6],
+        );
+        test_merge(
+            vec![1u8, 2, 3, 4, 5, 6],
+            vec![3, 4, 5, 6],
+            vec![1, 2, 3, 4, 5, 6, 7],
+            vec![3, 4, 5, 6, 7],
+        );
+        test_merge(
+            vec![1u8, 2, 3, 4, 5],
+            vec![1, 9, 9, 4, 5],
+            vec![1, 2, 3, 4, 5, 6],
+            vec![1, 9, 9, 4, 5, 6],
+        );
+        test_merge(
+            vec![1u8, 2, 3, 4, 5],
+            vec![1, 2, 3, 4, 5, 6],
+            vec![1, 2, 3, 4, 5, 6],
+            vec![1, 2, 3, 4, 5, 6],
+        );
+        test_merge(vec![1u8, 2, 3], vec![1, 2, 3], vec![], vec![]);
+    }
+
+    /// Verify that modifying the same range of a sequence returns a conflict at the start of the
+    /// range.
+    #[test]
+    fn merge_overlapping_ranges_conflicts() {
+        let base = vec![1u8, 2, 3];
+
+        let conflicts = Vec::<u8>::merge_patches(
+            &base,
+            vec![SequenceModificationDeltaOwned::AppendOne { item: 4 }],
+            vec![SequenceModificationDeltaOwned::AppendMany { items: vec![5, 6] }],
+        )
+        .unwrap_err();
+
+        assert_eq!(conflicts.conflicts().len(), 1);
+        assert_eq!(
+            conflicts.conflicts()[0].path().collect::<Vec<_>>(),
+            vec![&PathSegment::Index(3)]
+        );
+    }
+
+    /// Verify that we can convert sequence deltas into owned deltas without serializing them.
+    #[test]
+    fn sequence_to_owned_delta() {
+        test_to_owned_delta(vec![1u8, 2, 3], vec![1, 2, 3, 4, 5]);
+        test_to_owned_delta(vec![1u8, 2, 3], vec![1, 9, 3]);
+        test_to_owned_delta(vec![1u8, 2, 3, 4, 5], vec![3]);
+        test_to_owned_delta(vec![1u8, 2, 3], vec![]);
+        test_to_owned_delta(vec![], vec![4u8, 5]);
+        test_to_owned_delta(
+            vec!["a".to_string(), "b".to_string()],
+            vec!["c".to_string(), "a".to_string(), "b".to_string()],
+        );
+    }
+
+    /// Verify that sequence changes are described using the index of each item that was
+    /// inserted, removed or replaced.
+    #[test]
+    fn print_sequence_delta() {
+        test_print(vec![1u32, 2], vec![1, 2, 3], &["[2]: inserted"]);
+        test_print(
+            vec![1u32, 2, 3, 4, 5],
+            vec![1, 5],
+            &["[1]: removed", "[2]: removed", "[3]: removed"],
+        );
+        test_print(
+            vec![1u32, 2, 3, 4],
+            vec![1, 7, 3, 4, 5],
+            &["[1]: replaced", "[4]: inserted"],
+        );
+        test_print(vec![1u32, 2], vec![], &["[0]: removed", "[1]: removed"]);
+        test_print(vec![1u32, 2], vec![1, 2], &[]);
+    }
+
+    /// Verify that applying a sequence's JSON Patch to the start array gives us the end array.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn sequence_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        test_json_patch(vec![1u32, 2], vec![1, 2, 3]);
+        test_json_patch(vec![1u32, 2, 3, 4, 5], vec![1, 5]);
+        test_json_patch(vec![1u32, 2, 3, 4], vec![1, 7, 3, 4, 5]);
+        test_json_patch(vec![1u32, 2, 3, 4, 5, 6], vec![0, 1, 2, 9, 4, 6]);
+        test_json_patch(vec![1u32, 2, 3], vec![2, 3]);
+        test_json_patch(vec![1u32, 2], vec![]);
+        test_json_patch(vec![], vec![1u32, 2]);
+        test_json_patch(vec![1u32, 2], vec![1, 2]);
+    }
+
+    /// Verify that we can patch a byte vector from a borrowed patch.
+    #[test]
+    fn byte_vec_borrowed_patch() {
+        test_borrowed_patch(vec![1u8, 2, 3], vec![1, 2, 3, 4, 5]);
+        test_borrowed_patch(vec![1u8, 2, 3], vec![1, 9, 3]);
+        test_borrowed_patch(vec![1u8, 2, 3, 4, 5], vec![3]);
+        test_borrowed_patch(vec![1u8, 2, 3], vec![]);
+        test_borrowed_patch(vec![], vec![4u8, 5]);
+        test_borrowed_patch(vec![1u8, 2, 3, 4], vec![5, 6, 3, 4]);
+    }
+
+    /// Verify that we can patch a vector of items other than bytes from a borrowed patch.
+    #[test]
+    fn vec_borrowed_patch() {
+        test_borrowed_patch(vec![1u32, 2, 3], vec![1, 2, 3, 4, 5]);
+        test_borrowed_patch(vec![1u32, 2, 3, 4, 5], vec![1, 9, 5]);
+        test_borrowed_patch(vec![1u32, 2, 3], vec![]);
+        test_borrowed_patch(
+            vec!["a".to_string(), "b".to_string()],
+            vec!["c".to_string(), "b".to_string(), "d".to_string()],
+        );
+        test_borrowed_patch(
+            vec![1u16, 2].into_boxed_slice(),
+            vec![2u16, 3].into_boxed_slice(),
+        );
+    }
+
+    /// Verify that a borrowed byte patch falls back to copying the bytes from formats that can not
+    /// lend them out, such as JSON which writes them as a sequence of numbers.
+    #[test]
+    fn borrowed_byte_patch_from_json() {
+        let start = vec![1u8, 2, 3];
+        let end = vec![1u8, 2, 3, 4, 5];
+
+        let json = serde_json::to_string(&start.create_delta_towards(&end).delta).unwrap();
+        let patch: Vec<SequenceModificationDeltaBorrowed<u8>> =
+            serde_json::from_str(&json).unwrap();
+
+        let mut patched = start;
+        patched.apply_borrowed_patch(patch).unwrap();
+        assert_eq!(patched, end);
+    }
+
+    /// Verify that the bytes in a borrowed patch point into the buffer that it was deserialized
+    /// from.
+    #[test]
+    fn borrowed_patch_does_not_copy_bytes() {
+        let delta = vec![SequenceModificationDelta::AppendMany { items: &[4u8, 5] }];
+        let bytes = bincode::options()
+            .with_varint_encoding()
+            .serialize(&delta)
+            .unwrap();
+
+        let patch: Vec<SequenceModificationDeltaBorrowed<u8>> = bincode::options()
+            .with_varint_encoding()
+            .deserialize(&bytes)
+            .unwrap();
+
+        match patch.as_slice() {
+            [SequenceModificationDeltaBorrowed::AppendMany {
+                items: Cow::Borrowed(items),
+            }] => {
+                assert_eq!(*items, &[4, 5]);
+                assert!(bytes.as_ptr_range().contains(&items.as_ptr()));
+            }
+            _ => panic!("{:?}", patch),
+        };
+    }
+
+    /// Verify that a boxed slice is diffed and patched like a Vec, including from a borrowed patch.
+    #[test]
+    fn boxed_slice() {
+        DipaImplTester {
+            label: Some("Boxed slice append"),
+            start: &mut vec![1u8, 2].into_boxed_slice(),
+            end: &vec![1u8, 2, 3].into_boxed_slice(),
+            expected_delta: vec![SequenceModificationDelta::AppendOne { item: &3 }],
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        test_squash(
+            vec![1u8].into_boxed_slice(),
+            vec![1, 2].into_boxed_slice(),
+            vec![1, 2, 3].into_boxed_slice(),
+        );
+        test_merge(
+            vec![1u8, 2].into_boxed_slice(),
+            vec![1, 2, 3].into_boxed_slice(),
+            vec![1, 2].into_boxed_slice(),
+            vec![1, 2, 3].into_boxed_slice(),
+        );
+
+        test_to_owned_delta(
+            vec![1u8, 2, 3].into_boxed_slice(),
+            vec![1, 9].into_boxed_slice(),
+        );
+        test_borrowed_patch(
+            vec![1u8, 2, 3].into_boxed_slice(),
+            vec![1, 9, 3, 4].into_boxed_slice(),
+        );
+
+        assert_eq!(
+            <Box<[u16]> as crate::SchemaFingerprint>::FINGERPRINT,
+            <Vec<u16> as crate::SchemaFingerprint>::FINGERPRINT
+        );
+    }
+
+    fn serialized_size<D: Serialize>(delta: &D) -> usize {
+        bincode::options()
+            .with_varint_encoding()
+            .serialize(delta)
+            .unwrap()
+            .len()
+    }
 }
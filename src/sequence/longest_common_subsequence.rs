@@ -3,13 +3,14 @@
 /// Uses the dynamic programming approach to the LCS problem.
 ///
 /// FIXME: Refactor this. Messy.
-pub(super) fn get_longest_common_subsequence<'a, T: PartialEq>(
-    left: &'a [T],
-    right: &'a [T],
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn get_longest_common_subsequence<'a, L: PartialEq<R>, R>(
+    left: &'a [L],
+    right: &'a [R],
 ) -> Vec<(usize, usize)> {
     let mut solution = vec![];
 
-    if left.len() == 0 || right.len() == 0 {
+    if left.is_empty() || right.is_empty() {
         return solution;
     }
 
@@ -140,6 +141,7 @@ struct SubproblemSolution {
 }
 
 #[derive(Copy, Clone)]
+#[allow(clippy::enum_variant_names)]
 enum NextSubproblem {
     ReduceLeftAndRight,
     ReduceLeft,
@@ -150,11 +152,11 @@ enum NextSubproblem {
 mod tests {
     use super::*;
 
-    const A: &'static str = "A";
-    const B: &'static str = "B";
-    const C: &'static str = "C";
-    const D: &'static str = "D";
-    const E: &'static str = "E";
+    const A: &str = "A";
+    const B: &str = "B";
+    const C: &str = "C";
+    const D: &str = "D";
+    const E: &str = "E";
 
     /// Verify that we properly determine longest common subsequences.
     #[test]
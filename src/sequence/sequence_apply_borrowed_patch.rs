@@ -0,0 +1,135 @@
+use crate::sequence::sequence_apply_patch::check_range;
+use crate::sequence::SequenceModificationDeltaBorrowed;
+use crate::PatchError;
+use std::borrow::Cow;
+use std::ops::RangeBounds;
+
+// Tested in parent module.
+//
+// Mirrors `apply_patch`, copying borrowed items straight into the receiver and moving owned ones.
+pub(crate) fn apply_borrowed_patch<T: Clone>(
+    receiver: &mut Vec<T>,
+    patch: Vec<SequenceModificationDeltaBorrowed<'_, T>>,
+) -> Result<(), PatchError> {
+    for modification in patch {
+        let len = receiver.len();
+
+        match modification {
+            SequenceModificationDeltaBorrowed::InsertOne { index, value } => {
+                check_range(index, 0, len)?;
+                receiver.insert(index, value);
+            }
+            SequenceModificationDeltaBorrowed::DeleteOne { index } => {
+                check_range(index, 1, len)?;
+                receiver.remove(index);
+            }
+            SequenceModificationDeltaBorrowed::DeleteMany {
+                start_index,
+                items_to_delete,
+            } => {
+                check_range(start_index, items_to_delete, len)?;
+                receiver.drain(start_index..start_index + items_to_delete);
+            }
+            SequenceModificationDeltaBorrowed::DeleteAllAfterIncluding { start_index } => {
+                check_range(start_index, 0, len)?;
+                receiver.truncate(start_index);
+            }
+            SequenceModificationDeltaBorrowed::AppendOne { item } => {
+                receiver.push(item);
+            }
+            SequenceModificationDeltaBorrowed::PrependOne { item } => {
+                receiver.insert(0, item);
+            }
+            SequenceModificationDeltaBorrowed::InsertMany { start_idx, items } => {
+                check_range(start_idx, 0, len)?;
+                splice(receiver, start_idx..start_idx, items);
+            }
+            SequenceModificationDeltaBorrowed::DeleteAllBeforeIncluding { end_index } => {
+                check_range(end_index, 1, len)?;
+                receiver.drain(..=end_index);
+            }
+            SequenceModificationDeltaBorrowed::AppendMany { items } => {
+                splice(receiver, len.., items);
+            }
+            SequenceModificationDeltaBorrowed::DeleteFirst => {
+                check_range(0, 1, len)?;
+                receiver.remove(0);
+            }
+            SequenceModificationDeltaBorrowed::DeleteLast => {
+                check_range(0, 1, len)?;
+                receiver.pop();
+            }
+            SequenceModificationDeltaBorrowed::PrependMany { items } => {
+                splice(receiver, 0..0, items);
+            }
+            SequenceModificationDeltaBorrowed::ReplaceOne { index, new } => {
+                check_range(index, 1, len)?;
+                receiver[index] = new;
+            }
+            SequenceModificationDeltaBorrowed::ReplaceFirst { item } => {
+                check_range(0, 1, len)?;
+                receiver[0] = item;
+            }
+            SequenceModificationDeltaBorrowed::ReplaceLast { item } => {
+                check_range(0, 1, len)?;
+                receiver[len - 1] = item;
+            }
+            SequenceModificationDeltaBorrowed::ReplaceMany {
+                start_idx,
+                items_to_replace,
+                new,
+            } => {
+                check_range(start_idx, items_to_replace, len)?;
+                splice(receiver, start_idx..start_idx + items_to_replace, new);
+            }
+            SequenceModificationDeltaBorrowed::ReplaceManySameAmountAddedAndRemoved {
+                index,
+                new,
+            } => {
+                check_range(index, new.len(), len)?;
+                splice(receiver, index..index + new.len(), new);
+            }
+            SequenceModificationDeltaBorrowed::ReplaceAllBeforeIncluding { before, new } => {
+                check_range(before, 1, len)?;
+                splice(receiver, ..=before, new);
+            }
+            SequenceModificationDeltaBorrowed::ReplaceAllAfterIncluding { after, new } => {
+                check_range(after, 0, len)?;
+                splice(receiver, after.., new);
+            }
+            SequenceModificationDeltaBorrowed::DeleteAll => {
+                receiver.clear();
+            }
+            SequenceModificationDeltaBorrowed::ReplaceAll { new } => {
+                splice(receiver, .., new);
+            }
+            SequenceModificationDeltaBorrowed::PopFrontPushBackOne { item } => {
+                check_range(0, 1, len)?;
+                receiver.remove(0);
+                receiver.push(item);
+            }
+            SequenceModificationDeltaBorrowed::PopFrontPushBackMany {
+                pop_front,
+                push_back,
+            } => {
+                check_range(0, pop_front, len)?;
+                receiver.drain(..pop_front);
+                splice(receiver, len - pop_front.., push_back);
+            }
+        };
+    }
+
+    Ok(())
+}
+
+/// Replace the `range` of the receiver with the items, cloning them only if they are borrowed.
+fn splice<T: Clone>(receiver: &mut Vec<T>, range: impl RangeBounds<usize>, items: Cow<'_, [T]>) {
+    match items {
+        Cow::Borrowed(items) => {
+            receiver.splice(range, items.iter().cloned());
+        }
+        Cow::Owned(items) => {
+            receiver.splice(range, items);
+        }
+    }
+}
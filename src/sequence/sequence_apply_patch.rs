@@ -1,27 +1,32 @@
 use crate::sequence::SequenceModificationDeltaOwned;
+use crate::{PatchError, PatchErrorReason};
 
 // Tested in parent module.
-pub(super) fn apply_patch<'p, T>(
+pub(super) fn apply_patch<T>(
     receiver: &mut Vec<T>,
     patch: Vec<SequenceModificationDeltaOwned<T>>,
-) {
+) -> Result<(), PatchError> {
     for modification in patch {
+        let len = receiver.len();
+
         match modification {
             SequenceModificationDeltaOwned::InsertOne { index, value } => {
+                check_range(index, 0, len)?;
                 receiver.insert(index, value);
             }
             SequenceModificationDeltaOwned::DeleteOne { index } => {
+                check_range(index, 1, len)?;
                 receiver.remove(index);
             }
             SequenceModificationDeltaOwned::DeleteMany {
                 start_index,
                 items_to_delete,
             } => {
-                for _ in 0..items_to_delete {
-                    receiver.remove(start_index);
-                }
+                check_range(start_index, items_to_delete, len)?;
+                receiver.drain(start_index..start_index + items_to_delete);
             }
             SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index } => {
+                check_range(start_index, 0, len)?;
                 receiver.truncate(start_index);
             }
             SequenceModificationDeltaOwned::AppendOne { item } => {
@@ -31,88 +36,300 @@ pub(super) fn apply_patch<'p, T>(
                 receiver.insert(0, item);
             }
             SequenceModificationDeltaOwned::InsertMany { start_idx, items } => {
-                let mut current = start_idx;
-
-                for item in items {
-                    receiver.insert(current, item);
-                    current += 1;
-                }
+                check_range(start_idx, 0, len)?;
+                receiver.splice(start_idx..start_idx, items);
             }
             SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index } => {
-                for _ in 0..=end_index {
-                    receiver.remove(0);
-                }
+                check_range(end_index, 1, len)?;
+                receiver.drain(..=end_index);
             }
             SequenceModificationDeltaOwned::AppendMany { items } => {
-                for item in items {
-                    receiver.push(item);
-                }
+                receiver.extend(items);
             }
             SequenceModificationDeltaOwned::DeleteFirst => {
+                check_range(0, 1, len)?;
                 receiver.remove(0);
             }
             SequenceModificationDeltaOwned::DeleteLast => {
-                receiver.remove(receiver.len() - 1);
+                check_range(0, 1, len)?;
+                receiver.pop();
             }
             SequenceModificationDeltaOwned::PrependMany { items } => {
-                let mut idx = 0;
-
-                for item in items {
-                    receiver.insert(idx, item);
-                    idx += 1;
-                }
+                receiver.splice(0..0, items);
             }
             SequenceModificationDeltaOwned::ReplaceOne { index, new } => {
+                check_range(index, 1, len)?;
                 receiver[index] = new;
             }
             SequenceModificationDeltaOwned::ReplaceFirst { item } => {
+                check_range(0, 1, len)?;
                 receiver[0] = item;
             }
             SequenceModificationDeltaOwned::ReplaceLast { item } => {
-                *receiver.last_mut().unwrap() = item;
+                check_range(0, 1, len)?;
+                receiver[len - 1] = item;
             }
             SequenceModificationDeltaOwned::ReplaceMany {
                 start_idx,
                 items_to_replace,
                 new,
             } => {
-                for _ in 0..items_to_replace {
-                    receiver.remove(start_idx);
-                }
-
-                let mut offset = 0;
-                for item in new {
-                    receiver.insert(start_idx + offset, item);
-                    offset += 1;
-                }
+                check_range(start_idx, items_to_replace, len)?;
+                receiver.splice(start_idx..start_idx + items_to_replace, new);
             }
             SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved { index, new } => {
+                check_range(index, new.len(), len)?;
+
                 for (offset, item) in new.into_iter().enumerate() {
                     receiver[index + offset] = item;
                 }
             }
             SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding { before, new } => {
-                for _ in 0..=before {
-                    receiver.remove(0);
+                check_range(before, 1, len)?;
+                receiver.splice(..=before, new);
+            }
+            SequenceModificationDeltaOwned::ReplaceAllAfterIncluding { after, new } => {
+                check_range(after, 0, len)?;
+                receiver.truncate(after);
+                receiver.extend(new);
+            }
+            SequenceModificationDeltaOwned::DeleteAll => {
+                receiver.clear();
+            }
+            SequenceModificationDeltaOwned::ReplaceAll { new } => {
+                *receiver = new;
+            }
+            SequenceModificationDeltaOwned::PopFrontPushBackOne { item } => {
+                check_range(0, 1, len)?;
+                receiver.remove(0);
+                receiver.push(item);
+            }
+            SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                pop_front,
+                push_back,
+            } => {
+                check_range(0, pop_front, len)?;
+                receiver.drain(..pop_front);
+                receiver.extend(push_back);
+            }
+        };
+    }
+
+    Ok(())
+}
+
+// Tested in parent module.
+//
+// Each modification is inverted using the state of the receiver right before it gets applied, and
+// the inverted modifications are returned in reverse order so that they can be applied in sequence.
+pub(super) fn apply_patch_returning_inverse<T>(
+    receiver: &mut Vec<T>,
+    patch: Vec<SequenceModificationDeltaOwned<T>>,
+) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+    let mut inverse = Vec::with_capacity(patch.len());
+
+    for modification in patch {
+        let len = receiver.len();
+
+        let inverted = match modification {
+            SequenceModificationDeltaOwned::InsertOne { index, value } => {
+                check_range(index, 0, len)?;
+                receiver.insert(index, value);
+
+                SequenceModificationDeltaOwned::DeleteOne { index }
+            }
+            SequenceModificationDeltaOwned::DeleteOne { index } => {
+                check_range(index, 1, len)?;
+                let value = receiver.remove(index);
+
+                SequenceModificationDeltaOwned::InsertOne { index, value }
+            }
+            SequenceModificationDeltaOwned::DeleteMany {
+                start_index,
+                items_to_delete,
+            } => {
+                check_range(start_index, items_to_delete, len)?;
+                let items = receiver
+                    .drain(start_index..start_index + items_to_delete)
+                    .collect();
+
+                SequenceModificationDeltaOwned::InsertMany {
+                    start_idx: start_index,
+                    items,
+                }
+            }
+            SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index } => {
+                check_range(start_index, 0, len)?;
+                let items = receiver.split_off(start_index);
+
+                SequenceModificationDeltaOwned::AppendMany { items }
+            }
+            SequenceModificationDeltaOwned::AppendOne { item } => {
+                receiver.push(item);
+
+                SequenceModificationDeltaOwned::DeleteLast
+            }
+            SequenceModificationDeltaOwned::PrependOne { item } => {
+                receiver.insert(0, item);
+
+                SequenceModificationDeltaOwned::DeleteFirst
+            }
+            SequenceModificationDeltaOwned::InsertMany { start_idx, items } => {
+                check_range(start_idx, 0, len)?;
+                let items_to_delete = items.len();
+                receiver.splice(start_idx..start_idx, items);
+
+                SequenceModificationDeltaOwned::DeleteMany {
+                    start_index: start_idx,
+                    items_to_delete,
                 }
+            }
+            SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index } => {
+                check_range(end_index, 1, len)?;
+                let items = receiver.drain(..=end_index).collect();
+
+                SequenceModificationDeltaOwned::PrependMany { items }
+            }
+            SequenceModificationDeltaOwned::AppendMany { items } => {
+                receiver.extend(items);
+
+                SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index: len }
+            }
+            SequenceModificationDeltaOwned::DeleteFirst => {
+                check_range(0, 1, len)?;
+                let item = receiver.remove(0);
 
-                for (idx, item) in new.into_iter().enumerate() {
-                    receiver.insert(idx, item);
+                SequenceModificationDeltaOwned::PrependOne { item }
+            }
+            SequenceModificationDeltaOwned::DeleteLast => {
+                check_range(0, 1, len)?;
+                let item = receiver.remove(len - 1);
+
+                SequenceModificationDeltaOwned::AppendOne { item }
+            }
+            SequenceModificationDeltaOwned::PrependMany { items } => {
+                let items_to_delete = items.len();
+                receiver.splice(0..0, items);
+
+                SequenceModificationDeltaOwned::DeleteMany {
+                    start_index: 0,
+                    items_to_delete,
                 }
             }
-            SequenceModificationDeltaOwned::ReplaceAllAfterIncluding { after, new } => {
-                receiver.truncate(after);
+            SequenceModificationDeltaOwned::ReplaceOne { index, new } => {
+                check_range(index, 1, len)?;
+                let new = std::mem::replace(&mut receiver[index], new);
+
+                SequenceModificationDeltaOwned::ReplaceOne { index, new }
+            }
+            SequenceModificationDeltaOwned::ReplaceFirst { item } => {
+                check_range(0, 1, len)?;
+                let item = std::mem::replace(&mut receiver[0], item);
 
-                for item in new {
-                    receiver.push(item);
+                SequenceModificationDeltaOwned::ReplaceFirst { item }
+            }
+            SequenceModificationDeltaOwned::ReplaceLast { item } => {
+                check_range(0, 1, len)?;
+                let item = std::mem::replace(&mut receiver[len - 1], item);
+
+                SequenceModificationDeltaOwned::ReplaceLast { item }
+            }
+            SequenceModificationDeltaOwned::ReplaceMany {
+                start_idx,
+                items_to_replace,
+                new,
+            } => {
+                check_range(start_idx, items_to_replace, len)?;
+                let inserted = new.len();
+                let new = receiver
+                    .splice(start_idx..start_idx + items_to_replace, new)
+                    .collect();
+
+                SequenceModificationDeltaOwned::ReplaceMany {
+                    start_idx,
+                    items_to_replace: inserted,
+                    new,
                 }
             }
+            SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved { index, new } => {
+                check_range(index, new.len(), len)?;
+                let new = receiver.splice(index..index + new.len(), new).collect();
+
+                SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved { index, new }
+            }
+            SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding { before, new } => {
+                check_range(before, 1, len)?;
+                let inserted = new.len();
+                let new = receiver.splice(..=before, new).collect();
+
+                if inserted == 0 {
+                    SequenceModificationDeltaOwned::PrependMany { items: new }
+                } else {
+                    SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding {
+                        before: inserted - 1,
+                        new,
+                    }
+                }
+            }
+            SequenceModificationDeltaOwned::ReplaceAllAfterIncluding { after, new } => {
+                check_range(after, 0, len)?;
+                let new = receiver.splice(after.., new).collect();
+
+                SequenceModificationDeltaOwned::ReplaceAllAfterIncluding { after, new }
+            }
             SequenceModificationDeltaOwned::DeleteAll => {
-                receiver.clear();
+                let new = std::mem::take(receiver);
+
+                SequenceModificationDeltaOwned::ReplaceAll { new }
             }
             SequenceModificationDeltaOwned::ReplaceAll { new } => {
-                *receiver = new;
+                let new = std::mem::replace(receiver, new);
+
+                SequenceModificationDeltaOwned::ReplaceAll { new }
+            }
+            // There is no modification that pops from the back and pushes to the front, so these
+            // are inverted with two modifications. The second one gets pushed first since the
+            // inverse is reversed at the end.
+            SequenceModificationDeltaOwned::PopFrontPushBackOne { item } => {
+                check_range(0, 1, len)?;
+                let popped = receiver.remove(0);
+                receiver.push(item);
+
+                inverse.push(SequenceModificationDeltaOwned::PrependOne { item: popped });
+                SequenceModificationDeltaOwned::DeleteLast
+            }
+            SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                pop_front,
+                push_back,
+            } => {
+                check_range(0, pop_front, len)?;
+                let popped = receiver.drain(..pop_front).collect();
+                let start_index = receiver.len();
+                receiver.extend(push_back);
+
+                inverse.push(SequenceModificationDeltaOwned::PrependMany { items: popped });
+                SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index }
             }
         };
+
+        inverse.push(inverted);
     }
+
+    inverse.reverse();
+
+    Ok(inverse)
+}
+
+/// Make sure that the `count` items starting at `start` are within a sequence of length `len`.
+///
+/// A `count` of zero checks that `start` is a valid insertion point.
+pub(super) fn check_range(start: usize, count: usize, len: usize) -> Result<(), PatchError> {
+    if start <= len && count <= len - start {
+        return Ok(());
+    }
+
+    Err(PatchError::new(PatchErrorReason::IndexOutOfBounds {
+        index: start.max(len),
+        len,
+    }))
 }
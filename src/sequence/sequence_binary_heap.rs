@@ -0,0 +1,198 @@
+//! A `BinaryHeap` does not have a meaningful order for its items, so it is diffed by its sorted
+//! contents. The indices in its delta refer to positions within the heap's items in ascending
+//! order, which is also the order that `BinaryHeap::into_sorted_vec` returns them in.
+
+use crate::sequence::sequence_apply_patch::{apply_patch, apply_patch_returning_inverse};
+use crate::sequence::sequence_delta_patch_towards::delta_towards;
+use crate::sequence::sequence_merge_patches::merge_sequence_patches;
+use crate::sequence::sequence_squash_patches::squash_sequence_patches;
+use crate::sequence::sequence_visit_delta::visit_sequence_modifications;
+use crate::sequence::SequenceModificationDeltaOwned;
+use crate::{
+    CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches, PatchError, Patchable,
+    ReversiblePatchable, SquashablePatches, ToOwnedDelta, VisitDelta,
+};
+use std::collections::BinaryHeap;
+
+impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> Diffable<'s, 'e, BinaryHeap<T>> for BinaryHeap<T>
+where
+    T: Ord,
+    &'e T: serde::Serialize,
+{
+    type Delta = Vec<SequenceModificationDeltaOwned<&'e T>>;
+
+    type DeltaOwned = Vec<SequenceModificationDeltaOwned<T>>;
+
+    fn create_delta_towards(&self, end_state: &'e Self) -> CreatedDelta<Self::Delta> {
+        let start = sorted(self);
+        let end = sorted(end_state);
+
+        let CreatedDelta { delta, did_change } = delta_towards::<&T, &'e T>(&start, &end);
+
+        CreatedDelta {
+            delta: delta
+                .into_iter()
+                .map(ToOwnedDelta::to_owned_delta)
+                .collect(),
+            did_change,
+        }
+    }
+}
+
+impl<T: Ord> Patchable<Vec<SequenceModificationDeltaOwned<T>>> for BinaryHeap<T> {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<(), PatchError> {
+        with_sorted_vec(self, |vec| apply_patch(vec, patch))
+    }
+}
+
+impl<T: Ord> ReversiblePatchable<Vec<SequenceModificationDeltaOwned<T>>> for BinaryHeap<T> {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+        with_sorted_vec(self, |vec| apply_patch_returning_inverse(vec, patch))
+    }
+}
+
+impl<T: Ord> SquashablePatches<Vec<SequenceModificationDeltaOwned<T>>> for BinaryHeap<T> {
+    fn squash_patches(
+        first: Vec<SequenceModificationDeltaOwned<T>>,
+        second: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+        squash_sequence_patches(first, second)
+    }
+}
+
+impl<T: Ord> MergeablePatches<Vec<SequenceModificationDeltaOwned<T>>> for BinaryHeap<T> {
+    fn merge_patches(
+        base: &Self,
+        a: Vec<SequenceModificationDeltaOwned<T>>,
+        b: Vec<SequenceModificationDeltaOwned<T>>,
+    ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, MergeConflicts> {
+        merge_sequence_patches(base.len(), a, b)
+    }
+}
+
+impl<'s, 'e, T: 'e + crate::SchemaFingerprint<'s, 'e>> crate::SchemaFingerprint<'s, 'e>
+    for BinaryHeap<T>
+where
+    T: Ord,
+    &'e T: serde::Serialize,
+{
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("BinaryHeap")
+        .write_u64(T::FINGERPRINT)
+        .finish();
+}
+
+// There is no `ToJsonPatch` impl since a heap serializes its items in heap order, which the
+// indices of the delta do not refer to.
+impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> VisitDelta<'s, 'e> for BinaryHeap<T>
+where
+    T: Ord,
+    &'e T: serde::Serialize,
+{
+    fn visit_delta(
+        &self,
+        delta: &Vec<SequenceModificationDeltaOwned<&'e T>>,
+        visitor: &mut dyn DeltaVisitor,
+    ) {
+        visit_sequence_modifications(self.len(), delta.clone(), visitor)
+    }
+}
+
+fn sorted<T: Ord>(heap: &BinaryHeap<T>) -> Vec<&T> {
+    let mut items: Vec<&T> = heap.iter().collect();
+    items.sort();
+
+    items
+}
+
+fn with_sorted_vec<T: Ord, R>(heap: &mut BinaryHeap<T>, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+    let mut vec = std::mem::take(heap).into_sorted_vec();
+    let result = f(&mut vec);
+    *heap = BinaryHeap::from(vec);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta_visitor::test_print;
+
+    /// Verify that we diff a heap by its sorted contents.
+    ///
+    /// `BinaryHeap` does not implement `PartialEq`, so we compare the sorted items.
+    #[test]
+    fn diff_sorted_contents() {
+        let mut start = BinaryHeap::from(vec![3u8, 1, 5]);
+        let end = BinaryHeap::from(vec![5u8, 3, 1, 4]);
+
+        let diff = start.create_delta_towards(&end);
+        assert!(diff.did_change);
+        assert_eq!(
+            diff.delta,
+            vec![SequenceModificationDeltaOwned::InsertOne {
+                index: 2,
+                value: &4
+            }]
+        );
+
+        let inverse = start
+            .apply_patch_returning_inverse(diff.delta.to_owned_delta())
+            .unwrap();
+        assert_eq!(start.clone().into_sorted_vec(), vec![1, 3, 4, 5]);
+
+        start.apply_patch(inverse).unwrap();
+        assert_eq!(start.into_sorted_vec(), vec![1, 3, 5]);
+    }
+
+    /// Verify that heaps with the same items in a different heap order are unchanged.
+    #[test]
+    fn same_items_unchanged() {
+        let start: BinaryHeap<u8> = vec![1, 2, 3, 4].into_iter().collect();
+        let end: BinaryHeap<u8> = vec![4, 3, 2, 1].into_iter().collect();
+
+        let diff = start.create_delta_towards(&end);
+        assert!(!diff.did_change);
+        assert!(diff.delta.is_empty());
+    }
+
+    /// Verify that we can squash and merge heap patches.
+    #[test]
+    fn squash_and_merge() {
+        let base = BinaryHeap::from(vec![1u8, 3]);
+        let a = BinaryHeap::from(vec![1u8, 2, 3]);
+        let b = BinaryHeap::from(vec![1u8, 3, 4]);
+
+        let to_a = base.create_delta_towards(&a).delta.to_owned_delta();
+        let a_to_b = a.create_delta_towards(&b).delta.to_owned_delta();
+        let mut squashed = base.clone();
+        squashed
+            .apply_patch(BinaryHeap::squash_patches(to_a, a_to_b).unwrap())
+            .unwrap();
+        assert_eq!(squashed.into_sorted_vec(), vec![1, 3, 4]);
+
+        let to_a = base.create_delta_towards(&a).delta.to_owned_delta();
+        let to_b = base.create_delta_towards(&b).delta.to_owned_delta();
+        let mut merged = base.clone();
+        merged
+            .apply_patch(BinaryHeap::merge_patches(&base, to_a, to_b).unwrap())
+            .unwrap();
+        assert_eq!(merged.into_sorted_vec(), vec![1, 2, 3, 4]);
+    }
+
+    /// Verify that we can visit a heap's delta.
+    #[test]
+    fn visit_heap_delta() {
+        test_print(
+            BinaryHeap::from(vec![1u8, 3]),
+            BinaryHeap::from(vec![1u8, 2, 3]),
+            &["[1]: inserted"],
+        );
+    }
+}
@@ -3,21 +3,21 @@ use crate::sequence::SequenceModificationDelta;
 use crate::CreatedDelta;
 
 // Tests are in the parent module.
-pub(super) fn delta_towards<'p, T: PartialEq>(
-    before: &[T],
+pub(super) fn delta_towards<'p, B: PartialEq<T>, T>(
+    before: &[B],
     target_state: &'p [T],
 ) -> CreatedDelta<Vec<SequenceModificationDelta<'p, T>>>
 where
     &'p T: serde::Serialize,
 {
-    if target_state.len() == 0 && before.len() > 0 {
+    if target_state.is_empty() && !before.is_empty() {
         return CreatedDelta {
             delta: vec![SequenceModificationDelta::DeleteAll],
             did_change: true,
         };
     }
 
-    if before.len() == 0 && target_state.len() > 0 {
+    if before.is_empty() && !target_state.is_empty() {
         return CreatedDelta {
             delta: vec![SequenceModificationDelta::ReplaceAll { new: target_state }],
             did_change: true,
@@ -28,6 +28,14 @@ where
 
     let lcs = get_longest_common_subsequence(before, target_state);
 
+    // Both sequences have items but none of them are in common.
+    if lcs.is_empty() && !target_state.is_empty() {
+        return CreatedDelta {
+            delta: vec![SequenceModificationDelta::ReplaceAll { new: target_state }],
+            did_change: true,
+        };
+    }
+
     let mut previous_start_idx = None;
     let mut previous_target_idx = None;
 
@@ -66,15 +74,15 @@ where
                     Some(
                         SequenceModificationDelta::ReplaceManySameAmountAddedAndRemoved {
                             index: previous_start_idx.unwrap() + 1,
-                            new: &target_state[previous_target_idx.unwrap() + 1..target_advance],
+                            new: &target_state[previous_target_idx.unwrap() + 1..target_common_idx],
                         },
                     )
                 }
-                (start_advance, target_advance) if start_advance > 2 && target_advance > 1 => {
+                (start_advance, target_advance) if start_advance > 1 && target_advance > 1 => {
                     Some(SequenceModificationDelta::ReplaceMany {
                         start_idx: previous_start_idx.unwrap() + 1,
                         items_to_replace: start_advance - 1,
-                        new: &target_state[previous_target_idx.unwrap() + 1..target_advance],
+                        new: &target_state[previous_target_idx.unwrap() + 1..target_common_idx],
                     })
                 }
                 (start_advance, target_advance) if start_advance == 2 && target_advance == 1 => {
@@ -128,6 +136,12 @@ where
                     items: &target_state[0..target_common_idx],
                 };
                 modifications.push(modification);
+            } else if start_common_idx > 0 && target_common_idx > 0 {
+                let modification = SequenceModificationDelta::ReplaceAllBeforeIncluding {
+                    before: start_common_idx - 1,
+                    new: &target_state[0..target_common_idx],
+                };
+                modifications.push(modification);
             }
         }
 
@@ -164,6 +178,11 @@ where
             Some(SequenceModificationDelta::AppendMany {
                 items: &target_state[target_state.len() - target_remaining..],
             })
+        } else if start_remaining > 0 && target_remaining > 0 {
+            Some(SequenceModificationDelta::ReplaceAllAfterIncluding {
+                after: previous_start_idx + 1,
+                new: &target_state[previous_target_idx + 1..],
+            })
         } else {
             None
         };
@@ -174,7 +193,7 @@ where
 
     modifications.reverse();
 
-    let did_change = modifications.len() > 0;
+    let did_change = !modifications.is_empty();
     CreatedDelta {
         delta: modifications,
         did_change,
@@ -186,3 +205,65 @@ struct AdvancedBy {
     start_sequence: usize,
     target_sequence: usize,
 }
+
+// Tested in the sequence_queue module.
+//
+// A queue usually changes by having items popped from its front and pushed to its back. That is
+// checked for first since it is much cheaper than filling in a table for every pair of items like
+// the longest common subsequence does.
+//
+// Every candidate number of popped items is checked by comparing the kept items with the front of
+// the target state. Most candidates are rejected by their first item, but a queue full of repeated
+// items can match many items before being rejected, so the comparisons are capped at a couple of
+// passes over the items. Once the cap is reached we fall back to the longest common subsequence.
+pub(super) fn queue_delta_towards<'p, B: PartialEq<T>, T>(
+    before: &[B],
+    target_state: &'p [T],
+) -> CreatedDelta<Vec<SequenceModificationDelta<'p, T>>>
+where
+    &'p T: serde::Serialize,
+{
+    let mut comparisons_left = 2 * (before.len() + target_state.len());
+
+    // Fewer popped items would leave more items than the target state has.
+    let min_popped = before.len().saturating_sub(target_state.len());
+    let popped = (min_popped..before.len()).find(|&popped| {
+        before[popped..]
+            .iter()
+            .zip(target_state)
+            .all(|(kept, target)| {
+                if comparisons_left == 0 {
+                    return false;
+                }
+                comparisons_left -= 1;
+
+                kept == target
+            })
+    });
+
+    let popped = match popped {
+        Some(popped) => popped,
+        None => return delta_towards(before, target_state),
+    };
+    let pushed = &target_state[before.len() - popped..];
+
+    let modification = match (popped, pushed.len()) {
+        (0, 0) => None,
+        (0, 1) => Some(SequenceModificationDelta::AppendOne { item: &pushed[0] }),
+        (0, _) => Some(SequenceModificationDelta::AppendMany { items: pushed }),
+        (1, 0) => Some(SequenceModificationDelta::DeleteFirst),
+        (_, 0) => Some(SequenceModificationDelta::DeleteAllBeforeIncluding {
+            end_index: popped - 1,
+        }),
+        (1, 1) => Some(SequenceModificationDelta::PopFrontPushBackOne { item: &pushed[0] }),
+        (_, _) => Some(SequenceModificationDelta::PopFrontPushBackMany {
+            pop_front: popped,
+            push_back: pushed,
+        }),
+    };
+
+    CreatedDelta {
+        did_change: modification.is_some(),
+        delta: modification.into_iter().collect(),
+    }
+}
@@ -0,0 +1,59 @@
+use crate::sequence::sequence_merge_patches::hunks;
+use crate::sequence::sequence_visit_delta::borrowed_items;
+use crate::sequence::{SequenceModificationDelta, SequenceModificationDeltaOwned};
+use crate::{JsonPatch, JsonPatchError, JsonPointer};
+use serde::Serialize;
+
+// Tested in parent module.
+//
+// JSON Patch operations are applied one after another, so every index points into the array as it
+// is after the operations before it. We walk the replaced ranges of the start sequence from front
+// to back and shift each range by the number of items that the earlier ranges added or removed.
+pub(crate) fn push_sequence_json_patch<'a, T>(
+    start_len: usize,
+    delta: &[SequenceModificationDelta<'a, T>],
+    path: &JsonPointer,
+    patch: &mut JsonPatch,
+) -> Result<(), JsonPatchError>
+where
+    &'a T: Serialize,
+{
+    let modifications = delta.iter().map(borrowed_items).collect();
+    push_sequence_modifications_json_patch(start_len, modifications, path, patch)
+}
+
+/// Push the operations for modifications that are already in their owned form, such as the deltas
+/// of queues which hold references to the end queue's items.
+pub(crate) fn push_sequence_modifications_json_patch<T: Serialize>(
+    start_len: usize,
+    modifications: Vec<SequenceModificationDeltaOwned<T>>,
+    path: &JsonPointer,
+    patch: &mut JsonPatch,
+) -> Result<(), JsonPatchError> {
+    let hunks = hunks(start_len, modifications)
+        .map_err(|_| JsonPatchError::not_created_from("sequence"))?;
+
+    let mut inserted_before = 0;
+    let mut removed_before = 0;
+
+    for hunk in hunks {
+        let start = hunk.start + inserted_before - removed_before;
+        let removed = hunk.end - hunk.start;
+        let replaced = removed.min(hunk.new.len());
+
+        for (offset, item) in hunk.new[..replaced].iter().enumerate() {
+            patch.replace(&path.child(start + offset), item)?;
+        }
+        for _ in replaced..removed {
+            patch.remove(&path.child(start + replaced));
+        }
+        for (offset, item) in hunk.new[replaced..].iter().enumerate() {
+            patch.add(&path.child(start + replaced + offset), item)?;
+        }
+
+        inserted_before += hunk.new.len();
+        removed_before += removed;
+    }
+
+    Ok(())
+}
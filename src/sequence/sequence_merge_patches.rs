@@ -0,0 +1,267 @@
+use crate::sequence::sequence_apply_patch::apply_patch;
+use crate::sequence::SequenceModificationDeltaOwned;
+use crate::{MergeConflictReason, MergeConflicts, PathSegment};
+
+/// Where an item in a patched sequence came from.
+enum Origin<T> {
+    /// The item at this index in the base sequence.
+    Base(usize),
+    /// An item that the patch added.
+    New(T),
+}
+
+/// A range of the base sequence that a patch replaces with new items.
+///
+/// Insertions are empty ranges and deletions have no new items.
+#[derive(PartialEq)]
+pub(crate) struct Hunk<T> {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) new: Vec<T>,
+}
+
+// Tested in parent module.
+//
+// Modifications are applied one after another, so each patch gets turned into the ranges of the
+// base sequence that it replaced, which then get combined by `merge_hunks`.
+pub(crate) fn merge_sequence_patches<T: PartialEq>(
+    base_len: usize,
+    a: Vec<SequenceModificationDeltaOwned<T>>,
+    b: Vec<SequenceModificationDeltaOwned<T>>,
+) -> Result<Vec<SequenceModificationDeltaOwned<T>>, MergeConflicts> {
+    if a.is_empty() {
+        return Ok(b);
+    }
+    if b.is_empty() {
+        return Ok(a);
+    }
+
+    let a = hunks(base_len, a)?;
+    let b = hunks(base_len, b)?;
+
+    Ok(merge_hunks(a, b)?
+        .into_iter()
+        .map(hunk_to_modification)
+        .collect())
+}
+
+/// Combine the hunks of two patches, ordered from the end of the base sequence to the start so
+/// that applying them one after another keeps the indices of the ones that come first valid.
+///
+/// Hunks that are the same in both patches are only kept once.
+pub(crate) fn merge_hunks<T: PartialEq>(
+    a: Vec<Hunk<T>>,
+    b: Vec<Hunk<T>>,
+) -> Result<Vec<Hunk<T>>, MergeConflicts> {
+    let mut merged: Vec<Hunk<T>> = Vec::with_capacity(a.len() + b.len());
+    let mut conflicts = MergeConflicts::default();
+
+    for hunk in a.into_iter().chain(b) {
+        match merged.iter().find(|other| overlaps(&hunk, other)) {
+            Some(other) if *other == hunk => {}
+            Some(_) => conflicts.extend(
+                MergeConflicts::new(MergeConflictReason::ConcurrentChange)
+                    .with_parent(PathSegment::Index(hunk.start)),
+            ),
+            None => merged.push(hunk),
+        };
+    }
+
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+
+    merged.sort_by_key(|hunk| std::cmp::Reverse(hunk.start));
+
+    Ok(merged)
+}
+
+/// Whether two hunks from different patches touch the same part of the base sequence.
+///
+/// Replacing the items right next to each other is fine, but an insertion next to or inside of
+/// another hunk is a conflict since the order that the new items end up in would be ambiguous.
+fn overlaps<T>(a: &Hunk<T>, b: &Hunk<T>) -> bool {
+    let a_is_insert = a.start == a.end;
+    let b_is_insert = b.start == b.end;
+
+    if a_is_insert || b_is_insert {
+        a.start <= b.end && b.start <= a.end
+    } else {
+        a.start < b.end && b.start < a.end
+    }
+}
+
+/// The ranges of the base sequence that a patch replaces, ordered from the start of the sequence
+/// to the end.
+pub(super) fn hunks<T>(
+    base_len: usize,
+    patch: Vec<SequenceModificationDeltaOwned<T>>,
+) -> Result<Vec<Hunk<T>>, MergeConflicts> {
+    let mut origins: Vec<Origin<T>> = (0..base_len).map(Origin::Base).collect();
+    let patch = patch
+        .into_iter()
+        .map(|modification| map_items(modification, Origin::New))
+        .collect();
+    apply_patch(&mut origins, patch)?;
+
+    let mut hunks = vec![];
+    let mut next_base = 0;
+    let mut new = vec![];
+
+    for origin in origins
+        .into_iter()
+        .chain(std::iter::once(Origin::Base(base_len)))
+    {
+        match origin {
+            Origin::Base(idx) => {
+                if idx > next_base || !new.is_empty() {
+                    hunks.push(Hunk {
+                        start: next_base,
+                        end: idx,
+                        new: std::mem::take(&mut new),
+                    });
+                }
+
+                next_base = idx + 1;
+            }
+            Origin::New(item) => new.push(item),
+        }
+    }
+
+    Ok(hunks)
+}
+
+fn hunk_to_modification<T>(hunk: Hunk<T>) -> SequenceModificationDeltaOwned<T> {
+    let Hunk { start, end, new } = hunk;
+
+    if start == end {
+        SequenceModificationDeltaOwned::InsertMany {
+            start_idx: start,
+            items: new,
+        }
+    } else if new.is_empty() {
+        SequenceModificationDeltaOwned::DeleteMany {
+            start_index: start,
+            items_to_delete: end - start,
+        }
+    } else {
+        SequenceModificationDeltaOwned::ReplaceMany {
+            start_idx: start,
+            items_to_replace: end - start,
+            new,
+        }
+    }
+}
+
+pub(super) fn map_items<T, U>(
+    modification: SequenceModificationDeltaOwned<T>,
+    mut map: impl FnMut(T) -> U,
+) -> SequenceModificationDeltaOwned<U> {
+    match modification {
+        SequenceModificationDeltaOwned::InsertOne { index, value } => {
+            SequenceModificationDeltaOwned::InsertOne {
+                index,
+                value: map(value),
+            }
+        }
+        SequenceModificationDeltaOwned::PrependOne { item } => {
+            SequenceModificationDeltaOwned::PrependOne { item: map(item) }
+        }
+        SequenceModificationDeltaOwned::AppendOne { item } => {
+            SequenceModificationDeltaOwned::AppendOne { item: map(item) }
+        }
+        SequenceModificationDeltaOwned::DeleteOne { index } => {
+            SequenceModificationDeltaOwned::DeleteOne { index }
+        }
+        SequenceModificationDeltaOwned::ReplaceOne { index, new } => {
+            SequenceModificationDeltaOwned::ReplaceOne {
+                index,
+                new: map(new),
+            }
+        }
+        SequenceModificationDeltaOwned::DeleteFirst => SequenceModificationDeltaOwned::DeleteFirst,
+        SequenceModificationDeltaOwned::DeleteLast => SequenceModificationDeltaOwned::DeleteLast,
+        SequenceModificationDeltaOwned::ReplaceFirst { item } => {
+            SequenceModificationDeltaOwned::ReplaceFirst { item: map(item) }
+        }
+        SequenceModificationDeltaOwned::ReplaceLast { item } => {
+            SequenceModificationDeltaOwned::ReplaceLast { item: map(item) }
+        }
+        SequenceModificationDeltaOwned::PrependMany { items } => {
+            SequenceModificationDeltaOwned::PrependMany {
+                items: map_many(items, &mut map),
+            }
+        }
+        SequenceModificationDeltaOwned::InsertMany { start_idx, items } => {
+            SequenceModificationDeltaOwned::InsertMany {
+                start_idx,
+                items: map_many(items, &mut map),
+            }
+        }
+        SequenceModificationDeltaOwned::DeleteMany {
+            start_index,
+            items_to_delete,
+        } => SequenceModificationDeltaOwned::DeleteMany {
+            start_index,
+            items_to_delete,
+        },
+        SequenceModificationDeltaOwned::AppendMany { items } => {
+            SequenceModificationDeltaOwned::AppendMany {
+                items: map_many(items, &mut map),
+            }
+        }
+        SequenceModificationDeltaOwned::ReplaceMany {
+            start_idx,
+            items_to_replace,
+            new,
+        } => SequenceModificationDeltaOwned::ReplaceMany {
+            start_idx,
+            items_to_replace,
+            new: map_many(new, &mut map),
+        },
+        SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved { index, new } => {
+            SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved {
+                index,
+                new: map_many(new, &mut map),
+            }
+        }
+        SequenceModificationDeltaOwned::ReplaceAll { new } => {
+            SequenceModificationDeltaOwned::ReplaceAll {
+                new: map_many(new, &mut map),
+            }
+        }
+        SequenceModificationDeltaOwned::DeleteAll => SequenceModificationDeltaOwned::DeleteAll,
+        SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index } => {
+            SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index }
+        }
+        SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index } => {
+            SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index }
+        }
+        SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding { before, new } => {
+            SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding {
+                before,
+                new: map_many(new, &mut map),
+            }
+        }
+        SequenceModificationDeltaOwned::ReplaceAllAfterIncluding { after, new } => {
+            SequenceModificationDeltaOwned::ReplaceAllAfterIncluding {
+                after,
+                new: map_many(new, &mut map),
+            }
+        }
+        SequenceModificationDeltaOwned::PopFrontPushBackOne { item } => {
+            SequenceModificationDeltaOwned::PopFrontPushBackOne { item: map(item) }
+        }
+        SequenceModificationDeltaOwned::PopFrontPushBackMany {
+            pop_front,
+            push_back,
+        } => SequenceModificationDeltaOwned::PopFrontPushBackMany {
+            pop_front,
+            push_back: map_many(push_back, &mut map),
+        },
+    }
+}
+
+fn map_many<T, U>(items: Vec<T>, map: &mut impl FnMut(T) -> U) -> Vec<U> {
+    items.into_iter().map(map).collect()
+}
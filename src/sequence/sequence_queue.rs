@@ -0,0 +1,509 @@
+//! `VecDeque` and `LinkedList` do not store their items in a single slice, so their deltas can not
+//! borrow slices of the end queue like a `Vec`'s delta does. They hold references to the end
+//! queue's items instead, which serialize the same way as the items themselves.
+//!
+//! Patches that only pop items from the front and push items to the back, which is what a queue's
+//! delta usually looks like, are applied to the queue in place. Any other patch gets applied to
+//! the queue's items as a `Vec`.
+
+use crate::sequence::sequence_apply_borrowed_patch::apply_borrowed_patch;
+use crate::sequence::sequence_apply_patch::{
+    apply_patch, apply_patch_returning_inverse, check_range,
+};
+use crate::sequence::sequence_delta_patch_towards::queue_delta_towards;
+use crate::sequence::sequence_merge_patches::merge_sequence_patches;
+use crate::sequence::sequence_squash_patches::squash_sequence_patches;
+use crate::sequence::sequence_visit_delta::visit_sequence_modifications;
+use crate::sequence::{SequenceModificationDeltaBorrowed, SequenceModificationDeltaOwned};
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SquashablePatches, ToOwnedDelta, VisitDelta,
+};
+use serde::Deserialize;
+use std::collections::{LinkedList, VecDeque};
+
+/// A queue that patches can be applied to.
+trait Queue<T>: Default + Extend<T> {
+    fn len(&self) -> usize;
+
+    fn pop_front(&mut self) -> Option<T>;
+
+    fn into_vec(self) -> Vec<T>;
+
+    fn from_vec(vec: Vec<T>) -> Self;
+}
+
+impl<T> Queue<T> for VecDeque<T> {
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        VecDeque::pop_front(self)
+    }
+
+    fn into_vec(self) -> Vec<T> {
+        Vec::from(self)
+    }
+
+    fn from_vec(vec: Vec<T>) -> Self {
+        VecDeque::from(vec)
+    }
+}
+
+impl<T> Queue<T> for LinkedList<T> {
+    fn len(&self) -> usize {
+        LinkedList::len(self)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        LinkedList::pop_front(self)
+    }
+
+    fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    fn from_vec(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+macro_rules! queue_impl {
+    ($queue:ident) => {
+        impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> Diffable<'s, 'e, $queue<T>> for $queue<T>
+        where
+            T: PartialEq,
+            &'e T: serde::Serialize,
+        {
+            type Delta = Vec<SequenceModificationDeltaOwned<&'e T>>;
+
+            type DeltaOwned = Vec<SequenceModificationDeltaOwned<T>>;
+
+            fn create_delta_towards(&self, end_state: &'e Self) -> CreatedDelta<Self::Delta> {
+                let start: Vec<&T> = self.iter().collect();
+                let end: Vec<&'e T> = end_state.iter().collect();
+
+                let CreatedDelta { delta, did_change } =
+                    queue_delta_towards::<&T, &'e T>(&start, &end);
+
+                CreatedDelta {
+                    delta: delta
+                        .into_iter()
+                        .map(ToOwnedDelta::to_owned_delta)
+                        .collect(),
+                    did_change,
+                }
+            }
+        }
+
+        impl<T> Patchable<Vec<SequenceModificationDeltaOwned<T>>> for $queue<T> {
+            fn apply_patch(
+                &mut self,
+                patch: Vec<SequenceModificationDeltaOwned<T>>,
+            ) -> Result<(), PatchError> {
+                apply_queue_patch(self, patch)
+            }
+        }
+
+        impl<T> ReversiblePatchable<Vec<SequenceModificationDeltaOwned<T>>> for $queue<T> {
+            fn apply_patch_returning_inverse(
+                &mut self,
+                patch: Vec<SequenceModificationDeltaOwned<T>>,
+            ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+                with_vec(self, |vec| apply_patch_returning_inverse(vec, patch))
+            }
+        }
+
+        impl<T> SquashablePatches<Vec<SequenceModificationDeltaOwned<T>>> for $queue<T> {
+            fn squash_patches(
+                first: Vec<SequenceModificationDeltaOwned<T>>,
+                second: Vec<SequenceModificationDeltaOwned<T>>,
+            ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+                squash_sequence_patches(first, second)
+            }
+        }
+
+        impl<T: PartialEq> MergeablePatches<Vec<SequenceModificationDeltaOwned<T>>> for $queue<T> {
+            fn merge_patches(
+                base: &Self,
+                a: Vec<SequenceModificationDeltaOwned<T>>,
+                b: Vec<SequenceModificationDeltaOwned<T>>,
+            ) -> Result<Vec<SequenceModificationDeltaOwned<T>>, MergeConflicts> {
+                merge_sequence_patches(base.len(), a, b)
+            }
+        }
+
+        // Encoded the same way as a `Vec<T>`.
+        impl<'s, 'e, T: 'e + crate::SchemaFingerprint<'s, 'e>> crate::SchemaFingerprint<'s, 'e>
+            for $queue<T>
+        where
+            T: PartialEq,
+            &'e T: serde::Serialize,
+        {
+            const FINGERPRINT: u64 = <Vec<T> as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+        }
+
+        impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> VisitDelta<'s, 'e> for $queue<T>
+        where
+            T: PartialEq,
+            &'e T: serde::Serialize,
+        {
+            fn visit_delta(
+                &self,
+                delta: &Vec<SequenceModificationDeltaOwned<&'e T>>,
+                visitor: &mut dyn DeltaVisitor,
+            ) {
+                visit_sequence_modifications(self.len(), delta.clone(), visitor)
+            }
+        }
+
+        #[cfg(feature = "json-patch")]
+        impl<'s, 'e, T: 'e + Diffable<'s, 'e, T>> crate::ToJsonPatch<'s, 'e> for $queue<T>
+        where
+            T: PartialEq,
+            &'e T: serde::Serialize,
+        {
+            fn push_json_patch(
+                &self,
+                delta: &Vec<SequenceModificationDeltaOwned<&'e T>>,
+                path: &crate::JsonPointer,
+                patch: &mut crate::JsonPatch,
+            ) -> Result<(), crate::JsonPatchError> {
+                crate::sequence::sequence_json_patch::push_sequence_modifications_json_patch(
+                    self.len(),
+                    delta.clone(),
+                    path,
+                    patch,
+                )
+            }
+        }
+    };
+}
+
+queue_impl!(VecDeque);
+queue_impl!(LinkedList);
+
+impl<'b, T: Clone> Patchable<Vec<SequenceModificationDeltaBorrowed<'b, T>>> for VecDeque<T> {
+    fn apply_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'b, T>>,
+    ) -> Result<(), PatchError> {
+        with_vec(self, |vec| apply_borrowed_patch(vec, patch))
+    }
+}
+
+impl<'de, T> BorrowedDelta<'de> for VecDeque<T>
+where
+    T: 'de + BorrowedDelta<'de> + Clone + Deserialize<'de>,
+{
+    type DeltaBorrowed = Vec<SequenceModificationDeltaBorrowed<'de, T>>;
+
+    fn apply_borrowed_patch(
+        &mut self,
+        patch: Vec<SequenceModificationDeltaBorrowed<'de, T>>,
+    ) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+fn apply_queue_patch<Q: Queue<T>, T>(
+    queue: &mut Q,
+    patch: Vec<SequenceModificationDeltaOwned<T>>,
+) -> Result<(), PatchError> {
+    let pops_and_pushes_only = patch.iter().all(|modification| {
+        matches!(
+            modification,
+            SequenceModificationDeltaOwned::AppendOne { .. }
+                | SequenceModificationDeltaOwned::AppendMany { .. }
+                | SequenceModificationDeltaOwned::DeleteFirst
+                | SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { .. }
+                | SequenceModificationDeltaOwned::PopFrontPushBackOne { .. }
+                | SequenceModificationDeltaOwned::PopFrontPushBackMany { .. }
+        )
+    });
+    if !pops_and_pushes_only {
+        return with_vec(queue, |vec| apply_patch(vec, patch));
+    }
+
+    for modification in patch {
+        let (pop_front, push_back) = match modification {
+            SequenceModificationDeltaOwned::AppendOne { item } => (0, vec![item]),
+            SequenceModificationDeltaOwned::AppendMany { items } => (0, items),
+            SequenceModificationDeltaOwned::DeleteFirst => (1, vec![]),
+            SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index } => {
+                check_range(end_index, 1, queue.len())?;
+                (end_index + 1, vec![])
+            }
+            SequenceModificationDeltaOwned::PopFrontPushBackOne { item } => (1, vec![item]),
+            SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                pop_front,
+                push_back,
+            } => (pop_front, push_back),
+            _ => unreachable!("Checked that the patch only pops and pushes"),
+        };
+
+        check_range(0, pop_front, queue.len())?;
+        for _ in 0..pop_front {
+            queue.pop_front();
+        }
+        queue.extend(push_back);
+    }
+
+    Ok(())
+}
+
+fn with_vec<Q: Queue<T>, T, R>(queue: &mut Q, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+    let mut vec = std::mem::take(queue).into_vec();
+    let result = f(&mut vec);
+    *queue = Q::from_vec(vec);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::dipa_impl_tester::DipaImplTester;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
+    use crate::SchemaFingerprint;
+
+    /// Verify that a queue that had items popped from its front and pushed to its back uses the
+    /// queue modifications.
+    #[test]
+    fn pop_front_push_back() {
+        DipaImplTester {
+            label: Some("Pop one push one"),
+            start: &mut VecDeque::from(vec![1u8, 2, 3]),
+            end: &VecDeque::from(vec![2u8, 3, 4]),
+            expected_delta: vec![SequenceModificationDeltaOwned::PopFrontPushBackOne { item: &4 }],
+            // 1 for the length of the delta, 1 for the variant and 1 for the item
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Pop many push many"),
+            start: &mut VecDeque::from(vec![1u8, 2, 3, 4]),
+            end: &VecDeque::from(vec![3u8, 4, 5, 6, 7]),
+            expected_delta: vec![SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                pop_front: 2,
+                push_back: vec![&5, &6, &7],
+            }],
+            // 1 for the length of the delta, 1 for the variant, 1 for the number of popped items,
+            // 1 for the number of pushed items and 3 for the items
+            expected_serialized_patch_size: 7,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Push only"),
+            start: &mut LinkedList::from_vec(vec![1u8, 2]),
+            end: &LinkedList::from_vec(vec![1u8, 2, 3, 4]),
+            expected_delta: vec![SequenceModificationDeltaOwned::AppendMany {
+                items: vec![&3, &4],
+            }],
+            expected_serialized_patch_size: 5,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Pop only"),
+            start: &mut LinkedList::from_vec(vec![1u8, 2, 3]),
+            end: &LinkedList::from_vec(vec![3u8]),
+            expected_delta: vec![SequenceModificationDeltaOwned::DeleteAllBeforeIncluding {
+                end_index: 1,
+            }],
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that changes that do not just pop and push fall back to the longest common
+    /// subsequence.
+    #[test]
+    fn other_changes() {
+        DipaImplTester {
+            label: Some("No change"),
+            start: &mut VecDeque::from(vec![1u8, 2, 3]),
+            end: &VecDeque::from(vec![1u8, 2, 3]),
+            expected_delta: vec![],
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Replace middle"),
+            start: &mut VecDeque::from(vec![1u8, 2, 3]),
+            end: &VecDeque::from(vec![1u8, 5, 3]),
+            expected_delta: vec![SequenceModificationDeltaOwned::ReplaceOne { index: 1, new: &5 }],
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Nothing kept"),
+            start: &mut LinkedList::from_vec(vec![1u8, 2]),
+            end: &LinkedList::new(),
+            expected_delta: vec![SequenceModificationDeltaOwned::DeleteAll],
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that we diff a queue whose items wrap around the end of its buffer.
+    #[test]
+    fn wrapped_ring_buffer() {
+        let mut start = VecDeque::with_capacity(4);
+        start.extend([0u8, 0, 1, 2]);
+        start.drain(..2);
+        start.extend([3, 4]);
+        assert!(!start.as_slices().1.is_empty());
+
+        let end = VecDeque::from(vec![3u8, 4, 5]);
+
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(
+            delta,
+            vec![SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                pop_front: 2,
+                push_back: vec![&5],
+            }]
+        );
+
+        start.apply_patch(delta.to_owned_delta()).unwrap();
+        assert_eq!(start, end);
+    }
+
+    /// Verify that a queue full of repeated items still finds the popped and pushed items, and
+    /// falls back to the longest common subsequence when many items match before a mismatch.
+    #[test]
+    fn repeated_items() {
+        DipaImplTester {
+            label: Some("Pop and push repeated items"),
+            start: &mut VecDeque::from(vec![1u8, 1, 1, 2]),
+            end: &VecDeque::from(vec![1u8, 1, 2, 3]),
+            expected_delta: vec![SequenceModificationDeltaOwned::PopFrontPushBackOne { item: &3 }],
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        let start = VecDeque::from(vec![7u8; 200]);
+        let mut end = start.clone();
+        end[100] = 8;
+
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(
+            delta,
+            vec![SequenceModificationDeltaOwned::ReplaceOne {
+                index: 100,
+                new: &8
+            }]
+        );
+    }
+
+    /// Verify that squashing the patches of a ring buffer that wrapped past its capacity over
+    /// several ticks combines all of the pops and all of the pushes.
+    ///
+    /// The pushes come first since the squashed pops could reach the pushed items of a queue that
+    /// had fewer items.
+    #[test]
+    fn squash_wrapped_ring_buffer() {
+        let mut ticks = vec![VecDeque::with_capacity(4)];
+        ticks[0].extend([1u8, 2, 3, 4]);
+        for item in 5..=8 {
+            let mut next = ticks.last().unwrap().clone();
+            next.pop_front();
+            next.push_back(item);
+            ticks.push(next);
+        }
+
+        let mut squashed = vec![];
+        for pair in ticks.windows(2) {
+            let patch = pair[0]
+                .create_delta_towards(&pair[1])
+                .delta
+                .to_owned_delta();
+            squashed = VecDeque::squash_patches(squashed, patch).unwrap();
+        }
+
+        assert_eq!(
+            squashed,
+            vec![
+                SequenceModificationDeltaOwned::AppendMany {
+                    items: vec![5, 6, 7, 8]
+                },
+                SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index: 3 },
+            ]
+        );
+
+        let mut queue = ticks[0].clone();
+        queue.apply_patch(squashed).unwrap();
+        assert_eq!(&queue, ticks.last().unwrap());
+    }
+
+    /// Verify that a patch that pops more items than the queue has is an error.
+    #[test]
+    fn pop_out_of_bounds() {
+        let mut queue = VecDeque::from(vec![1u8]);
+
+        assert!(queue
+            .apply_patch(vec![SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                pop_front: 2,
+                push_back: vec![],
+            }])
+            .is_err());
+    }
+
+    /// Verify that the queue impls work with the other dipa traits.
+    #[test]
+    fn queue_traits() {
+        test_squash(
+            VecDeque::from(vec![1u8, 2]),
+            VecDeque::from(vec![2, 3]),
+            VecDeque::from(vec![3, 4]),
+        );
+        test_merge(
+            LinkedList::from_vec(vec![1u8, 2, 3]),
+            LinkedList::from_vec(vec![1, 5, 3]),
+            LinkedList::from_vec(vec![1, 2, 3, 4]),
+            LinkedList::from_vec(vec![1, 5, 3, 4]),
+        );
+        test_to_owned_delta(VecDeque::from(vec![1u8, 2]), VecDeque::from(vec![2u8, 3]));
+        test_borrowed_patch(
+            VecDeque::from(vec![1u8, 2, 3]),
+            VecDeque::from(vec![3u8, 4, 5]),
+        );
+        test_borrowed_patch(
+            VecDeque::from(vec![1u32, 2, 3]),
+            VecDeque::from(vec![3u32, 4, 5]),
+        );
+        test_print(
+            VecDeque::from(vec![1u8, 2]),
+            VecDeque::from(vec![2u8, 3]),
+            &["[0]: removed", "[1]: inserted"],
+        );
+        #[cfg(feature = "json-patch")]
+        crate::json_patch::test_json_patch(
+            VecDeque::from(vec![1u8, 2]),
+            VecDeque::from(vec![2u8, 3]),
+        );
+
+        assert_eq!(
+            <VecDeque<u16> as SchemaFingerprint>::FINGERPRINT,
+            <Vec<u16> as SchemaFingerprint>::FINGERPRINT
+        );
+    }
+}
@@ -0,0 +1,514 @@
+use crate::sequence::sequence_apply_patch::apply_patch;
+use crate::sequence::sequence_merge_patches::{map_items, Hunk};
+use crate::sequence::SequenceModificationDeltaOwned;
+use crate::PatchError;
+
+/// The most items that we will pull out of the untouched part of the sequence in order to apply a
+/// modification. Modifications that reach further than this are kept as they are instead, so that
+/// a corrupt index can't make us allocate an enormous amount of memory.
+const MAX_SPLIT_ITEMS: usize = 1 << 20;
+
+/// An item in the sequence that the patches get applied to.
+///
+/// We don't know how long that sequence is, so the items that no modification has reached yet are
+/// kept together in a single `Rest`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Slot {
+    /// The item at this index of the original sequence.
+    Head(usize),
+    /// The items of the original sequence that come after every `Head` and before every `Tail`.
+    /// There might not be any.
+    Rest,
+    /// The item at this many places before the end of the original sequence, where 0 is the
+    /// last item.
+    Tail(usize),
+    /// The new item at this index in the list of new items.
+    New(usize),
+}
+
+/// Modifications that have been applied to a sequence of unknown length.
+struct Squashed {
+    slots: Vec<Slot>,
+    /// How many `Head`s have been pulled out of the `Rest`.
+    heads: usize,
+    /// How many `Tail`s have been pulled out of the `Rest`.
+    tails: usize,
+    /// How many items get removed from the front of the `Rest` and everything after it, for when
+    /// we don't know if a queue is popping items that were in the original sequence or items that
+    /// were pushed onto it.
+    popped: usize,
+    /// The modifications that were applied, in case the result can't be expressed as a patch.
+    modifications: Vec<SequenceModificationDeltaOwned<usize>>,
+}
+
+// Tested in parent module.
+//
+// The second patch's indices only make sense after the first patch has been applied, so we apply
+// both patches to a stand-in for the original sequence and then describe the ranges of the
+// original sequence that ended up being replaced. Overlapping and adjacent modifications end up in
+// the same range, so they get combined into one modification.
+//
+// We don't know how long the original sequence is, so the stand-in only tracks the items that the
+// modifications touched. When a modification's indices could point to different items depending
+// on the length of the original sequence, the modifications before it get squashed on their own
+// and we start over from there.
+pub(crate) fn squash_sequence_patches<T>(
+    first: Vec<SequenceModificationDeltaOwned<T>>,
+    second: Vec<SequenceModificationDeltaOwned<T>>,
+) -> Result<Vec<SequenceModificationDeltaOwned<T>>, PatchError> {
+    let mut items = vec![];
+    let modifications: Vec<_> = first
+        .into_iter()
+        .chain(second)
+        .map(|modification| {
+            map_items(modification, |item| {
+                items.push(Some(item));
+                items.len() - 1
+            })
+        })
+        .collect();
+
+    let mut squashed = vec![];
+    let mut current = Squashed::new();
+
+    for modification in modifications {
+        if matches!(
+            modification,
+            SequenceModificationDeltaOwned::ReplaceAll { .. }
+                | SequenceModificationDeltaOwned::DeleteAll
+        ) {
+            squashed.clear();
+            current = Squashed::new();
+        }
+
+        if current.apply(&modification)? {
+            continue;
+        }
+
+        squashed.extend(current.into_modifications());
+        current = Squashed::new();
+
+        if !current.apply(&modification)? {
+            squashed.push(modification);
+            current = Squashed::new();
+        }
+    }
+    squashed.extend(current.into_modifications());
+
+    Ok(squashed
+        .into_iter()
+        .map(|modification| {
+            map_items(modification, |idx| {
+                items[idx].take().expect("Every new item is used once")
+            })
+        })
+        .collect())
+}
+
+impl Squashed {
+    fn new() -> Self {
+        Squashed {
+            slots: vec![Slot::Rest],
+            heads: 0,
+            tails: 0,
+            popped: 0,
+            modifications: vec![],
+        }
+    }
+
+    fn rest(&self) -> Option<usize> {
+        self.slots.iter().position(|slot| *slot == Slot::Rest)
+    }
+
+    /// Apply a modification, or return false if it could point to different items depending on
+    /// how long the original sequence is.
+    fn apply(
+        &mut self,
+        modification: &SequenceModificationDeltaOwned<usize>,
+    ) -> Result<bool, PatchError> {
+        let rest = self.rest();
+
+        if let Some((pop_front, push_back)) = front_pops(modification) {
+            let known = match rest {
+                None => true,
+                Some(rest) => {
+                    rest >= pop_front
+                        || (rest + 1 == self.slots.len() && pop_front - rest <= MAX_SPLIT_ITEMS)
+                }
+            };
+
+            if self.popped > 0 || !known {
+                if rest != Some(0) {
+                    return Ok(false);
+                }
+
+                self.popped = match self.popped.checked_add(pop_front) {
+                    Some(popped) => popped,
+                    None => return Ok(false),
+                };
+                self.slots.extend(push_back.iter().copied().map(Slot::New));
+                self.modifications.push(modification.clone());
+
+                return Ok(true);
+            }
+        } else if self.popped > 0 && !is_end_modification(modification) {
+            return Ok(false);
+        }
+
+        if let Some(rest) = rest {
+            let (front, back) = reach(modification);
+
+            if rest < front {
+                if rest + 1 != self.slots.len() || front - rest > MAX_SPLIT_ITEMS {
+                    return Ok(false);
+                }
+
+                let heads = self.heads..self.heads + front - rest;
+                self.heads = heads.end;
+                self.slots.splice(rest..rest, heads.map(Slot::Head));
+            }
+
+            let rest = self.rest().unwrap();
+            let after_rest = self.slots.len() - rest - 1;
+            if after_rest < back {
+                if rest != 0 {
+                    return Ok(false);
+                }
+
+                for _ in after_rest..back {
+                    self.slots.insert(rest + 1, Slot::Tail(self.tails));
+                    self.tails += 1;
+                }
+            }
+        }
+
+        apply_patch(
+            &mut self.slots,
+            vec![map_items(modification.clone(), Slot::New)],
+        )?;
+        self.modifications.push(modification.clone());
+
+        Ok(true)
+    }
+
+    fn into_modifications(self) -> Vec<SequenceModificationDeltaOwned<usize>> {
+        match self.condense() {
+            Some(condensed) => condensed,
+            None => self.modifications,
+        }
+    }
+
+    /// Describe the ranges of the original sequence that were replaced, or `None` if that would
+    /// require knowing how long the original sequence is.
+    fn condense(&self) -> Option<Vec<SequenceModificationDeltaOwned<usize>>> {
+        let rest = match self.rest() {
+            Some(rest) => rest,
+            None => return self.condense_without_rest(),
+        };
+
+        let hunks = head_hunks(&self.slots[..rest], self.heads);
+        let (deleted_tails, appended) = tail_changes(&self.slots[rest + 1..], self.tails)?;
+
+        // The front of the sequence only had items removed from it.
+        let popped = match hunks.as_slice() {
+            [] => Some(0),
+            [Hunk { start: 0, end, new }] if new.is_empty() => Some(*end),
+            _ => None,
+        };
+
+        let mut modifications = vec![];
+
+        if self.popped > 0 {
+            // Items get pushed before they get popped, since we don't know if the items that get
+            // popped are ones that were pushed.
+            push_tail_modifications(&mut modifications, deleted_tails, appended);
+
+            match self.popped + popped? {
+                1 => modifications.push(SequenceModificationDeltaOwned::DeleteFirst),
+                pop_front => {
+                    modifications.push(SequenceModificationDeltaOwned::DeleteAllBeforeIncluding {
+                        end_index: pop_front - 1,
+                    })
+                }
+            };
+
+            return Some(modifications);
+        }
+
+        match popped {
+            Some(pop_front) if pop_front > 0 && deleted_tails == 0 && !appended.is_empty() => {
+                if pop_front == 1 && appended.len() == 1 {
+                    modifications.push(SequenceModificationDeltaOwned::PopFrontPushBackOne {
+                        item: appended[0],
+                    });
+                } else {
+                    modifications.push(SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                        pop_front,
+                        push_back: appended,
+                    });
+                }
+            }
+            _ => {
+                modifications.extend(hunks.into_iter().rev().map(hunk_to_smallest_modification));
+                push_tail_modifications(&mut modifications, deleted_tails, appended);
+            }
+        };
+
+        Some(modifications)
+    }
+
+    /// The part of the original sequence that no modification reached was removed, so everything
+    /// from the last `Head` that is still around until the end of the original sequence is
+    /// replaced.
+    fn condense_without_rest(&self) -> Option<Vec<SequenceModificationDeltaOwned<usize>>> {
+        if self.popped > 0 {
+            return None;
+        }
+
+        let mut hunks = vec![];
+        let mut next_base = 0;
+        let mut new = vec![];
+
+        for slot in self.slots.iter() {
+            match *slot {
+                Slot::Head(idx) => {
+                    if idx > next_base || !new.is_empty() {
+                        hunks.push(Hunk {
+                            start: next_base,
+                            end: idx,
+                            new: std::mem::take(&mut new),
+                        });
+                    }
+
+                    next_base = idx + 1;
+                }
+                Slot::New(item) => new.push(item),
+                // We can't delete the items in between a `Head` and a `Tail` without knowing how
+                // many there are.
+                Slot::Tail(_) | Slot::Rest => return None,
+            }
+        }
+
+        let truncate = match (next_base, new.is_empty()) {
+            (0, true) => SequenceModificationDeltaOwned::DeleteAll,
+            (0, false) => SequenceModificationDeltaOwned::ReplaceAll { new },
+            (start_index, true) => {
+                SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index }
+            }
+            (after, false) => {
+                SequenceModificationDeltaOwned::ReplaceAllAfterIncluding { after, new }
+            }
+        };
+
+        Some(
+            std::iter::once(truncate)
+                .chain(hunks.into_iter().rev().map(hunk_to_smallest_modification))
+                .collect(),
+        )
+    }
+}
+
+/// The ranges of the original sequence before the `Rest` that were replaced, ordered from the start
+/// of the sequence to the end.
+fn head_hunks(slots: &[Slot], heads: usize) -> Vec<Hunk<usize>> {
+    let mut hunks = vec![];
+    let mut next_base = 0;
+    let mut new = vec![];
+
+    for slot in slots.iter().chain(std::iter::once(&Slot::Head(heads))) {
+        match *slot {
+            Slot::Head(idx) => {
+                if idx > next_base || !new.is_empty() {
+                    hunks.push(Hunk {
+                        start: next_base,
+                        end: idx,
+                        new: std::mem::take(&mut new),
+                    });
+                }
+
+                next_base = idx + 1;
+            }
+            Slot::New(item) => new.push(item),
+            Slot::Rest | Slot::Tail(_) => {
+                unreachable!("Only called with the slots before the Rest")
+            }
+        }
+    }
+
+    hunks
+}
+
+/// How many items were removed from the end of the original sequence and the items that were
+/// appended after that, or `None` if the end of the sequence was changed in some other way.
+fn tail_changes(slots: &[Slot], tails: usize) -> Option<(usize, Vec<usize>)> {
+    let kept = slots
+        .iter()
+        .enumerate()
+        .take_while(|(idx, slot)| matches!(slot, Slot::Tail(tail) if tail + idx + 1 == tails))
+        .count();
+
+    let appended = slots[kept..]
+        .iter()
+        .map(|slot| match slot {
+            Slot::New(item) => Some(*item),
+            _ => None,
+        })
+        .collect::<Option<Vec<usize>>>()?;
+
+    Some((tails - kept, appended))
+}
+
+fn push_tail_modifications(
+    modifications: &mut Vec<SequenceModificationDeltaOwned<usize>>,
+    deleted: usize,
+    appended: Vec<usize>,
+) {
+    if deleted > 0 && appended.len() == 1 {
+        for _ in 1..deleted {
+            modifications.push(SequenceModificationDeltaOwned::DeleteLast);
+        }
+        modifications.push(SequenceModificationDeltaOwned::ReplaceLast { item: appended[0] });
+
+        return;
+    }
+
+    for _ in 0..deleted {
+        modifications.push(SequenceModificationDeltaOwned::DeleteLast);
+    }
+
+    match appended.len() {
+        0 => {}
+        1 => modifications.push(SequenceModificationDeltaOwned::AppendOne { item: appended[0] }),
+        _ => modifications.push(SequenceModificationDeltaOwned::AppendMany { items: appended }),
+    };
+}
+
+/// Use the smallest modification that replaces a range of the original sequence.
+fn hunk_to_smallest_modification(hunk: Hunk<usize>) -> SequenceModificationDeltaOwned<usize> {
+    let Hunk {
+        start,
+        end,
+        mut new,
+    } = hunk;
+    let replaced = end - start;
+
+    match (start, replaced, new.len()) {
+        (0, 0, 1) => SequenceModificationDeltaOwned::PrependOne { item: new[0] },
+        (0, 0, _) => SequenceModificationDeltaOwned::PrependMany { items: new },
+        (index, 0, 1) => SequenceModificationDeltaOwned::InsertOne {
+            index,
+            value: new[0],
+        },
+        (start_idx, 0, _) => SequenceModificationDeltaOwned::InsertMany {
+            start_idx,
+            items: new,
+        },
+        (0, 1, 0) => SequenceModificationDeltaOwned::DeleteFirst,
+        (index, 1, 0) => SequenceModificationDeltaOwned::DeleteOne { index },
+        (0, _, 0) => {
+            SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index: end - 1 }
+        }
+        (start_index, items_to_delete, 0) => SequenceModificationDeltaOwned::DeleteMany {
+            start_index,
+            items_to_delete,
+        },
+        (0, 1, 1) => SequenceModificationDeltaOwned::ReplaceFirst {
+            item: new.pop().unwrap(),
+        },
+        (index, 1, 1) => SequenceModificationDeltaOwned::ReplaceOne {
+            index,
+            new: new.pop().unwrap(),
+        },
+        (index, replaced, added) if replaced == added => {
+            SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved { index, new }
+        }
+        (0, _, _) => SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding {
+            before: end - 1,
+            new,
+        },
+        (start_idx, items_to_replace, _) => SequenceModificationDeltaOwned::ReplaceMany {
+            start_idx,
+            items_to_replace,
+            new,
+        },
+    }
+}
+
+/// The number of items that a modification only removes from the front of the sequence, along
+/// with the items that it then appends.
+fn front_pops(modification: &SequenceModificationDeltaOwned<usize>) -> Option<(usize, &[usize])> {
+    match modification {
+        SequenceModificationDeltaOwned::DeleteFirst => Some((1, &[])),
+        SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index } => {
+            Some((end_index.checked_add(1)?, &[]))
+        }
+        SequenceModificationDeltaOwned::DeleteMany {
+            start_index: 0,
+            items_to_delete,
+        } => Some((*items_to_delete, &[])),
+        SequenceModificationDeltaOwned::PopFrontPushBackOne { item } => {
+            Some((1, std::slice::from_ref(item)))
+        }
+        SequenceModificationDeltaOwned::PopFrontPushBackMany {
+            pop_front,
+            push_back,
+        } => Some((*pop_front, push_back)),
+        _ => None,
+    }
+}
+
+/// Whether a modification only touches the end of the sequence.
+fn is_end_modification(modification: &SequenceModificationDeltaOwned<usize>) -> bool {
+    matches!(
+        modification,
+        SequenceModificationDeltaOwned::AppendOne { .. }
+            | SequenceModificationDeltaOwned::AppendMany { .. }
+            | SequenceModificationDeltaOwned::DeleteLast
+            | SequenceModificationDeltaOwned::ReplaceLast { .. }
+    )
+}
+
+/// How many items from the start and from the end of the sequence a modification needs to know
+/// about.
+fn reach(modification: &SequenceModificationDeltaOwned<usize>) -> (usize, usize) {
+    match modification {
+        SequenceModificationDeltaOwned::InsertOne { index, .. } => (*index, 0),
+        SequenceModificationDeltaOwned::DeleteOne { index }
+        | SequenceModificationDeltaOwned::ReplaceOne { index, .. } => (index.saturating_add(1), 0),
+        SequenceModificationDeltaOwned::PrependOne { .. }
+        | SequenceModificationDeltaOwned::PrependMany { .. }
+        | SequenceModificationDeltaOwned::AppendOne { .. }
+        | SequenceModificationDeltaOwned::AppendMany { .. }
+        | SequenceModificationDeltaOwned::ReplaceAll { .. }
+        | SequenceModificationDeltaOwned::DeleteAll => (0, 0),
+        SequenceModificationDeltaOwned::DeleteFirst
+        | SequenceModificationDeltaOwned::ReplaceFirst { .. }
+        | SequenceModificationDeltaOwned::PopFrontPushBackOne { .. } => (1, 0),
+        SequenceModificationDeltaOwned::DeleteLast
+        | SequenceModificationDeltaOwned::ReplaceLast { .. } => (0, 1),
+        SequenceModificationDeltaOwned::InsertMany { start_idx, .. } => (*start_idx, 0),
+        SequenceModificationDeltaOwned::DeleteMany {
+            start_index,
+            items_to_delete,
+        } => (start_index.saturating_add(*items_to_delete), 0),
+        SequenceModificationDeltaOwned::ReplaceMany {
+            start_idx,
+            items_to_replace,
+            ..
+        } => (start_idx.saturating_add(*items_to_replace), 0),
+        SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved { index, new } => {
+            (index.saturating_add(new.len()), 0)
+        }
+        SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index } => {
+            (end_index.saturating_add(1), 0)
+        }
+        SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding { before, .. } => {
+            (before.saturating_add(1), 0)
+        }
+        SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index } => {
+            (*start_index, 0)
+        }
+        SequenceModificationDeltaOwned::ReplaceAllAfterIncluding { after, .. } => (*after, 0),
+        SequenceModificationDeltaOwned::PopFrontPushBackMany { pop_front, .. } => (*pop_front, 0),
+    }
+}
@@ -0,0 +1,137 @@
+use crate::sequence::sequence_merge_patches::map_items;
+use crate::sequence::{SequenceModificationDelta, SequenceModificationDeltaOwned};
+use crate::ToOwnedDelta;
+
+impl<'a, T: Clone> ToOwnedDelta<Vec<SequenceModificationDeltaOwned<T>>>
+    for Vec<SequenceModificationDelta<'a, T>>
+where
+    &'a T: serde::Serialize,
+{
+    fn to_owned_delta(self) -> Vec<SequenceModificationDeltaOwned<T>> {
+        self.into_iter().map(ToOwnedDelta::to_owned_delta).collect()
+    }
+}
+
+// The delta of a queue, which holds references to the end queue's items.
+//
+// Tested in the sequence_queue module.
+impl<T: Clone> ToOwnedDelta<Vec<SequenceModificationDeltaOwned<T>>>
+    for Vec<SequenceModificationDeltaOwned<&T>>
+{
+    fn to_owned_delta(self) -> Vec<SequenceModificationDeltaOwned<T>> {
+        self.into_iter()
+            .map(|modification| map_items(modification, T::clone))
+            .collect()
+    }
+}
+
+// Tested in parent module.
+impl<'a, T: Clone> ToOwnedDelta<SequenceModificationDeltaOwned<T>>
+    for SequenceModificationDelta<'a, T>
+where
+    &'a T: serde::Serialize,
+{
+    fn to_owned_delta(self) -> SequenceModificationDeltaOwned<T> {
+        match self {
+            SequenceModificationDelta::InsertOne { index, value } => {
+                SequenceModificationDeltaOwned::InsertOne {
+                    index,
+                    value: value.clone(),
+                }
+            }
+            SequenceModificationDelta::PrependOne { item } => {
+                SequenceModificationDeltaOwned::PrependOne { item: item.clone() }
+            }
+            SequenceModificationDelta::AppendOne { item } => {
+                SequenceModificationDeltaOwned::AppendOne { item: item.clone() }
+            }
+            SequenceModificationDelta::DeleteOne { index } => {
+                SequenceModificationDeltaOwned::DeleteOne { index }
+            }
+            SequenceModificationDelta::ReplaceOne { index, new } => {
+                SequenceModificationDeltaOwned::ReplaceOne {
+                    index,
+                    new: new.clone(),
+                }
+            }
+            SequenceModificationDelta::DeleteFirst => SequenceModificationDeltaOwned::DeleteFirst,
+            SequenceModificationDelta::DeleteLast => SequenceModificationDeltaOwned::DeleteLast,
+            SequenceModificationDelta::ReplaceFirst { item } => {
+                SequenceModificationDeltaOwned::ReplaceFirst { item: item.clone() }
+            }
+            SequenceModificationDelta::ReplaceLast { item } => {
+                SequenceModificationDeltaOwned::ReplaceLast { item: item.clone() }
+            }
+            SequenceModificationDelta::PrependMany { items } => {
+                SequenceModificationDeltaOwned::PrependMany {
+                    items: items.to_vec(),
+                }
+            }
+            SequenceModificationDelta::InsertMany { start_idx, items } => {
+                SequenceModificationDeltaOwned::InsertMany {
+                    start_idx,
+                    items: items.to_vec(),
+                }
+            }
+            SequenceModificationDelta::DeleteMany {
+                start_index,
+                items_to_delete,
+            } => SequenceModificationDeltaOwned::DeleteMany {
+                start_index,
+                items_to_delete,
+            },
+            SequenceModificationDelta::AppendMany { items } => {
+                SequenceModificationDeltaOwned::AppendMany {
+                    items: items.to_vec(),
+                }
+            }
+            SequenceModificationDelta::ReplaceMany {
+                start_idx,
+                items_to_replace,
+                new,
+            } => SequenceModificationDeltaOwned::ReplaceMany {
+                start_idx,
+                items_to_replace,
+                new: new.to_vec(),
+            },
+            SequenceModificationDelta::ReplaceManySameAmountAddedAndRemoved { index, new } => {
+                SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved {
+                    index,
+                    new: new.to_vec(),
+                }
+            }
+            SequenceModificationDelta::ReplaceAll { new } => {
+                SequenceModificationDeltaOwned::ReplaceAll { new: new.to_vec() }
+            }
+            SequenceModificationDelta::DeleteAll => SequenceModificationDeltaOwned::DeleteAll,
+            SequenceModificationDelta::DeleteAllBeforeIncluding { end_index } => {
+                SequenceModificationDeltaOwned::DeleteAllBeforeIncluding { end_index }
+            }
+            SequenceModificationDelta::DeleteAllAfterIncluding { start_index } => {
+                SequenceModificationDeltaOwned::DeleteAllAfterIncluding { start_index }
+            }
+            SequenceModificationDelta::ReplaceAllBeforeIncluding { before, new } => {
+                SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding {
+                    before,
+                    new: new.to_vec(),
+                }
+            }
+            SequenceModificationDelta::ReplaceAllAfterIncluding { after, new } => {
+                SequenceModificationDeltaOwned::ReplaceAllAfterIncluding {
+                    after,
+                    new: new.to_vec(),
+                }
+            }
+            SequenceModificationDelta::PopFrontPushBackOne { item } => {
+                SequenceModificationDeltaOwned::PopFrontPushBackOne { item: item.clone() }
+            }
+            SequenceModificationDelta::PopFrontPushBackMany {
+                pop_front,
+                push_back,
+            } => SequenceModificationDeltaOwned::PopFrontPushBackMany {
+                pop_front,
+                push_back: push_back.to_vec(),
+            },
+        }
+    }
+}
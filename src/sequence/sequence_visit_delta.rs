@@ -0,0 +1,179 @@
+use crate::sequence::sequence_merge_patches::hunks;
+use crate::sequence::{SequenceModificationDelta, SequenceModificationDeltaOwned};
+use crate::{DeltaChange, DeltaVisitor, PathSegment};
+
+// Tested in parent module.
+//
+// Modifications are applied one after another, so an index in a later modification does not
+// always point at the same item as in the start sequence. We turn the delta into the ranges of the
+// start sequence that it replaced so that each change can be described with a single index.
+// Replaced and removed items use their index in the start sequence and inserted items use their
+// index in the end sequence.
+pub(crate) fn visit_sequence_delta<'a, T>(
+    start_len: usize,
+    delta: &[SequenceModificationDelta<'a, T>],
+    visitor: &mut dyn DeltaVisitor,
+) where
+    &'a T: serde::Serialize,
+{
+    visit_sequence_modifications(
+        start_len,
+        delta.iter().map(borrowed_items).collect(),
+        visitor,
+    )
+}
+
+/// Visit the changes of modifications that are already in their owned form, such as the deltas of
+/// queues which hold references to the end queue's items.
+pub(crate) fn visit_sequence_modifications<T>(
+    start_len: usize,
+    modifications: Vec<SequenceModificationDeltaOwned<T>>,
+    visitor: &mut dyn DeltaVisitor,
+) {
+    let hunks = match hunks(start_len, modifications) {
+        Ok(hunks) => hunks,
+        Err(_) => return,
+    };
+
+    let mut inserted_before = 0;
+    let mut removed_before = 0;
+
+    let mut visit_index = |index: usize, change: DeltaChange| {
+        visitor.enter(PathSegment::Index(index));
+        visitor.visit_change(change);
+        visitor.exit();
+    };
+
+    for hunk in hunks {
+        let replaced = (hunk.end - hunk.start).min(hunk.new.len());
+
+        for index in hunk.start..hunk.start + replaced {
+            visit_index(index, DeltaChange::Replaced);
+        }
+        for index in hunk.start + replaced..hunk.end {
+            visit_index(index, DeltaChange::Removed);
+        }
+        for new_idx in replaced..hunk.new.len() {
+            visit_index(
+                hunk.start + new_idx + inserted_before - removed_before,
+                DeltaChange::Inserted,
+            );
+        }
+
+        inserted_before += hunk.new.len();
+        removed_before += hunk.end - hunk.start;
+    }
+}
+
+/// The same modification with its items borrowed, so that it can be split into hunks without
+/// cloning every item.
+pub(super) fn borrowed_items<'a, T>(
+    modification: &SequenceModificationDelta<'a, T>,
+) -> SequenceModificationDeltaOwned<&'a T>
+where
+    &'a T: serde::Serialize,
+{
+    let many = |items: &'a [T]| items.iter().collect();
+
+    match modification {
+        SequenceModificationDelta::InsertOne { index, value } => {
+            SequenceModificationDeltaOwned::InsertOne {
+                index: *index,
+                value: *value,
+            }
+        }
+        SequenceModificationDelta::PrependOne { item } => {
+            SequenceModificationDeltaOwned::PrependOne { item: *item }
+        }
+        SequenceModificationDelta::AppendOne { item } => {
+            SequenceModificationDeltaOwned::AppendOne { item: *item }
+        }
+        SequenceModificationDelta::DeleteOne { index } => {
+            SequenceModificationDeltaOwned::DeleteOne { index: *index }
+        }
+        SequenceModificationDelta::ReplaceOne { index, new } => {
+            SequenceModificationDeltaOwned::ReplaceOne {
+                index: *index,
+                new: *new,
+            }
+        }
+        SequenceModificationDelta::DeleteFirst => SequenceModificationDeltaOwned::DeleteFirst,
+        SequenceModificationDelta::DeleteLast => SequenceModificationDeltaOwned::DeleteLast,
+        SequenceModificationDelta::ReplaceFirst { item } => {
+            SequenceModificationDeltaOwned::ReplaceFirst { item: *item }
+        }
+        SequenceModificationDelta::ReplaceLast { item } => {
+            SequenceModificationDeltaOwned::ReplaceLast { item: *item }
+        }
+        SequenceModificationDelta::PrependMany { items } => {
+            SequenceModificationDeltaOwned::PrependMany { items: many(items) }
+        }
+        SequenceModificationDelta::InsertMany { start_idx, items } => {
+            SequenceModificationDeltaOwned::InsertMany {
+                start_idx: *start_idx,
+                items: many(items),
+            }
+        }
+        SequenceModificationDelta::DeleteMany {
+            start_index,
+            items_to_delete,
+        } => SequenceModificationDeltaOwned::DeleteMany {
+            start_index: *start_index,
+            items_to_delete: *items_to_delete,
+        },
+        SequenceModificationDelta::AppendMany { items } => {
+            SequenceModificationDeltaOwned::AppendMany { items: many(items) }
+        }
+        SequenceModificationDelta::ReplaceMany {
+            start_idx,
+            items_to_replace,
+            new,
+        } => SequenceModificationDeltaOwned::ReplaceMany {
+            start_idx: *start_idx,
+            items_to_replace: *items_to_replace,
+            new: many(new),
+        },
+        SequenceModificationDelta::ReplaceManySameAmountAddedAndRemoved { index, new } => {
+            SequenceModificationDeltaOwned::ReplaceManySameAmountAddedAndRemoved {
+                index: *index,
+                new: many(new),
+            }
+        }
+        SequenceModificationDelta::ReplaceAll { new } => {
+            SequenceModificationDeltaOwned::ReplaceAll { new: many(new) }
+        }
+        SequenceModificationDelta::DeleteAll => SequenceModificationDeltaOwned::DeleteAll,
+        SequenceModificationDelta::DeleteAllBeforeIncluding { end_index } => {
+            SequenceModificationDeltaOwned::DeleteAllBeforeIncluding {
+                end_index: *end_index,
+            }
+        }
+        SequenceModificationDelta::DeleteAllAfterIncluding { start_index } => {
+            SequenceModificationDeltaOwned::DeleteAllAfterIncluding {
+                start_index: *start_index,
+            }
+        }
+        SequenceModificationDelta::ReplaceAllBeforeIncluding { before, new } => {
+            SequenceModificationDeltaOwned::ReplaceAllBeforeIncluding {
+                before: *before,
+                new: many(new),
+            }
+        }
+        SequenceModificationDelta::ReplaceAllAfterIncluding { after, new } => {
+            SequenceModificationDeltaOwned::ReplaceAllAfterIncluding {
+                after: *after,
+                new: many(new),
+            }
+        }
+        SequenceModificationDelta::PopFrontPushBackOne { item } => {
+            SequenceModificationDeltaOwned::PopFrontPushBackOne { item: *item }
+        }
+        SequenceModificationDelta::PopFrontPushBackMany {
+            pop_front,
+            push_back,
+        } => SequenceModificationDeltaOwned::PopFrontPushBackMany {
+            pop_front: *pop_front,
+            push_back: many(push_back),
+        },
+    }
+}
@@ -1,12 +1,57 @@
+use crate::{DeltaChange, DeltaVisitor, ToOwnedDelta};
 use serde::Serialize;
 use std::fmt::{Debug, Formatter};
 
 #[macro_use]
 mod set_impl_macro;
+#[cfg(feature = "json-patch")]
+mod set_json_patch;
+mod set_merge_patches;
+mod set_squash_patches;
 
 set_impl!(std::collections::HashSet<K>, hash_map_impl,);
 set_impl!(std::collections::BTreeSet<K>, btree_map_impl, + Ord);
 
+/// Describe the keys that a delta adds to or removes from a set.
+fn visit_set_delta<'b, K: 'b + Debug>(
+    start_keys: impl Iterator<Item = &'b K>,
+    delta: &SetDelta<K>,
+    visitor: &mut dyn DeltaVisitor,
+) {
+    let mut visit_key = |key: &K, change: DeltaChange| {
+        visitor.enter_key(key);
+        visitor.visit_change(change);
+        visitor.exit();
+    };
+
+    match delta {
+        SetDelta::NoChange => {}
+        SetDelta::RemoveAll => {
+            for key in start_keys {
+                visit_key(key, DeltaChange::Removed);
+            }
+        }
+        SetDelta::AddOneField(key) => visit_key(key, DeltaChange::Inserted),
+        SetDelta::RemoveOneField(key) => visit_key(key, DeltaChange::Removed),
+        SetDelta::ModifyMany { added, removed } => {
+            for key in removed {
+                visit_key(key, DeltaChange::Removed);
+            }
+            for key in added {
+                visit_key(key, DeltaChange::Inserted);
+            }
+        }
+        SetDelta::ReplaceAll(keys) => {
+            for key in start_keys {
+                visit_key(key, DeltaChange::Removed);
+            }
+            for key in keys {
+                visit_key(key, DeltaChange::Inserted);
+            }
+        }
+    }
+}
+
 #[derive(Serialize)]
 /// The delta between two sets.
 pub enum SetDelta<'s, 'e, K> {
@@ -23,6 +68,11 @@ pub enum SetDelta<'s, 'e, K> {
         added: Vec<&'e K>,
         removed: Vec<&'s K>,
     },
+    /// Remove all entries and then add new ones.
+    ///
+    /// Not created when diffing. Used when squashing a patch that removes all entries with the
+    /// patches that come after it.
+    ReplaceAll(Vec<&'e K>),
 }
 
 #[derive(Deserialize)]
@@ -39,6 +89,29 @@ pub enum SetDeltaOwned<K> {
     RemoveOneField(K),
     /// Modify multiple entries
     ModifyMany { added: Vec<K>, removed: Vec<K> },
+    /// Remove all entries and then add new ones.
+    ///
+    /// Not created when diffing. Used when squashing a patch that removes all entries with the
+    /// patches that come after it.
+    ReplaceAll(Vec<K>),
+}
+
+impl<'s, 'e, K: Clone> ToOwnedDelta<SetDeltaOwned<K>> for SetDelta<'s, 'e, K> {
+    fn to_owned_delta(self) -> SetDeltaOwned<K> {
+        match self {
+            SetDelta::NoChange => SetDeltaOwned::NoChange,
+            SetDelta::RemoveAll => SetDeltaOwned::RemoveAll,
+            SetDelta::AddOneField(k) => SetDeltaOwned::AddOneField(k.clone()),
+            SetDelta::RemoveOneField(k) => SetDeltaOwned::RemoveOneField(k.clone()),
+            SetDelta::ModifyMany { added, removed } => SetDeltaOwned::ModifyMany {
+                added: added.into_iter().cloned().collect(),
+                removed: removed.into_iter().cloned().collect(),
+            },
+            SetDelta::ReplaceAll(entries) => {
+                SetDeltaOwned::ReplaceAll(entries.into_iter().cloned().collect())
+            }
+        }
+    }
 }
 
 // Used by DipaImplTester
@@ -66,6 +139,9 @@ where
                     .field("removed", removed)
                     .finish()?;
             }
+            SetDelta::ReplaceAll(entries) => {
+                f.debug_tuple("ReplaceAll").field(entries).finish()?;
+            }
         };
 
         Ok(())
@@ -103,6 +179,9 @@ where
 
             (Self::RemoveAll, Self::RemoveAll) => true,
             (Self::RemoveAll, _) => false,
+
+            (Self::ReplaceAll(left), Self::ReplaceAll(right)) => left == right,
+            (Self::ReplaceAll(_), _) => false,
         }
     }
 }
@@ -110,7 +189,13 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
     use crate::DipaImplTester;
+    use crate::{MergeablePatches, Patchable, ReversiblePatchable, SquashablePatches};
     use std::collections::{BTreeSet, HashSet};
 
     /// Verify that we properly handle an unchanged empty HashMap
@@ -124,7 +209,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we properly handle an unchanged HashMap that has fields.
@@ -138,7 +223,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can remove a field from the original HashMap
@@ -152,7 +237,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can add a field to the original HashMap
@@ -166,7 +251,7 @@ mod tests {
             expected_serialized_patch_size: 2,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can remove a field to the original HashMap
@@ -180,7 +265,7 @@ mod tests {
             expected_serialized_patch_size: 2,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can add multiple fields to the map.
@@ -197,7 +282,7 @@ mod tests {
             expected_serialized_patch_size: 5,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
     }
 
     /// Verify that we can remove multiple entries from the map.
@@ -214,6 +299,116 @@ mod tests {
             expected_serialized_patch_size: 5,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
+    }
+
+    /// Verify that we can squash consecutive set patches.
+    #[test]
+    fn squash_set_patches() {
+        let set = |keys: &[u32]| keys.iter().copied().collect::<BTreeSet<_>>();
+
+        test_squash(set(&[1]), set(&[1, 2]), set(&[1, 2, 3]));
+        test_squash(set(&[1]), set(&[1, 2]), set(&[1]));
+        test_squash(set(&[1, 2]), set(&[]), set(&[3]));
+        test_squash(set(&[1, 2]), set(&[]), set(&[]));
+    }
+
+    /// Verify that clearing a set and then adding keys squashes into a ReplaceAll, and that the
+    /// ReplaceAll can be undone.
+    #[test]
+    fn squash_remove_all_then_add() {
+        let mut set: HashSet<u32> = vec![1, 2].into_iter().collect();
+
+        let squashed =
+            HashSet::<u32>::squash_patches(SetDeltaOwned::RemoveAll, SetDeltaOwned::AddOneField(3))
+                .unwrap();
+        assert!(matches!(&squashed, SetDeltaOwned::ReplaceAll(keys) if keys == &[3]));
+
+        let inverse = set.apply_patch_returning_inverse(squashed).unwrap();
+        assert_eq!(set, vec![3].into_iter().collect());
+
+        set.apply_patch(inverse).unwrap();
+        assert_eq!(set, vec![1, 2].into_iter().collect());
+    }
+
+    /// Verify that we can merge set patches that add and remove different keys.
+    #[test]
+    fn merge_set_patches() {
+        let set = |keys: &[u32]| keys.iter().copied().collect::<BTreeSet<_>>();
+
+        test_merge(set(&[1, 2]), set(&[1, 2, 3]), set(&[2]), set(&[2, 3]));
+        test_merge(set(&[1, 2]), set(&[1]), set(&[1, 4]), set(&[1, 4]));
+        test_merge(set(&[1, 2]), set(&[]), set(&[1, 2]), set(&[]));
+    }
+
+    /// Verify that clearing a set conflicts with any other change to it.
+    #[test]
+    fn merge_remove_all_conflicts() {
+        let base: HashSet<u32> = vec![1, 2].into_iter().collect();
+
+        let conflicts = HashSet::<u32>::merge_patches(
+            &base,
+            SetDeltaOwned::RemoveAll,
+            SetDeltaOwned::AddOneField(3),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        assert_eq!(conflicts.conflicts().len(), 1);
+        assert_eq!(conflicts.conflicts()[0].path().count(), 0);
+    }
+
+    /// Verify that set changes are described using the keys that were added or removed.
+    #[test]
+    fn print_set_delta() {
+        let set = |keys: &[u32]| keys.iter().copied().collect::<BTreeSet<_>>();
+
+        test_print(set(&[1]), set(&[1, 2]), &["[2]: inserted"]);
+        test_print(
+            set(&[1, 2, 3]),
+            set(&[1, 4]),
+            &["[2]: removed", "[3]: removed", "[4]: inserted"],
+        );
+        test_print(set(&[1, 2]), set(&[]), &["[1]: removed", "[2]: removed"]);
+    }
+
+    /// Verify that applying a set's JSON Patch to the start array gives us an array with the keys
+    /// of the end set.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn set_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        let set = |keys: &[u32]| keys.iter().copied().collect::<BTreeSet<_>>();
+        test_json_patch(set(&[1]), set(&[1, 2]));
+        test_json_patch(set(&[1, 2, 3, 4]), set(&[0, 2, 4]));
+        test_json_patch(set(&[1, 2]), set(&[]));
+
+        let hash_set = |keys: &[u32]| keys.iter().copied().collect::<HashSet<_>>();
+        test_json_patch(hash_set(&[1, 2, 3, 4, 5]), hash_set(&[2, 4, 6, 8]));
+    }
+
+    /// Verify that we can convert set deltas into owned deltas without serializing them.
+    #[test]
+    fn set_to_owned_delta() {
+        let start: HashSet<u8> = vec![1, 2].into_iter().collect();
+
+        test_to_owned_delta(start.clone(), HashSet::new());
+        test_to_owned_delta(HashSet::new(), start.clone());
+        test_to_owned_delta(start.clone(), vec![1, 2, 3].into_iter().collect());
+        test_to_owned_delta(start, vec![1].into_iter().collect());
+
+        test_to_owned_delta(BTreeSet::new(), vec![1u8].into_iter().collect());
+    }
+
+    /// Verify that we can patch a set from a borrowed patch.
+    #[test]
+    fn set_borrowed_patch() {
+        let start: HashSet<u8> = vec![1, 2].into_iter().collect();
+
+        test_borrowed_patch(start.clone(), HashSet::new());
+        test_borrowed_patch(start, vec![1, 3, 4].into_iter().collect());
+
+        test_borrowed_patch(BTreeSet::new(), vec!["a".to_string()].into_iter().collect());
     }
 }
@@ -2,8 +2,16 @@
 macro_rules! set_impl {
     ($map_ty:ty, $module:ident, $($additional_key_bounds:tt)*) => {
         mod $module {
-            use super::{SetDelta, SetDeltaOwned};
-            use crate::{CreatedDelta, Diffable, Patchable};
+            use super::{visit_set_delta, SetDelta, SetDeltaOwned};
+            use super::set_merge_patches::merge_set_patches;
+            use super::set_squash_patches::squash_set_patches;
+            use $crate::{
+                BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts,
+                MergeablePatches, PatchError, Patchable, ReversiblePatchable, SquashablePatches,
+                VisitDelta,
+            };
+            use serde::Deserialize;
+            use std::fmt::Debug;
             use std::hash::Hash;
 
             type SetAssociatedDeltaOwned<'s, 'e, K> =
@@ -80,7 +88,10 @@ macro_rules! set_impl {
             where
                 K: 's + 'e + Eq + Hash $($additional_key_bounds)*,
             {
-                fn apply_patch(&mut self, patch: SetAssociatedDeltaOwned<'s, 'e, K>) {
+                fn apply_patch(
+                    &mut self,
+                    patch: SetAssociatedDeltaOwned<'s, 'e, K>,
+                ) -> Result<(), PatchError> {
                     match patch {
                         SetDeltaOwned::NoChange => {}
                         SetDeltaOwned::RemoveAll => self.clear(),
@@ -102,7 +113,147 @@ macro_rules! set_impl {
                                 self.remove(&remove);
                             }
                         }
+                        SetDeltaOwned::ReplaceAll(entries) => {
+                            self.clear();
+                            self.extend(entries);
+                        }
                     }
+
+                    Ok(())
+                }
+            }
+
+            // The delta only holds whole keys, so there is nothing to borrow that the keys
+            // themselves can not borrow.
+            impl<'de, K> BorrowedDelta<'de> for $map_ty
+            where
+                K: Eq + Hash + Deserialize<'de> $($additional_key_bounds)*,
+            {
+                type DeltaBorrowed = SetDeltaOwned<K>;
+
+                fn apply_borrowed_patch(&mut self, patch: SetDeltaOwned<K>) -> Result<(), PatchError> {
+                    self.apply_patch(patch)
+                }
+            }
+
+            impl<'s, 'e, K> ReversiblePatchable<SetAssociatedDeltaOwned<'s, 'e, K>> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + Clone $($additional_key_bounds)*,
+            {
+                fn apply_patch_returning_inverse(
+                    &mut self,
+                    patch: SetAssociatedDeltaOwned<'s, 'e, K>,
+                ) -> Result<SetAssociatedDeltaOwned<'s, 'e, K>, PatchError> {
+                    let inverse = match patch {
+                        SetDeltaOwned::NoChange => SetDeltaOwned::NoChange,
+                        SetDeltaOwned::RemoveAll => SetDeltaOwned::ModifyMany {
+                            added: std::mem::take(self).into_iter().collect(),
+                            removed: vec![],
+                        },
+                        SetDeltaOwned::AddOneField(k) => {
+                            if self.insert(k.clone()) {
+                                SetDeltaOwned::RemoveOneField(k)
+                            } else {
+                                SetDeltaOwned::NoChange
+                            }
+                        }
+                        SetDeltaOwned::RemoveOneField(k) => {
+                            if self.remove(&k) {
+                                SetDeltaOwned::AddOneField(k)
+                            } else {
+                                SetDeltaOwned::NoChange
+                            }
+                        }
+                        SetDeltaOwned::ModifyMany {
+                            added,
+                            removed,
+                        } => {
+                            let mut inverse_added = vec![];
+                            let mut inverse_removed = vec![];
+
+                            for add in added {
+                                if self.insert(add.clone()) {
+                                    inverse_removed.push(add);
+                                }
+                            }
+
+                            for remove in removed {
+                                if self.remove(&remove) {
+                                    inverse_added.push(remove);
+                                }
+                            }
+
+                            SetDeltaOwned::ModifyMany {
+                                added: inverse_added,
+                                removed: inverse_removed,
+                            }
+                        }
+                        SetDeltaOwned::ReplaceAll(entries) => {
+                            let previous = std::mem::replace(self, entries.into_iter().collect());
+                            SetDeltaOwned::ReplaceAll(previous.into_iter().collect())
+                        }
+                    };
+
+                    Ok(inverse)
+                }
+            }
+
+            impl<'s, 'e, K> SquashablePatches<SetAssociatedDeltaOwned<'s, 'e, K>> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash $($additional_key_bounds)*,
+            {
+                fn squash_patches(
+                    first: SetAssociatedDeltaOwned<'s, 'e, K>,
+                    second: SetAssociatedDeltaOwned<'s, 'e, K>,
+                ) -> Result<SetAssociatedDeltaOwned<'s, 'e, K>, PatchError> {
+                    Ok(squash_set_patches(first, second))
+                }
+            }
+
+            impl<'s, 'e, K> MergeablePatches<SetAssociatedDeltaOwned<'s, 'e, K>> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash $($additional_key_bounds)*,
+            {
+                fn merge_patches(
+                    _base: &Self,
+                    a: SetAssociatedDeltaOwned<'s, 'e, K>,
+                    b: SetAssociatedDeltaOwned<'s, 'e, K>,
+                ) -> Result<SetAssociatedDeltaOwned<'s, 'e, K>, MergeConflicts> {
+                    merge_set_patches(a, b)
+                }
+            }
+
+            impl<'s, 'e, K> $crate::SchemaFingerprint<'s, 'e> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + $crate::SchemaFingerprint<'s, 'e> $($additional_key_bounds)*,
+            {
+                const FINGERPRINT: u64 = $crate::FingerprintHasher::new()
+                    .write_str("Set")
+                    .write_u64(K::FINGERPRINT)
+                    .finish();
+            }
+
+            impl<'s, 'e, K> VisitDelta<'s, 'e> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + Debug $($additional_key_bounds)*,
+            {
+                fn visit_delta(&self, delta: &SetDelta<'s, 'e, K>, visitor: &mut dyn DeltaVisitor) {
+                    visit_set_delta(self.iter(), delta, visitor)
+                }
+            }
+
+            #[cfg(feature = "json-patch")]
+            impl<'s, 'e, K> $crate::ToJsonPatch<'s, 'e> for $map_ty
+            where
+                K: 's + 'e + Eq + Hash + serde::Serialize $($additional_key_bounds)*,
+            {
+                fn push_json_patch(
+                    &self,
+                    delta: &SetDelta<'s, 'e, K>,
+                    path: &$crate::JsonPointer,
+                    patch: &mut $crate::JsonPatch,
+                ) -> Result<(), $crate::JsonPatchError> {
+                    super::set_json_patch::push_set_json_patch(self.iter(), delta, path, patch)
                 }
             }
         }
@@ -0,0 +1,50 @@
+use crate::set::SetDelta;
+use crate::{JsonPatch, JsonPatchError, JsonPointer};
+use serde::Serialize;
+
+// Tested in parent module.
+//
+// `serde_json` writes sets as arrays in iteration order, so removed keys are addressed by their
+// index in the start set. We remove from the back of the array so that the earlier indices stay
+// valid, then append the added keys. The array can end up in a different order than the end set
+// would be written in, which does not matter once it is deserialized back into a set.
+pub(super) fn push_set_json_patch<'b, K>(
+    start_keys: impl Iterator<Item = &'b K>,
+    delta: &SetDelta<K>,
+    path: &JsonPointer,
+    patch: &mut JsonPatch,
+) -> Result<(), JsonPatchError>
+where
+    K: 'b + PartialEq + Serialize,
+{
+    let start_keys: Vec<&K> = start_keys.collect();
+
+    let (removed, added): (Vec<&K>, &[&K]) = match delta {
+        SetDelta::NoChange => (vec![], &[]),
+        SetDelta::RemoveAll => (start_keys.clone(), &[]),
+        SetDelta::AddOneField(key) => (vec![], std::slice::from_ref(key)),
+        SetDelta::RemoveOneField(key) => (vec![*key], &[]),
+        SetDelta::ModifyMany { added, removed } => (removed.clone(), added),
+        SetDelta::ReplaceAll(keys) => (start_keys.clone(), keys),
+    };
+
+    let mut removed_indices = removed
+        .into_iter()
+        .map(|key| {
+            start_keys
+                .iter()
+                .position(|start_key| *start_key == key)
+                .ok_or_else(|| JsonPatchError::not_created_from("set"))
+        })
+        .collect::<Result<Vec<usize>, JsonPatchError>>()?;
+    removed_indices.sort_unstable_by(|a, b| b.cmp(a));
+
+    for idx in removed_indices {
+        patch.remove(&path.child(idx));
+    }
+    for key in added {
+        patch.add(&path.array_end(), key)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::set::set_squash_patches::SquashedSetPatch;
+use crate::set::SetDeltaOwned;
+use crate::{MergeConflictReason, MergeConflicts};
+
+// Tested in parent module.
+//
+// A set's keys have no values to merge, so patches that touch the same key only merge when they
+// both add it or both remove it.
+pub(super) fn merge_set_patches<K: Eq>(
+    a: SetDeltaOwned<K>,
+    b: SetDeltaOwned<K>,
+) -> Result<SetDeltaOwned<K>, MergeConflicts> {
+    let a = split_set_patch(a);
+    let mut b = split_set_patch(b);
+
+    if a.cleared || b.cleared {
+        return match (is_no_change(&a), is_no_change(&b)) {
+            (_, true) => Ok(a.into_patch()),
+            (true, false) => Ok(b.into_patch()),
+            (false, false) => Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange)),
+        };
+    }
+
+    let mut merged = SquashedSetPatch {
+        cleared: false,
+        entries: Vec::with_capacity(a.entries.len() + b.entries.len()),
+    };
+
+    for (key, a_added) in a.entries {
+        if let Some(idx) = b.entries.iter().position(|(k, _)| *k == key) {
+            let (_, b_added) = b.entries.remove(idx);
+
+            if a_added != b_added {
+                return Err(MergeConflicts::new(MergeConflictReason::ConcurrentChange));
+            }
+        }
+
+        merged.entries.push((key, a_added));
+    }
+
+    merged.entries.extend(b.entries);
+
+    Ok(merged.into_patch())
+}
+
+fn split_set_patch<K: Eq>(patch: SetDeltaOwned<K>) -> SquashedSetPatch<K> {
+    let mut split = SquashedSetPatch {
+        cleared: false,
+        entries: vec![],
+    };
+    split.push_patch(patch);
+
+    split
+}
+
+fn is_no_change<K>(patch: &SquashedSetPatch<K>) -> bool {
+    !patch.cleared && patch.entries.is_empty()
+}
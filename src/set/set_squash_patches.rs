@@ -0,0 +1,100 @@
+use crate::set::SetDeltaOwned;
+
+/// The combined effect of a series of set patches.
+///
+/// Keys are looked up with a linear search since squashed patches tend to touch a small number of
+/// keys, and it lets us keep the order that keys were first modified in.
+pub(super) struct SquashedSetPatch<K> {
+    /// Whether the set gets cleared before the keys are added and removed.
+    pub(super) cleared: bool,
+    /// Each modified key and whether it gets added (`true`) or removed (`false`).
+    pub(super) entries: Vec<(K, bool)>,
+}
+
+// Tested in parent module.
+pub(super) fn squash_set_patches<K: Eq>(
+    first: SetDeltaOwned<K>,
+    second: SetDeltaOwned<K>,
+) -> SetDeltaOwned<K> {
+    let mut squashed = SquashedSetPatch {
+        cleared: false,
+        entries: vec![],
+    };
+
+    squashed.push_patch(first);
+    squashed.push_patch(second);
+
+    squashed.into_patch()
+}
+
+impl<K: Eq> SquashedSetPatch<K> {
+    pub(super) fn push_patch(&mut self, patch: SetDeltaOwned<K>) {
+        match patch {
+            SetDeltaOwned::NoChange => {}
+            SetDeltaOwned::RemoveAll => {
+                self.clear();
+            }
+            SetDeltaOwned::AddOneField(k) => {
+                self.push_entry(k, true);
+            }
+            SetDeltaOwned::RemoveOneField(k) => {
+                self.push_entry(k, false);
+            }
+            // Pushed in the same order that they get applied in.
+            SetDeltaOwned::ModifyMany { added, removed } => {
+                for k in added {
+                    self.push_entry(k, true);
+                }
+
+                for k in removed {
+                    self.push_entry(k, false);
+                }
+            }
+            SetDeltaOwned::ReplaceAll(entries) => {
+                self.clear();
+
+                for k in entries {
+                    self.push_entry(k, true);
+                }
+            }
+        };
+    }
+
+    fn clear(&mut self) {
+        self.cleared = true;
+        self.entries.clear();
+    }
+
+    /// The last modification to a key is the only one that matters.
+    fn push_entry(&mut self, key: K, added: bool) {
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push((key, added));
+    }
+
+    pub(super) fn into_patch(self) -> SetDeltaOwned<K> {
+        let mut added = vec![];
+        let mut removed = vec![];
+
+        for (k, is_added) in self.entries {
+            match is_added {
+                true => added.push(k),
+                false => removed.push(k),
+            };
+        }
+
+        // Removing keys from a set that was just cleared does nothing.
+        if self.cleared {
+            return match added.is_empty() {
+                true => SetDeltaOwned::RemoveAll,
+                false => SetDeltaOwned::ReplaceAll(added),
+            };
+        }
+
+        match (added.len(), removed.len()) {
+            (0, 0) => SetDeltaOwned::NoChange,
+            (1, 0) => SetDeltaOwned::AddOneField(added.remove(0)),
+            (0, 1) => SetDeltaOwned::RemoveOneField(removed.remove(0)),
+            _ => SetDeltaOwned::ModifyMany { added, removed },
+        }
+    }
+}
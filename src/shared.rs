@@ -0,0 +1,421 @@
+//! An `Rc<T>` or an `Arc<T>` is diffed and patched as its value.
+//!
+//! Patching clones the value if it is shared with another pointer, so that the other pointers keep
+//! seeing the unpatched value.
+
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SquashablePatches, ToOwnedDelta, VisitDelta,
+};
+#[cfg(feature = "json-patch")]
+use crate::{JsonPatch, JsonPatchError, JsonPointer, ToJsonPatch};
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The delta for an `Rc<T>` or an `Arc<T>`.
+///
+/// Cloning an `Rc` or `Arc` shares the value instead of copying it, so two states that were cloned
+/// from one another will often still point to the same value. Those values are known to be equal
+/// without diffing them, so the delta for an unchanged value is `None` no matter how large the
+/// value is, even for values such as a NaN float that do not equal themselves.
+///
+/// The value's own delta can only describe no change by diffing the value, which is what this
+/// type avoids, so the delta is one byte larger than the value's delta.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedDelta<D>(Option<D>);
+
+impl<D> SharedDelta<D> {
+    /// The delta for the shared value, or `None` if it did not change.
+    pub fn inner(&self) -> Option<&D> {
+        self.0.as_ref()
+    }
+}
+
+impl<D, O> ToOwnedDelta<SharedDelta<O>> for SharedDelta<D>
+where
+    D: ToOwnedDelta<O>,
+{
+    fn to_owned_delta(self) -> SharedDelta<O> {
+        SharedDelta(self.0.map(ToOwnedDelta::to_owned_delta))
+    }
+}
+
+macro_rules! shared_pointer_impl {
+    ($pointer:ident) => {
+        impl<'s, 'e, T> Diffable<'s, 'e, $pointer<T>> for $pointer<T>
+        where
+            T: ?Sized,
+            T: Diffable<'s, 'e, T>,
+        {
+            type Delta = SharedDelta<<T as Diffable<'s, 'e, T>>::Delta>;
+            type DeltaOwned = SharedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>;
+
+            fn create_delta_towards(
+                &'s self,
+                end_state: &'e $pointer<T>,
+            ) -> CreatedDelta<Self::Delta> {
+                self.create_delta_towards_version(end_state, u32::MAX)
+            }
+
+            fn create_delta_towards_version(
+                &'s self,
+                end_state: &'e $pointer<T>,
+                version: u32,
+            ) -> CreatedDelta<Self::Delta> {
+                if $pointer::ptr_eq(self, end_state) {
+                    return CreatedDelta {
+                        delta: SharedDelta(None),
+                        did_change: false,
+                    };
+                }
+
+                let CreatedDelta { delta, did_change } =
+                    (**self).create_delta_towards_version(&**end_state, version);
+
+                CreatedDelta {
+                    delta: SharedDelta(did_change.then(|| delta)),
+                    did_change,
+                }
+            }
+        }
+
+        // An empty patch does not call `make_mut`, so it never clones a shared value.
+        impl<T, D> Patchable<SharedDelta<D>> for $pointer<T>
+        where
+            T: Clone + Patchable<D>,
+        {
+            fn apply_patch(&mut self, patch: SharedDelta<D>) -> Result<(), PatchError> {
+                match patch.0 {
+                    Some(delta) => $pointer::make_mut(self).apply_patch(delta),
+                    None => Ok(()),
+                }
+            }
+        }
+
+        impl<T, D> ReversiblePatchable<SharedDelta<D>> for $pointer<T>
+        where
+            T: Clone + ReversiblePatchable<D>,
+        {
+            fn apply_patch_returning_inverse(
+                &mut self,
+                patch: SharedDelta<D>,
+            ) -> Result<SharedDelta<D>, PatchError> {
+                let inverse = match patch.0 {
+                    Some(delta) => {
+                        Some($pointer::make_mut(self).apply_patch_returning_inverse(delta)?)
+                    }
+                    None => None,
+                };
+
+                Ok(SharedDelta(inverse))
+            }
+        }
+
+        impl<T, D> SquashablePatches<SharedDelta<D>> for $pointer<T>
+        where
+            T: Clone + SquashablePatches<D>,
+        {
+            fn squash_patches(
+                first: SharedDelta<D>,
+                second: SharedDelta<D>,
+            ) -> Result<SharedDelta<D>, PatchError> {
+                let squashed = match (first.0, second.0) {
+                    (Some(first), Some(second)) => Some(T::squash_patches(first, second)?),
+                    (first, second) => second.or(first),
+                };
+
+                Ok(SharedDelta(squashed))
+            }
+        }
+
+        impl<T, D> MergeablePatches<SharedDelta<D>> for $pointer<T>
+        where
+            T: Clone + MergeablePatches<D>,
+        {
+            fn merge_patches(
+                base: &Self,
+                a: SharedDelta<D>,
+                b: SharedDelta<D>,
+            ) -> Result<SharedDelta<D>, MergeConflicts> {
+                let merged = match (a.0, b.0) {
+                    (Some(a), Some(b)) => Some(T::merge_patches(base, a, b)?),
+                    (a, b) => a.or(b),
+                };
+
+                Ok(SharedDelta(merged))
+            }
+        }
+
+        impl<'de, T> BorrowedDelta<'de> for $pointer<T>
+        where
+            T: Clone + BorrowedDelta<'de>,
+        {
+            type DeltaBorrowed = SharedDelta<<T as BorrowedDelta<'de>>::DeltaBorrowed>;
+
+            fn apply_borrowed_patch(
+                &mut self,
+                patch: Self::DeltaBorrowed,
+            ) -> Result<(), PatchError> {
+                match patch.0 {
+                    Some(delta) => $pointer::make_mut(self).apply_borrowed_patch(delta),
+                    None => Ok(()),
+                }
+            }
+        }
+
+        // The value's delta is wrapped in a `SharedDelta`, so the fingerprint differs from the
+        // value's own fingerprint and from a `Box`'s, which does not wrap it. An `Rc` and an
+        // `Arc` encode their deltas the same way, so they share a fingerprint.
+        impl<'s, 'e, T> crate::SchemaFingerprint<'s, 'e> for $pointer<T>
+        where
+            T: ?Sized,
+            T: crate::SchemaFingerprint<'s, 'e>,
+        {
+            const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+                .write_str("SharedDelta")
+                .write_u64(T::FINGERPRINT)
+                .finish();
+        }
+
+        impl<'s, 'e, T> VisitDelta<'s, 'e> for $pointer<T>
+        where
+            T: ?Sized,
+            T: VisitDelta<'s, 'e>,
+        {
+            fn visit_delta(
+                &self,
+                delta: &SharedDelta<<T as Diffable<'s, 'e, T>>::Delta>,
+                visitor: &mut dyn DeltaVisitor,
+            ) {
+                if let Some(delta) = &delta.0 {
+                    (**self).visit_delta(delta, visitor);
+                }
+            }
+        }
+
+        #[cfg(feature = "json-patch")]
+        impl<'s, 'e, T> ToJsonPatch<'s, 'e> for $pointer<T>
+        where
+            T: ?Sized,
+            T: ToJsonPatch<'s, 'e>,
+        {
+            fn push_json_patch(
+                &self,
+                delta: &SharedDelta<<T as Diffable<'s, 'e, T>>::Delta>,
+                path: &JsonPointer,
+                patch: &mut JsonPatch,
+            ) -> Result<(), JsonPatchError> {
+                match &delta.0 {
+                    Some(delta) => (**self).push_json_patch(delta, path, patch),
+                    None => Ok(()),
+                }
+            }
+        }
+    };
+}
+
+shared_pointer_impl!(Rc);
+shared_pointer_impl!(Arc);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta_visitor::test_print;
+    use crate::dipa_impl_tester::DipaImplTester;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::ToOwnedDelta;
+
+    /// Verify that we can diff/patch an Rc and an Arc.
+    #[test]
+    fn dipa_shared_impl() {
+        DipaImplTester {
+            label: Some("Rc no change"),
+            start: &mut Rc::new(vec![1u8, 2]),
+            end: &Rc::new(vec![1u8, 2]),
+            expected_delta: SharedDelta(None),
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Rc changed"),
+            start: &mut Rc::new(5u16),
+            end: &Rc::new(6u16),
+            expected_delta: SharedDelta(Some(Some(6))),
+            // 1 for the shared delta's option, 1 for the number's option, 1 for the number
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Arc changed"),
+            start: &mut Arc::new(5u16),
+            end: &Arc::new(6u16),
+            expected_delta: SharedDelta(Some(Some(6))),
+            // 1 for the shared delta's option, 1 for the number's option, 1 for the number
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that two pointers to the same value are unchanged without diffing the value.
+    #[test]
+    fn pointers_to_same_value_are_unchanged() {
+        /// A value whose diff always reports a change, so that we can tell if it was diffed.
+        struct AlwaysChanged;
+        impl<'s, 'e> Diffable<'s, 'e, AlwaysChanged> for AlwaysChanged {
+            type Delta = ();
+            type DeltaOwned = ();
+
+            fn create_delta_towards(&self, _end_state: &AlwaysChanged) -> CreatedDelta<()> {
+                CreatedDelta {
+                    delta: (),
+                    did_change: true,
+                }
+            }
+        }
+
+        let start = Arc::new(AlwaysChanged);
+        let end = start.clone();
+
+        let diff = start.create_delta_towards(&end);
+        assert!(!diff.did_change);
+        assert!(diff.delta.inner().is_none());
+
+        assert!(
+            Arc::new(AlwaysChanged)
+                .create_delta_towards(&end)
+                .did_change
+        );
+    }
+
+    /// Verify that patching a shared value leaves the other pointers to it untouched.
+    #[test]
+    fn patch_clones_shared_value() {
+        let mut start = Rc::new(1u32);
+        let other = start.clone();
+
+        start.apply_patch(SharedDelta(Some(Some(2)))).unwrap();
+
+        assert_eq!(*start, 2);
+        assert_eq!(*other, 1);
+    }
+
+    /// Verify that a NaN is unchanged when both states point to it, even though it does not
+    /// equal itself, and that only the fields of a value that changed are described.
+    #[test]
+    fn shared_nan_is_unchanged() {
+        let nan = Rc::new(f32::NAN);
+
+        assert!(!nan.create_delta_towards(&nan.clone()).did_change);
+        assert!(Rc::new(f32::NAN).create_delta_towards(&nan).did_change);
+
+        test_print((nan.clone(), 2u8), (nan, 3u8), &["1: 2 -> 3"]);
+    }
+
+    /// Verify that reversing or applying a borrowed patch to a shared value leaves the other
+    /// pointers to it untouched.
+    #[test]
+    fn reverse_and_borrowed_patch_clone_shared_value() {
+        let mut value = Arc::new("a".to_string());
+        let other = value.clone();
+
+        let inverse = value
+            .apply_patch_returning_inverse(SharedDelta(Some(
+                "a".create_delta_towards("ab").delta.to_owned_delta(),
+            )))
+            .unwrap();
+        assert_eq!(*value, "ab");
+        assert_eq!(*other, "a");
+
+        value.apply_patch(inverse).unwrap();
+        assert_eq!(*value, "a");
+        assert!(!Arc::ptr_eq(&value, &other));
+
+        let mut value = other.clone();
+        let bytes =
+            bincode::serialize(&SharedDelta(Some("a".create_delta_towards("abc").delta))).unwrap();
+        value
+            .apply_borrowed_patch(bincode::deserialize(&bytes).unwrap())
+            .unwrap();
+        assert_eq!(*value, "abc");
+        assert_eq!(*other, "a");
+    }
+
+    /// Verify that a patch between two pointers to the same value squashes and merges with a
+    /// patch that changes the value.
+    #[test]
+    fn unchanged_pointer_patches_squash_and_merge() {
+        let shared = Rc::new(vec![1u8, 2]);
+
+        test_squash(shared.clone(), shared.clone(), Rc::new(vec![1, 2, 3]));
+        test_squash(shared.clone(), Rc::new(vec![2]), Rc::new(vec![2]));
+        test_merge(
+            shared.clone(),
+            shared.clone(),
+            Rc::new(vec![0, 1, 2]),
+            Rc::new(vec![0, 1, 2]),
+        );
+
+        assert!(Rc::<u32>::merge_patches(
+            &Rc::new(1),
+            SharedDelta(Some(Some(2))),
+            SharedDelta(Some(Some(3)))
+        )
+        .is_err());
+    }
+
+    /// Verify that applying a patch that does not change the value keeps sharing it instead of
+    /// cloning it.
+    #[test]
+    fn empty_patch_does_not_clone_shared_value() {
+        let mut value = Arc::new(1u32);
+        let empty = SharedDelta::<Option<u32>>(None);
+        let other = value.clone();
+
+        value.apply_patch(empty.clone()).unwrap();
+        assert!(Arc::ptr_eq(&value, &other));
+
+        let inverse = value.apply_patch_returning_inverse(empty.clone()).unwrap();
+        assert_eq!(inverse, empty);
+        assert!(Arc::ptr_eq(&value, &other));
+
+        value.apply_borrowed_patch(empty).unwrap();
+        assert!(Arc::ptr_eq(&value, &other));
+    }
+
+    /// Verify that an `Rc` and an `Arc` share a fingerprint since they encode their deltas the same
+    /// way, and that it differs from the value's and a `Box`'s since those are not wrapped in a
+    /// `SharedDelta`.
+    #[test]
+    fn shared_fingerprint() {
+        use crate::SchemaFingerprint;
+
+        let rc = <Rc<u32> as SchemaFingerprint>::FINGERPRINT;
+
+        assert_eq!(rc, <Arc<u32> as SchemaFingerprint>::FINGERPRINT);
+        assert_ne!(rc, <u32 as SchemaFingerprint>::FINGERPRINT);
+        assert_ne!(rc, <Box<u32> as SchemaFingerprint>::FINGERPRINT);
+        assert_ne!(rc, <Rc<u16> as SchemaFingerprint>::FINGERPRINT);
+    }
+
+    /// Verify that a JSON Patch for a shared value is the value's JSON Patch, and that two
+    /// pointers to the same value have none.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn shared_json_patch() {
+        let start = Arc::new(vec![1u8]);
+        let end = Arc::new(vec![1u8, 2]);
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(
+            serde_json::to_string(&start.to_json_patch(&delta).unwrap()).unwrap(),
+            r#"[{"op":"add","path":"/1","value":2}]"#
+        );
+
+        let delta = start.create_delta_towards(&start).delta;
+        assert!(start.to_json_patch(&delta).unwrap().is_empty());
+    }
+}
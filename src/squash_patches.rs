@@ -0,0 +1,71 @@
+use crate::{PatchError, Patchable};
+
+/// Combines consecutive patches into a single patch.
+///
+/// Useful when a receiver has fallen behind by several patches, since sending one squashed patch
+/// is usually smaller than sending every patch that was missed.
+///
+/// ```
+/// # use dipa::{Patchable, SquashablePatches};
+/// let mut score: u32 = 5;
+///
+/// let squashed = u32::squash_patches(Some(10), None).unwrap();
+/// assert_eq!(squashed, Some(10));
+///
+/// score.apply_patch(squashed).unwrap();
+/// assert_eq!(score, 10);
+/// ```
+pub trait SquashablePatches<P>: Patchable<P> {
+    /// Combine two patches into one patch that has the same effect as applying `first` and then
+    /// `second`.
+    ///
+    /// Fails if `second` can not be applied on top of `first`, such as when `first` sets an
+    /// `Option` to `None` and `second` modifies the value inside of it.
+    fn squash_patches(first: P, second: P) -> Result<P, PatchError>;
+}
+
+#[cfg(test)]
+pub(crate) use self::test_utils::test_squash;
+
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+    use crate::Diffable;
+    use bincode::Options;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::fmt::Debug;
+
+    /// Squash the patch from `start` to `middle` with the patch from `middle` to `end` and verify
+    /// that applying it to `start` gives us `end`.
+    pub(crate) fn test_squash<T>(start: T, middle: T, end: T)
+    where
+        T: Debug + Clone + PartialEq,
+        T: for<'s, 'e> Diffable<'s, 'e, T>,
+        T: for<'s, 'e> SquashablePatches<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+        for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta: Serialize,
+        for<'s, 'e> <T as Diffable<'s, 'e, T>>::DeltaOwned: DeserializeOwned,
+    {
+        let first = round_trip(&start.create_delta_towards(&middle).delta);
+        let second = round_trip(&middle.create_delta_towards(&end).delta);
+
+        let squashed = T::squash_patches(first, second).unwrap();
+
+        let mut patched = start.clone();
+        patched.apply_patch(squashed).unwrap();
+
+        assert_eq!(patched, end, "{:?} -> {:?} -> {:?}", start, middle, end);
+    }
+
+    fn round_trip<D: Serialize, P: DeserializeOwned>(delta: &D) -> P {
+        let bytes = bincode::options()
+            .with_varint_encoding()
+            .serialize(delta)
+            .unwrap();
+
+        bincode::options()
+            .with_varint_encoding()
+            .deserialize(&bytes)
+            .unwrap()
+    }
+}
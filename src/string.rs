@@ -1,39 +1,140 @@
-use crate::sequence::{SequenceModificationDelta, SequenceModificationDeltaOwned};
-use crate::{CreatedDelta, Diffable, Patchable};
+use crate::text::{
+    apply_text_patch, merge_text_patches, squash_text_patches, text_delta_towards,
+    visit_text_delta, TextEdit, TextEditOwned, Utf8Bytes,
+};
+#[cfg(feature = "json-patch")]
+use crate::{text::push_text_json_patch, JsonPatch, JsonPatchError, JsonPointer, ToJsonPatch};
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, ReversiblePatchable, SquashablePatches, VisitDelta,
+};
 
+// Strings are diffed one char at a time and measure positions in bytes, the same way that a `str`
+// does. `dipa::Text` can measure them in other units.
 impl<'s, 'e> Diffable<'s, 'e, String> for String {
-    type Delta = Vec<SequenceModificationDelta<'e, u8>>;
-    type DeltaOwned = Vec<SequenceModificationDeltaOwned<u8>>;
+    type Delta = Vec<TextEdit<'e>>;
+    type DeltaOwned = Vec<TextEditOwned>;
 
     fn create_delta_towards(&self, end_state: &'e String) -> CreatedDelta<Self::Delta> {
-        self.as_bytes().create_delta_towards(&end_state.as_bytes())
+        text_delta_towards::<Utf8Bytes>(self, end_state)
     }
 }
 
-impl Patchable<Vec<SequenceModificationDeltaOwned<u8>>> for String {
-    fn apply_patch(&mut self, patch: Vec<SequenceModificationDeltaOwned<u8>>) {
-        // TODO: More efficient implementation without copying.. Just quickly getting things working.
-        let mut bytes = self.as_bytes().to_vec();
+impl Patchable<Vec<TextEditOwned>> for String {
+    fn apply_patch(&mut self, patch: Vec<TextEditOwned>) -> Result<(), PatchError> {
+        apply_text_patch::<Utf8Bytes>(self, patch.iter().map(TextEditOwned::as_edit))?;
 
-        bytes.apply_patch(patch);
+        Ok(())
+    }
+}
+
+impl ReversiblePatchable<Vec<TextEditOwned>> for String {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Vec<TextEditOwned>,
+    ) -> Result<Vec<TextEditOwned>, PatchError> {
+        apply_text_patch::<Utf8Bytes>(self, patch.iter().map(TextEditOwned::as_edit))
+    }
+}
+
+impl SquashablePatches<Vec<TextEditOwned>> for String {
+    fn squash_patches(
+        first: Vec<TextEditOwned>,
+        second: Vec<TextEditOwned>,
+    ) -> Result<Vec<TextEditOwned>, PatchError> {
+        Ok(squash_text_patches(first, second))
+    }
+}
+
+impl MergeablePatches<Vec<TextEditOwned>> for String {
+    fn merge_patches(
+        base: &Self,
+        a: Vec<TextEditOwned>,
+        b: Vec<TextEditOwned>,
+    ) -> Result<Vec<TextEditOwned>, MergeConflicts> {
+        merge_text_patches::<Utf8Bytes>(base, a, b)
+    }
+}
+
+impl<'b> Patchable<Vec<TextEdit<'b>>> for String {
+    fn apply_patch(&mut self, patch: Vec<TextEdit<'b>>) -> Result<(), PatchError> {
+        apply_text_patch::<Utf8Bytes>(self, patch)?;
+
+        Ok(())
+    }
+}
+
+impl<'de> BorrowedDelta<'de> for String {
+    type DeltaBorrowed = Vec<TextEdit<'de>>;
 
-        *self = String::from_utf8(bytes).unwrap()
+    fn apply_borrowed_patch(&mut self, patch: Vec<TextEdit<'de>>) -> Result<(), PatchError> {
+        self.apply_patch(patch)
     }
 }
 
 impl<'s, 'e> Diffable<'s, 'e, str> for str {
-    type Delta = Vec<SequenceModificationDelta<'e, u8>>;
-    type DeltaOwned = Vec<SequenceModificationDeltaOwned<u8>>;
+    type Delta = Vec<TextEdit<'e>>;
+    type DeltaOwned = Vec<TextEditOwned>;
 
     fn create_delta_towards(&'s self, end_state: &'e str) -> CreatedDelta<Self::Delta> {
-        self.as_bytes().create_delta_towards(&end_state.as_bytes())
+        text_delta_towards::<Utf8Bytes>(self, end_state)
+    }
+}
+
+// A `String` and a `str` are delta encoded the same way as a `Text` that measures bytes.
+impl<'s, 'e> crate::SchemaFingerprint<'s, 'e> for String {
+    const FINGERPRINT: u64 = <str as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+}
+
+impl<'s, 'e> crate::SchemaFingerprint<'s, 'e> for str {
+    const FINGERPRINT: u64 =
+        <crate::Text<Utf8Bytes> as crate::SchemaFingerprint<'s, 'e>>::FINGERPRINT;
+}
+
+impl<'s, 'e> VisitDelta<'s, 'e> for String {
+    fn visit_delta(&self, delta: &Vec<TextEdit<'e>>, visitor: &mut dyn DeltaVisitor) {
+        self.as_str().visit_delta(delta, visitor)
+    }
+}
+
+impl<'s, 'e> VisitDelta<'s, 'e> for str {
+    fn visit_delta(&self, delta: &Vec<TextEdit<'e>>, visitor: &mut dyn DeltaVisitor) {
+        visit_text_delta::<Utf8Bytes>(self, delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e> ToJsonPatch<'s, 'e> for String {
+    fn push_json_patch(
+        &self,
+        delta: &Vec<TextEdit<'e>>,
+        path: &JsonPointer,
+        patch: &mut JsonPatch,
+    ) -> Result<(), JsonPatchError> {
+        self.as_str().push_json_patch(delta, path, patch)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e> ToJsonPatch<'s, 'e> for str {
+    fn push_json_patch(
+        &self,
+        delta: &Vec<TextEdit<'e>>,
+        path: &JsonPointer,
+        patch: &mut JsonPatch,
+    ) -> Result<(), JsonPatchError> {
+        push_text_json_patch::<Utf8Bytes>(self, delta, path, patch)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::sequence::SequenceModificationDelta;
-    use crate::DipaImplTester;
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::{DipaImplTester, PatchErrorReason};
 
     /// Verify that we can diff and patch strings.
     #[test]
@@ -47,17 +148,122 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("String changed"),
             start: &mut "ABCDE".to_string(),
             end: &"ABDE".to_string(),
-            expected_delta: vec![SequenceModificationDelta::DeleteOne { index: 2 }],
-            // 1 for vec length, 1 for variant, 1 for index
-            expected_serialized_patch_size: 3,
+            expected_delta: vec![TextEdit {
+                index: 2,
+                remove: 1,
+                insert: "",
+            }],
+            // 1 for vec length, 1 for index, 1 for remove, 1 for insert
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("String changed in two places"),
+            start: &mut "hello world".to_string(),
+            end: &"jello word".to_string(),
+            expected_delta: vec![
+                TextEdit {
+                    index: 0,
+                    remove: 1,
+                    insert: "j",
+                },
+                TextEdit {
+                    index: 9,
+                    remove: 1,
+                    insert: "",
+                },
+            ],
+            expected_serialized_patch_size: 8,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("String with multibyte chars changed"),
+            start: &mut "crab 🦀".to_string(),
+            end: &"crab 🦞".to_string(),
+            expected_delta: vec![TextEdit {
+                index: 5,
+                remove: 4,
+                insert: "🦞",
+            }],
+            expected_serialized_patch_size: 8,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a patch that would split a char returns an error and leaves the string
+    /// unchanged.
+    #[test]
+    fn invalid_utf8_errors() {
+        let mut string = "é".to_string();
+
+        let err = string
+            .apply_patch(vec![TextEditOwned {
+                index: 1,
+                remove: 1,
+                insert: "".to_string(),
+            }])
+            .unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::InvalidUtf8);
+        assert_eq!(string, "é");
+    }
+
+    /// Verify that we can squash consecutive String patches.
+    #[test]
+    fn squash_string_patches() {
+        test_squash("ABC".to_string(), "ABCDE".to_string(), "BCDE".to_string());
+        test_squash("ABC".to_string(), "".to_string(), "XY".to_string());
+    }
+
+    /// Verify that we can merge String patches that modify different parts of the String.
+    #[test]
+    fn merge_string_patches() {
+        test_merge(
+            "hello world".to_string(),
+            "hello brave world".to_string(),
+            "hello world!".to_string(),
+            "hello brave world!".to_string(),
+        );
+    }
+
+    /// Verify that a changed String is described using its old and new text.
+    #[test]
+    fn print_string_delta() {
+        test_print(
+            "hello".to_string(),
+            "hello world".to_string(),
+            &[r#""hello" -> "hello world""#],
+        );
+        test_print("hello".to_string(), "hello".to_string(), &[]);
+    }
+
+    /// Verify that a changed String is replaced in the JSON Patch.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn string_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        test_json_patch("hello".to_string(), "hello world".to_string());
+        test_json_patch("hello".to_string(), "hello".to_string());
+    }
+
+    /// Verify that we can patch a String from a borrowed patch.
+    #[test]
+    fn string_borrowed_patch() {
+        test_borrowed_patch("ABC".to_string(), "ABCDE".to_string());
+        test_borrowed_patch("ABC".to_string(), "".to_string());
+        test_borrowed_patch("".to_string(), "XY".to_string());
+        test_borrowed_patch("hello world".to_string(), "hello there world".to_string());
     }
 }
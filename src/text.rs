@@ -0,0 +1,567 @@
+pub(crate) use crate::text::text_apply_patch::apply_text_patch;
+pub(crate) use crate::text::text_delta_towards::text_delta_towards;
+pub(crate) use crate::text::text_merge_patches::merge_text_patches;
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaChange, DeltaVisitor, Diffable, MergeConflicts,
+    MergeablePatches, PatchError, Patchable, ReversiblePatchable, SquashablePatches, ToOwnedDelta,
+    VisitDelta,
+};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+mod text_apply_patch;
+mod text_delta_towards;
+mod text_merge_patches;
+
+/// A `String` whose deltas measure positions in the text with the unit `U`.
+///
+/// Strings are delta encoded as edits that replace a range of the text, and a `String` measures
+/// where those ranges start and end in bytes. Clients that measure text differently, such as
+/// JavaScript which uses UTF-16 code units, can use a `Text` so that they can apply the edits
+/// without converting every position first.
+///
+/// ```
+/// # use dipa::{Diffable, Patchable, Text, ToOwnedDelta, Utf16CodeUnits};
+/// let mut start: Text<Utf16CodeUnits> = Text::new("🦀 crab".to_string());
+/// let end = Text::new("🦀 crabs".to_string());
+///
+/// let delta = start.create_delta_towards(&end).delta;
+/// // The crab takes up two UTF-16 code units, so the `s` gets inserted at the 7th one.
+/// assert_eq!(format!("{:?}", delta), r#"[TextEdit { index: 7, remove: 0, insert: "s" }]"#);
+///
+/// start.apply_patch(delta.to_owned_delta()).unwrap();
+/// assert_eq!(start, end);
+/// ```
+///
+/// A `Text` serializes the same way as a `String`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Text<U> {
+    string: String,
+    #[serde(skip)]
+    unit: PhantomData<U>,
+}
+
+/// A unit that a [`Text`] measures positions in its text with.
+///
+/// This is implemented for [`Utf8Bytes`], [`Chars`] and [`Utf16CodeUnits`] and can not be
+/// implemented outside of dipa.
+pub trait TextUnit: sealed::Sealed {
+    #[doc(hidden)]
+    const NAME: &'static str;
+
+    /// The length of the text in this unit.
+    #[doc(hidden)]
+    fn count(text: &str) -> usize;
+
+    /// The byte offset of the position that is `units` into the text, or `None` if that position
+    /// is past the end of the text or inside of a `char`.
+    #[doc(hidden)]
+    fn byte_offset(text: &str, units: usize) -> Option<usize>;
+}
+
+/// Measures text in UTF-8 bytes, the same way that a Rust `str` does.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf8Bytes;
+
+/// Measures text in `char`s, which are Unicode scalar values.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Chars;
+
+/// Measures text in UTF-16 code units, the same way that a JavaScript string does.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf16CodeUnits;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Utf8Bytes {}
+    impl Sealed for super::Chars {}
+    impl Sealed for super::Utf16CodeUnits {}
+}
+
+impl TextUnit for Utf8Bytes {
+    const NAME: &'static str = "Utf8Bytes";
+
+    fn count(text: &str) -> usize {
+        text.len()
+    }
+
+    fn byte_offset(text: &str, units: usize) -> Option<usize> {
+        text.is_char_boundary(units).then_some(units)
+    }
+}
+
+impl TextUnit for Chars {
+    const NAME: &'static str = "Chars";
+
+    fn count(text: &str) -> usize {
+        text.chars().count()
+    }
+
+    fn byte_offset(text: &str, units: usize) -> Option<usize> {
+        text.char_indices()
+            .map(|(byte, _)| byte)
+            .chain(std::iter::once(text.len()))
+            .nth(units)
+    }
+}
+
+impl TextUnit for Utf16CodeUnits {
+    const NAME: &'static str = "Utf16CodeUnits";
+
+    fn count(text: &str) -> usize {
+        text.chars().map(char::len_utf16).sum()
+    }
+
+    fn byte_offset(text: &str, units: usize) -> Option<usize> {
+        let mut counted = 0;
+
+        for (byte, c) in text.char_indices() {
+            if counted >= units {
+                return (counted == units).then_some(byte);
+            }
+
+            counted += c.len_utf16();
+        }
+
+        (counted == units).then_some(text.len())
+    }
+}
+
+/// One edit to some text, which removes `remove` units of text starting at `index` and inserts
+/// `insert` in their place.
+///
+/// The edits in a delta are applied one after another, so `index` is a position in the text as
+/// the edits before it left it. Positions are measured in the [`TextUnit`] of the type that was
+/// diffed, which is bytes for a `String`.
+///
+/// An edit can also be deserialized as a borrowed patch, in which case `insert` borrows from the
+/// buffer that it was deserialized from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct TextEdit<'a> {
+    /// Where the edit starts.
+    pub index: usize,
+    /// How much of the text to remove.
+    pub remove: usize,
+    /// The text to insert at `index`.
+    #[serde(borrow)]
+    pub insert: &'a str,
+}
+
+/// Used to patch text such as a `String`.
+///
+/// Serialized the same way as a [`TextEdit`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct TextEditOwned {
+    /// Where the edit starts.
+    pub index: usize,
+    /// How much of the text to remove.
+    pub remove: usize,
+    /// The text to insert at `index`.
+    pub insert: String,
+}
+
+impl TextEditOwned {
+    /// The same edit, borrowing the text that it inserts.
+    pub(crate) fn as_edit(&self) -> TextEdit<'_> {
+        TextEdit {
+            index: self.index,
+            remove: self.remove,
+            insert: &self.insert,
+        }
+    }
+}
+
+impl<U> Text<U> {
+    /// Create a `Text` that holds the string.
+    pub fn new(string: String) -> Self {
+        Text {
+            string,
+            unit: PhantomData,
+        }
+    }
+
+    /// The string that this `Text` holds.
+    pub fn into_string(self) -> String {
+        self.string
+    }
+}
+
+impl<U> Deref for Text<U> {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.string
+    }
+}
+
+impl<U> DerefMut for Text<U> {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.string
+    }
+}
+
+impl<U> From<String> for Text<U> {
+    fn from(string: String) -> Self {
+        Text::new(string)
+    }
+}
+
+impl<U> From<&str> for Text<U> {
+    fn from(string: &str) -> Self {
+        Text::new(string.to_string())
+    }
+}
+
+impl<'s, 'e, U: TextUnit> Diffable<'s, 'e, Text<U>> for Text<U> {
+    type Delta = Vec<TextEdit<'e>>;
+    type DeltaOwned = Vec<TextEditOwned>;
+
+    fn create_delta_towards(&self, end_state: &'e Text<U>) -> CreatedDelta<Self::Delta> {
+        text_delta_towards::<U>(&self.string, &end_state.string)
+    }
+}
+
+impl<U: TextUnit> Patchable<Vec<TextEditOwned>> for Text<U> {
+    fn apply_patch(&mut self, patch: Vec<TextEditOwned>) -> Result<(), PatchError> {
+        apply_text_patch::<U>(&mut self.string, patch.iter().map(TextEditOwned::as_edit))?;
+
+        Ok(())
+    }
+}
+
+impl<U: TextUnit> ReversiblePatchable<Vec<TextEditOwned>> for Text<U> {
+    fn apply_patch_returning_inverse(
+        &mut self,
+        patch: Vec<TextEditOwned>,
+    ) -> Result<Vec<TextEditOwned>, PatchError> {
+        apply_text_patch::<U>(&mut self.string, patch.iter().map(TextEditOwned::as_edit))
+    }
+}
+
+impl<U: TextUnit> SquashablePatches<Vec<TextEditOwned>> for Text<U> {
+    fn squash_patches(
+        first: Vec<TextEditOwned>,
+        second: Vec<TextEditOwned>,
+    ) -> Result<Vec<TextEditOwned>, PatchError> {
+        Ok(squash_text_patches(first, second))
+    }
+}
+
+impl<U: TextUnit> MergeablePatches<Vec<TextEditOwned>> for Text<U> {
+    fn merge_patches(
+        base: &Self,
+        a: Vec<TextEditOwned>,
+        b: Vec<TextEditOwned>,
+    ) -> Result<Vec<TextEditOwned>, MergeConflicts> {
+        merge_text_patches::<U>(&base.string, a, b)
+    }
+}
+
+impl<'b, U: TextUnit> Patchable<Vec<TextEdit<'b>>> for Text<U> {
+    fn apply_patch(&mut self, patch: Vec<TextEdit<'b>>) -> Result<(), PatchError> {
+        apply_text_patch::<U>(&mut self.string, patch)?;
+
+        Ok(())
+    }
+}
+
+impl<'de, U: TextUnit> BorrowedDelta<'de> for Text<U> {
+    type DeltaBorrowed = Vec<TextEdit<'de>>;
+
+    fn apply_borrowed_patch(&mut self, patch: Vec<TextEdit<'de>>) -> Result<(), PatchError> {
+        self.apply_patch(patch)
+    }
+}
+
+impl<'s, 'e, U: TextUnit> crate::SchemaFingerprint<'s, 'e> for Text<U> {
+    const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+        .write_str("Text")
+        .write_str(U::NAME)
+        .finish();
+}
+
+impl<'s, 'e, U: TextUnit> VisitDelta<'s, 'e> for Text<U> {
+    fn visit_delta(&self, delta: &Vec<TextEdit<'e>>, visitor: &mut dyn DeltaVisitor) {
+        visit_text_delta::<U>(&self.string, delta, visitor)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<'s, 'e, U: TextUnit> crate::ToJsonPatch<'s, 'e> for Text<U> {
+    fn push_json_patch(
+        &self,
+        delta: &Vec<TextEdit<'e>>,
+        path: &crate::JsonPointer,
+        patch: &mut crate::JsonPatch,
+    ) -> Result<(), crate::JsonPatchError> {
+        push_text_json_patch::<U>(&self.string, delta, path, patch)
+    }
+}
+
+impl<'a> ToOwnedDelta<TextEditOwned> for TextEdit<'a> {
+    fn to_owned_delta(self) -> TextEditOwned {
+        TextEditOwned {
+            index: self.index,
+            remove: self.remove,
+            insert: self.insert.to_string(),
+        }
+    }
+}
+
+impl<'a> ToOwnedDelta<Vec<TextEditOwned>> for Vec<TextEdit<'a>> {
+    fn to_owned_delta(self) -> Vec<TextEditOwned> {
+        self.into_iter().map(ToOwnedDelta::to_owned_delta).collect()
+    }
+}
+
+// Every edit is applied to the text as it was left by the edits before it, so appending the
+// second patch's edits to the first patch's keeps all of their positions valid.
+pub(crate) fn squash_text_patches(
+    mut first: Vec<TextEditOwned>,
+    second: Vec<TextEditOwned>,
+) -> Vec<TextEditOwned> {
+    first.extend(second);
+    first
+}
+
+// Positions in the text are not very useful when reading about a change to it, so we describe
+// the whole old and new text instead.
+pub(crate) fn visit_text_delta<U: TextUnit>(
+    text: &str,
+    delta: &[TextEdit<'_>],
+    visitor: &mut dyn DeltaVisitor,
+) {
+    if delta.is_empty() {
+        return;
+    }
+
+    let mut new = text.to_string();
+    if apply_text_patch::<U>(&mut new, delta.iter().cloned()).is_ok() {
+        visitor.visit_change(DeltaChange::Value {
+            old: &text,
+            new: &new,
+        });
+    }
+}
+
+// JSON strings can't be edited in place, so changed text is replaced as a whole.
+#[cfg(feature = "json-patch")]
+pub(crate) fn push_text_json_patch<U: TextUnit>(
+    text: &str,
+    delta: &[TextEdit<'_>],
+    path: &crate::JsonPointer,
+    patch: &mut crate::JsonPatch,
+) -> Result<(), crate::JsonPatchError> {
+    if delta.is_empty() {
+        return Ok(());
+    }
+
+    let mut new = text.to_string();
+    apply_text_patch::<U>(&mut new, delta.iter().cloned())
+        .map_err(|_| crate::JsonPatchError::not_created_from("string"))?;
+
+    patch.replace(path, &new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
+    use crate::{DipaImplTester, MergeConflictReason, PatchErrorReason};
+
+    const CRAB: &str = "🦀 crab";
+
+    /// Verify that every unit measures the same text differently.
+    #[test]
+    fn unit_counts() {
+        assert_eq!(Utf8Bytes::count(CRAB), 9);
+        assert_eq!(Chars::count(CRAB), 6);
+        assert_eq!(Utf16CodeUnits::count(CRAB), 7);
+    }
+
+    /// Verify that positions inside of a char or past the end of the text have no byte offset.
+    #[test]
+    fn unit_byte_offsets() {
+        assert_eq!(Utf8Bytes::byte_offset(CRAB, 4), Some(4));
+        assert_eq!(Utf8Bytes::byte_offset(CRAB, 2), None);
+        assert_eq!(Utf8Bytes::byte_offset(CRAB, 9), Some(9));
+        assert_eq!(Utf8Bytes::byte_offset(CRAB, 10), None);
+
+        assert_eq!(Chars::byte_offset(CRAB, 1), Some(4));
+        assert_eq!(Chars::byte_offset(CRAB, 6), Some(9));
+        assert_eq!(Chars::byte_offset(CRAB, 7), None);
+
+        assert_eq!(Utf16CodeUnits::byte_offset(CRAB, 1), None);
+        assert_eq!(Utf16CodeUnits::byte_offset(CRAB, 2), Some(4));
+        assert_eq!(Utf16CodeUnits::byte_offset(CRAB, 7), Some(9));
+        assert_eq!(Utf16CodeUnits::byte_offset(CRAB, 8), None);
+    }
+
+    /// Verify that the positions in a delta are measured in the text's unit.
+    #[test]
+    fn text_dipa() {
+        DipaImplTester {
+            label: Some("Chars"),
+            start: &mut Text::<Chars>::from(CRAB),
+            end: &Text::from("🦀 crabs"),
+            expected_delta: vec![TextEdit {
+                index: 6,
+                remove: 0,
+                insert: "s",
+            }],
+            // 1 for vec length, 1 for index, 1 for remove, 2 for insert
+            expected_serialized_patch_size: 5,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Utf16CodeUnits"),
+            start: &mut Text::<Utf16CodeUnits>::from("é🦀 crab"),
+            end: &Text::from("é🦀 cab"),
+            expected_delta: vec![TextEdit {
+                index: 5,
+                remove: 1,
+                insert: "",
+            }],
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Text unchanged"),
+            start: &mut Text::<Chars>::from(CRAB),
+            end: &Text::from(CRAB),
+            expected_delta: vec![],
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that edits never split a char, even when chars that are next to each other share
+    /// some of their bytes.
+    #[test]
+    fn edits_replace_whole_chars() {
+        // 'é' is [0xC3, 0xA9] and 'ã' is [0xC3, 0xA3].
+        let start = Text::<Utf8Bytes>::from("café");
+        let end = Text::from("cafã");
+
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(
+            delta,
+            vec![TextEdit {
+                index: 3,
+                remove: 2,
+                insert: "ã",
+            }]
+        );
+
+        test_borrowed_patch(start, end);
+    }
+
+    /// Verify that edits that would split a char or that go past the end of the text return an
+    /// error and leave the text unchanged.
+    #[test]
+    fn invalid_edits_error() {
+        let mut text = Text::<Utf16CodeUnits>::from(CRAB);
+
+        let patch = vec![
+            TextEditOwned {
+                index: 7,
+                remove: 0,
+                insert: "s".to_string(),
+            },
+            TextEditOwned {
+                index: 1,
+                remove: 1,
+                insert: "".to_string(),
+            },
+        ];
+        let err = text.apply_patch(patch).unwrap_err();
+        assert_eq!(err.reason(), &PatchErrorReason::InvalidUtf8);
+        assert_eq!(text.as_str(), CRAB);
+
+        let patch = vec![TextEditOwned {
+            index: 5,
+            remove: 3,
+            insert: "".to_string(),
+        }];
+        let err = text.apply_patch(patch).unwrap_err();
+        assert_eq!(
+            err.reason(),
+            &PatchErrorReason::IndexOutOfBounds { index: 8, len: 7 }
+        );
+        assert_eq!(text.as_str(), CRAB);
+    }
+
+    /// Verify that we can squash and merge Text patches.
+    #[test]
+    fn squash_and_merge_text_patches() {
+        test_squash(
+            Text::<Chars>::from("🦀 crab"),
+            Text::from("🦀 crabs"),
+            Text::from("🦀🦀 crabs!"),
+        );
+
+        test_merge(
+            Text::<Utf16CodeUnits>::from("🦀 hello world"),
+            Text::from("🦀 hello brave world"),
+            Text::from("🦀 hello world 🌍"),
+            Text::from("🦀 hello brave world 🌍"),
+        );
+    }
+
+    /// Verify that patches that change the same part of the text conflict.
+    #[test]
+    fn merge_conflicting_text_patches() {
+        let base = Text::<Chars>::from("🦀 crab");
+
+        let a = Text::from("🦀 crabs");
+        let b = Text::from("🦀 crab!");
+
+        let a = base.create_delta_towards(&a).delta;
+        let b = base.create_delta_towards(&b).delta;
+
+        let conflicts =
+            Text::merge_patches(&base, a.to_owned_delta(), b.to_owned_delta()).unwrap_err();
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::ConcurrentChange
+        );
+    }
+
+    /// Verify that changed Text is described using its old and new text.
+    #[test]
+    fn print_text_delta() {
+        test_print(
+            Text::<Utf16CodeUnits>::from(CRAB),
+            Text::from("🦀 crabs"),
+            &[r#""🦀 crab" -> "🦀 crabs""#],
+        );
+    }
+
+    /// Verify that changed Text is replaced in the JSON Patch.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn text_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        test_json_patch(Text::<Chars>::from(CRAB), Text::from("🦀 crabs"));
+    }
+
+    /// Verify that we can convert a Text delta into its owned form.
+    #[test]
+    fn text_to_owned_delta() {
+        test_to_owned_delta(Text::<Chars>::from(CRAB), Text::from("🦀 cab!"));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::text::{TextEdit, TextEditOwned, TextUnit};
+use crate::{PatchError, PatchErrorReason};
+use std::ops::Range;
+
+// Tested in parent module.
+//
+// Each edit replaces its range of the string in place, so only the bytes after the range get
+// moved. If an edit can not be applied the edits before it are undone, which leaves the string
+// the way that it was before the patch.
+//
+// Returns the edits that undo the patch.
+pub(crate) fn apply_text_patch<'a, U: TextUnit>(
+    text: &mut String,
+    patch: impl IntoIterator<Item = TextEdit<'a>>,
+) -> Result<Vec<TextEditOwned>, PatchError> {
+    let mut inverse = vec![];
+
+    for edit in patch {
+        match apply_edit::<U>(text, edit) {
+            Ok(undo) => inverse.push(undo),
+            Err(err) => {
+                for undo in inverse.iter().rev() {
+                    // Undoing an edit that was just applied can not fail.
+                    let _ = apply_edit::<U>(text, undo.as_edit());
+                }
+
+                return Err(err);
+            }
+        }
+    }
+
+    inverse.reverse();
+    Ok(inverse)
+}
+
+/// Apply one edit, returning the edit that undoes it.
+fn apply_edit<U: TextUnit>(
+    text: &mut String,
+    edit: TextEdit<'_>,
+) -> Result<TextEditOwned, PatchError> {
+    let range = byte_range::<U>(text, edit.index, edit.remove)?;
+
+    let removed = text[range.clone()].to_string();
+    text.replace_range(range, edit.insert);
+
+    Ok(TextEditOwned {
+        index: edit.index,
+        remove: U::count(edit.insert),
+        insert: removed,
+    })
+}
+
+/// The bytes of the text that `remove` units starting at `index` cover.
+fn byte_range<U: TextUnit>(
+    text: &str,
+    index: usize,
+    remove: usize,
+) -> Result<Range<usize>, PatchError> {
+    let start = U::byte_offset(text, index).ok_or_else(|| position_error::<U>(text, index))?;
+
+    let end = U::byte_offset(&text[start..], remove)
+        .ok_or_else(|| position_error::<U>(text, index.saturating_add(remove)))?;
+
+    Ok(start..start + end)
+}
+
+/// Why there is no byte offset for a position in the text.
+fn position_error<U: TextUnit>(text: &str, index: usize) -> PatchError {
+    let len = U::count(text);
+
+    if index > len {
+        PatchError::new(PatchErrorReason::IndexOutOfBounds { index, len })
+    } else {
+        PatchError::new(PatchErrorReason::InvalidUtf8)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::sequence::get_longest_common_subsequence;
+use crate::text::{TextEdit, TextUnit};
+use crate::CreatedDelta;
+
+// Tested in parent module.
+//
+// The text is compared one char at a time so that an edit never splits a char, even when two
+// different chars start with the same bytes.
+//
+// Only the text between the common prefix and suffix gets compared, since comparing the rest
+// would only find that it did not change.
+pub(crate) fn text_delta_towards<'e, U: TextUnit>(
+    start: &str,
+    end: &'e str,
+) -> CreatedDelta<Vec<TextEdit<'e>>> {
+    if start == end {
+        return CreatedDelta {
+            delta: vec![],
+            did_change: false,
+        };
+    }
+
+    let prefix: usize = start
+        .chars()
+        .zip(end.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+    let suffix: usize = start[prefix..]
+        .chars()
+        .rev()
+        .zip(end[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+
+    let start_middle = &start[prefix..start.len() - suffix];
+    let end_middle = &end[prefix..end.len() - suffix];
+
+    let start_chars: Vec<(usize, char)> = start_middle.char_indices().collect();
+    let end_chars: Vec<(usize, char)> = end_middle.char_indices().collect();
+
+    let lcs = get_longest_common_subsequence(
+        &start_chars.iter().map(|(_, c)| *c).collect::<Vec<char>>(),
+        &end_chars.iter().map(|(_, c)| *c).collect::<Vec<char>>(),
+    );
+
+    let start_byte = |idx: usize| start_chars.get(idx).map_or(start_middle.len(), |c| c.0);
+    let end_byte = |idx: usize| end_chars.get(idx).map_or(end_middle.len(), |c| c.0);
+
+    let mut delta = vec![];
+
+    // Everything before the next edit has already been edited, so it matches the end text.
+    let mut index = U::count(&end[..prefix]);
+    let mut counted_to = 0;
+
+    let mut next_start = 0;
+    let mut next_end = 0;
+
+    for (start_idx, end_idx) in lcs
+        .into_iter()
+        .chain(std::iter::once((start_chars.len(), end_chars.len())))
+    {
+        if start_idx > next_start || end_idx > next_end {
+            let removed = &start_middle[start_byte(next_start)..start_byte(start_idx)];
+            let inserted = end_byte(next_end)..end_byte(end_idx);
+
+            index += U::count(&end_middle[counted_to..inserted.start]);
+            counted_to = inserted.end;
+
+            let insert = &end_middle[inserted];
+            delta.push(TextEdit {
+                index,
+                remove: U::count(removed),
+                insert,
+            });
+
+            index += U::count(insert);
+        }
+
+        next_start = start_idx + 1;
+        next_end = end_idx + 1;
+    }
+
+    CreatedDelta {
+        delta,
+        did_change: true,
+    }
+}
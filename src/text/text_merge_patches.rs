@@ -0,0 +1,111 @@
+use crate::sequence::{merge_hunks, Hunk};
+use crate::text::{TextEditOwned, TextUnit};
+use crate::{MergeConflicts, PatchError, PatchErrorReason};
+
+/// A char of a patched text, along with where it starts in the base text if it came from there.
+type Origin = (Option<usize>, char);
+
+// Tested in parent module.
+//
+// Edits are applied one after another, so each patch gets turned into the ranges of the base text
+// that it replaced, which then get combined the same way as the ranges of a sequence.
+pub(crate) fn merge_text_patches<U: TextUnit>(
+    base: &str,
+    a: Vec<TextEditOwned>,
+    b: Vec<TextEditOwned>,
+) -> Result<Vec<TextEditOwned>, MergeConflicts> {
+    if a.is_empty() {
+        return Ok(b);
+    }
+    if b.is_empty() {
+        return Ok(a);
+    }
+
+    let a = hunks::<U>(base, a)?;
+    let b = hunks::<U>(base, b)?;
+
+    Ok(merge_hunks(a, b)?
+        .into_iter()
+        .map(|hunk| TextEditOwned {
+            index: hunk.start,
+            remove: hunk.end - hunk.start,
+            insert: hunk.new.into_iter().collect(),
+        })
+        .collect())
+}
+
+/// The ranges of the base text that a patch replaces, ordered from the start of the text to the
+/// end. Ranges are measured in the unit `U`.
+fn hunks<U: TextUnit>(
+    base: &str,
+    patch: Vec<TextEditOwned>,
+) -> Result<Vec<Hunk<char>>, MergeConflicts> {
+    let mut origins: Vec<Origin> = vec![];
+    let mut base_len = 0;
+    for c in base.chars() {
+        origins.push((Some(base_len), c));
+        base_len += char_units::<U>(c);
+    }
+
+    for edit in patch {
+        let start = char_position::<U>(&origins, edit.index)?;
+        let end = char_position::<U>(&origins, edit.index.saturating_add(edit.remove))?;
+
+        origins.splice(start..end, edit.insert.chars().map(|c| (None, c)));
+    }
+
+    let mut hunks = vec![];
+    let mut next_base = 0;
+    let mut new = vec![];
+
+    for (origin, c) in origins
+        .into_iter()
+        .chain(std::iter::once((Some(base_len), '\0')))
+    {
+        match origin {
+            Some(base_start) => {
+                if base_start > next_base || !new.is_empty() {
+                    hunks.push(Hunk {
+                        start: next_base,
+                        end: base_start,
+                        new: std::mem::take(&mut new),
+                    });
+                }
+
+                next_base = base_start + char_units::<U>(c);
+            }
+            None => new.push(c),
+        }
+    }
+
+    Ok(hunks)
+}
+
+/// Where the char that starts `units` into the text is, or the end of the text if `units` is its
+/// length.
+fn char_position<U: TextUnit>(origins: &[Origin], units: usize) -> Result<usize, PatchError> {
+    let mut counted = 0;
+
+    for (position, (_, c)) in origins.iter().enumerate() {
+        if counted == units {
+            return Ok(position);
+        }
+
+        counted += char_units::<U>(*c);
+    }
+
+    if counted == units {
+        Ok(origins.len())
+    } else if units > counted {
+        Err(PatchError::new(PatchErrorReason::IndexOutOfBounds {
+            index: units,
+            len: counted,
+        }))
+    } else {
+        Err(PatchError::new(PatchErrorReason::InvalidUtf8))
+    }
+}
+
+fn char_units<U: TextUnit>(c: char) -> usize {
+    U::count(c.encode_utf8(&mut [0; 4]))
+}
@@ -0,0 +1,372 @@
+use crate::{Incremental, PatchError, PatchErrorReason};
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Implement the dipa traits for a time that is delta encoded as an `Incremental` count of
+/// nanoseconds, so a time that moves forwards by a few milliseconds only costs a few bytes.
+///
+/// `$to_nanos` turns the time into its `Incremental` nanoseconds and `$from_nanos` turns them back,
+/// failing if the time can not hold them.
+macro_rules! incremental_time_impl {
+    ($time:ty, $nanos:ty, $unsigned:ty, $to_nanos:expr, $from_nanos:expr) => {
+        impl<'s, 'e> crate::Diffable<'s, 'e, $time> for $time {
+            type Delta = Option<$unsigned>;
+
+            type DeltaOwned = Option<$unsigned>;
+
+            fn create_delta_towards(&self, end_state: &$time) -> crate::CreatedDelta<Self::Delta> {
+                crate::Diffable::create_delta_towards(&$to_nanos(self), &$to_nanos(end_state))
+            }
+        }
+
+        impl crate::Patchable<Option<$unsigned>> for $time {
+            fn apply_patch(&mut self, patch: Option<$unsigned>) -> Result<(), crate::PatchError> {
+                let mut nanos: Incremental<$nanos> = $to_nanos(self);
+                crate::Patchable::apply_patch(&mut nanos, patch)?;
+                *self = $from_nanos(nanos.0)?;
+
+                Ok(())
+            }
+        }
+
+        impl crate::ReversiblePatchable<Option<$unsigned>> for $time {
+            fn apply_patch_returning_inverse(
+                &mut self,
+                patch: Option<$unsigned>,
+            ) -> Result<Option<$unsigned>, crate::PatchError> {
+                let mut nanos: Incremental<$nanos> = $to_nanos(self);
+                let inverse =
+                    crate::ReversiblePatchable::apply_patch_returning_inverse(&mut nanos, patch)?;
+                *self = $from_nanos(nanos.0)?;
+
+                Ok(inverse)
+            }
+        }
+
+        impl crate::SquashablePatches<Option<$unsigned>> for $time {
+            fn squash_patches(
+                first: Option<$unsigned>,
+                second: Option<$unsigned>,
+            ) -> Result<Option<$unsigned>, crate::PatchError> {
+                <Incremental<$nanos> as crate::SquashablePatches<_>>::squash_patches(first, second)
+            }
+        }
+
+        // Times are usually set to an absolute value, so concurrent patches that move the time
+        // by different amounts conflict instead of adding up to a time that neither of them set.
+        impl crate::MergeablePatches<Option<$unsigned>> for $time {
+            fn merge_patches(
+                _base: &Self,
+                a: Option<$unsigned>,
+                b: Option<$unsigned>,
+            ) -> Result<Option<$unsigned>, crate::MergeConflicts> {
+                match (a, b) {
+                    (Some(a), Some(b)) if a != b => Err(crate::MergeConflicts::new(
+                        crate::MergeConflictReason::ConcurrentChange,
+                    )),
+                    (a, b) => Ok(a.or(b)),
+                }
+            }
+        }
+
+        impl<'de> crate::BorrowedDelta<'de> for $time {
+            type DeltaBorrowed = Option<$unsigned>;
+
+            fn apply_borrowed_patch(
+                &mut self,
+                patch: Option<$unsigned>,
+            ) -> Result<(), crate::PatchError> {
+                crate::Patchable::apply_patch(self, patch)
+            }
+        }
+
+        impl<'s, 'e> crate::VisitDelta<'s, 'e> for $time {
+            fn visit_delta(
+                &self,
+                delta: &Option<$unsigned>,
+                visitor: &mut dyn crate::DeltaVisitor,
+            ) {
+                let mut new = *self;
+                if delta.is_some() && crate::Patchable::apply_patch(&mut new, *delta).is_ok() {
+                    visitor.visit_change(crate::DeltaChange::Value {
+                        old: self,
+                        new: &new,
+                    });
+                }
+            }
+        }
+
+        impl<'s, 'e> crate::SchemaFingerprint<'s, 'e> for $time {
+            const FINGERPRINT: u64 = crate::FingerprintHasher::new()
+                .write_str(stringify!($time))
+                .finish();
+        }
+
+        #[cfg(feature = "json-patch")]
+        impl<'s, 'e> crate::ToJsonPatch<'s, 'e> for $time {
+            fn push_json_patch(
+                &self,
+                delta: &Option<$unsigned>,
+                path: &crate::JsonPointer,
+                patch: &mut crate::JsonPatch,
+            ) -> Result<(), crate::JsonPatchError> {
+                let mut new = *self;
+                match delta {
+                    Some(_) if crate::Patchable::apply_patch(&mut new, *delta).is_ok() => {
+                        patch.replace(path, &new)
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+    };
+}
+
+incremental_time_impl!(Duration, u128, u128, duration_nanos, duration_from_nanos);
+
+// Times before the epoch are negative, so the nanoseconds are signed.
+incremental_time_impl!(SystemTime, i128, u128, nanos_since_epoch, time_from_nanos);
+
+fn duration_nanos(duration: &Duration) -> Incremental<u128> {
+    Incremental(duration.as_nanos())
+}
+
+fn duration_from_nanos(nanos: u128) -> Result<Duration, PatchError> {
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| out_of_range())?;
+
+    Ok(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
+
+fn nanos_since_epoch(time: &SystemTime) -> Incremental<i128> {
+    // A `Duration` holds fewer than 2^94 nanoseconds, so they always fit in an i128.
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => Incremental(after.as_nanos() as i128),
+        Err(before) => Incremental(-(before.duration().as_nanos() as i128)),
+    }
+}
+
+fn time_from_nanos(nanos: i128) -> Result<SystemTime, PatchError> {
+    let duration = duration_from_nanos(nanos.unsigned_abs())?;
+
+    match nanos < 0 {
+        true => UNIX_EPOCH.checked_sub(duration),
+        false => UNIX_EPOCH.checked_add(duration),
+    }
+    .ok_or_else(out_of_range)
+}
+
+fn out_of_range() -> PatchError {
+    PatchError::new(PatchErrorReason::TimeOutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::{
+        BorrowedDelta, DeltaPrinter, Diffable, DipaImplTester, MergeConflictReason,
+        MergeablePatches, Patchable,
+    };
+
+    /// Verify that a duration is delta encoded as the difference in nanoseconds.
+    #[test]
+    fn duration_dipa() {
+        DipaImplTester {
+            label: Some("Duration unchanged"),
+            start: &mut Duration::from_secs(60),
+            end: &Duration::from_secs(60),
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Duration increased by a nanosecond"),
+            start: &mut Duration::from_secs(60),
+            end: &Duration::new(60, 1),
+            expected_delta: Some(2),
+            // 1 for the option, 1 for the difference
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Duration decreased by a millisecond"),
+            start: &mut Duration::from_secs(60),
+            end: &Duration::from_millis(59_999),
+            expected_delta: Some(1_999_999),
+            // 1 for the option, 5 for the difference
+            expected_serialized_patch_size: 6,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that squashed differences as large as the whole range of a duration cancel out.
+    #[test]
+    fn squash_duration_patches() {
+        let secs = Duration::from_secs;
+
+        test_squash(secs(1), Duration::MAX, secs(2));
+        test_squash(Duration::MAX, Duration::ZERO, Duration::MAX);
+    }
+
+    /// Verify that concurrent patches that set a duration to different values conflict instead of
+    /// adding their differences together, and that patches that set the same value merge.
+    #[test]
+    fn merge_duration_patches() {
+        let secs = Duration::from_secs;
+
+        test_merge(secs(10), secs(11), secs(10), secs(11));
+        test_merge(secs(10), secs(13), secs(13), secs(13));
+
+        let a = secs(10).create_delta_towards(&secs(11)).delta;
+        let b = secs(10).create_delta_towards(&secs(13)).delta;
+        let conflicts = Duration::merge_patches(&secs(10), a, b).unwrap_err();
+        assert_eq!(
+            conflicts.conflicts()[0].reason(),
+            &MergeConflictReason::ConcurrentChange
+        );
+    }
+
+    /// Verify that a patch that would move a duration past the largest number of seconds that it
+    /// can hold fails and leaves it unchanged, including when it is a borrowed patch.
+    #[test]
+    fn duration_nanos_overflow() {
+        let one_nano = Duration::ZERO
+            .create_delta_towards(&Duration::from_nanos(1))
+            .delta;
+
+        let mut duration = Duration::MAX;
+        let err = duration.apply_patch(one_nano).unwrap_err();
+        assert_eq!(err.reason(), &PatchErrorReason::TimeOutOfRange);
+        assert_eq!(duration, Duration::MAX);
+
+        let err = duration.apply_borrowed_patch(one_nano).unwrap_err();
+        assert_eq!(err.reason(), &PatchErrorReason::TimeOutOfRange);
+        assert_eq!(duration, Duration::MAX);
+
+        let mut duration = Duration::new(u64::MAX, 999_999_998);
+        duration.apply_patch(one_nano).unwrap();
+        assert_eq!(duration, Duration::MAX);
+    }
+
+    /// Verify that a patch that would move a duration below zero fails and leaves it unchanged.
+    #[test]
+    fn duration_out_of_range() {
+        let mut duration = Duration::from_secs(1);
+        let patch = Duration::from_secs(5)
+            .create_delta_towards(&Duration::from_secs(0))
+            .delta;
+
+        let err = duration.apply_patch(patch).unwrap_err();
+
+        assert_eq!(err.reason(), &PatchErrorReason::TimeOutOfRange);
+        assert_eq!(duration, Duration::from_secs(1));
+    }
+
+    /// Verify that a system time is delta encoded as the difference in nanoseconds, including
+    /// times before the epoch.
+    #[test]
+    fn system_time_dipa() {
+        let after_epoch = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(60);
+
+        DipaImplTester {
+            label: Some("SystemTime unchanged"),
+            start: &mut after_epoch.clone(),
+            end: &after_epoch,
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("SystemTime moved forwards a microsecond"),
+            start: &mut after_epoch.clone(),
+            end: &(after_epoch + Duration::from_micros(1)),
+            expected_delta: Some(2_000),
+            // 1 for the option, 3 for the difference
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("SystemTime moved before the epoch"),
+            start: &mut UNIX_EPOCH.clone(),
+            end: &before_epoch,
+            expected_delta: Some(119_999_999_999),
+            // 1 for the option, 9 for the difference
+            expected_serialized_patch_size: 10,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        test_squash(before_epoch, after_epoch, UNIX_EPOCH);
+        test_merge(UNIX_EPOCH, after_epoch, UNIX_EPOCH, after_epoch);
+        assert!(SystemTime::merge_patches(
+            &UNIX_EPOCH,
+            UNIX_EPOCH.create_delta_towards(&after_epoch).delta,
+            UNIX_EPOCH.create_delta_towards(&before_epoch).delta,
+        )
+        .is_err());
+    }
+
+    /// Verify that times before the epoch keep their fractions of a second when a patch moves
+    /// them across the epoch.
+    #[test]
+    fn system_time_before_epoch() {
+        let before = UNIX_EPOCH - Duration::from_millis(1500);
+        let after = UNIX_EPOCH + Duration::from_millis(250);
+
+        assert_eq!(nanos_since_epoch(&before), Incremental(-1_500_000_000));
+        assert_eq!(time_from_nanos(-1_500_000_000).unwrap(), before);
+
+        let mut patched = before;
+        patched
+            .apply_patch(before.create_delta_towards(&after).delta)
+            .unwrap();
+        assert_eq!(patched, after);
+
+        test_squash(before, after, UNIX_EPOCH - Duration::from_nanos(1));
+    }
+
+    /// Verify that changes to a duration are described with its old and new values, and that a
+    /// delta that can not be applied describes no change.
+    #[test]
+    fn print_duration_delta() {
+        test_print(
+            Duration::from_secs(1),
+            Duration::from_millis(1500),
+            &["1s -> 1.5s"],
+        );
+
+        let below_zero = Duration::from_secs(5)
+            .create_delta_towards(&Duration::ZERO)
+            .delta;
+        assert!(DeltaPrinter::print(&Duration::from_secs(1), &below_zero)
+            .lines()
+            .is_empty());
+    }
+
+    /// Verify that applying a duration's JSON Patch to the start object gives us the end object.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn duration_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        test_json_patch(Duration::from_secs(1), Duration::from_millis(1500));
+        test_json_patch(
+            UNIX_EPOCH + Duration::from_secs(1),
+            UNIX_EPOCH + Duration::from_secs(2),
+        );
+    }
+}
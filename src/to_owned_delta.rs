@@ -0,0 +1,60 @@
+/// Converts a delta that borrows from the start and end values into the owned delta that
+/// [`crate::Patchable::apply_patch`] accepts.
+///
+/// Useful when the process that creates a delta also needs to apply it, such as a server that keeps
+/// its own replica of the state that it sends to clients, since it avoids serializing the delta
+/// only to deserialize it again.
+///
+/// ```
+/// # use dipa::{Diffable, Patchable, ToOwnedDelta};
+/// let mut replica = vec![1u8, 2, 3];
+/// let latest = vec![1u8, 2, 3, 4];
+///
+/// let delta = replica.create_delta_towards(&latest).delta;
+/// let patch = delta.to_owned_delta();
+///
+/// replica.apply_patch(patch).unwrap();
+/// assert_eq!(replica, latest);
+/// ```
+///
+/// The lifetimes on an owned delta type only select the `Diffable` implementation that it belongs
+/// to, so implementations are generic over them.
+pub trait ToOwnedDelta<Owned> {
+    /// Convert this delta into its owned form, cloning any values that it borrows.
+    fn to_owned_delta(self) -> Owned;
+}
+
+/// A borrowed value, such as the new value of a field when an enum changes variants.
+impl<T: Clone> ToOwnedDelta<T> for &T {
+    fn to_owned_delta(self) -> T {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+pub(crate) use self::test_utils::test_to_owned_delta;
+
+#[cfg(test)]
+mod test_utils {
+    use super::*;
+    use crate::{Diffable, Patchable};
+    use std::fmt::Debug;
+
+    /// Verify that converting the delta from `start` to `end` into an owned delta and applying it
+    /// to `start` gives us `end`.
+    pub(crate) fn test_to_owned_delta<T>(start: T, end: T)
+    where
+        T: Debug + Clone + PartialEq,
+        T: for<'s, 'e> Diffable<'s, 'e, T>,
+        T: for<'s, 'e> Patchable<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+        for<'s, 'e> <T as Diffable<'s, 'e, T>>::Delta:
+            ToOwnedDelta<<T as Diffable<'s, 'e, T>>::DeltaOwned>,
+    {
+        let patch = start.create_delta_towards(&end).delta.to_owned_delta();
+
+        let mut patched = start.clone();
+        patched.apply_patch(patch).unwrap();
+
+        assert_eq!(patched, end, "{:?} -> {:?}", start, end);
+    }
+}
@@ -1,69 +1,21 @@
-use crate::delta_n::{Delta2, DeltaOwned2};
-use crate::{CreatedDelta, Diffable, Patchable};
+use crate::{
+    BorrowedDelta, CreatedDelta, DeltaVisitor, Diffable, MergeConflicts, MergeablePatches,
+    PatchError, Patchable, PathSegment, ReversiblePatchable, SquashablePatches, VisitDelta,
+};
 
-// TODO: 3-tuple and 4-tuple implementations. Similar to 2-tuple just with more fields.
-//  We already generate code like this in dipa-derive so we can probably look to re-use that
-//  in dipa's build script in order to generate the tuple-2 .. tuple-n implementations that we
-//  need.
-
-impl<'s, 'e, A: Diffable<'s, 'e, A>, B: Diffable<'s, 'e, B>> Diffable<'s, 'e, (A, B)> for (A, B) {
-    type Delta = Delta2<<A as Diffable<'s, 'e, A>>::Delta, <B as Diffable<'s, 'e, B>>::Delta>;
-    type DeltaOwned =
-        DeltaOwned2<<A as Diffable<'s, 'e, A>>::DeltaOwned, <B as Diffable<'s, 'e, B>>::DeltaOwned>;
-
-    fn create_delta_towards(&'s self, end_state: &'e (A, B)) -> CreatedDelta<Self::Delta> {
-        let diff0 = self.0.create_delta_towards(&end_state.0);
-        let diff1 = self.1.create_delta_towards(&end_state.1);
-
-        let did_change = diff0.did_change || diff1.did_change;
-
-        let delta = match (diff0.did_change, diff1.did_change) {
-            (false, false) => Delta2::NoChange,
-            (true, false) => Delta2::Change_0(diff0.delta),
-            (false, true) => Delta2::Change_1(diff1.delta),
-            (true, true) => Delta2::Change_0_1(diff0.delta, diff1.delta),
-        };
-
-        CreatedDelta { delta, did_change }
-    }
-}
-
-impl<
-        's,
-        'e,
-        A: Diffable<'s, 'e, A> + Patchable<<A as Diffable<'s, 'e, A>>::DeltaOwned>,
-        B: Diffable<'s, 'e, B> + Patchable<<B as Diffable<'s, 'e, B>>::DeltaOwned>,
-    >
-    Patchable<
-        DeltaOwned2<<A as Diffable<'s, 'e, A>>::DeltaOwned, <B as Diffable<'s, 'e, B>>::DeltaOwned>,
-    > for (A, B)
-{
-    fn apply_patch(
-        &mut self,
-        patch: DeltaOwned2<
-            <A as Diffable<'s, 'e, A>>::DeltaOwned,
-            <B as Diffable<'s, 'e, B>>::DeltaOwned,
-        >,
-    ) {
-        match patch {
-            DeltaOwned2::NoChange => {}
-            DeltaOwned2::Change_0(patch0) => {
-                self.0.apply_patch(patch0);
-            }
-            DeltaOwned2::Change_1(patch1) => {
-                self.1.apply_patch(patch1);
-            }
-            DeltaOwned2::Change_0_1(patch0, patch1) => {
-                self.0.apply_patch(patch0);
-                self.1.apply_patch(patch1);
-            }
-        }
-    }
-}
+// Implementations for 1-tuples up to 4-tuples, or larger tuples when the `delta-N` features are
+// enabled. See `generate_tuple_impls` in the build script.
+include!(concat!(env!("OUT_DIR"), "/tuple_impls.rs"));
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::borrowed_delta::test_borrowed_patch;
+    use crate::delta_n::{Delta1, Delta2, Delta3, Delta4, DeltaOwned2};
+    use crate::delta_visitor::test_print;
+    use crate::merge_patches::test_merge;
+    use crate::squash_patches::test_squash;
+    use crate::to_owned_delta::test_to_owned_delta;
     use crate::DipaImplTester;
 
     /// Verify that we can diff and patch a 2-tuple
@@ -77,7 +29,7 @@ mod tests {
             expected_serialized_patch_size: 1,
             expected_did_change: false,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("2 tuple Change_1"),
@@ -87,7 +39,7 @@ mod tests {
             expected_serialized_patch_size: 3,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("2 tuple Change_1"),
@@ -97,7 +49,7 @@ mod tests {
             expected_serialized_patch_size: 3,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
 
         DipaImplTester {
             label: Some("2 tuple Change_0_1"),
@@ -107,6 +59,238 @@ mod tests {
             expected_serialized_patch_size: 5,
             expected_did_change: true,
         }
-        .test();
+        .test_reversible();
+    }
+
+    /// Verify that we can squash consecutive 2-tuple patches.
+    #[test]
+    fn squash_two_tuple_patches() {
+        test_squash((1u16, 2u32), (3, 2), (3, 4));
+        test_squash((1u16, 2u32), (3, 4), (5, 6));
+        test_squash((1u16, 2u32), (1, 2), (1, 2));
+    }
+
+    /// Verify that we can merge 2-tuple patches that change different fields.
+    #[test]
+    fn merge_two_tuple_patches() {
+        test_merge((1u16, 2u32), (3, 2), (1, 4), (3, 4));
+        test_merge((1u16, 2u32), (3, 4), (3, 2), (3, 4));
+    }
+
+    /// Verify that every conflicting field gets reported.
+    #[test]
+    fn merge_two_tuple_conflicts() {
+        let conflicts = <(u16, u32)>::merge_patches(
+            &(1, 2),
+            DeltaOwned2::Change_0_1(Some(3), Some(4)),
+            DeltaOwned2::Change_0_1(Some(5), Some(6)),
+        )
+        .map(|_| ())
+        .unwrap_err();
+
+        let paths: Vec<Vec<&PathSegment>> = conflicts
+            .conflicts()
+            .iter()
+            .map(|conflict| conflict.path().collect())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec![&PathSegment::TupleField(0)],
+                vec![&PathSegment::TupleField(1)]
+            ]
+        );
+    }
+
+    /// Verify that changes to a 2-tuple are described with the index of the field that changed.
+    #[test]
+    fn print_two_tuple_delta() {
+        test_print((1u16, 2u32), (5u16, 2u32), &["0: 1 -> 5"]);
+        test_print((1u16, 2u32), (5u16, 6u32), &["0: 1 -> 5", "1: 2 -> 6"]);
+    }
+
+    /// Verify that applying a 2-tuple's JSON Patch to the start array gives us the end array.
+    #[cfg(feature = "json-patch")]
+    #[test]
+    fn two_tuple_json_patch() {
+        use crate::json_patch::test_json_patch;
+
+        test_json_patch((1u16, vec![2u32]), (5u16, vec![2u32]));
+        test_json_patch((1u16, vec![2u32]), (5u16, vec![2u32, 3]));
+        test_json_patch((1u16, vec![2u32]), (1u16, vec![2u32]));
+    }
+
+    /// Verify that we can convert 2-tuple deltas into owned deltas without serializing them.
+    #[test]
+    fn two_tuple_to_owned_delta() {
+        test_to_owned_delta((1u16, 2u32), (3, 2));
+        test_to_owned_delta((1u16, 2u32), (1, 4));
+        test_to_owned_delta((true, vec![1u8]), (false, vec![1, 2]));
+        test_to_owned_delta((1u16, 2u32), (1, 2));
+    }
+
+    /// Verify that we can diff and patch a 1-tuple
+    #[test]
+    fn one_tuple() {
+        DipaImplTester {
+            label: Some("1 tuple no change"),
+            start: &mut (1u16,),
+            end: &(1u16,),
+            expected_delta: Delta1::NoChange,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("1 tuple Change_0"),
+            start: &mut (1u16,),
+            end: &(5u16,),
+            expected_delta: Delta1::Change_0(Some(5)),
+            expected_serialized_patch_size: 3,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that we can diff and patch a 3-tuple
+    #[test]
+    fn three_tuple() {
+        DipaImplTester {
+            label: Some("3 tuple no change"),
+            start: &mut (1u16, 2u32, true),
+            end: &(1u16, 2u32, true),
+            expected_delta: Delta3::NoChange,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("3 tuple Change_0_2"),
+            start: &mut (1u16, 2u32, true),
+            end: &(5u16, 2u32, false),
+            expected_delta: Delta3::Change_0_2(Some(5), false),
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that we can diff and patch a 4-tuple
+    #[test]
+    fn four_tuple() {
+        DipaImplTester {
+            label: Some("4 tuple Change_1_3"),
+            start: &mut (1u16, 2u32, true, 3u8),
+            end: &(1u16, 6u32, true, 7u8),
+            expected_delta: Delta4::Change_1_3(Some(6), 7),
+            expected_serialized_patch_size: 4,
+            expected_did_change: true,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("4 tuple Change_0_1_2_3"),
+            start: &mut (1u16, 2u32, true, 3u8),
+            end: &(5u16, 6u32, false, 7u8),
+            expected_delta: Delta4::Change_0_1_2_3(Some(5), Some(6), false, 7),
+            expected_serialized_patch_size: 7,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that the other dipa traits work with larger tuples.
+    #[test]
+    fn four_tuple_traits() {
+        test_squash((1u8, 2u8, 3u8, 4u8), (5, 2, 3, 4), (5, 2, 3, 6));
+        test_merge(
+            (1u8, 2u8, 3u8, 4u8),
+            (5, 2, 3, 4),
+            (1, 2, 3, 6),
+            (5, 2, 3, 6),
+        );
+        test_to_owned_delta((1u8, 2u8, 3u8, 4u8), (1, 5, 3, 4));
+        test_print((1u8, 2u8, 3u8, 4u8), (1, 2, 3, 5), &["3: 4 -> 5"]);
+    }
+
+    /// Verify that deltas can be deserialized from self describing formats, which identify
+    /// variants by name instead of by index.
+    #[test]
+    fn tuple_delta_json_round_trip() {
+        use crate::delta_n::DeltaOwned3;
+
+        for end in [(1u8, 2u8, 3u8), (5, 2, 3), (5, 6, 3), (5, 6, 7)].iter() {
+            let mut start = (1u8, 2u8, 3u8);
+
+            let delta = start.create_delta_towards(end).delta;
+            let json = serde_json::to_string(&delta).unwrap();
+            let patch: DeltaOwned3<u8, u8, u8> = serde_json::from_str(&json).unwrap();
+
+            start.apply_patch(patch).unwrap();
+            assert_eq!(&start, end);
+        }
+    }
+
+    /// Verify that tuples with a different number of fields have different fingerprints.
+    #[test]
+    fn tuple_fingerprints() {
+        use crate::SchemaFingerprint;
+
+        assert_ne!(
+            <(u8, u8, u8) as SchemaFingerprint>::FINGERPRINT,
+            <(u8, u8, u8, u8) as SchemaFingerprint>::FINGERPRINT
+        );
+        assert_ne!(
+            <(u8,) as SchemaFingerprint>::FINGERPRINT,
+            <u8 as SchemaFingerprint>::FINGERPRINT
+        );
+    }
+
+    /// Verify that tuples larger than 4 fields can be diffed and patched when their `delta-N`
+    /// feature is enabled.
+    #[cfg(feature = "delta-8")]
+    #[test]
+    fn eight_tuple() {
+        let mut start = (0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8);
+        let end = (0u8, 1u8, 2u8, 3u8, 4u8, 9u8, 6u8, 8u8);
+
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(delta, crate::delta_n::Delta8::Change_5_7(9, 8));
+
+        start
+            .apply_patch(crate::ToOwnedDelta::to_owned_delta(delta))
+            .unwrap();
+        assert_eq!(start, end);
+    }
+
+    /// Verify that the largest tuples, which the `delta-12` feature enables, can be diffed and
+    /// patched.
+    #[cfg(feature = "delta-12")]
+    #[test]
+    fn twelve_tuple() {
+        let mut start = (0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8);
+        let end = (0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 12u8);
+
+        let delta = start.create_delta_towards(&end).delta;
+        assert_eq!(delta, crate::delta_n::Delta12::Change_11(12));
+
+        start
+            .apply_patch(crate::ToOwnedDelta::to_owned_delta(delta))
+            .unwrap();
+        assert_eq!(start, end);
+    }
+
+    /// Verify that we can patch a tuple from a borrowed patch, which only holds the borrowed
+    /// deltas of the fields that changed.
+    #[test]
+    fn tuple_borrowed_patch() {
+        test_borrowed_patch((1u8,), (2u8,));
+        test_borrowed_patch((1u8, "a".to_string()), (1u8, "abc".to_string()));
+        test_borrowed_patch(
+            ("a".to_string(), vec![1u32], 3u16),
+            ("b".to_string(), vec![1u32], 4u16),
+        );
     }
 }
@@ -0,0 +1,79 @@
+/// Compares floats with a tolerance, for struct fields that have a `#[dipa(epsilon = 0.01)]`
+/// attribute.
+///
+/// A derived struct treats an epsilon field as unchanged while it is within epsilon of the field
+/// in the start state, and leaves it out of the delta.
+///
+/// Since only the start state is compared against, small changes keep adding up until they are
+/// larger than epsilon as long as the start state is what the receiver has. So either create
+/// deltas from your copy of the receiver's state that every sent delta gets applied to, or wrap
+/// the field in an [`EpsilonTracked`](crate::EpsilonTracked) that remembers the last value that
+/// was sent. Otherwise a value that changes slowly might never be sent.
+///
+/// ```
+/// # use dipa::WithinEpsilon;
+/// assert!([0.004f32, 0., 0.].within_epsilon(&[0., 0., 0.], 0.01));
+/// assert!(![0.012f32, 0., 0.].within_epsilon(&[0., 0., 0.], 0.01));
+/// ```
+///
+/// This is implemented for floats along with arrays and tuples of types that implement it.
+pub trait WithinEpsilon {
+    /// Whether or not every float in `other` is within `epsilon` of the same float in `self`.
+    fn within_epsilon(&self, other: &Self, epsilon: f64) -> bool;
+
+    /// Whether `end_state` moved more than `epsilon` away from `self`, which is how a field with an
+    /// epsilon attribute decides whether it changed.
+    ///
+    /// [`EpsilonTracked`](crate::EpsilonTracked) compares against the last value that was sent
+    /// instead of `self`, and records on `end_state` which value will be sent.
+    fn moved_beyond_epsilon(&self, end_state: &Self, epsilon: f64) -> bool {
+        !self.within_epsilon(end_state, epsilon)
+    }
+}
+
+impl WithinEpsilon for f32 {
+    fn within_epsilon(&self, other: &Self, epsilon: f64) -> bool {
+        (*self as f64).within_epsilon(&(*other as f64), epsilon)
+    }
+}
+
+impl WithinEpsilon for f64 {
+    fn within_epsilon(&self, other: &Self, epsilon: f64) -> bool {
+        (self - other).abs() <= epsilon
+    }
+}
+
+impl<T: WithinEpsilon, const N: usize> WithinEpsilon for [T; N] {
+    fn within_epsilon(&self, other: &Self, epsilon: f64) -> bool {
+        self.iter()
+            .zip(other.iter())
+            .all(|(a, b)| a.within_epsilon(b, epsilon))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that floats are within epsilon when the distance between them is at most epsilon.
+    #[test]
+    fn floats() {
+        assert!(1.0f32.within_epsilon(&1.0, 0.));
+        assert!(1.0f32.within_epsilon(&1.25, 0.25));
+        assert!(1.0f64.within_epsilon(&0.75, 0.25));
+        assert!(!1.0f64.within_epsilon(&1.5, 0.25));
+
+        assert!(!f64::NAN.within_epsilon(&f64::NAN, 1.));
+        assert!(!1.0f32.within_epsilon(&f32::INFINITY, 1.));
+    }
+
+    /// Verify that arrays and tuples are within epsilon when all of their floats are.
+    #[test]
+    fn arrays_and_tuples() {
+        assert!([1.0f32, 2.].within_epsilon(&[1.1, 1.9], 0.2));
+        assert!(![1.0f32, 2.].within_epsilon(&[1.1, 2.5], 0.2));
+
+        assert!((1.0f32, [2.0f64]).within_epsilon(&(1.1, [1.9]), 0.2));
+        assert!(!(1.0f32, [2.0f64]).within_epsilon(&(1.1, [1.5]), 0.2));
+    }
+}
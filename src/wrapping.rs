@@ -0,0 +1,112 @@
+use crate::{
+    number_diff_impl_option_wrapped, number_diff_impl_u8_or_i8, number_patch_impl_option_wrapped,
+    number_patch_impl_u8_or_i8,
+};
+use std::num::Wrapping;
+
+// serde encodes a `Wrapping` the same way as the integer inside of it, so its deltas are the same
+// size as the integer's.
+number_diff_impl_u8_or_i8!(Wrapping<u8>, Wrapping<u8>);
+number_patch_impl_u8_or_i8!(Wrapping<u8>, Wrapping<u8>);
+number_diff_impl_u8_or_i8!(Wrapping<i8>, Wrapping<i8>);
+number_patch_impl_u8_or_i8!(Wrapping<i8>, Wrapping<i8>);
+
+number_diff_impl_option_wrapped!(Wrapping<u16>, Wrapping<u16>);
+number_patch_impl_option_wrapped!(Wrapping<u16>, Option<Wrapping<u16>>);
+number_diff_impl_option_wrapped!(Wrapping<i16>, Wrapping<i16>);
+number_patch_impl_option_wrapped!(Wrapping<i16>, Option<Wrapping<i16>>);
+
+number_diff_impl_option_wrapped!(Wrapping<u32>, Wrapping<u32>);
+number_patch_impl_option_wrapped!(Wrapping<u32>, Option<Wrapping<u32>>);
+number_diff_impl_option_wrapped!(Wrapping<i32>, Wrapping<i32>);
+number_patch_impl_option_wrapped!(Wrapping<i32>, Option<Wrapping<i32>>);
+
+number_diff_impl_option_wrapped!(Wrapping<u64>, Wrapping<u64>);
+number_patch_impl_option_wrapped!(Wrapping<u64>, Option<Wrapping<u64>>);
+number_diff_impl_option_wrapped!(Wrapping<i64>, Wrapping<i64>);
+number_patch_impl_option_wrapped!(Wrapping<i64>, Option<Wrapping<i64>>);
+
+number_diff_impl_option_wrapped!(Wrapping<u128>, Wrapping<u128>);
+number_patch_impl_option_wrapped!(Wrapping<u128>, Option<Wrapping<u128>>);
+number_diff_impl_option_wrapped!(Wrapping<i128>, Wrapping<i128>);
+number_patch_impl_option_wrapped!(Wrapping<i128>, Option<Wrapping<i128>>);
+
+number_diff_impl_option_wrapped!(Wrapping<usize>, Wrapping<usize>);
+number_patch_impl_option_wrapped!(Wrapping<usize>, Option<Wrapping<usize>>);
+number_diff_impl_option_wrapped!(Wrapping<isize>, Wrapping<isize>);
+number_patch_impl_option_wrapped!(Wrapping<isize>, Option<Wrapping<isize>>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta_visitor::test_print;
+    use crate::{Diffable, DipaImplTester};
+
+    #[test]
+    fn diff_patch_wrapping_u8() {
+        DipaImplTester {
+            label: Some("Diff patch wrapped around Wrapping<u8>"),
+            start: &mut Wrapping(255u8),
+            end: &(Wrapping(255u8) + Wrapping(1)),
+            expected_delta: Wrapping(0),
+            expected_serialized_patch_size: 1,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    #[test]
+    fn diff_patch_wrapping_u32() {
+        DipaImplTester {
+            label: Some("Diff patch same Wrapping<u32>"),
+            start: &mut Wrapping(5u32),
+            end: &Wrapping(5u32),
+            expected_delta: None,
+            expected_serialized_patch_size: 1,
+            expected_did_change: false,
+        }
+        .test_reversible();
+
+        DipaImplTester {
+            label: Some("Diff patch different Wrapping<u32>"),
+            start: &mut Wrapping(5u32),
+            end: &Wrapping(6u32),
+            expected_delta: Some(Wrapping(6)),
+            expected_serialized_patch_size: 2,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a signed value that wrapped around past its minimum is sent as its new value.
+    #[test]
+    fn diff_patch_wrapping_i8() {
+        DipaImplTester {
+            label: Some("Diff patch wrapped around Wrapping<i8>"),
+            start: &mut Wrapping(i8::MIN),
+            end: &(Wrapping(i8::MIN) - Wrapping(1)),
+            expected_delta: Wrapping(i8::MAX),
+            expected_serialized_patch_size: 1,
+            expected_did_change: true,
+        }
+        .test_reversible();
+    }
+
+    /// Verify that a wrapped value's delta is encoded the same way as the integer's delta.
+    #[test]
+    fn encoded_as_integer() {
+        let wrapped = Wrapping(u64::MAX).create_delta_towards(&(Wrapping(u64::MAX) + Wrapping(3)));
+        let integer = u64::MAX.create_delta_towards(&2);
+
+        assert_eq!(
+            bincode::serialize(&wrapped.delta).unwrap(),
+            bincode::serialize(&integer.delta).unwrap()
+        );
+
+        test_print(
+            Wrapping(u16::MAX),
+            Wrapping(u16::MAX) + Wrapping(1),
+            &["65535 -> 0"],
+        );
+    }
+}